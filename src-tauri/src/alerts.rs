@@ -0,0 +1,494 @@
+use crate::countdown::{self, TimeUntil};
+use crate::error::AppError;
+use crate::models::Target;
+use crate::speech;
+use crate::state::AppState;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+/// Values accepted by the `alert_method` setting and per-target override.
+pub const METHODS: &[&str] = &["notification", "sound", "both", "speak", "none"];
+
+/// Emitted on desktop when an alert fires; the frontend plays the sound
+/// when `play_sound` is set.
+pub const ALERT_EVENT: &str = "target-alert";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AlertFired {
+    pub target_id: i64,
+    pub minutes_before: u32,
+    pub title: String,
+    pub body: String,
+    pub play_sound: bool,
+}
+
+/// How an alert reaches the user, from the target's `alert_method` or else
+/// the `alert_method` setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertMethod {
+    /// A silent notification.
+    Notification,
+    /// A sound without a notification. Background alerts on mobile can
+    /// only sound through a notification, so there it behaves like `Both`.
+    Sound,
+    Both,
+    /// Announced with the platform's speech engine ("5 minutes to
+    /// Presale"). Mobile has none, so there it behaves like `Both`.
+    Speak,
+    None,
+}
+
+impl AlertMethod {
+    /// Unknown values (rejected on save) fall back to the default.
+    pub fn from_setting(value: &str) -> Self {
+        match value {
+            "notification" => Self::Notification,
+            "sound" => Self::Sound,
+            "speak" => Self::Speak,
+            "none" => Self::None,
+            _ => Self::Both,
+        }
+    }
+}
+
+/// How often the loop checks for alerts that are due.
+const TICK: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Offsets drift and targets change; recompute the plan at least this often.
+const REPLAN_EVERY: Duration = Duration::seconds(30);
+
+/// A missed tick (sleep, busy runtime) may still fire an alert this late.
+const GRACE: Duration = Duration::seconds(5);
+
+/// One T-minus notification for a target.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedAlert {
+    /// Stable per target and interval, so a new plan replaces the old
+    /// notification instead of adding a second one. See `alert_id`.
+    pub id: i32,
+    pub target_id: i64,
+    pub minutes_before: u32,
+    /// Local wall-clock time at which the server clock reads T-minus.
+    pub fire_at: DateTime<Utc>,
+    pub title: String,
+    pub body: String,
+    /// What `Speak` says.
+    pub spoken: String,
+    pub method: AlertMethod,
+}
+
+/// Fires T-10/T-5/T-1 (per `alert_intervals`) notifications for armed
+/// targets. On desktop the loop shows each alert when it is due; on
+/// Android and iOS the whole plan is handed to the OS up front, so alerts
+/// fire while the app is in the background. `alert_method` is read on
+/// every plan, so changing it applies within a tick of saving settings.
+pub struct Alerts {
+    replan: AtomicBool,
+}
+
+impl Alerts {
+    pub fn new() -> Self {
+        Self {
+            replan: AtomicBool::new(true),
+        }
+    }
+
+    /// Recompute the plan on the next tick (target added or removed,
+    /// offset resynced, settings saved).
+    pub fn refresh(&self) {
+        self.replan.store(true, Ordering::SeqCst);
+    }
+}
+
+pub fn start(app: &AppHandle) {
+    tauri::async_runtime::spawn(run(app.clone()));
+}
+
+async fn run(app: AppHandle) {
+    let mut interval = tokio::time::interval(TICK);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    let mut planned: Vec<PlannedAlert> = Vec::new();
+    let mut planned_at = DateTime::<Utc>::MIN_UTC;
+    let mut fired: HashSet<(i64, u32)> = HashSet::new();
+
+    loop {
+        interval.tick().await;
+        let state = app.state::<AppState>();
+        let now = Utc::now();
+
+        if state.alerts.replan.swap(false, Ordering::SeqCst) || now - planned_at >= REPLAN_EVERY {
+            let handle = app.clone();
+            let next =
+                tokio::task::spawn_blocking(move || build_plan(&handle.state::<AppState>(), now))
+                    .await
+                    .unwrap_or_else(|e| Err(std::io::Error::other(e).into()));
+            match next {
+                Ok(next) => {
+                    #[cfg(mobile)]
+                    schedule_on_device(&app, &planned, &next, now);
+                    // An alert that left the plan has passed or lost its
+                    // target; it cannot come due again.
+                    fired.retain(|&key| next.iter().any(|a| a.key() == key));
+                    planned = next;
+                    planned_at = now;
+                }
+                Err(e) => log::warn!("failed to plan target alerts: {e}"),
+            }
+        }
+
+        if cfg!(desktop) {
+            let ready: Vec<PlannedAlert> = due(&planned, &fired, now).cloned().collect();
+            for alert in &ready {
+                fired.insert(alert.key());
+                if let Err(e) = fire(&app, alert) {
+                    log::warn!("failed to show alert for target {}: {e}", alert.target_id);
+                }
+            }
+        }
+    }
+}
+
+fn build_plan(state: &AppState, now: DateTime<Utc>) -> Result<Vec<PlannedAlert>, AppError> {
+    let settings = state.db.get_settings()?;
+    if settings.alert_intervals.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut alerts = Vec::new();
+    for target in state.db.list_targets()? {
+        let method = AlertMethod::from_setting(
+            target
+                .alert_method
+                .as_deref()
+                .unwrap_or(&settings.alert_method),
+        );
+        if !target.armed || target.target_at < now || method == AlertMethod::None {
+            continue;
+        }
+        // A server that cannot be read only costs its own targets' alerts.
+        let Ok(server) = state.db.get_server(target.server_id) else {
+            continue;
+        };
+        // Targets on never-synced servers have no corrected clock to count on.
+        let Ok(until) = countdown::time_until(&target, &server, now, settings.extrapolate_drift)
+        else {
+            continue;
+        };
+        let name = server.name.as_deref().unwrap_or(&server.url);
+        alerts.extend(plan(
+            &target,
+            &until,
+            name,
+            &settings.alert_intervals,
+            method,
+            now,
+        ));
+    }
+    let mut ids = HashSet::new();
+    alerts.retain(|alert| {
+        let unique = ids.insert(alert.id);
+        if !unique {
+            log::warn!(
+                "alert id {} of target {} is taken; skipping its T-{} alert",
+                alert.id,
+                alert.target_id,
+                alert.minutes_before
+            );
+        }
+        unique
+    });
+    Ok(alerts)
+}
+
+/// Alerts for one target that have not passed more than `GRACE` ago.
+pub fn plan(
+    target: &Target,
+    until: &TimeUntil,
+    server_name: &str,
+    intervals: &[u32],
+    method: AlertMethod,
+    now: DateTime<Utc>,
+) -> Vec<PlannedAlert> {
+    let remaining = Duration::microseconds((until.remaining_ms * 1000.0) as i64);
+    let label = target.label.as_deref().unwrap_or("Target");
+    intervals
+        .iter()
+        .map(|&minutes| PlannedAlert {
+            id: alert_id(target.id, minutes),
+            target_id: target.id,
+            minutes_before: minutes,
+            fire_at: now + remaining - Duration::minutes(minutes.into()),
+            title: format!("{label} in {minutes} min"),
+            body: format!(
+                "{} on {server_name}'s clock",
+                target.target_at.format("%H:%M:%S UTC")
+            ),
+            spoken: format!(
+                "{minutes} minute{} to {label}",
+                if minutes == 1 { "" } else { "s" }
+            ),
+            method,
+        })
+        .filter(|alert| alert.fire_at > now - GRACE)
+        .collect()
+}
+
+impl PlannedAlert {
+    /// Which alert this is, whatever its notification id.
+    fn key(&self) -> (i64, u32) {
+        (self.target_id, self.minutes_before)
+    }
+}
+
+/// Alerts whose time has come and that have not been shown yet.
+fn due<'a>(
+    planned: &'a [PlannedAlert],
+    fired: &'a HashSet<(i64, u32)>,
+    now: DateTime<Utc>,
+) -> impl Iterator<Item = &'a PlannedAlert> {
+    planned
+        .iter()
+        .filter(move |a| a.fire_at <= now && !fired.contains(&a.key()))
+}
+
+/// Notification ids are 32-bit. Intervals are at most 1440 minutes, so
+/// targets up to id 1 073 741 each get ids of their own; past that they
+/// wrap and may collide, which `build_plan` reports.
+fn alert_id(target_id: i64, minutes: u32) -> i32 {
+    target_id
+        .wrapping_mul(2000)
+        .wrapping_add(i64::from(minutes)) as i32
+}
+
+/// Deliver a due alert on desktop: a notification unless the method is
+/// sound only or spoken, and the event the frontend plays the sound from.
+/// A spoken alert that cannot be spoken falls back to a notification with
+/// sound.
+fn fire(app: &AppHandle, alert: &PlannedAlert) -> Result<(), AppError> {
+    let event = AlertFired {
+        target_id: alert.target_id,
+        minutes_before: alert.minutes_before,
+        title: alert.title.clone(),
+        body: alert.body.clone(),
+        play_sound: alert.method == AlertMethod::Sound,
+    };
+    if let Err(e) = app.emit(ALERT_EVENT, &event) {
+        log::warn!("failed to emit alert: {e}");
+    }
+    match alert.method {
+        AlertMethod::Notification | AlertMethod::Both => show(app, alert, None),
+        AlertMethod::Speak => speech::speak(&alert.spoken).or_else(|e| {
+            log::warn!("failed to speak alert, notifying instead: {e}");
+            show(app, alert, None)
+        }),
+        AlertMethod::Sound | AlertMethod::None => Ok(()),
+    }
+}
+
+/// `at` schedules the notification with the OS (mobile only; desktop
+/// shows it immediately).
+fn show(
+    app: &AppHandle,
+    alert: &PlannedAlert,
+    at: Option<tauri_plugin_notification::Schedule>,
+) -> Result<(), AppError> {
+    let with_sound = alert.method != AlertMethod::Notification;
+    let mut builder = app
+        .notification()
+        .builder()
+        .id(alert.id)
+        .title(&alert.title)
+        .body(&alert.body);
+    if with_sound {
+        builder = builder.sound("default");
+    }
+    if let Some(schedule) = at {
+        builder = builder.schedule(schedule);
+    }
+    builder
+        .show()
+        .map_err(|e| AppError::Notification(e.to_string()))
+}
+
+/// Replace the OS-scheduled notifications with `next`.
+#[cfg(mobile)]
+fn schedule_on_device(
+    app: &AppHandle,
+    previous: &[PlannedAlert],
+    next: &[PlannedAlert],
+    now: DateTime<Utc>,
+) {
+    if previous == next {
+        return;
+    }
+    let stale: Vec<i32> = previous.iter().map(|a| a.id).collect();
+    if !stale.is_empty() {
+        if let Err(e) = app.notification().cancel(stale) {
+            log::warn!("failed to cancel scheduled alerts: {e}");
+        }
+    }
+    for alert in next.iter().filter(|a| a.fire_at > now) {
+        let Some(nanos) = alert.fire_at.timestamp_nanos_opt() else {
+            continue;
+        };
+        let Ok(date) = time::OffsetDateTime::from_unix_timestamp_nanos(nanos.into()) else {
+            continue;
+        };
+        let at = tauri_plugin_notification::Schedule::At {
+            date,
+            repeating: false,
+            allow_while_idle: true,
+        };
+        if let Err(e) = show(app, alert, Some(at)) {
+            log::warn!(
+                "failed to schedule alert for target {}: {e}",
+                alert.target_id
+            );
+        }
+    }
+}
+
+/// Ask the OS for permission to post notifications. Returns the resulting
+/// state: "granted", "denied" or "prompt".
+pub fn request_permission(app: &AppHandle) -> Result<String, AppError> {
+    let notification = app.notification();
+    let current = notification
+        .permission_state()
+        .map_err(|e| AppError::Notification(e.to_string()))?;
+    let state = if current == tauri_plugin_notification::PermissionState::Granted {
+        current
+    } else {
+        notification
+            .request_permission()
+            .map_err(|e| AppError::Notification(e.to_string()))?
+    };
+    app.state::<AppState>().alerts.refresh();
+    Ok(state.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(target_at: DateTime<Utc>) -> Target {
+        Target {
+            id: 7,
+            server_id: 1,
+            label: Some("Presale".to_string()),
+            target_at,
+            armed: true,
+            alert_method: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    fn until(target: &Target, remaining_ms: f64, now: DateTime<Utc>) -> TimeUntil {
+        TimeUntil {
+            target_id: target.id,
+            server_id: target.server_id,
+            target_at: target.target_at,
+            corrected_now: now,
+            remaining_ms,
+            offset_ms: 0.0,
+            drift_ms_per_hour: None,
+            offset_age_ms: Some(0),
+            stale: false,
+        }
+    }
+
+    #[test]
+    fn plans_each_interval_on_the_corrected_clock() {
+        let now = Utc::now();
+        let t = target(now + Duration::minutes(30));
+        // Server clock runs 2 s ahead: 29:58 left on its clock.
+        let alerts = plan(
+            &t,
+            &until(&t, 1_798_000.0, now),
+            "tm",
+            &[10, 5, 1],
+            AlertMethod::Both,
+            now,
+        );
+
+        assert_eq!(alerts.len(), 3);
+        assert_eq!(alerts[0].minutes_before, 10);
+        assert_eq!(
+            alerts[0].fire_at,
+            now + Duration::milliseconds(1_798_000) - Duration::minutes(10)
+        );
+        assert_eq!(alerts[0].title, "Presale in 10 min");
+        assert_eq!(alerts[0].spoken, "10 minutes to Presale");
+        assert_eq!(alerts[2].spoken, "1 minute to Presale");
+        assert_ne!(alerts[0].id, alerts[1].id);
+    }
+
+    #[test]
+    fn alert_ids_are_distinct_until_they_wrap() {
+        assert_ne!(alert_id(1_073_740, 1440), alert_id(1_073_741, 0));
+        assert_eq!(alert_id(1, 5), 2005);
+        // Past the 32-bit range ids wrap instead of panicking.
+        let _ = alert_id(i64::MAX, 1440);
+    }
+
+    #[test]
+    fn every_method_setting_parses() {
+        let parsed: Vec<AlertMethod> = METHODS
+            .iter()
+            .map(|m| AlertMethod::from_setting(m))
+            .collect();
+        assert_eq!(
+            parsed,
+            vec![
+                AlertMethod::Notification,
+                AlertMethod::Sound,
+                AlertMethod::Both,
+                AlertMethod::Speak,
+                AlertMethod::None,
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_alerts_that_already_passed() {
+        let now = Utc::now();
+        let t = target(now + Duration::minutes(3));
+        let alerts = plan(
+            &t,
+            &until(&t, 180_000.0, now),
+            "tm",
+            &[10, 5, 1],
+            AlertMethod::Both,
+            now,
+        );
+        assert_eq!(
+            alerts.iter().map(|a| a.minutes_before).collect::<Vec<_>>(),
+            vec![1]
+        );
+    }
+
+    #[test]
+    fn due_fires_each_alert_once() {
+        let now = Utc::now();
+        let t = target(now + Duration::minutes(1));
+        let alerts = plan(
+            &t,
+            &until(&t, 60_000.0, now),
+            "tm",
+            &[1],
+            AlertMethod::Both,
+            now,
+        );
+        let mut fired = HashSet::new();
+
+        assert_eq!(due(&alerts, &fired, now).count(), 1);
+        fired.insert(alerts[0].key());
+        assert_eq!(due(&alerts, &fired, now).count(), 0);
+        assert_eq!(
+            due(&alerts, &HashSet::new(), now - Duration::seconds(1)).count(),
+            0
+        );
+    }
+}
@@ -0,0 +1,190 @@
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::SyncResult;
+use tauri::AppHandle;
+use tauri_plugin_notification::NotificationExt;
+
+/// Recent syncs the new one is compared against.
+const WINDOW: i64 = 20;
+
+/// Fewer earlier syncs than this say nothing about what is normal.
+const MIN_HISTORY: usize = 5;
+
+/// Deviation from the median, in robust standard deviations, past which an
+/// offset is flagged.
+const THRESHOLD: f64 = 6.0;
+
+/// Floors on the spread and on the deviation itself, so a server whose
+/// offsets agree to a millisecond is not flagged for moving by a few.
+const MIN_SPREAD_MS: f64 = 2.0;
+const MIN_DEVIATION_MS: f64 = 50.0;
+
+/// Flagged syncs in a row that agree with each other, and with the new
+/// one, after which they are the server's new normal: its offset has
+/// stepped rather than gone wild.
+const REBASELINE_AFTER: usize = 3;
+
+/// Scales a median absolute deviation to a standard deviation for
+/// normally distributed offsets.
+const MAD_TO_SIGMA: f64 = 1.4826;
+
+/// Median/MAD outlier test: whether `offset_ms` is wildly off the
+/// `baseline` offsets. Robust to a few earlier outliers, unlike a mean and
+/// standard deviation.
+pub fn is_anomalous(offset_ms: f64, baseline: &[f64]) -> bool {
+    if baseline.len() < MIN_HISTORY {
+        return false;
+    }
+    (offset_ms - median(baseline.to_vec())).abs() > tolerance(baseline)
+}
+
+/// How far from the baseline's median an offset may be before it is
+/// flagged.
+fn tolerance(baseline: &[f64]) -> f64 {
+    let center = median(baseline.to_vec());
+    let mad = median(baseline.iter().map(|o| (o - center).abs()).collect());
+    let spread = (mad * MAD_TO_SIGMA).max(MIN_SPREAD_MS);
+    (THRESHOLD * spread).max(MIN_DEVIATION_MS)
+}
+
+/// Set `result.anomalous` against the server's stored history; call before
+/// saving it. Earlier anomalies are left out of the baseline, unless the
+/// latest ones agree with this result: then the server has stepped, and
+/// it is not flagged.
+pub fn flag(db: &Database, result: &mut SyncResult) -> Result<bool, AppError> {
+    let history = db.get_sync_history(result.server_id, None, Some(WINDOW))?;
+    let recent: Vec<(f64, bool)> = history
+        .iter()
+        .map(|r| (r.total_offset_ms, r.anomalous))
+        .collect();
+    result.anomalous = is_anomalous_now(result.total_offset_ms, &recent);
+    Ok(result.anomalous)
+}
+
+/// `recent` is the stored history, newest first, as (offset, flagged).
+fn is_anomalous_now(offset_ms: f64, recent: &[(f64, bool)]) -> bool {
+    let baseline: Vec<f64> = recent
+        .iter()
+        .filter(|(_, anomalous)| !anomalous)
+        .map(|(offset, _)| *offset)
+        .collect();
+    if !is_anomalous(offset_ms, &baseline) {
+        return false;
+    }
+    let tolerance = tolerance(&baseline);
+    let run: Vec<bool> = recent
+        .iter()
+        .take_while(|(offset, _)| (offset - offset_ms).abs() <= tolerance)
+        .map(|(_, anomalous)| *anomalous)
+        .collect();
+    let stepped = run.iter().filter(|&&anomalous| anomalous).count() >= REBASELINE_AFTER;
+    !stepped
+}
+
+/// Tell the user about an anomalous sync, if `notify_anomalies` is on.
+pub fn notify(app: &AppHandle, db: &Database, result: &SyncResult) -> Result<(), AppError> {
+    if !result.anomalous || !db.get_settings()?.notify_anomalies {
+        return Ok(());
+    }
+    let server = db.get_server(result.server_id)?;
+    app.notification()
+        .builder()
+        .title("Unusual sync result")
+        .body(format!(
+            "{}: offset {:+.1} ms is far off its recent syncs",
+            server.name.as_deref().unwrap_or(&server.url),
+            result.total_offset_ms
+        ))
+        .show()
+        .map_err(|e| AppError::Notification(e.to_string()))
+}
+
+fn median(mut values: Vec<f64>) -> f64 {
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let mid = values.len() / 2;
+    if values.len() % 2 == 0 {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{LatencyProfile, SyncPhase};
+    use chrono::Utc;
+
+    fn result(server_id: i64, offset_ms: f64) -> SyncResult {
+        SyncResult {
+            server_id,
+            whole_second_offset: 0,
+            subsecond_offset: 0.0,
+            total_offset_ms: offset_ms,
+            latency_profile: LatencyProfile {
+                min: 0.0,
+                q1: 0.0,
+                median: 0.0,
+                mean: 0.0,
+                q3: 0.0,
+                max: 0.0,
+                breakdown: None,
+            },
+            verified: true,
+            synced_at: Utc::now(),
+            duration_ms: 0,
+            phase_reached: SyncPhase::Complete,
+            fingerprint: None,
+            asymmetry: None,
+            response: None,
+            id: 0,
+            timeline: Vec::new(),
+            note: None,
+            anomalous: false,
+            clock_issue: None,
+            network: None,
+            grade: None,
+            precision_ms: None,
+        }
+    }
+
+    #[test]
+    fn flags_only_wild_deviations_from_enough_history() {
+        let steady = [120.0, 121.5, 119.0, 120.4, 122.0, 118.8];
+        assert!(!is_anomalous(125.0, &steady));
+        assert!(!is_anomalous(165.0, &steady));
+        assert!(is_anomalous(1_120.0, &steady));
+        assert!(!is_anomalous(1_120.0, &steady[..4]));
+
+        // A noisy server needs a bigger jump; one earlier outlier in the
+        // baseline does not widen the spread.
+        let noisy = [100.0, 160.0, 40.0, 130.0, 70.0, 5_000.0];
+        assert!(!is_anomalous(250.0, &noisy));
+        assert!(is_anomalous(800.0, &noisy));
+    }
+
+    #[test]
+    fn flag_sets_the_result_and_skips_earlier_anomalies() {
+        let db = Database::new_in_memory().unwrap();
+        let server = db.add_server("https://example.com").unwrap();
+        let sync = |offset: f64| {
+            let mut result = result(server.id, offset);
+            let flagged = flag(&db, &mut result).unwrap();
+            db.save_sync_result(&result).unwrap();
+            flagged
+        };
+        for offset in [50.0, 51.0, 49.0, 50.5, 50.2] {
+            assert!(!sync(offset));
+        }
+        assert!(sync(950.0));
+        assert!(sync(955.0), "the first jump must not become normal");
+        assert!(sync(948.0));
+
+        // Three agreeing anomalies in a row: the offset has stepped.
+        assert!(!sync(952.0));
+        assert!(!sync(951.0));
+
+        let history = db.get_sync_history(server.id, None, None).unwrap();
+        assert_eq!(history.iter().filter(|r| r.anomalous).count(), 3);
+    }
+}
@@ -0,0 +1,75 @@
+use crate::error::AppError;
+use crate::settings_validation::FieldError;
+
+/// Share of the round trip spent on the way to the server when nothing
+/// better is known: half each way.
+pub const SYMMETRIC: f64 = 0.5;
+
+/// Factors outside this range mean one direction is nearly free, which no
+/// real link does; calibrations landing there measured something else.
+pub const MIN: f64 = 0.05;
+pub const MAX: f64 = 0.95;
+
+/// Check a manually entered factor.
+pub fn validate(asymmetry: f64) -> Result<(), AppError> {
+    if (MIN..=MAX).contains(&asymmetry) {
+        Ok(())
+    } else {
+        Err(AppError::Validation(vec![FieldError {
+            field: "asymmetry".to_string(),
+            message: format!("must be between {MIN} and {MAX}"),
+        }]))
+    }
+}
+
+/// Estimate the outbound share from a sync that assumed a symmetric path
+/// and the server's true offset (both ms, server minus local).
+///
+/// The server stamps its `Date` `asymmetry * rtt` after we send, but the
+/// symmetric sync assumed `rtt / 2`, so its offset is off by
+/// `(asymmetry - 0.5) * rtt`.
+pub fn estimate(
+    symmetric_offset_ms: f64,
+    true_offset_ms: f64,
+    rtt_ms: f64,
+) -> Result<f64, AppError> {
+    if rtt_ms <= 0.0 {
+        return Err(AppError::CalibrationFailed(
+            "round trip too short to measure".to_string(),
+        ));
+    }
+    let asymmetry = SYMMETRIC + (symmetric_offset_ms - true_offset_ms) / rtt_ms;
+    if !(MIN..=MAX).contains(&asymmetry) {
+        return Err(AppError::CalibrationFailed(format!(
+            "server is {:.0} ms off NTP time, more than path asymmetry can explain; its clock is probably not NTP-synced",
+            symmetric_offset_ms - true_offset_ms
+        )));
+    }
+    Ok(asymmetry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn symmetric_path_estimates_one_half() {
+        assert_eq!(estimate(120.0, 120.0, 40.0).unwrap(), SYMMETRIC);
+    }
+
+    #[test]
+    fn slow_uplink_shifts_the_symmetric_offset() {
+        // 30 of 40 ms spent reaching the server: the symmetric sync
+        // credits only 20, so it reads the server 10 ms further ahead.
+        let asymmetry = estimate(130.0, 120.0, 40.0).unwrap();
+        assert!((asymmetry - 0.75).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rejects_servers_that_are_not_on_ntp_time() {
+        let err = estimate(2_000.0, 0.0, 40.0).unwrap_err();
+        assert!(matches!(err, AppError::CalibrationFailed(_)));
+        assert!(validate(0.5).is_ok());
+        assert!(validate(1.0).is_err());
+    }
+}
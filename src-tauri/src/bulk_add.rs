@@ -0,0 +1,181 @@
+use crate::db::{Database, NewServer};
+use crate::error::AppError;
+use crate::server_url;
+use crate::settings_validation::FieldError;
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// More than this in one paste is almost certainly the wrong clipboard.
+pub const MAX_URLS: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkAddStatus {
+    Added,
+    /// Already a server, or repeated earlier in the same paste.
+    Duplicate,
+    Invalid,
+}
+
+/// What happened to one URL found in the pasted text.
+#[derive(Debug, Clone, Serialize)]
+pub struct BulkAddEntry {
+    /// As found in the text.
+    pub input: String,
+    /// After normalization; `None` when it could not be parsed.
+    pub url: Option<String>,
+    pub status: BulkAddStatus,
+    /// The added server, or the existing one for duplicates.
+    pub server_id: Option<i64>,
+    pub message: Option<String>,
+}
+
+/// Pull URL-looking tokens out of free text, in order. Anything with an
+/// `http(s)://` scheme counts wherever it appears; a bare domain only when
+/// it is alone on its line, so prose like "e.g." or "notes.txt" in a
+/// forwarded message is not picked up.
+pub fn extract_urls(text: &str) -> Vec<String> {
+    let mut found = Vec::new();
+    for line in text.lines() {
+        let tokens: Vec<&str> = line
+            .split(|c: char| c.is_whitespace() || "<>\"'`|".contains(c))
+            .map(|t| t.trim_start_matches(['(', '[']))
+            .map(|t| t.trim_end_matches(['.', ',', ';', ':', '!', '?', ')', ']']))
+            .filter(|t| !t.is_empty())
+            .collect();
+        for token in &tokens {
+            let lower = token.to_ascii_lowercase();
+            if let Some(at) = lower.find("http://").or_else(|| lower.find("https://")) {
+                found.push(token[at..].to_string());
+            } else if tokens.len() == 1 && looks_like_domain(token) {
+                found.push(token.to_string());
+            }
+        }
+    }
+    found
+}
+
+fn looks_like_domain(token: &str) -> bool {
+    let host = token.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = host.split(':').next().unwrap_or_default();
+    let labels: Vec<&str> = host.split('.').collect();
+    labels.len() >= 2
+        && labels
+            .iter()
+            .all(|l| !l.is_empty() && l.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'))
+        && labels
+            .last()
+            .is_some_and(|tld| tld.len() >= 2 && tld.chars().all(|c| c.is_ascii_alphabetic()))
+}
+
+/// Normalize every URL in `text`, drop duplicates and add the rest in a
+/// single transaction.
+pub fn add_from_text(db: &Database, text: &str) -> Result<Vec<BulkAddEntry>, AppError> {
+    let inputs = extract_urls(text);
+    if inputs.len() > MAX_URLS {
+        return Err(AppError::Validation(vec![FieldError {
+            field: "text".to_string(),
+            message: format!("contains {} URLs; at most {MAX_URLS} at once", inputs.len()),
+        }]));
+    }
+
+    let mut report = Vec::with_capacity(inputs.len());
+    let mut pending = Vec::new();
+    let mut seen = HashSet::new();
+    for input in inputs {
+        let url = match server_url::normalize(&input) {
+            Ok(url) => NewServer::new(&url).url,
+            Err(e) => {
+                report.push(BulkAddEntry {
+                    input,
+                    url: None,
+                    status: BulkAddStatus::Invalid,
+                    server_id: None,
+                    message: Some(e.to_string()),
+                });
+                continue;
+            }
+        };
+        let existing = server_url::find_existing(db, &url)?;
+        let status = if existing.is_some() || !seen.insert(url.clone()) {
+            BulkAddStatus::Duplicate
+        } else {
+            pending.push(report.len());
+            BulkAddStatus::Added
+        };
+        report.push(BulkAddEntry {
+            input,
+            url: Some(url),
+            status,
+            server_id: existing.map(|s| s.id),
+            message: None,
+        });
+    }
+
+    let new: Vec<NewServer> = pending
+        .iter()
+        .filter_map(|&i| report[i].url.as_deref())
+        .map(NewServer::new)
+        .collect();
+    let added = db.add_servers(&new)?;
+    for (&i, server) in pending.iter().zip(&added) {
+        report[i].server_id = Some(server.id);
+    }
+    // Repeats within the paste point at the server their first copy added.
+    for entry in report.iter_mut().filter(|e| e.server_id.is_none()) {
+        if let Some(url) = &entry.url {
+            entry.server_id = added.iter().find(|s| &s.url == url).map(|s| s.id);
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_urls_in_lists_and_messages() {
+        let text = "tickets.example.com\n\
+                    Sale opens at 10! Use https://shop.example.org/event?id=5, or\n\
+                    (https://backup.example.net).\n\
+                    notes.txt is attached, e.g. see above";
+        assert_eq!(
+            extract_urls(text),
+            vec![
+                "tickets.example.com",
+                "https://shop.example.org/event?id=5",
+                "https://backup.example.net",
+            ]
+        );
+        assert!(extract_urls("meet at 10.30").is_empty());
+    }
+
+    #[test]
+    fn reports_added_duplicate_and_invalid_urls() {
+        let db = Database::new_in_memory().unwrap();
+        let existing = db.add_server("https://a.example.com").unwrap();
+        let report = add_from_text(
+            &db,
+            "https://a.example.com\nb.example.com\nhttps://b.example.com\nhttps://[::1\n",
+        )
+        .unwrap();
+
+        let statuses: Vec<_> = report.iter().map(|e| e.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                BulkAddStatus::Duplicate,
+                BulkAddStatus::Added,
+                BulkAddStatus::Duplicate,
+                BulkAddStatus::Invalid,
+            ]
+        );
+        assert_eq!(report[0].server_id, Some(existing.id));
+        assert_eq!(report[1].url.as_deref(), Some("https://b.example.com"));
+        assert!(report[1].server_id.is_some());
+        assert_eq!(report[2].server_id, report[1].server_id);
+        assert!(report[3].message.is_some());
+        assert_eq!(db.list_servers().unwrap().len(), 2);
+    }
+}
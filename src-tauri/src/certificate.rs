@@ -0,0 +1,183 @@
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::{CertificateInfo, Server, SyncResult};
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_notification::NotificationExt;
+use x509_parser::prelude::{FromDer, X509Certificate};
+
+/// Emitted when a synced server's certificate is close to expiring or was
+/// replaced by one for another subject or from another issuer.
+pub const CERTIFICATE_EVENT: &str = "certificate-warning";
+
+/// Left on a certificate from which its expiry is worth a warning. Most
+/// issuers renew with a month to go, so one still this close was missed.
+const EXPIRY_WARNING: Duration = Duration::days(14);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CertificateWarningKind {
+    Expiring,
+    Changed,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CertificateWarning {
+    pub server_id: i64,
+    pub kind: CertificateWarningKind,
+    pub certificate: CertificateInfo,
+    /// What the previous sync saw, for `Changed`.
+    pub previous: Option<CertificateInfo>,
+    pub days_left: i64,
+}
+
+/// Subject, issuer, validity and fingerprint of a DER certificate.
+pub fn parse(der: &[u8]) -> Option<CertificateInfo> {
+    let (_, cert) = X509Certificate::from_der(der).ok()?;
+    let validity = cert.validity();
+    let at = |time: i64| DateTime::from_timestamp(time, 0);
+    Some(CertificateInfo {
+        subject: cert.subject().to_string(),
+        issuer: cert.issuer().to_string(),
+        not_before: at(validity.not_before.timestamp())?,
+        not_after: at(validity.not_after.timestamp())?,
+        sha256: Sha256::digest(der)
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect(),
+    })
+}
+
+/// Compare `result`'s certificate with the one the server's previous sync
+/// saw; call before saving `result`.
+pub fn check(db: &Database, result: &SyncResult) -> Result<Option<CertificateWarning>, AppError> {
+    let Some(current) = result
+        .response
+        .as_ref()
+        .and_then(|r| r.certificate.as_ref())
+    else {
+        return Ok(None);
+    };
+    let history = db.get_sync_history(result.server_id, None, Some(1))?;
+    let previous = history.first().and_then(|r| {
+        let cert = r.response.as_ref()?.certificate.as_ref()?;
+        Some((cert, r.synced_at))
+    });
+    Ok(warning(
+        result.server_id,
+        previous,
+        current,
+        result.synced_at,
+    ))
+}
+
+/// `Changed` when the subject or issuer differs from the previous sync's;
+/// a renewal from the same issuer is routine. `Expiring` once per
+/// certificate, on the first sync inside `EXPIRY_WARNING`.
+pub fn warning(
+    server_id: i64,
+    previous: Option<(&CertificateInfo, DateTime<Utc>)>,
+    current: &CertificateInfo,
+    now: DateTime<Utc>,
+) -> Option<CertificateWarning> {
+    let left = current.not_after - now;
+    let make = |kind, previous: Option<&CertificateInfo>| CertificateWarning {
+        server_id,
+        kind,
+        certificate: current.clone(),
+        previous: previous.cloned(),
+        days_left: left.num_days(),
+    };
+    if let Some((prev, _)) = previous {
+        if prev.subject != current.subject || prev.issuer != current.issuer {
+            return Some(make(CertificateWarningKind::Changed, Some(prev)));
+        }
+    }
+    if left >= EXPIRY_WARNING {
+        return None;
+    }
+    let warned = previous.is_some_and(|(prev, at)| {
+        prev.sha256 == current.sha256 && prev.not_after - at < EXPIRY_WARNING
+    });
+    (!warned).then(|| make(CertificateWarningKind::Expiring, None))
+}
+
+/// Emit the warning for the frontend and show it as a notification.
+pub fn notify(
+    app: &AppHandle,
+    server: &Server,
+    warning: &CertificateWarning,
+) -> Result<(), AppError> {
+    if let Err(e) = app.emit(CERTIFICATE_EVENT, warning) {
+        log::warn!("failed to emit certificate warning: {e}");
+    }
+    let name = server.name.as_deref().unwrap_or(&server.url);
+    let body = match warning.kind {
+        CertificateWarningKind::Expiring => format!(
+            "{name}: its certificate expires in {} day{}",
+            warning.days_left,
+            if warning.days_left == 1 { "" } else { "s" }
+        ),
+        CertificateWarningKind::Changed => format!(
+            "{name}: now presents a certificate issued by {}",
+            warning.certificate.issuer
+        ),
+    };
+    app.notification()
+        .builder()
+        .title("Server certificate")
+        .body(body)
+        .show()
+        .map_err(|e| AppError::Notification(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cert(issuer: &str, not_after: &str, sha256: &str) -> CertificateInfo {
+        CertificateInfo {
+            subject: "CN=tickets.example.com".to_string(),
+            issuer: issuer.to_string(),
+            not_before: "2025-04-01T00:00:00Z".parse().unwrap(),
+            not_after: not_after.parse().unwrap(),
+            sha256: sha256.to_string(),
+        }
+    }
+
+    fn at(time: &str) -> DateTime<Utc> {
+        time.parse().unwrap()
+    }
+
+    #[test]
+    fn warns_once_when_expiry_gets_close() {
+        let current = cert("CN=R11", "2025-06-30T00:00:00Z", "aa");
+        let now = at("2025-06-20T00:00:00Z");
+        let first = warning(1, None, &current, now).unwrap();
+        assert_eq!(first.kind, CertificateWarningKind::Expiring);
+        assert_eq!(first.days_left, 10);
+
+        // Already inside the window at the previous sync.
+        let earlier = at("2025-06-19T00:00:00Z");
+        assert!(warning(1, Some((&current, earlier)), &current, now).is_none());
+        // Outside it then: this sync is the first to see it.
+        let weeks_ago = at("2025-06-01T00:00:00Z");
+        assert!(warning(1, Some((&current, weeks_ago)), &current, now).is_some());
+        assert!(warning(1, None, &current, at("2025-06-01T00:00:00Z")).is_none());
+    }
+
+    #[test]
+    fn a_new_issuer_is_a_change_but_a_renewal_is_not() {
+        let now = at("2025-06-01T00:00:00Z");
+        let old = cert("CN=R11", "2025-06-25T00:00:00Z", "aa");
+        let renewed = cert("CN=R11", "2025-08-30T00:00:00Z", "bb");
+        assert!(warning(1, Some((&old, now)), &renewed, now).is_none());
+
+        let moved = cert("CN=Cloudflare Inc ECC CA-3", "2025-08-30T00:00:00Z", "cc");
+        let change = warning(1, Some((&old, now)), &moved, now).unwrap();
+        assert_eq!(change.kind, CertificateWarningKind::Changed);
+        assert_eq!(change.previous, Some(old));
+    }
+}
@@ -0,0 +1,290 @@
+use crate::anomaly;
+use crate::db::Database;
+use crate::domain_presets;
+use crate::error::AppError;
+use crate::models::{ServerStatus, ServerTime};
+use crate::network_context;
+use crate::sync_engine::{self, ProbeSettings};
+use crate::sync_profile;
+use crate::sync_recording;
+use crate::time_extractor::{ExtractorRegistry, DATE_HEADER};
+use crate::wasm_extractor;
+use crate::workspace;
+use chrono::Utc;
+use serde::Serialize;
+use std::path::PathBuf;
+use tokio_util::sync::CancellationToken;
+
+/// Must match `identifier` in tauri.conf.json so the CLI opens the same
+/// database as the GUI.
+const APP_IDENTIFIER: &str = "com.ticketime.app";
+
+const USAGE: &str = "\
+Usage: ticketime <command>
+
+Commands:
+  list                 List saved servers
+  time <id>            Print a server's corrected time
+  sync <id|url>        Run a full sync; results for saved servers are stored
+  replay <file>        Replay a recorded sync offline and compare the outcome
+  help                 Show this message
+
+Output is JSON on stdout; errors are JSON on stderr with a non-zero exit.";
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    List,
+    Time(i64),
+    Sync(SyncTarget),
+    Replay(PathBuf),
+    Help,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SyncTarget {
+    Id(i64),
+    Url(String),
+}
+
+/// Parse process arguments (without the executable). `None` means the
+/// arguments are not a CLI invocation and the GUI should start instead.
+pub fn parse(args: &[String]) -> Option<Result<Command, String>> {
+    let (name, rest) = args.split_first()?;
+    let command = match name.as_str() {
+        "list" => expect_no_args(rest).map(|_| Command::List),
+        "time" => single_arg(rest, "time <id>").and_then(|arg| {
+            arg.parse()
+                .map(Command::Time)
+                .map_err(|_| format!("invalid server id: {arg}"))
+        }),
+        "sync" => single_arg(rest, "sync <id|url>").map(|arg| match arg.parse() {
+            Ok(id) => Command::Sync(SyncTarget::Id(id)),
+            Err(_) => Command::Sync(SyncTarget::Url(arg.to_string())),
+        }),
+        "replay" => single_arg(rest, "replay <file>").map(|arg| Command::Replay(arg.into())),
+        "help" | "--help" | "-h" => Ok(Command::Help),
+        _ => return None,
+    };
+    Some(command)
+}
+
+fn expect_no_args(rest: &[String]) -> Result<(), String> {
+    match rest {
+        [] => Ok(()),
+        [extra, ..] => Err(format!("unexpected argument: {extra}")),
+    }
+}
+
+fn single_arg<'a>(rest: &'a [String], usage: &str) -> Result<&'a str, String> {
+    match rest {
+        [arg] => Ok(arg),
+        _ => Err(format!("usage: ticketime {usage}")),
+    }
+}
+
+/// Run a CLI command if `args` is one, returning the process exit code.
+pub fn run(args: &[String]) -> Option<i32> {
+    let command = parse(args)?;
+    attach_console();
+
+    let code = match command {
+        Ok(Command::Help) => {
+            println!("{USAGE}");
+            0
+        }
+        Ok(command) => match execute(command) {
+            Ok(json) => {
+                println!("{json}");
+                0
+            }
+            Err(e) => {
+                print_error(&e.to_string());
+                1
+            }
+        },
+        Err(message) => {
+            print_error(&message);
+            eprintln!("{USAGE}");
+            2
+        }
+    };
+    Some(code)
+}
+
+fn print_error(message: &str) {
+    eprintln!("{}", serde_json::json!({ "error": message }));
+}
+
+fn execute(command: Command) -> Result<String, AppError> {
+    // Replays never touch the database.
+    if let Command::Replay(path) = command {
+        let recording = sync_recording::load(&path)?;
+        let runtime = tokio::runtime::Runtime::new()?;
+        return Ok(to_json(&runtime.block_on(sync_engine::replay(&recording))));
+    }
+    let db = open_database()?;
+    match command {
+        Command::List => Ok(to_json(&db.list_servers()?)),
+        Command::Time(id) => {
+            let server = db.get_server(id)?;
+            let extrapolate_drift = db.get_settings()?.extrapolate_drift;
+            let time = ServerTime::at(&server, Utc::now(), extrapolate_drift)
+                .ok_or_else(|| AppError::NotSynced(server.id))?;
+            Ok(to_json(&time))
+        }
+        Command::Sync(target) => run_sync(&db, target),
+        Command::Replay(_) => unreachable!("replays are handled before opening the database"),
+        Command::Help => unreachable!("help is handled before opening the database"),
+    }
+}
+
+fn run_sync(db: &Database, target: SyncTarget) -> Result<String, AppError> {
+    // Saved servers get their result persisted like a GUI sync; ad-hoc URLs
+    // are measured without touching the database.
+    let server = match target {
+        SyncTarget::Id(id) => Some(db.get_server(id)?),
+        SyncTarget::Url(ref url) => db.find_server_by_url(url)?,
+    };
+    let (server_id, url, method) = match (&server, target) {
+        (Some(server), _) => (server.id, server.url.clone(), server.probe_method.clone()),
+        (None, SyncTarget::Url(url)) => {
+            let method =
+                domain_presets::for_url(&url).map_or(domain_presets::HEAD, |p| p.probe_method);
+            (0, url, method.to_string())
+        }
+        (None, SyncTarget::Id(_)) => unreachable!("get_server errors for unknown ids"),
+    };
+
+    let settings = db.get_settings()?;
+    let profile = match &server {
+        Some(server) => sync_profile::resolve(server, &settings),
+        None => sync_profile::find(&settings.sync_profile).unwrap_or(sync_profile::BALANCED),
+    };
+
+    let probe_settings = ProbeSettings {
+        portal_check: settings.captive_portal_check,
+        ..match &server {
+            Some(server) => ProbeSettings::for_server(server),
+            None => ProbeSettings::new(&method),
+        }
+    };
+    // Plugins are only scanned for a server that uses one.
+    let extractors = ExtractorRegistry::new();
+    let extractor_type = server.as_ref().map_or(DATE_HEADER, |s| &s.extractor_type);
+    if extractor_type != DATE_HEADER {
+        extractors.load_plugins(&data_dir()?.join(wasm_extractor::PLUGIN_DIR));
+    }
+    let extractor = extractors.get(extractor_type)?;
+
+    let runtime = tokio::runtime::Runtime::new()?;
+    let mut result = runtime.block_on(sync_engine::synchronize(
+        server_id,
+        &url,
+        &probe_settings,
+        extractor.as_ref(),
+        &profile,
+        CancellationToken::new(),
+        Box::new(|_| {}),
+    ))?;
+
+    if server.is_some() {
+        db.update_server_offset(server_id, result.total_offset_ms, result.synced_at)?;
+        db.update_server_status(server_id, &ServerStatus::Synced)?;
+        result.network = Some(network_context::capture());
+        anomaly::flag(db, &mut result)?;
+        result.id = db.save_sync_result(&result)?;
+        if let Some(fingerprint) = &result.fingerprint {
+            db.set_server_fingerprint(server_id, fingerprint)?;
+        }
+    }
+    Ok(to_json(&result))
+}
+
+fn data_dir() -> Result<PathBuf, AppError> {
+    let dir = dirs::data_dir()
+        .map(|dir| dir.join(APP_IDENTIFIER))
+        .ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "could not resolve the app data directory",
+            )
+        })?;
+    Ok(dir)
+}
+
+fn open_database() -> Result<Database, AppError> {
+    let dir = data_dir()?;
+    std::fs::create_dir_all(&dir)?;
+    let workspace = workspace::active(&dir);
+    Database::open(&workspace::prepare(&dir, &workspace)?)
+}
+
+fn to_json<T: Serialize>(value: &T) -> String {
+    serde_json::to_string_pretty(value).expect("CLI output is always serializable")
+}
+
+/// Release builds on Windows have no console of their own; borrow the
+/// parent terminal's so stdout/stderr reach the caller.
+#[cfg(windows)]
+fn attach_console() {
+    use windows_sys::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+    // SAFETY: plain Win32 call; failure (no parent console) is harmless.
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(windows))]
+fn attach_console() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_returns_none_for_gui_launches() {
+        assert_eq!(parse(&args(&[])), None);
+        assert_eq!(parse(&args(&["ticketime://add?url=x"])), None);
+    }
+
+    #[test]
+    fn parse_list_and_help() {
+        assert_eq!(parse(&args(&["list"])), Some(Ok(Command::List)));
+        assert_eq!(parse(&args(&["--help"])), Some(Ok(Command::Help)));
+        assert!(matches!(parse(&args(&["list", "extra"])), Some(Err(_))));
+    }
+
+    #[test]
+    fn parse_time_requires_numeric_id() {
+        assert_eq!(parse(&args(&["time", "3"])), Some(Ok(Command::Time(3))));
+        assert!(matches!(parse(&args(&["time", "abc"])), Some(Err(_))));
+        assert!(matches!(parse(&args(&["time"])), Some(Err(_))));
+    }
+
+    #[test]
+    fn parse_sync_accepts_id_or_url() {
+        assert_eq!(
+            parse(&args(&["sync", "4"])),
+            Some(Ok(Command::Sync(SyncTarget::Id(4))))
+        );
+        assert_eq!(
+            parse(&args(&["sync", "https://example.com"])),
+            Some(Ok(Command::Sync(SyncTarget::Url(
+                "https://example.com".to_string()
+            ))))
+        );
+    }
+
+    #[test]
+    fn parse_replay_takes_a_path() {
+        assert_eq!(
+            parse(&args(&["replay", "3-20260101T000000.000.json"])),
+            Some(Ok(Command::Replay("3-20260101T000000.000.json".into())))
+        );
+        assert!(matches!(parse(&args(&["replay"])), Some(Err(_))));
+    }
+}
@@ -0,0 +1,313 @@
+use crate::error::AppError;
+use serde::Serialize;
+
+/// How far a fresh measurement may drift from the one the user confirmed
+/// before we refuse to act on the confirmation.
+const CONFIRM_TOLERANCE_MS: f64 = 250.0;
+
+/// Errors below this are slewed (the OS speeds up or slows down the clock
+/// until it catches up) so running timers never see time jump; larger
+/// errors are stepped.
+const SLEW_LIMIT_MS: f64 = 500.0;
+
+/// How fast `adjtime` closes a slewed gap: 500 µs per second on Linux and
+/// macOS.
+const SLEW_RATE: f64 = 0.000_5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AdjustMethod {
+    Slew,
+    Step,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ClockAdjustment {
+    pub offset_ms: f64,
+    pub method: AdjustMethod,
+}
+
+impl ClockAdjustment {
+    /// How long until the clock has fully moved: at once for a step, over
+    /// up to ~17 minutes for a slew.
+    pub fn settles_in(&self) -> std::time::Duration {
+        match self.method {
+            AdjustMethod::Step => std::time::Duration::ZERO,
+            AdjustMethod::Slew => {
+                std::time::Duration::from_secs_f64(self.offset_ms.abs() / 1000.0 / SLEW_RATE)
+            }
+        }
+    }
+}
+
+/// Move the OS clock forward by `offset_ms` (negative moves it back).
+/// Needs root / administrator rights; without them this fails with
+/// `AppError::ClockPermissionDenied`.
+pub fn adjust_system_clock(offset_ms: f64) -> Result<ClockAdjustment, AppError> {
+    let method = choose_method(offset_ms, platform::CAN_SLEW);
+    match method {
+        AdjustMethod::Slew => platform::slew(offset_ms)?,
+        AdjustMethod::Step => platform::step(offset_ms)?,
+    }
+    log::info!("adjusted system clock by {offset_ms:+.1} ms ({method:?})");
+    Ok(ClockAdjustment { offset_ms, method })
+}
+
+/// Refuse if the error measured now differs from what the user agreed to.
+pub fn check_confirmed(confirmed_ms: f64, measured_ms: f64) -> Result<(), AppError> {
+    if (confirmed_ms - measured_ms).abs() > CONFIRM_TOLERANCE_MS {
+        return Err(AppError::ClockErrorChanged {
+            confirmed: confirmed_ms,
+            measured: measured_ms,
+        });
+    }
+    Ok(())
+}
+
+fn choose_method(offset_ms: f64, can_slew: bool) -> AdjustMethod {
+    if can_slew && offset_ms.abs() < SLEW_LIMIT_MS {
+        AdjustMethod::Slew
+    } else {
+        AdjustMethod::Step
+    }
+}
+
+/// Split signed microseconds into `(secs, micros)` with `0 <= micros < 1e6`,
+/// the normalized form `timeval` expects.
+#[cfg_attr(not(unix), allow(dead_code))]
+fn split_micros(total_us: i64) -> (i64, i64) {
+    (
+        total_us.div_euclid(1_000_000),
+        total_us.rem_euclid(1_000_000),
+    )
+}
+
+fn permission_or_io(e: std::io::Error) -> AppError {
+    if e.kind() == std::io::ErrorKind::PermissionDenied {
+        AppError::ClockPermissionDenied
+    } else {
+        AppError::Io(e)
+    }
+}
+
+// ── Platform backends ──
+
+#[cfg(unix)]
+mod platform {
+    use super::{permission_or_io, split_micros};
+    use crate::error::AppError;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    pub const CAN_SLEW: bool = true;
+
+    fn timeval(total_us: i64) -> libc::timeval {
+        let (secs, micros) = split_micros(total_us);
+        libc::timeval {
+            tv_sec: secs as _,
+            tv_usec: micros as _,
+        }
+    }
+
+    pub fn slew(offset_ms: f64) -> Result<(), AppError> {
+        let delta = timeval((offset_ms * 1000.0).round() as i64);
+        // SAFETY: `delta` is a valid timeval; the old-delta pointer may be null.
+        let rc = unsafe { libc::adjtime(&delta, std::ptr::null_mut()) };
+        if rc != 0 {
+            return Err(permission_or_io(std::io::Error::last_os_error()));
+        }
+        Ok(())
+    }
+
+    pub fn step(offset_ms: f64) -> Result<(), AppError> {
+        let now_us = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system clock before 1970")
+            .as_micros() as i64;
+        let target = timeval(now_us + (offset_ms * 1000.0).round() as i64);
+        // SAFETY: `target` is a valid timeval; the timezone argument may be null.
+        let rc = unsafe { libc::settimeofday(&target, std::ptr::null()) };
+        if rc != 0 {
+            return Err(permission_or_io(std::io::Error::last_os_error()));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::permission_or_io;
+    use crate::error::AppError;
+    use windows_sys::Win32::Foundation::{CloseHandle, FILETIME, HANDLE, LUID, SYSTEMTIME};
+    use windows_sys::Win32::Security::{
+        AdjustTokenPrivileges, LookupPrivilegeValueW, LUID_AND_ATTRIBUTES, SE_PRIVILEGE_ENABLED,
+        SE_SYSTEMTIME_NAME, TOKEN_ADJUST_PRIVILEGES, TOKEN_PRIVILEGES,
+    };
+    use windows_sys::Win32::System::SystemInformation::{GetSystemTimeAsFileTime, SetSystemTime};
+    use windows_sys::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+    use windows_sys::Win32::System::Time::FileTimeToSystemTime;
+
+    /// `SetSystemTimeAdjustment` changes the tick rate globally and must be
+    /// reset later, so Windows only steps.
+    pub const CAN_SLEW: bool = false;
+
+    const ERROR_PRIVILEGE_NOT_HELD: i32 = 1314;
+
+    fn last_error() -> AppError {
+        let e = std::io::Error::last_os_error();
+        if e.raw_os_error() == Some(ERROR_PRIVILEGE_NOT_HELD) {
+            AppError::ClockPermissionDenied
+        } else {
+            permission_or_io(e)
+        }
+    }
+
+    pub fn slew(offset_ms: f64) -> Result<(), AppError> {
+        step(offset_ms)
+    }
+
+    pub fn step(offset_ms: f64) -> Result<(), AppError> {
+        enable_systemtime_privilege()?;
+
+        // FILETIME counts 100 ns intervals.
+        let mut now = FILETIME {
+            dwLowDateTime: 0,
+            dwHighDateTime: 0,
+        };
+        // SAFETY: `now` is a valid out-pointer.
+        unsafe { GetSystemTimeAsFileTime(&mut now) };
+        let ticks = ((now.dwHighDateTime as u64) << 32 | now.dwLowDateTime as u64) as i64
+            + (offset_ms * 10_000.0).round() as i64;
+        let target = FILETIME {
+            dwLowDateTime: ticks as u32,
+            dwHighDateTime: (ticks >> 32) as u32,
+        };
+
+        // SAFETY: SYSTEMTIME is plain data; zeroed is a valid initial value.
+        let mut system_time: SYSTEMTIME = unsafe { std::mem::zeroed() };
+        // SAFETY: both pointers are valid for the duration of the calls.
+        let ok = unsafe {
+            FileTimeToSystemTime(&target, &mut system_time) != 0 && SetSystemTime(&system_time) != 0
+        };
+        if !ok {
+            return Err(last_error());
+        }
+        Ok(())
+    }
+
+    /// Administrators hold SeSystemtimePrivilege but it starts disabled.
+    fn enable_systemtime_privilege() -> Result<(), AppError> {
+        let mut token: HANDLE = std::ptr::null_mut();
+        // SAFETY: the pseudo-handle from GetCurrentProcess needs no cleanup;
+        // `token` is closed below.
+        unsafe {
+            if OpenProcessToken(GetCurrentProcess(), TOKEN_ADJUST_PRIVILEGES, &mut token) == 0 {
+                return Err(last_error());
+            }
+            let mut luid = LUID {
+                LowPart: 0,
+                HighPart: 0,
+            };
+            let looked_up =
+                LookupPrivilegeValueW(std::ptr::null(), SE_SYSTEMTIME_NAME, &mut luid) != 0;
+            let privileges = TOKEN_PRIVILEGES {
+                PrivilegeCount: 1,
+                Privileges: [LUID_AND_ATTRIBUTES {
+                    Luid: luid,
+                    Attributes: SE_PRIVILEGE_ENABLED,
+                }],
+            };
+            let adjusted = looked_up
+                && AdjustTokenPrivileges(
+                    token,
+                    0,
+                    &privileges,
+                    0,
+                    std::ptr::null_mut(),
+                    std::ptr::null_mut(),
+                ) != 0;
+            let result = if adjusted { Ok(()) } else { Err(last_error()) };
+            CloseHandle(token);
+            // A non-admin "succeeds" here without gaining the privilege;
+            // SetSystemTime then fails with ERROR_PRIVILEGE_NOT_HELD.
+            result
+        }
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+mod platform {
+    use crate::error::AppError;
+
+    pub const CAN_SLEW: bool = false;
+
+    pub fn slew(_offset_ms: f64) -> Result<(), AppError> {
+        Err(unsupported())
+    }
+
+    pub fn step(_offset_ms: f64) -> Result<(), AppError> {
+        Err(unsupported())
+    }
+
+    fn unsupported() -> AppError {
+        AppError::Io(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "adjusting the system clock is not supported on this platform",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_errors_are_slewed_when_supported() {
+        assert_eq!(choose_method(120.0, true), AdjustMethod::Slew);
+        assert_eq!(choose_method(-499.0, true), AdjustMethod::Slew);
+        assert_eq!(choose_method(120.0, false), AdjustMethod::Step);
+    }
+
+    #[test]
+    fn large_errors_are_stepped() {
+        assert_eq!(choose_method(500.0, true), AdjustMethod::Step);
+        assert_eq!(choose_method(-3_600_000.0, true), AdjustMethod::Step);
+    }
+
+    #[test]
+    fn a_slew_settles_at_the_slew_rate() {
+        let slew = ClockAdjustment {
+            offset_ms: -120.0,
+            method: AdjustMethod::Slew,
+        };
+        assert_eq!(slew.settles_in().as_secs(), 240);
+        let step = ClockAdjustment {
+            method: AdjustMethod::Step,
+            ..slew
+        };
+        assert!(step.settles_in().is_zero());
+    }
+
+    #[test]
+    fn check_confirmed_allows_small_drift_only() {
+        assert!(check_confirmed(1000.0, 1100.0).is_ok());
+        assert!(matches!(
+            check_confirmed(1000.0, 1300.0),
+            Err(AppError::ClockErrorChanged { .. })
+        ));
+    }
+
+    #[test]
+    fn split_micros_normalizes_negative_values() {
+        assert_eq!(split_micros(1_500_000), (1, 500_000));
+        assert_eq!(split_micros(-250_000), (-1, 750_000));
+        assert_eq!(split_micros(-1_000_000), (-1, 0));
+    }
+
+    #[test]
+    fn permission_errors_map_to_dedicated_variant() {
+        let e = permission_or_io(std::io::Error::from(std::io::ErrorKind::PermissionDenied));
+        assert!(matches!(e, AppError::ClockPermissionDenied));
+        let e = permission_or_io(std::io::Error::from(std::io::ErrorKind::Other));
+        assert!(matches!(e, AppError::Io(_)));
+    }
+}
@@ -0,0 +1,142 @@
+use serde::{Deserialize, Serialize};
+
+/// Below this a server is just off, not misconfigured.
+const MIN_SKEW_SECS: f64 = 60.0;
+
+/// Offsets are rounded to whole quarter hours before they are looked up.
+const ZONE_STEP_SECS: f64 = 15.0 * 60.0;
+
+/// How far off a whole quarter hour an offset may be and still read as a
+/// time zone: the server's clock may be a little off on top of it.
+const ZONE_TOLERANCE_SECS: f64 = 5.0;
+
+/// UTC-12:00 to UTC+14:00, the zones in use.
+const ZONE_RANGE_MINUTES: std::ops::RangeInclusive<i32> = -12 * 60..=14 * 60;
+
+/// The zones in use that are not whole hours from UTC, daylight saving
+/// included: Newfoundland, Iran, Afghanistan, India, Nepal, Myanmar, Eucla,
+/// central Australia, Lord Howe, the Marquesas and Chatham.
+const PART_HOUR_ZONES_MINUTES: &[i32] = &[
+    -570, -210, -150, 210, 270, 330, 345, 390, 525, 570, 630, 765, 825,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClockIssueKind {
+    /// Off by a whole time zone: the server stamps its local time as GMT.
+    Timezone,
+    /// Minutes or more off with no zone to explain it: an unsynchronized
+    /// clock.
+    Skew,
+}
+
+/// A grossly wrong server clock, named so the UI can say what is wrong
+/// instead of showing a huge offset.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClockIssue {
+    pub kind: ClockIssueKind,
+    /// The zone the server appears to be set to, for `Timezone`.
+    pub utc_offset_minutes: Option<i32>,
+    pub label: String,
+}
+
+/// `None` for offsets under a minute.
+pub fn classify(offset_ms: f64) -> Option<ClockIssue> {
+    let secs = offset_ms / 1000.0;
+    if secs.abs() < MIN_SKEW_SECS {
+        return None;
+    }
+    let zones = (secs / ZONE_STEP_SECS).round();
+    let minutes = (zones * ZONE_STEP_SECS / 60.0) as i32;
+    if (secs - zones * ZONE_STEP_SECS).abs() <= ZONE_TOLERANCE_SECS && is_zone(minutes) {
+        return Some(ClockIssue {
+            kind: ClockIssueKind::Timezone,
+            utc_offset_minutes: Some(minutes),
+            label: format!(
+                "server clock appears set to local time ({}), not UTC",
+                zone_name(minutes)
+            ),
+        });
+    }
+    Some(ClockIssue {
+        kind: ClockIssueKind::Skew,
+        utc_offset_minutes: None,
+        label: format!(
+            "server clock is {} {}",
+            duration_words(secs.abs().round() as i64),
+            if secs > 0.0 { "ahead" } else { "behind" }
+        ),
+    })
+}
+
+fn is_zone(minutes: i32) -> bool {
+    ZONE_RANGE_MINUTES.contains(&minutes)
+        && (minutes % 60 == 0 || PART_HOUR_ZONES_MINUTES.contains(&minutes))
+}
+
+fn zone_name(minutes: i32) -> String {
+    let sign = if minutes < 0 { '-' } else { '+' };
+    let (hours, rest) = (minutes.abs() / 60, minutes.abs() % 60);
+    if rest == 0 {
+        format!("UTC{sign}{hours}")
+    } else {
+        format!("UTC{sign}{hours}:{rest:02}")
+    }
+}
+
+fn duration_words(secs: i64) -> String {
+    let (hours, minutes, seconds) = (secs / 3600, secs % 3600 / 60, secs % 60);
+    match (hours, minutes) {
+        (0, _) => format!("{minutes} min {seconds} s"),
+        _ => format!("{hours} h {minutes} min"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn whole_zones_read_as_a_timezone_mistake() {
+        let issue = classify(9.0 * 3_600_000.0 + 840.0).unwrap();
+        assert_eq!(issue.kind, ClockIssueKind::Timezone);
+        assert_eq!(issue.utc_offset_minutes, Some(540));
+        assert_eq!(
+            issue.label,
+            "server clock appears set to local time (UTC+9), not UTC"
+        );
+
+        let india = classify(5.5 * 3_600_000.0).unwrap();
+        assert!(india.label.contains("(UTC+5:30)"), "{}", india.label);
+        let nepal = classify(5.75 * 3_600_000.0).unwrap();
+        assert!(nepal.label.contains("(UTC+5:45)"), "{}", nepal.label);
+        let pacific = classify(-8.0 * 3_600_000.0 - 2_000.0).unwrap();
+        assert_eq!(pacific.utc_offset_minutes, Some(-480));
+    }
+
+    #[test]
+    fn other_large_offsets_read_as_skew() {
+        let issue = classify(252_400.0).unwrap();
+        assert_eq!(issue.kind, ClockIssueKind::Skew);
+        assert_eq!(issue.label, "server clock is 4 min 12 s ahead");
+        // An hour and a bit is not a zone.
+        assert_eq!(
+            classify(-3_700_000.0).unwrap().label,
+            "server clock is 1 h 1 min behind"
+        );
+        // Quarter hours that no zone uses.
+        assert_eq!(classify(900_000.0).unwrap().kind, ClockIssueKind::Skew);
+        assert_eq!(
+            classify(-2.75 * 3_600_000.0).unwrap().label,
+            "server clock is 2 h 45 min behind"
+        );
+        // Past the last zone in use.
+        assert_eq!(
+            classify(20.0 * 3_600_000.0).unwrap().kind,
+            ClockIssueKind::Skew
+        );
+
+        assert_eq!(classify(59_000.0), None);
+        assert_eq!(classify(-850.0), None);
+    }
+}
@@ -1,27 +1,531 @@
+use crate::alerts;
+use crate::anomaly;
+use crate::asymmetry;
+use crate::bulk_add::{self, BulkAddEntry, BulkAddStatus};
+use crate::certificate;
+use crate::clock_adjust::{self, AdjustMethod, ClockAdjustment};
+use crate::comparison::{self, ServerComparison, SyncResultDiff};
+use crate::countdown::{self, TimeUntil};
+use crate::countdown_stream::CountdownTick;
+use crate::dashboard::{self, Dashboard};
+use crate::db::NewServer;
+use crate::domain_presets::{self, DomainPreset};
+use crate::drift;
 use crate::error::AppError;
+use crate::export_crypto;
+use crate::favicon;
+use crate::health;
+use crate::latency_stats::{self, LatencyStats};
+use crate::local_api;
+use crate::local_ntp::{self, LocalNtpStatus};
 use crate::models::{
-    AppSettings, Server, ServerStatus, SyncCompletePayload, SyncErrorPayload, SyncEvent, SyncPhase,
-    SyncProgressPayload, SyncResult,
+    AppSettings, AsymmetryCalibration, BenchmarkReport, ConnectionTest, FailureContext,
+    OffsetEstimate, RelativeOffset, ScheduledSync, Server, ServerStatus, ServerStatusChange,
+    ServerTime, SyncCompletePayload, SyncErrorPayload, SyncErrorRecord, SyncEvent,
+    SyncHistoryFilter, SyncHistoryPage, SyncPhase, SyncProgressPayload, SyncResult, Target,
+    TransportOptions, TrashedServer, Workspace,
 };
+use crate::network_baseline::{self, NetworkBaseline};
+use crate::network_context;
+use crate::ntp::NtpMeasurement;
+use crate::offset_series;
+use crate::preflight::{self, UrlValidation};
+use crate::progress_coalescer::ProgressCoalescer;
+use crate::reference_sources::{self, ReferenceSource};
+use crate::scheduler::SchedulerStatus;
+use crate::scripting;
+use crate::server_name;
+use crate::server_timezone;
+use crate::server_url;
+use crate::settings_export::{self, ImportSummary};
+use crate::settings_validation::{self, FieldError};
+use crate::share::{self, ShareImportSummary};
+use crate::simulation::DemoServer;
 use crate::state::AppState;
-use crate::sync_engine;
-use crate::time_extractor::DateHeaderExtractor;
-use std::time::Instant;
+use crate::sync_engine::{self, ProbeSettings, SyncFailure};
+use crate::sync_eta::EtaEstimator;
+use crate::sync_profile::{self, SyncProfile};
+use crate::sync_recording::{
+    self, ProbeRecorder, RecordingInfo, ReplayReport, SyncRecording,
+};
+use crate::sync_report::{self, SyncReport};
+use crate::target_time::TargetTime;
+use crate::time_extractor::{ExtractorInfo, PluginFailure};
+use crate::time_format::TimeFormat;
+use crate::time_source::{self, TimeSource};
+use crate::tray;
+use crate::wasm_extractor;
+use crate::watchdog::{self, Heartbeat};
+use crate::workspace;
+use crate::world_clock::{self, WorldClock};
+use chrono::{DateTime, Utc};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tauri::ipc::Channel;
-use tauri::{Manager, State};
+use tauri::{AppHandle, Emitter, Manager, State};
 use tokio_util::sync::CancellationToken;
 
 #[tauri::command]
-pub async fn add_server(url: String, state: State<'_, AppState>) -> Result<Server, AppError> {
-    let parsed = reqwest::Url::parse(&url).map_err(|e| AppError::InvalidUrl(e.to_string()))?;
+pub async fn add_server(
+    url: String,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Server, AppError> {
+    let new = NewServer::new(&server_url::normalize(&url)?);
+    if let Some(existing) = server_url::find_existing(&state.db, &new.url)? {
+        return Err(AppError::DuplicateServer {
+            id: existing.id,
+            url: new.url,
+        });
+    }
+    let server = state.db.add_servers(&[new])?.remove(0);
+    state
+        .db
+        .set_server_name(server.id, &server_name::fetch(&server).await)?;
+    let server = state.db.get_server(server.id)?;
+    tray::refresh_menu(&app_handle);
 
-    let final_url = if parsed.scheme() == "http" || parsed.scheme() == "https" {
-        url
-    } else {
-        format!("https://{url}")
+    // The icon is only for show; the tray picks it up once it is cached.
+    let app = app_handle.clone();
+    let added = server.clone();
+    tauri::async_runtime::spawn(async move {
+        match favicon::refresh(&app, &added).await {
+            Ok(icon) if !icon.is_empty() => tray::refresh_menu(&app),
+            Ok(_) => {}
+            Err(e) => log::warn!("failed to cache server {}'s icon: {e}", added.id),
+        }
+    });
+    Ok(server)
+}
+
+/// The server's icon as a `data:` URL, fetched on first use and cached;
+/// `None` when the site has none.
+#[tauri::command]
+pub async fn get_server_favicon(
+    server_id: i64,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Option<String>, AppError> {
+    let server = state.db.get_server(server_id)?;
+    Ok(favicon::get(&app_handle, &server)
+        .await?
+        .map(|icon| icon.data_url()))
+}
+
+/// Replace the server's name with the one its page gives now; see
+/// `server_name::fetch`.
+#[tauri::command]
+pub async fn refresh_server_name(
+    server_id: i64,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Server, AppError> {
+    let server = state.db.get_server(server_id)?;
+    state
+        .db
+        .set_server_name(server_id, &server_name::fetch(&server).await)?;
+    tray::refresh_menu(&app_handle);
+    state.db.get_server(server_id)
+}
+
+/// Add every URL found in a pasted blob (a list, or a forwarded message),
+/// skipping duplicates, and report what happened to each.
+#[tauri::command]
+pub async fn add_servers_from_text(
+    text: String,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<BulkAddEntry>, AppError> {
+    let report = bulk_add::add_from_text(&state.db, &text)?;
+    if report.iter().any(|e| e.status == BulkAddStatus::Added) {
+        tray::refresh_menu(&app_handle);
+    }
+    Ok(report)
+}
+
+/// Add a simulated server that syncs instantly with the given offset and
+/// jitter. Adding the same configuration twice returns the existing server.
+#[tauri::command]
+pub async fn add_demo_server(
+    demo: DemoServer,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Server, AppError> {
+    demo.validate()?;
+    let url = demo.url();
+    if let Some(existing) = state.db.find_server_by_url(&url)? {
+        return Ok(existing);
+    }
+    let server = state.db.add_server(&url)?;
+    let name = format!("Demo ({:+} ms)", demo.offset_ms);
+    state
+        .db
+        .update_server_config(server.id, Some(&name), &server.extractor_type)?;
+    tray::refresh_menu(&app_handle);
+    state.db.get_server(server.id)
+}
+
+#[tauri::command]
+pub async fn list_reference_sources() -> Result<Vec<ReferenceSource>, AppError> {
+    Ok(reference_sources::SOURCES.to_vec())
+}
+
+/// Add a public time service as a server to compare against; see
+/// `reference_sources::add`.
+#[tauri::command]
+pub async fn add_reference_source(
+    kind: String,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Server, AppError> {
+    let server = reference_sources::add(&state.db, &kind)?;
+    tray::refresh_menu(&app_handle);
+    Ok(server)
+}
+
+/// Probe a URL once before saving it: reachability, Date header, HTTP
+/// version, redirects and round trip.
+#[tauri::command]
+pub async fn validate_url(url: String) -> Result<UrlValidation, AppError> {
+    preflight::validate_url(url).await
+}
+
+/// Three probes against a saved server for a quick up/RTT check.
+#[tauri::command]
+pub async fn test_connection(
+    server_id: i64,
+    state: State<'_, AppState>,
+) -> Result<ConnectionTest, AppError> {
+    let server = state.db.get_server(server_id)?;
+    let profile = sync_profile::resolve(&server, &state.db.get_settings()?);
+    sync_engine::test_connection(
+        &server.url,
+        &state.probe_settings(&server, &profile),
+        state.extractors.get(&server.extractor_type)?.as_ref(),
+        &profile,
+    )
+    .await
+}
+
+/// One probe for a rough, unverified offset; nothing is saved.
+#[tauri::command]
+pub async fn estimate_offset(
+    server_id: i64,
+    state: State<'_, AppState>,
+) -> Result<OffsetEstimate, AppError> {
+    let server = state.db.get_server(server_id)?;
+    let profile = sync_profile::resolve(&server, &state.db.get_settings()?);
+    sync_engine::estimate_offset(
+        server.id,
+        &server.url,
+        &state.probe_settings(&server, &profile),
+        state.extractors.get(&server.extractor_type)?.as_ref(),
+        &profile,
+    )
+    .await
+}
+
+/// Run both offset estimators against a server and keep the comparison.
+/// Does not touch the server's offset or sync history.
+#[tauri::command]
+pub async fn benchmark_sync(
+    server_id: i64,
+    state: State<'_, AppState>,
+) -> Result<BenchmarkReport, AppError> {
+    let server = state.db.get_server(server_id)?;
+    let profile = sync_profile::resolve(&server, &state.db.get_settings()?);
+    let report = sync_engine::benchmark(
+        server.id,
+        &server.url,
+        &state.probe_settings(&server, &profile),
+        state.extractors.get(&server.extractor_type)?.as_ref(),
+        &profile,
+        CancellationToken::new(),
+    )
+    .await?;
+    state.db.save_benchmark_report(&report)
+}
+
+#[tauri::command]
+pub async fn list_benchmark_reports(
+    server_id: i64,
+    state: State<'_, AppState>,
+) -> Result<Vec<BenchmarkReport>, AppError> {
+    state.db.list_benchmark_reports(server_id)
+}
+
+#[tauri::command]
+pub async fn list_sync_recordings(app: AppHandle) -> Result<Vec<RecordingInfo>, AppError> {
+    Ok(sync_recording::list(&sync_recording::recording_dir(&app)?))
+}
+
+/// Feed a recorded sync back through the engine, off the network.
+#[tauri::command]
+pub async fn replay_sync_recording(file: String, app: AppHandle) -> Result<ReplayReport, AppError> {
+    let path = sync_recording::path_in(&sync_recording::recording_dir(&app)?, &file)?;
+    Ok(sync_engine::replay(&sync_recording::load(&path)?).await)
+}
+
+#[tauri::command]
+pub async fn list_sync_profiles() -> Result<Vec<SyncProfile>, AppError> {
+    Ok(sync_profile::PROFILES.to_vec())
+}
+
+/// Pin a server to a profile, or pass `None` to follow the global setting.
+#[tauri::command]
+pub async fn set_server_sync_profile(
+    server_id: i64,
+    profile: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Server, AppError> {
+    if let Some(id) = &profile {
+        sync_profile::find(id)?;
+    }
+    state
+        .db
+        .set_server_sync_profile(server_id, profile.as_deref())?;
+    state.db.get_server(server_id)
+}
+
+/// Set a server's path asymmetry by hand, or pass `None` to assume a
+/// symmetric path again.
+#[tauri::command]
+pub async fn set_server_asymmetry(
+    server_id: i64,
+    asymmetry: Option<f64>,
+    state: State<'_, AppState>,
+) -> Result<Server, AppError> {
+    if let Some(value) = asymmetry {
+        asymmetry::validate(value)?;
+    }
+    state.db.set_server_asymmetry(server_id, asymmetry)?;
+    state.db.get_server(server_id)
+}
+
+/// Set the User-Agent sent with this server's probes, or clear it (`None`
+/// or blank) to send `sync_engine::DEFAULT_USER_AGENT`.
+#[tauri::command]
+pub async fn set_server_user_agent(
+    server_id: i64,
+    user_agent: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Server, AppError> {
+    let user_agent = user_agent
+        .as_deref()
+        .map(str::trim)
+        .filter(|ua| !ua.is_empty());
+    if let Some(ua) = user_agent {
+        let problem = if ua.len() > sync_engine::MAX_USER_AGENT_LEN {
+            Some(format!(
+                "must be at most {} characters",
+                sync_engine::MAX_USER_AGENT_LEN
+            ))
+        } else if reqwest::header::HeaderValue::from_str(ua).is_err() {
+            Some("contains characters not allowed in a header".to_string())
+        } else {
+            None
+        };
+        if let Some(message) = problem {
+            return Err(AppError::Validation(vec![FieldError {
+                field: "user_agent".to_string(),
+                message,
+            }]));
+        }
+    }
+    state.db.set_server_user_agent(server_id, user_agent)?;
+    state.db.get_server(server_id)
+}
+
+/// Replace this server's advanced socket options.
+#[tauri::command]
+pub async fn set_server_transport(
+    server_id: i64,
+    transport: TransportOptions,
+    state: State<'_, AppState>,
+) -> Result<Server, AppError> {
+    if let Some(ms) = transport.connect_timeout_ms {
+        let range = sync_engine::CONNECT_TIMEOUT_RANGE_MS;
+        if !range.contains(&ms) {
+            return Err(AppError::Validation(vec![FieldError {
+                field: "connect_timeout_ms".to_string(),
+                message: format!("must be between {} and {}", range.start(), range.end()),
+            }]));
+        }
+    }
+    state.db.set_server_transport(server_id, &transport)?;
+    state.db.get_server(server_id)
+}
+
+/// Set how fast this server is probed (see `sync_profile::resolve`), or
+/// pass `None` to follow the global setting.
+#[tauri::command]
+pub async fn set_server_request_interval(
+    server_id: i64,
+    interval_ms: Option<u32>,
+    state: State<'_, AppState>,
+) -> Result<Server, AppError> {
+    if let Some(ms) = interval_ms {
+        let range = sync_profile::REQUEST_INTERVAL_RANGE_MS;
+        if !range.contains(&ms) {
+            return Err(AppError::Validation(vec![FieldError {
+                field: "interval_ms".to_string(),
+                message: format!("must be between {} and {}", range.start(), range.end()),
+            }]));
+        }
+    }
+    state
+        .db
+        .set_server_request_interval(server_id, interval_ms)?;
+    state.db.get_server(server_id)
+}
+
+/// Mark a server as self-hosted or otherwise trusted, letting it be
+/// probed faster than the `public_host_floor_ms` setting.
+#[tauri::command]
+pub async fn set_server_trusted(
+    server_id: i64,
+    trusted: bool,
+    state: State<'_, AppState>,
+) -> Result<Server, AppError> {
+    state.db.set_server_trusted(server_id, trusted)?;
+    state.db.get_server(server_id)
+}
+
+/// Pin the timezone `server-local` targets are read in; `None` goes back
+/// to guessing it from the URL and headers.
+#[tauri::command]
+pub async fn set_server_timezone(
+    server_id: i64,
+    timezone: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Server, AppError> {
+    let timezone = timezone
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty());
+    if let Some(name) = &timezone {
+        server_timezone::parse(name)?;
+    }
+    state.db.set_server_timezone(server_id, timezone.as_deref())?;
+    state.db.get_server(server_id)
+}
+
+/// Flag a server to start syncing when the app launches.
+#[tauri::command]
+pub async fn set_server_sync_on_launch(
+    server_id: i64,
+    enabled: bool,
+    state: State<'_, AppState>,
+) -> Result<Server, AppError> {
+    state.db.set_server_sync_on_launch(server_id, enabled)?;
+    state.db.get_server(server_id)
+}
+
+/// Estimate a server's path asymmetry and keep it. Runs a symmetric sync
+/// (not saved to history) and puts its difference from NTP time down to
+/// the path, so only use it on servers whose clock is NTP-synced.
+#[tauri::command]
+pub async fn calibrate_asymmetry(
+    server_id: i64,
+    state: State<'_, AppState>,
+) -> Result<AsymmetryCalibration, AppError> {
+    let server = state.db.get_server(server_id)?;
+    let settings = state.db.get_settings()?;
+    let profile = SyncProfile {
+        asymmetry: asymmetry::SYMMETRIC,
+        ..sync_profile::resolve(&server, &settings)
     };
+    let ntp = time_source::measure(&settings.external_time_source).await?;
+    let result = sync_engine::synchronize(
+        server.id,
+        &server.url,
+        &state.probe_settings(&server, &profile),
+        state.extractors.get(&server.extractor_type)?.as_ref(),
+        &profile,
+        CancellationToken::new(),
+        Box::new(|_| {}),
+    )
+    .await?;
+    let rtt_ms = result.latency_profile.median * 1000.0;
+    let value = asymmetry::estimate(result.total_offset_ms, ntp.offset_ms, rtt_ms)?;
+    state.db.set_server_asymmetry(server.id, Some(value))?;
+    Ok(AsymmetryCalibration {
+        server_id: server.id,
+        asymmetry: value,
+        symmetric_offset_ms: result.total_offset_ms,
+        ntp_offset_ms: ntp.offset_ms,
+        rtt_ms,
+        calibrated_at: Utc::now(),
+    })
+}
+
+#[tauri::command]
+pub async fn list_domain_presets() -> Result<Vec<DomainPreset>, AppError> {
+    Ok(domain_presets::PRESETS.to_vec())
+}
 
-    state.db.add_server(&final_url)
+/// Apply a preset to a server regardless of its host, or pass `None` to
+/// drop back to plain `HEAD` probes. The URL path is only filled in for
+/// bare-domain URLs.
+#[tauri::command]
+pub async fn set_server_domain_preset(
+    server_id: i64,
+    preset: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Server, AppError> {
+    let server = state.db.get_server(server_id)?;
+    match preset {
+        Some(id) => {
+            let preset = domain_presets::find(&id)?;
+            state.db.set_server_preset(
+                server_id,
+                Some(preset.id),
+                &domain_presets::apply_path(&server.url, &preset),
+                preset.extractor_type,
+                preset.probe_method,
+            )?;
+        }
+        None => state.db.set_server_preset(
+            server_id,
+            None,
+            &server.url,
+            &server.extractor_type,
+            domain_presets::HEAD,
+        )?,
+    }
+    state.db.get_server(server_id)
+}
+
+/// Built-in extractors and the loaded WASM plugins.
+#[tauri::command]
+pub async fn list_extractors(state: State<'_, AppState>) -> Result<Vec<ExtractorInfo>, AppError> {
+    Ok(state.extractors.list())
+}
+
+/// Rescan the plugins directory; returns the files that failed to load.
+#[tauri::command]
+pub async fn reload_extractor_plugins(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<PluginFailure>, AppError> {
+    let dir = wasm_extractor::plugin_dir(&app_handle)?;
+    std::fs::create_dir_all(&dir)?;
+    Ok(state.extractors.load_plugins(&dir))
+}
+
+/// Point a server at another extractor; it must be loaded right now.
+#[tauri::command]
+pub async fn set_server_extractor(
+    server_id: i64,
+    extractor_type: String,
+    state: State<'_, AppState>,
+) -> Result<Server, AppError> {
+    let server = state.db.get_server(server_id)?;
+    state.extractors.get(&extractor_type)?;
+    state
+        .db
+        .update_server_config(server.id, server.name.as_deref(), &extractor_type)?;
+    state.db.get_server(server_id)
 }
 
 #[tauri::command]
@@ -34,15 +538,278 @@ pub async fn list_servers(state: State<'_, AppState>) -> Result<Vec<Server>, App
     state.db.list_servers()
 }
 
+/// Move a server, with its history and targets, to the trash. It can be
+/// restored for `trash_retention_days`.
+#[tauri::command]
+pub async fn delete_server(
+    id: i64,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    {
+        let mut syncs = state.active_syncs.lock().expect("active_syncs poisoned");
+        if let Some(token) = syncs.remove(&id) {
+            token.cancel();
+        }
+    }
+    state.db.trash_server(id, Utc::now())?;
+    state.alerts.refresh();
+    tray::refresh_menu(&app_handle);
+    Ok(())
+}
+
+/// Deleted servers still restorable, after purging expired ones.
+#[tauri::command]
+pub async fn list_trash(state: State<'_, AppState>) -> Result<Vec<TrashedServer>, AppError> {
+    let retention = state.db.get_settings()?.trash_retention();
+    state.db.empty_trash(Some(Utc::now() - retention))?;
+    state.db.list_trashed_servers(retention)
+}
+
 #[tauri::command]
-pub async fn delete_server(id: i64, state: State<'_, AppState>) -> Result<(), AppError> {
+pub async fn restore_server(
+    id: i64,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Server, AppError> {
+    let server = state.db.restore_server(id)?;
+    state.alerts.refresh();
+    tray::refresh_menu(&app_handle);
+    Ok(server)
+}
+
+/// Delete one server for good, skipping the trash.
+#[tauri::command]
+pub async fn purge_server(
+    id: i64,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
     {
         let mut syncs = state.active_syncs.lock().expect("active_syncs poisoned");
         if let Some(token) = syncs.remove(&id) {
             token.cancel();
         }
     }
-    state.db.delete_server(id)
+    state.db.delete_server(id)?;
+    state.alerts.refresh();
+    tray::refresh_menu(&app_handle);
+    Ok(())
+}
+
+/// Purge every trashed server now; returns how many.
+#[tauri::command]
+pub async fn empty_trash(state: State<'_, AppState>) -> Result<usize, AppError> {
+    state.db.empty_trash(None)
+}
+
+/// Servers, staleness, next scheduled syncs and upcoming targets in one
+/// payload, so the app does not fan out per-server requests on start.
+#[tauri::command]
+pub async fn get_dashboard(state: State<'_, AppState>) -> Result<Dashboard, AppError> {
+    let settings = state.db.get_settings()?;
+    Ok(dashboard::build(
+        state.db.list_servers()?,
+        state.db.list_targets()?,
+        settings.auto_resync_interval_minutes,
+        state.scheduler.status().paused,
+        state.network.changed_at(),
+        settings.extrapolate_drift,
+        Utc::now(),
+    ))
+}
+
+#[tauri::command]
+pub async fn get_primary_server(state: State<'_, AppState>) -> Result<Option<i64>, AppError> {
+    state.db.get_primary_server()
+}
+
+#[tauri::command]
+pub async fn set_primary_server(
+    id: Option<i64>,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    if let Some(id) = id {
+        state.db.get_server(id)?;
+    }
+    state.db.set_primary_server(id)
+}
+
+#[tauri::command]
+pub async fn get_reference_server(state: State<'_, AppState>) -> Result<Option<i64>, AppError> {
+    state.db.get_reference_server()
+}
+
+/// The server other offsets are reported against by
+/// `get_relative_offsets`; `None` goes back to the local clock.
+#[tauri::command]
+pub async fn set_reference_server(
+    id: Option<i64>,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    if let Some(id) = id {
+        state.db.get_server(id)?;
+    }
+    state.db.set_reference_server(id)
+}
+
+/// Every server's offset relative to `reference_id`, or to the saved
+/// reference server when omitted.
+#[tauri::command]
+pub async fn get_relative_offsets(
+    reference_id: Option<i64>,
+    state: State<'_, AppState>,
+) -> Result<Vec<RelativeOffset>, AppError> {
+    let reference_id = match reference_id {
+        Some(id) => id,
+        None => state.db.get_reference_server()?.ok_or_else(|| {
+            AppError::Validation(vec![FieldError {
+                field: "reference_id".to_string(),
+                message: "no reference server is set".to_string(),
+            }])
+        })?,
+    };
+    let reference = state.db.get_server(reference_id)?;
+    if reference.offset_ms.is_none() {
+        return Err(AppError::NotSynced(reference_id));
+    }
+    Ok(RelativeOffset::for_servers(
+        &state.db.list_servers()?,
+        &reference,
+    ))
+}
+
+/// Pairwise offset differences, staleness and jitter for `server_ids`, in
+/// the given order. Duplicate ids are dropped.
+#[tauri::command]
+pub async fn compare_servers(
+    server_ids: Vec<i64>,
+    state: State<'_, AppState>,
+) -> Result<ServerComparison, AppError> {
+    let mut ids = server_ids;
+    let mut seen = std::collections::HashSet::new();
+    ids.retain(|id| seen.insert(*id));
+    if ids.len() < 2 {
+        return Err(AppError::Validation(vec![FieldError {
+            field: "server_ids".to_string(),
+            message: "pick at least two servers to compare".to_string(),
+        }]));
+    }
+
+    let mut entries = Vec::with_capacity(ids.len());
+    for id in ids {
+        let server = state.db.get_server(id)?;
+        let latest = state.db.get_sync_history(id, None, Some(1))?.pop();
+        entries.push((server, latest));
+    }
+    Ok(comparison::compare(&entries, Utc::now()))
+}
+
+/// `target_at` is an RFC 3339 instant or a wall-clock time such as
+/// `10:00 server-local`; see [`TargetTime`].
+#[tauri::command]
+pub async fn create_target(
+    server_id: i64,
+    target_at: String,
+    label: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Target, AppError> {
+    let server = state.db.get_server(server_id)?;
+    let target_at = target_at
+        .parse::<TargetTime>()?
+        .resolve(server_timezone::of(&server), Utc::now())?;
+    let target = state
+        .db
+        .add_target(server_id, target_at, label.as_deref())?;
+    state.alerts.refresh();
+    Ok(target)
+}
+
+#[tauri::command]
+pub async fn list_targets(state: State<'_, AppState>) -> Result<Vec<Target>, AppError> {
+    state.db.list_targets()
+}
+
+/// Override how `id`'s alerts are delivered; `None` follows the
+/// `alert_method` setting again.
+#[tauri::command]
+pub async fn set_target_alert_method(
+    id: i64,
+    method: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Target, AppError> {
+    if matches!(method.as_deref(), Some(m) if !alerts::METHODS.contains(&m)) {
+        return Err(AppError::Validation(vec![FieldError {
+            field: "alert_method".to_string(),
+            message: format!("must be one of {}", alerts::METHODS.join(", ")),
+        }]));
+    }
+    state.db.set_target_alert_method(id, method.as_deref())?;
+    state.alerts.refresh();
+    state.db.get_target(id)
+}
+
+#[tauri::command]
+pub async fn delete_target(id: i64, state: State<'_, AppState>) -> Result<(), AppError> {
+    state.db.delete_target(id)?;
+    state.alerts.refresh();
+    Ok(())
+}
+
+/// The hook script's source, empty when none is saved.
+#[tauri::command]
+pub async fn get_hook_script(app_handle: tauri::AppHandle) -> Result<String, AppError> {
+    scripting::saved_source(&app_handle)
+}
+
+/// Compile, save and load the hook script; returns the hooks it defines.
+/// A script that doesn't compile is rejected and the running one kept.
+#[tauri::command]
+pub async fn save_hook_script(
+    source: String,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<String>, AppError> {
+    state.hooks.save(&app_handle, &source)
+}
+
+/// Queue a one-time sync of `server_id` for `at`, e.g. a few minutes
+/// before an on-sale. Survives restarts; see `scheduler::run_scheduled`.
+#[tauri::command]
+pub async fn schedule_sync(
+    server_id: i64,
+    at: DateTime<Utc>,
+    state: State<'_, AppState>,
+) -> Result<ScheduledSync, AppError> {
+    state.db.get_server(server_id)?;
+    if at <= Utc::now() {
+        return Err(AppError::Validation(vec![FieldError {
+            field: "at".to_string(),
+            message: "must be in the future".to_string(),
+        }]));
+    }
+    state.db.add_scheduled_sync(server_id, at)
+}
+
+#[tauri::command]
+pub async fn list_scheduled_syncs(
+    state: State<'_, AppState>,
+) -> Result<Vec<ScheduledSync>, AppError> {
+    state.db.list_scheduled_syncs()
+}
+
+#[tauri::command]
+pub async fn cancel_scheduled_sync(id: i64, state: State<'_, AppState>) -> Result<(), AppError> {
+    state.db.delete_scheduled_sync(id)
+}
+
+/// Ask the OS to allow target alerts. Returns "granted", "denied" or
+/// "prompt".
+#[tauri::command]
+pub async fn request_notification_permission(
+    app_handle: tauri::AppHandle,
+) -> Result<String, AppError> {
+    alerts::request_permission(&app_handle)
 }
 
 #[tauri::command]
@@ -50,10 +817,85 @@ pub async fn start_sync(
     id: i64,
     on_event: Channel<SyncEvent>,
     app_handle: tauri::AppHandle,
-    state: State<'_, AppState>,
 ) -> Result<(), AppError> {
+    let sink: SyncEventSink = Arc::new(move |event| {
+        let _ = on_event.send(event);
+    });
+    spawn_sync(&app_handle, id, sink)
+}
+
+/// Receives every event of one sync run.
+pub(crate) type SyncEventSink = Arc<dyn Fn(SyncEvent) + Send + Sync + 'static>;
+
+/// Sink for syncs started from Rust (tray, schedulers): events are broadcast
+/// to all windows as `SYNC_EVENT` instead of a per-call Channel.
+pub(crate) fn broadcast_sink(app_handle: &tauri::AppHandle) -> SyncEventSink {
+    let handle = app_handle.clone();
+    Arc::new(move |event| {
+        let _ = handle.emit(SYNC_EVENT, event);
+    })
+}
+
+pub(crate) const SYNC_EVENT: &str = "sync-event";
+
+pub const SERVER_STATUS_EVENT: &str = "server-status-changed";
+
+/// Store a server's status and tell every window, so the overlay and other
+/// windows don't have to poll for it.
+fn set_server_status(
+    app_handle: &tauri::AppHandle,
+    id: i64,
+    status: ServerStatus,
+) -> Result<(), AppError> {
+    app_handle
+        .state::<AppState>()
+        .db
+        .update_server_status(id, &status)?;
+    let change = ServerStatusChange {
+        server_id: id,
+        status,
+    };
+    if let Err(e) = app_handle.emit(SERVER_STATUS_EVENT, change) {
+        log::warn!("failed to emit server status change: {e}");
+    }
+    Ok(())
+}
+
+/// Start a background sync for `id`, reporting through `sink`. This is the
+/// single path used by `start_sync` and every Rust-side trigger.
+pub(crate) fn spawn_sync(
+    app_handle: &tauri::AppHandle,
+    id: i64,
+    sink: SyncEventSink,
+) -> Result<(), AppError> {
+    let state = app_handle.state::<AppState>();
     let server = state.db.get_server(id)?;
-    let url = server.url.clone();
+    let settings = state.db.get_settings()?;
+    let profile = sync_profile::resolve(&server, &settings);
+    let warm_client = state.keep_warm.client(&server, &profile);
+    let limiter = state.host_limiter.clone();
+    let extractor = state.extractors.get(&server.extractor_type)?;
+    let recorder = settings
+        .record_syncs
+        .then(|| Arc::new(ProbeRecorder::new()));
+    let portal_check = settings.captive_portal_check;
+    // Demo servers never touch the network; "none" opts out of references.
+    let precheck = if settings.network_precheck && DemoServer::from_url(&server.url).is_none() {
+        TimeSource::from_setting(&settings.external_time_source)
+            .ok()
+            .flatten()
+    } else {
+        None
+    };
+
+    // Mirror every event to local WebSocket subscribers as well.
+    let sink: SyncEventSink = {
+        let subscribers = state.local_api.sync_events();
+        Arc::new(move |event: SyncEvent| {
+            let _ = subscribers.send(event.clone());
+            sink(event)
+        })
+    };
 
     let token = CancellationToken::new();
     {
@@ -61,14 +903,24 @@ pub async fn start_sync(
         syncs.insert(id, token.clone());
     }
 
-    state.db.update_server_status(id, &ServerStatus::Syncing)?;
+    set_server_status(app_handle, id, ServerStatus::Syncing)?;
+    tray::refresh_menu(app_handle);
+
+    // Keep the machine awake until this sync finishes, whatever the outcome.
+    let sleep_guard = state.sleep_inhibitor.acquire();
 
     let sync_start = Instant::now();
-    let on_event_clone = on_event.clone();
-    let extractor = DateHeaderExtractor;
+    let heartbeat = Arc::new(Heartbeat::new());
+    let stall_limit = watchdog::stall_limit(settings.sync_stall_timeout_secs, &profile);
 
-    // Progress callback sends through Channel
-    let on_event_progress = on_event.clone();
+    // Progress callback forwards to the sink and the taskbar indicator,
+    // rate-limited per sync.
+    let sink_progress = sink.clone();
+    let progress_app = app_handle.clone();
+    let last_percent = std::sync::Mutex::new(0.0_f64);
+    let coalescer = std::sync::Mutex::new(ProgressCoalescer::new(settings.progress_event_limit));
+    let eta = std::sync::Mutex::new(EtaEstimator::new(&profile));
+    let pulse = heartbeat.clone();
     let progress_callback: sync_engine::ProgressCallback = Box::new(move |data| {
         let phase: SyncPhase = serde_json::from_value(
             data.get("phase")
@@ -76,8 +928,13 @@ pub async fn start_sync(
                 .clone(),
         )
         .expect("progress phase must be a valid SyncPhase");
+        pulse.beat(phase);
 
+        // Retry notices carry no progress of their own; hold the bar still.
+        let is_retry = data.get("retry").is_some();
+        let mut last = last_percent.lock().expect("last_percent poisoned");
         let progress_percent = match phase {
+            _ if is_retry => *last,
             SyncPhase::LatencyProfiling => {
                 let idx = data
                     .get("probe_index")
@@ -89,33 +946,124 @@ pub async fn start_sync(
                     .unwrap_or(10.0);
                 (idx / total) * 25.0
             }
-            SyncPhase::WholeSecondOffset => 30.0,
             SyncPhase::BinarySearch => {
                 let convergence = data
                     .get("convergence_percent")
                     .and_then(|v| v.as_f64())
                     .unwrap_or(0.0);
-                35.0 + convergence * 0.55
+                25.0 + convergence * 0.63
             }
+            SyncPhase::WholeSecondOffset => 90.0,
             SyncPhase::Verification => 92.0,
             SyncPhase::Complete => 100.0,
         };
+        *last = progress_percent;
+        drop(last);
 
         let elapsed_ms = sync_start.elapsed().as_millis() as u64;
-
-        let _ = on_event_progress.send(SyncEvent::Progress(SyncProgressPayload {
+        let eta_ms = eta
+            .lock()
+            .expect("eta poisoned")
+            .remaining_ms(phase, &data);
+        let payload = SyncProgressPayload {
             server_id: id,
             phase,
             progress_percent,
             phase_data: data,
             elapsed_ms,
-        }));
+            eta_ms,
+        };
+        let ready = coalescer.lock().expect("coalescer poisoned").offer(
+            Instant::now(),
+            phase,
+            is_retry,
+            payload,
+        );
+
+        for payload in ready {
+            progress_app.state::<AppState>().taskbar.update(
+                &progress_app,
+                id,
+                payload.progress_percent,
+            );
+            sink_progress(SyncEvent::Progress(payload));
+        }
     });
 
     let handle = app_handle.clone();
 
     tokio::spawn(async move {
-        let result = sync_engine::synchronize(id, &url, &extractor, token, progress_callback).await;
+        let probe_settings = ProbeSettings {
+            client: warm_client,
+            recorder: recorder.clone(),
+            limiter: Some(limiter),
+            portal_check,
+            ..ProbeSettings::for_server(&server)
+        };
+        let baseline = match precheck {
+            Some(source) => Some(network_baseline::measure(source).await),
+            None => None,
+        };
+        if let Some(baseline) = &baseline {
+            progress_callback(serde_json::json!({
+                "phase": SyncPhase::LatencyProfiling,
+                "baseline": baseline,
+            }));
+        }
+
+        // A probe stuck on a dead connection never returns, so the
+        // watchdog drops the whole sync rather than waiting on the token.
+        let result = match baseline.filter(|b| b.degraded) {
+            Some(baseline) => {
+                log::warn!("not syncing server {id}: {}", baseline.summary());
+                Err(SyncFailure::before_start(AppError::NetworkDegraded(
+                    baseline.summary(),
+                )))
+            }
+            None => tokio::select! {
+                result = sync_engine::synchronize(
+                    id,
+                    &server.url,
+                    &probe_settings,
+                    extractor.as_ref(),
+                    &profile,
+                    token,
+                    progress_callback,
+                ) => result,
+                phase = heartbeat.stalled(stall_limit) => {
+                    log::warn!("sync of server {id} stalled in {phase:?}; stopping it");
+                    if let Err(e) = watchdog::notify(&handle, &server, stall_limit) {
+                        log::warn!("failed to notify about a stalled sync: {e}");
+                    }
+                    Err(SyncFailure {
+                        error: AppError::SyncStalled(stall_limit.as_secs()),
+                        context: FailureContext {
+                            phase,
+                            phase_attempts: 0,
+                            total_attempts: 0,
+                            latency_profile: None,
+                            partial_offset_ms: None,
+                            elapsed_ms: sync_start.elapsed().as_millis() as u64,
+                        },
+                    })
+                }
+            },
+        };
+        drop(sleep_guard);
+
+        if let Some(recorder) = recorder {
+            // Serialized and written off the runtime; nothing waits on it.
+            let recording = SyncRecording::new(&server, &profile, recorder.take(), &result);
+            let handle_inner = handle.clone();
+            tokio::task::spawn_blocking(move || {
+                match sync_recording::recording_dir(&handle_inner)
+                    .and_then(|dir| sync_recording::save(&dir, &recording))
+                {
+                    Ok(path) => log::info!("sync recorded to {}", path.display()),
+                    Err(e) => log::warn!("failed to save the sync recording: {e}"),
+                }
+            });
+        }
 
         let app_state = handle.state::<AppState>();
 
@@ -127,45 +1075,106 @@ pub async fn start_sync(
                 .expect("active_syncs poisoned");
             syncs.remove(&id);
         }
+        app_state.taskbar.finish(&handle, id);
 
         match result {
             Ok(ref sync_result) => {
                 // Persist to DB via spawn_blocking to avoid blocking the tokio runtime.
                 // Gracefully ignore errors (server may have been deleted during sync).
-                let sync_result_clone = sync_result.clone();
+                let mut sync_result_clone = sync_result.clone();
                 let handle_inner = handle.clone();
-                let _ = tokio::task::spawn_blocking(move || {
+                let saved = tokio::task::spawn_blocking(move || {
                     let state = handle_inner.state::<AppState>();
+                    let previous_offset_ms = state.db.get_server(id).ok().and_then(|s| s.offset_ms);
                     let _ = state.db.update_server_offset(
                         id,
                         sync_result_clone.total_offset_ms,
                         sync_result_clone.synced_at,
                     );
-                    let _ = state.db.update_server_status(id, &ServerStatus::Synced);
-                    let _ = state.db.save_sync_result(&sync_result_clone);
+                    let _ = set_server_status(&handle_inner, id, ServerStatus::Synced);
+                    sync_result_clone.network = Some(network_context::capture());
+                    let _ = anomaly::flag(&state.db, &mut sync_result_clone);
+                    let certificate_warning = certificate::check(&state.db, &sync_result_clone)
+                        .ok()
+                        .flatten();
+                    let saved_id = state.db.save_sync_result(&sync_result_clone).ok();
+                    if let Some(warning) = &certificate_warning {
+                        let notified = state.db.get_server(id).and_then(|server| {
+                            certificate::notify(&handle_inner, &server, warning)
+                        });
+                        if let Err(e) = notified {
+                            log::warn!("failed to warn about server {id}'s certificate: {e}");
+                        }
+                    }
+                    if let Err(e) = anomaly::notify(&handle_inner, &state.db, &sync_result_clone) {
+                        log::warn!("failed to notify about an anomalous sync: {e}");
+                    }
+                    if let Some(fingerprint) = &sync_result_clone.fingerprint {
+                        let _ = state.db.set_server_fingerprint(id, fingerprint);
+                    }
+                    let _ = health::refresh(&state.db, id, Utc::now());
+                    let _ = drift::refresh(&state.db, id);
+                    state.alerts.refresh();
+
+                    sync_result_clone.id = saved_id.unwrap_or_default();
+                    state.hooks.fire(scripting::ON_SYNC_COMPLETE, &sync_result_clone);
+                    let threshold_ms = state
+                        .db
+                        .get_settings()
+                        .map(|s| s.drift_warning_threshold_ms)
+                        .unwrap_or_default();
+                    if let Some(warning) = drift::warning(
+                        id,
+                        previous_offset_ms,
+                        sync_result_clone.total_offset_ms,
+                        threshold_ms,
+                    ) {
+                        state.hooks.fire(scripting::ON_DRIFT_WARNING, &warning);
+                    }
+                    (saved_id, sync_result_clone)
                 })
-                .await;
+                .await
+                .ok();
 
-                let _ = on_event_clone.send(SyncEvent::Complete(SyncCompletePayload {
+                let result = match saved {
+                    Some((saved_id, mut saved)) => {
+                        saved.id = saved_id.unwrap_or_default();
+                        saved
+                    }
+                    None => sync_result.clone(),
+                };
+                sink(SyncEvent::Complete(Box::new(SyncCompletePayload {
                     server_id: id,
-                    result: sync_result.clone(),
-                }));
+                    result,
+                })));
             }
-            Err(ref e) => {
+            Err(ref failure) => {
                 // Gracefully ignore DB errors (server may have been deleted)
                 let handle_inner = handle.clone();
+                let error = failure.error.to_string();
+                let record = (!matches!(failure.error, AppError::Cancelled))
+                    .then(|| (failure.error.code(), error, failure.context.clone()));
                 let _ = tokio::task::spawn_blocking(move || {
                     let state = handle_inner.state::<AppState>();
-                    let _ = state.db.update_server_status(id, &ServerStatus::Error);
+                    let _ = set_server_status(&handle_inner, id, ServerStatus::Error);
+                    if let Some((code, message, context)) = record {
+                        let _ = state
+                            .db
+                            .save_sync_error(id, code, &message, &context, Utc::now());
+                    }
+                    let _ = health::refresh(&state.db, id, Utc::now());
                 })
                 .await;
 
-                let _ = on_event_clone.send(SyncEvent::Error(SyncErrorPayload {
+                sink(SyncEvent::Error(SyncErrorPayload {
                     server_id: id,
-                    error: e.to_string(),
+                    error: failure.error.payload(),
+                    context: failure.context.clone(),
                 }));
             }
         }
+
+        tray::refresh_menu(&handle);
     });
 
     Ok(())
@@ -190,6 +1199,144 @@ pub async fn get_sync_history(
     state.db.get_sync_history(id, since.as_deref(), limit)
 }
 
+/// Results per page when the caller does not say, and the most it may ask for.
+const DEFAULT_HISTORY_PAGE: i64 = 50;
+const MAX_HISTORY_PAGE: i64 = 500;
+
+/// `get_sync_history` one page at a time, with the total row count, so
+/// long histories are not sent over IPC in one go. `verified` and
+/// `phase_reached` narrow it to e.g. unverified syncs when debugging.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn get_sync_history_page(
+    id: i64,
+    since: Option<String>,
+    verified: Option<bool>,
+    phase_reached: Option<SyncPhase>,
+    anomalous: Option<bool>,
+    offset: Option<i64>,
+    limit: Option<i64>,
+    state: State<'_, AppState>,
+) -> Result<SyncHistoryPage, AppError> {
+    let offset = offset.unwrap_or(0);
+    let limit = limit.unwrap_or(DEFAULT_HISTORY_PAGE);
+    let mut errors = Vec::new();
+    if offset < 0 {
+        errors.push(FieldError {
+            field: "offset".to_string(),
+            message: "must not be negative".to_string(),
+        });
+    }
+    if !(1..=MAX_HISTORY_PAGE).contains(&limit) {
+        errors.push(FieldError {
+            field: "limit".to_string(),
+            message: format!("must be between 1 and {MAX_HISTORY_PAGE}"),
+        });
+    }
+    if !errors.is_empty() {
+        return Err(AppError::Validation(errors));
+    }
+    let filter = SyncHistoryFilter {
+        since,
+        verified,
+        phase_reached,
+        anomalous,
+    };
+    state.db.get_sync_history_page(id, &filter, offset, limit)
+}
+
+/// A stored sync rendered for sharing; `format` is "markdown" (default) or
+/// "json".
+#[tauri::command]
+pub async fn generate_sync_report(
+    sync_id: i64,
+    format: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<String, AppError> {
+    let result = state.db.get_sync_result(sync_id)?;
+    let server = state.db.get_server(result.server_id)?;
+    SyncReport::new(&server, &result).render(format.as_deref().unwrap_or(sync_report::MARKDOWN))
+}
+
+/// Write the server's offsets over time to `path` for plotting; `format`
+/// is "csv" or "json". Returns how many syncs were written. Off the
+/// runtime, since a passphrase means a slow key derivation.
+#[tauri::command]
+pub async fn export_offset_series(
+    server_id: i64,
+    path: PathBuf,
+    format: String,
+    passphrase: Option<String>,
+    app_handle: tauri::AppHandle,
+) -> Result<usize, AppError> {
+    tokio::task::spawn_blocking(move || {
+        let state = app_handle.state::<AppState>();
+        offset_series::export_to(&state.db, server_id, &path, &format, passphrase.as_deref())
+    })
+    .await
+    .unwrap_or_else(|e| Err(std::io::Error::other(e).into()))
+}
+
+/// Write an export the frontend rendered, such as the sync history,
+/// encrypted when `passphrase` is set.
+#[tauri::command]
+pub async fn write_export(
+    path: PathBuf,
+    contents: String,
+    passphrase: Option<String>,
+) -> Result<(), AppError> {
+    tokio::task::spawn_blocking(move || {
+        export_crypto::write(&path, contents.as_bytes(), passphrase.as_deref())
+    })
+    .await
+    .unwrap_or_else(|e| Err(std::io::Error::other(e).into()))
+}
+
+/// What changed between two stored syncs, from `id_a` to `id_b`.
+#[tauri::command]
+pub async fn compare_sync_results(
+    id_a: i64,
+    id_b: i64,
+    state: State<'_, AppState>,
+) -> Result<SyncResultDiff, AppError> {
+    let a = state.db.get_sync_result(id_a)?;
+    let b = state.db.get_sync_result(id_b)?;
+    Ok(comparison::diff_results(&a, &b))
+}
+
+/// Attach a note to a stored sync. A blank note clears it.
+#[tauri::command]
+pub async fn annotate_sync_result(
+    id: i64,
+    note: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    let note = note.as_deref().map(str::trim).filter(|n| !n.is_empty());
+    state.db.annotate_sync_result(id, note)
+}
+
+/// Recorded sync failures for a server, newest first.
+#[tauri::command]
+pub async fn get_error_history(
+    server_id: i64,
+    limit: Option<i64>,
+    state: State<'_, AppState>,
+) -> Result<Vec<SyncErrorRecord>, AppError> {
+    state.db.get_error_history(server_id, limit)
+}
+
+/// The server's stored latency profiles by hour of day and day of week,
+/// in its timezone when it has one.
+#[tauri::command]
+pub async fn get_latency_stats(
+    server_id: i64,
+    state: State<'_, AppState>,
+) -> Result<LatencyStats, AppError> {
+    let server = state.db.get_server(server_id)?;
+    let history = state.db.get_sync_history(server_id, None, None)?;
+    Ok(latency_stats::compute(&server, &history))
+}
+
 #[tauri::command]
 pub async fn get_settings(state: State<'_, AppState>) -> Result<AppSettings, AppError> {
     state.db.get_settings()
@@ -198,7 +1345,425 @@ pub async fn get_settings(state: State<'_, AppState>) -> Result<AppSettings, App
 #[tauri::command]
 pub async fn update_settings(
     settings: AppSettings,
+    app_handle: tauri::AppHandle,
     state: State<'_, AppState>,
 ) -> Result<(), AppError> {
-    state.db.update_settings(&settings)
+    settings_validation::validate(&settings)?;
+    state.db.update_settings(&settings)?;
+    settings_saved(&app_handle, &state, &settings);
+    Ok(())
+}
+
+pub const SETTINGS_CHANGED_EVENT: &str = "settings-changed";
+
+/// Push freshly saved settings to background services and every window.
+fn settings_saved(app_handle: &tauri::AppHandle, state: &AppState, settings: &AppSettings) {
+    state.local_api.apply(app_handle, settings);
+    state.lan_time.apply(app_handle, settings);
+    state.alerts.refresh();
+    if let Err(e) = app_handle.emit(SETTINGS_CHANGED_EVENT, settings) {
+        log::warn!("failed to emit settings change: {e}");
+    }
+}
+
+/// Render a server's corrected time (or local time when `server_id` is
+/// `None`) with the display settings. `pattern` overrides the saved
+/// `time_format` for one-off renders.
+#[tauri::command]
+pub async fn format_time(
+    server_id: Option<i64>,
+    pattern: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<String, AppError> {
+    let settings = state.db.get_settings()?;
+    let format = match pattern {
+        Some(pattern) => TimeFormat::try_from_settings(&AppSettings {
+            time_format: pattern,
+            ..settings
+        })?,
+        None => TimeFormat::from_settings(&settings),
+    };
+
+    let now = Utc::now();
+    let time = match server_id {
+        Some(id) => {
+            let server = state.db.get_server(id)?;
+            ServerTime::at(&server, now, settings.extrapolate_drift)
+                .ok_or(AppError::NotSynced(id))?
+                .corrected_time
+        }
+        None => now,
+    };
+    Ok(format.format_local(time))
+}
+
+/// The server's corrected "now", extrapolated from the stored offset and
+/// drift so it is there right after launch, with how old and how uncertain
+/// that offset is.
+#[tauri::command]
+pub async fn get_corrected_time(
+    server_id: i64,
+    state: State<'_, AppState>,
+) -> Result<ServerTime, AppError> {
+    let server = state.db.get_server(server_id)?;
+    let extrapolate_drift = state.db.get_settings()?.extrapolate_drift;
+    let time = ServerTime::at(&server, Utc::now(), extrapolate_drift)
+        .ok_or(AppError::NotSynced(server_id))?;
+    Ok(time.offline(state.scheduler.status().offline))
+}
+
+/// The server's corrected "now" shown in each IANA timezone, for lining up
+/// on-sale times across regions.
+#[tauri::command]
+pub async fn get_world_clock(
+    server_id: i64,
+    timezones: Vec<String>,
+    state: State<'_, AppState>,
+) -> Result<WorldClock, AppError> {
+    let server = state.db.get_server(server_id)?;
+    let settings = state.db.get_settings()?;
+    let format = TimeFormat::from_settings(&settings);
+    let corrected = ServerTime::at(&server, Utc::now(), settings.extrapolate_drift)
+        .ok_or(AppError::NotSynced(server_id))?
+        .corrected_time;
+    Ok(WorldClock {
+        server_id,
+        corrected_time: corrected,
+        zones: world_clock::zone_times(corrected, &timezones, &format)?,
+    })
+}
+
+/// Remaining time to a target on its server's drift-extrapolated clock.
+#[tauri::command]
+pub async fn get_time_until(
+    target_id: i64,
+    state: State<'_, AppState>,
+) -> Result<TimeUntil, AppError> {
+    let target = state.db.get_target(target_id)?;
+    let server = state.db.get_server(target.server_id)?;
+    let extrapolate_drift = state.db.get_settings()?.extrapolate_drift;
+    countdown::time_until(&target, &server, Utc::now(), extrapolate_drift)
+}
+
+/// Stream a target's countdown to `on_tick` every `countdown_tick_ms`, with
+/// the remaining time already formatted. Returns the id to stop it with.
+#[tauri::command]
+pub async fn start_countdown_stream(
+    target_id: i64,
+    on_tick: Channel<CountdownTick>,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<u64, AppError> {
+    state
+        .countdown_streams
+        .start(&app_handle, target_id, move |tick| {
+            on_tick.send(tick).is_ok()
+        })
+}
+
+#[tauri::command]
+pub async fn stop_countdown_stream(
+    stream_id: u64,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    state.countdown_streams.stop(stream_id);
+    Ok(())
+}
+
+/// Write settings and per-server configuration to a versioned JSON file,
+/// encrypted when `passphrase` is set.
+#[tauri::command]
+pub async fn export_settings(
+    path: PathBuf,
+    passphrase: Option<String>,
+    app_handle: tauri::AppHandle,
+) -> Result<(), AppError> {
+    tokio::task::spawn_blocking(move || {
+        let state = app_handle.state::<AppState>();
+        settings_export::export_to(&state.db, &path, passphrase.as_deref())
+    })
+    .await
+    .unwrap_or_else(|e| Err(std::io::Error::other(e).into()))
+}
+
+/// Load a file written by `export_settings`, replacing current settings
+/// and adding or updating servers by URL. An encrypted file without its
+/// passphrase fails with `PassphraseRequired`.
+#[tauri::command]
+pub async fn import_settings(
+    path: PathBuf,
+    passphrase: Option<String>,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<ImportSummary, AppError> {
+    let handle = app_handle.clone();
+    let summary = tokio::task::spawn_blocking(move || {
+        let state = handle.state::<AppState>();
+        settings_export::import_from(&state.db, &path, passphrase.as_deref())
+    })
+    .await
+    .unwrap_or_else(|e| Err(std::io::Error::other(e).into()))?;
+    settings_saved(&app_handle, &state, &state.db.get_settings()?);
+    tray::refresh_menu(&app_handle);
+    Ok(summary)
+}
+
+/// Sign the chosen servers and targets into a compact payload another
+/// device can import, e.g. by scanning it as a QR code.
+#[tauri::command]
+pub async fn export_share_payload(
+    server_ids: Vec<i64>,
+    target_ids: Vec<i64>,
+    passphrase: String,
+    state: State<'_, AppState>,
+) -> Result<String, AppError> {
+    let payload = share::collect(&state.db, &server_ids, &target_ids)?;
+    share::encode(&payload, &passphrase)
+}
+
+/// Verify a payload from `export_share_payload` and add its servers and
+/// upcoming targets.
+#[tauri::command]
+pub async fn import_share_payload(
+    payload: String,
+    passphrase: String,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<ShareImportSummary, AppError> {
+    let payload = share::decode(&payload, &passphrase)?;
+    let summary = share::apply(&state.db, &state.extractors, &payload)?;
+    state.alerts.refresh();
+    tray::refresh_menu(&app_handle);
+    Ok(summary)
+}
+
+pub const WORKSPACE_CHANGED_EVENT: &str = "workspace-changed";
+
+fn app_data_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, AppError> {
+    app_handle.path().app_data_dir().map_err(|e| {
+        AppError::Io(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            e.to_string(),
+        ))
+    })
+}
+
+#[tauri::command]
+pub async fn list_workspaces(app_handle: tauri::AppHandle) -> Result<Vec<Workspace>, AppError> {
+    workspace::list(&app_data_dir(&app_handle)?)
+}
+
+/// Open workspace `name`, creating it empty if it does not exist yet.
+/// Refused while a sync is running, since its result would land in the
+/// newly opened database.
+#[tauri::command]
+pub async fn switch_workspace(
+    name: String,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<Workspace>, AppError> {
+    let dir = app_data_dir(&app_handle)?;
+    let path = workspace::prepare(&dir, &name)?;
+    if !state
+        .active_syncs
+        .lock()
+        .expect("active_syncs poisoned")
+        .is_empty()
+    {
+        return Err(AppError::Validation(vec![FieldError {
+            field: "name".to_string(),
+            message: "finish or cancel running syncs before switching workspaces".to_string(),
+        }]));
+    }
+    state.db.reopen(&path)?;
+    workspace::set_active(&dir, &name)?;
+    log::info!("switched to workspace {name}");
+
+    settings_saved(&app_handle, &state, &state.db.get_settings()?);
+    tray::refresh_menu(&app_handle);
+    if let Err(e) = app_handle.emit(WORKSPACE_CHANGED_EVENT, &name) {
+        log::warn!("failed to emit workspace change: {e}");
+    }
+    workspace::list(&dir)
+}
+
+/// What the user types to confirm `factory_reset`.
+pub const FACTORY_RESET_CONFIRMATION: &str = "erase everything";
+
+/// Erase every server, sync, target, setting and the local API token in
+/// all workspaces, plus recordings, the hook script and extractor plugins,
+/// leaving a freshly migrated default database: for handing the machine to
+/// someone else. `confirmation` must be `FACTORY_RESET_CONFIRMATION`.
+#[tauri::command]
+pub async fn factory_reset(
+    confirmation: String,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    if confirmation.trim() != FACTORY_RESET_CONFIRMATION {
+        return Err(AppError::Validation(vec![FieldError {
+            field: "confirmation".to_string(),
+            message: format!("type \"{FACTORY_RESET_CONFIRMATION}\" to confirm"),
+        }]));
+    }
+    if !state
+        .active_syncs
+        .lock()
+        .expect("active_syncs poisoned")
+        .is_empty()
+    {
+        return Err(AppError::Validation(vec![FieldError {
+            field: "confirmation".to_string(),
+            message: "finish or cancel running syncs before resetting".to_string(),
+        }]));
+    }
+
+    let dir = app_data_dir(&app_handle)?;
+    // Back on the default file first so no workspace is open when it goes.
+    let default_db = workspace::database_path(&dir, workspace::DEFAULT_WORKSPACE);
+    state.db.reopen(&default_db)?;
+    state.db.reset()?;
+    workspace::remove_all(&dir)?;
+    for data in [
+        sync_recording::recording_dir(&app_handle)?,
+        wasm_extractor::plugin_dir(&app_handle)?,
+    ] {
+        match std::fs::remove_dir_all(&data) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+            _ => {}
+        }
+    }
+    state.hooks.save(&app_handle, "")?;
+    state
+        .extractors
+        .load_plugins(&wasm_extractor::plugin_dir(&app_handle)?);
+    log::info!("factory reset: all data erased");
+
+    settings_saved(&app_handle, &state, &state.db.get_settings()?);
+    tray::refresh_menu(&app_handle);
+    if let Err(e) = app_handle.emit(WORKSPACE_CHANGED_EVENT, workspace::DEFAULT_WORKSPACE) {
+        log::warn!("failed to emit workspace change: {e}");
+    }
+    Ok(())
+}
+
+/// Local clock error against the configured external time source
+/// (positive: local clock is behind).
+#[tauri::command]
+pub async fn measure_clock_error(state: State<'_, AppState>) -> Result<NtpMeasurement, AppError> {
+    let source = state.db.get_settings()?.external_time_source;
+    time_source::measure(&source).await
+}
+
+/// Loss, latency and jitter to the configured external time source, as
+/// checked before each sync when `network_precheck` is on.
+#[tauri::command]
+pub async fn check_network_baseline(
+    state: State<'_, AppState>,
+) -> Result<NetworkBaseline, AppError> {
+    let source = state.db.get_settings()?.external_time_source;
+    let source = TimeSource::from_setting(&source)?.ok_or(AppError::TimeSourceDisabled)?;
+    Ok(network_baseline::measure(source).await)
+}
+
+/// Tracking state of the machine's own time daemon, if one is running.
+#[tauri::command]
+pub async fn get_local_ntp_status() -> Result<Option<LocalNtpStatus>, AppError> {
+    Ok(local_ntp::status().await)
+}
+
+/// Step or slew the OS clock onto NTP time. `confirmed_offset_ms` is the
+/// error the user approved; we re-measure and refuse if it has moved.
+#[tauri::command]
+pub async fn adjust_system_clock(
+    confirmed_offset_ms: f64,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<ClockAdjustment, AppError> {
+    let settings = state.db.get_settings()?;
+    if !settings.allow_clock_adjustment {
+        return Err(AppError::ClockAdjustmentDisabled);
+    }
+    let measured = time_source::measure(&settings.external_time_source).await?;
+    clock_adjust::check_confirmed(confirmed_offset_ms, measured.offset_ms)?;
+
+    let adjustment = clock_adjust::adjust_system_clock(measured.offset_ms)?;
+    match adjustment.method {
+        AdjustMethod::Step => {
+            state.db.shift_server_offsets(-adjustment.offset_ms)?;
+            tray::refresh_menu(&app_handle);
+        }
+        // The clock only gets there gradually, so shifting the offsets now
+        // would put them off by whatever is still to slew. They are
+        // measured again once it has.
+        AdjustMethod::Slew => resync_after(&app_handle, adjustment.settles_in()),
+    }
+    Ok(adjustment)
+}
+
+/// After `delay`, resync every server that has an offset and is not
+/// already syncing.
+fn resync_after(app: &AppHandle, delay: Duration) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(delay).await;
+        let state = app.state::<AppState>();
+        let servers = state.db.list_servers().unwrap_or_default();
+        for server in servers.iter().filter(|s| s.offset_ms.is_some()) {
+            let running = state
+                .active_syncs
+                .lock()
+                .expect("active_syncs poisoned")
+                .contains_key(&server.id);
+            if running {
+                continue;
+            }
+            if let Err(e) = spawn_sync(&app, server.id, broadcast_sink(&app)) {
+                log::warn!(
+                    "resync of server {} after slewing the clock failed to start: {e}",
+                    server.id
+                );
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_scheduler_status(state: State<'_, AppState>) -> Result<SchedulerStatus, AppError> {
+    Ok(state.scheduler.status())
+}
+
+/// Stop background resyncs until resumed; schedules and settings are kept.
+#[tauri::command]
+pub async fn pause_scheduler(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<SchedulerStatus, AppError> {
+    Ok(state.scheduler.set_paused(&app_handle, true))
+}
+
+#[tauri::command]
+pub async fn resume_scheduler(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<SchedulerStatus, AppError> {
+    Ok(state.scheduler.set_paused(&app_handle, false))
+}
+
+/// Token external clients must present to the local API; created on first use.
+#[tauri::command]
+pub async fn get_local_api_token(state: State<'_, AppState>) -> Result<String, AppError> {
+    if let Some(token) = state.db.get_local_api_token()? {
+        return Ok(token);
+    }
+    let token = local_api::generate_token();
+    state.db.set_local_api_token(&token)?;
+    Ok(token)
+}
+
+#[tauri::command]
+pub async fn regenerate_local_api_token(state: State<'_, AppState>) -> Result<String, AppError> {
+    let token = local_api::generate_token();
+    state.db.set_local_api_token(&token)?;
+    Ok(token)
 }
@@ -0,0 +1,305 @@
+use crate::countdown::STALE_AFTER;
+use crate::models::{LatencyProfile, Server, SyncPhase, SyncResult};
+use crate::network_context::NetworkContext;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// One server's side of a comparison.
+#[derive(Debug, Clone, Serialize)]
+pub struct ComparedServer {
+    pub server_id: i64,
+    pub label: String,
+    pub offset_ms: Option<f64>,
+    pub offset_age_ms: Option<i64>,
+    pub stale: bool,
+    /// Interquartile RTT spread of the latest sync; a rough measure of how
+    /// much to trust this server's offset.
+    pub jitter_ms: Option<f64>,
+}
+
+/// Pairwise offset differences between servers, e.g. several endpoints of
+/// the same ticketer that ought to agree.
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerComparison {
+    pub servers: Vec<ComparedServer>,
+    /// `matrix[i][j]` is how far server `i` runs ahead of server `j`;
+    /// `None` when either has not been synced.
+    pub matrix: Vec<Vec<Option<f64>>>,
+    pub compared_at: DateTime<Utc>,
+}
+
+/// `entries` pairs each server with its most recent sync result, in the
+/// order the caller wants rows and columns.
+pub fn compare(entries: &[(Server, Option<SyncResult>)], now: DateTime<Utc>) -> ServerComparison {
+    let servers: Vec<ComparedServer> = entries
+        .iter()
+        .map(|(server, latest)| {
+            let age = server.last_sync_at.map(|at| now - at);
+            ComparedServer {
+                server_id: server.id,
+                label: server.name.clone().unwrap_or_else(|| server.url.clone()),
+                offset_ms: server.offset_ms,
+                offset_age_ms: age.map(|a| a.num_milliseconds()),
+                stale: !matches!(age, Some(a) if a <= STALE_AFTER),
+                jitter_ms: latest.as_ref().map(|r| r.latency_profile.iqr() * 1000.0),
+            }
+        })
+        .collect();
+
+    let matrix = servers
+        .iter()
+        .map(|row| {
+            servers
+                .iter()
+                .map(|col| row.offset_ms.zip(col.offset_ms).map(|(a, b)| a - b))
+                .collect()
+        })
+        .collect();
+
+    ServerComparison {
+        servers,
+        matrix,
+        compared_at: now,
+    }
+}
+
+/// One measure in two syncs; `change` is `after - before`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Delta {
+    pub before: f64,
+    pub after: f64,
+    pub change: f64,
+}
+
+impl Delta {
+    fn new(before: f64, after: f64) -> Self {
+        Self {
+            before,
+            after,
+            change: after - before,
+        }
+    }
+}
+
+/// A value that is compared for equality only.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Change<T> {
+    pub before: T,
+    pub after: T,
+    pub changed: bool,
+}
+
+impl<T: PartialEq> Change<T> {
+    fn new(before: T, after: T) -> Self {
+        let changed = before != after;
+        Self {
+            before,
+            after,
+            changed,
+        }
+    }
+}
+
+/// Round-trip statistics of two syncs, in ms.
+#[derive(Debug, Clone, Serialize)]
+pub struct LatencyDelta {
+    pub min: Delta,
+    pub q1: Delta,
+    pub median: Delta,
+    pub mean: Delta,
+    pub q3: Delta,
+    pub max: Delta,
+    pub iqr: Delta,
+}
+
+impl LatencyDelta {
+    fn new(before: &LatencyProfile, after: &LatencyProfile) -> Self {
+        let ms = |f: fn(&LatencyProfile) -> f64| Delta::new(f(before) * 1000.0, f(after) * 1000.0);
+        Self {
+            min: ms(|p| p.min),
+            q1: ms(|p| p.q1),
+            median: ms(|p| p.median),
+            mean: ms(|p| p.mean),
+            q3: ms(|p| p.q3),
+            max: ms(|p| p.max),
+            iqr: ms(LatencyProfile::iqr),
+        }
+    }
+}
+
+/// What changed from one stored sync to another, usually two syncs of the
+/// same server a day apart.
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncResultDiff {
+    pub before_id: i64,
+    pub after_id: i64,
+    pub same_server: bool,
+    /// From the first sync to the second; negative if given newest first.
+    pub elapsed_ms: i64,
+    pub offset_ms: Delta,
+    pub latency_ms: LatencyDelta,
+    pub duration_ms: Delta,
+    /// Probes sent over the whole run.
+    pub probes: Delta,
+    pub verified: Change<bool>,
+    pub phase_reached: Change<SyncPhase>,
+    pub asymmetry: Change<Option<f64>>,
+    /// A jump in offset usually lines up with a change here.
+    pub network: Change<Option<NetworkContext>>,
+}
+
+pub fn diff_results(before: &SyncResult, after: &SyncResult) -> SyncResultDiff {
+    let probes = |r: &SyncResult| r.timeline.iter().map(|p| p.probes).sum::<u32>() as f64;
+    SyncResultDiff {
+        before_id: before.id,
+        after_id: after.id,
+        same_server: before.server_id == after.server_id,
+        elapsed_ms: (after.synced_at - before.synced_at).num_milliseconds(),
+        offset_ms: Delta::new(before.total_offset_ms, after.total_offset_ms),
+        latency_ms: LatencyDelta::new(&before.latency_profile, &after.latency_profile),
+        duration_ms: Delta::new(before.duration_ms as f64, after.duration_ms as f64),
+        probes: Delta::new(probes(before), probes(after)),
+        verified: Change::new(before.verified, after.verified),
+        phase_reached: Change::new(before.phase_reached, after.phase_reached),
+        asymmetry: Change::new(before.asymmetry, after.asymmetry),
+        network: Change::new(before.network.clone(), after.network.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{LatencyProfile, ServerStatus, SyncPhase};
+    use chrono::Duration;
+
+    fn server(id: i64, offset_ms: Option<f64>, last_sync_at: Option<DateTime<Utc>>) -> Server {
+        Server {
+            id,
+            url: format!("https://{id}.example.com"),
+            name: None,
+            offset_ms,
+            last_sync_at,
+            created_at: Utc::now(),
+            status: ServerStatus::Synced,
+            extractor_type: "date_header".to_string(),
+            probe_method: "HEAD".to_string(),
+            domain_preset: None,
+            sync_profile: None,
+            fingerprint: None,
+            asymmetry: None,
+            sync_on_launch: false,
+            health_score: None,
+            drift: None,
+            min_request_interval_ms: None,
+            trusted: false,
+            user_agent: None,
+            transport: Default::default(),
+            timezone: None,
+            timezone_guessed: false,
+        }
+    }
+
+    fn result(server_id: i64, q1: f64, q3: f64) -> SyncResult {
+        SyncResult {
+            server_id,
+            whole_second_offset: 0,
+            subsecond_offset: 0.0,
+            total_offset_ms: 0.0,
+            latency_profile: LatencyProfile {
+                min: q1,
+                q1,
+                median: (q1 + q3) / 2.0,
+                mean: (q1 + q3) / 2.0,
+                q3,
+                max: q3,
+                breakdown: None,
+            },
+            verified: true,
+            synced_at: Utc::now(),
+            duration_ms: 0,
+            phase_reached: SyncPhase::Complete,
+            fingerprint: None,
+            asymmetry: None,
+            anomalous: false,
+            clock_issue: None,
+            network: None,
+            grade: None,
+            precision_ms: None,
+            response: None,
+            id: 0,
+            timeline: Vec::new(),
+            note: None,
+        }
+    }
+
+    #[test]
+    fn matrix_is_antisymmetric_with_gaps_for_unsynced() {
+        let now = Utc::now();
+        let entries = vec![
+            (
+                server(1, Some(120.0), Some(now)),
+                Some(result(1, 0.020, 0.026)),
+            ),
+            (
+                server(2, Some(100.0), Some(now)),
+                Some(result(2, 0.030, 0.031)),
+            ),
+            (server(3, None, None), None),
+        ];
+        let cmp = compare(&entries, now);
+
+        assert_eq!(cmp.matrix[0][1], Some(20.0));
+        assert_eq!(cmp.matrix[1][0], Some(-20.0));
+        assert_eq!(cmp.matrix[0][0], Some(0.0));
+        assert_eq!(cmp.matrix[2][0], None);
+        assert!((cmp.servers[0].jitter_ms.unwrap() - 6.0).abs() < 1e-9);
+        assert_eq!(cmp.servers[2].jitter_ms, None);
+    }
+
+    #[test]
+    fn flags_old_and_missing_offsets_as_stale() {
+        let now = Utc::now();
+        let entries = vec![
+            (server(1, Some(0.0), Some(now - Duration::minutes(5))), None),
+            (server(2, Some(0.0), Some(now - Duration::hours(3))), None),
+            (server(3, None, None), None),
+        ];
+        let stale: Vec<bool> = compare(&entries, now)
+            .servers
+            .iter()
+            .map(|s| s.stale)
+            .collect();
+        assert_eq!(stale, vec![false, true, true]);
+    }
+
+    #[test]
+    fn diff_reports_changes_from_the_first_sync_to_the_second() {
+        let before = SyncResult {
+            id: 4,
+            total_offset_ms: 120.0,
+            duration_ms: 9_000,
+            synced_at: Utc::now() - Duration::days(1),
+            ..result(1, 0.020, 0.026)
+        };
+        let after = SyncResult {
+            id: 9,
+            total_offset_ms: 95.5,
+            duration_ms: 7_500,
+            verified: false,
+            phase_reached: SyncPhase::Verification,
+            ..result(1, 0.030, 0.050)
+        };
+        let diff = diff_results(&before, &after);
+
+        assert!(diff.same_server);
+        assert_eq!((diff.before_id, diff.after_id), (4, 9));
+        assert!((diff.elapsed_ms - 86_400_000).abs() < 1_000);
+        assert_eq!(diff.offset_ms.change, -24.5);
+        assert_eq!(diff.duration_ms.change, -1_500.0);
+        assert!((diff.latency_ms.iqr.change - 14.0).abs() < 1e-9);
+        assert!((diff.latency_ms.min.before - 20.0).abs() < 1e-9);
+        assert!(diff.verified.changed);
+        assert!(diff.phase_reached.changed);
+        assert!(!diff.asymmetry.changed);
+    }
+}
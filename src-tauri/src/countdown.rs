@@ -0,0 +1,155 @@
+use crate::drift;
+use crate::error::AppError;
+use crate::models::{Server, Target};
+use crate::timing;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+/// An offset older than this is flagged so the UI can suggest a resync.
+pub(crate) const STALE_AFTER: Duration = Duration::hours(1);
+
+/// Remaining time to a target on its server's corrected clock.
+#[derive(Debug, Clone, Serialize)]
+pub struct TimeUntil {
+    pub target_id: i64,
+    pub server_id: i64,
+    pub target_at: DateTime<Utc>,
+    pub corrected_now: DateTime<Utc>,
+    /// Negative once the target has passed.
+    pub remaining_ms: f64,
+    /// Offset actually applied, after drift extrapolation.
+    pub offset_ms: f64,
+    pub drift_ms_per_hour: Option<f64>,
+    pub offset_age_ms: Option<i64>,
+    pub stale: bool,
+}
+
+/// `extrapolate_drift` carries the offset forward along the server's stored
+/// drift; without it the offset is used as measured.
+pub fn time_until(
+    target: &Target,
+    server: &Server,
+    now: DateTime<Utc>,
+    extrapolate_drift: bool,
+) -> Result<TimeUntil, AppError> {
+    let offset_ms =
+        drift::offset_at(server, now, extrapolate_drift).ok_or(AppError::NotSynced(server.id))?;
+    let drift = drift::applied(server, extrapolate_drift);
+    let corrected_now = timing::corrected_time(now, offset_ms);
+
+    let age = server.last_sync_at.map(|at| now - at);
+    let remaining = target.target_at - corrected_now;
+    Ok(TimeUntil {
+        target_id: target.id,
+        server_id: server.id,
+        target_at: target.target_at,
+        corrected_now,
+        remaining_ms: remaining.num_microseconds().unwrap_or(i64::MAX) as f64 / 1000.0,
+        offset_ms,
+        drift_ms_per_hour: drift.map(|d| d.rate_ms_per_hour),
+        offset_age_ms: age.map(|a| a.num_milliseconds()),
+        stale: !matches!(age, Some(a) if a <= STALE_AFTER),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ServerStatus;
+
+    fn server(offset_ms: Option<f64>, last_sync_at: Option<DateTime<Utc>>) -> Server {
+        Server {
+            id: 3,
+            url: "https://example.com".to_string(),
+            name: None,
+            offset_ms,
+            last_sync_at,
+            created_at: Utc::now(),
+            status: ServerStatus::Synced,
+            extractor_type: "date_header".to_string(),
+            probe_method: "HEAD".to_string(),
+            domain_preset: None,
+            sync_profile: None,
+            fingerprint: None,
+            asymmetry: None,
+            sync_on_launch: false,
+            health_score: None,
+            drift: None,
+            min_request_interval_ms: None,
+            trusted: false,
+            user_agent: None,
+            transport: Default::default(),
+            timezone: None,
+            timezone_guessed: false,
+        }
+    }
+
+    fn target(target_at: DateTime<Utc>) -> Target {
+        Target {
+            id: 9,
+            server_id: 3,
+            label: None,
+            target_at,
+            armed: true,
+            alert_method: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn remaining_uses_corrected_clock() {
+        let now = Utc::now();
+        let server = server(Some(1500.0), Some(now - Duration::minutes(5)));
+        let result = time_until(&target(now + Duration::seconds(10)), &server, now, true).unwrap();
+        assert!((result.remaining_ms - 8500.0).abs() < 0.01);
+        assert!(!result.stale);
+        assert_eq!(result.drift_ms_per_hour, None);
+    }
+
+    #[test]
+    fn stored_drift_applies_unless_turned_off() {
+        let now = Utc::now();
+        let server = Server {
+            drift: Some(drift::DriftEstimate {
+                rate_ms_per_hour: 120.0,
+                samples: 6,
+                residual_ms: 0.0,
+                rate_error_ms_per_hour: 0.0,
+            }),
+            ..server(Some(1500.0), Some(now - Duration::minutes(30)))
+        };
+        let target = target(now + Duration::seconds(10));
+        let drifted = time_until(&target, &server, now, true).unwrap();
+        assert!((drifted.offset_ms - 1560.0).abs() < 0.01);
+        assert!((drifted.remaining_ms - 8440.0).abs() < 0.01);
+        assert_eq!(drifted.drift_ms_per_hour, Some(120.0));
+
+        let measured = time_until(&target, &server, now, false).unwrap();
+        assert_eq!(measured.offset_ms, 1500.0);
+        assert_eq!(measured.drift_ms_per_hour, None);
+    }
+
+    #[test]
+    fn old_or_missing_sync_time_is_stale() {
+        let now = Utc::now();
+        let old = server(Some(0.0), Some(now - Duration::hours(2)));
+        assert!(time_until(&target(now), &old, now, true).unwrap().stale);
+        let unknown = server(Some(0.0), None);
+        assert!(time_until(&target(now), &unknown, now, true).unwrap().stale);
+    }
+
+    #[test]
+    fn passed_target_is_negative() {
+        let now = Utc::now();
+        let server = server(Some(0.0), Some(now));
+        let result = time_until(&target(now - Duration::seconds(2)), &server, now, true).unwrap();
+        assert!((result.remaining_ms + 2000.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn unsynced_server_is_an_error() {
+        let now = Utc::now();
+        let err = time_until(&target(now), &server(None, None), now, true).unwrap_err();
+        assert!(matches!(err, AppError::NotSynced(3)));
+    }
+}
@@ -0,0 +1,200 @@
+use crate::countdown::{self, TimeUntil};
+use crate::error::AppError;
+use crate::models::{Server, Target};
+use crate::state::AppState;
+use crate::time_format::TimeFormat;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tokio_util::sync::CancellationToken;
+
+/// How often the target, server and settings are re-read, so a resync or a
+/// pre-target recheck reaches a running stream within a second.
+const RELOAD_EVERY: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// One frame of the overlay's countdown, ready to paint as is.
+#[derive(Debug, Clone, Serialize)]
+pub struct CountdownTick {
+    pub target_id: i64,
+    /// Negative once the target has passed.
+    pub remaining_ms: i64,
+    /// `"1:02:03.4"` before the target, `"+0:00:02.1"` after it.
+    pub remaining_text: String,
+    /// The server's corrected time in the user's time format.
+    pub corrected_time: String,
+    pub offset_ms: f64,
+    pub stale: bool,
+}
+
+/// Countdown streams open in some window, by stream id.
+pub struct CountdownStreams {
+    streams: Mutex<HashMap<u64, CancellationToken>>,
+    next_id: AtomicU64,
+}
+
+impl CountdownStreams {
+    pub fn new() -> Self {
+        Self {
+            streams: Mutex::new(HashMap::new()),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    /// Stream ticks for `target_id` to `send` every `countdown_tick_ms`
+    /// until stopped, the target or its server is removed, or `send`
+    /// returns false (the window is gone). Fails up front if the server has
+    /// no offset yet.
+    pub fn start(
+        &self,
+        app: &AppHandle,
+        target_id: i64,
+        send: impl Fn(CountdownTick) -> bool + Send + 'static,
+    ) -> Result<u64, AppError> {
+        let state = app.state::<AppState>();
+        let inputs = Inputs::load(&state, target_id)?;
+        inputs.time_until()?;
+
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let token = CancellationToken::new();
+        self.streams.lock().unwrap().insert(id, token.clone());
+        tauri::async_runtime::spawn(run(app.clone(), id, inputs, token, send));
+        Ok(id)
+    }
+
+    pub fn stop(&self, id: u64) {
+        if let Some(token) = self.streams.lock().unwrap().remove(&id) {
+            token.cancel();
+        }
+    }
+}
+
+/// Everything a tick is computed from, re-read every `RELOAD_EVERY`.
+struct Inputs {
+    target: Target,
+    server: Server,
+    extrapolate_drift: bool,
+    format: TimeFormat,
+    tick: std::time::Duration,
+}
+
+impl Inputs {
+    fn load(state: &AppState, target_id: i64) -> Result<Self, AppError> {
+        let target = state.db.get_target(target_id)?;
+        let server = state.db.get_server(target.server_id)?;
+        let settings = state.db.get_settings()?;
+        Ok(Self {
+            target,
+            server,
+            extrapolate_drift: settings.extrapolate_drift,
+            format: TimeFormat::from_settings(&settings),
+            tick: std::time::Duration::from_millis(settings.countdown_tick_ms.into()),
+        })
+    }
+
+    fn time_until(&self) -> Result<TimeUntil, AppError> {
+        countdown::time_until(
+            &self.target,
+            &self.server,
+            chrono::Utc::now(),
+            self.extrapolate_drift,
+        )
+    }
+}
+
+async fn run(
+    app: AppHandle,
+    id: u64,
+    mut inputs: Inputs,
+    token: CancellationToken,
+    send: impl Fn(CountdownTick) -> bool + Send,
+) {
+    let mut interval = tokio::time::interval(inputs.tick);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    let mut loaded_at = tokio::time::Instant::now();
+
+    loop {
+        tokio::select! {
+            _ = token.cancelled() => break,
+            _ = interval.tick() => {}
+        }
+        if loaded_at.elapsed() >= RELOAD_EVERY {
+            let state = app.state::<AppState>();
+            match Inputs::load(&state, inputs.target.id) {
+                Ok(fresh) => {
+                    if fresh.tick != inputs.tick {
+                        interval = tokio::time::interval(fresh.tick);
+                        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+                    }
+                    inputs = fresh;
+                }
+                Err(e) => {
+                    log::info!("countdown stream {id} ended: {e}");
+                    break;
+                }
+            }
+            loaded_at = tokio::time::Instant::now();
+        }
+        let Ok(until) = inputs.time_until() else {
+            break;
+        };
+        if !send(tick(&until, &inputs.format)) {
+            break;
+        }
+    }
+    app.state::<AppState>().countdown_streams.stop(id);
+}
+
+fn tick(until: &TimeUntil, format: &TimeFormat) -> CountdownTick {
+    let remaining_ms = until.remaining_ms.floor() as i64;
+    CountdownTick {
+        target_id: until.target_id,
+        remaining_ms,
+        remaining_text: remaining_text(remaining_ms, format.fraction_digits()),
+        corrected_time: format.format_local(until.corrected_now),
+        offset_ms: until.offset_ms,
+        stale: until.stale,
+    }
+}
+
+/// `H:MM:SS` plus `digits` fractional digits. Before the target the
+/// display rounds up, so it reads zero exactly at T-0; after it, it counts
+/// up from zero with a leading `+`.
+fn remaining_text(remaining_ms: i64, digits: u8) -> String {
+    let unit = 10_i64.pow(3 - u32::from(digits.min(3)));
+    let (sign, units) = if remaining_ms >= 0 {
+        ("", (remaining_ms + unit - 1) / unit)
+    } else {
+        ("+", -remaining_ms / unit)
+    };
+    let per_second = 1000 / unit;
+    let (secs, frac) = (units / per_second, units % per_second);
+    let text = format!(
+        "{sign}{}:{:02}:{:02}",
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    );
+    if digits == 0 {
+        text
+    } else {
+        format!("{text}.{frac:0width$}", width = usize::from(digits.min(3)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remaining_text_reads_zero_exactly_at_the_target() {
+        assert_eq!(remaining_text(3_723_400, 1), "1:02:03.4");
+        assert_eq!(remaining_text(3_723_401, 1), "1:02:03.5");
+        assert_eq!(remaining_text(59_001, 0), "0:01:00");
+        assert_eq!(remaining_text(1, 3), "0:00:00.001");
+        assert_eq!(remaining_text(0, 1), "0:00:00.0");
+        assert_eq!(remaining_text(-2_150, 1), "+0:00:02.1");
+        assert_eq!(remaining_text(-999, 0), "+0:00:00");
+    }
+}
@@ -0,0 +1,199 @@
+use crate::countdown::STALE_AFTER;
+use crate::drift;
+use crate::models::{Server, Target};
+use crate::network_watch;
+use crate::scheduler;
+use crate::timing;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// How many upcoming targets the dashboard lists.
+const MAX_UPCOMING: usize = 10;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DashboardServer {
+    pub server: Server,
+    pub offset_age_ms: Option<i64>,
+    /// Old, never synced, or measured before the last network change.
+    pub stale: bool,
+    /// `None` when auto-resync is off or the server was never synced.
+    pub next_sync_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UpcomingTarget {
+    pub target: Target,
+    /// On the server's corrected clock, without drift extrapolation; `None`
+    /// until the server has been synced.
+    pub remaining_ms: Option<f64>,
+}
+
+/// Everything the dashboard needs on start, built from one read of the
+/// servers, targets and settings instead of per-server round trips.
+#[derive(Debug, Clone, Serialize)]
+pub struct Dashboard {
+    pub servers: Vec<DashboardServer>,
+    /// Armed targets still ahead, soonest first.
+    pub upcoming_targets: Vec<UpcomingTarget>,
+    pub scheduler_paused: bool,
+    pub generated_at: DateTime<Utc>,
+}
+
+pub fn build(
+    servers: Vec<Server>,
+    targets: Vec<Target>,
+    auto_resync_interval_minutes: u32,
+    scheduler_paused: bool,
+    network_changed_at: Option<DateTime<Utc>>,
+    extrapolate_drift: bool,
+    now: DateTime<Utc>,
+) -> Dashboard {
+    let mut upcoming: Vec<UpcomingTarget> = targets
+        .into_iter()
+        .filter(|t| t.armed && t.target_at > now)
+        .map(|target| {
+            let offset = servers
+                .iter()
+                .find(|s| s.id == target.server_id)
+                .and_then(|s| drift::offset_at(s, now, extrapolate_drift));
+            let remaining_ms = offset.map(|offset| {
+                let remaining = target.target_at - timing::corrected_time(now, offset);
+                remaining.num_microseconds().unwrap_or(i64::MAX) as f64 / 1000.0
+            });
+            UpcomingTarget {
+                target,
+                remaining_ms,
+            }
+        })
+        .collect();
+    upcoming.sort_by_key(|u| u.target.target_at);
+    upcoming.truncate(MAX_UPCOMING);
+
+    let servers = servers
+        .into_iter()
+        .map(|server| {
+            let age = server.last_sync_at.map(|at| now - at);
+            DashboardServer {
+                offset_age_ms: age.map(|a| a.num_milliseconds()),
+                stale: !matches!(age, Some(a) if a <= STALE_AFTER)
+                    || network_watch::is_suspect(&server, network_changed_at),
+                next_sync_at: if scheduler_paused {
+                    None
+                } else {
+                    scheduler::next_sync_at(&server, auto_resync_interval_minutes)
+                },
+                server,
+            }
+        })
+        .collect();
+
+    Dashboard {
+        servers,
+        upcoming_targets: upcoming,
+        scheduler_paused,
+        generated_at: now,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ServerStatus;
+    use chrono::Duration;
+
+    fn server(id: i64, offset_ms: Option<f64>, last_sync_at: Option<DateTime<Utc>>) -> Server {
+        Server {
+            id,
+            url: format!("https://{id}.example.com"),
+            name: None,
+            offset_ms,
+            last_sync_at,
+            created_at: Utc::now(),
+            status: ServerStatus::Synced,
+            extractor_type: "date_header".to_string(),
+            probe_method: "HEAD".to_string(),
+            domain_preset: None,
+            sync_profile: None,
+            fingerprint: None,
+            asymmetry: None,
+            sync_on_launch: false,
+            health_score: None,
+            drift: None,
+            min_request_interval_ms: None,
+            trusted: false,
+            user_agent: None,
+            transport: Default::default(),
+            timezone: None,
+            timezone_guessed: false,
+        }
+    }
+
+    fn target(id: i64, server_id: i64, target_at: DateTime<Utc>, armed: bool) -> Target {
+        Target {
+            id,
+            server_id,
+            label: None,
+            target_at,
+            armed,
+            alert_method: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn lists_armed_future_targets_soonest_first() {
+        let now = Utc::now();
+        let servers = vec![server(1, Some(1000.0), Some(now)), server(2, None, None)];
+        let targets = vec![
+            target(1, 1, now + Duration::minutes(30), true),
+            target(2, 2, now + Duration::minutes(10), true),
+            target(3, 1, now + Duration::minutes(5), false),
+            target(4, 1, now - Duration::minutes(5), true),
+        ];
+        let dashboard = build(servers, targets, 0, false, None, true, now);
+
+        let ids: Vec<i64> = dashboard
+            .upcoming_targets
+            .iter()
+            .map(|u| u.target.id)
+            .collect();
+        assert_eq!(ids, vec![2, 1]);
+        assert_eq!(dashboard.upcoming_targets[0].remaining_ms, None);
+        // Server clock runs 1 s ahead, so there is 1 s less to go.
+        assert_eq!(
+            dashboard.upcoming_targets[1].remaining_ms,
+            Some(1_799_000.0)
+        );
+    }
+
+    #[test]
+    fn next_sync_follows_the_resync_interval_unless_paused() {
+        let now = Utc::now();
+        let synced_at = now - Duration::minutes(20);
+        let servers = vec![server(1, Some(0.0), Some(synced_at)), server(2, None, None)];
+
+        let dashboard = build(servers.clone(), vec![], 30, false, None, true, now);
+        assert_eq!(
+            dashboard.servers[0].next_sync_at,
+            Some(synced_at + Duration::minutes(30))
+        );
+        assert_eq!(dashboard.servers[1].next_sync_at, None);
+        assert!(!dashboard.servers[0].stale);
+        assert!(dashboard.servers[1].stale);
+
+        let paused = build(servers.clone(), vec![], 30, true, None, true, now);
+        assert_eq!(paused.servers[0].next_sync_at, None);
+
+        // A network change after the last sync makes the offset suspect.
+        let moved = build(
+            servers,
+            vec![],
+            30,
+            false,
+            Some(now - Duration::minutes(5)),
+            true,
+            now,
+        );
+        assert!(moved.servers[0].stale);
+    }
+}
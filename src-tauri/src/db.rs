@@ -1,11 +1,26 @@
-use crate::error::AppError;
-use crate::models::{AppSettings, LatencyProfile, Server, ServerStatus, SyncPhase, SyncResult};
+use crate::clock_issue;
+use crate::domain_presets;
+use crate::drift::DriftEstimate;
+use crate::error::{AppError, ErrorCode};
+use crate::favicon::Favicon;
+use crate::fingerprint::ServerFingerprint;
+use crate::models::{
+    AppSettings, BenchmarkReport, FailureContext, LatencyProfile, Server, ServerStatus,
+    ScheduledSync, SyncErrorRecord, SyncHistoryFilter, SyncHistoryPage, SyncPhase, SyncResult, Target,
+    TransportOptions, TrashedServer,
+};
+use crate::server_timezone;
+use crate::sync_grade::SyncGrade;
+use crate::workspace;
 use chrono::{DateTime, Utc};
-use rusqlite::{params, Connection};
+use rusqlite::{params, Connection, OptionalExtension};
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Mutex;
 use tauri::{AppHandle, Manager};
 
+pub const DB_FILE_NAME: &str = "ticketime.db";
+
 pub struct Database {
     conn: Mutex<Connection>,
 }
@@ -18,8 +33,14 @@ impl Database {
             .expect("failed to resolve app data dir");
         std::fs::create_dir_all(&app_dir).expect("failed to create app data dir");
 
-        let db_path = app_dir.join("ticketime.db");
-        let conn = Connection::open(db_path)?;
+        let workspace = workspace::active(&app_dir);
+        Self::open(&workspace::prepare(&app_dir, &workspace)?)
+    }
+
+    /// Open (creating if needed) the database at `path`. Used directly by the
+    /// headless CLI, which has no `AppHandle` to resolve the data dir.
+    pub fn open(path: &Path) -> Result<Self, AppError> {
+        let conn = Connection::open(path)?;
 
         conn.execute_batch("PRAGMA journal_mode=WAL;")?;
 
@@ -30,6 +51,42 @@ impl Database {
         Ok(db)
     }
 
+    /// Swap in the database at `path`. Callers hold `&self` through
+    /// `AppState`, so the connection is replaced rather than the `Database`.
+    pub fn reopen(&self, path: &Path) -> Result<(), AppError> {
+        let next = Self::open(path)?;
+        *self.conn.lock().unwrap() = next.conn.into_inner().unwrap();
+        Ok(())
+    }
+
+    /// Drop every table and migrate again, leaving what a fresh install
+    /// creates. The file is vacuumed so dropped rows cannot be recovered
+    /// from free pages.
+    pub fn reset(&self) -> Result<(), AppError> {
+        {
+            let conn = self.conn.lock().unwrap();
+            let tables: Vec<String> = conn
+                .prepare(
+                    "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'",
+                )?
+                .query_map([], |row| row.get(0))?
+                .collect::<Result<_, _>>()?;
+            let foreign_keys: bool = conn.query_row("PRAGMA foreign_keys", [], |row| row.get(0))?;
+            // Tables go in any order, so references must not be enforced.
+            conn.execute_batch("PRAGMA foreign_keys=OFF;")?;
+            let tx = conn.unchecked_transaction()?;
+            for table in &tables {
+                tx.execute_batch(&format!("DROP TABLE \"{table}\";"))?;
+            }
+            tx.commit()?;
+            if foreign_keys {
+                conn.execute_batch("PRAGMA foreign_keys=ON;")?;
+            }
+            conn.execute_batch("VACUUM;")?;
+        }
+        self.run_migrations()
+    }
+
     fn run_migrations(&self) -> Result<(), AppError> {
         let conn = self.conn.lock().unwrap();
         conn.execute_batch(
@@ -61,14 +118,86 @@ impl Database {
             CREATE TABLE IF NOT EXISTS settings (
                 key TEXT PRIMARY KEY,
                 value TEXT NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS benchmark_reports (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                server_id INTEGER NOT NULL,
+                created_at TEXT NOT NULL,
+                report_json TEXT NOT NULL,
+                FOREIGN KEY (server_id) REFERENCES servers(id) ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS targets (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                server_id INTEGER NOT NULL,
+                label TEXT,
+                target_at TEXT NOT NULL,
+                armed INTEGER NOT NULL DEFAULT 1,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (server_id) REFERENCES servers(id) ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS errors (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                server_id INTEGER NOT NULL,
+                phase INTEGER NOT NULL,
+                code TEXT NOT NULL,
+                message TEXT NOT NULL,
+                total_attempts INTEGER NOT NULL,
+                occurred_at TEXT NOT NULL,
+                FOREIGN KEY (server_id) REFERENCES servers(id) ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS scheduled_syncs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                server_id INTEGER NOT NULL,
+                run_at TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (server_id) REFERENCES servers(id) ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS favicons (
+                server_id INTEGER PRIMARY KEY,
+                content_type TEXT NOT NULL,
+                data BLOB NOT NULL,
+                fetched_at TEXT NOT NULL,
+                FOREIGN KEY (server_id) REFERENCES servers(id) ON DELETE CASCADE
             );",
         )?;
+
+        // Columns added after the first release.
+        add_column_if_missing(&conn, "servers", "sync_profile", "TEXT")?;
+        add_column_if_missing(&conn, "servers", "fingerprint_json", "TEXT")?;
+        add_column_if_missing(&conn, "servers", "probe_method", "TEXT NOT NULL DEFAULT 'HEAD'")?;
+        add_column_if_missing(&conn, "servers", "domain_preset", "TEXT")?;
+        add_column_if_missing(&conn, "servers", "asymmetry", "REAL")?;
+        add_column_if_missing(&conn, "servers", "sync_on_launch", "INTEGER NOT NULL DEFAULT 0")?;
+        add_column_if_missing(&conn, "servers", "health_score", "INTEGER")?;
+        add_column_if_missing(&conn, "servers", "min_request_interval_ms", "INTEGER")?;
+        add_column_if_missing(&conn, "servers", "user_agent", "TEXT")?;
+        add_column_if_missing(&conn, "servers", "transport_json", "TEXT")?;
+        add_column_if_missing(&conn, "servers", "trusted", "INTEGER NOT NULL DEFAULT 0")?;
+        add_column_if_missing(&conn, "servers", "timezone", "TEXT")?;
+        add_column_if_missing(&conn, "servers", "deleted_at", "TEXT")?;
+        add_column_if_missing(&conn, "servers", "trashed_designations", "TEXT")?;
+        add_column_if_missing(&conn, "servers", "drift_json", "TEXT")?;
+        add_column_if_missing(&conn, "sync_results", "response_json", "TEXT")?;
+        add_column_if_missing(&conn, "sync_results", "timeline_json", "TEXT")?;
+        add_column_if_missing(&conn, "sync_results", "note", "TEXT")?;
+        add_column_if_missing(&conn, "sync_results", "asymmetry", "REAL")?;
+        add_column_if_missing(&conn, "sync_results", "anomalous", "INTEGER NOT NULL DEFAULT 0")?;
+        add_column_if_missing(&conn, "sync_results", "network_json", "TEXT")?;
+        add_column_if_missing(&conn, "sync_results", "grade", "TEXT")?;
+        add_column_if_missing(&conn, "sync_results", "precision_ms", "REAL")?;
+        add_column_if_missing(&conn, "targets", "alert_method", "TEXT")?;
         Ok(())
     }
 
     pub fn add_server(&self, url: &str) -> Result<Server, AppError> {
         let conn = self.conn.lock().unwrap();
         let now = Utc::now();
+        purge_trashed_url(&conn, url)?;
         conn.execute(
             "INSERT INTO servers (url, created_at, status, extractor_type) VALUES (?1, ?2, ?3, ?4)",
             params![url, now.to_rfc3339(), "idle", "date_header"],
@@ -83,36 +212,145 @@ impl Database {
             created_at: now,
             status: ServerStatus::Idle,
             extractor_type: "date_header".to_string(),
+            probe_method: domain_presets::HEAD.to_string(),
+            domain_preset: None,
+            sync_profile: None,
+            fingerprint: None,
+            asymmetry: None,
+            sync_on_launch: false,
+            health_score: None,
+            drift: None,
+            min_request_interval_ms: None,
+            trusted: false,
+            user_agent: None,
+            transport: Default::default(),
+            timezone: None,
+            timezone_guessed: false,
         })
     }
 
+    /// Insert several servers in one transaction: either all are added or
+    /// none are.
+    pub fn add_servers(&self, servers: &[NewServer]) -> Result<Vec<Server>, AppError> {
+        let ids = {
+            let conn = self.conn.lock().unwrap();
+            let tx = conn.unchecked_transaction()?;
+            let now = Utc::now().to_rfc3339();
+            let mut ids = Vec::with_capacity(servers.len());
+            for server in servers {
+                purge_trashed_url(&tx, &server.url)?;
+                tx.execute(
+                    "INSERT INTO servers (url, created_at, status, extractor_type, probe_method, domain_preset) VALUES (?1, ?2, 'idle', ?3, ?4, ?5)",
+                    params![
+                        server.url,
+                        now,
+                        server.extractor_type,
+                        server.probe_method,
+                        server.domain_preset
+                    ],
+                )?;
+                ids.push(tx.last_insert_rowid());
+            }
+            tx.commit()?;
+            ids
+        };
+        ids.into_iter().map(|id| self.get_server(id)).collect()
+    }
+
+    /// Upsert servers from a share link and add their targets (index into
+    /// `servers`, instant, label) in one transaction. A server the link
+    /// leaves unnamed keeps its name. Returns each server's id and whether
+    /// it was added.
+    pub fn import_shared(
+        &self,
+        servers: &[ImportedServer],
+        targets: &[(usize, DateTime<Utc>, Option<&str>)],
+    ) -> Result<Vec<(i64, bool)>, AppError> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        let now = Utc::now().to_rfc3339();
+        let mut ids = Vec::with_capacity(servers.len());
+        for server in servers {
+            // A URL listed twice is added once.
+            let existing = match server.id {
+                Some(id) => Some(id),
+                None => tx
+                    .query_row(
+                        "SELECT id FROM servers WHERE url = ?1 AND deleted_at IS NULL",
+                        params![server.url],
+                        |row| row.get(0),
+                    )
+                    .optional()?,
+            };
+            let id = match existing {
+                Some(id) => id,
+                None => {
+                    purge_trashed_url(&tx, &server.url)?;
+                    tx.execute(
+                        "INSERT INTO servers (url, created_at, status, extractor_type) VALUES (?1, ?2, 'idle', 'date_header')",
+                        params![server.url, now],
+                    )?;
+                    tx.last_insert_rowid()
+                }
+            };
+            tx.execute(
+                "UPDATE servers SET name = COALESCE(?1, name), extractor_type = ?2, sync_profile = ?3 WHERE id = ?4",
+                params![server.name, server.extractor_type, server.sync_profile, id],
+            )?;
+            ids.push((id, existing.is_none()));
+        }
+        for (server, target_at, label) in targets {
+            tx.execute(
+                "INSERT INTO targets (server_id, label, target_at, armed, created_at) VALUES (?1, ?2, ?3, 1, ?4)",
+                params![ids[*server].0, label, target_at.to_rfc3339(), now],
+            )?;
+        }
+        tx.commit()?;
+        Ok(ids)
+    }
+
+    /// Insert a public reference source, named and on `sync_profile`, in
+    /// one transaction. It becomes the reference server unless one is
+    /// already chosen.
+    pub fn add_reference_source(
+        &self,
+        server: &NewServer,
+        name: &str,
+        sync_profile: &str,
+    ) -> Result<Server, AppError> {
+        let id = {
+            let conn = self.conn.lock().unwrap();
+            let tx = conn.unchecked_transaction()?;
+            purge_trashed_url(&tx, &server.url)?;
+            tx.execute(
+                "INSERT INTO servers (url, name, created_at, status, extractor_type, probe_method, sync_profile) VALUES (?1, ?2, ?3, 'idle', ?4, ?5, ?6)",
+                params![
+                    server.url,
+                    name,
+                    Utc::now().to_rfc3339(),
+                    server.extractor_type,
+                    server.probe_method,
+                    sync_profile
+                ],
+            )?;
+            let id = tx.last_insert_rowid();
+            tx.execute(
+                "INSERT OR IGNORE INTO settings (key, value) VALUES ('reference_server_id', ?1)",
+                params![id.to_string()],
+            )?;
+            tx.commit()?;
+            id
+        };
+        self.get_server(id)
+    }
+
     pub fn list_servers(&self) -> Result<Vec<Server>, AppError> {
         let conn = self.conn.lock().unwrap();
-        let mut stmt = conn.prepare(
-            "SELECT id, url, name, offset_ms, last_sync_at, created_at, status, extractor_type FROM servers ORDER BY id",
-        )?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {SERVER_COLUMNS} FROM servers WHERE deleted_at IS NULL ORDER BY id"
+        ))?;
         let servers = stmt
-            .query_map([], |row| {
-                let status_str: String = row.get(6)?;
-                let last_sync_str: Option<String> = row.get(4)?;
-                let created_str: String = row.get(5)?;
-                Ok(Server {
-                    id: row.get(0)?,
-                    url: row.get(1)?,
-                    name: row.get(2)?,
-                    offset_ms: row.get(3)?,
-                    last_sync_at: last_sync_str.and_then(|s| {
-                        DateTime::parse_from_rfc3339(&s)
-                            .ok()
-                            .map(|dt| dt.with_timezone(&Utc))
-                    }),
-                    created_at: DateTime::parse_from_rfc3339(&created_str)
-                        .map(|dt| dt.with_timezone(&Utc))
-                        .unwrap_or_else(|_| Utc::now()),
-                    status: status_str.parse().unwrap_or(ServerStatus::Idle),
-                    extractor_type: row.get(7)?,
-                })
-            })?
+            .query_map([], server_from_row)?
             .collect::<Result<Vec<_>, _>>()?;
         Ok(servers)
     }
@@ -120,42 +358,135 @@ impl Database {
     pub fn get_server(&self, id: i64) -> Result<Server, AppError> {
         let conn = self.conn.lock().unwrap();
         let server = conn.query_row(
-            "SELECT id, url, name, offset_ms, last_sync_at, created_at, status, extractor_type FROM servers WHERE id = ?1",
+            &format!("SELECT {SERVER_COLUMNS} FROM servers WHERE id = ?1 AND deleted_at IS NULL"),
             params![id],
-            |row| {
-                let status_str: String = row.get(6)?;
-                let last_sync_str: Option<String> = row.get(4)?;
-                let created_str: String = row.get(5)?;
-                Ok(Server {
-                    id: row.get(0)?,
-                    url: row.get(1)?,
-                    name: row.get(2)?,
-                    offset_ms: row.get(3)?,
-                    last_sync_at: last_sync_str.and_then(|s| {
-                        DateTime::parse_from_rfc3339(&s)
-                            .ok()
-                            .map(|dt| dt.with_timezone(&Utc))
-                    }),
-                    created_at: DateTime::parse_from_rfc3339(&created_str)
-                        .map(|dt| dt.with_timezone(&Utc))
-                        .unwrap_or_else(|_| Utc::now()),
-                    status: status_str
-                        .parse()
-                        .unwrap_or(ServerStatus::Idle),
-                    extractor_type: row.get(7)?,
-                })
-            },
+            server_from_row,
         )?;
         Ok(server)
     }
 
+    pub fn find_server_by_url(&self, url: &str) -> Result<Option<Server>, AppError> {
+        let id: Option<i64> = {
+            let conn = self.conn.lock().unwrap();
+            conn.query_row(
+                "SELECT id FROM servers WHERE url = ?1 AND deleted_at IS NULL",
+                params![url],
+                |row| row.get(0),
+            )
+            .optional()?
+        };
+        id.map(|id| self.get_server(id)).transpose()
+    }
+
+    /// Remove a server and its history for good, trashed or not.
     pub fn delete_server(&self, id: i64) -> Result<(), AppError> {
         let conn = self.conn.lock().unwrap();
-        conn.execute("DELETE FROM sync_results WHERE server_id = ?1", params![id])?;
-        conn.execute("DELETE FROM servers WHERE id = ?1", params![id])?;
+        purge_server(&conn, id)
+    }
+
+    /// Hide a server, with its history, targets and schedules, until it is
+    /// restored or the trash is emptied. Whether it was the primary or
+    /// reference server is kept for `restore_server`.
+    pub fn trash_server(&self, id: i64, now: DateTime<Utc>) -> Result<(), AppError> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        let keys = tx
+            .prepare(
+                "SELECT key FROM settings WHERE key IN ('primary_server_id', 'reference_server_id')
+                 AND value = ?1 ORDER BY key",
+            )?
+            .query_map(params![id.to_string()], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        let designations = (!keys.is_empty()).then(|| keys.join(","));
+        let changed = tx.execute(
+            "UPDATE servers SET deleted_at = ?1, trashed_designations = ?2
+             WHERE id = ?3 AND deleted_at IS NULL",
+            params![now.to_rfc3339(), designations, id],
+        )?;
+        if changed == 0 {
+            return Err(AppError::Db(rusqlite::Error::QueryReturnedNoRows));
+        }
+        clear_designations(&tx, id)?;
+        tx.commit()?;
         Ok(())
     }
 
+    /// Bring a trashed server back, as primary or reference server again if
+    /// it was one and no other server has taken that place meanwhile.
+    pub fn restore_server(&self, id: i64) -> Result<Server, AppError> {
+        {
+            let conn = self.conn.lock().unwrap();
+            let tx = conn.unchecked_transaction()?;
+            let designations: Option<String> = tx
+                .query_row(
+                    "SELECT trashed_designations FROM servers
+                     WHERE id = ?1 AND deleted_at IS NOT NULL",
+                    params![id],
+                    |row| row.get(0),
+                )
+                .optional()?
+                .ok_or(AppError::Db(rusqlite::Error::QueryReturnedNoRows))?;
+            tx.execute(
+                "UPDATE servers SET deleted_at = NULL, trashed_designations = NULL WHERE id = ?1",
+                params![id],
+            )?;
+            for key in designations.iter().flat_map(|keys| keys.split(',')) {
+                tx.execute(
+                    "INSERT OR IGNORE INTO settings (key, value) VALUES (?1, ?2)",
+                    params![key, id.to_string()],
+                )?;
+            }
+            tx.commit()?;
+        }
+        self.get_server(id)
+    }
+
+    /// Trashed servers, most recently deleted first, each with the time
+    /// `retention` after its deletion when it is purged.
+    pub fn list_trashed_servers(
+        &self,
+        retention: chrono::Duration,
+    ) -> Result<Vec<TrashedServer>, AppError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {SERVER_COLUMNS}, deleted_at FROM servers
+             WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC"
+        ))?;
+        let trashed = stmt
+            .query_map([], |row| {
+                let deleted_str: String = row.get(21)?;
+                let deleted_at = DateTime::parse_from_rfc3339(&deleted_str)
+                    .map(|dt| dt.with_timezone(&Utc))
+                    .unwrap_or_default();
+                Ok(TrashedServer {
+                    server: server_from_row(row)?,
+                    deleted_at,
+                    purge_at: deleted_at + retention,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(trashed)
+    }
+
+    /// Purge trashed servers deleted before `before`, or all of them.
+    /// Returns how many went.
+    pub fn empty_trash(&self, before: Option<DateTime<Utc>>) -> Result<usize, AppError> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        let ids: Vec<i64> = tx
+            .prepare(
+                "SELECT id FROM servers WHERE deleted_at IS NOT NULL
+                 AND (?1 IS NULL OR deleted_at < ?1)",
+            )?
+            .query_map(params![before.map(|b| b.to_rfc3339())], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+        for id in &ids {
+            purge_server(&tx, *id)?;
+        }
+        tx.commit()?;
+        Ok(ids.len())
+    }
+
     pub fn update_server_offset(
         &self,
         id: i64,
@@ -170,6 +501,20 @@ impl Database {
         Ok(())
     }
 
+    /// Shift every stored offset by `delta_ms`. Offsets are relative to the
+    /// local clock, so moving that clock by `d` changes them by `-d`. Drift
+    /// fits were made against the old clock and are dropped until the next
+    /// sync refits them.
+    pub fn shift_server_offsets(&self, delta_ms: f64) -> Result<(), AppError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE servers SET offset_ms = offset_ms + ?1, drift_json = NULL
+             WHERE offset_ms IS NOT NULL",
+            params![delta_ms],
+        )?;
+        Ok(())
+    }
+
     pub fn update_server_status(&self, id: i64, status: &ServerStatus) -> Result<(), AppError> {
         let conn = self.conn.lock().unwrap();
         conn.execute(
@@ -179,13 +524,231 @@ impl Database {
         Ok(())
     }
 
-    pub fn save_sync_result(&self, result: &SyncResult) -> Result<(), AppError> {
+    pub fn update_server_config(
+        &self,
+        id: i64,
+        name: Option<&str>,
+        extractor_type: &str,
+    ) -> Result<(), AppError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE servers SET name = ?1, extractor_type = ?2 WHERE id = ?3",
+            params![name, extractor_type, id],
+        )?;
+        Ok(())
+    }
+
+    pub fn set_server_name(&self, id: i64, name: &str) -> Result<(), AppError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE servers SET name = ?1 WHERE id = ?2",
+            params![name, id],
+        )?;
+        Ok(())
+    }
+
+    pub fn set_server_sync_profile(&self, id: i64, profile: Option<&str>) -> Result<(), AppError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE servers SET sync_profile = ?1 WHERE id = ?2",
+            params![profile, id],
+        )?;
+        Ok(())
+    }
+
+    /// `None` goes back to assuming a symmetric path.
+    pub fn set_server_asymmetry(&self, id: i64, asymmetry: Option<f64>) -> Result<(), AppError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE servers SET asymmetry = ?1 WHERE id = ?2",
+            params![asymmetry, id],
+        )?;
+        Ok(())
+    }
+
+    pub fn set_server_sync_on_launch(&self, id: i64, enabled: bool) -> Result<(), AppError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE servers SET sync_on_launch = ?1 WHERE id = ?2",
+            params![enabled as i32, id],
+        )?;
+        Ok(())
+    }
+
+    pub fn set_server_user_agent(&self, id: i64, user_agent: Option<&str>) -> Result<(), AppError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE servers SET user_agent = ?1 WHERE id = ?2",
+            params![user_agent, id],
+        )?;
+        Ok(())
+    }
+
+    pub fn set_server_request_interval(
+        &self,
+        id: i64,
+        interval_ms: Option<u32>,
+    ) -> Result<(), AppError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE servers SET min_request_interval_ms = ?1 WHERE id = ?2",
+            params![interval_ms, id],
+        )?;
+        Ok(())
+    }
+
+    /// `None` goes back to guessing the timezone.
+    pub fn set_server_timezone(&self, id: i64, timezone: Option<&str>) -> Result<(), AppError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE servers SET timezone = ?1 WHERE id = ?2",
+            params![timezone, id],
+        )?;
+        Ok(())
+    }
+
+    pub fn set_server_trusted(&self, id: i64, trusted: bool) -> Result<(), AppError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE servers SET trusted = ?1 WHERE id = ?2",
+            params![trusted as i32, id],
+        )?;
+        Ok(())
+    }
+
+    pub fn set_server_drift(&self, id: i64, drift: Option<&DriftEstimate>) -> Result<(), AppError> {
+        let conn = self.conn.lock().unwrap();
+        let json = drift.and_then(|d| serde_json::to_string(d).ok());
+        conn.execute(
+            "UPDATE servers SET drift_json = ?1 WHERE id = ?2",
+            params![json, id],
+        )?;
+        Ok(())
+    }
+
+    pub fn save_favicon(&self, server_id: i64, favicon: &Favicon) -> Result<(), AppError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO favicons (server_id, content_type, data, fetched_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                server_id,
+                favicon.content_type,
+                favicon.data,
+                favicon.fetched_at.to_rfc3339()
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_favicon(&self, server_id: i64) -> Result<Option<Favicon>, AppError> {
+        let conn = self.conn.lock().unwrap();
+        let row = conn
+            .query_row(
+                "SELECT content_type, data, fetched_at FROM favicons WHERE server_id = ?1",
+                params![server_id],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, Vec<u8>>(1)?,
+                        row.get::<_, String>(2)?,
+                    ))
+                },
+            )
+            .optional()?;
+        Ok(row.map(|(content_type, data, fetched_at)| Favicon {
+            content_type,
+            data,
+            fetched_at: DateTime::parse_from_rfc3339(&fetched_at)
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_default(),
+        }))
+    }
+
+    pub fn set_server_health(&self, id: i64, score: Option<u8>) -> Result<(), AppError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE servers SET health_score = ?1 WHERE id = ?2",
+            params![score, id],
+        )?;
+        Ok(())
+    }
+
+    /// Servers flagged to sync when the app starts.
+    pub fn list_sync_on_launch_servers(&self) -> Result<Vec<Server>, AppError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {SERVER_COLUMNS} FROM servers
+             WHERE sync_on_launch != 0 AND deleted_at IS NULL ORDER BY id"
+        ))?;
+        let servers = stmt
+            .query_map([], server_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(servers)
+    }
+
+    /// Record the preset a server follows and the probe settings it implies.
+    pub fn set_server_preset(
+        &self,
+        id: i64,
+        preset: Option<&str>,
+        url: &str,
+        extractor_type: &str,
+        probe_method: &str,
+    ) -> Result<(), AppError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE servers SET domain_preset = ?1, url = ?2, extractor_type = ?3, probe_method = ?4 WHERE id = ?5",
+            params![preset, url, extractor_type, probe_method, id],
+        )?;
+        Ok(())
+    }
+
+    pub fn set_server_transport(
+        &self,
+        id: i64,
+        transport: &TransportOptions,
+    ) -> Result<(), AppError> {
+        let conn = self.conn.lock().unwrap();
+        let json = serde_json::to_string(transport).unwrap_or_else(|_| "{}".to_string());
+        conn.execute(
+            "UPDATE servers SET transport_json = ?1 WHERE id = ?2",
+            params![json, id],
+        )?;
+        Ok(())
+    }
+
+    pub fn set_server_fingerprint(
+        &self,
+        id: i64,
+        fingerprint: &ServerFingerprint,
+    ) -> Result<(), AppError> {
+        let conn = self.conn.lock().unwrap();
+        let json = serde_json::to_string(fingerprint).unwrap_or_else(|_| "{}".to_string());
+        conn.execute(
+            "UPDATE servers SET fingerprint_json = ?1 WHERE id = ?2",
+            params![json, id],
+        )?;
+        Ok(())
+    }
+
+    /// Returns the new row id.
+    pub fn save_sync_result(&self, result: &SyncResult) -> Result<i64, AppError> {
         let conn = self.conn.lock().unwrap();
         let profile_json =
             serde_json::to_string(&result.latency_profile).unwrap_or_else(|_| "{}".to_string());
+        let response_json = result
+            .response
+            .as_ref()
+            .and_then(|r| serde_json::to_string(r).ok());
+        let timeline_json = serde_json::to_string(&result.timeline).ok();
+        let network_json = result
+            .network
+            .as_ref()
+            .and_then(|n| serde_json::to_string(n).ok());
         conn.execute(
-            "INSERT INTO sync_results (server_id, whole_second_offset, subsecond_offset, total_offset_ms, latency_profile_json, verified, synced_at, duration_ms, phase_reached)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            "INSERT INTO sync_results (server_id, whole_second_offset, subsecond_offset, total_offset_ms, latency_profile_json, verified, synced_at, duration_ms, phase_reached, response_json, timeline_json, asymmetry, anomalous, network_json, grade, precision_ms)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)",
             params![
                 result.server_id,
                 result.whole_second_offset,
@@ -196,33 +759,184 @@ impl Database {
                 result.synced_at.to_rfc3339(),
                 result.duration_ms as i64,
                 i32::from(result.phase_reached),
+                response_json,
+                timeline_json,
+                result.asymmetry,
+                result.anomalous as i32,
+                network_json,
+                result.grade.map(SyncGrade::as_str),
+                result.precision_ms,
             ],
         )?;
-        Ok(())
+        Ok(conn.last_insert_rowid())
     }
 
-    pub fn get_settings(&self) -> Result<AppSettings, AppError> {
+    pub fn get_sync_result(&self, id: i64) -> Result<SyncResult, AppError> {
         let conn = self.conn.lock().unwrap();
-        let mut stmt = conn.prepare("SELECT key, value FROM settings")?;
-        let rows: HashMap<String, String> = stmt
-            .query_map([], |row| {
-                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
-            })?
-            .filter_map(|r| r.ok())
-            .collect();
+        let result = conn.query_row(
+            &format!("SELECT {SYNC_RESULT_COLUMNS} FROM sync_results WHERE id = ?1"),
+            params![id],
+            sync_result_from_row,
+        )?;
+        Ok(result)
+    }
 
-        let defaults = AppSettings::default();
+    /// Attach a note to a stored sync; `None` clears it.
+    pub fn annotate_sync_result(&self, id: i64, note: Option<&str>) -> Result<(), AppError> {
+        let conn = self.conn.lock().unwrap();
+        let changed = conn.execute(
+            "UPDATE sync_results SET note = ?1 WHERE id = ?2",
+            params![note, id],
+        )?;
+        if changed == 0 {
+            return Err(rusqlite::Error::QueryReturnedNoRows.into());
+        }
+        Ok(())
+    }
 
-        Ok(AppSettings {
-            theme: rows.get("theme").cloned().unwrap_or(defaults.theme),
-            min_request_interval_ms: rows
-                .get("min_request_interval_ms")
-                .and_then(|v| v.parse().ok())
-                .unwrap_or(defaults.min_request_interval_ms),
-            health_resync_threshold: rows
-                .get("health_resync_threshold")
+    pub fn save_sync_error(
+        &self,
+        server_id: i64,
+        code: ErrorCode,
+        message: &str,
+        context: &FailureContext,
+        occurred_at: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        let conn = self.conn.lock().unwrap();
+        let code = serde_json::to_value(code)
+            .ok()
+            .and_then(|v| v.as_str().map(str::to_string))
+            .unwrap_or_default();
+        conn.execute(
+            "INSERT INTO errors (server_id, phase, code, message, total_attempts, occurred_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                server_id,
+                i32::from(context.phase),
+                code,
+                message,
+                context.total_attempts,
+                occurred_at.to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Newest first.
+    pub fn get_error_history(
+        &self,
+        server_id: i64,
+        limit: Option<i64>,
+    ) -> Result<Vec<SyncErrorRecord>, AppError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, server_id, phase, code, message, total_attempts, occurred_at
+             FROM errors WHERE server_id = ?1 ORDER BY occurred_at DESC, id DESC LIMIT ?2",
+        )?;
+        let rows = stmt
+            .query_map(params![server_id, limit.unwrap_or(-1)], |row| {
+                let code: String = row.get(3)?;
+                let occurred_str: String = row.get(6)?;
+                Ok(SyncErrorRecord {
+                    id: row.get(0)?,
+                    server_id: row.get(1)?,
+                    phase: SyncPhase::try_from(row.get::<_, i32>(2)?).map_err(|e| {
+                        rusqlite::Error::FromSqlConversionFailure(
+                            2,
+                            rusqlite::types::Type::Integer,
+                            Box::from(e),
+                        )
+                    })?,
+                    code: serde_json::from_value(serde_json::Value::String(code)).map_err(|e| {
+                        rusqlite::Error::FromSqlConversionFailure(
+                            3,
+                            rusqlite::types::Type::Text,
+                            Box::new(e),
+                        )
+                    })?,
+                    message: row.get(4)?,
+                    total_attempts: row.get(5)?,
+                    occurred_at: DateTime::parse_from_rfc3339(&occurred_str)
+                        .map(|dt| dt.with_timezone(&Utc))
+                        .unwrap_or_else(|_| Utc::now()),
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    /// Store a benchmark report and return it with its new id.
+    pub fn save_benchmark_report(
+        &self,
+        report: &BenchmarkReport,
+    ) -> Result<BenchmarkReport, AppError> {
+        let conn = self.conn.lock().unwrap();
+        let json = serde_json::to_string(report).unwrap_or_else(|_| "{}".to_string());
+        conn.execute(
+            "INSERT INTO benchmark_reports (server_id, created_at, report_json) VALUES (?1, ?2, ?3)",
+            params![report.server_id, report.created_at.to_rfc3339(), json],
+        )?;
+        Ok(BenchmarkReport {
+            id: conn.last_insert_rowid(),
+            ..report.clone()
+        })
+    }
+
+    /// Newest first. Rows that no longer parse are skipped.
+    pub fn list_benchmark_reports(&self, server_id: i64) -> Result<Vec<BenchmarkReport>, AppError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, report_json FROM benchmark_reports WHERE server_id = ?1 ORDER BY created_at DESC",
+        )?;
+        let rows = stmt
+            .query_map(params![server_id], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|(id, json)| {
+                serde_json::from_str::<BenchmarkReport>(&json)
+                    .ok()
+                    .map(|report| BenchmarkReport { id, ..report })
+            })
+            .collect())
+    }
+
+    pub fn get_settings(&self) -> Result<AppSettings, AppError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT key, value FROM settings")?;
+        let rows: HashMap<String, String> = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        let defaults = AppSettings::default();
+
+        Ok(AppSettings {
+            theme: rows.get("theme").cloned().unwrap_or(defaults.theme),
+            min_request_interval_ms: rows
+                .get("min_request_interval_ms")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.min_request_interval_ms),
+            public_host_floor_ms: rows
+                .get("public_host_floor_ms")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.public_host_floor_ms),
+            sync_profile: rows
+                .get("sync_profile")
+                .cloned()
+                .unwrap_or(defaults.sync_profile),
+            health_resync_threshold: rows
+                .get("health_resync_threshold")
                 .and_then(|v| v.parse().ok())
                 .unwrap_or(defaults.health_resync_threshold),
+            auto_resync_interval_minutes: rows
+                .get("auto_resync_interval_minutes")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.auto_resync_interval_minutes),
             external_time_source: rows
                 .get("external_time_source")
                 .cloned()
@@ -239,6 +953,10 @@ impl Database {
                 .get("show_timezone_offset")
                 .map(|v| v == "true")
                 .unwrap_or(defaults.show_timezone_offset),
+            time_format: rows
+                .get("time_format")
+                .cloned()
+                .unwrap_or(defaults.time_format),
             overlay_opacity: rows
                 .get("overlay_opacity")
                 .and_then(|v| v.parse().ok())
@@ -251,18 +969,84 @@ impl Database {
                 .get("overlay_always_on_top")
                 .map(|v| v == "true")
                 .unwrap_or(defaults.overlay_always_on_top),
+            countdown_tick_ms: rows
+                .get("countdown_tick_ms")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.countdown_tick_ms),
             alert_intervals: rows
                 .get("alert_intervals")
                 .and_then(|v| serde_json::from_str(v).ok())
                 .unwrap_or(defaults.alert_intervals),
-            alert_method: rows
-                .get("alert_method")
-                .cloned()
-                .unwrap_or(defaults.alert_method),
+            // "visual" was the name of "notification" before "none" existed.
+            alert_method: match rows.get("alert_method").map(String::as_str) {
+                Some("visual") => "notification".to_string(),
+                Some(method) => method.to_string(),
+                None => defaults.alert_method,
+            },
+            keep_warm_minutes: rows
+                .get("keep_warm_minutes")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.keep_warm_minutes),
             drift_warning_threshold_ms: rows
                 .get("drift_warning_threshold_ms")
                 .and_then(|v| v.parse().ok())
                 .unwrap_or(defaults.drift_warning_threshold_ms),
+            notify_anomalies: rows
+                .get("notify_anomalies")
+                .map(|v| v == "true")
+                .unwrap_or(defaults.notify_anomalies),
+            local_api_enabled: rows
+                .get("local_api_enabled")
+                .map(|v| v == "true")
+                .unwrap_or(defaults.local_api_enabled),
+            local_api_port: rows
+                .get("local_api_port")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.local_api_port),
+            allow_clock_adjustment: rows
+                .get("allow_clock_adjustment")
+                .map(|v| v == "true")
+                .unwrap_or(defaults.allow_clock_adjustment),
+            progress_event_limit: rows
+                .get("progress_event_limit")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.progress_event_limit),
+            record_syncs: rows
+                .get("record_syncs")
+                .map(|v| v == "true")
+                .unwrap_or(defaults.record_syncs),
+            trash_retention_days: rows
+                .get("trash_retention_days")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.trash_retention_days),
+            sync_stall_timeout_secs: rows
+                .get("sync_stall_timeout_secs")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.sync_stall_timeout_secs),
+            sync_deadline_secs: rows
+                .get("sync_deadline_secs")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.sync_deadline_secs),
+            network_precheck: rows
+                .get("network_precheck")
+                .map(|v| v == "true")
+                .unwrap_or(defaults.network_precheck),
+            captive_portal_check: rows
+                .get("captive_portal_check")
+                .map(|v| v == "true")
+                .unwrap_or(defaults.captive_portal_check),
+            extrapolate_drift: rows
+                .get("extrapolate_drift")
+                .map(|v| v == "true")
+                .unwrap_or(defaults.extrapolate_drift),
+            lan_time_enabled: rows
+                .get("lan_time_enabled")
+                .map(|v| v == "true")
+                .unwrap_or(defaults.lan_time_enabled),
+            lan_time_port: rows
+                .get("lan_time_port")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.lan_time_port),
         })
     }
 
@@ -276,10 +1060,19 @@ impl Database {
                 "min_request_interval_ms",
                 settings.min_request_interval_ms.to_string(),
             ),
+            (
+                "public_host_floor_ms",
+                settings.public_host_floor_ms.to_string(),
+            ),
+            ("sync_profile", settings.sync_profile.clone()),
             (
                 "health_resync_threshold",
                 settings.health_resync_threshold.to_string(),
             ),
+            (
+                "auto_resync_interval_minutes",
+                settings.auto_resync_interval_minutes.to_string(),
+            ),
             (
                 "external_time_source",
                 settings.external_time_source.clone(),
@@ -293,22 +1086,54 @@ impl Database {
                 "show_timezone_offset",
                 settings.show_timezone_offset.to_string(),
             ),
+            ("time_format", settings.time_format.clone()),
             ("overlay_opacity", settings.overlay_opacity.to_string()),
             ("overlay_auto_hide", settings.overlay_auto_hide.to_string()),
             (
                 "overlay_always_on_top",
                 settings.overlay_always_on_top.to_string(),
             ),
+            ("countdown_tick_ms", settings.countdown_tick_ms.to_string()),
             (
                 "alert_intervals",
                 serde_json::to_string(&settings.alert_intervals)
                     .unwrap_or_else(|_| "[]".to_string()),
             ),
             ("alert_method", settings.alert_method.clone()),
+            ("keep_warm_minutes", settings.keep_warm_minutes.to_string()),
             (
                 "drift_warning_threshold_ms",
                 settings.drift_warning_threshold_ms.to_string(),
             ),
+            ("notify_anomalies", settings.notify_anomalies.to_string()),
+            ("local_api_enabled", settings.local_api_enabled.to_string()),
+            ("local_api_port", settings.local_api_port.to_string()),
+            (
+                "allow_clock_adjustment",
+                settings.allow_clock_adjustment.to_string(),
+            ),
+            (
+                "progress_event_limit",
+                settings.progress_event_limit.to_string(),
+            ),
+            ("record_syncs", settings.record_syncs.to_string()),
+            (
+                "trash_retention_days",
+                settings.trash_retention_days.to_string(),
+            ),
+            (
+                "sync_stall_timeout_secs",
+                settings.sync_stall_timeout_secs.to_string(),
+            ),
+            ("sync_deadline_secs", settings.sync_deadline_secs.to_string()),
+            ("network_precheck", settings.network_precheck.to_string()),
+            (
+                "captive_portal_check",
+                settings.captive_portal_check.to_string(),
+            ),
+            ("extrapolate_drift", settings.extrapolate_drift.to_string()),
+            ("lan_time_enabled", settings.lan_time_enabled.to_string()),
+            ("lan_time_port", settings.lan_time_port.to_string()),
         ];
 
         for (key, value) in pairs {
@@ -322,6 +1147,75 @@ impl Database {
         Ok(())
     }
 
+    pub fn get_primary_server(&self) -> Result<Option<i64>, AppError> {
+        let conn = self.conn.lock().unwrap();
+        let value: Option<String> = conn
+            .query_row(
+                "SELECT value FROM settings WHERE key = 'primary_server_id'",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(value.and_then(|v| v.parse().ok()))
+    }
+
+    pub fn set_primary_server(&self, id: Option<i64>) -> Result<(), AppError> {
+        let conn = self.conn.lock().unwrap();
+        match id {
+            Some(id) => conn.execute(
+                "INSERT OR REPLACE INTO settings (key, value) VALUES ('primary_server_id', ?1)",
+                params![id.to_string()],
+            )?,
+            None => conn.execute("DELETE FROM settings WHERE key = 'primary_server_id'", [])?,
+        };
+        Ok(())
+    }
+
+    pub fn get_reference_server(&self) -> Result<Option<i64>, AppError> {
+        let conn = self.conn.lock().unwrap();
+        let value: Option<String> = conn
+            .query_row(
+                "SELECT value FROM settings WHERE key = 'reference_server_id'",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(value.and_then(|v| v.parse().ok()))
+    }
+
+    pub fn set_reference_server(&self, id: Option<i64>) -> Result<(), AppError> {
+        let conn = self.conn.lock().unwrap();
+        match id {
+            Some(id) => conn.execute(
+                "INSERT OR REPLACE INTO settings (key, value) VALUES ('reference_server_id', ?1)",
+                params![id.to_string()],
+            )?,
+            None => conn.execute("DELETE FROM settings WHERE key = 'reference_server_id'", [])?,
+        };
+        Ok(())
+    }
+
+    pub fn get_local_api_token(&self) -> Result<Option<String>, AppError> {
+        let conn = self.conn.lock().unwrap();
+        let value = conn
+            .query_row(
+                "SELECT value FROM settings WHERE key = 'local_api_token'",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(value)
+    }
+
+    pub fn set_local_api_token(&self, token: &str) -> Result<(), AppError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('local_api_token', ?1)",
+            params![token],
+        )?;
+        Ok(())
+    }
+
     pub fn get_sync_history(
         &self,
         server_id: i64,
@@ -330,10 +1224,8 @@ impl Database {
     ) -> Result<Vec<SyncResult>, AppError> {
         let conn = self.conn.lock().unwrap();
 
-        let mut sql = String::from(
-            "SELECT server_id, whole_second_offset, subsecond_offset, total_offset_ms, latency_profile_json, verified, synced_at, duration_ms, phase_reached
-             FROM sync_results WHERE server_id = ?1",
-        );
+        let mut sql =
+            format!("SELECT {SYNC_RESULT_COLUMNS} FROM sync_results WHERE server_id = ?1");
         if since.is_some() {
             sql.push_str(" AND synced_at >= ?2");
         }
@@ -348,36 +1240,7 @@ impl Database {
 
         let mut stmt = conn.prepare(&sql)?;
 
-        let row_mapper = |row: &rusqlite::Row| {
-            let profile_json: String = row.get(4)?;
-            let synced_str: String = row.get(6)?;
-            Ok(SyncResult {
-                server_id: row.get(0)?,
-                whole_second_offset: row.get(1)?,
-                subsecond_offset: row.get(2)?,
-                total_offset_ms: row.get(3)?,
-                latency_profile: serde_json::from_str(&profile_json).unwrap_or(LatencyProfile {
-                    min: 0.0,
-                    q1: 0.0,
-                    median: 0.0,
-                    mean: 0.0,
-                    q3: 0.0,
-                    max: 0.0,
-                }),
-                verified: row.get::<_, i32>(5)? != 0,
-                synced_at: DateTime::parse_from_rfc3339(&synced_str)
-                    .map(|dt| dt.with_timezone(&Utc))
-                    .unwrap_or_else(|_| Utc::now()),
-                duration_ms: row.get::<_, i64>(7)? as u64,
-                phase_reached: SyncPhase::try_from(row.get::<_, i32>(8)?).map_err(|e| {
-                    rusqlite::Error::FromSqlConversionFailure(
-                        8,
-                        rusqlite::types::Type::Integer,
-                        Box::from(e),
-                    )
-                })?,
-            })
-        };
+        let row_mapper = sync_result_from_row;
 
         let results = match (since, limit) {
             (Some(s), Some(l)) => stmt
@@ -396,6 +1259,424 @@ impl Database {
 
         Ok(results)
     }
+
+    /// `limit` results matching `filter` from `offset` on, newest first,
+    /// and how many match in all. Ties on `synced_at` are broken by id so
+    /// pages never overlap.
+    pub fn get_sync_history_page(
+        &self,
+        server_id: i64,
+        filter: &SyncHistoryFilter,
+        offset: i64,
+        limit: i64,
+    ) -> Result<SyncHistoryPage, AppError> {
+        const MATCHES: &str = "server_id = ?1
+             AND (?2 IS NULL OR synced_at >= ?2)
+             AND (?3 IS NULL OR verified = ?3)
+             AND (?4 IS NULL OR phase_reached = ?4)
+             AND (?5 IS NULL OR anomalous = ?5)";
+        let conn = self.conn.lock().unwrap();
+        let verified = filter.verified.map(i32::from);
+        let phase = filter.phase_reached.map(i32::from);
+        let anomalous = filter.anomalous.map(i32::from);
+        let total = conn.query_row(
+            &format!("SELECT COUNT(*) FROM sync_results WHERE {MATCHES}"),
+            params![server_id, filter.since, verified, phase, anomalous],
+            |row| row.get(0),
+        )?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {SYNC_RESULT_COLUMNS} FROM sync_results WHERE {MATCHES}
+             ORDER BY synced_at DESC, id DESC LIMIT ?6 OFFSET ?7"
+        ))?;
+        let results = stmt
+            .query_map(
+                params![
+                    server_id,
+                    filter.since,
+                    verified,
+                    phase,
+                    anomalous,
+                    limit,
+                    offset
+                ],
+                sync_result_from_row,
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(SyncHistoryPage {
+            results,
+            total,
+            offset,
+        })
+    }
+
+    // ── Targets ──
+
+    pub fn add_target(
+        &self,
+        server_id: i64,
+        target_at: DateTime<Utc>,
+        label: Option<&str>,
+    ) -> Result<Target, AppError> {
+        let conn = self.conn.lock().unwrap();
+        let now = Utc::now();
+        conn.execute(
+            "INSERT INTO targets (server_id, label, target_at, armed, created_at) VALUES (?1, ?2, ?3, 1, ?4)",
+            params![server_id, label, target_at.to_rfc3339(), now.to_rfc3339()],
+        )?;
+        Ok(Target {
+            id: conn.last_insert_rowid(),
+            server_id,
+            label: label.map(str::to_string),
+            target_at,
+            armed: true,
+            created_at: now,
+            alert_method: None,
+        })
+    }
+
+    pub fn list_targets(&self) -> Result<Vec<Target>, AppError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, server_id, label, target_at, armed, created_at, alert_method FROM targets
+             WHERE server_id NOT IN (SELECT id FROM servers WHERE deleted_at IS NOT NULL)
+             ORDER BY target_at",
+        )?;
+        let targets = stmt
+            .query_map([], target_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(targets)
+    }
+
+    pub fn get_target(&self, id: i64) -> Result<Target, AppError> {
+        let conn = self.conn.lock().unwrap();
+        let target = conn.query_row(
+            "SELECT id, server_id, label, target_at, armed, created_at, alert_method FROM targets WHERE id = ?1",
+            params![id],
+            target_from_row,
+        )?;
+        Ok(target)
+    }
+
+    /// `None` makes the target follow the `alert_method` setting again.
+    pub fn set_target_alert_method(&self, id: i64, method: Option<&str>) -> Result<(), AppError> {
+        let conn = self.conn.lock().unwrap();
+        let changed = conn.execute(
+            "UPDATE targets SET alert_method = ?2 WHERE id = ?1",
+            params![id, method],
+        )?;
+        if changed == 0 {
+            return Err(rusqlite::Error::QueryReturnedNoRows.into());
+        }
+        Ok(())
+    }
+
+    pub fn set_target_armed(&self, id: i64, armed: bool) -> Result<(), AppError> {
+        let conn = self.conn.lock().unwrap();
+        let changed = conn.execute(
+            "UPDATE targets SET armed = ?2 WHERE id = ?1",
+            params![id, armed as i32],
+        )?;
+        if changed == 0 {
+            return Err(rusqlite::Error::QueryReturnedNoRows.into());
+        }
+        Ok(())
+    }
+
+    pub fn delete_target(&self, id: i64) -> Result<(), AppError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM targets WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    // ── Scheduled syncs ──
+
+    pub fn add_scheduled_sync(
+        &self,
+        server_id: i64,
+        run_at: DateTime<Utc>,
+    ) -> Result<ScheduledSync, AppError> {
+        let conn = self.conn.lock().unwrap();
+        let now = Utc::now();
+        conn.execute(
+            "INSERT INTO scheduled_syncs (server_id, run_at, created_at) VALUES (?1, ?2, ?3)",
+            params![server_id, run_at.to_rfc3339(), now.to_rfc3339()],
+        )?;
+        Ok(ScheduledSync {
+            id: conn.last_insert_rowid(),
+            server_id,
+            run_at,
+            created_at: now,
+        })
+    }
+
+    /// Pending schedules, soonest first.
+    pub fn list_scheduled_syncs(&self) -> Result<Vec<ScheduledSync>, AppError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, server_id, run_at, created_at FROM scheduled_syncs
+             WHERE server_id NOT IN (SELECT id FROM servers WHERE deleted_at IS NOT NULL)
+             ORDER BY run_at",
+        )?;
+        let schedules = stmt
+            .query_map([], scheduled_sync_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(schedules)
+    }
+
+    pub fn delete_scheduled_sync(&self, id: i64) -> Result<(), AppError> {
+        let conn = self.conn.lock().unwrap();
+        let changed = conn.execute("DELETE FROM scheduled_syncs WHERE id = ?1", params![id])?;
+        if changed == 0 {
+            return Err(rusqlite::Error::QueryReturnedNoRows.into());
+        }
+        Ok(())
+    }
+}
+
+fn scheduled_sync_from_row(row: &rusqlite::Row) -> rusqlite::Result<ScheduledSync> {
+    let run_str: String = row.get(2)?;
+    let created_str: String = row.get(3)?;
+    Ok(ScheduledSync {
+        id: row.get(0)?,
+        server_id: row.get(1)?,
+        run_at: DateTime::parse_from_rfc3339(&run_str)
+            .map(|dt| dt.with_timezone(&Utc))
+            .unwrap_or_else(|_| Utc::now()),
+        created_at: DateTime::parse_from_rfc3339(&created_str)
+            .map(|dt| dt.with_timezone(&Utc))
+            .unwrap_or_else(|_| Utc::now()),
+    })
+}
+
+fn target_from_row(row: &rusqlite::Row) -> rusqlite::Result<Target> {
+    let target_str: String = row.get(3)?;
+    let created_str: String = row.get(5)?;
+    Ok(Target {
+        id: row.get(0)?,
+        server_id: row.get(1)?,
+        label: row.get(2)?,
+        target_at: DateTime::parse_from_rfc3339(&target_str)
+            .map(|dt| dt.with_timezone(&Utc))
+            .unwrap_or_else(|_| Utc::now()),
+        armed: row.get::<_, i32>(4)? != 0,
+        created_at: DateTime::parse_from_rfc3339(&created_str)
+            .map(|dt| dt.with_timezone(&Utc))
+            .unwrap_or_else(|_| Utc::now()),
+        alert_method: row.get(6)?,
+    })
+}
+
+/// `ALTER TABLE ... ADD COLUMN` for databases created before the column
+/// existed; a no-op on fresh ones, where the column is already there.
+/// A server to upsert with `import_shared`; `id` is the saved server its
+/// URL already matches, if any.
+#[derive(Debug, Clone)]
+pub struct ImportedServer {
+    pub id: Option<i64>,
+    pub url: String,
+    pub name: Option<String>,
+    pub extractor_type: String,
+    pub sync_profile: Option<String>,
+}
+
+/// A server to insert with `add_servers`, domain preset already applied.
+#[derive(Debug, Clone)]
+pub struct NewServer {
+    pub url: String,
+    pub extractor_type: String,
+    pub probe_method: String,
+    pub domain_preset: Option<String>,
+}
+
+impl NewServer {
+    pub fn new(url: &str) -> Self {
+        match domain_presets::for_url(url) {
+            Some(preset) => Self {
+                url: domain_presets::apply_path(url, &preset),
+                extractor_type: preset.extractor_type.to_string(),
+                probe_method: preset.probe_method.to_string(),
+                domain_preset: Some(preset.id.to_string()),
+            },
+            None => Self {
+                url: url.to_string(),
+                extractor_type: "date_header".to_string(),
+                probe_method: domain_presets::HEAD.to_string(),
+                domain_preset: None,
+            },
+        }
+    }
+}
+
+const SERVER_COLUMNS: &str = "id, url, name, offset_ms, last_sync_at, created_at, status, extractor_type, sync_profile, fingerprint_json, probe_method, domain_preset, asymmetry, sync_on_launch, health_score, min_request_interval_ms, user_agent, transport_json, trusted, timezone, drift_json";
+
+fn server_from_row(row: &rusqlite::Row) -> rusqlite::Result<Server> {
+    let status_str: String = row.get(6)?;
+    let last_sync_str: Option<String> = row.get(4)?;
+    let created_str: String = row.get(5)?;
+    let url: String = row.get(1)?;
+    let fingerprint: Option<ServerFingerprint> = row
+        .get::<_, Option<String>>(9)?
+        .and_then(|json| serde_json::from_str(&json).ok());
+    let timezone: Option<String> = row.get(19)?;
+    let guessed = match &timezone {
+        Some(_) => None,
+        None => server_timezone::guess(
+            &url,
+            fingerprint
+                .as_ref()
+                .and_then(|f| f.content_language.as_deref()),
+        ),
+    };
+    Ok(Server {
+        id: row.get(0)?,
+        name: row.get(2)?,
+        offset_ms: row.get(3)?,
+        last_sync_at: last_sync_str.and_then(|s| {
+            DateTime::parse_from_rfc3339(&s)
+                .ok()
+                .map(|dt| dt.with_timezone(&Utc))
+        }),
+        created_at: DateTime::parse_from_rfc3339(&created_str)
+            .map(|dt| dt.with_timezone(&Utc))
+            .unwrap_or_else(|_| Utc::now()),
+        status: status_str.parse().unwrap_or(ServerStatus::Idle),
+        extractor_type: row.get(7)?,
+        sync_profile: row.get(8)?,
+        fingerprint,
+        probe_method: row.get(10)?,
+        domain_preset: row.get(11)?,
+        asymmetry: row.get(12)?,
+        sync_on_launch: row.get::<_, i32>(13)? != 0,
+        health_score: row.get(14)?,
+        drift: row
+            .get::<_, Option<String>>(20)?
+            .and_then(|json| serde_json::from_str(&json).ok()),
+        min_request_interval_ms: row.get(15)?,
+        user_agent: row.get(16)?,
+        transport: row
+            .get::<_, Option<String>>(17)?
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default(),
+        trusted: row.get::<_, i32>(18)? != 0,
+        timezone_guessed: guessed.is_some(),
+        timezone: timezone.or(guessed.map(str::to_string)),
+        url,
+    })
+}
+
+const SYNC_RESULT_COLUMNS: &str = "server_id, whole_second_offset, subsecond_offset, total_offset_ms, latency_profile_json, verified, synced_at, duration_ms, phase_reached, response_json, id, timeline_json, note, asymmetry, anomalous, network_json, grade, precision_ms";
+
+fn sync_result_from_row(row: &rusqlite::Row) -> rusqlite::Result<SyncResult> {
+    let profile_json: String = row.get(4)?;
+    let synced_str: String = row.get(6)?;
+    Ok(SyncResult {
+        server_id: row.get(0)?,
+        whole_second_offset: row.get(1)?,
+        subsecond_offset: row.get(2)?,
+        total_offset_ms: row.get(3)?,
+        latency_profile: serde_json::from_str(&profile_json).unwrap_or(LatencyProfile {
+            min: 0.0,
+            q1: 0.0,
+            median: 0.0,
+            mean: 0.0,
+            q3: 0.0,
+            max: 0.0,
+            breakdown: None,
+        }),
+        verified: row.get::<_, i32>(5)? != 0,
+        synced_at: DateTime::parse_from_rfc3339(&synced_str)
+            .map(|dt| dt.with_timezone(&Utc))
+            .unwrap_or_else(|_| Utc::now()),
+        duration_ms: row.get::<_, i64>(7)? as u64,
+        phase_reached: SyncPhase::try_from(row.get::<_, i32>(8)?).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(
+                8,
+                rusqlite::types::Type::Integer,
+                Box::from(e),
+            )
+        })?,
+        fingerprint: None,
+        response: row
+            .get::<_, Option<String>>(9)?
+            .and_then(|json| serde_json::from_str(&json).ok()),
+        id: row.get(10)?,
+        timeline: row
+            .get::<_, Option<String>>(11)?
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default(),
+        note: row.get(12)?,
+        asymmetry: row.get(13)?,
+        anomalous: row.get::<_, i32>(14)? != 0,
+        clock_issue: clock_issue::classify(row.get(3)?),
+        network: row
+            .get::<_, Option<String>>(15)?
+            .and_then(|json| serde_json::from_str(&json).ok()),
+        grade: row
+            .get::<_, Option<String>>(16)?
+            .and_then(|g| SyncGrade::parse(&g)),
+        precision_ms: row.get(17)?,
+    })
+}
+
+/// Delete a server and everything hanging off it.
+fn purge_server(conn: &Connection, id: i64) -> Result<(), AppError> {
+    conn.execute("DELETE FROM sync_results WHERE server_id = ?1", params![id])?;
+    conn.execute("DELETE FROM targets WHERE server_id = ?1", params![id])?;
+    conn.execute(
+        "DELETE FROM scheduled_syncs WHERE server_id = ?1",
+        params![id],
+    )?;
+    conn.execute("DELETE FROM errors WHERE server_id = ?1", params![id])?;
+    conn.execute(
+        "DELETE FROM benchmark_reports WHERE server_id = ?1",
+        params![id],
+    )?;
+    conn.execute("DELETE FROM favicons WHERE server_id = ?1", params![id])?;
+    conn.execute("DELETE FROM servers WHERE id = ?1", params![id])?;
+    clear_designations(conn, id)
+}
+
+/// Stop `id` being the primary or reference server.
+fn clear_designations(conn: &Connection, id: i64) -> Result<(), AppError> {
+    conn.execute(
+        "DELETE FROM settings WHERE key IN ('primary_server_id', 'reference_server_id')
+         AND value = ?1",
+        params![id.to_string()],
+    )?;
+    Ok(())
+}
+
+/// Adding a URL that sits in the trash replaces the trashed server, since
+/// URLs are unique.
+fn purge_trashed_url(conn: &Connection, url: &str) -> Result<(), AppError> {
+    let trashed: Option<i64> = conn
+        .query_row(
+            "SELECT id FROM servers WHERE url = ?1 AND deleted_at IS NOT NULL",
+            params![url],
+            |row| row.get(0),
+        )
+        .optional()?;
+    match trashed {
+        Some(id) => purge_server(conn, id),
+        None => Ok(()),
+    }
+}
+
+fn add_column_if_missing(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    decl: &str,
+) -> Result<(), AppError> {
+    let exists = conn
+        .prepare(&format!("PRAGMA table_info({table})"))?
+        .query_map([], |row| row.get::<_, String>(1))?
+        .filter_map(|r| r.ok())
+        .any(|name| name == column);
+    if !exists {
+        conn.execute_batch(&format!("ALTER TABLE {table} ADD COLUMN {column} {decl}"))?;
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -414,7 +1695,10 @@ impl Database {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::{AppSettings, LatencyProfile, ServerStatus, SyncPhase, SyncResult};
+    use crate::models::{
+        AppSettings, CertificateInfo, LatencyProfile, ResponseMetadata, ServerStatus, SyncPhase,
+        SyncResult,
+    };
     use chrono::{Duration, Utc};
 
     fn make_test_sync_result(server_id: i64, offset_ms: f64, synced_at: chrono::DateTime<Utc>) -> SyncResult {
@@ -430,11 +1714,23 @@ mod tests {
                 mean: 0.050,
                 q3: 0.055,
                 max: 0.060,
+                breakdown: None,
             },
             verified: true,
             synced_at,
             duration_ms: 5000,
             phase_reached: SyncPhase::Complete,
+            fingerprint: None,
+            asymmetry: None,
+            anomalous: false,
+            clock_issue: None,
+            network: None,
+            grade: None,
+            precision_ms: None,
+            response: None,
+            id: 0,
+            timeline: Vec::new(),
+            note: None,
         }
     }
 
@@ -514,31 +1810,162 @@ mod tests {
     }
 
     #[test]
-    fn test_update_server_status_changes_status() {
+    fn test_set_server_preset_updates_probe_settings() {
         let db = Database::new_in_memory().unwrap();
-        let server = db.add_server("https://example.com").unwrap();
-        db.update_server_status(server.id, &ServerStatus::Syncing).unwrap();
+        let server = db.add_server("https://www.ticketmaster.com").unwrap();
+        db.set_server_preset(
+            server.id,
+            Some("ticketmaster"),
+            "https://www.ticketmaster.com/robots.txt",
+            "date_header",
+            "GET",
+        )
+        .unwrap();
+
         let updated = db.get_server(server.id).unwrap();
-        assert_eq!(updated.status, ServerStatus::Syncing);
+        assert_eq!(updated.domain_preset.as_deref(), Some("ticketmaster"));
+        assert_eq!(updated.url, "https://www.ticketmaster.com/robots.txt");
+        assert_eq!(updated.probe_method, "GET");
     }
 
     #[test]
-    fn test_save_and_retrieve_sync_result() {
+    fn test_benchmark_reports_round_trip_newest_first() {
         let db = Database::new_in_memory().unwrap();
         let server = db.add_server("https://example.com").unwrap();
-        let now = Utc::now();
-        let result = make_test_sync_result(server.id, 150.0, now);
-        db.save_sync_result(&result).unwrap();
+        let report = |created_at| BenchmarkReport {
+            id: 0,
+            server_id: server.id,
+            created_at,
+            runs: Vec::new(),
+            difference_ms: None,
+            recommended: Some("four_phase".to_string()),
+        };
+        let older = db
+            .save_benchmark_report(&report(Utc::now() - Duration::minutes(5)))
+            .unwrap();
+        let newer = db.save_benchmark_report(&report(Utc::now())).unwrap();
+        assert_ne!(older.id, newer.id);
 
-        let history = db.get_sync_history(server.id, None, None).unwrap();
-        assert_eq!(history.len(), 1);
-        let r = &history[0];
-        assert_eq!(r.server_id, server.id);
-        assert!((r.total_offset_ms - 150.0).abs() < 0.001);
-        assert_eq!(r.verified, true);
-        assert_eq!(r.duration_ms, 5000);
-        assert_eq!(r.phase_reached, SyncPhase::Complete);
-        assert!((r.latency_profile.median - 0.050).abs() < 0.0001);
+        let reports = db.list_benchmark_reports(server.id).unwrap();
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[0].id, newer.id);
+        assert_eq!(reports[1].recommended.as_deref(), Some("four_phase"));
+
+        db.delete_server(server.id).unwrap();
+        assert!(db.list_benchmark_reports(server.id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_set_server_fingerprint_round_trips() {
+        let db = Database::new_in_memory().unwrap();
+        let server = db.add_server("https://example.com").unwrap();
+        assert!(db.get_server(server.id).unwrap().fingerprint.is_none());
+
+        let fingerprint = ServerFingerprint {
+            server: Some("AkamaiGHost".to_string()),
+            via: None,
+            cache: Some("TCP_MISS".to_string()),
+            cdn: Some("Akamai".to_string()),
+            content_language: None,
+        };
+        db.set_server_fingerprint(server.id, &fingerprint).unwrap();
+        assert_eq!(db.get_server(server.id).unwrap().fingerprint, Some(fingerprint));
+    }
+
+    #[test]
+    fn test_update_server_status_changes_status() {
+        let db = Database::new_in_memory().unwrap();
+        let server = db.add_server("https://example.com").unwrap();
+        db.update_server_status(server.id, &ServerStatus::Syncing).unwrap();
+        let updated = db.get_server(server.id).unwrap();
+        assert_eq!(updated.status, ServerStatus::Syncing);
+    }
+
+    #[test]
+    fn test_save_and_retrieve_sync_result() {
+        let db = Database::new_in_memory().unwrap();
+        let server = db.add_server("https://example.com").unwrap();
+        let now = Utc::now();
+        let result = make_test_sync_result(server.id, 150.0, now);
+        db.save_sync_result(&result).unwrap();
+
+        let history = db.get_sync_history(server.id, None, None).unwrap();
+        assert_eq!(history.len(), 1);
+        let r = &history[0];
+        assert_eq!(r.server_id, server.id);
+        assert!((r.total_offset_ms - 150.0).abs() < 0.001);
+        assert_eq!(r.verified, true);
+        assert_eq!(r.duration_ms, 5000);
+        assert_eq!(r.phase_reached, SyncPhase::Complete);
+        assert!((r.latency_profile.median - 0.050).abs() < 0.0001);
+        assert_eq!(r.response, None);
+    }
+
+    #[test]
+    fn test_get_sync_result_by_id_keeps_timeline() {
+        let db = Database::new_in_memory().unwrap();
+        let server = db.add_server("https://example.com").unwrap();
+        let timeline = vec![crate::models::PhaseTiming {
+            phase: SyncPhase::BinarySearch,
+            duration_ms: 2_400,
+            probes: 12,
+        }];
+        let result = SyncResult {
+            timeline: timeline.clone(),
+            ..make_test_sync_result(server.id, 150.0, Utc::now())
+        };
+        let id = db.save_sync_result(&result).unwrap();
+
+        let loaded = db.get_sync_result(id).unwrap();
+        assert_eq!(loaded.id, id);
+        assert_eq!(loaded.timeline, timeline);
+        assert_eq!(db.get_sync_history(server.id, None, None).unwrap()[0].id, id);
+        assert!(db.get_sync_result(id + 1).is_err());
+    }
+
+    #[test]
+    fn test_annotate_sync_result() {
+        let db = Database::new_in_memory().unwrap();
+        let server = db.add_server("https://example.com").unwrap();
+        let id = db
+            .save_sync_result(&make_test_sync_result(server.id, 150.0, Utc::now()))
+            .unwrap();
+
+        db.annotate_sync_result(id, Some("hotel Wi-Fi")).unwrap();
+        let history = db.get_sync_history(server.id, None, None).unwrap();
+        assert_eq!(history[0].note.as_deref(), Some("hotel Wi-Fi"));
+
+        db.annotate_sync_result(id, None).unwrap();
+        assert_eq!(db.get_sync_result(id).unwrap().note, None);
+        assert!(db.annotate_sync_result(id + 1, Some("x")).is_err());
+    }
+
+    #[test]
+    fn test_sync_result_response_metadata_round_trips() {
+        let db = Database::new_in_memory().unwrap();
+        let server = db.add_server("https://example.com").unwrap();
+        let response = ResponseMetadata {
+            status: 200,
+            http_version: "HTTP/2".to_string(),
+            remote_ip: Some("203.0.113.7".to_string()),
+            redirects: 1,
+            probe_overhead_us: 42.5,
+            certificate: Some(CertificateInfo {
+                subject: "CN=example.com".to_string(),
+                issuer: "C=US, O=Let's Encrypt, CN=R11".to_string(),
+                not_before: "2025-05-01T00:00:00Z".parse().unwrap(),
+                not_after: "2025-07-30T00:00:00Z".parse().unwrap(),
+                sha256: "ab".repeat(32),
+            }),
+        };
+        let result = SyncResult {
+            response: Some(response.clone()),
+            ..make_test_sync_result(server.id, 150.0, Utc::now())
+        };
+        db.save_sync_result(&result).unwrap();
+
+        let history = db.get_sync_history(server.id, None, None).unwrap();
+        assert_eq!(history[0].response, Some(response));
     }
 
     #[test]
@@ -571,6 +1998,142 @@ mod tests {
         assert!((history[0].total_offset_ms - 20.0).abs() < 0.001);
     }
 
+    #[test]
+    fn test_get_sync_history_page_counts_and_pages() {
+        let db = Database::new_in_memory().unwrap();
+        let server = db.add_server("https://example.com").unwrap();
+        let base = Utc::now();
+        for i in 0..5i64 {
+            let r = make_test_sync_result(server.id, i as f64, base + Duration::seconds(i));
+            db.save_sync_result(&r).unwrap();
+        }
+
+        let all = SyncHistoryFilter::default();
+        let first = db.get_sync_history_page(server.id, &all, 0, 2).unwrap();
+        assert_eq!(first.total, 5);
+        assert_eq!(
+            first.results.iter().map(|r| r.total_offset_ms).collect::<Vec<_>>(),
+            vec![4.0, 3.0]
+        );
+        let last = db.get_sync_history_page(server.id, &all, 4, 2).unwrap();
+        assert_eq!(last.results.len(), 1);
+        assert_eq!(last.results[0].total_offset_ms, 0.0);
+
+        let recent = SyncHistoryFilter {
+            since: Some((base + Duration::seconds(3)).to_rfc3339()),
+            ..Default::default()
+        };
+        let recent = db.get_sync_history_page(server.id, &recent, 0, 10).unwrap();
+        assert_eq!(recent.total, 2);
+        assert_eq!(recent.results.len(), 2);
+    }
+
+    #[test]
+    fn test_get_sync_history_page_filters_verified_and_phase() {
+        let db = Database::new_in_memory().unwrap();
+        let server = db.add_server("https://example.com").unwrap();
+        let base = Utc::now();
+        db.save_sync_result(&make_test_sync_result(server.id, 1.0, base))
+            .unwrap();
+        db.save_sync_result(&SyncResult {
+            verified: false,
+            phase_reached: SyncPhase::BinarySearch,
+            ..make_test_sync_result(server.id, 2.0, base + Duration::seconds(1))
+        })
+        .unwrap();
+
+        let unverified = SyncHistoryFilter {
+            verified: Some(false),
+            ..Default::default()
+        };
+        let page = db.get_sync_history_page(server.id, &unverified, 0, 10).unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.results[0].total_offset_ms, 2.0);
+
+        let complete = SyncHistoryFilter {
+            phase_reached: Some(SyncPhase::Complete),
+            ..Default::default()
+        };
+        let page = db.get_sync_history_page(server.id, &complete, 0, 10).unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.results[0].total_offset_ms, 1.0);
+    }
+
+    #[test]
+    fn test_anomalous_flag_is_stored_and_filterable() {
+        let db = Database::new_in_memory().unwrap();
+        let server = db.add_server("https://example.com").unwrap();
+        let base = Utc::now();
+        db.save_sync_result(&make_test_sync_result(server.id, 1.0, base))
+            .unwrap();
+        let flagged = db
+            .save_sync_result(&SyncResult {
+                anomalous: true,
+                ..make_test_sync_result(server.id, 900.0, base + Duration::seconds(1))
+            })
+            .unwrap();
+        assert!(db.get_sync_result(flagged).unwrap().anomalous);
+
+        let anomalies = SyncHistoryFilter {
+            anomalous: Some(true),
+            ..Default::default()
+        };
+        let page = db.get_sync_history_page(server.id, &anomalies, 0, 10).unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.results[0].id, flagged);
+    }
+
+    #[test]
+    fn test_network_context_round_trips() {
+        use crate::network_context::{InterfaceKind, NetworkContext};
+
+        let db = Database::new_in_memory().unwrap();
+        let server = db.add_server("https://example.com").unwrap();
+        let network = NetworkContext {
+            interface: Some("wlan0".to_string()),
+            kind: Some(InterfaceKind::Wifi),
+            vpn: true,
+            ssid_hash: Some("2f1c0a9e4b7d3a61".to_string()),
+        };
+        let id = db
+            .save_sync_result(&SyncResult {
+                network: Some(network.clone()),
+                ..make_test_sync_result(server.id, 1.0, Utc::now())
+            })
+            .unwrap();
+        assert_eq!(db.get_sync_result(id).unwrap().network, Some(network));
+    }
+
+    #[test]
+    fn test_sync_grade_is_stored() {
+        let db = Database::new_in_memory().unwrap();
+        let server = db.add_server("https://example.com").unwrap();
+        let graded = db
+            .save_sync_result(&SyncResult {
+                grade: Some(SyncGrade::B),
+                ..make_test_sync_result(server.id, 1.0, Utc::now())
+            })
+            .unwrap();
+        assert_eq!(db.get_sync_result(graded).unwrap().grade, Some(SyncGrade::B));
+        let ungraded = db
+            .save_sync_result(&make_test_sync_result(server.id, 1.0, Utc::now()))
+            .unwrap();
+        assert_eq!(db.get_sync_result(ungraded).unwrap().grade, None);
+    }
+
+    #[test]
+    fn test_sync_precision_is_stored() {
+        let db = Database::new_in_memory().unwrap();
+        let server = db.add_server("https://example.com").unwrap();
+        let id = db
+            .save_sync_result(&SyncResult {
+                precision_ms: Some(15.625),
+                ..make_test_sync_result(server.id, 1.0, Utc::now())
+            })
+            .unwrap();
+        assert_eq!(db.get_sync_result(id).unwrap().precision_ms, Some(15.625));
+    }
+
     #[test]
     fn test_get_sync_history_ordered_desc() {
         let db = Database::new_in_memory().unwrap();
@@ -613,6 +2176,541 @@ mod tests {
         assert_eq!(loaded.overlay_opacity, 80);
     }
 
+    #[test]
+    fn test_legacy_visual_alert_method_reads_as_notification() {
+        let db = Database::new_in_memory().unwrap();
+        let settings = AppSettings {
+            alert_method: "visual".to_string(),
+            ..AppSettings::default()
+        };
+        db.update_settings(&settings).unwrap();
+        assert_eq!(db.get_settings().unwrap().alert_method, "notification");
+    }
+
+    #[test]
+    fn test_local_api_settings_roundtrip() {
+        let db = Database::new_in_memory().unwrap();
+        let settings = AppSettings {
+            local_api_enabled: true,
+            local_api_port: 8123,
+            ..AppSettings::default()
+        };
+        db.update_settings(&settings).unwrap();
+
+        let loaded = db.get_settings().unwrap();
+        assert!(loaded.local_api_enabled);
+        assert_eq!(loaded.local_api_port, 8123);
+    }
+
+    #[test]
+    fn test_time_format_and_clock_adjustment_roundtrip() {
+        let db = Database::new_in_memory().unwrap();
+        let settings = AppSettings {
+            time_format: "%Y-%m-%d %H:%M:%S".to_string(),
+            allow_clock_adjustment: true,
+            record_syncs: true,
+            public_host_floor_ms: 1_000,
+            ..AppSettings::default()
+        };
+        db.update_settings(&settings).unwrap();
+
+        let loaded = db.get_settings().unwrap();
+        assert_eq!(loaded.time_format, "%Y-%m-%d %H:%M:%S");
+        assert!(loaded.allow_clock_adjustment);
+        assert!(loaded.record_syncs);
+        assert_eq!(loaded.public_host_floor_ms, 1_000);
+    }
+
+    #[test]
+    fn test_local_api_token_is_stored_outside_app_settings() {
+        let db = Database::new_in_memory().unwrap();
+        assert_eq!(db.get_local_api_token().unwrap(), None);
+
+        db.set_local_api_token("abc").unwrap();
+        db.update_settings(&AppSettings::default()).unwrap();
+        assert_eq!(db.get_local_api_token().unwrap().as_deref(), Some("abc"));
+    }
+
+    #[test]
+    fn test_reset_leaves_a_fresh_database() {
+        let db = Database::new_in_memory().unwrap();
+        let server = db.add_server("https://tickets.example.com").unwrap();
+        db.add_target(server.id, Utc::now(), Some("Presale")).unwrap();
+        db.set_local_api_token("abc").unwrap();
+        db.update_settings(&AppSettings {
+            public_host_floor_ms: 900,
+            ..AppSettings::default()
+        })
+        .unwrap();
+
+        db.reset().unwrap();
+        assert!(db.list_servers().unwrap().is_empty());
+        assert!(db.list_targets().unwrap().is_empty());
+        assert_eq!(db.get_local_api_token().unwrap(), None);
+        assert_eq!(db.get_settings().unwrap().public_host_floor_ms, 500);
+        // Migrated again, so it works as before.
+        db.add_server("https://tickets.example.com").unwrap();
+    }
+
+    #[test]
+    fn test_primary_server_defaults_to_none() {
+        let db = Database::new_in_memory().unwrap();
+        assert_eq!(db.get_primary_server().unwrap(), None);
+    }
+
+    #[test]
+    fn test_set_and_clear_primary_server() {
+        let db = Database::new_in_memory().unwrap();
+        let server = db.add_server("https://example.com").unwrap();
+        db.set_primary_server(Some(server.id)).unwrap();
+        assert_eq!(db.get_primary_server().unwrap(), Some(server.id));
+
+        db.set_primary_server(None).unwrap();
+        assert_eq!(db.get_primary_server().unwrap(), None);
+    }
+
+    #[test]
+    fn test_trashed_servers_hide_until_restored() {
+        let db = Database::new_in_memory().unwrap();
+        let server = db.add_server("https://tickets.example.com").unwrap();
+        db.add_target(server.id, Utc::now(), None).unwrap();
+        db.save_sync_result(&make_test_sync_result(server.id, 50.0, Utc::now()))
+            .unwrap();
+        db.set_primary_server(Some(server.id)).unwrap();
+
+        db.trash_server(server.id, Utc::now()).unwrap();
+        assert!(db.get_server(server.id).is_err());
+        assert!(db.list_servers().unwrap().is_empty());
+        assert!(db.list_targets().unwrap().is_empty());
+        assert_eq!(db.get_primary_server().unwrap(), None);
+        let week = chrono::Duration::days(7);
+        let trashed = db.list_trashed_servers(week).unwrap();
+        assert_eq!(trashed.len(), 1);
+        assert_eq!(trashed[0].purge_at - trashed[0].deleted_at, week);
+
+        db.restore_server(server.id).unwrap();
+        assert_eq!(db.list_targets().unwrap().len(), 1);
+        assert_eq!(db.get_primary_server().unwrap(), Some(server.id));
+        assert_eq!(db.get_sync_history(server.id, None, None).unwrap().len(), 1);
+        assert!(db.restore_server(server.id).is_err());
+    }
+
+    #[test]
+    fn test_restore_keeps_a_designation_taken_meanwhile() {
+        let db = Database::new_in_memory().unwrap();
+        let a = db.add_server("https://a.example.com").unwrap();
+        let b = db.add_server("https://b.example.com").unwrap();
+        db.set_primary_server(Some(a.id)).unwrap();
+        db.set_reference_server(Some(a.id)).unwrap();
+
+        db.trash_server(a.id, Utc::now()).unwrap();
+        db.set_primary_server(Some(b.id)).unwrap();
+        db.restore_server(a.id).unwrap();
+        assert_eq!(db.get_primary_server().unwrap(), Some(b.id));
+        assert_eq!(db.get_reference_server().unwrap(), Some(a.id));
+    }
+
+    #[test]
+    fn test_empty_trash_purges_only_expired_servers() {
+        let db = Database::new_in_memory().unwrap();
+        let old = db.add_server("https://old.example.com").unwrap();
+        let recent = db.add_server("https://recent.example.com").unwrap();
+        let (now, week) = (Utc::now(), chrono::Duration::days(7));
+        db.trash_server(old.id, now - week * 2).unwrap();
+        db.trash_server(recent.id, now).unwrap();
+
+        assert_eq!(db.empty_trash(Some(now - week)).unwrap(), 1);
+        assert_eq!(db.list_trashed_servers(week).unwrap().len(), 1);
+        assert_eq!(db.empty_trash(None).unwrap(), 1);
+        assert!(db.list_trashed_servers(week).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_adding_a_trashed_url_replaces_it() {
+        let db = Database::new_in_memory().unwrap();
+        let url = "https://tickets.example.com";
+        let server = db.add_server(url).unwrap();
+        db.trash_server(server.id, Utc::now()).unwrap();
+        assert!(db.find_server_by_url(url).unwrap().is_none());
+
+        let again = db.add_server(url).unwrap();
+        assert_ne!(again.id, server.id);
+        let trashed = db.list_trashed_servers(chrono::Duration::days(7)).unwrap();
+        assert!(trashed.is_empty());
+    }
+
+    #[test]
+    fn test_delete_server_clears_primary_designation() {
+        let db = Database::new_in_memory().unwrap();
+        let a = db.add_server("https://alpha.example.com").unwrap();
+        let b = db.add_server("https://beta.example.com").unwrap();
+        db.set_primary_server(Some(a.id)).unwrap();
+
+        db.delete_server(b.id).unwrap();
+        assert_eq!(db.get_primary_server().unwrap(), Some(a.id));
+
+        db.delete_server(a.id).unwrap();
+        assert_eq!(db.get_primary_server().unwrap(), None);
+    }
+
+    #[test]
+    fn test_error_history_newest_first_and_removed_with_server() {
+        let db = Database::new_in_memory().unwrap();
+        let server = db.add_server("https://flaky.example.com").unwrap();
+        let context = FailureContext {
+            phase: SyncPhase::BinarySearch,
+            phase_attempts: 4,
+            total_attempts: 12,
+            latency_profile: None,
+            partial_offset_ms: None,
+            elapsed_ms: 3_000,
+        };
+        let earlier = Utc::now() - chrono::Duration::minutes(5);
+        let no_date = AppError::NoDateHeader;
+        db.save_sync_error(
+            server.id,
+            no_date.code(),
+            &no_date.to_string(),
+            &context,
+            earlier,
+        )
+        .unwrap();
+        let retries = AppError::MaxRetriesExceeded(10);
+        db.save_sync_error(
+            server.id,
+            retries.code(),
+            &retries.to_string(),
+            &context,
+            Utc::now(),
+        )
+        .unwrap();
+
+        let history = db.get_error_history(server.id, None).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].code, ErrorCode::MaxRetriesExceeded);
+        assert_eq!(history[1].code, ErrorCode::NoDateHeader);
+        assert_eq!(history[1].phase, SyncPhase::BinarySearch);
+        assert_eq!(history[1].total_attempts, 12);
+        assert_eq!(db.get_error_history(server.id, Some(1)).unwrap().len(), 1);
+
+        db.delete_server(server.id).unwrap();
+        assert!(db.get_error_history(server.id, None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_delete_server_clears_reference_designation() {
+        let db = Database::new_in_memory().unwrap();
+        let a = db.add_server("https://alpha.example.com").unwrap();
+        let b = db.add_server("https://beta.example.com").unwrap();
+        db.set_primary_server(Some(a.id)).unwrap();
+        db.set_reference_server(Some(b.id)).unwrap();
+
+        db.delete_server(b.id).unwrap();
+        assert_eq!(db.get_reference_server().unwrap(), None);
+        assert_eq!(db.get_primary_server().unwrap(), Some(a.id));
+    }
+
+    #[test]
+    fn test_shift_server_offsets_skips_unsynced() {
+        let db = Database::new_in_memory().unwrap();
+        let synced = db.add_server("https://a.example.com").unwrap();
+        let unsynced = db.add_server("https://b.example.com").unwrap();
+        db.update_server_offset(synced.id, 250.0, Utc::now()).unwrap();
+
+        db.shift_server_offsets(-100.0).unwrap();
+        assert_eq!(db.get_server(synced.id).unwrap().offset_ms, Some(150.0));
+        assert_eq!(db.get_server(unsynced.id).unwrap().offset_ms, None);
+    }
+
+    #[test]
+    fn test_update_server_config() {
+        let db = Database::new_in_memory().unwrap();
+        let server = db.add_server("https://example.com").unwrap();
+        db.update_server_config(server.id, Some("Example"), "date_header")
+            .unwrap();
+
+        let loaded = db.get_server(server.id).unwrap();
+        assert_eq!(loaded.name.as_deref(), Some("Example"));
+        assert_eq!(loaded.extractor_type, "date_header");
+    }
+
+    #[test]
+    fn test_set_server_name_keeps_the_extractor() {
+        let db = Database::new_in_memory().unwrap();
+        let server = db.add_server("https://example.com").unwrap();
+        db.set_server_name(server.id, "Example").unwrap();
+
+        let loaded = db.get_server(server.id).unwrap();
+        assert_eq!(loaded.name.as_deref(), Some("Example"));
+        assert_eq!(loaded.extractor_type, server.extractor_type);
+    }
+
+    #[test]
+    fn test_server_and_result_asymmetry_round_trip() {
+        let db = Database::new_in_memory().unwrap();
+        let server = db.add_server("https://example.com").unwrap();
+        assert_eq!(server.asymmetry, None);
+
+        db.set_server_asymmetry(server.id, Some(0.7)).unwrap();
+        assert_eq!(db.get_server(server.id).unwrap().asymmetry, Some(0.7));
+
+        let result = SyncResult {
+            asymmetry: Some(0.7),
+            ..make_test_sync_result(server.id, 150.0, Utc::now())
+        };
+        let id = db.save_sync_result(&result).unwrap();
+        assert_eq!(db.get_sync_result(id).unwrap().asymmetry, Some(0.7));
+
+        db.set_server_asymmetry(server.id, None).unwrap();
+        assert_eq!(db.get_server(server.id).unwrap().asymmetry, None);
+    }
+
+    #[test]
+    fn test_sync_on_launch_flag() {
+        let db = Database::new_in_memory().unwrap();
+        let a = db.add_server("https://a.example.com").unwrap();
+        let b = db.add_server("https://b.example.com").unwrap();
+        assert!(!a.sync_on_launch);
+        assert!(db.list_sync_on_launch_servers().unwrap().is_empty());
+
+        db.set_server_sync_on_launch(b.id, true).unwrap();
+        assert!(db.get_server(b.id).unwrap().sync_on_launch);
+        let flagged = db.list_sync_on_launch_servers().unwrap();
+        assert_eq!(flagged.iter().map(|s| s.id).collect::<Vec<_>>(), vec![b.id]);
+    }
+
+    #[test]
+    fn test_set_server_trusted() {
+        let db = Database::new_in_memory().unwrap();
+        let server = db.add_server("https://time.internal.example").unwrap();
+        assert!(!server.trusted);
+        db.set_server_trusted(server.id, true).unwrap();
+        assert!(db.get_server(server.id).unwrap().trusted);
+    }
+
+    #[test]
+    fn test_drift_is_stored_with_the_server() {
+        let db = Database::new_in_memory().unwrap();
+        let server = db.add_server("https://example.com").unwrap();
+        let base = Utc::now();
+        for h in 0..4i64 {
+            let r =
+                make_test_sync_result(server.id, 100.0 + 4.0 * h as f64, base + Duration::hours(h));
+            db.save_sync_result(&r).unwrap();
+        }
+        let drift = crate::drift::refresh(&db, server.id).unwrap();
+        assert_eq!(db.get_server(server.id).unwrap().drift, drift);
+        assert!((drift.unwrap().rate_ms_per_hour - 4.0).abs() < 1e-6);
+
+        db.set_server_drift(server.id, None).unwrap();
+        assert_eq!(db.get_server(server.id).unwrap().drift, None);
+
+        // Stepping the local clock drops the fit made against it.
+        db.update_server_offset(server.id, 112.0, base).unwrap();
+        crate::drift::refresh(&db, server.id).unwrap();
+        db.shift_server_offsets(-100.0).unwrap();
+        assert_eq!(db.get_server(server.id).unwrap().drift, None);
+    }
+
+    #[test]
+    fn test_favicon_round_trip_and_purge() {
+        let db = Database::new_in_memory().unwrap();
+        let server = db.add_server("https://example.com").unwrap();
+        assert_eq!(db.get_favicon(server.id).unwrap(), None);
+
+        let favicon = Favicon {
+            content_type: "image/png".to_string(),
+            data: b"\x89PNG\r\n\x1a\n".to_vec(),
+            fetched_at: "2025-06-01T00:00:00Z".parse().unwrap(),
+        };
+        db.save_favicon(server.id, &favicon).unwrap();
+        assert_eq!(db.get_favicon(server.id).unwrap(), Some(favicon));
+
+        db.delete_server(server.id).unwrap();
+        assert_eq!(db.get_favicon(server.id).unwrap(), None);
+    }
+
+    #[test]
+    fn test_server_timezone_guess_and_override() {
+        let db = Database::new_in_memory().unwrap();
+        let server = db.add_server("https://tickets.example.jp").unwrap();
+        let loaded = db.get_server(server.id).unwrap();
+        assert_eq!(loaded.timezone.as_deref(), Some("Asia/Tokyo"));
+        assert!(loaded.timezone_guessed);
+
+        db.set_server_timezone(server.id, Some("Asia/Seoul")).unwrap();
+        let loaded = db.get_server(server.id).unwrap();
+        assert_eq!(loaded.timezone.as_deref(), Some("Asia/Seoul"));
+        assert!(!loaded.timezone_guessed);
+    }
+
+    #[test]
+    fn test_set_server_sync_profile() {
+        let db = Database::new_in_memory().unwrap();
+        let server = db.add_server("https://example.com").unwrap();
+        assert_eq!(server.sync_profile, None);
+
+        db.set_server_sync_profile(server.id, Some("polite")).unwrap();
+        assert_eq!(
+            db.get_server(server.id).unwrap().sync_profile.as_deref(),
+            Some("polite")
+        );
+        db.set_server_sync_profile(server.id, None).unwrap();
+        assert_eq!(db.get_server(server.id).unwrap().sync_profile, None);
+    }
+
+    #[test]
+    fn test_migrations_add_columns_to_old_servers_table() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE servers (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                url TEXT NOT NULL UNIQUE,
+                name TEXT,
+                offset_ms REAL,
+                last_sync_at TEXT,
+                created_at TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'idle',
+                extractor_type TEXT NOT NULL DEFAULT 'date_header'
+            );
+            INSERT INTO servers (url, created_at) VALUES ('https://old.example.com', '2025-01-01T00:00:00Z');",
+        )
+        .unwrap();
+        let db = Database {
+            conn: Mutex::new(conn),
+        };
+        db.run_migrations().unwrap();
+        // Running again must not try to re-add the column.
+        db.run_migrations().unwrap();
+
+        let servers = db.list_servers().unwrap();
+        assert_eq!(servers[0].url, "https://old.example.com");
+        assert_eq!(servers[0].sync_profile, None);
+        assert_eq!(servers[0].probe_method, "HEAD");
+        assert_eq!(servers[0].domain_preset, None);
+    }
+
+    #[test]
+    fn test_find_server_by_url() {
+        let db = Database::new_in_memory().unwrap();
+        let server = db.add_server("https://example.com").unwrap();
+        let found = db.find_server_by_url("https://example.com").unwrap();
+        assert_eq!(found.map(|s| s.id), Some(server.id));
+        assert!(db
+            .find_server_by_url("https://other.example.com")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_add_servers_in_one_transaction() {
+        let db = Database::new_in_memory().unwrap();
+        let added = db
+            .add_servers(&[
+                NewServer::new("https://a.example.com"),
+                NewServer::new("https://b.example.com"),
+            ])
+            .unwrap();
+        assert_eq!(added.len(), 2);
+        assert_eq!(added[1].url, "https://b.example.com");
+        assert_eq!(added[1].extractor_type, "date_header");
+        assert_eq!(db.list_servers().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_add_and_list_targets_ordered_by_time() {
+        let db = Database::new_in_memory().unwrap();
+        let server = db.add_server("https://example.com").unwrap();
+        let later = Utc::now() + Duration::hours(2);
+        let sooner = Utc::now() + Duration::hours(1);
+        db.add_target(server.id, later, Some("Presale")).unwrap();
+        let first = db.add_target(server.id, sooner, None).unwrap();
+
+        let targets = db.list_targets().unwrap();
+        assert_eq!(targets.len(), 2);
+        assert_eq!(targets[0].id, first.id);
+        assert!(targets[0].armed);
+        assert_eq!(targets[1].label.as_deref(), Some("Presale"));
+    }
+
+    #[test]
+    fn test_get_target_by_id() {
+        let db = Database::new_in_memory().unwrap();
+        let server = db.add_server("https://example.com").unwrap();
+        let target = db.add_target(server.id, Utc::now(), Some("Drop")).unwrap();
+
+        let loaded = db.get_target(target.id).unwrap();
+        assert_eq!(loaded.server_id, server.id);
+        assert_eq!(loaded.label.as_deref(), Some("Drop"));
+        assert!(db.get_target(target.id + 1).is_err());
+    }
+
+    #[test]
+    fn test_target_alert_method_override() {
+        let db = Database::new_in_memory().unwrap();
+        let server = db.add_server("https://example.com").unwrap();
+        let target = db.add_target(server.id, Utc::now(), None).unwrap();
+        assert_eq!(target.alert_method, None);
+
+        db.set_target_alert_method(target.id, Some("speak")).unwrap();
+        assert_eq!(
+            db.get_target(target.id).unwrap().alert_method.as_deref(),
+            Some("speak")
+        );
+        db.set_target_alert_method(target.id, None).unwrap();
+        assert_eq!(db.get_target(target.id).unwrap().alert_method, None);
+        assert!(db.set_target_alert_method(target.id + 1, None).is_err());
+    }
+
+    #[test]
+    fn test_set_target_armed() {
+        let db = Database::new_in_memory().unwrap();
+        let server = db.add_server("https://example.com").unwrap();
+        let target = db.add_target(server.id, Utc::now(), None).unwrap();
+
+        db.set_target_armed(target.id, false).unwrap();
+        assert!(!db.get_target(target.id).unwrap().armed);
+        db.set_target_armed(target.id, true).unwrap();
+        assert!(db.get_target(target.id).unwrap().armed);
+        assert!(db.set_target_armed(target.id + 1, true).is_err());
+    }
+
+    #[test]
+    fn test_delete_server_removes_its_targets() {
+        let db = Database::new_in_memory().unwrap();
+        let server = db.add_server("https://example.com").unwrap();
+        let target = db.add_target(server.id, Utc::now(), None).unwrap();
+        db.delete_target(target.id).unwrap();
+        db.add_target(server.id, Utc::now(), None).unwrap();
+
+        db.delete_server(server.id).unwrap();
+        assert!(db.list_targets().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_scheduled_syncs_persist_until_deleted() {
+        let db = Database::new_in_memory().unwrap();
+        let server = db.add_server("https://example.com").unwrap();
+        let now = Utc::now();
+        let later = db
+            .add_scheduled_sync(server.id, now + chrono::Duration::hours(2))
+            .unwrap();
+        let sooner = db
+            .add_scheduled_sync(server.id, now + chrono::Duration::hours(1))
+            .unwrap();
+
+        let pending = db.list_scheduled_syncs().unwrap();
+        assert_eq!(
+            pending.iter().map(|s| s.id).collect::<Vec<_>>(),
+            vec![sooner.id, later.id]
+        );
+
+        db.delete_scheduled_sync(sooner.id).unwrap();
+        assert!(db.delete_scheduled_sync(sooner.id).is_err());
+        db.delete_server(server.id).unwrap();
+        assert!(db.list_scheduled_syncs().unwrap().is_empty());
+    }
+
     #[test]
     fn test_delete_server_cascades_sync_results() {
         let db = Database::new_in_memory().unwrap();
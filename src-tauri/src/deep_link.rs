@@ -0,0 +1,203 @@
+use crate::error::AppError;
+use crate::models::{Server, Target};
+use crate::server_timezone;
+use crate::server_url;
+use crate::state::AppState;
+use crate::target_time::TargetTime;
+use crate::tray;
+use chrono::Utc;
+use reqwest::Url;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+pub const SCHEME: &str = "ticketime";
+pub const DEEP_LINK_EVENT: &str = "deep-link";
+
+/// What a `ticketime://` link asks us to do.
+#[derive(Debug, PartialEq)]
+pub enum DeepLinkAction {
+    /// `ticketime://add?url=<server>[&target=<time>][&label=<text>]`, the
+    /// time as [`TargetTime`] reads it, e.g. `10:00 server-local`.
+    Add {
+        url: String,
+        target_at: Option<TargetTime>,
+        label: Option<String>,
+    },
+}
+
+/// Emitted after a link has been applied so the UI can refresh.
+#[derive(Debug, Clone, Serialize)]
+pub struct DeepLinkPayload {
+    pub server: Server,
+    pub target: Option<Target>,
+}
+
+pub fn parse(url: &Url) -> Result<DeepLinkAction, AppError> {
+    if url.scheme() != SCHEME {
+        return Err(AppError::InvalidDeepLink(format!(
+            "unsupported scheme: {}",
+            url.scheme()
+        )));
+    }
+
+    // `ticketime://add` puts the action in the host; `ticketime:add` in the path.
+    let action = url
+        .host_str()
+        .unwrap_or_else(|| url.path().trim_matches('/'));
+
+    match action {
+        "add" => {
+            let mut server_url = None;
+            let mut target_at = None;
+            let mut label = None;
+            for (key, value) in url.query_pairs() {
+                match key.as_ref() {
+                    "url" => server_url = Some(value.into_owned()),
+                    "target" => {
+                        let parsed = value
+                            .parse::<TargetTime>()
+                            .map_err(|e| AppError::InvalidDeepLink(e.to_string()))?;
+                        target_at = Some(parsed);
+                    }
+                    "label" => label = Some(value.into_owned()),
+                    _ => {}
+                }
+            }
+            let url = server_url
+                .filter(|u| !u.is_empty())
+                .ok_or_else(|| AppError::InvalidDeepLink("missing url parameter".to_string()))?;
+            Ok(DeepLinkAction::Add {
+                url,
+                target_at,
+                label,
+            })
+        }
+        other => Err(AppError::InvalidDeepLink(format!(
+            "unknown action: {other}"
+        ))),
+    }
+}
+
+/// Apply a link: parse it, run the action, and tell the frontend.
+pub fn handle(app: &AppHandle, url: &Url) {
+    let result = parse(url).and_then(|action| apply(app, action));
+    match result {
+        Ok(payload) => {
+            tray::refresh_menu(app);
+            tray::show_main_window(app);
+            if let Err(e) = app.emit(DEEP_LINK_EVENT, payload) {
+                log::warn!("failed to emit deep link event: {e}");
+            }
+        }
+        Err(e) => log::warn!("ignoring deep link {url}: {e}"),
+    }
+}
+
+fn apply(app: &AppHandle, action: DeepLinkAction) -> Result<DeepLinkPayload, AppError> {
+    let state = app.state::<AppState>();
+    match action {
+        DeepLinkAction::Add {
+            url,
+            target_at,
+            label,
+        } => {
+            let url = server_url::normalize(&url)?;
+            // Reuse an existing server so repeated links only add targets.
+            let server = match server_url::find_existing(&state.db, &url)? {
+                Some(server) => server,
+                None => state.db.add_server(&url)?,
+            };
+            let target = target_at
+                .map(|at| {
+                    let at = at.resolve(server_timezone::of(&server), Utc::now())?;
+                    state.db.add_target(server.id, at, label.as_deref())
+                })
+                .transpose()?;
+            Ok(DeepLinkPayload { server, target })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_str(s: &str) -> Result<DeepLinkAction, AppError> {
+        parse(&Url::parse(s).unwrap())
+    }
+
+    #[test]
+    fn parse_add_with_target() {
+        let action = parse_str(
+            "ticketime://add?url=https%3A%2F%2Ftickets.example.com&target=2025-06-01T10:00:00Z",
+        )
+        .unwrap();
+        assert_eq!(
+            action,
+            DeepLinkAction::Add {
+                url: "https://tickets.example.com".to_string(),
+                target_at: Some(TargetTime::Instant("2025-06-01T10:00:00Z".parse().unwrap())),
+                label: None,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_add_accepts_unencoded_url_and_label() {
+        let action =
+            parse_str("ticketime://add?url=https://tickets.example.com&label=Presale").unwrap();
+        assert_eq!(
+            action,
+            DeepLinkAction::Add {
+                url: "https://tickets.example.com".to_string(),
+                target_at: None,
+                label: Some("Presale".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_target_with_offset_converts_to_utc() {
+        let action =
+            parse_str("ticketime://add?url=https://example.com&target=2025-06-01T19:00:00%2B09:00")
+                .unwrap();
+        let DeepLinkAction::Add { target_at, .. } = action;
+        assert_eq!(
+            target_at,
+            Some(TargetTime::Instant("2025-06-01T10:00:00Z".parse().unwrap()))
+        );
+    }
+
+    #[test]
+    fn parse_target_accepts_server_local_times() {
+        let action =
+            parse_str("ticketime://add?url=https://example.jp&target=10:00%20server-local")
+                .unwrap();
+        let DeepLinkAction::Add { target_at, .. } = action;
+        assert!(matches!(
+            target_at,
+            Some(TargetTime::Wall {
+                zone: crate::target_time::WallZone::Server,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn parse_rejects_missing_url() {
+        let err = parse_str("ticketime://add?target=2025-06-01T10:00:00Z").unwrap_err();
+        assert!(matches!(err, AppError::InvalidDeepLink(_)));
+    }
+
+    #[test]
+    fn parse_rejects_bad_target_time() {
+        let err = parse_str("ticketime://add?url=https://example.com&target=tomorrow").unwrap_err();
+        assert!(err.to_string().contains("invalid target time"));
+    }
+
+    #[test]
+    fn parse_rejects_unknown_action_and_scheme() {
+        assert!(parse_str("ticketime://remove?url=https://example.com").is_err());
+        assert!(parse_str("https://add?url=https://example.com").is_err());
+    }
+}
@@ -0,0 +1,153 @@
+use crate::error::AppError;
+use serde::Serialize;
+
+pub const HEAD: &str = "HEAD";
+pub const GET: &str = "GET";
+
+/// Recommended probe settings for a known ticketing platform, applied when
+/// a matching server is added.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct DomainPreset {
+    pub id: &'static str,
+    pub label: &'static str,
+    /// Registrable domains; subdomains match too.
+    pub hosts: &'static [&'static str],
+    pub extractor_type: &'static str,
+    /// Replaces the path of a bare-domain URL. Static, uncached-by-origin
+    /// paths answer faster than a full storefront page.
+    pub probe_path: Option<&'static str>,
+    /// `GET` where the platform's bot protection rejects `HEAD`.
+    pub probe_method: &'static str,
+}
+
+pub const PRESETS: [DomainPreset; 6] = [
+    DomainPreset {
+        id: "ticketmaster",
+        label: "Ticketmaster / Live Nation",
+        hosts: &[
+            "ticketmaster.com",
+            "ticketmaster.co.uk",
+            "ticketmaster.de",
+            "ticketmaster.ca",
+            "ticketmaster.com.au",
+            "livenation.com",
+        ],
+        extractor_type: "date_header",
+        probe_path: Some("/robots.txt"),
+        probe_method: GET,
+    },
+    DomainPreset {
+        id: "eventim",
+        label: "Eventim",
+        hosts: &["eventim.de", "eventim.com", "eventim.co.uk", "oeticket.com"],
+        extractor_type: "date_header",
+        probe_path: Some("/robots.txt"),
+        probe_method: GET,
+    },
+    DomainPreset {
+        id: "axs",
+        label: "AXS",
+        hosts: &["axs.com", "axs.co.uk"],
+        extractor_type: "date_header",
+        probe_path: Some("/robots.txt"),
+        probe_method: GET,
+    },
+    DomainPreset {
+        id: "queue_it",
+        label: "Queue-it waiting room",
+        hosts: &["queue-it.net"],
+        extractor_type: "date_header",
+        probe_path: None,
+        probe_method: HEAD,
+    },
+    DomainPreset {
+        id: "see_tickets",
+        label: "See Tickets",
+        hosts: &["seetickets.com", "seetickets.us"],
+        extractor_type: "date_header",
+        probe_path: Some("/robots.txt"),
+        probe_method: HEAD,
+    },
+    DomainPreset {
+        id: "dice",
+        label: "DICE",
+        hosts: &["dice.fm"],
+        extractor_type: "date_header",
+        probe_path: None,
+        probe_method: HEAD,
+    },
+];
+
+pub fn find(id: &str) -> Result<DomainPreset, AppError> {
+    PRESETS
+        .into_iter()
+        .find(|p| p.id == id)
+        .ok_or_else(|| AppError::UnknownDomainPreset(id.to_string()))
+}
+
+/// The preset whose hosts cover `url`'s host, if any.
+pub fn for_url(url: &str) -> Option<DomainPreset> {
+    let parsed = reqwest::Url::parse(url).ok()?;
+    let host = parsed.host_str()?.to_ascii_lowercase();
+    PRESETS.into_iter().find(|p| {
+        p.hosts.iter().any(|h| {
+            host == *h
+                || host
+                    .strip_suffix(h)
+                    .is_some_and(|prefix| prefix.ends_with('.'))
+        })
+    })
+}
+
+/// `url` with the preset's probe path, if the user gave a bare domain.
+/// An explicit path is always kept.
+pub fn apply_path(url: &str, preset: &DomainPreset) -> String {
+    let Some(path) = preset.probe_path else {
+        return url.to_string();
+    };
+    match reqwest::Url::parse(url) {
+        Ok(mut parsed) if parsed.path() == "/" && parsed.query().is_none() => {
+            parsed.set_path(path);
+            parsed.to_string()
+        }
+        _ => url.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_host_and_subdomains_only() {
+        let id = |url: &str| for_url(url).map(|p| p.id);
+        assert_eq!(id("https://www.ticketmaster.com/"), Some("ticketmaster"));
+        assert_eq!(id("https://ticketmaster.co.uk"), Some("ticketmaster"));
+        assert_eq!(id("https://shop.queue-it.net/?c=x"), Some("queue_it"));
+        assert_eq!(id("https://notticketmaster.com"), None);
+        assert_eq!(id("https://example.com"), None);
+    }
+
+    #[test]
+    fn probe_path_replaces_bare_domain_only() {
+        let tm = find("ticketmaster").unwrap();
+        assert_eq!(
+            apply_path("https://www.ticketmaster.com", &tm),
+            "https://www.ticketmaster.com/robots.txt"
+        );
+        assert_eq!(
+            apply_path("https://www.ticketmaster.com/event/123", &tm),
+            "https://www.ticketmaster.com/event/123"
+        );
+        let dice = find("dice").unwrap();
+        assert_eq!(apply_path("https://dice.fm/", &dice), "https://dice.fm/");
+    }
+
+    #[test]
+    fn find_rejects_unknown_ids() {
+        assert!(matches!(
+            find("nope"),
+            Err(AppError::UnknownDomainPreset(_))
+        ));
+    }
+}
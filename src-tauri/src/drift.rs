@@ -0,0 +1,269 @@
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::{Server, SyncResult};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// How many recent syncs feed the drift fit.
+pub const HISTORY_WINDOW: i64 = 20;
+
+const MIN_SAMPLES: usize = 3;
+/// Syncs closer together than this can't separate drift from measurement
+/// noise.
+const MIN_SPAN_HOURS: f64 = 10.0 / 60.0;
+
+/// How fast a server's offset moves against the local clock. Stored with
+/// the server, so a corrected time can be extrapolated right after launch.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DriftEstimate {
+    pub rate_ms_per_hour: f64,
+    pub samples: usize,
+    /// Scatter of the offsets around the fitted line: the noise of one
+    /// sync.
+    #[serde(default)]
+    pub residual_ms: f64,
+    /// Standard error of `rate_ms_per_hour`.
+    #[serde(default)]
+    pub rate_error_ms_per_hour: f64,
+}
+
+/// Least-squares slope of offset over time. `None` when the history is too
+/// short or too bunched up to say anything.
+pub fn estimate(history: &[SyncResult]) -> Option<DriftEstimate> {
+    if history.len() < MIN_SAMPLES {
+        return None;
+    }
+    let origin = history.iter().map(|r| r.synced_at).min()?;
+    let points: Vec<(f64, f64)> = history
+        .iter()
+        .map(|r| (hours_between(origin, r.synced_at), r.total_offset_ms))
+        .collect();
+
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let span = points.iter().map(|(x, _)| *x).fold(0.0, f64::max);
+    if span < MIN_SPAN_HOURS {
+        return None;
+    }
+
+    let (cov, var) = points.iter().fold((0.0, 0.0), |(cov, var), (x, y)| {
+        let dx = x - mean_x;
+        (cov + dx * (y - mean_y), var + dx * dx)
+    });
+    let rate = cov / var;
+    let squared_error: f64 = points
+        .iter()
+        .map(|(x, y)| (y - mean_y - rate * (x - mean_x)).powi(2))
+        .sum();
+    let residual_ms = (squared_error / (n - 2.0)).sqrt();
+    Some(DriftEstimate {
+        rate_ms_per_hour: rate,
+        samples: history.len(),
+        residual_ms,
+        rate_error_ms_per_hour: residual_ms / var.sqrt(),
+    })
+}
+
+/// Refit the server's drift on its recent syncs and store it.
+pub fn refresh(db: &Database, server_id: i64) -> Result<Option<DriftEstimate>, AppError> {
+    let drift = estimate(&db.get_sync_history(server_id, None, Some(HISTORY_WINDOW))?);
+    db.set_server_drift(server_id, drift.as_ref())?;
+    Ok(drift)
+}
+
+/// The stored offset carried forward from `last_sync_at` to `now` along the
+/// drift estimate; unchanged without one.
+pub fn extrapolate(
+    offset_ms: f64,
+    last_sync_at: Option<DateTime<Utc>>,
+    drift: Option<&DriftEstimate>,
+    now: DateTime<Utc>,
+) -> f64 {
+    match (last_sync_at, drift) {
+        (Some(at), Some(drift)) => {
+            offset_ms + drift.rate_ms_per_hour * hours_between(at, now).max(0.0)
+        }
+        _ => offset_ms,
+    }
+}
+
+/// The server's stored drift, unless the `extrapolate_drift` setting is off
+/// and offsets are to be taken as measured.
+pub fn applied(server: &Server, extrapolate_drift: bool) -> Option<&DriftEstimate> {
+    server.drift.as_ref().filter(|_| extrapolate_drift)
+}
+
+/// The server's offset at `now`: the stored one, carried forward along its
+/// drift when `extrapolate_drift` allows. `None` until it has been synced.
+pub fn offset_at(server: &Server, now: DateTime<Utc>, extrapolate_drift: bool) -> Option<f64> {
+    Some(extrapolate(
+        server.offset_ms?,
+        server.last_sync_at,
+        applied(server, extrapolate_drift),
+        now,
+    ))
+}
+
+/// Fit a drift for synced servers stored without one, such as those synced
+/// before fits were kept with the server.
+pub fn backfill(db: &Database) -> Result<(), AppError> {
+    for server in db.list_servers()? {
+        if server.offset_ms.is_some() && server.drift.is_none() {
+            refresh(db, server.id)?;
+        }
+    }
+    Ok(())
+}
+
+/// One standard error of an offset extrapolated with [`extrapolate`]: a
+/// sync's own noise, plus the rate's error over the hours since the sync.
+pub fn uncertainty_ms(
+    drift: &DriftEstimate,
+    last_sync_at: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> f64 {
+    let hours = hours_between(last_sync_at, now).max(0.0);
+    drift
+        .residual_ms
+        .hypot(drift.rate_error_ms_per_hour * hours)
+}
+
+/// A sync whose offset moved further from the previous sync's than the
+/// `drift_warning_threshold_ms` setting allows.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DriftWarning {
+    pub server_id: i64,
+    pub previous_offset_ms: f64,
+    pub offset_ms: f64,
+    pub moved_ms: f64,
+    pub threshold_ms: u32,
+}
+
+/// `None` for a first sync or a move within `threshold_ms`.
+pub fn warning(
+    server_id: i64,
+    previous_offset_ms: Option<f64>,
+    offset_ms: f64,
+    threshold_ms: u32,
+) -> Option<DriftWarning> {
+    let previous_offset_ms = previous_offset_ms?;
+    let moved_ms = offset_ms - previous_offset_ms;
+    (moved_ms.abs() > f64::from(threshold_ms)).then_some(DriftWarning {
+        server_id,
+        previous_offset_ms,
+        offset_ms,
+        moved_ms,
+        threshold_ms,
+    })
+}
+
+fn hours_between(from: DateTime<Utc>, to: DateTime<Utc>) -> f64 {
+    (to - from).num_milliseconds() as f64 / 3_600_000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{LatencyProfile, SyncPhase};
+    use chrono::Duration;
+
+    fn result(offset_ms: f64, synced_at: DateTime<Utc>) -> SyncResult {
+        SyncResult {
+            server_id: 1,
+            whole_second_offset: 0,
+            subsecond_offset: 0.0,
+            total_offset_ms: offset_ms,
+            latency_profile: LatencyProfile {
+                min: 0.0,
+                q1: 0.0,
+                median: 0.0,
+                mean: 0.0,
+                q3: 0.0,
+                max: 0.0,
+                breakdown: None,
+            },
+            verified: true,
+            synced_at,
+            duration_ms: 0,
+            phase_reached: SyncPhase::Complete,
+            fingerprint: None,
+            asymmetry: None,
+            anomalous: false,
+            clock_issue: None,
+            network: None,
+            grade: None,
+            precision_ms: None,
+            response: None,
+            id: 0,
+            timeline: Vec::new(),
+            note: None,
+        }
+    }
+
+    #[test]
+    fn estimate_fits_linear_drift() {
+        let t0 = Utc::now();
+        let history: Vec<_> = (0..4)
+            .map(|h| result(100.0 + 5.0 * h as f64, t0 + Duration::hours(h)))
+            .collect();
+        let drift = estimate(&history).unwrap();
+        assert!((drift.rate_ms_per_hour - 5.0).abs() < 1e-9);
+        assert_eq!(drift.samples, 4);
+        assert!(drift.residual_ms < 1e-9);
+    }
+
+    #[test]
+    fn uncertainty_grows_with_time_since_the_sync() {
+        let t0 = Utc::now();
+        let noise = [0.0, 2.0, -2.0, 0.0, 2.0, -2.0];
+        let history: Vec<_> = noise
+            .iter()
+            .enumerate()
+            .map(|(h, n)| result(100.0 + 5.0 * h as f64 + n, t0 + Duration::hours(h as i64)))
+            .collect();
+        let drift = estimate(&history).unwrap();
+        assert!(drift.residual_ms > 1.0 && drift.rate_error_ms_per_hour > 0.0);
+
+        let last = t0 + Duration::hours(5);
+        let fresh = uncertainty_ms(&drift, last, last);
+        assert!((fresh - drift.residual_ms).abs() < 1e-9);
+        assert!(uncertainty_ms(&drift, last, last + Duration::hours(24)) > fresh);
+    }
+
+    #[test]
+    fn estimate_needs_enough_spread_out_samples() {
+        let t0 = Utc::now();
+        let two = [result(0.0, t0), result(1.0, t0 + Duration::hours(1))];
+        assert_eq!(estimate(&two), None);
+
+        let bunched: Vec<_> = (0..5)
+            .map(|s| result(s as f64, t0 + Duration::seconds(s)))
+            .collect();
+        assert_eq!(estimate(&bunched), None);
+    }
+
+    #[test]
+    fn extrapolate_applies_rate_since_last_sync() {
+        let at = Utc::now();
+        let drift = DriftEstimate {
+            rate_ms_per_hour: -12.0,
+            samples: 5,
+            residual_ms: 0.0,
+            rate_error_ms_per_hour: 0.0,
+        };
+        let now = at + Duration::minutes(30);
+        assert_eq!(extrapolate(200.0, Some(at), Some(&drift), now), 194.0);
+        assert_eq!(extrapolate(200.0, Some(at), None, now), 200.0);
+        assert_eq!(extrapolate(200.0, None, Some(&drift), now), 200.0);
+    }
+
+    #[test]
+    fn warning_only_for_moves_past_the_threshold() {
+        assert_eq!(warning(1, None, 5_000.0, 1000), None);
+        assert_eq!(warning(1, Some(100.0), 1_050.0, 1000), None);
+        let moved = warning(1, Some(100.0), -1_000.0, 1000).unwrap();
+        assert_eq!(moved.moved_ms, -1_100.0);
+        assert_eq!(moved.previous_offset_ms, 100.0);
+    }
+}
@@ -1,4 +1,5 @@
-use serde::Serialize;
+use crate::settings_validation::FieldError;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, thiserror::Error)]
 pub enum AppError {
@@ -6,6 +7,8 @@ pub enum AppError {
     Db(#[from] rusqlite::Error),
     #[error(transparent)]
     Http(#[from] reqwest::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
     #[error("server returned no Date header")]
     NoDateHeader,
     #[error("invalid Date header format: {0}")]
@@ -14,8 +17,227 @@ pub enum AppError {
     Cancelled,
     #[error("max retries exceeded ({0} attempts)")]
     MaxRetriesExceeded(u32),
+    #[error("response came from a cache (Age: {0}s)")]
+    CachedResponse(u64),
     #[error("invalid URL: {0}")]
     InvalidUrl(String),
+    #[error("invalid deep link: {0}")]
+    InvalidDeepLink(String),
+    #[error("invalid time format: {0}")]
+    InvalidTimeFormat(String),
+    #[error("unknown timezone: {0}")]
+    InvalidTimezone(String),
+    #[error("invalid target time: {0}")]
+    InvalidTargetTime(String),
+    #[error("invalid settings file: {0}")]
+    InvalidSettingsFile(String),
+    #[error("unknown sync profile: {0}")]
+    UnknownSyncProfile(String),
+    #[error("unknown domain preset: {0}")]
+    UnknownDomainPreset(String),
+    #[error("unknown extractor: {0}")]
+    UnknownExtractor(String),
+    #[error("extractor plugin failed: {0}")]
+    Plugin(String),
+    #[error("no time in the response body: {0}")]
+    InvalidTimeResponse(String),
+    #[error("sync replay failed: {0}")]
+    Replay(String),
+    #[error("server {0} has not been synced yet")]
+    NotSynced(i64),
+    #[error("NTP query failed: {0}")]
+    Ntp(String),
+    #[error("system clock adjustment is disabled in settings")]
+    ClockAdjustmentDisabled,
+    #[error("clock error changed since confirmation (confirmed {confirmed:.0} ms, now {measured:.0} ms)")]
+    ClockErrorChanged { confirmed: f64, measured: f64 },
+    #[error("adjusting the system clock requires administrator privileges")]
+    ClockPermissionDenied,
+    #[error("notification failed: {0}")]
+    Notification(String),
+    #[error("asymmetry calibration failed: {0}")]
+    CalibrationFailed(String),
+    #[error("no network connection")]
+    Offline,
+    #[error("invalid share payload: {0}")]
+    InvalidSharePayload(String),
+    #[error("sync stalled: no progress for {0} s")]
+    SyncStalled(u64),
+    #[error("sync ran past its {0} s deadline")]
+    SyncDeadlineExceeded(u64),
+    #[error("too close to the target to recheck the offset")]
+    RecheckTooLate,
+    #[error("the local network looks degraded ({0}); try syncing later")]
+    NetworkDegraded(String),
+    #[error("probes are not reaching the server: {0}")]
+    Intercepted(String),
+    #[error("this export is encrypted; enter its passphrase to open it")]
+    PassphraseRequired,
+    #[error("could not decrypt the export: wrong passphrase or damaged file")]
+    DecryptionFailed,
+    #[error("no external time source is configured")]
+    TimeSourceDisabled,
+    #[error("{url} is already added (server {id})")]
+    DuplicateServer { id: i64, url: String },
+    #[error("invalid settings: {}", describe_fields(.0))]
+    Validation(Vec<FieldError>),
+}
+
+fn describe_fields(errors: &[FieldError]) -> String {
+    errors
+        .iter()
+        .map(|e| format!("{} {}", e.field, e.message))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Stable identifier for each error kind, so the frontend can branch on
+/// `code` instead of matching message text. Never rename a variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    NotFound,
+    Database,
+    Network,
+    Io,
+    NoDateHeader,
+    InvalidDateHeader,
+    Cancelled,
+    MaxRetriesExceeded,
+    CachedResponse,
+    InvalidUrl,
+    InvalidDeepLink,
+    InvalidTimeFormat,
+    InvalidTimezone,
+    InvalidTargetTime,
+    InvalidSettingsFile,
+    UnknownSyncProfile,
+    UnknownDomainPreset,
+    UnknownExtractor,
+    Plugin,
+    InvalidTimeResponse,
+    Replay,
+    NotSynced,
+    Ntp,
+    ClockAdjustmentDisabled,
+    ClockErrorChanged,
+    ClockPermissionDenied,
+    Notification,
+    CalibrationFailed,
+    Offline,
+    InvalidSharePayload,
+    SyncStalled,
+    SyncDeadlineExceeded,
+    RecheckTooLate,
+    NetworkDegraded,
+    Intercepted,
+    PassphraseRequired,
+    DecryptionFailed,
+    DuplicateServer,
+    TimeSourceDisabled,
+    Validation,
+}
+
+/// What an `AppError` looks like on the wire. `Clone`, unlike `AppError`,
+/// so it can ride along in events.
+#[derive(Debug, Clone, Serialize)]
+pub struct ErrorPayload {
+    pub code: ErrorCode,
+    pub message: String,
+    /// Whether trying the same thing again may succeed.
+    pub retryable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fields: Option<Vec<FieldError>>,
+    /// The server that already exists, for `DuplicateServer`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_id: Option<i64>,
+}
+
+impl AppError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            AppError::Db(rusqlite::Error::QueryReturnedNoRows) => ErrorCode::NotFound,
+            AppError::Db(_) => ErrorCode::Database,
+            AppError::Http(_) => ErrorCode::Network,
+            AppError::Io(_) => ErrorCode::Io,
+            AppError::NoDateHeader => ErrorCode::NoDateHeader,
+            AppError::InvalidDateHeader(_) => ErrorCode::InvalidDateHeader,
+            AppError::Cancelled => ErrorCode::Cancelled,
+            AppError::MaxRetriesExceeded(_) => ErrorCode::MaxRetriesExceeded,
+            AppError::CachedResponse(_) => ErrorCode::CachedResponse,
+            AppError::InvalidUrl(_) => ErrorCode::InvalidUrl,
+            AppError::InvalidDeepLink(_) => ErrorCode::InvalidDeepLink,
+            AppError::InvalidTimeFormat(_) => ErrorCode::InvalidTimeFormat,
+            AppError::InvalidTimezone(_) => ErrorCode::InvalidTimezone,
+            AppError::InvalidTargetTime(_) => ErrorCode::InvalidTargetTime,
+            AppError::InvalidSettingsFile(_) => ErrorCode::InvalidSettingsFile,
+            AppError::UnknownSyncProfile(_) => ErrorCode::UnknownSyncProfile,
+            AppError::UnknownDomainPreset(_) => ErrorCode::UnknownDomainPreset,
+            AppError::UnknownExtractor(_) => ErrorCode::UnknownExtractor,
+            AppError::Plugin(_) => ErrorCode::Plugin,
+            AppError::InvalidTimeResponse(_) => ErrorCode::InvalidTimeResponse,
+            AppError::Replay(_) => ErrorCode::Replay,
+            AppError::NotSynced(_) => ErrorCode::NotSynced,
+            AppError::Ntp(_) => ErrorCode::Ntp,
+            AppError::ClockAdjustmentDisabled => ErrorCode::ClockAdjustmentDisabled,
+            AppError::ClockErrorChanged { .. } => ErrorCode::ClockErrorChanged,
+            AppError::ClockPermissionDenied => ErrorCode::ClockPermissionDenied,
+            AppError::Notification(_) => ErrorCode::Notification,
+            AppError::CalibrationFailed(_) => ErrorCode::CalibrationFailed,
+            AppError::Offline => ErrorCode::Offline,
+            AppError::InvalidSharePayload(_) => ErrorCode::InvalidSharePayload,
+            AppError::SyncStalled(_) => ErrorCode::SyncStalled,
+            AppError::SyncDeadlineExceeded(_) => ErrorCode::SyncDeadlineExceeded,
+            AppError::RecheckTooLate => ErrorCode::RecheckTooLate,
+            AppError::NetworkDegraded(_) => ErrorCode::NetworkDegraded,
+            AppError::Intercepted(_) => ErrorCode::Intercepted,
+            AppError::PassphraseRequired => ErrorCode::PassphraseRequired,
+            AppError::DecryptionFailed => ErrorCode::DecryptionFailed,
+            AppError::DuplicateServer { .. } => ErrorCode::DuplicateServer,
+            AppError::TimeSourceDisabled => ErrorCode::TimeSourceDisabled,
+            AppError::Validation(_) => ErrorCode::Validation,
+        }
+    }
+
+    /// Transient network trouble is worth another go; bad input, missing
+    /// permissions and user cancellation are not.
+    pub fn retryable(&self) -> bool {
+        match self {
+            AppError::Http(e) => e.is_timeout() || e.is_connect() || e.is_request() || e.is_body(),
+            AppError::Io(e) => matches!(
+                e.kind(),
+                std::io::ErrorKind::TimedOut
+                    | std::io::ErrorKind::Interrupted
+                    | std::io::ErrorKind::ConnectionReset
+                    | std::io::ErrorKind::ConnectionAborted
+                    | std::io::ErrorKind::BrokenPipe
+                    | std::io::ErrorKind::UnexpectedEof
+            ),
+            AppError::MaxRetriesExceeded(_)
+            | AppError::CachedResponse(_)
+            | AppError::Ntp(_)
+            | AppError::Offline
+            | AppError::NetworkDegraded(_)
+            | AppError::ClockErrorChanged { .. } => true,
+            _ => false,
+        }
+    }
+
+    pub fn payload(&self) -> ErrorPayload {
+        ErrorPayload {
+            code: self.code(),
+            message: self.to_string(),
+            retryable: self.retryable(),
+            fields: match self {
+                AppError::Validation(fields) => Some(fields.clone()),
+                _ => None,
+            },
+            server_id: match self {
+                AppError::DuplicateServer { id, .. } => Some(*id),
+                _ => None,
+            },
+        }
+    }
 }
 
 impl Serialize for AppError {
@@ -23,7 +245,7 @@ impl Serialize for AppError {
     where
         S: serde::Serializer,
     {
-        serializer.serialize_str(&self.to_string())
+        self.payload().serialize(serializer)
     }
 }
 
@@ -61,26 +283,130 @@ mod tests {
         assert_eq!(e.to_string(), "invalid URL: not-a-url");
     }
 
+    #[test]
+    fn invalid_deep_link_display() {
+        let e = AppError::InvalidDeepLink("missing url".to_string());
+        assert_eq!(e.to_string(), "invalid deep link: missing url");
+    }
+
+    #[test]
+    fn unknown_sync_profile_display() {
+        let e = AppError::UnknownSyncProfile("reckless".to_string());
+        assert_eq!(e.to_string(), "unknown sync profile: reckless");
+    }
+
+    #[test]
+    fn not_synced_display() {
+        assert_eq!(
+            AppError::NotSynced(4).to_string(),
+            "server 4 has not been synced yet"
+        );
+    }
+
+    #[test]
+    fn invalid_time_format_display() {
+        let e = AppError::InvalidTimeFormat("%Q".to_string());
+        assert_eq!(e.to_string(), "invalid time format: %Q");
+    }
+
+    #[test]
+    fn invalid_timezone_display() {
+        let e = AppError::InvalidTimezone("Mars/Olympus".to_string());
+        assert_eq!(e.to_string(), "unknown timezone: Mars/Olympus");
+    }
+
+    #[test]
+    fn invalid_settings_file_display() {
+        let e = AppError::InvalidSettingsFile("unsupported version 2".to_string());
+        assert_eq!(e.to_string(), "invalid settings file: unsupported version 2");
+    }
+
+    #[test]
+    fn clock_error_changed_display() {
+        let e = AppError::ClockErrorChanged {
+            confirmed: 120.4,
+            measured: 480.6,
+        };
+        assert_eq!(
+            e.to_string(),
+            "clock error changed since confirmation (confirmed 120 ms, now 481 ms)"
+        );
+    }
+
     // ── Serialize ──
 
     #[test]
-    fn app_error_serializes_to_its_display_string() {
-        let e = AppError::NoDateHeader;
-        let json = serde_json::to_string(&e).unwrap();
-        assert_eq!(json, "\"server returned no Date header\"");
+    fn app_error_serializes_to_code_message_and_retryable() {
+        let json = serde_json::to_value(AppError::NoDateHeader).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "code": "no_date_header",
+                "message": "server returned no Date header",
+                "retryable": false,
+            })
+        );
     }
 
     #[test]
-    fn invalid_date_header_serializes_to_display_string() {
+    fn invalid_date_header_serializes_with_its_message() {
         let e = AppError::InvalidDateHeader("garbage".to_string());
-        let json = serde_json::to_string(&e).unwrap();
-        assert_eq!(json, "\"invalid Date header format: garbage\"");
+        let json = serde_json::to_value(&e).unwrap();
+        assert_eq!(json["code"], "invalid_date_header");
+        assert_eq!(json["message"], "invalid Date header format: garbage");
+    }
+
+    #[test]
+    fn max_retries_exceeded_is_retryable() {
+        let json = serde_json::to_value(AppError::MaxRetriesExceeded(3)).unwrap();
+        assert_eq!(json["code"], "max_retries_exceeded");
+        assert_eq!(json["message"], "max retries exceeded (3 attempts)");
+        assert_eq!(json["retryable"], true);
+    }
+
+    #[test]
+    fn missing_rows_are_not_found() {
+        let e = AppError::Db(rusqlite::Error::QueryReturnedNoRows);
+        assert_eq!(e.code(), ErrorCode::NotFound);
+        assert_eq!(
+            AppError::Db(rusqlite::Error::InvalidQuery).code(),
+            ErrorCode::Database
+        );
+    }
+
+    #[test]
+    fn cancelled_and_bad_input_are_not_retryable() {
+        assert!(!AppError::Cancelled.retryable());
+        assert!(!AppError::InvalidUrl("x".to_string()).retryable());
+        assert!(!AppError::Io(std::io::ErrorKind::NotFound.into()).retryable());
+        assert!(AppError::Io(std::io::ErrorKind::TimedOut.into()).retryable());
+    }
+
+    #[test]
+    fn validation_serializes_field_errors() {
+        let e = AppError::Validation(vec![FieldError {
+            field: "overlay_opacity".to_string(),
+            message: "must be between 10 and 100 (got 255)".to_string(),
+        }]);
+        let json = serde_json::to_value(&e).unwrap();
+        assert_eq!(json["code"], "validation");
+        assert_eq!(
+            json["message"],
+            "invalid settings: overlay_opacity must be between 10 and 100 (got 255)"
+        );
+        assert_eq!(json["fields"][0]["field"], "overlay_opacity");
     }
 
     #[test]
-    fn max_retries_exceeded_serializes_to_display_string() {
-        let e = AppError::MaxRetriesExceeded(3);
-        let json = serde_json::to_string(&e).unwrap();
-        assert_eq!(json, "\"max retries exceeded (3 attempts)\"");
+    fn duplicate_server_carries_the_existing_id() {
+        let e = AppError::DuplicateServer {
+            id: 7,
+            url: "https://example.com".to_string(),
+        };
+        let json = serde_json::to_value(&e).unwrap();
+        assert_eq!(json["code"], "duplicate_server");
+        assert_eq!(json["message"], "https://example.com is already added (server 7)");
+        assert_eq!(json["server_id"], 7);
+        assert!(serde_json::to_value(AppError::Cancelled).unwrap()["server_id"].is_null());
     }
 }
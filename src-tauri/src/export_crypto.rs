@@ -0,0 +1,114 @@
+use crate::error::AppError;
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use argon2::Argon2;
+use rand::RngCore;
+use std::path::Path;
+
+/// Leading bytes of an encrypted export. The digit is the layout version;
+/// after it come the salt, the nonce and the AES-256-GCM ciphertext.
+const MAGIC: &[u8] = b"TTSEAL1\n";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// Exports can carry auth headers and the servers an organization
+/// watches, so a passphrase turns them into an opaque file.
+pub fn seal(plain: &[u8], passphrase: &str) -> Result<Vec<u8>, AppError> {
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    rand::thread_rng().fill_bytes(&mut salt);
+    rand::thread_rng().fill_bytes(&mut nonce);
+    let ciphertext = cipher(passphrase, &salt)?
+        .encrypt(Nonce::from_slice(&nonce), plain)
+        .map_err(|e| AppError::Io(std::io::Error::other(e.to_string())))?;
+
+    let mut out = Vec::with_capacity(MAGIC.len() + SALT_LEN + NONCE_LEN + ciphertext.len());
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&salt);
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&ciphertext);
+    Ok(out)
+}
+
+/// Undo [`seal`]. A wrong passphrase and a damaged file look the same.
+pub fn open(sealed: &[u8], passphrase: &str) -> Result<Vec<u8>, AppError> {
+    let body = sealed
+        .strip_prefix(MAGIC)
+        .filter(|body| body.len() >= SALT_LEN + NONCE_LEN)
+        .ok_or(AppError::DecryptionFailed)?;
+    let (salt, rest) = body.split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+    cipher(passphrase, salt)?
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| AppError::DecryptionFailed)
+}
+
+pub fn is_sealed(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+/// Write an export, sealed when a non-blank passphrase is given.
+pub fn write(path: &Path, contents: &[u8], passphrase: Option<&str>) -> Result<(), AppError> {
+    match passphrase.filter(|p| !p.is_empty()) {
+        Some(passphrase) => std::fs::write(path, seal(contents, passphrase)?)?,
+        None => std::fs::write(path, contents)?,
+    }
+    Ok(())
+}
+
+/// Read an export written by [`write`], sealed or not.
+pub fn read(path: &Path, passphrase: Option<&str>) -> Result<Vec<u8>, AppError> {
+    let data = std::fs::read(path)?;
+    if !is_sealed(&data) {
+        return Ok(data);
+    }
+    match passphrase.filter(|p| !p.is_empty()) {
+        Some(passphrase) => open(&data, passphrase),
+        None => Err(AppError::PassphraseRequired),
+    }
+}
+
+/// Argon2id stretches the passphrase so a stolen file resists guessing.
+/// That takes a noticeable fraction of a second of CPU, so async callers
+/// run sealing and opening on the blocking pool.
+fn cipher(passphrase: &str, salt: &[u8]) -> Result<Aes256Gcm, AppError> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| AppError::Io(std::io::Error::other(e.to_string())))?;
+    Ok(Aes256Gcm::new(&key.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sealed_exports_need_the_passphrase() {
+        let sealed = seal(b"{\"servers\":[]}", "hunter2").unwrap();
+        assert!(is_sealed(&sealed));
+        assert_eq!(open(&sealed, "hunter2").unwrap(), b"{\"servers\":[]}");
+        assert!(matches!(
+            open(&sealed, "hunter3"),
+            Err(AppError::DecryptionFailed)
+        ));
+    }
+
+    #[test]
+    fn plain_files_read_as_is() {
+        let dir = std::env::temp_dir().join(format!("ticketime-seal-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let plain = dir.join("plain.json");
+        let sealed = dir.join("sealed.json");
+        write(&plain, b"{}", Some("")).unwrap();
+        write(&sealed, b"{}", Some("pass")).unwrap();
+
+        assert_eq!(read(&plain, None).unwrap(), b"{}");
+        assert!(matches!(
+            read(&sealed, None),
+            Err(AppError::PassphraseRequired)
+        ));
+        assert_eq!(read(&sealed, Some("pass")).unwrap(), b"{}");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,212 @@
+use crate::error::AppError;
+use crate::models::Server;
+use crate::server_name;
+use crate::state::AppState;
+use crate::sync_engine::DEFAULT_USER_AGENT;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chrono::{DateTime, Duration, Utc};
+use reqwest::Url;
+use tauri::{AppHandle, Manager};
+
+const FETCH_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(4);
+/// Larger than any icon worth showing at list size.
+const MAX_BYTES: usize = 512 * 1024;
+/// Sites rarely change their icon; a lookup that found none is retried
+/// after the same time.
+const REFRESH_AFTER: Duration = Duration::days(30);
+
+/// A server's cached icon. `data` is empty when the lookup found none, so
+/// it is not repeated on every request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Favicon {
+    pub content_type: String,
+    pub data: Vec<u8>,
+    pub fetched_at: DateTime<Utc>,
+}
+
+impl Favicon {
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// The icon as a `data:` URL an `<img>` can show directly.
+    pub fn data_url(&self) -> String {
+        format!(
+            "data:{};base64,{}",
+            self.content_type,
+            STANDARD.encode(&self.data)
+        )
+    }
+
+    /// Whether the tray's menu icons can decode it.
+    pub fn is_bitmap(&self) -> bool {
+        matches!(self.content_type.as_str(), "image/png" | "image/x-icon")
+    }
+}
+
+/// The cached icon, looked up again once it is `REFRESH_AFTER` old.
+/// `None` when the server has none.
+pub async fn get(app: &AppHandle, server: &Server) -> Result<Option<Favicon>, AppError> {
+    let (handle, id) = (app.clone(), server.id);
+    let cached = tokio::task::spawn_blocking(move || handle.state::<AppState>().db.get_favicon(id))
+        .await
+        .unwrap_or_else(|e| Err(std::io::Error::other(e).into()))?;
+    let favicon = match cached {
+        Some(cached) if Utc::now() - cached.fetched_at < REFRESH_AFTER => cached,
+        _ => refresh(app, server).await?,
+    };
+    Ok((!favicon.is_empty()).then_some(favicon))
+}
+
+/// Look the icon up now and cache the outcome, found or not.
+pub async fn refresh(app: &AppHandle, server: &Server) -> Result<Favicon, AppError> {
+    let (content_type, data) = fetch(server).await.unwrap_or_default();
+    let favicon = Favicon {
+        content_type,
+        data,
+        fetched_at: Utc::now(),
+    };
+    let (handle, id) = (app.clone(), server.id);
+    tokio::task::spawn_blocking(move || {
+        handle.state::<AppState>().db.save_favicon(id, &favicon)?;
+        Ok(favicon)
+    })
+    .await
+    .unwrap_or_else(|e| Err(std::io::Error::other(e).into()))
+}
+
+/// The icons the page links to, best first, then `/favicon.ico`; the first
+/// that downloads as an image wins.
+async fn fetch(server: &Server) -> Option<(String, Vec<u8>)> {
+    let client = reqwest::Client::builder()
+        .timeout(FETCH_TIMEOUT)
+        .user_agent(server.user_agent.as_deref().unwrap_or(DEFAULT_USER_AGENT))
+        .danger_accept_invalid_certs(server.transport.accept_invalid_certs)
+        .build()
+        .ok()?;
+    let mut candidates = match server_name::page_head(server).await {
+        Some((base, html)) => linked_icons(&base, &html),
+        None => Vec::new(),
+    };
+    let root = Url::parse(&server.url).ok()?.join("/favicon.ico").ok()?;
+    if !candidates.contains(&root) {
+        candidates.push(root);
+    }
+    for url in candidates {
+        if let Some(icon) = download(&client, url).await {
+            return Some(icon);
+        }
+    }
+    None
+}
+
+async fn download(client: &reqwest::Client, url: Url) -> Option<(String, Vec<u8>)> {
+    let mut response = client.get(url).send().await.ok()?;
+    if !response.status().is_success()
+        || response
+            .content_length()
+            .is_some_and(|len| len > MAX_BYTES as u64)
+    {
+        return None;
+    }
+    let declared = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.split(';').next().unwrap_or(v).trim().to_ascii_lowercase());
+    // Chunk by chunk, since a chunked response declares no length: a cut
+    // off icon is no use, so an oversized one is dropped.
+    let mut data = Vec::new();
+    while let Some(chunk) = response.chunk().await.ok()? {
+        if data.len() + chunk.len() > MAX_BYTES {
+            return None;
+        }
+        data.extend_from_slice(&chunk);
+    }
+    if data.is_empty() {
+        return None;
+    }
+    let content_type = sniff(&data)
+        .map(str::to_string)
+        .or(declared.filter(|t| t.starts_with("image/")))?;
+    Some((content_type, data))
+}
+
+/// `<link rel="icon">` and friends, resolved against `base`. Apple touch
+/// icons come last: they are large and often lack transparency.
+fn linked_icons(base: &Url, html: &str) -> Vec<Url> {
+    let mut icons: Vec<(bool, Url)> = server_name::tags(html, "link")
+        .into_iter()
+        .filter_map(|tag| {
+            let rel = server_name::attribute(tag, "rel")?.to_ascii_lowercase();
+            let rels: Vec<&str> = rel.split_whitespace().collect();
+            let touch = rels.iter().any(|r| r.starts_with("apple-touch-icon"));
+            if !touch && !rels.contains(&"icon") {
+                return None;
+            }
+            let href = server_name::attribute(tag, "href")?.replace("&amp;", "&");
+            Some((touch, base.join(href.trim()).ok()?))
+        })
+        .collect();
+    icons.sort_by_key(|(touch, _)| *touch);
+    icons.into_iter().map(|(_, url)| url).collect()
+}
+
+/// The image type from the data's first bytes; servers often label icons
+/// `application/octet-stream`, or answer a missing one with an HTML page.
+fn sniff(data: &[u8]) -> Option<&'static str> {
+    const SIGNATURES: [(&[u8], &str); 4] = [
+        (b"\x89PNG\r\n\x1a\n", "image/png"),
+        (b"\x00\x00\x01\x00", "image/x-icon"),
+        (b"GIF8", "image/gif"),
+        (b"\xff\xd8\xff", "image/jpeg"),
+    ];
+    if let Some((_, kind)) = SIGNATURES.iter().find(|(magic, _)| data.starts_with(magic)) {
+        return Some(kind);
+    }
+    if data.starts_with(b"RIFF") && data.get(8..12) == Some(&b"WEBP"[..]) {
+        return Some("image/webp");
+    }
+    let head = String::from_utf8_lossy(&data[..data.len().min(512)]).to_ascii_lowercase();
+    head.contains("<svg").then_some("image/svg+xml")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn linked_icons_resolve_and_put_touch_icons_last() {
+        let base = Url::parse("https://tickets.example.com/events/").unwrap();
+        let html = r#"<head>
+            <link rel="apple-touch-icon" href="/touch.png">
+            <link rel="stylesheet" href="/site.css">
+            <link href="icons/32.png?v=2&amp;x=1" rel="icon" sizes="32x32">
+            <link rel="shortcut icon" href="https://cdn.example.net/favicon.ico">
+        </head>"#;
+        let urls: Vec<String> = linked_icons(&base, html)
+            .into_iter()
+            .map(String::from)
+            .collect();
+        assert_eq!(
+            urls,
+            [
+                "https://tickets.example.com/events/icons/32.png?v=2&x=1",
+                "https://cdn.example.net/favicon.ico",
+                "https://tickets.example.com/touch.png",
+            ]
+        );
+    }
+
+    #[test]
+    fn sniff_tells_images_from_error_pages() {
+        assert_eq!(sniff(b"\x89PNG\r\n\x1a\n...."), Some("image/png"));
+        assert_eq!(sniff(b"\x00\x00\x01\x00\x01\x00"), Some("image/x-icon"));
+        assert_eq!(
+            sniff(b"<?xml version=\"1.0\"?>\n<svg xmlns=\"http://www.w3.org/2000/svg\">"),
+            Some("image/svg+xml")
+        );
+        assert_eq!(sniff(b"<!doctype html><title>Not found</title>"), None);
+    }
+}
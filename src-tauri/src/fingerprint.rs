@@ -0,0 +1,133 @@
+use reqwest::header::HeaderMap;
+use serde::{Deserialize, Serialize};
+
+/// What a server's responses say about the software and CDN in front of it.
+/// Edge caches and load balancers explain a lot of odd timing behaviour.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ServerFingerprint {
+    /// The `Server` header, e.g. "nginx/1.25.3" or "AkamaiGHost".
+    pub server: Option<String>,
+    pub via: Option<String>,
+    /// The `X-Cache` header (HIT/MISS from most CDNs).
+    pub cache: Option<String>,
+    /// The CDN recognised from vendor-specific headers.
+    pub cdn: Option<String>,
+    /// The `Content-Language` header, a hint at the site's home timezone.
+    #[serde(default)]
+    pub content_language: Option<String>,
+}
+
+/// Headers that only one CDN sends, checked in order.
+const CDN_MARKERS: &[(&str, &str)] = &[
+    ("cf-ray", "Cloudflare"),
+    ("x-amz-cf-id", "CloudFront"),
+    ("x-akamai-transformed", "Akamai"),
+    ("akamai-grn", "Akamai"),
+    ("x-fastly-request-id", "Fastly"),
+    ("x-vercel-id", "Vercel"),
+    ("x-azure-ref", "Azure Front Door"),
+    ("x-goog-generation", "Google Cloud"),
+];
+
+/// `None` when the response carries nothing worth recording.
+pub fn from_headers(headers: &HeaderMap) -> Option<ServerFingerprint> {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+
+    let server = header("server");
+    let via = header("via");
+    let cache = header("x-cache");
+    let cdn = detect_cdn(headers, server.as_deref(), via.as_deref());
+    let content_language = header("content-language");
+
+    if server.is_none()
+        && via.is_none()
+        && cache.is_none()
+        && cdn.is_none()
+        && content_language.is_none()
+    {
+        return None;
+    }
+    Some(ServerFingerprint {
+        server,
+        via,
+        cache,
+        cdn,
+        content_language,
+    })
+}
+
+fn detect_cdn(headers: &HeaderMap, server: Option<&str>, via: Option<&str>) -> Option<String> {
+    if let Some((_, cdn)) = CDN_MARKERS
+        .iter()
+        .find(|(name, _)| headers.contains_key(*name))
+    {
+        return Some(cdn.to_string());
+    }
+
+    // Fall back to the product names CDNs put in `Server` and `Via`.
+    let haystack = format!("{} {}", server.unwrap_or(""), via.unwrap_or("")).to_lowercase();
+    [
+        ("cloudflare", "Cloudflare"),
+        ("akamai", "Akamai"),
+        ("cloudfront", "CloudFront"),
+        ("varnish", "Varnish"),
+        ("fastly", "Fastly"),
+    ]
+    .into_iter()
+    .find(|(needle, _)| haystack.contains(needle))
+    .map(|(_, cdn)| cdn.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::{HeaderName, HeaderValue};
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.insert(
+                HeaderName::from_static(name),
+                HeaderValue::from_static(value),
+            );
+        }
+        map
+    }
+
+    #[test]
+    fn recognises_cdn_from_vendor_header() {
+        let fp = from_headers(&headers(&[
+            ("server", "cloudflare"),
+            ("cf-ray", "8a1b2c3d4e5f-AMS"),
+        ]))
+        .unwrap();
+        assert_eq!(fp.server.as_deref(), Some("cloudflare"));
+        assert_eq!(fp.cdn.as_deref(), Some("Cloudflare"));
+    }
+
+    #[test]
+    fn falls_back_to_server_and_via_names() {
+        let fp = from_headers(&headers(&[
+            ("server", "nginx"),
+            ("via", "1.1 varnish (Varnish/7.4)"),
+            ("x-cache", "HIT"),
+        ]))
+        .unwrap();
+        assert_eq!(fp.cdn.as_deref(), Some("Varnish"));
+        assert_eq!(fp.cache.as_deref(), Some("HIT"));
+
+        let plain = from_headers(&headers(&[("server", "nginx/1.25.3")])).unwrap();
+        assert_eq!(plain.cdn, None);
+    }
+
+    #[test]
+    fn nothing_to_record_is_none() {
+        assert_eq!(from_headers(&headers(&[("date", "x")])), None);
+    }
+}
@@ -0,0 +1,161 @@
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::SyncResult;
+use chrono::{DateTime, Utc};
+
+/// Syncs the score looks back over.
+pub const WINDOW: i64 = 20;
+
+/// Points each part can take off the score; together they make 100.
+const SUCCESS_WEIGHT: f64 = 30.0;
+const STABILITY_WEIGHT: f64 = 25.0;
+const JITTER_WEIGHT: f64 = 20.0;
+const STALENESS_WEIGHT: f64 = 25.0;
+
+/// Offset spread across syncs (standard deviation) that costs the full
+/// stability weight.
+const UNSTABLE_SPREAD_MS: f64 = 50.0;
+
+/// Age of the last sync that costs the full staleness weight.
+const STALE_HOURS: f64 = 24.0;
+
+/// 0–100: how much this server's offset can be trusted right now.
+///
+/// - success rate: failed syncs among the recent attempts
+/// - offset stability: spread of the measured offsets
+/// - RTT jitter: interquartile range of the latest sync's round trips
+///   relative to their median
+/// - staleness: age of the latest sync
+///
+/// `history` is newest first, `failures` the failed syncs over the same
+/// stretch. `None` when the server has never synced.
+pub fn score(history: &[SyncResult], failures: usize, now: DateTime<Utc>) -> Option<u8> {
+    let latest = history.first()?;
+
+    let attempts = history.len() + failures;
+    let success_penalty = SUCCESS_WEIGHT * failures as f64 / attempts as f64;
+
+    let offsets: Vec<f64> = history.iter().map(|r| r.total_offset_ms).collect();
+    let mean = offsets.iter().sum::<f64>() / offsets.len() as f64;
+    let spread =
+        (offsets.iter().map(|o| (o - mean).powi(2)).sum::<f64>() / offsets.len() as f64).sqrt();
+    let stability_penalty = STABILITY_WEIGHT * (spread / UNSTABLE_SPREAD_MS).min(1.0);
+
+    let profile = &latest.latency_profile;
+    let jitter_ratio = if profile.median > 0.0 {
+        (profile.q3 - profile.q1) / profile.median
+    } else {
+        0.0
+    };
+    let jitter_penalty = JITTER_WEIGHT * jitter_ratio.clamp(0.0, 1.0);
+
+    let age_hours = (now - latest.synced_at).num_seconds().max(0) as f64 / 3600.0;
+    let staleness_penalty = STALENESS_WEIGHT * (age_hours / STALE_HOURS).min(1.0);
+
+    let total = 100.0 - success_penalty - stability_penalty - jitter_penalty - staleness_penalty;
+    Some(total.round().clamp(0.0, 100.0) as u8)
+}
+
+/// Recompute and store the score of `server_id`.
+pub fn refresh(db: &Database, server_id: i64, now: DateTime<Utc>) -> Result<Option<u8>, AppError> {
+    let history = db.get_sync_history(server_id, None, Some(WINDOW))?;
+    let failures = match history.last() {
+        Some(oldest) => db
+            .get_error_history(server_id, Some(WINDOW))?
+            .iter()
+            .filter(|e| e.occurred_at >= oldest.synced_at)
+            .count(),
+        None => 0,
+    };
+    let health = score(&history, failures, now);
+    db.set_server_health(server_id, health)?;
+    Ok(health)
+}
+
+/// Whether a score calls for a resync; a threshold of 0 never does.
+pub fn needs_resync(score: Option<u8>, threshold: u8) -> bool {
+    matches!(score, Some(s) if s < threshold)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{LatencyProfile, SyncPhase};
+    use chrono::Duration;
+
+    fn result(offset_ms: f64, synced_at: DateTime<Utc>, q1: f64, q3: f64) -> SyncResult {
+        SyncResult {
+            server_id: 1,
+            whole_second_offset: 0,
+            subsecond_offset: offset_ms,
+            total_offset_ms: offset_ms,
+            latency_profile: LatencyProfile {
+                min: q1,
+                q1,
+                median: 40.0,
+                mean: 40.0,
+                q3,
+                max: q3,
+                breakdown: None,
+            },
+            verified: true,
+            synced_at,
+            duration_ms: 1000,
+            phase_reached: SyncPhase::Complete,
+            fingerprint: None,
+            response: None,
+            id: 0,
+            timeline: Vec::new(),
+            note: None,
+            asymmetry: None,
+            anomalous: false,
+            clock_issue: None,
+            network: None,
+            grade: None,
+            precision_ms: None,
+        }
+    }
+
+    #[test]
+    fn fresh_steady_server_scores_full_marks() {
+        let now = Utc::now();
+        let history = [
+            result(120.0, now, 40.0, 40.0),
+            result(120.0, now, 40.0, 40.0),
+        ];
+        assert_eq!(score(&history, 0, now), Some(100));
+        assert_eq!(score(&[], 0, now), None);
+    }
+
+    #[test]
+    fn each_part_costs_at_most_its_weight() {
+        let now = Utc::now();
+        // Day-old, wildly jittery, half the attempts failed, offsets
+        // swinging by far more than the stability limit.
+        let old = now - Duration::days(2);
+        let history = [result(0.0, old, 0.0, 400.0), result(500.0, old, 0.0, 400.0)];
+        assert_eq!(score(&history, 2, now), Some(100 - 15 - 25 - 20 - 25));
+
+        // Only staleness: twelve hours costs half its weight.
+        let history = [result(0.0, now - Duration::hours(12), 40.0, 40.0)];
+        assert_eq!(score(&history, 0, now), Some(88));
+    }
+
+    #[test]
+    fn refresh_stores_the_score() {
+        let db = Database::new_in_memory().unwrap();
+        let server = db.add_server("https://example.com").unwrap();
+        assert_eq!(refresh(&db, server.id, Utc::now()).unwrap(), None);
+
+        let mut synced = result(10.0, Utc::now(), 40.0, 40.0);
+        synced.server_id = server.id;
+        db.save_sync_result(&synced).unwrap();
+        let health = refresh(&db, server.id, Utc::now()).unwrap();
+        assert_eq!(health, Some(100));
+        assert_eq!(db.get_server(server.id).unwrap().health_score, health);
+
+        assert!(needs_resync(Some(40), 50));
+        assert!(!needs_resync(Some(40), 0));
+        assert!(!needs_resync(None, 50));
+    }
+}
@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// The latest probe handed out for a host.
+#[derive(Debug, Clone, Copy)]
+struct Slot {
+    /// Wall-clock send time, unix seconds.
+    at: f64,
+    /// Gap the claimant kept after it, seconds.
+    gap: f64,
+}
+
+/// Spaces probes to one host across every sync running against it. Each
+/// sync already keeps its own probes apart; this makes two servers on the
+/// same host share that budget instead of each spending it in full.
+#[derive(Default)]
+pub struct HostLimiter {
+    hosts: Mutex<HashMap<String, Slot>>,
+}
+
+impl HostLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Claim a send time for `host`: `wanted` if it is clear of the last
+    /// probe to the host, else the first time `align` accepts after it.
+    /// The gap kept is the larger of `gap` and the last claimant's, so a
+    /// gentle server is not crowded by a faster one on the same host.
+    pub fn claim(&self, host: &str, gap: f64, wanted: f64, align: &dyn Fn(f64) -> f64) -> f64 {
+        let mut hosts = self.hosts.lock().unwrap();
+        let at = match hosts.get(host) {
+            Some(last) if wanted < last.at + gap.max(last.gap) => {
+                align(last.at + gap.max(last.gap))
+            }
+            _ => wanted,
+        };
+        hosts.insert(host.to_string(), Slot { at, gap });
+        at
+    }
+}
+
+/// The key probes to `url` are spaced under: its host, lowercased.
+pub fn host_key(url: &str) -> Option<String> {
+    reqwest::Url::parse(url)
+        .ok()?
+        .host_str()
+        .map(str::to_ascii_lowercase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn second_claimant_waits_out_the_gap() {
+        let limiter = HostLimiter::new();
+        let same = |t: f64| t;
+        assert_eq!(limiter.claim("example.com", 0.5, 10.0, &same), 10.0);
+        assert_eq!(limiter.claim("example.com", 0.5, 10.2, &same), 10.5);
+        // Other hosts are not held up.
+        assert_eq!(limiter.claim("example.org", 0.5, 10.2, &same), 10.2);
+    }
+
+    #[test]
+    fn delayed_claims_keep_their_alignment_and_the_wider_gap() {
+        let limiter = HostLimiter::new();
+        limiter.claim("example.com", 2.0, 10.0, &|t| t);
+        // A fast sync aiming at .3 of a second lands on the next .3 after
+        // the slow one's gap.
+        let at_point_three = |t: f64| {
+            let target = t.floor() + 0.3;
+            if t > target {
+                target + 1.0
+            } else {
+                target
+            }
+        };
+        let at = limiter.claim("example.com", 0.2, 10.3, &at_point_three);
+        assert!((at - 12.3).abs() < 1e-9, "{at}");
+    }
+
+    #[test]
+    fn hosts_are_keyed_case_insensitively() {
+        assert_eq!(
+            host_key("https://Example.COM/path"),
+            Some("example.com".to_string())
+        );
+        assert_eq!(host_key("not a url"), None);
+    }
+}
@@ -0,0 +1,49 @@
+use crate::tray;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+pub const SECOND_INSTANCE_EVENT: &str = "second-instance";
+
+/// Arguments a second launch handed over before exiting.
+#[derive(Debug, Clone, Serialize)]
+pub struct SecondInstancePayload {
+    pub args: Vec<String>,
+    pub cwd: String,
+}
+
+/// Called in the running instance when the app is launched again. The new
+/// process exits immediately; we surface our window and forward its args.
+pub fn on_second_instance(app: &AppHandle, argv: Vec<String>, cwd: String) {
+    tray::show_main_window(app);
+
+    let payload = SecondInstancePayload {
+        args: forwarded_args(argv),
+        cwd,
+    };
+    if let Err(e) = app.emit(SECOND_INSTANCE_EVENT, payload) {
+        log::warn!("failed to forward second-instance args: {e}");
+    }
+}
+
+/// Drop the executable path from `argv`.
+fn forwarded_args(argv: Vec<String>) -> Vec<String> {
+    argv.into_iter().skip(1).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forwarded_args_skips_executable() {
+        let argv = vec![
+            "/usr/bin/ticketime".to_string(),
+            "ticketime://add?url=https://example.com".to_string(),
+        ];
+        assert_eq!(
+            forwarded_args(argv),
+            vec!["ticketime://add?url=https://example.com".to_string()]
+        );
+        assert!(forwarded_args(vec![]).is_empty());
+    }
+}
@@ -0,0 +1,241 @@
+use crate::error::AppError;
+use reqwest::header;
+use std::time::Duration;
+
+/// Google answers this with an empty 204 from anywhere on the open
+/// internet. A captive portal answers it with a redirect or its login page.
+const CANARY_URL: &str = "http://connectivitycheck.gstatic.com/generate_204";
+/// The same check over TLS, to tell a proxy re-signing every connection
+/// from one server with a broken certificate.
+const CANARY_TLS_URL: &str = "https://connectivitycheck.gstatic.com/generate_204";
+const CANARY_TIMEOUT: Duration = Duration::from_secs(3);
+/// Status of a network that wants a sign-in first (RFC 6585).
+const NETWORK_AUTHENTICATION_REQUIRED: u16 = 511;
+
+/// Who answered a request, as far as the response gives away.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Answer {
+    pub status: u16,
+    /// Host the answer came from, after any redirects that were followed.
+    pub host: Option<String>,
+    /// Where a redirect that was not followed points.
+    pub location: Option<String>,
+    pub server: Option<String>,
+    pub date: Option<String>,
+}
+
+impl Answer {
+    pub fn of(response: &reqwest::Response) -> Self {
+        let text = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        Self {
+            status: response.status().as_u16(),
+            host: response.url().host_str().map(str::to_string),
+            location: text(header::LOCATION),
+            server: text(header::SERVER),
+            date: text(header::DATE),
+        }
+    }
+
+    /// Host the answer sends the client on to.
+    fn redirect_host(&self) -> Option<String> {
+        let location = reqwest::Url::parse(self.location.as_deref()?).ok()?;
+        location.host_str().map(str::to_string)
+    }
+}
+
+pub fn sign_in_required() -> AppError {
+    AppError::Intercepted(
+        "the network asks for a sign-in (HTTP 511); sign in to it and sync again".to_string(),
+    )
+}
+
+/// Look at a sync's warm-up request for signs that something other than
+/// `url`'s server is answering. A 511 says so outright. Anything else is
+/// only checked against the canary with `use_canary` on, and only when
+/// the warm-up looks off (see `suspicious`), so a clean sync sends nothing
+/// to a third party. `None` when nothing points that way, or when the
+/// canary cannot tell either way.
+pub async fn check(
+    url: &str,
+    warm_up: &Result<Answer, AppError>,
+    use_canary: bool,
+) -> Option<AppError> {
+    let host = reqwest::Url::parse(url).ok()?.host_str()?.to_string();
+    match warm_up {
+        Ok(answer) if answer.status == NETWORK_AUTHENTICATION_REQUIRED => Some(sign_in_required()),
+        Ok(answer) if use_canary && suspicious(&host, answer) => {
+            verdict(&host, answer, canary().await.as_ref())
+        }
+        Err(AppError::Http(e))
+            if use_canary && e.is_connect() && fails_on_certificate(url).await =>
+        {
+            certificate_verdict(&host).await
+        }
+        _ => None,
+    }
+}
+
+/// Whether the answer is unlike a server's own: sent on to another host,
+/// or without a Date a clock could be read from.
+fn suspicious(host: &str, answer: &Answer) -> bool {
+    let elsewhere = |other: Option<String>| other.is_some_and(|h| h != host);
+    let dated = answer
+        .date
+        .as_deref()
+        .is_some_and(|d| chrono::DateTime::parse_from_rfc2822(d).is_ok());
+    elsewhere(answer.host.clone()) || elsewhere(answer.redirect_host()) || !dated
+}
+
+/// The canary's answer, or `None` when it could not be reached.
+async fn canary() -> Option<Answer> {
+    let client = reqwest::Client::builder()
+        .timeout(CANARY_TIMEOUT)
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .ok()?;
+    let response = client.get(CANARY_URL).send().await.ok()?;
+    Some(Answer::of(&response))
+}
+
+/// A portal shows itself on the canary; it only matters for the sync when
+/// the target's answer came from the same place.
+fn verdict(host: &str, target: &Answer, canary: Option<&Answer>) -> Option<AppError> {
+    if target.status == NETWORK_AUTHENTICATION_REQUIRED {
+        return Some(sign_in_required());
+    }
+    let canary = canary.filter(|c| c.status != 204)?;
+    if let Some(portal) = canary.redirect_host() {
+        if target.host.as_deref() == Some(portal.as_str()) && portal != host {
+            return Some(AppError::Intercepted(format!(
+                "requests to {host} end up at {portal}, a captive portal; sign in to the \
+                 network and sync again"
+            )));
+        }
+    }
+    if target.date.is_some()
+        && (target.status, &target.server, &target.date)
+            == (canary.status, &canary.server, &canary.date)
+    {
+        return Some(AppError::Intercepted(format!(
+            "{host} and an unrelated host gave identical answers (HTTP {}, Date {}); a captive \
+             portal or proxy is answering for both",
+            target.status,
+            target.date.as_deref().unwrap_or_default()
+        )));
+    }
+    None
+}
+
+/// One server's certificate failing says little. An unrelated host's
+/// failing too means something on the path is re-signing TLS.
+async fn certificate_verdict(host: &str) -> Option<AppError> {
+    fails_on_certificate(CANARY_TLS_URL).await.then(|| {
+        AppError::Intercepted(format!(
+            "the TLS certificates of {host} and an unrelated host were both rejected; a proxy \
+             or captive portal is intercepting encrypted traffic"
+        ))
+    })
+}
+
+/// Whether `url` can only be reached with certificate checks off: the
+/// handshake completes, so what it rejects is the certificate. The TLS
+/// backends give no typed error to tell this apart.
+async fn fails_on_certificate(url: &str) -> bool {
+    let client = |accept_invalid_certs| {
+        reqwest::Client::builder()
+            .timeout(CANARY_TIMEOUT)
+            .redirect(reqwest::redirect::Policy::none())
+            .danger_accept_invalid_certs(accept_invalid_certs)
+            .build()
+    };
+    let (Ok(strict), Ok(lenient)) = (client(false), client(true)) else {
+        return false;
+    };
+    matches!(strict.head(url).send().await, Err(e) if e.is_connect())
+        && lenient.head(url).send().await.is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answer(status: u16, host: &str) -> Answer {
+        Answer {
+            status,
+            host: Some(host.to_string()),
+            location: None,
+            server: Some("nginx".to_string()),
+            date: Some("Mon, 02 Jun 2025 01:00:00 GMT".to_string()),
+        }
+    }
+
+    #[test]
+    fn an_open_network_passes() {
+        let target = answer(200, "tickets.example.com");
+        let canary = answer(204, "connectivitycheck.gstatic.com");
+        assert!(verdict("tickets.example.com", &target, Some(&canary)).is_none());
+        assert!(verdict("tickets.example.com", &target, None).is_none());
+    }
+
+    #[test]
+    fn portals_are_caught_by_redirect_status_or_identical_answers() {
+        let portal = Answer {
+            location: Some("http://login.hotel-wifi.net/?orig=gstatic".to_string()),
+            ..answer(302, "connectivitycheck.gstatic.com")
+        };
+        let redirected = answer(200, "login.hotel-wifi.net");
+        assert!(matches!(
+            verdict("tickets.example.com", &redirected, Some(&portal)),
+            Some(AppError::Intercepted(_))
+        ));
+
+        let gated = answer(511, "tickets.example.com");
+        assert!(verdict("tickets.example.com", &gated, None).is_some());
+
+        // A transparent proxy answering both hosts itself, in the same second.
+        let proxied = answer(200, "tickets.example.com");
+        let canary = answer(200, "connectivitycheck.gstatic.com");
+        assert!(verdict("tickets.example.com", &proxied, Some(&canary)).is_some());
+    }
+
+    #[test]
+    fn only_an_odd_warm_up_is_suspicious() {
+        let host = "tickets.example.com";
+        assert!(!suspicious(host, &answer(200, host)));
+
+        let undated = Answer {
+            date: None,
+            ..answer(200, host)
+        };
+        assert!(suspicious(host, &undated));
+        let garbled = Answer {
+            date: Some("yesterday".to_string()),
+            ..answer(200, host)
+        };
+        assert!(suspicious(host, &garbled));
+        assert!(suspicious(host, &answer(200, "login.hotel-wifi.net")));
+        let sent_away = Answer {
+            location: Some("http://login.hotel-wifi.net/".to_string()),
+            ..answer(302, host)
+        };
+        assert!(suspicious(host, &sent_away));
+    }
+
+    #[test]
+    fn a_walled_garden_target_still_syncs() {
+        // The portal holds back the canary but lets the ticket site through.
+        let portal = Answer {
+            location: Some("http://login.hotel-wifi.net/".to_string()),
+            server: None,
+            ..answer(302, "connectivitycheck.gstatic.com")
+        };
+        let target = answer(200, "tickets.example.com");
+        assert!(verdict("tickets.example.com", &target, Some(&portal)).is_none());
+    }
+}
@@ -0,0 +1,241 @@
+use crate::error::AppError;
+use crate::models::{Server, Target, TransportOptions};
+use crate::simulation::DemoServer;
+use crate::state::AppState;
+use crate::sync_engine::{self, ProbeClient, ProbeSettings};
+use crate::sync_profile::{self, SyncProfile};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+/// Gap between keep-alive requests. Well under the idle timeout of common
+/// servers and CDNs (nginx 75 s, most edges 15–60 s) and of the probe
+/// client's own pool.
+const PING_EVERY: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// What a pooled client was built from; a change means building a new one.
+#[derive(Debug, Clone, PartialEq)]
+struct ClientKey {
+    timeout_ms: u64,
+    user_agent: Option<String>,
+    transport: TransportOptions,
+}
+
+impl ClientKey {
+    fn new(server: &Server, profile: &SyncProfile) -> Self {
+        Self {
+            timeout_ms: profile.timeout_ms,
+            user_agent: server.user_agent.clone(),
+            transport: server.transport,
+        }
+    }
+}
+
+/// In the last `keep_warm_minutes` before an armed target, sends a tiny
+/// request to the target's server every `PING_EVERY`, so the connection
+/// and TLS session are still open when a sync or estimate probes it.
+pub struct KeepWarm {
+    clients: Mutex<HashMap<i64, (ClientKey, ProbeClient)>>,
+}
+
+impl KeepWarm {
+    pub fn new() -> Self {
+        Self {
+            clients: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The client being kept warm for `server`, if there is one and it
+    /// still matches the server's settings.
+    pub fn client(&self, server: &Server, profile: &SyncProfile) -> Option<ProbeClient> {
+        let key = ClientKey::new(server, profile);
+        self.clients
+            .lock()
+            .unwrap()
+            .get(&server.id)
+            .filter(|(built_from, _)| *built_from == key)
+            .map(|(_, client)| client.clone())
+    }
+
+    /// The server's probe settings, reusing the warm client if there is one.
+    pub fn probe_settings<'a>(
+        &self,
+        server: &'a Server,
+        profile: &SyncProfile,
+    ) -> ProbeSettings<'a> {
+        ProbeSettings {
+            client: self.client(server, profile),
+            ..ProbeSettings::for_server(server)
+        }
+    }
+
+    /// The pooled client for a keep-alive ping, built if there is none.
+    /// `None` while an estimate, test or benchmark has it out: a ping would
+    /// land in the redirect count and connect timings it reads.
+    fn client_for_ping(
+        &self,
+        server: &Server,
+        profile: &SyncProfile,
+    ) -> Result<Option<ProbeClient>, AppError> {
+        let key = ClientKey::new(server, profile);
+        let mut clients = self.clients.lock().unwrap();
+        if let Some((built_from, client)) = clients.get(&server.id) {
+            if *built_from == key {
+                return Ok((!client.is_shared()).then(|| client.clone()));
+            }
+        }
+        let client = ProbeClient::new(profile, &ProbeSettings::for_server(server))?;
+        clients.insert(server.id, (key, client.clone()));
+        Ok(Some(client))
+    }
+}
+
+pub fn start(app: &AppHandle) {
+    tauri::async_runtime::spawn(run(app.clone()));
+}
+
+async fn run(app: AppHandle) {
+    let mut interval = tokio::time::interval(PING_EVERY);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    loop {
+        interval.tick().await;
+        ping_due(&app).await;
+    }
+}
+
+async fn ping_due(app: &AppHandle) {
+    let handle = app.clone();
+    let due = tokio::task::spawn_blocking(move || servers_due(&handle.state::<AppState>()))
+        .await
+        .unwrap_or_else(|e| Err(std::io::Error::other(e).into()));
+    let due = match due {
+        Ok(due) => due,
+        Err(e) => {
+            log::warn!("keep-alive could not read its targets: {e}");
+            return;
+        }
+    };
+
+    let state = app.state::<AppState>();
+    for (server, profile) in due {
+        if let Err(e) = ping(&state, &server, &profile).await {
+            log::warn!("keep-alive for server {} failed: {e}", server.id);
+        }
+    }
+}
+
+/// The servers to ping this round, with the profile each resolves to.
+/// Drops the pooled clients of servers no longer due.
+fn servers_due(state: &AppState) -> Result<Vec<(Server, SyncProfile)>, AppError> {
+    let settings = state.db.get_settings()?;
+    let window = Duration::minutes(settings.keep_warm_minutes.into());
+    let due = servers_to_warm(&state.db.list_targets()?, Utc::now(), window);
+    state
+        .keep_warm
+        .clients
+        .lock()
+        .unwrap()
+        .retain(|id, _| due.contains(id));
+
+    let mut servers = Vec::new();
+    for id in due {
+        // A running sync keeps the connection busy, and its timings are
+        // read from the same client.
+        if state.active_syncs.lock().unwrap().contains_key(&id) {
+            continue;
+        }
+        let Ok(server) = state.db.get_server(id) else {
+            continue;
+        };
+        if DemoServer::from_url(&server.url).is_some() {
+            continue;
+        }
+        let profile = sync_profile::resolve(&server, &settings);
+        servers.push((server, profile));
+    }
+    Ok(servers)
+}
+
+async fn ping(state: &AppState, server: &Server, profile: &SyncProfile) -> Result<(), AppError> {
+    let Some(client) = state.keep_warm.client_for_ping(server, profile)? else {
+        return Ok(());
+    };
+    let probe_settings = ProbeSettings {
+        client: Some(client),
+        ..ProbeSettings::for_server(server)
+    };
+    sync_engine::keep_alive(&server.url, &probe_settings, profile).await
+}
+
+/// Servers with an armed target due within `window`; none when the window
+/// is zero (the setting is off).
+fn servers_to_warm(targets: &[Target], now: DateTime<Utc>, window: Duration) -> HashSet<i64> {
+    targets
+        .iter()
+        .filter(|t| t.armed && t.target_at > now && t.target_at - now <= window)
+        .map(|t| t.server_id)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(server_id: i64, target_at: DateTime<Utc>, armed: bool) -> Target {
+        Target {
+            id: server_id,
+            server_id,
+            label: None,
+            target_at,
+            armed,
+            alert_method: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn warms_only_servers_with_an_armed_target_inside_the_window() {
+        let now = Utc::now();
+        let targets = [
+            target(1, now + Duration::minutes(3), true),
+            target(2, now + Duration::minutes(30), true),
+            target(3, now + Duration::minutes(2), false),
+            target(4, now - Duration::minutes(1), true),
+        ];
+        assert_eq!(
+            servers_to_warm(&targets, now, Duration::minutes(5)),
+            HashSet::from([1])
+        );
+        assert!(servers_to_warm(&targets, now, Duration::zero()).is_empty());
+    }
+
+    #[test]
+    fn pooled_client_is_dropped_when_settings_change() {
+        let db = crate::db::Database::new_in_memory().unwrap();
+        let mut server = db.add_server("https://example.com").unwrap();
+        let profile = sync_profile::BALANCED;
+        let warm = KeepWarm::new();
+        assert!(warm.client(&server, &profile).is_none());
+
+        drop(warm.client_for_ping(&server, &profile).unwrap());
+        assert!(warm.client(&server, &profile).is_some());
+
+        server.user_agent = Some("Custom/1.0".to_string());
+        assert!(warm.client(&server, &profile).is_none());
+    }
+
+    #[test]
+    fn no_ping_while_a_probe_has_the_client() {
+        let db = crate::db::Database::new_in_memory().unwrap();
+        let server = db.add_server("https://example.com").unwrap();
+        let profile = sync_profile::BALANCED;
+        let warm = KeepWarm::new();
+        drop(warm.client_for_ping(&server, &profile).unwrap());
+
+        let in_use = warm.client(&server, &profile).unwrap();
+        assert!(warm.client_for_ping(&server, &profile).unwrap().is_none());
+        drop(in_use);
+        assert!(warm.client_for_ping(&server, &profile).unwrap().is_some());
+    }
+}
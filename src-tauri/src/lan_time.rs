@@ -0,0 +1,306 @@
+use crate::models::{AppSettings, Server, ServerTime};
+use crate::ntp::{self, PACKET_LEN};
+use crate::state::AppState;
+use crate::timing;
+use chrono::{DateTime, Utc};
+use mdns_sd::{ServiceDaemon, ServiceInfo};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::net::UdpSocket;
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
+
+/// What other devices browse for. The TXT record says which protocol the
+/// port speaks, so a later one can be added beside SNTP.
+const SERVICE_TYPE: &str = "_ticketime._udp.local.";
+/// How often the primary server and settings are re-read, so a resync or a
+/// new primary reaches clients within a second.
+const RELOAD_EVERY: Duration = Duration::from_secs(1);
+/// Reported as the stratum of a synced answer: one step below the server
+/// whose clock is passed on.
+const STRATUM: u8 = 2;
+/// NTPv4's "unsynchronized" stratum, sent until the primary server has an
+/// offset; clients then discard the answer.
+const UNSYNCHRONIZED: u8 = 16;
+/// About a millisecond (2^-10 s), the best a synced offset gets.
+const PRECISION: i8 = -10;
+/// Kept in the reference ID field, where a stratum 2 server would put its
+/// upstream's address.
+const REFERENCE_ID: &[u8; 4] = b"TKTM";
+
+/// Optional SNTP (RFC 4330) responder on the LAN that hands out the
+/// primary server's corrected time, so a second device, such as a tablet
+/// showing the countdown on stage, can follow ticketime's clock.
+pub struct LanTime {
+    running: Mutex<Option<Running>>,
+}
+
+struct Running {
+    port: u16,
+    shutdown: CancellationToken,
+}
+
+impl LanTime {
+    pub fn new() -> Self {
+        Self {
+            running: Mutex::new(None),
+        }
+    }
+
+    /// Start, stop or rebind the responder so it matches `settings`.
+    pub fn apply(&self, app: &AppHandle, settings: &AppSettings) {
+        let mut running = self.running.lock().expect("lan time poisoned");
+        let wanted = settings.lan_time_enabled.then_some(settings.lan_time_port);
+
+        // A responder that could not bind has cancelled itself; asking for
+        // the same port again retries it.
+        let current = running.as_ref().filter(|r| !r.shutdown.is_cancelled());
+        if current.map(|r| r.port) == wanted {
+            return;
+        }
+        if let Some(current) = running.take() {
+            current.shutdown.cancel();
+        }
+        if let Some(port) = wanted {
+            let shutdown = CancellationToken::new();
+            tauri::async_runtime::spawn(serve(app.clone(), port, shutdown.clone()));
+            *running = Some(Running { port, shutdown });
+        }
+    }
+}
+
+impl Default for LanTime {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The clock being passed on: the primary server, as last read.
+struct Source {
+    server: Option<Server>,
+    extrapolate_drift: bool,
+}
+
+impl Source {
+    /// Read off the async runtime: the database lock may be held by a sync.
+    async fn load(app: &AppHandle) -> Self {
+        let handle = app.clone();
+        tokio::task::spawn_blocking(move || Self::read(&handle))
+            .await
+            .unwrap_or(Self {
+                server: None,
+                extrapolate_drift: true,
+            })
+    }
+
+    fn read(app: &AppHandle) -> Self {
+        let state = app.state::<AppState>();
+        let server = state
+            .db
+            .get_primary_server()
+            .ok()
+            .flatten()
+            .and_then(|id| state.db.get_server(id).ok());
+        Self {
+            server,
+            extrapolate_drift: state
+                .db
+                .get_settings()
+                .map(|s| s.extrapolate_drift)
+                .unwrap_or(true),
+        }
+    }
+
+    fn time(&self, now: DateTime<Utc>) -> Option<ServerTime> {
+        ServerTime::at(self.server.as_ref()?, now, self.extrapolate_drift)
+    }
+}
+
+async fn serve(app: AppHandle, port: u16, shutdown: CancellationToken) {
+    let socket = match UdpSocket::bind(("0.0.0.0", port)).await {
+        Ok(socket) => socket,
+        Err(e) => {
+            log::warn!("LAN time could not bind UDP port {port}: {e}");
+            shutdown.cancel();
+            return;
+        }
+    };
+    log::info!("LAN time answering SNTP on UDP port {port}");
+    let mdns = advertise(port);
+
+    // Reloaded beside the loop, so replies never wait on the database.
+    let (reloaded, source) = watch::channel(Source::load(&app).await);
+    tauri::async_runtime::spawn(reload(app, reloaded, shutdown.clone()));
+    let mut buf = [0u8; 512];
+    loop {
+        tokio::select! {
+            _ = shutdown.cancelled() => break,
+            received = socket.recv_from(&mut buf) => {
+                let received_at = Utc::now();
+                let Ok((len, peer)) = received else {
+                    continue;
+                };
+                let now = Utc::now();
+                let time = source.borrow().time(now);
+                let Some(packet) = reply(&buf[..len], time.as_ref(), received_at, now) else {
+                    continue;
+                };
+                if let Err(e) = socket.send_to(&packet, peer).await {
+                    log::debug!("LAN time reply to {peer} failed: {e}");
+                }
+            }
+        }
+    }
+    if let Some(daemon) = mdns {
+        let _ = daemon.shutdown();
+    }
+}
+
+async fn reload(app: AppHandle, source: watch::Sender<Source>, shutdown: CancellationToken) {
+    let mut interval = tokio::time::interval(RELOAD_EVERY);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    // The first tick is immediate, and `serve` has just loaded the source.
+    interval.tick().await;
+    loop {
+        tokio::select! {
+            _ = shutdown.cancelled() => break,
+            _ = interval.tick() => {
+                source.send_replace(Source::load(&app).await);
+            }
+        }
+    }
+}
+
+/// Announce the responder over mDNS. Serving goes on without it when the
+/// network refuses multicast; clients can still be pointed at the port.
+fn advertise(port: u16) -> Option<ServiceDaemon> {
+    let suffix = format!("{:04x}", rand::random::<u16>());
+    let registered = ServiceDaemon::new().and_then(|daemon| {
+        let info = ServiceInfo::new(
+            SERVICE_TYPE,
+            &format!("Ticketime {suffix}"),
+            &format!("ticketime-{suffix}.local."),
+            (),
+            port,
+            &[("proto", "sntp")][..],
+        )?
+        .enable_addr_auto();
+        daemon.register(info)?;
+        Ok(daemon)
+    });
+    match registered {
+        Ok(daemon) => Some(daemon),
+        Err(e) => {
+            log::warn!("LAN time could not be advertised over mDNS: {e}");
+            None
+        }
+    }
+}
+
+/// The SNTP answer to `request`, stamped with `time`'s corrected clock.
+/// `None` for anything but a client (mode 3) request. Without `time` the
+/// answer says the clock is unsynchronized.
+fn reply(
+    request: &[u8],
+    time: Option<&ServerTime>,
+    received_at: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Option<[u8; PACKET_LEN]> {
+    if request.len() < PACKET_LEN || request[0] & 0b111 != 3 {
+        return None;
+    }
+    let version = request[0] & 0b0011_1000;
+    let offset_ms = time.map_or(0.0, |t| t.offset_ms);
+    let stamp = |at: DateTime<Utc>| {
+        ntp::unix_ms_to_ntp(
+            timing::corrected_time(at, offset_ms).timestamp_micros() as f64 / 1000.0,
+        )
+    };
+
+    let mut packet = [0u8; PACKET_LEN];
+    let (leap, stratum) = match time {
+        Some(_) => (0, STRATUM),
+        None => (0b11, UNSYNCHRONIZED),
+    };
+    packet[0] = leap << 6 | version | 4;
+    packet[1] = stratum;
+    packet[2] = request[2];
+    packet[3] = PRECISION as u8;
+    let dispersion_ms = time.and_then(|t| t.uncertainty_ms).unwrap_or(0.0);
+    let dispersion = (dispersion_ms / 1000.0 * 65_536.0).min(u32::MAX as f64) as u32;
+    packet[8..12].copy_from_slice(&dispersion.to_be_bytes());
+    packet[12..16].copy_from_slice(REFERENCE_ID);
+    if let Some(synced_at) = time.and_then(|t| t.last_sync_at) {
+        packet[16..24].copy_from_slice(&stamp(synced_at));
+    }
+    packet[24..32].copy_from_slice(&request[40..48]);
+    packet[32..40].copy_from_slice(&stamp(received_at));
+    packet[40..48].copy_from_slice(&stamp(now));
+    Some(packet)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ServerStatus;
+
+    fn time(offset_ms: f64, now: DateTime<Utc>) -> ServerTime {
+        let server = Server {
+            id: 1,
+            url: "https://example.com".to_string(),
+            name: None,
+            offset_ms: Some(offset_ms),
+            last_sync_at: Some(now),
+            created_at: now,
+            status: ServerStatus::Synced,
+            extractor_type: "date_header".to_string(),
+            probe_method: "HEAD".to_string(),
+            domain_preset: None,
+            sync_profile: None,
+            fingerprint: None,
+            asymmetry: None,
+            sync_on_launch: false,
+            health_score: None,
+            drift: None,
+            min_request_interval_ms: None,
+            trusted: false,
+            user_agent: None,
+            transport: Default::default(),
+            timezone: None,
+            timezone_guessed: false,
+        };
+        ServerTime::at(&server, now, true).unwrap()
+    }
+
+    fn unix_ms(at: DateTime<Utc>) -> f64 {
+        at.timestamp_micros() as f64 / 1000.0
+    }
+
+    #[test]
+    fn clients_see_the_corrected_offset() {
+        let now = Utc::now();
+        let request = ntp::request_packet(unix_ms(now));
+        let packet = reply(&request, Some(&time(250.0, now)), now, now).unwrap();
+        assert_eq!(packet[0] & 0b111, 4);
+        assert_eq!(packet[1], STRATUM);
+
+        let (offset, round_trip) =
+            ntp::parse_response(&packet, unix_ms(now), unix_ms(now)).unwrap();
+        assert!((offset - 250.0).abs() < 0.01);
+        assert!(round_trip.abs() < 0.01);
+    }
+
+    #[test]
+    fn unsynced_and_non_client_packets() {
+        let now = Utc::now();
+        let request = ntp::request_packet(unix_ms(now));
+        let packet = reply(&request, None, now, now).unwrap();
+        assert_eq!(packet[0] >> 6, 0b11);
+        assert_eq!(packet[1], UNSYNCHRONIZED);
+
+        // A server reply bounced back, and a runt.
+        assert!(reply(&packet, None, now, now).is_none());
+        assert!(reply(&request[..20], None, now, now).is_none());
+    }
+}
@@ -0,0 +1,199 @@
+use crate::models::{Server, SyncResult};
+use crate::server_timezone;
+use chrono::{DateTime, Datelike, Local, Timelike};
+use serde::Serialize;
+
+/// Syncs an hour needs before it can be called the quietest.
+const MIN_SYNCS: usize = 3;
+
+/// The round trips of the syncs that ran in one hour of the day or on one
+/// day of the week.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LatencyBucket {
+    /// Hour 0–23, or weekday 0–6 from Monday.
+    pub slot: u32,
+    pub syncs: usize,
+    /// Median of the syncs' median RTTs.
+    pub median_rtt_ms: Option<f64>,
+    /// Median of the syncs' RTT spreads (IQR). The binary search cannot
+    /// narrow the offset below it, so it decides how precise a sync gets.
+    pub jitter_ms: Option<f64>,
+}
+
+/// A server's stored latency profiles by when they were measured.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LatencyStats {
+    pub server_id: i64,
+    /// Zone the hours and days are in: the server's, so they line up with
+    /// on-sale times, or `None` for this machine's when it has none.
+    pub timezone: Option<String>,
+    pub syncs: usize,
+    pub by_hour: Vec<LatencyBucket>,
+    pub by_weekday: Vec<LatencyBucket>,
+    /// The hour with the least jitter, among those with enough syncs.
+    pub quietest_hour: Option<u32>,
+}
+
+pub fn compute(server: &Server, history: &[SyncResult]) -> LatencyStats {
+    let zone = server_timezone::of(server);
+    let slots: Vec<(u32, u32)> = history
+        .iter()
+        .map(|r| match zone {
+            Some(tz) => hour_and_weekday(r.synced_at.with_timezone(&tz)),
+            None => hour_and_weekday(r.synced_at.with_timezone(&Local)),
+        })
+        .collect();
+
+    let bucket = |slot: u32, of: fn(&(u32, u32)) -> u32| {
+        let profiles: Vec<_> = history
+            .iter()
+            .zip(&slots)
+            .filter(|(_, s)| of(s) == slot)
+            .map(|(r, _)| &r.latency_profile)
+            .collect();
+        LatencyBucket {
+            slot,
+            syncs: profiles.len(),
+            median_rtt_ms: median(profiles.iter().map(|p| p.median * 1000.0).collect()),
+            jitter_ms: median(profiles.iter().map(|p| p.iqr() * 1000.0).collect()),
+        }
+    };
+    let by_hour: Vec<LatencyBucket> = (0..24).map(|h| bucket(h, |s| s.0)).collect();
+    let by_weekday = (0..7).map(|d| bucket(d, |s| s.1)).collect();
+
+    let quietest_hour = by_hour
+        .iter()
+        .filter(|b| b.syncs >= MIN_SYNCS)
+        .filter_map(|b| Some((b.slot, b.jitter_ms?, b.median_rtt_ms?)))
+        .min_by(|a, b| (a.1, a.2).partial_cmp(&(b.1, b.2)).unwrap())
+        .map(|(slot, _, _)| slot);
+
+    LatencyStats {
+        server_id: server.id,
+        timezone: zone.map(|tz| tz.name().to_string()),
+        syncs: history.len(),
+        by_hour,
+        by_weekday,
+        quietest_hour,
+    }
+}
+
+fn hour_and_weekday<Z: chrono::TimeZone>(at: DateTime<Z>) -> (u32, u32) {
+    (at.hour(), at.weekday().num_days_from_monday())
+}
+
+fn median(mut values: Vec<f64>) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let mid = values.len() / 2;
+    Some(if values.len() % 2 == 0 {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{LatencyProfile, ServerStatus, SyncPhase};
+    use chrono::Utc;
+
+    fn server(timezone: Option<&str>) -> Server {
+        Server {
+            id: 1,
+            url: "https://example.com".to_string(),
+            name: None,
+            offset_ms: None,
+            last_sync_at: None,
+            created_at: Utc::now(),
+            status: ServerStatus::Idle,
+            extractor_type: "date_header".to_string(),
+            probe_method: "HEAD".to_string(),
+            domain_preset: None,
+            sync_profile: None,
+            fingerprint: None,
+            asymmetry: None,
+            sync_on_launch: false,
+            health_score: None,
+            drift: None,
+            min_request_interval_ms: None,
+            trusted: false,
+            user_agent: None,
+            transport: Default::default(),
+            timezone: timezone.map(str::to_string),
+            timezone_guessed: false,
+        }
+    }
+
+    fn result(synced_at: &str, median: f64, iqr: f64) -> SyncResult {
+        SyncResult {
+            server_id: 1,
+            whole_second_offset: 0,
+            subsecond_offset: 0.0,
+            total_offset_ms: 0.0,
+            latency_profile: LatencyProfile {
+                min: median - iqr,
+                q1: median - iqr / 2.0,
+                median,
+                mean: median,
+                q3: median + iqr / 2.0,
+                max: median + iqr,
+                breakdown: None,
+            },
+            verified: true,
+            synced_at: synced_at.parse().unwrap(),
+            duration_ms: 1000,
+            phase_reached: SyncPhase::Complete,
+            fingerprint: None,
+            response: None,
+            id: 0,
+            timeline: Vec::new(),
+            note: None,
+            asymmetry: None,
+            anomalous: false,
+            clock_issue: None,
+            network: None,
+            grade: None,
+            precision_ms: None,
+        }
+    }
+
+    #[test]
+    fn buckets_follow_the_server_zone() {
+        // 01:00 UTC is 10:00 in Seoul, on a Monday.
+        let history = [
+            result("2025-06-02T01:00:00Z", 0.040, 0.004),
+            result("2025-06-02T01:30:00Z", 0.060, 0.008),
+        ];
+        let stats = compute(&server(Some("Asia/Seoul")), &history);
+        assert_eq!(stats.timezone.as_deref(), Some("Asia/Seoul"));
+        assert_eq!(stats.syncs, 2);
+        let ten = &stats.by_hour[10];
+        assert_eq!(ten.syncs, 2);
+        assert!((ten.median_rtt_ms.unwrap() - 50.0).abs() < 1e-9);
+        assert!((ten.jitter_ms.unwrap() - 6.0).abs() < 1e-9);
+        assert_eq!(stats.by_weekday[0].syncs, 2);
+        assert_eq!(stats.by_hour[1].median_rtt_ms, None);
+    }
+
+    #[test]
+    fn quietest_hour_needs_enough_syncs() {
+        let tokyo = server(Some("Asia/Tokyo"));
+        // Busy evenings at 20:00 Tokyo, calm mornings at 06:00, and one
+        // lucky sync at 03:00 that is too little to go on.
+        let mut history = Vec::new();
+        for day in 1..=3 {
+            history.push(result(&format!("2025-06-0{day}T11:00:00Z"), 0.080, 0.030));
+            history.push(result(&format!("2025-06-0{day}T21:00:00Z"), 0.045, 0.002));
+        }
+        history.push(result("2025-06-01T18:00:00Z", 0.030, 0.001));
+
+        let stats = compute(&tokyo, &history);
+        assert_eq!(stats.quietest_hour, Some(6));
+        assert_eq!(stats.by_hour[3].syncs, 1);
+        assert_eq!(compute(&tokyo, &history[..1]).quietest_hour, None);
+    }
+}
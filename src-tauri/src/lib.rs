@@ -1,19 +1,98 @@
+mod alerts;
+mod anomaly;
+mod asymmetry;
+mod bulk_add;
+mod certificate;
+mod cli;
+mod clock_adjust;
+mod clock_issue;
 mod commands;
+mod comparison;
+mod countdown;
+mod countdown_stream;
+mod dashboard;
 mod db;
+mod deep_link;
+mod domain_presets;
+mod drift;
 mod error;
+mod export_crypto;
+mod favicon;
+mod fingerprint;
+mod health;
+mod host_limiter;
+#[cfg(desktop)]
+mod instance;
+mod interception;
+mod keep_warm;
+mod lan_time;
+mod latency_stats;
+mod local_api;
+mod local_ntp;
 mod models;
+mod network_baseline;
+mod network_context;
+mod network_watch;
+mod ntp;
+mod offset_series;
+mod power;
+mod preflight;
+mod probe_timing;
+mod progress_coalescer;
+mod reference_sources;
+mod scheduler;
+mod scripting;
+mod server_name;
+mod server_timezone;
+mod server_url;
+mod settings_export;
+mod settings_validation;
+mod share;
+mod simulation;
+mod speech;
+mod standby;
 mod state;
 mod sync_engine;
+mod sync_eta;
+mod sync_grade;
+mod sync_profile;
+mod sync_recording;
+mod sync_report;
+mod target_time;
+mod taskbar;
 mod time_extractor;
+mod time_format;
+mod time_source;
 mod timing;
+mod tray;
+mod trigger;
+mod wasm_extractor;
+mod watchdog;
+mod workspace;
+mod world_clock;
 
 use db::Database;
 use state::AppState;
 use tauri::Manager;
+use tauri_plugin_deep_link::DeepLinkExt;
+
+/// Handle a headless CLI invocation (`ticketime list`, `sync`, `time`).
+/// Returns the exit code, or `None` if `args` should launch the GUI.
+pub fn run_cli(args: &[String]) -> Option<i32> {
+    cli::run(args)
+}
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    tauri::Builder::default()
+    let builder = tauri::Builder::default();
+
+    // Must be the first plugin so a second launch exits before touching the DB.
+    #[cfg(desktop)]
+    let builder = builder.plugin(tauri_plugin_single_instance::init(
+        instance::on_second_instance,
+    ));
+
+    builder
         .setup(|app| {
             if cfg!(debug_assertions) {
                 app.handle().plugin(
@@ -24,23 +103,149 @@ pub fn run() {
             }
 
             let db = Database::new(app.handle())?;
+            let settings = db.get_settings()?;
+            let expired = chrono::Utc::now() - settings.trash_retention();
+            match db.empty_trash(Some(expired)) {
+                Ok(0) => {}
+                Ok(purged) => log::info!("purged {purged} server(s) from the trash"),
+                Err(e) => log::warn!("failed to purge the trash: {e}"),
+            }
+            if let Err(e) = drift::backfill(&db) {
+                log::warn!("failed to fit drift for synced servers: {e}");
+            }
             let app_state = AppState::new(db);
+            app_state.local_api.apply(app.handle(), &settings);
+            app_state.lan_time.apply(app.handle(), &settings);
+            app_state.hooks.load_saved(app.handle());
+            if let Ok(dir) = wasm_extractor::plugin_dir(app.handle()) {
+                app_state.extractors.load_plugins(&dir);
+            }
             app.manage(app_state);
 
+            tray::init(app.handle())?;
+            scheduler::start(app.handle());
+            scheduler::sync_on_launch(app.handle());
+            alerts::start(app.handle());
+            keep_warm::start(app.handle());
+            standby::start(app.handle());
+            trigger::start(app.handle());
+            network_watch::start(app.handle());
+
+            // Installed bundles register the scheme themselves; dev builds
+            // and AppImages on Linux/Windows need it done at runtime.
+            #[cfg(any(windows, target_os = "linux"))]
+            if let Err(e) = app.deep_link().register_all() {
+                log::warn!("failed to register deep link scheme: {e}");
+            }
+
+            let handle = app.handle().clone();
+            app.deep_link().on_open_url(move |event| {
+                for url in event.urls() {
+                    deep_link::handle(&handle, &url);
+                }
+            });
+            // Links that launched the app arrive before the listener exists.
+            if let Ok(Some(urls)) = app.deep_link().get_current() {
+                for url in urls {
+                    deep_link::handle(app.handle(), &url);
+                }
+            }
+
             Ok(())
         })
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
+        .plugin(tauri_plugin_notification::init())
         .invoke_handler(tauri::generate_handler![
             commands::add_server,
+            commands::refresh_server_name,
+            commands::get_server_favicon,
+            commands::add_servers_from_text,
+            commands::validate_url,
+            commands::test_connection,
+            commands::list_sync_profiles,
+            commands::set_server_sync_profile,
+            commands::set_server_asymmetry,
+            commands::set_server_request_interval,
+            commands::set_server_user_agent,
+            commands::set_server_transport,
+            commands::set_server_sync_on_launch,
+            commands::set_server_trusted,
+            commands::set_server_timezone,
+            commands::calibrate_asymmetry,
+            commands::add_demo_server,
+            commands::list_reference_sources,
+            commands::add_reference_source,
+            commands::list_domain_presets,
+            commands::estimate_offset,
+            commands::benchmark_sync,
+            commands::list_benchmark_reports,
+            commands::list_sync_recordings,
+            commands::replay_sync_recording,
+            commands::set_server_domain_preset,
+            commands::list_extractors,
+            commands::reload_extractor_plugins,
+            commands::set_server_extractor,
             commands::get_server,
             commands::list_servers,
             commands::delete_server,
+            commands::list_trash,
+            commands::restore_server,
+            commands::purge_server,
+            commands::empty_trash,
+            commands::get_dashboard,
+            commands::get_primary_server,
+            commands::set_primary_server,
+            commands::get_reference_server,
+            commands::set_reference_server,
+            commands::get_relative_offsets,
+            commands::compare_servers,
+            commands::create_target,
+            commands::list_targets,
+            commands::get_time_until,
+            commands::set_target_alert_method,
+            commands::get_hook_script,
+            commands::save_hook_script,
+            commands::delete_target,
+            commands::schedule_sync,
+            commands::list_scheduled_syncs,
+            commands::cancel_scheduled_sync,
+            commands::request_notification_permission,
             commands::start_sync,
             commands::cancel_sync,
             commands::get_sync_history,
+            commands::get_sync_history_page,
+            commands::get_error_history,
+            commands::get_latency_stats,
+            commands::generate_sync_report,
+            commands::export_offset_series,
+            commands::write_export,
+            commands::start_countdown_stream,
+            commands::stop_countdown_stream,
+            commands::annotate_sync_result,
+            commands::compare_sync_results,
             commands::get_settings,
             commands::update_settings,
+            commands::export_settings,
+            commands::import_settings,
+            commands::export_share_payload,
+            commands::import_share_payload,
+            commands::list_workspaces,
+            commands::switch_workspace,
+            commands::factory_reset,
+            commands::format_time,
+            commands::get_corrected_time,
+            commands::get_world_clock,
+            commands::measure_clock_error,
+            commands::check_network_baseline,
+            commands::get_local_ntp_status,
+            commands::adjust_system_clock,
+            commands::get_scheduler_status,
+            commands::pause_scheduler,
+            commands::resume_scheduler,
+            commands::get_local_api_token,
+            commands::regenerate_local_api_token,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,523 @@
+use crate::commands;
+use crate::error::AppError;
+use crate::models::{AppSettings, Server, ServerTime, SyncEvent};
+use crate::state::AppState;
+use crate::time_format::TimeFormat;
+use crate::trigger::TargetFired;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Path, Query, Request, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use chrono::{DateTime, Utc};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::sync::broadcast;
+use tokio::time::MissedTickBehavior;
+use tokio_util::sync::CancellationToken;
+
+/// Optional localhost HTTP server exposing corrected time to scripts.
+/// Every request must carry the API token, either as
+/// `Authorization: Bearer <token>` or as a `?token=` query parameter.
+pub struct LocalApi {
+    running: Mutex<Option<Running>>,
+    sync_events: broadcast::Sender<SyncEvent>,
+    target_events: broadcast::Sender<TargetFired>,
+}
+
+struct Running {
+    port: u16,
+    shutdown: CancellationToken,
+}
+
+impl LocalApi {
+    pub fn new() -> Self {
+        let (sync_events, _) = broadcast::channel(SYNC_EVENT_BUFFER);
+        let (target_events, _) = broadcast::channel(TARGET_EVENT_BUFFER);
+        Self {
+            running: Mutex::new(None),
+            sync_events,
+            target_events,
+        }
+    }
+
+    /// Sender that fans sync events out to connected WebSocket clients.
+    /// Sending with no subscribers is a cheap no-op.
+    pub fn sync_events(&self) -> broadcast::Sender<SyncEvent> {
+        self.sync_events.clone()
+    }
+
+    /// Sender for `target_fired` messages to connected WebSocket clients.
+    pub fn target_events(&self) -> broadcast::Sender<TargetFired> {
+        self.target_events.clone()
+    }
+
+    /// Start, stop or rebind the server so it matches `settings`.
+    pub fn apply(&self, app: &AppHandle, settings: &AppSettings) {
+        let mut running = self.running.lock().expect("local api poisoned");
+        let wanted = settings
+            .local_api_enabled
+            .then_some(settings.local_api_port);
+
+        // A server that could not bind has cancelled itself; asking for the
+        // same port again retries it.
+        let current = running.as_ref().filter(|r| !r.shutdown.is_cancelled());
+        if current.map(|r| r.port) == wanted {
+            return;
+        }
+        if let Some(current) = running.take() {
+            current.shutdown.cancel();
+        }
+        if let Some(port) = wanted {
+            let shutdown = CancellationToken::new();
+            tauri::async_runtime::spawn(serve(app.clone(), port, shutdown.clone()));
+            *running = Some(Running { port, shutdown });
+        }
+    }
+}
+
+impl Default for LocalApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+async fn serve(app: AppHandle, port: u16, shutdown: CancellationToken) {
+    let listener = match tokio::net::TcpListener::bind(("127.0.0.1", port)).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            log::warn!("local API could not bind 127.0.0.1:{port}: {e}");
+            shutdown.cancel();
+            return;
+        }
+    };
+    log::info!("local API listening on 127.0.0.1:{port}");
+
+    let result = axum::serve(listener, router(app))
+        .with_graceful_shutdown(shutdown.cancelled_owned())
+        .await;
+    if let Err(e) = result {
+        log::warn!("local API stopped: {e}");
+    }
+}
+
+fn router(app: AppHandle) -> Router {
+    Router::new()
+        .route("/servers", get(list_servers))
+        .route("/time/{id}", get(server_time))
+        .route("/sync/{id}", post(start_sync))
+        .route("/ws", get(websocket))
+        .layer(middleware::from_fn_with_state(app.clone(), require_token))
+        .with_state(app)
+}
+
+// ── Auth ──
+
+/// Generate a fresh random API token (64 hex chars).
+pub(crate) fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+async fn require_token(
+    State(app): State<AppHandle>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let expected = app
+        .state::<AppState>()
+        .db
+        .get_local_api_token()?
+        .ok_or_else(|| ApiError::new(StatusCode::UNAUTHORIZED, "API token not configured"))?;
+
+    match request_token(request.headers(), request.uri().query()) {
+        Some(token) if constant_time_eq(token.as_bytes(), expected.as_bytes()) => {
+            Ok(next.run(request).await)
+        }
+        _ => Err(ApiError::new(
+            StatusCode::UNAUTHORIZED,
+            "missing or invalid token",
+        )),
+    }
+}
+
+fn request_token<'a>(headers: &'a HeaderMap, query: Option<&'a str>) -> Option<&'a str> {
+    let from_header = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    from_header.or_else(|| {
+        query?
+            .split('&')
+            .find_map(|pair| pair.strip_prefix("token="))
+    })
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+// ── Handlers ──
+
+async fn list_servers(State(app): State<AppHandle>) -> Result<Json<Vec<Server>>, ApiError> {
+    Ok(Json(app.state::<AppState>().db.list_servers()?))
+}
+
+async fn server_time(
+    State(app): State<AppHandle>,
+    Path(id): Path<i64>,
+) -> Result<Json<ServerTime>, ApiError> {
+    let state = app.state::<AppState>();
+    let server = state.db.get_server(id)?;
+    let extrapolate_drift = state.db.get_settings()?.extrapolate_drift;
+    time_response(
+        &server,
+        Utc::now(),
+        state.scheduler.status().offline,
+        extrapolate_drift,
+    )
+    .map(Json)
+}
+
+fn time_response(
+    server: &Server,
+    now: DateTime<Utc>,
+    offline: bool,
+    extrapolate_drift: bool,
+) -> Result<ServerTime, ApiError> {
+    let time =
+        ServerTime::at(server, now, extrapolate_drift).ok_or(AppError::NotSynced(server.id))?;
+    Ok(time.offline(offline))
+}
+
+async fn start_sync(
+    State(app): State<AppHandle>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, ApiError> {
+    let already_running = app
+        .state::<AppState>()
+        .active_syncs
+        .lock()
+        .expect("active_syncs poisoned")
+        .contains_key(&id);
+    if already_running {
+        return Err(ApiError::new(StatusCode::CONFLICT, "sync already running"));
+    }
+
+    commands::spawn_sync(&app, id, commands::broadcast_sink(&app))?;
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(serde_json::json!({ "server_id": id, "status": "started" })),
+    ))
+}
+
+// ── WebSocket ──
+
+/// Progress events arrive in bursts during binary search; slow clients that
+/// fall further behind than this skip ahead instead of stalling the sync.
+const SYNC_EVENT_BUFFER: usize = 256;
+const TARGET_EVENT_BUFFER: usize = 16;
+const DEFAULT_TICK_MS: u64 = 1000;
+const MIN_TICK_MS: u64 = 50;
+const MAX_TICK_MS: u64 = 60_000;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+enum WsMessage {
+    Tick(Vec<TickEntry>),
+    Sync(Box<SyncEvent>),
+    TargetFired(TargetFired),
+}
+
+/// One server's reading in a `tick`, plus the same rendering the tray and
+/// `format_time` produce.
+#[derive(Debug, Clone, Serialize)]
+struct TickEntry {
+    #[serde(flatten)]
+    time: ServerTime,
+    formatted: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct WsParams {
+    interval_ms: Option<u64>,
+}
+
+/// `GET /ws[?interval_ms=N]` streams a `tick` with every synced server's
+/// corrected time each interval, plus every `SyncEvent` as `sync` and every
+/// armed target coming up as `target_fired`.
+async fn websocket(
+    State(app): State<AppHandle>,
+    Query(params): Query<WsParams>,
+    upgrade: WebSocketUpgrade,
+) -> Response {
+    let interval = tick_interval(params.interval_ms);
+    let local_api = &app.state::<AppState>().local_api;
+    let events = local_api.sync_events.subscribe();
+    let fired = local_api.target_events.subscribe();
+    upgrade.on_upgrade(move |socket| stream_updates(app, socket, interval, events, fired))
+}
+
+fn tick_interval(requested_ms: Option<u64>) -> Duration {
+    Duration::from_millis(
+        requested_ms
+            .unwrap_or(DEFAULT_TICK_MS)
+            .clamp(MIN_TICK_MS, MAX_TICK_MS),
+    )
+}
+
+async fn stream_updates(
+    app: AppHandle,
+    mut socket: WebSocket,
+    interval: Duration,
+    mut events: broadcast::Receiver<SyncEvent>,
+    mut fired: broadcast::Receiver<TargetFired>,
+) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+    loop {
+        let message = tokio::select! {
+            // A target firing goes out ahead of anything else that is ready.
+            biased;
+            event = fired.recv() => match event {
+                Ok(event) => WsMessage::TargetFired(event),
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            _ = ticker.tick() => {
+                let state = app.state::<AppState>();
+                let servers = state.db.list_servers().unwrap_or_default();
+                let settings = state.db.get_settings().unwrap_or_default();
+                let format = TimeFormat::from_settings(&settings);
+                let offline = state.scheduler.status().offline;
+                WsMessage::Tick(tick_payload(
+                    &servers,
+                    Utc::now(),
+                    offline,
+                    settings.extrapolate_drift,
+                    &format,
+                ))
+            }
+            event = events.recv() => match event {
+                Ok(event) => WsMessage::Sync(Box::new(event)),
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    log::debug!("websocket client lagged, skipped {skipped} sync events");
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | None | Some(Err(_)) => break,
+                Some(Ok(_)) => continue,
+            },
+        };
+
+        let Ok(text) = serde_json::to_string(&message) else {
+            continue;
+        };
+        if socket.send(Message::Text(text.into())).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// Corrected time for every server that has an offset.
+fn tick_payload(
+    servers: &[Server],
+    now: DateTime<Utc>,
+    offline: bool,
+    extrapolate_drift: bool,
+    format: &TimeFormat,
+) -> Vec<TickEntry> {
+    servers
+        .iter()
+        .filter_map(|server| ServerTime::at(server, now, extrapolate_drift))
+        .map(|time| time.offline(offline))
+        .map(|time| TickEntry {
+            formatted: format.format_local(time.corrected_time),
+            time,
+        })
+        .collect()
+}
+
+// ── Errors ──
+
+#[derive(Debug)]
+struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<AppError> for ApiError {
+    fn from(e: AppError) -> Self {
+        let status = match e {
+            AppError::Db(rusqlite::Error::QueryReturnedNoRows) => StatusCode::NOT_FOUND,
+            AppError::NotSynced(_) => StatusCode::CONFLICT,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        Self::new(status, e.to_string())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = Json(serde_json::json!({ "error": self.message }));
+        (self.status, body).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ServerStatus;
+    use axum::http::HeaderValue;
+
+    fn make_server(offset_ms: Option<f64>) -> Server {
+        Server {
+            id: 7,
+            url: "https://example.com".to_string(),
+            name: None,
+            offset_ms,
+            last_sync_at: None,
+            created_at: Utc::now(),
+            status: ServerStatus::Idle,
+            extractor_type: "date_header".to_string(),
+            probe_method: "HEAD".to_string(),
+            domain_preset: None,
+            sync_profile: None,
+            fingerprint: None,
+            asymmetry: None,
+            sync_on_launch: false,
+            health_score: None,
+            drift: None,
+            min_request_interval_ms: None,
+            trusted: false,
+            user_agent: None,
+            transport: Default::default(),
+            timezone: None,
+            timezone_guessed: false,
+        }
+    }
+
+    #[test]
+    fn generate_token_is_64_hex_chars_and_unique() {
+        let a = generate_token();
+        let b = generate_token();
+        assert_eq!(a.len(), 64);
+        assert!(a.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn request_token_prefers_bearer_header() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer abc"),
+        );
+        assert_eq!(request_token(&headers, Some("token=xyz")), Some("abc"));
+    }
+
+    #[test]
+    fn request_token_falls_back_to_query() {
+        let headers = HeaderMap::new();
+        assert_eq!(
+            request_token(&headers, Some("foo=1&token=xyz")),
+            Some("xyz")
+        );
+        assert_eq!(request_token(&headers, Some("foo=1")), None);
+        assert_eq!(request_token(&headers, None), None);
+    }
+
+    #[test]
+    fn constant_time_eq_compares_contents_and_length() {
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secrets"));
+    }
+
+    #[test]
+    fn time_response_applies_offset() {
+        let server = make_server(Some(1500.0));
+        let now = Utc::now();
+        let body = time_response(&server, now, false, true).unwrap();
+        assert_eq!(body.server_id, 7);
+        assert_eq!(body.corrected_unix_ms, now.timestamp_millis() + 1500);
+    }
+
+    #[test]
+    fn time_response_flags_stale_offsets() {
+        let now = Utc::now();
+        let fresh = Server {
+            last_sync_at: Some(now - chrono::Duration::minutes(5)),
+            ..make_server(Some(1500.0))
+        };
+        let body = time_response(&fresh, now, false, true).unwrap();
+        assert_eq!(body.offset_age_ms, Some(300_000));
+        assert!(!body.stale);
+        assert!(time_response(&fresh, now, true, true).unwrap().stale);
+        // Never-synced timestamps are no basis for extrapolation either.
+        assert!(
+            time_response(&make_server(Some(1500.0)), now, false, true)
+                .unwrap()
+                .stale
+        );
+    }
+
+    #[test]
+    fn time_response_unsynced_server_is_conflict() {
+        let err = time_response(&make_server(None), Utc::now(), false, true).unwrap_err();
+        assert_eq!(err.status, StatusCode::CONFLICT);
+    }
+
+    #[test]
+    fn tick_payload_skips_unsynced_servers() {
+        let synced = make_server(Some(10.0));
+        let unsynced = Server {
+            id: 8,
+            ..make_server(None)
+        };
+        let format = TimeFormat::from_settings(&AppSettings::default());
+        let ticks = tick_payload(&[synced, unsynced], Utc::now(), false, true, &format);
+        assert_eq!(ticks.len(), 1);
+        assert_eq!(ticks[0].time.server_id, 7);
+        assert!(!ticks[0].formatted.is_empty());
+    }
+
+    #[test]
+    fn tick_interval_defaults_and_clamps() {
+        assert_eq!(tick_interval(None), Duration::from_millis(1000));
+        assert_eq!(tick_interval(Some(1)), Duration::from_millis(50));
+        assert_eq!(tick_interval(Some(250)), Duration::from_millis(250));
+        assert_eq!(tick_interval(Some(u64::MAX)), Duration::from_millis(60_000));
+    }
+
+    #[test]
+    fn ws_messages_are_tagged_by_type() {
+        let v = serde_json::to_value(WsMessage::Tick(vec![])).unwrap();
+        assert_eq!(v["type"], "tick");
+        assert!(v["data"].as_array().unwrap().is_empty());
+    }
+
+    #[test]
+    fn missing_server_maps_to_not_found() {
+        let err = ApiError::from(AppError::Db(rusqlite::Error::QueryReturnedNoRows));
+        assert_eq!(err.status, StatusCode::NOT_FOUND);
+    }
+}
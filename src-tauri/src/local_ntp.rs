@@ -0,0 +1,227 @@
+use serde::Serialize;
+
+/// What the machine's own time daemon reports, shown next to our
+/// measurements: a synced daemon and an off server means the server is
+/// off, and the reverse means it's the local clock.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LocalNtpStatus {
+    /// `chrony`, `timesyncd` or `ntpd`.
+    pub daemon: &'static str,
+    pub synchronized: bool,
+    /// The upstream server the daemon is following.
+    pub server: Option<String>,
+    pub stratum: Option<u8>,
+    /// Reference minus local, like `NtpMeasurement`: positive means the
+    /// local clock is behind.
+    pub offset_ms: Option<f64>,
+    pub jitter_ms: Option<f64>,
+}
+
+/// A query program, its arguments and the parser for what it prints.
+type Daemon = (
+    &'static str,
+    &'static [&'static str],
+    fn(&str) -> Option<LocalNtpStatus>,
+);
+
+/// Daemons in the order they are asked. Only one is normally running; the
+/// others fail fast.
+#[cfg_attr(not(any(target_os = "linux", target_os = "macos")), allow(dead_code))]
+const DAEMONS: [Daemon; 3] = [
+    ("chronyc", &["tracking"], parse_chrony),
+    ("timedatectl", &["timesync-status"], parse_timesyncd),
+    ("ntpq", &["-c", "rv"], parse_ntpd),
+];
+
+/// The first running daemon's state, or `None` when there is none we can
+/// read (or on Windows, whose time service we don't query).
+pub async fn status() -> Option<LocalNtpStatus> {
+    tokio::task::spawn_blocking(query).await.ok().flatten()
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn query() -> Option<LocalNtpStatus> {
+    use std::process::{Command, Stdio};
+
+    DAEMONS.into_iter().find_map(|(program, args, parse)| {
+        let out = Command::new(program)
+            .args(args)
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output()
+            .ok()?;
+        if !out.status.success() {
+            return None;
+        }
+        parse(&String::from_utf8_lossy(&out.stdout))
+    })
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn query() -> Option<LocalNtpStatus> {
+    None
+}
+
+/// `Label : value` lines, as chronyc and timedatectl print them.
+fn field<'a>(text: &'a str, label: &str) -> Option<&'a str> {
+    text.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        (key.trim() == label).then(|| value.trim())
+    })
+}
+
+/// `chronyc tracking`. The system time line says which way the clock is
+/// off; RMS offset stands in for jitter.
+fn parse_chrony(text: &str) -> Option<LocalNtpStatus> {
+    let system = field(text, "System time")?;
+    let mut words = system.split_whitespace();
+    let seconds: f64 = words.next()?.parse().ok()?;
+    let sign = match words.nth(1)? {
+        "slow" => 1.0,
+        "fast" => -1.0,
+        _ => return None,
+    };
+    let seconds_field = |label| {
+        field(text, label)?
+            .split_whitespace()
+            .next()?
+            .parse::<f64>()
+            .ok()
+    };
+    let server = field(text, "Reference ID").and_then(|r| {
+        let name = r.split_once('(')?.1.trim_end_matches(')');
+        (!name.is_empty()).then(|| name.to_string())
+    });
+    Some(LocalNtpStatus {
+        daemon: "chrony",
+        synchronized: field(text, "Leap status").is_some_and(|l| l != "Not synchronised"),
+        server,
+        stratum: field(text, "Stratum").and_then(|s| s.parse().ok()),
+        offset_ms: Some(sign * seconds * 1000.0),
+        jitter_ms: seconds_field("RMS offset").map(|s| s * 1000.0),
+    })
+}
+
+/// `timedatectl timesync-status` (systemd-timesyncd). Offsets are already
+/// reference minus local.
+fn parse_timesyncd(text: &str) -> Option<LocalNtpStatus> {
+    let server = field(text, "Server")?;
+    let offset_ms = field(text, "Offset").and_then(parse_timespan_ms);
+    let server = server
+        .split_once('(')
+        .map_or(server, |(_, name)| name.trim_end_matches(')'));
+    Some(LocalNtpStatus {
+        daemon: "timesyncd",
+        synchronized: offset_ms.is_some()
+            && field(text, "Leap").is_some_and(|l| l != "not synchronized"),
+        server: Some(server.to_string()),
+        stratum: field(text, "Stratum").and_then(|s| s.parse().ok()),
+        offset_ms,
+        jitter_ms: field(text, "Jitter").and_then(parse_timespan_ms),
+    })
+}
+
+/// systemd's short time spans: `-1.215ms`, `345us`, `1.2s`.
+fn parse_timespan_ms(value: &str) -> Option<f64> {
+    let (number, scale) = if let Some(n) = value.strip_suffix("us") {
+        (n, 1e-3)
+    } else if let Some(n) = value.strip_suffix("µs") {
+        (n, 1e-3)
+    } else if let Some(n) = value.strip_suffix("ms") {
+        (n, 1.0)
+    } else if let Some(n) = value.strip_suffix('s') {
+        (n, 1e3)
+    } else {
+        return None;
+    };
+    number.parse::<f64>().ok().map(|n| n * scale)
+}
+
+/// `ntpq -c rv`: comma-separated `key=value` system variables, offset and
+/// jitter in milliseconds.
+fn parse_ntpd(text: &str) -> Option<LocalNtpStatus> {
+    let var = |key: &str| {
+        text.split([',', '\n'])
+            .filter_map(|pair| pair.trim().split_once('='))
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| v.trim().trim_matches('"'))
+    };
+    let number = |key| var(key).and_then(|v| v.parse::<f64>().ok());
+    let stratum: Option<u8> = var("stratum").and_then(|s| s.parse().ok());
+    Some(LocalNtpStatus {
+        daemon: "ntpd",
+        // Leap 11 is the alarm state: not synchronized. Stratum 16 means
+        // no usable source.
+        synchronized: var("leap")? != "11" && stratum.is_some_and(|s| s < 16),
+        server: var("refid").map(str::to_string),
+        stratum,
+        offset_ms: number("offset"),
+        jitter_ms: number("sys_jitter"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chrony_tracking() {
+        let text = "Reference ID    : A29FC87B (time.cloudflare.com)\n\
+                    Stratum         : 4\n\
+                    System time     : 0.000412000 seconds slow of NTP time\n\
+                    Last offset     : -0.000012345 seconds\n\
+                    RMS offset      : 0.000045000 seconds\n\
+                    Leap status     : Normal\n";
+        let status = parse_chrony(text).unwrap();
+        assert!(status.synchronized);
+        assert_eq!(status.server.as_deref(), Some("time.cloudflare.com"));
+        assert_eq!(status.stratum, Some(4));
+        assert!((status.offset_ms.unwrap() - 0.412).abs() < 1e-9);
+        assert!((status.jitter_ms.unwrap() - 0.045).abs() < 1e-9);
+
+        let fast = text.replace("slow", "fast");
+        assert!(parse_chrony(&fast).unwrap().offset_ms.unwrap() < 0.0);
+
+        let unsynced = "Reference ID    : 00000000 ()\n\
+                        Stratum         : 0\n\
+                        System time     : 0.000000000 seconds fast of NTP time\n\
+                        Leap status     : Not synchronised\n";
+        let status = parse_chrony(unsynced).unwrap();
+        assert!(!status.synchronized);
+        assert_eq!(status.server, None);
+    }
+
+    #[test]
+    fn timesyncd_status() {
+        let text = "       Server: 185.125.190.56 (ntp.ubuntu.com)\n\
+                    Poll interval: 34min 8s (min: 32s; max 34min 8s)\n\
+                             Leap: normal\n\
+                          Stratum: 2\n\
+                           Offset: -1.215ms\n\
+                           Jitter: 350us\n";
+        let status = parse_timesyncd(text).unwrap();
+        assert!(status.synchronized);
+        assert_eq!(status.server.as_deref(), Some("ntp.ubuntu.com"));
+        assert_eq!(status.stratum, Some(2));
+        assert!((status.offset_ms.unwrap() + 1.215).abs() < 1e-9);
+        assert!((status.jitter_ms.unwrap() - 0.35).abs() < 1e-9);
+        assert_eq!(parse_timespan_ms("+1.5s"), Some(1500.0));
+        assert_eq!(parse_timespan_ms("34min 8s"), None);
+    }
+
+    #[test]
+    fn ntpd_system_variables() {
+        let text = "associd=0 status=0615 leap_none, sync_ntp, 1 event, clock_sync,\n\
+                    version=\"ntpd 4.2.8p15\", leap=00, stratum=3, precision=-23,\n\
+                    refid=192.0.2.7, offset=-0.384, frequency=-12.3, sys_jitter=0.081\n";
+        let status = parse_ntpd(text).unwrap();
+        assert!(status.synchronized);
+        assert_eq!(status.server.as_deref(), Some("192.0.2.7"));
+        assert_eq!(status.offset_ms, Some(-0.384));
+        assert_eq!(status.jitter_ms, Some(0.081));
+
+        let alarm = text.replace("leap=00", "leap=11");
+        assert!(!parse_ntpd(&alarm).unwrap().synchronized);
+        assert!(parse_chrony(text).is_none());
+    }
+}
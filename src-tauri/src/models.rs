@@ -1,3 +1,9 @@
+use crate::clock_issue::ClockIssue;
+use crate::drift::{self, DriftEstimate};
+use crate::error::{ErrorCode, ErrorPayload};
+use crate::fingerprint::ServerFingerprint;
+use crate::network_context::NetworkContext;
+use crate::sync_grade::SyncGrade;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::fmt;
@@ -50,6 +56,287 @@ pub struct Server {
     pub created_at: DateTime<Utc>,
     pub status: ServerStatus,
     pub extractor_type: String,
+    /// HTTP method used for probes, `HEAD` unless a preset says otherwise.
+    pub probe_method: String,
+    /// The domain preset applied to this server, if any.
+    pub domain_preset: Option<String>,
+    /// Overrides the global `sync_profile` setting when set.
+    pub sync_profile: Option<String>,
+    /// From the most recent successful sync.
+    pub fingerprint: Option<ServerFingerprint>,
+    /// Share of the round trip spent reaching the server, set by hand or
+    /// by calibration; `None` assumes a symmetric path (0.5).
+    #[serde(default)]
+    pub asymmetry: Option<f64>,
+    /// Start a sync for this server when the app launches.
+    #[serde(default)]
+    pub sync_on_launch: bool,
+    /// 0–100 from recent syncs; see `health::score`. `None` until synced.
+    #[serde(default)]
+    pub health_score: Option<u8>,
+    /// Fit of recent offsets over time, refreshed after each sync; `None`
+    /// until there are enough of them. See `drift::estimate`.
+    #[serde(default)]
+    pub drift: Option<DriftEstimate>,
+    /// Overrides the `min_request_interval_ms` setting for this server.
+    #[serde(default)]
+    pub min_request_interval_ms: Option<u32>,
+    /// Self-hosted or otherwise under the user's control, so probes may go
+    /// out faster than the `public_host_floor_ms` setting allows. Local
+    /// hosts count as trusted regardless; see `sync_profile::is_trusted`.
+    #[serde(default)]
+    pub trusted: bool,
+    /// Sent with every probe; `None` uses `sync_engine::DEFAULT_USER_AGENT`.
+    #[serde(default)]
+    pub user_agent: Option<String>,
+    /// Advanced socket options for probes.
+    #[serde(default)]
+    pub transport: TransportOptions,
+    /// IANA zone the server's wall clock presumably reads in; targets can
+    /// be given as `server-local` times in it.
+    #[serde(default)]
+    pub timezone: Option<String>,
+    /// `timezone` was guessed from the URL or headers, not set by hand.
+    #[serde(default)]
+    pub timezone_guessed: bool,
+}
+
+/// Address family probes connect over.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IpFamily {
+    /// Every address the resolver returns, IPv6 and IPv4 raced with the
+    /// connector's fixed happy-eyeballs fallback delay.
+    #[default]
+    Auto,
+    Ipv4,
+    Ipv6,
+}
+
+impl IpFamily {
+    pub fn allows(self, addr: &std::net::SocketAddr) -> bool {
+        match self {
+            IpFamily::Auto => true,
+            IpFamily::Ipv4 => addr.is_ipv4(),
+            IpFamily::Ipv6 => addr.is_ipv6(),
+        }
+    }
+}
+
+/// Low-level transport knobs for chasing the last milliseconds of jitter.
+/// The defaults match what probes did before these existed.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TransportOptions {
+    /// Send the probe request without waiting to coalesce it (Nagle off).
+    pub tcp_nodelay: bool,
+    /// Give up on a new connection after this long; `None` leaves it to
+    /// the sync profile's request timeout.
+    pub connect_timeout_ms: Option<u32>,
+    /// Pinning a family skips the IPv6/IPv4 race on dual-stack hosts.
+    pub ip_family: IpFamily,
+    /// Skip certificate checks, for internal and staging systems with
+    /// self-signed certificates. Anyone on the path can then answer as
+    /// the server.
+    pub accept_invalid_certs: bool,
+}
+
+impl Default for TransportOptions {
+    fn default() -> Self {
+        Self {
+            tcp_nodelay: true,
+            connect_timeout_ms: None,
+            ip_family: IpFamily::Auto,
+            accept_invalid_certs: false,
+        }
+    }
+}
+
+// ── Server Time ──
+
+/// A server's corrected clock reading at one instant.
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerTime {
+    pub server_id: i64,
+    /// The stored offset carried forward to now along the server's drift.
+    pub offset_ms: f64,
+    pub corrected_time: DateTime<Utc>,
+    pub corrected_unix_ms: i64,
+    pub last_sync_at: Option<DateTime<Utc>>,
+    pub offset_age_ms: Option<i64>,
+    /// The corrected time is extrapolated from an offset that is over an
+    /// hour old, or that cannot be refreshed because the machine is offline.
+    pub stale: bool,
+    /// `None` without enough syncs to fit a drift, or with extrapolation
+    /// turned off; the stored offset is then used as is.
+    pub drift_ms_per_hour: Option<f64>,
+    /// One standard error of `offset_ms`, growing with its age. `None`
+    /// without a drift fit.
+    pub uncertainty_ms: Option<f64>,
+}
+
+impl ServerTime {
+    /// `None` until the server has been synced at least once. Without
+    /// `extrapolate_drift` the stored offset is used as measured.
+    pub fn at(server: &Server, now: DateTime<Utc>, extrapolate_drift: bool) -> Option<Self> {
+        let drift = drift::applied(server, extrapolate_drift);
+        let offset_ms = drift::offset_at(server, now, extrapolate_drift)?;
+        let corrected = crate::timing::corrected_time(now, offset_ms);
+        let age = server.last_sync_at.map(|at| now - at);
+        Some(Self {
+            server_id: server.id,
+            offset_ms,
+            corrected_time: corrected,
+            corrected_unix_ms: corrected.timestamp_millis(),
+            last_sync_at: server.last_sync_at,
+            offset_age_ms: age.map(|a| a.num_milliseconds()),
+            stale: !matches!(age, Some(a) if a <= crate::countdown::STALE_AFTER),
+            drift_ms_per_hour: drift.map(|d| d.rate_ms_per_hour),
+            uncertainty_ms: drift
+                .zip(server.last_sync_at)
+                .map(|(d, at)| drift::uncertainty_ms(d, at, now)),
+        })
+    }
+
+    /// Flag the reading as stale while there is no network to resync over.
+    pub fn offline(self, offline: bool) -> Self {
+        Self {
+            stale: self.stale || offline,
+            ..self
+        }
+    }
+}
+
+/// A server's offset measured against the reference server instead of
+/// the local clock, which cancels out the local clock's own error.
+#[derive(Debug, Clone, Serialize)]
+pub struct RelativeOffset {
+    pub server_id: i64,
+    pub reference_server_id: i64,
+    /// Against the local clock, as stored.
+    pub offset_ms: Option<f64>,
+    /// Positive: this server runs ahead of the reference. `None` until the
+    /// server has been synced.
+    pub relative_offset_ms: Option<f64>,
+}
+
+impl RelativeOffset {
+    /// `reference` must have been synced; unsynced servers get `None`.
+    pub fn for_servers(servers: &[Server], reference: &Server) -> Vec<Self> {
+        let base = reference.offset_ms;
+        servers
+            .iter()
+            .map(|server| Self {
+                server_id: server.id,
+                reference_server_id: reference.id,
+                offset_ms: server.offset_ms,
+                relative_offset_ms: server.offset_ms.zip(base).map(|(o, b)| o - b),
+            })
+            .collect()
+    }
+}
+
+// ── Target ──
+
+/// A moment the user is counting down to on a given server's clock.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Target {
+    pub id: i64,
+    pub server_id: i64,
+    pub label: Option<String>,
+    pub target_at: DateTime<Utc>,
+    pub armed: bool,
+    pub created_at: DateTime<Utc>,
+    /// Overrides the `alert_method` setting for this target.
+    #[serde(default)]
+    pub alert_method: Option<String>,
+}
+
+/// A one-time sync queued for a wall-clock moment, e.g. just before an
+/// on-sale. Deleted once it has started.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledSync {
+    pub id: i64,
+    pub server_id: i64,
+    pub run_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+// ── Workspace ──
+
+/// A named, isolated set of servers, targets and settings.
+#[derive(Debug, Clone, Serialize)]
+pub struct Workspace {
+    pub name: String,
+    pub active: bool,
+}
+
+// ── Trash ──
+
+/// A deleted server, kept with its history until `purge_at`.
+#[derive(Debug, Clone, Serialize)]
+pub struct TrashedServer {
+    pub server: Server,
+    pub deleted_at: DateTime<Utc>,
+    pub purge_at: DateTime<Utc>,
+}
+
+// ── Connection Test ──
+
+/// Outcome of a quick `test_connection`; never written to history.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConnectionTest {
+    pub probes: usize,
+    pub min_rtt_ms: f64,
+    pub median_rtt_ms: f64,
+    /// Whole-second server time extracted from the last probe.
+    pub server_timestamp: i64,
+    pub tested_at: DateTime<Utc>,
+}
+
+/// Outcome of `calibrate_asymmetry`: a symmetric sync set against NTP
+/// time, on the assumption that the server's clock is NTP-synced.
+#[derive(Debug, Clone, Serialize)]
+pub struct AsymmetryCalibration {
+    pub server_id: i64,
+    /// Share of the round trip spent reaching the server (0.5: symmetric).
+    pub asymmetry: f64,
+    /// What the sync measured assuming a symmetric path.
+    pub symmetric_offset_ms: f64,
+    /// Server offset implied by NTP (local clock error).
+    pub ntp_offset_ms: f64,
+    /// Median round trip the factor was derived over.
+    pub rtt_ms: f64,
+    pub calibrated_at: DateTime<Utc>,
+}
+
+/// A single-probe offset for when there is no time for a full sync. Never
+/// verified, never written to history and never applied to the server.
+#[derive(Debug, Clone, Serialize)]
+pub struct OffsetEstimate {
+    pub server_id: i64,
+    pub offset_ms: f64,
+    /// RTT/2 for the unknown network split, plus half a second for the
+    /// whole-second Date header.
+    pub uncertainty_ms: f64,
+    pub rtt_ms: f64,
+    /// Always `false`; spelled out so the UI cannot mistake it for a sync.
+    pub verified: bool,
+    pub estimated_at: DateTime<Utc>,
+}
+
+/// A stored offset checked against a few fresh probes just before a
+/// target; see `sync_engine::recheck_offset`.
+#[derive(Debug, Clone, Serialize)]
+pub struct OffsetRecheck {
+    /// The stored offset, moved as little as the probes allow.
+    pub offset_ms: f64,
+    /// 0 when the probes agree with the stored offset.
+    pub moved_ms: f64,
+    /// Half the width of the region the probes pin the offset to.
+    pub uncertainty_ms: f64,
+    pub probes: u32,
+    pub checked_at: DateTime<Utc>,
 }
 
 // ── Latency Profile ──
@@ -62,6 +349,40 @@ pub struct LatencyProfile {
     pub mean: f64,
     pub q3: f64,
     pub max: f64,
+    /// Where the probes' round trips went, on average. `None` for probes
+    /// that cannot see their connection (simulated servers, older results).
+    #[serde(default)]
+    pub breakdown: Option<RttBreakdown>,
+}
+
+/// One round trip (or the mean of several) split by where the time went,
+/// in seconds. `dns` and `connect` are zero on a pooled connection, so
+/// RTT spikes with a non-zero `connect` are a reconnect, not the network.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RttBreakdown {
+    pub dns: f64,
+    /// TCP and TLS handshakes.
+    pub connect: f64,
+    /// Request sent to response headers received.
+    pub ttfb: f64,
+    /// Probes that had to open a new connection.
+    pub new_connections: u32,
+}
+
+impl RttBreakdown {
+    /// Mean of each part; `new_connections` is summed.
+    pub fn mean(samples: &[RttBreakdown]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        let n = samples.len() as f64;
+        Some(Self {
+            dns: samples.iter().map(|b| b.dns).sum::<f64>() / n,
+            connect: samples.iter().map(|b| b.connect).sum::<f64>() / n,
+            ttfb: samples.iter().map(|b| b.ttfb).sum::<f64>() / n,
+            new_connections: samples.iter().map(|b| b.new_connections).sum(),
+        })
+    }
 }
 
 impl LatencyProfile {
@@ -89,6 +410,129 @@ pub struct SyncResult {
     pub synced_at: DateTime<Utc>,
     pub duration_ms: u64,
     pub phase_reached: SyncPhase,
+    /// Taken from the last probe response; stored on the server record,
+    /// not in history.
+    #[serde(default)]
+    pub fingerprint: Option<ServerFingerprint>,
+    /// How the last probe of the run was answered.
+    #[serde(default)]
+    pub response: Option<ResponseMetadata>,
+    /// Row id in `sync_results`; 0 until the result is stored.
+    #[serde(default)]
+    pub id: i64,
+    /// How long each phase took and how many probes it sent, in order.
+    #[serde(default)]
+    pub timeline: Vec<PhaseTiming>,
+    /// Free-form context added afterwards ("synced over hotel Wi-Fi").
+    #[serde(default)]
+    pub note: Option<String>,
+    /// Path asymmetry factor the sync corrected for; `None` if it assumed
+    /// a symmetric path.
+    #[serde(default)]
+    pub asymmetry: Option<f64>,
+    /// Offset far off the server's recent history; see `anomaly`.
+    #[serde(default)]
+    pub anomalous: bool,
+    /// The offset read as a wrong time zone or a badly skewed clock.
+    #[serde(default)]
+    pub clock_issue: Option<ClockIssue>,
+    /// The network the sync ran over; `None` for syncs stored before it
+    /// was recorded or not run by the app.
+    #[serde(default)]
+    pub network: Option<NetworkContext>,
+    /// How far the offset can be trusted; `None` for syncs stored before
+    /// grading.
+    #[serde(default)]
+    pub grade: Option<SyncGrade>,
+    /// Width in ms of the window the sub-second offset was narrowed to;
+    /// wider than 1 ms when jitter ended the search early. `None` for
+    /// syncs stored before it was recorded.
+    #[serde(default)]
+    pub precision_ms: Option<f64>,
+}
+
+/// One phase of a completed sync.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PhaseTiming {
+    pub phase: SyncPhase,
+    pub duration_ms: u64,
+    pub probes: u32,
+}
+
+/// Transport details of a probe response, kept per run so offset jumps can
+/// be matched against infrastructure changes (new IP, protocol, redirect).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResponseMetadata {
+    /// Status of the final response after redirects.
+    pub status: u16,
+    pub http_version: String,
+    pub remote_ip: Option<String>,
+    pub redirects: u32,
+    /// Mean local work per probe (building the request, reading headers)
+    /// in microseconds, to show it is negligible next to the RTT.
+    #[serde(default)]
+    pub probe_overhead_us: f64,
+    /// The certificate the server presented; `None` over plain HTTP.
+    #[serde(default)]
+    pub certificate: Option<CertificateInfo>,
+}
+
+/// The parts of a server's TLS certificate worth watching between syncs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CertificateInfo {
+    pub subject: String,
+    pub issuer: String,
+    pub not_before: DateTime<Utc>,
+    pub not_after: DateTime<Utc>,
+    /// SHA-256 of the DER encoding, hex.
+    pub sha256: String,
+}
+
+/// Narrows a history query; fields left `None` match everything.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SyncHistoryFilter {
+    /// RFC 3339; only results synced at or after this.
+    pub since: Option<String>,
+    pub verified: Option<bool>,
+    pub phase_reached: Option<SyncPhase>,
+    pub anomalous: Option<bool>,
+}
+
+/// One page of a server's sync history, newest first.
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncHistoryPage {
+    pub results: Vec<SyncResult>,
+    /// Results matching the query across all pages.
+    pub total: i64,
+    pub offset: i64,
+}
+
+// ── Benchmark ──
+
+/// One estimator's outcome in a benchmark.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkRun {
+    pub method: String,
+    pub offset_ms: Option<f64>,
+    /// Half-width of the band the true offset most likely lies in.
+    pub uncertainty_ms: Option<f64>,
+    pub probes: u32,
+    pub duration_ms: u64,
+    pub error: Option<String>,
+}
+
+/// Both estimators run back-to-back against one server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkReport {
+    /// 0 until the report is saved.
+    pub id: i64,
+    pub server_id: i64,
+    pub created_at: DateTime<Utc>,
+    pub runs: Vec<BenchmarkRun>,
+    /// How far apart the two offsets are, when both succeeded.
+    pub difference_ms: Option<f64>,
+    /// The run with the tightest uncertainty.
+    pub recommended: Option<String>,
 }
 
 // ── Sync Phase ──
@@ -143,7 +587,8 @@ impl TryFrom<i32> for SyncPhase {
 #[serde(tag = "event", content = "data")]
 pub enum SyncEvent {
     Progress(SyncProgressPayload),
-    Complete(SyncCompletePayload),
+    /// Boxed: a full result dwarfs the other payloads.
+    Complete(Box<SyncCompletePayload>),
     Error(SyncErrorPayload),
 }
 
@@ -154,6 +599,8 @@ pub struct SyncProgressPayload {
     pub progress_percent: f64,
     pub phase_data: serde_json::Value,
     pub elapsed_ms: u64,
+    /// Estimated time left if no more probes need retrying.
+    pub eta_ms: u64,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -162,29 +609,117 @@ pub struct SyncCompletePayload {
     pub result: SyncResult,
 }
 
+/// Sent as `server-status-changed` whenever a server's status is stored.
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerStatusChange {
+    pub server_id: i64,
+    pub status: ServerStatus,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct SyncErrorPayload {
     pub server_id: i64,
-    pub error: String,
+    pub error: ErrorPayload,
+    #[serde(flatten)]
+    pub context: FailureContext,
+}
+
+/// Where a failed sync got to, so the UI can explain the failure and a
+/// retry can reuse the latency profile.
+#[derive(Debug, Clone, Serialize)]
+pub struct FailureContext {
+    /// The phase that was running when the sync stopped.
+    pub phase: SyncPhase,
+    /// Probes sent during that phase, retries included.
+    pub phase_attempts: u32,
+    pub total_attempts: u32,
+    /// Set once phase 1 has finished.
+    pub latency_profile: Option<LatencyProfile>,
+    /// The offset found before verification, when the sync got that far.
+    pub partial_offset_ms: Option<f64>,
+    pub elapsed_ms: u64,
+}
+
+/// A failed sync as kept in the `errors` table. User cancellations are not
+/// recorded.
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncErrorRecord {
+    pub id: i64,
+    pub server_id: i64,
+    pub phase: SyncPhase,
+    pub code: ErrorCode,
+    pub message: String,
+    pub total_attempts: u32,
+    pub occurred_at: DateTime<Utc>,
 }
 
 // ── App Settings ──
 
+/// Missing fields deserialize to their defaults so older settings files
+/// still load.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AppSettings {
     pub theme: String,
     pub min_request_interval_ms: u32,
+    /// Lowest request interval untrusted servers are probed at, whatever
+    /// their own or the global `min_request_interval_ms` says.
+    pub public_host_floor_ms: u32,
+    pub sync_profile: String,
+    /// Resync a server whose health score (0–100) drops below this; 0 disables.
     pub health_resync_threshold: u8,
+    pub auto_resync_interval_minutes: u32,
     pub external_time_source: String,
     pub show_milliseconds: bool,
     pub millisecond_precision: u8,
     pub show_timezone_offset: bool,
+    pub time_format: String,
     pub overlay_opacity: u8,
     pub overlay_auto_hide: bool,
     pub overlay_always_on_top: bool,
+    /// Milliseconds between countdown stream ticks.
+    pub countdown_tick_ms: u32,
     pub alert_intervals: Vec<u32>,
     pub alert_method: String,
+    /// Keep connections to a target's server open for this many minutes
+    /// before it fires; 0 disables.
+    pub keep_warm_minutes: u32,
     pub drift_warning_threshold_ms: u32,
+    /// Notify when a sync's offset is flagged as anomalous.
+    pub notify_anomalies: bool,
+    pub local_api_enabled: bool,
+    pub local_api_port: u16,
+    pub allow_clock_adjustment: bool,
+    /// Progress events per sync per 100 ms; 0 sends every event.
+    pub progress_event_limit: u32,
+    /// Save every probe of each sync to the recordings directory, for
+    /// replaying later.
+    pub record_syncs: bool,
+    /// Days a deleted server stays restorable before it is purged.
+    pub trash_retention_days: u32,
+    /// Stop a sync that has reported no progress for this long.
+    pub sync_stall_timeout_secs: u32,
+    /// Give up on a sync, keeping what it found, once it has run this long.
+    pub sync_deadline_secs: u32,
+    /// Probe the external time source before each sync and stop it when
+    /// the local network looks degraded.
+    pub network_precheck: bool,
+    /// When a sync's warm-up answer looks off, ask a well-known host
+    /// whether a captive portal or proxy is answering instead.
+    pub captive_portal_check: bool,
+    /// Carry each server's offset forward along its fitted drift since the
+    /// last sync. Off, corrected times use the offset as measured.
+    pub extrapolate_drift: bool,
+    /// Answer SNTP queries on the LAN with the primary server's corrected
+    /// time, advertised over mDNS.
+    pub lan_time_enabled: bool,
+    pub lan_time_port: u16,
+}
+
+impl AppSettings {
+    pub fn trash_retention(&self) -> chrono::Duration {
+        chrono::Duration::days(self.trash_retention_days.into())
+    }
 }
 
 impl Default for AppSettings {
@@ -192,17 +727,37 @@ impl Default for AppSettings {
         Self {
             theme: "dark".to_string(),
             min_request_interval_ms: 500,
+            public_host_floor_ms: 500,
+            sync_profile: crate::sync_profile::DEFAULT_PROFILE.to_string(),
             health_resync_threshold: 50,
+            auto_resync_interval_minutes: 0,
             external_time_source: "ntp".to_string(),
             show_milliseconds: true,
             millisecond_precision: 3,
             show_timezone_offset: false,
+            time_format: crate::time_format::DEFAULT_PATTERN.to_string(),
             overlay_opacity: 75,
             overlay_auto_hide: false,
             overlay_always_on_top: true,
+            countdown_tick_ms: 100,
             alert_intervals: vec![10, 5, 1],
             alert_method: "both".to_string(),
+            keep_warm_minutes: 0,
             drift_warning_threshold_ms: 1000,
+            notify_anomalies: false,
+            local_api_enabled: false,
+            local_api_port: 47_321,
+            allow_clock_adjustment: false,
+            progress_event_limit: 4,
+            record_syncs: false,
+            trash_retention_days: 7,
+            sync_stall_timeout_secs: 60,
+            sync_deadline_secs: 180,
+            network_precheck: false,
+            captive_portal_check: false,
+            extrapolate_drift: true,
+            lan_time_enabled: false,
+            lan_time_port: 47_323,
         }
     }
 }
@@ -210,6 +765,7 @@ impl Default for AppSettings {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::AppError;
     use chrono::Utc;
 
     // ── ServerStatus::Display ──
@@ -347,10 +903,12 @@ mod tests {
         assert_eq!(s.theme, "dark");
         assert_eq!(s.min_request_interval_ms, 500);
         assert_eq!(s.health_resync_threshold, 50);
+        assert_eq!(s.auto_resync_interval_minutes, 0);
         assert_eq!(s.external_time_source, "ntp");
         assert!(s.show_milliseconds);
         assert_eq!(s.millisecond_precision, 3);
         assert!(!s.show_timezone_offset);
+        assert_eq!(s.time_format, "%H:%M:%S");
         assert_eq!(s.overlay_opacity, 75);
         assert!(!s.overlay_auto_hide);
         assert!(s.overlay_always_on_top);
@@ -369,6 +927,7 @@ mod tests {
             progress_percent: 25.0,
             phase_data: serde_json::Value::Null,
             elapsed_ms: 100,
+            eta_ms: 20_000,
         };
         let event = SyncEvent::Progress(payload);
         let v: serde_json::Value = serde_json::to_value(&event).unwrap();
@@ -386,6 +945,7 @@ mod tests {
             mean: 3.0,
             q3: 4.0,
             max: 5.0,
+            breakdown: None,
         };
         let result = SyncResult {
             server_id: 2,
@@ -397,8 +957,19 @@ mod tests {
             synced_at: Utc::now(),
             duration_ms: 500,
             phase_reached: SyncPhase::Complete,
+            fingerprint: None,
+            asymmetry: None,
+            anomalous: false,
+            clock_issue: None,
+            network: None,
+            grade: None,
+            precision_ms: None,
+            response: None,
+            id: 0,
+            timeline: Vec::new(),
+            note: None,
         };
-        let event = SyncEvent::Complete(SyncCompletePayload { server_id: 2, result });
+        let event = SyncEvent::Complete(Box::new(SyncCompletePayload { server_id: 2, result }));
         let v: serde_json::Value = serde_json::to_value(&event).unwrap();
         assert_eq!(v["event"], "Complete");
         assert_eq!(v["data"]["server_id"], 2);
@@ -408,11 +979,94 @@ mod tests {
     fn sync_event_error_serializes_with_correct_tag() {
         let event = SyncEvent::Error(SyncErrorPayload {
             server_id: 3,
-            error: "something went wrong".to_string(),
+            error: AppError::MaxRetriesExceeded(10).payload(),
+            context: FailureContext {
+                phase: SyncPhase::BinarySearch,
+                phase_attempts: 7,
+                total_attempts: 21,
+                latency_profile: None,
+                partial_offset_ms: None,
+                elapsed_ms: 9_500,
+            },
         });
         let v: serde_json::Value = serde_json::to_value(&event).unwrap();
         assert_eq!(v["event"], "Error");
         assert_eq!(v["data"]["server_id"], 3);
-        assert_eq!(v["data"]["error"], "something went wrong");
+        assert_eq!(v["data"]["error"]["code"], "max_retries_exceeded");
+        assert_eq!(v["data"]["error"]["retryable"], true);
+        assert_eq!(v["data"]["phase"], "binary_search");
+        assert_eq!(v["data"]["phase_attempts"], 7);
+    }
+
+    // ── RelativeOffset ──
+
+    fn synced(id: i64, offset_ms: Option<f64>) -> Server {
+        Server {
+            id,
+            url: format!("https://{id}.example.com"),
+            name: None,
+            offset_ms,
+            last_sync_at: offset_ms.map(|_| Utc::now()),
+            created_at: Utc::now(),
+            status: ServerStatus::Idle,
+            extractor_type: "date_header".to_string(),
+            probe_method: "HEAD".to_string(),
+            domain_preset: None,
+            sync_profile: None,
+            fingerprint: None,
+            asymmetry: None,
+            sync_on_launch: false,
+            health_score: None,
+            drift: None,
+            min_request_interval_ms: None,
+            trusted: false,
+            user_agent: None,
+            transport: Default::default(),
+            timezone: None,
+            timezone_guessed: false,
+        }
+    }
+
+    #[test]
+    fn relative_offsets_cancel_the_local_clock_error() {
+        // Local clock 800 ms behind both servers; they differ by 25 ms.
+        let reference = synced(1, Some(800.0));
+        let servers = [reference.clone(), synced(2, Some(825.0)), synced(3, None)];
+
+        let relative = RelativeOffset::for_servers(&servers, &reference);
+        assert_eq!(relative[0].relative_offset_ms, Some(0.0));
+        assert_eq!(relative[1].relative_offset_ms, Some(25.0));
+        assert_eq!(relative[1].offset_ms, Some(825.0));
+        assert_eq!(relative[2].relative_offset_ms, None);
+        assert!(relative.iter().all(|r| r.reference_server_id == 1));
+    }
+
+    // ── ServerTime ──
+
+    #[test]
+    fn server_time_carries_the_offset_along_the_stored_drift() {
+        let now = Utc::now();
+        let server = Server {
+            last_sync_at: Some(now - chrono::Duration::hours(2)),
+            drift: Some(DriftEstimate {
+                rate_ms_per_hour: 3.0,
+                samples: 8,
+                residual_ms: 1.5,
+                rate_error_ms_per_hour: 0.5,
+            }),
+            ..synced(1, Some(100.0))
+        };
+        let time = ServerTime::at(&server, now, true).unwrap();
+        assert!((time.offset_ms - 106.0).abs() < 1e-6);
+        assert_eq!(time.drift_ms_per_hour, Some(3.0));
+        assert!((time.uncertainty_ms.unwrap() - 1.5f64.hypot(1.0)).abs() < 1e-6);
+
+        let measured = ServerTime::at(&server, now, false).unwrap();
+        assert_eq!(measured.offset_ms, 100.0);
+        assert_eq!(measured.drift_ms_per_hour, None);
+
+        let cold = ServerTime::at(&synced(1, Some(100.0)), now, true).unwrap();
+        assert_eq!(cold.offset_ms, 100.0);
+        assert_eq!(cold.uncertainty_ms, None);
     }
 }
@@ -0,0 +1,133 @@
+use crate::ntp;
+use crate::time_source::TimeSource;
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+const SAMPLES: usize = 6;
+/// Per probe. Six of them stay inside the shortest stall timeout, so the
+/// check cannot trip the sync watchdog.
+const PROBE_TIMEOUT: Duration = Duration::from_millis(1_500);
+/// Share of lost probes from which the network counts as degraded.
+const MAX_LOSS: f64 = 0.2;
+/// A known-fast reference slower than this means the trouble is local.
+const MAX_RTT_MS: f64 = 400.0;
+/// Spread between the fastest and slowest reply.
+const MAX_JITTER_MS: f64 = 150.0;
+
+/// How the local network fared against the configured time reference just
+/// before a sync.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NetworkBaseline {
+    pub reference: String,
+    pub sent: usize,
+    pub lost: usize,
+    pub median_rtt_ms: Option<f64>,
+    pub jitter_ms: Option<f64>,
+    /// Loss, latency or jitter far past what a healthy link shows. A
+    /// reference that never answers says nothing (NTP's port is often
+    /// blocked), so it does not count.
+    pub degraded: bool,
+}
+
+impl NetworkBaseline {
+    /// One line for the error and the progress panel.
+    pub fn summary(&self) -> String {
+        let mut parts = vec![format!(
+            "{} of {} probes to {} lost",
+            self.lost, self.sent, self.reference
+        )];
+        if let Some(rtt) = self.median_rtt_ms {
+            parts.push(format!("median RTT {rtt:.0} ms"));
+        }
+        if let Some(jitter) = self.jitter_ms {
+            parts.push(format!("jitter {jitter:.0} ms"));
+        }
+        parts.join(", ")
+    }
+}
+
+pub async fn measure(source: TimeSource) -> NetworkBaseline {
+    match source {
+        TimeSource::Ntp(host) => {
+            assess(host, &ntp::round_trips(host, SAMPLES, PROBE_TIMEOUT).await)
+        }
+        TimeSource::Http(url) => assess(url, &http_round_trips(url).await),
+    }
+}
+
+/// Like `ntp::round_trips`, over HEAD requests on one connection. The
+/// first request only opens it, so the TLS handshake is not counted.
+async fn http_round_trips(url: &str) -> Vec<Option<f64>> {
+    let Ok(client) = reqwest::Client::builder().timeout(PROBE_TIMEOUT).build() else {
+        return Vec::new();
+    };
+    if client.head(url).send().await.is_err() {
+        return Vec::new();
+    }
+    let mut rtts = Vec::with_capacity(SAMPLES);
+    for _ in 0..SAMPLES {
+        let started = Instant::now();
+        rtts.push(
+            client
+                .head(url)
+                .send()
+                .await
+                .ok()
+                .map(|_| started.elapsed().as_secs_f64() * 1000.0),
+        );
+    }
+    rtts
+}
+
+fn assess(reference: &str, rtts: &[Option<f64>]) -> NetworkBaseline {
+    let mut replies: Vec<f64> = rtts.iter().flatten().copied().collect();
+    replies.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let lost = rtts.len() - replies.len();
+    let median_rtt_ms = replies.get(replies.len() / 2).copied();
+    let jitter_ms = (replies.len() >= 2).then(|| replies[replies.len() - 1] - replies[0]);
+    let degraded = !replies.is_empty()
+        && (lost as f64 / rtts.len() as f64 >= MAX_LOSS
+            || median_rtt_ms.is_some_and(|rtt| rtt > MAX_RTT_MS)
+            || jitter_ms.is_some_and(|jitter| jitter > MAX_JITTER_MS));
+    NetworkBaseline {
+        reference: reference.to_string(),
+        sent: rtts.len(),
+        lost,
+        median_rtt_ms,
+        jitter_ms,
+        degraded,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn healthy_links_pass() {
+        let baseline = assess(
+            "pool.ntp.org",
+            &[Some(12.0), Some(14.0), Some(11.0), Some(13.0)],
+        );
+        assert!(!baseline.degraded);
+        assert_eq!(baseline.lost, 0);
+        assert_eq!(baseline.median_rtt_ms, Some(13.0));
+        assert_eq!(baseline.jitter_ms, Some(3.0));
+    }
+
+    #[test]
+    fn loss_latency_or_jitter_degrade() {
+        let lossy = [Some(12.0), None, Some(13.0), None, Some(12.0), Some(14.0)];
+        assert!(assess("pool.ntp.org", &lossy).degraded);
+        let slow = [Some(620.0), Some(650.0), Some(640.0)];
+        assert!(assess("pool.ntp.org", &slow).degraded);
+        let jittery = [Some(15.0), Some(20.0), Some(300.0)];
+        assert!(assess("pool.ntp.org", &jittery).degraded);
+    }
+
+    #[test]
+    fn a_silent_reference_is_inconclusive() {
+        assert!(!assess("pool.ntp.org", &[None; SAMPLES]).degraded);
+        assert!(!assess("pool.ntp.org", &[]).degraded);
+    }
+}
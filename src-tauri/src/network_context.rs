@@ -0,0 +1,464 @@
+use crate::network_watch::Route;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::net::IpAddr;
+
+/// Hex digits of the SSID's SHA-256 kept: plenty to tell two networks
+/// apart, without storing the network's name.
+const SSID_HASH_LEN: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InterfaceKind {
+    Wifi,
+    Ethernet,
+    Cellular,
+    Other,
+}
+
+/// The network a sync ran over. An offset that jumps between two syncs
+/// almost always comes with a change here.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NetworkContext {
+    /// The physical interface under the route, even when a VPN carries it.
+    /// `None` when offline or the interface could not be read.
+    pub interface: Option<String>,
+    pub kind: Option<InterfaceKind>,
+    /// A VPN or other tunnel was up.
+    pub vpn: bool,
+    /// Truncated SHA-256 of the Wi-Fi network's SSID.
+    pub ssid_hash: Option<String>,
+}
+
+/// One address of a network interface, as the platform reports it.
+#[derive(Debug, Clone)]
+struct Interface {
+    name: String,
+    address: IpAddr,
+    kind: InterfaceKind,
+    tunnel: bool,
+}
+
+/// Read the current network context. Blocks on OS queries and, for the
+/// SSID, a helper process; call it off the async runtime.
+pub fn capture() -> NetworkContext {
+    let mut context = describe(&Route::current(), &platform::interfaces());
+    if let (Some(name), Some(kind)) = (&context.interface, context.kind) {
+        context.kind = Some(platform::refine_kind(name, kind));
+    }
+    if context.kind == Some(InterfaceKind::Wifi) {
+        context.ssid_hash = context
+            .interface
+            .as_deref()
+            .and_then(platform::ssid)
+            .map(|ssid| hash_ssid(&ssid));
+    }
+    context
+}
+
+/// Match the route's source addresses to interfaces. With a full-tunnel VPN
+/// the route runs over the tunnel, so the physical interface is taken to
+/// be the first other one with a usable address.
+fn describe(route: &Route, interfaces: &[Interface]) -> NetworkContext {
+    let usable: Vec<&Interface> = interfaces
+        .iter()
+        .filter(|i| is_usable(&i.address))
+        .collect();
+    let routed = usable
+        .iter()
+        .find(|i| Some(i.address) == route.v4 || Some(i.address) == route.v6);
+    let physical = match routed {
+        Some(i) if !i.tunnel => Some(*i),
+        _ if route.is_offline() => None,
+        _ => usable
+            .iter()
+            .filter(|i| !i.tunnel)
+            .min_by_key(|i| i.kind == InterfaceKind::Other)
+            .copied(),
+    };
+    NetworkContext {
+        interface: physical.map(|i| i.name.clone()),
+        kind: physical.map(|i| i.kind),
+        vpn: usable.iter().any(|i| i.tunnel),
+        ssid_hash: None,
+    }
+}
+
+/// Loopback and link-local addresses say nothing about the network; macOS
+/// keeps several idle `utun` interfaces with only link-local ones.
+fn is_usable(address: &IpAddr) -> bool {
+    match address {
+        IpAddr::V4(a) => !a.is_loopback() && !a.is_link_local() && !a.is_unspecified(),
+        IpAddr::V6(a) => {
+            !a.is_loopback() && !a.is_unspecified() && (a.segments()[0] & 0xffc0) != 0xfe80
+        }
+    }
+}
+
+fn hash_ssid(ssid: &str) -> String {
+    let digest = Sha256::digest(ssid.as_bytes());
+    let mut hex: String = digest.iter().map(|b| format!("{b:02x}")).collect();
+    hex.truncate(SSID_HASH_LEN);
+    hex
+}
+
+/// Interface naming conventions on Linux, Android, macOS and iOS.
+#[cfg(unix)]
+fn kind_from_name(name: &str) -> (InterfaceKind, bool) {
+    const TUNNELS: [&str; 8] = [
+        "tun",
+        "tap",
+        "utun",
+        "wg",
+        "ppp",
+        "ipsec",
+        "tailscale",
+        "zt",
+    ];
+    if TUNNELS.iter().any(|prefix| name.starts_with(prefix)) {
+        return (InterfaceKind::Other, true);
+    }
+    let kind = if name.starts_with("wl") || name.starts_with("ath") {
+        InterfaceKind::Wifi
+    } else if ["wwan", "rmnet", "ccmni", "pdp_ip"]
+        .iter()
+        .any(|prefix| name.starts_with(prefix))
+    {
+        InterfaceKind::Cellular
+    } else if name.starts_with("en") || name.starts_with("eth") {
+        InterfaceKind::Ethernet
+    } else {
+        InterfaceKind::Other
+    };
+    (kind, false)
+}
+
+// ── Platform backends ──
+
+#[cfg(unix)]
+mod platform {
+    use super::{kind_from_name, Interface, InterfaceKind};
+    use std::ffi::CStr;
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    use std::process::{Command, Stdio};
+
+    pub fn interfaces() -> Vec<Interface> {
+        let mut head: *mut libc::ifaddrs = std::ptr::null_mut();
+        // SAFETY: `head` is a valid out-pointer; the list is freed below.
+        if unsafe { libc::getifaddrs(&mut head) } != 0 {
+            return Vec::new();
+        }
+        let mut out = Vec::new();
+        let mut cursor = head;
+        // SAFETY: getifaddrs returned a valid linked list, unchanged until
+        // freeifaddrs.
+        while let Some(ifa) = unsafe { cursor.as_ref() } {
+            cursor = ifa.ifa_next;
+            if ifa.ifa_addr.is_null() || ifa.ifa_flags & libc::IFF_UP as libc::c_uint == 0 {
+                continue;
+            }
+            // SAFETY: ifa_addr is non-null and its family says which
+            // sockaddr it is.
+            let address = unsafe {
+                match i32::from((*ifa.ifa_addr).sa_family) {
+                    libc::AF_INET => {
+                        let sin = &*(ifa.ifa_addr as *const libc::sockaddr_in);
+                        IpAddr::V4(Ipv4Addr::from(u32::from_be(sin.sin_addr.s_addr)))
+                    }
+                    libc::AF_INET6 => {
+                        let sin6 = &*(ifa.ifa_addr as *const libc::sockaddr_in6);
+                        IpAddr::V6(Ipv6Addr::from(sin6.sin6_addr.s6_addr))
+                    }
+                    _ => continue,
+                }
+            };
+            // SAFETY: ifa_name is a NUL-terminated string owned by the list.
+            let name = unsafe { CStr::from_ptr(ifa.ifa_name) }
+                .to_string_lossy()
+                .into_owned();
+            let (kind, tunnel) = kind_from_name(&name);
+            out.push(Interface {
+                name,
+                kind,
+                address,
+                tunnel,
+            });
+        }
+        // SAFETY: `head` came from getifaddrs and is freed exactly once.
+        unsafe { libc::freeifaddrs(head) };
+        out
+    }
+
+    /// Linux names some Wi-Fi cards `eth*` or `ra*`; sysfs knows better.
+    #[cfg(target_os = "linux")]
+    pub fn refine_kind(name: &str, kind: InterfaceKind) -> InterfaceKind {
+        if std::path::Path::new("/sys/class/net")
+            .join(name)
+            .join("wireless")
+            .exists()
+        {
+            InterfaceKind::Wifi
+        } else {
+            kind
+        }
+    }
+
+    /// `en0` is Wi-Fi on a MacBook but Ethernet on an iMac; only
+    /// `networksetup` can tell.
+    #[cfg(target_os = "macos")]
+    pub fn refine_kind(name: &str, kind: InterfaceKind) -> InterfaceKind {
+        if kind == InterfaceKind::Ethernet && airport_network(name).is_some() {
+            InterfaceKind::Wifi
+        } else {
+            kind
+        }
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    pub fn refine_kind(_name: &str, kind: InterfaceKind) -> InterfaceKind {
+        kind
+    }
+
+    /// Output of `networksetup -getairportnetwork`, `None` for interfaces
+    /// that are not Wi-Fi.
+    #[cfg(target_os = "macos")]
+    fn airport_network(name: &str) -> Option<String> {
+        let out = output(Command::new("networksetup").args(["-getairportnetwork", name]))?;
+        (!out.contains("not a Wi-Fi interface")).then_some(out)
+    }
+
+    #[cfg(target_os = "macos")]
+    pub fn ssid(name: &str) -> Option<String> {
+        airport_network(name)?
+            .strip_prefix("Current Wi-Fi Network: ")
+            .map(|ssid| ssid.trim().to_string())
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn ssid(name: &str) -> Option<String> {
+        let out = output(Command::new("iw").args(["dev", name, "link"]))?;
+        out.lines()
+            .find_map(|line| line.trim().strip_prefix("SSID: "))
+            .map(str::to_string)
+    }
+
+    /// Mobile platforms keep the SSID behind location permission.
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    pub fn ssid(_name: &str) -> Option<String> {
+        None
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    fn output(command: &mut Command) -> Option<String> {
+        let out = command
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output()
+            .ok()?;
+        out.status
+            .success()
+            .then(|| String::from_utf8_lossy(&out.stdout).into_owned())
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::{Interface, InterfaceKind};
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+    use std::os::windows::process::CommandExt;
+    use std::process::{Command, Stdio};
+    use windows_sys::Win32::Foundation::{ERROR_BUFFER_OVERFLOW, ERROR_SUCCESS};
+    use windows_sys::Win32::NetworkManagement::IpHelper::{
+        GetAdaptersAddresses, GAA_FLAG_SKIP_ANYCAST, GAA_FLAG_SKIP_DNS_SERVER,
+        GAA_FLAG_SKIP_MULTICAST, IF_TYPE_ETHERNET_CSMACD, IF_TYPE_IEEE80211, IF_TYPE_PPP,
+        IF_TYPE_PROP_VIRTUAL, IF_TYPE_TUNNEL, IF_TYPE_WWANPP, IF_TYPE_WWANPP2,
+        IP_ADAPTER_ADDRESSES_LH,
+    };
+    use windows_sys::Win32::NetworkManagement::Ndis::IfOperStatusUp;
+    use windows_sys::Win32::Networking::WinSock::{
+        AF_INET, AF_INET6, AF_UNSPEC, SOCKADDR_IN, SOCKADDR_IN6,
+    };
+
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    /// VPN clients whose adapters report themselves as plain Ethernet.
+    const VPN_DESCRIPTIONS: [&str; 4] = ["vpn", "tap-windows", "wintun", "wireguard"];
+
+    pub fn interfaces() -> Vec<Interface> {
+        let flags = GAA_FLAG_SKIP_ANYCAST | GAA_FLAG_SKIP_MULTICAST | GAA_FLAG_SKIP_DNS_SERVER;
+        // Microsoft's suggested starting size; the call reports the size it
+        // needs if adapters were added in between.
+        let mut size: u32 = 15 * 1024;
+        let mut buffer: Vec<u64> = Vec::new();
+        for _ in 0..3 {
+            buffer = vec![0; (size as usize).div_ceil(8)];
+            // SAFETY: `buffer` is at least `size` bytes and 8-byte aligned.
+            let rc = unsafe {
+                GetAdaptersAddresses(
+                    AF_UNSPEC as u32,
+                    flags,
+                    std::ptr::null(),
+                    buffer.as_mut_ptr().cast(),
+                    &mut size,
+                )
+            };
+            match rc {
+                ERROR_SUCCESS => return walk(buffer.as_ptr().cast()),
+                ERROR_BUFFER_OVERFLOW => continue,
+                _ => break,
+            }
+        }
+        Vec::new()
+    }
+
+    fn walk(mut adapter: *const IP_ADAPTER_ADDRESSES_LH) -> Vec<Interface> {
+        let mut out = Vec::new();
+        // SAFETY: the list and everything it points to live in the buffer
+        // filled by GetAdaptersAddresses, which outlives this walk.
+        while let Some(a) = unsafe { adapter.as_ref() } {
+            adapter = a.Next;
+            if a.OperStatus != IfOperStatusUp {
+                continue;
+            }
+            let name = unsafe { wide(a.FriendlyName) };
+            let description = unsafe { wide(a.Description) }.to_lowercase();
+            let tunnel = matches!(
+                a.IfType,
+                IF_TYPE_PPP | IF_TYPE_TUNNEL | IF_TYPE_PROP_VIRTUAL
+            ) || VPN_DESCRIPTIONS.iter().any(|d| description.contains(d));
+            let kind = match a.IfType {
+                _ if tunnel => InterfaceKind::Other,
+                IF_TYPE_IEEE80211 => InterfaceKind::Wifi,
+                IF_TYPE_WWANPP | IF_TYPE_WWANPP2 => InterfaceKind::Cellular,
+                IF_TYPE_ETHERNET_CSMACD => InterfaceKind::Ethernet,
+                _ => InterfaceKind::Other,
+            };
+            let mut unicast = a.FirstUnicastAddress;
+            while let Some(u) = unsafe { unicast.as_ref() } {
+                unicast = u.Next;
+                let sockaddr = u.Address.lpSockaddr;
+                if sockaddr.is_null() {
+                    continue;
+                }
+                let address = unsafe {
+                    match (*sockaddr).sa_family {
+                        AF_INET => {
+                            let sin = &*(sockaddr as *const SOCKADDR_IN);
+                            IpAddr::V4(Ipv4Addr::from(u32::from_be(sin.sin_addr.S_un.S_addr)))
+                        }
+                        AF_INET6 => {
+                            let sin6 = &*(sockaddr as *const SOCKADDR_IN6);
+                            IpAddr::V6(Ipv6Addr::from(sin6.sin6_addr.u.Byte))
+                        }
+                        _ => continue,
+                    }
+                };
+                out.push(Interface {
+                    name: name.clone(),
+                    address,
+                    kind,
+                    tunnel,
+                });
+            }
+        }
+        out
+    }
+
+    /// `IfType` already says what the adapter is.
+    pub fn refine_kind(_name: &str, kind: InterfaceKind) -> InterfaceKind {
+        kind
+    }
+
+    /// SAFETY: `p` is null or a NUL-terminated UTF-16 string.
+    unsafe fn wide(p: *const u16) -> String {
+        if p.is_null() {
+            return String::new();
+        }
+        let len = (0..).take_while(|&i| *p.add(i) != 0).count();
+        String::from_utf16_lossy(std::slice::from_raw_parts(p, len))
+    }
+
+    /// From `netsh wlan show interfaces`: the `SSID` line of the block for
+    /// `name` (not `BSSID`, which is the access point).
+    pub fn ssid(name: &str) -> Option<String> {
+        let out = Command::new("netsh")
+            .args(["wlan", "show", "interfaces"])
+            .creation_flags(CREATE_NO_WINDOW)
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output()
+            .ok()?;
+        let text = String::from_utf8_lossy(&out.stdout);
+        let mut in_block = false;
+        for line in text.lines() {
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            match key.trim() {
+                "Name" => in_block = value.trim() == name,
+                "SSID" if in_block => return Some(value.trim().to_string()),
+                _ => {}
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    fn interface(name: &str, last: u8, kind: InterfaceKind, tunnel: bool) -> Interface {
+        Interface {
+            name: name.to_string(),
+            address: IpAddr::V4(Ipv4Addr::new(10, 0, 0, last)),
+            kind,
+            tunnel,
+        }
+    }
+
+    fn route(last: u8) -> Route {
+        Route {
+            v4: Some(IpAddr::V4(Ipv4Addr::new(10, 0, 0, last))),
+            v6: None,
+        }
+    }
+
+    #[test]
+    fn describes_the_interface_under_the_route() {
+        let interfaces = [
+            interface("docker0", 1, InterfaceKind::Other, false),
+            interface("eth0", 2, InterfaceKind::Ethernet, false),
+            interface("wlan0", 3, InterfaceKind::Wifi, false),
+        ];
+        let context = describe(&route(3), &interfaces);
+        assert_eq!(context.interface.as_deref(), Some("wlan0"));
+        assert_eq!(context.kind, Some(InterfaceKind::Wifi));
+        assert!(!context.vpn);
+
+        let offline = describe(&Route { v4: None, v6: None }, &interfaces);
+        assert_eq!(offline.interface, None);
+    }
+
+    #[test]
+    fn a_full_tunnel_vpn_still_names_the_physical_interface() {
+        let interfaces = [
+            interface("docker0", 1, InterfaceKind::Other, false),
+            interface("wlan0", 3, InterfaceKind::Wifi, false),
+            interface("wg0", 9, InterfaceKind::Other, true),
+        ];
+        let context = describe(&route(9), &interfaces);
+        assert_eq!(context.interface.as_deref(), Some("wlan0"));
+        assert!(context.vpn);
+    }
+
+    #[test]
+    fn ssid_hash_is_short_and_stable() {
+        let hash = hash_ssid("Hotel Guest");
+        assert_eq!(hash.len(), SSID_HASH_LEN);
+        assert_eq!(hash, hash_ssid("Hotel Guest"));
+        assert_ne!(hash, hash_ssid("Hotel Guest 5G"));
+    }
+}
@@ -0,0 +1,258 @@
+use crate::commands;
+use crate::models::Server;
+use crate::state::AppState;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, UdpSocket};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+pub const NETWORK_EVENT: &str = "network-changed";
+
+/// How often the loop re-reads the outbound route.
+const CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Documentation addresses: never answered, but routed like any public host.
+const PROBE_V4: SocketAddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1)), 9);
+const PROBE_V6: SocketAddr = SocketAddr::new(
+    IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1)),
+    9,
+);
+
+/// Source addresses the OS picks for traffic to the internet. Switching
+/// from Wi-Fi to Ethernet, joining another network or bringing a VPN up or
+/// down changes at least one of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Route {
+    pub v4: Option<IpAddr>,
+    pub v6: Option<IpAddr>,
+}
+
+impl Route {
+    /// Connecting a UDP socket only asks the routing table; nothing is sent.
+    pub fn current() -> Self {
+        Self {
+            v4: source_address("0.0.0.0:0", PROBE_V4),
+            v6: source_address("[::]:0", PROBE_V6),
+        }
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.v4.is_none() && self.v6.is_none()
+    }
+}
+
+fn source_address(bind: &str, to: SocketAddr) -> Option<IpAddr> {
+    let socket = UdpSocket::bind(bind).ok()?;
+    socket.connect(to).ok()?;
+    Some(socket.local_addr().ok()?.ip())
+}
+
+/// A VPN coming up goes through a few intermediate routes; only act once
+/// the same route is read twice in a row.
+#[derive(Debug)]
+struct RouteTracker {
+    settled: Route,
+    pending: Option<Route>,
+}
+
+impl RouteTracker {
+    fn new(initial: Route) -> Self {
+        Self {
+            settled: initial,
+            pending: None,
+        }
+    }
+
+    /// Returns true when `route` is a new route that has now settled.
+    fn observe(&mut self, route: Route) -> bool {
+        if route == self.settled {
+            self.pending = None;
+            return false;
+        }
+        if self.pending == Some(route) {
+            self.settled = route;
+            self.pending = None;
+            return true;
+        }
+        self.pending = Some(route);
+        false
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NetworkChange {
+    pub changed_at: DateTime<Utc>,
+    /// Servers whose resync was started; empty when auto-resync is off or
+    /// paused, or the machine went offline.
+    pub resyncing: Vec<i64>,
+}
+
+/// Offsets measured before the last network change were taken over a path
+/// that no longer exists, and possibly against another CDN node.
+pub struct NetworkWatch {
+    changed_at: Mutex<Option<DateTime<Utc>>>,
+}
+
+impl NetworkWatch {
+    pub fn new() -> Self {
+        Self {
+            changed_at: Mutex::new(None),
+        }
+    }
+
+    /// When the route last changed since launch.
+    pub fn changed_at(&self) -> Option<DateTime<Utc>> {
+        *self.changed_at.lock().expect("network watch poisoned")
+    }
+}
+
+/// Whether `server`'s offset predates the network change at `changed_at`.
+pub fn is_suspect(server: &Server, changed_at: Option<DateTime<Utc>>) -> bool {
+    matches!((server.last_sync_at, changed_at), (Some(synced), Some(changed)) if synced < changed)
+}
+
+pub fn start(app: &AppHandle) {
+    tauri::async_runtime::spawn(run(app.clone()));
+}
+
+async fn run(app: AppHandle) {
+    let mut tracker = RouteTracker::new(read_route().await);
+    let mut interval = tokio::time::interval(CHECK_INTERVAL);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    loop {
+        interval.tick().await;
+        let route = read_route().await;
+        if tracker.observe(route) {
+            log::info!("network route changed to {route:?}");
+            on_change(&app, &route).await;
+        }
+    }
+}
+
+/// Whether the machine has no route to the internet right now.
+pub async fn is_offline() -> bool {
+    read_route().await.is_offline()
+}
+
+async fn read_route() -> Route {
+    tokio::task::spawn_blocking(Route::current)
+        .await
+        .unwrap_or(Route { v4: None, v6: None })
+}
+
+/// Resync what the auto-resync scheduler would eventually resync anyway,
+/// now rather than when the interval runs out.
+async fn on_change(app: &AppHandle, route: &Route) {
+    let state = app.state::<AppState>();
+    let now = Utc::now();
+    *state
+        .network
+        .changed_at
+        .lock()
+        .expect("network watch poisoned") = Some(now);
+    state.scheduler.set_offline(app, route.is_offline());
+
+    let servers = if route.is_offline() || state.scheduler.status().paused {
+        Vec::new()
+    } else {
+        let handle = app.clone();
+        tokio::task::spawn_blocking(move || {
+            let state = handle.state::<AppState>();
+            match state.db.get_settings() {
+                Ok(s) if s.auto_resync_interval_minutes > 0 => {
+                    state.db.list_servers().unwrap_or_default()
+                }
+                _ => Vec::new(),
+            }
+        })
+        .await
+        .unwrap_or_default()
+    };
+    let mut resyncing = Vec::new();
+    for server in servers.iter().filter(|s| is_suspect(s, Some(now))) {
+        let running = state
+            .active_syncs
+            .lock()
+            .expect("active_syncs poisoned")
+            .contains_key(&server.id);
+        if running {
+            continue;
+        }
+        match commands::spawn_sync(app, server.id, commands::broadcast_sink(app)) {
+            Ok(()) => resyncing.push(server.id),
+            Err(e) => log::warn!(
+                "network-change resync of server {} failed to start: {e}",
+                server.id
+            ),
+        }
+    }
+
+    let change = NetworkChange {
+        changed_at: now,
+        resyncing,
+    };
+    if let Err(e) = app.emit(NETWORK_EVENT, change) {
+        log::warn!("failed to emit network event: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn route(last: u8) -> Route {
+        Route {
+            v4: Some(IpAddr::V4(Ipv4Addr::new(192, 168, 1, last))),
+            v6: None,
+        }
+    }
+
+    #[test]
+    fn tracker_waits_for_the_route_to_settle() {
+        let mut tracker = RouteTracker::new(route(10));
+        assert!(!tracker.observe(route(10)));
+        assert!(!tracker.observe(route(20)));
+        // A different intermediate route restarts the wait.
+        assert!(!tracker.observe(route(30)));
+        assert!(tracker.observe(route(30)));
+        assert!(!tracker.observe(route(30)));
+        // A one-read blip is not a change.
+        assert!(!tracker.observe(route(20)));
+        assert!(!tracker.observe(route(30)));
+    }
+
+    #[test]
+    fn offsets_synced_before_the_change_are_suspect() {
+        let now = Utc::now();
+        let mut server = Server {
+            id: 1,
+            url: "https://example.com".to_string(),
+            name: None,
+            offset_ms: Some(10.0),
+            last_sync_at: Some(now - Duration::minutes(1)),
+            created_at: now,
+            status: crate::models::ServerStatus::Synced,
+            extractor_type: "date_header".to_string(),
+            probe_method: "HEAD".to_string(),
+            domain_preset: None,
+            sync_profile: None,
+            fingerprint: None,
+            asymmetry: None,
+            sync_on_launch: false,
+            health_score: None,
+            drift: None,
+            min_request_interval_ms: None,
+            trusted: false,
+            user_agent: None,
+            transport: Default::default(),
+            timezone: None,
+            timezone_guessed: false,
+        };
+        assert!(!is_suspect(&server, None));
+        assert!(is_suspect(&server, Some(now)));
+        server.last_sync_at = Some(now + Duration::seconds(1));
+        assert!(!is_suspect(&server, Some(now)));
+    }
+}
@@ -0,0 +1,202 @@
+use crate::error::AppError;
+use serde::Serialize;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::net::UdpSocket;
+
+pub const DEFAULT_NTP_HOST: &str = "pool.ntp.org";
+
+const NTP_PORT: u16 = 123;
+pub(crate) const PACKET_LEN: usize = 48;
+/// Seconds between the NTP epoch (1900) and the Unix epoch (1970).
+const NTP_UNIX_DELTA_SECS: f64 = 2_208_988_800.0;
+const QUERY_TIMEOUT: Duration = Duration::from_secs(3);
+const SAMPLES: usize = 4;
+
+/// Local clock error against an NTP server.
+#[derive(Debug, Clone, Serialize)]
+pub struct NtpMeasurement {
+    pub server: String,
+    /// Reference minus local: positive means the local clock is behind.
+    pub offset_ms: f64,
+    pub round_trip_ms: f64,
+}
+
+/// Query `host` a few times and keep the sample with the shortest round
+/// trip, whose offset is least skewed by path asymmetry.
+pub async fn measure(host: &str) -> Result<NtpMeasurement, AppError> {
+    let socket = UdpSocket::bind(("0.0.0.0", 0)).await?;
+    socket.connect((host, NTP_PORT)).await?;
+
+    let mut best: Option<(f64, f64)> = None;
+    let mut last_error = None;
+    for _ in 0..SAMPLES {
+        match query_once(&socket).await {
+            Ok((offset_ms, round_trip_ms)) => {
+                if !matches!(best, Some((_, rtt)) if rtt <= round_trip_ms) {
+                    best = Some((offset_ms, round_trip_ms));
+                }
+            }
+            Err(e) => last_error = Some(e),
+        }
+    }
+
+    match (best, last_error) {
+        (Some((offset_ms, round_trip_ms)), _) => Ok(NtpMeasurement {
+            server: host.to_string(),
+            offset_ms,
+            round_trip_ms,
+        }),
+        (None, Some(e)) => Err(e),
+        (None, None) => Err(AppError::Ntp("no samples collected".to_string())),
+    }
+}
+
+/// Round trip of each of `samples` queries to `host` in ms, `None` for one
+/// that got no reply within `timeout`. Empty when the host cannot even be
+/// resolved.
+pub async fn round_trips(host: &str, samples: usize, timeout: Duration) -> Vec<Option<f64>> {
+    let socket = match UdpSocket::bind(("0.0.0.0", 0)).await {
+        Ok(socket) if socket.connect((host, NTP_PORT)).await.is_ok() => socket,
+        _ => return Vec::new(),
+    };
+    let mut rtts = Vec::with_capacity(samples);
+    for _ in 0..samples {
+        let reply = tokio::time::timeout(timeout, query_once(&socket)).await;
+        rtts.push(match reply {
+            Ok(Ok((_, round_trip_ms))) => Some(round_trip_ms),
+            _ => None,
+        });
+    }
+    rtts
+}
+
+async fn query_once(socket: &UdpSocket) -> Result<(f64, f64), AppError> {
+    let t1 = now_unix_ms();
+    socket.send(&request_packet(t1)).await?;
+
+    let mut buf = [0u8; PACKET_LEN];
+    let len = tokio::time::timeout(QUERY_TIMEOUT, socket.recv(&mut buf))
+        .await
+        .map_err(|_| AppError::Ntp("timed out waiting for reply".to_string()))??;
+    let t4 = now_unix_ms();
+
+    if len < PACKET_LEN {
+        return Err(AppError::Ntp(format!("short reply ({len} bytes)")));
+    }
+    parse_response(&buf, t1, t4)
+}
+
+fn now_unix_ms() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system clock before 1970")
+        .as_secs_f64()
+        * 1000.0
+}
+
+/// Client request: LI 0, version 4, mode 3, our send time as transmit stamp.
+pub(crate) fn request_packet(t1_unix_ms: f64) -> [u8; PACKET_LEN] {
+    let mut packet = [0u8; PACKET_LEN];
+    packet[0] = 0b00_100_011;
+    packet[40..48].copy_from_slice(&unix_ms_to_ntp(t1_unix_ms));
+    packet
+}
+
+/// Returns `(offset_ms, round_trip_ms)` using the standard four-timestamp
+/// formulas.
+pub(crate) fn parse_response(
+    buf: &[u8; PACKET_LEN],
+    t1: f64,
+    t4: f64,
+) -> Result<(f64, f64), AppError> {
+    let mode = buf[0] & 0b111;
+    if mode != 4 {
+        return Err(AppError::Ntp(format!("unexpected mode {mode}")));
+    }
+    if buf[1] == 0 {
+        return Err(AppError::Ntp("server sent kiss-o'-death".to_string()));
+    }
+    // The server echoes our transmit stamp as its originate stamp.
+    if buf[24..32] != unix_ms_to_ntp(t1) {
+        return Err(AppError::Ntp("reply does not match request".to_string()));
+    }
+
+    let t2 = ntp_to_unix_ms(&buf[32..40]);
+    let t3 = ntp_to_unix_ms(&buf[40..48]);
+    let offset = ((t2 - t1) + (t3 - t4)) / 2.0;
+    let round_trip = (t4 - t1) - (t3 - t2);
+    Ok((offset, round_trip))
+}
+
+fn ntp_to_unix_ms(bytes: &[u8]) -> f64 {
+    let secs = u32::from_be_bytes(bytes[0..4].try_into().unwrap()) as f64;
+    let frac = u32::from_be_bytes(bytes[4..8].try_into().unwrap()) as f64 / 4_294_967_296.0;
+    (secs + frac - NTP_UNIX_DELTA_SECS) * 1000.0
+}
+
+pub(crate) fn unix_ms_to_ntp(unix_ms: f64) -> [u8; 8] {
+    let ntp_secs = unix_ms / 1000.0 + NTP_UNIX_DELTA_SECS;
+    let secs = ntp_secs.trunc() as u32;
+    let frac = (ntp_secs.fract() * 4_294_967_296.0) as u32;
+    let mut out = [0u8; 8];
+    out[0..4].copy_from_slice(&secs.to_be_bytes());
+    out[4..8].copy_from_slice(&frac.to_be_bytes());
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reply(t1: f64, t2: f64, t3: f64) -> [u8; PACKET_LEN] {
+        let mut buf = [0u8; PACKET_LEN];
+        buf[0] = 0b00_100_100; // version 4, server mode
+        buf[1] = 2; // stratum
+        buf[24..32].copy_from_slice(&unix_ms_to_ntp(t1));
+        buf[32..40].copy_from_slice(&unix_ms_to_ntp(t2));
+        buf[40..48].copy_from_slice(&unix_ms_to_ntp(t3));
+        buf
+    }
+
+    #[test]
+    fn ntp_timestamp_roundtrip() {
+        let ms = 1_700_000_000_123.5;
+        let back = ntp_to_unix_ms(&unix_ms_to_ntp(ms));
+        assert!((back - ms).abs() < 0.01, "got {back}");
+    }
+
+    #[test]
+    fn request_packet_is_v4_client_with_transmit_stamp() {
+        let packet = request_packet(1_700_000_000_000.0);
+        assert_eq!(packet[0] >> 3 & 0b111, 4);
+        assert_eq!(packet[0] & 0b111, 3);
+        assert_eq!(packet[40..48], unix_ms_to_ntp(1_700_000_000_000.0));
+    }
+
+    #[test]
+    fn parse_response_computes_offset_and_round_trip() {
+        // Local clock 100 ms behind, 20 ms each way, 5 ms server processing.
+        let t1 = 1_700_000_000_000.0;
+        let t2 = t1 + 20.0 + 100.0;
+        let t3 = t2 + 5.0;
+        let t4 = t1 + 45.0;
+        let (offset, rtt) = parse_response(&reply(t1, t2, t3), t1, t4).unwrap();
+        assert!((offset - 100.0).abs() < 0.01, "offset {offset}");
+        assert!((rtt - 40.0).abs() < 0.01, "rtt {rtt}");
+    }
+
+    #[test]
+    fn parse_response_rejects_mismatched_originate() {
+        let t1 = 1_700_000_000_000.0;
+        let buf = reply(t1 - 1.0, t1, t1);
+        assert!(parse_response(&buf, t1, t1 + 10.0).is_err());
+    }
+
+    #[test]
+    fn parse_response_rejects_kiss_of_death() {
+        let t1 = 1_700_000_000_000.0;
+        let mut buf = reply(t1, t1, t1);
+        buf[1] = 0;
+        assert!(parse_response(&buf, t1, t1).is_err());
+    }
+}
@@ -0,0 +1,123 @@
+use crate::db::Database;
+use crate::error::AppError;
+use crate::export_crypto;
+use crate::models::SyncResult;
+use crate::settings_validation::FieldError;
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Serialize;
+use std::path::Path;
+
+pub const CSV: &str = "csv";
+pub const JSON: &str = "json";
+
+const CSV_HEADER: &str = "timestamp,offset_ms,uncertainty_ms,verified";
+
+/// One sync, reduced to what a plot of the offset over time needs.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SeriesPoint {
+    pub timestamp: DateTime<Utc>,
+    pub offset_ms: f64,
+    /// Half the fastest round trip, as in the sync report.
+    pub uncertainty_ms: f64,
+    pub verified: bool,
+}
+
+impl From<&SyncResult> for SeriesPoint {
+    fn from(result: &SyncResult) -> Self {
+        Self {
+            timestamp: result.synced_at,
+            offset_ms: result.total_offset_ms,
+            uncertainty_ms: result.latency_profile.min * 1000.0 / 2.0,
+            verified: result.verified,
+        }
+    }
+}
+
+/// Write the server's whole history as an offset time series, oldest
+/// first. Unlike the history export it carries no latency profiles, so
+/// plotting tools can read it as is (unless a `passphrase` encrypts it).
+pub fn export_to(
+    db: &Database,
+    server_id: i64,
+    path: &Path,
+    format: &str,
+    passphrase: Option<&str>,
+) -> Result<usize, AppError> {
+    db.get_server(server_id)?;
+    let mut points: Vec<SeriesPoint> = db
+        .get_sync_history(server_id, None, None)?
+        .iter()
+        .map(SeriesPoint::from)
+        .collect();
+    points.reverse();
+    export_crypto::write(path, render(&points, format)?.as_bytes(), passphrase)?;
+    Ok(points.len())
+}
+
+/// `format` is `"csv"` or `"json"`.
+pub fn render(points: &[SeriesPoint], format: &str) -> Result<String, AppError> {
+    match format {
+        CSV => {
+            let mut out = String::from(CSV_HEADER);
+            for p in points {
+                out.push_str(&format!(
+                    "\n{},{},{},{}",
+                    p.timestamp.to_rfc3339_opts(SecondsFormat::Millis, true),
+                    p.offset_ms,
+                    p.uncertainty_ms,
+                    p.verified
+                ));
+            }
+            out.push('\n');
+            Ok(out)
+        }
+        JSON => Ok(serde_json::to_string_pretty(points).expect("series is serializable")),
+        other => Err(AppError::Validation(vec![FieldError {
+            field: "format".to_string(),
+            message: format!("unknown series format \"{other}\" (use csv or json)"),
+        }])),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn points() -> Vec<SeriesPoint> {
+        vec![
+            SeriesPoint {
+                timestamp: Utc.with_ymd_and_hms(2026, 3, 1, 9, 0, 0).unwrap(),
+                offset_ms: -412.5,
+                uncertainty_ms: 15.0,
+                verified: true,
+            },
+            SeriesPoint {
+                timestamp: Utc.with_ymd_and_hms(2026, 3, 1, 10, 0, 0).unwrap(),
+                offset_ms: -410.25,
+                uncertainty_ms: 16.5,
+                verified: false,
+            },
+        ]
+    }
+
+    #[test]
+    fn csv_has_one_row_per_sync() {
+        assert_eq!(
+            render(&points(), CSV).unwrap(),
+            "timestamp,offset_ms,uncertainty_ms,verified\n\
+             2026-03-01T09:00:00.000Z,-412.5,15,true\n\
+             2026-03-01T10:00:00.000Z,-410.25,16.5,false\n"
+        );
+        assert_eq!(render(&[], CSV).unwrap(), format!("{CSV_HEADER}\n"));
+    }
+
+    #[test]
+    fn json_is_an_array_of_points() {
+        let json: serde_json::Value =
+            serde_json::from_str(&render(&points(), JSON).unwrap()).unwrap();
+        assert_eq!(json[1]["offset_ms"], -410.25);
+        assert_eq!(json[0]["timestamp"], "2026-03-01T09:00:00Z");
+        assert!(render(&points(), "xlsx").is_err());
+    }
+}
@@ -0,0 +1,281 @@
+use std::any::Any;
+use std::sync::{Arc, Mutex};
+
+/// Something that can stop the OS from sleeping. The returned handle keeps
+/// the inhibition alive; dropping it lets the machine sleep again.
+pub(crate) trait InhibitBackend: Send + Sync {
+    fn inhibit(&self) -> std::io::Result<Box<dyn Any + Send>>;
+}
+
+/// Reference-counted sleep inhibition: the OS is kept awake while at least
+/// one `SleepGuard` is alive, however many overlapping syncs hold one.
+pub struct SleepInhibitor {
+    backend: Box<dyn InhibitBackend>,
+    inner: Mutex<Holders>,
+}
+
+struct Holders {
+    count: usize,
+    handle: Option<Box<dyn Any + Send>>,
+}
+
+/// Releases its share of the inhibition when dropped.
+pub struct SleepGuard {
+    inhibitor: Arc<SleepInhibitor>,
+}
+
+impl SleepInhibitor {
+    pub fn new() -> Self {
+        Self::with_backend(Box::new(platform::PlatformBackend))
+    }
+
+    pub(crate) fn with_backend(backend: Box<dyn InhibitBackend>) -> Self {
+        Self {
+            backend,
+            inner: Mutex::new(Holders {
+                count: 0,
+                handle: None,
+            }),
+        }
+    }
+
+    /// Keep the machine awake until the returned guard is dropped.
+    pub fn acquire(self: &Arc<Self>) -> SleepGuard {
+        let mut holders = self.inner.lock().expect("sleep inhibitor poisoned");
+        holders.count += 1;
+        if holders.count == 1 {
+            match self.backend.inhibit() {
+                Ok(handle) => holders.handle = Some(handle),
+                Err(e) => log::warn!("could not prevent system sleep: {e}"),
+            }
+        }
+        SleepGuard {
+            inhibitor: Arc::clone(self),
+        }
+    }
+
+    fn release(&self) {
+        let mut holders = self.inner.lock().expect("sleep inhibitor poisoned");
+        holders.count = holders.count.saturating_sub(1);
+        if holders.count == 0 {
+            holders.handle = None;
+        }
+    }
+}
+
+impl Default for SleepInhibitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for SleepGuard {
+    fn drop(&mut self) {
+        self.inhibitor.release();
+    }
+}
+
+// ── Platform backends ──
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+mod platform {
+    use super::InhibitBackend;
+    use std::any::Any;
+    use std::process::{Child, Command, Stdio};
+
+    pub struct PlatformBackend;
+
+    /// Inhibition lasts as long as the helper process runs.
+    struct ChildInhibit(Child);
+
+    impl Drop for ChildInhibit {
+        fn drop(&mut self) {
+            let _ = self.0.kill();
+            let _ = self.0.wait();
+        }
+    }
+
+    impl InhibitBackend for PlatformBackend {
+        fn inhibit(&self) -> std::io::Result<Box<dyn Any + Send>> {
+            #[cfg(target_os = "linux")]
+            let mut command = {
+                let mut c = Command::new("systemd-inhibit");
+                c.args([
+                    "--what=sleep:idle",
+                    "--who=Ticketime",
+                    "--why=Time synchronization in progress",
+                    "--mode=block",
+                    "sleep",
+                    "infinity",
+                ]);
+                c
+            };
+            #[cfg(target_os = "macos")]
+            let mut command = {
+                // -w ties caffeinate to our pid so it can never outlive the app.
+                let mut c = Command::new("caffeinate");
+                c.args(["-i", "-w", &std::process::id().to_string()]);
+                c
+            };
+
+            let child = command
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()?;
+            Ok(Box::new(ChildInhibit(child)))
+        }
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::InhibitBackend;
+    use std::any::Any;
+    use std::sync::mpsc;
+    use std::thread::JoinHandle;
+    use windows_sys::Win32::System::Power::{
+        SetThreadExecutionState, ES_CONTINUOUS, ES_SYSTEM_REQUIRED,
+    };
+
+    pub struct PlatformBackend;
+
+    /// Execution state is per-thread, so a dedicated thread holds it and
+    /// clears it once the sender is dropped.
+    struct ThreadInhibit {
+        stop: Option<mpsc::Sender<()>>,
+        thread: Option<JoinHandle<()>>,
+    }
+
+    impl Drop for ThreadInhibit {
+        fn drop(&mut self) {
+            self.stop.take();
+            if let Some(thread) = self.thread.take() {
+                let _ = thread.join();
+            }
+        }
+    }
+
+    impl InhibitBackend for PlatformBackend {
+        fn inhibit(&self) -> std::io::Result<Box<dyn Any + Send>> {
+            let (tx, rx) = mpsc::channel::<()>();
+            let thread = std::thread::Builder::new()
+                .name("sleep-inhibitor".into())
+                .spawn(move || {
+                    // SAFETY: plain Win32 call with valid flag constants.
+                    unsafe { SetThreadExecutionState(ES_CONTINUOUS | ES_SYSTEM_REQUIRED) };
+                    let _ = rx.recv();
+                    // SAFETY: as above; restores the default execution state.
+                    unsafe { SetThreadExecutionState(ES_CONTINUOUS) };
+                })?;
+            Ok(Box::new(ThreadInhibit {
+                stop: Some(tx),
+                thread: Some(thread),
+            }))
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+mod platform {
+    use super::InhibitBackend;
+    use std::any::Any;
+
+    pub struct PlatformBackend;
+
+    impl InhibitBackend for PlatformBackend {
+        fn inhibit(&self) -> std::io::Result<Box<dyn Any + Send>> {
+            Ok(Box::new(()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Default)]
+    struct Counters {
+        started: AtomicUsize,
+        stopped: AtomicUsize,
+    }
+
+    struct FakeHandle(Arc<Counters>);
+
+    impl Drop for FakeHandle {
+        fn drop(&mut self) {
+            self.0.stopped.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    struct FakeBackend {
+        counters: Arc<Counters>,
+        fail: bool,
+    }
+
+    impl InhibitBackend for FakeBackend {
+        fn inhibit(&self) -> std::io::Result<Box<dyn Any + Send>> {
+            if self.fail {
+                return Err(std::io::Error::other("no inhibitor"));
+            }
+            self.counters.started.fetch_add(1, Ordering::SeqCst);
+            Ok(Box::new(FakeHandle(self.counters.clone())))
+        }
+    }
+
+    fn is_active(inhibitor: &SleepInhibitor) -> bool {
+        inhibitor.inner.lock().unwrap().count > 0
+    }
+
+    fn fake_inhibitor(fail: bool) -> (Arc<SleepInhibitor>, Arc<Counters>) {
+        let counters = Arc::new(Counters::default());
+        let backend = FakeBackend {
+            counters: counters.clone(),
+            fail,
+        };
+        (
+            Arc::new(SleepInhibitor::with_backend(Box::new(backend))),
+            counters,
+        )
+    }
+
+    #[test]
+    fn overlapping_guards_share_one_inhibition() {
+        let (inhibitor, counters) = fake_inhibitor(false);
+
+        let a = inhibitor.acquire();
+        let b = inhibitor.acquire();
+        assert_eq!(counters.started.load(Ordering::SeqCst), 1);
+        assert!(is_active(&inhibitor));
+
+        drop(a);
+        assert_eq!(counters.stopped.load(Ordering::SeqCst), 0);
+        assert!(is_active(&inhibitor));
+
+        drop(b);
+        assert_eq!(counters.stopped.load(Ordering::SeqCst), 1);
+        assert!(!is_active(&inhibitor));
+    }
+
+    #[test]
+    fn reacquiring_after_release_starts_a_new_inhibition() {
+        let (inhibitor, counters) = fake_inhibitor(false);
+
+        drop(inhibitor.acquire());
+        drop(inhibitor.acquire());
+
+        assert_eq!(counters.started.load(Ordering::SeqCst), 2);
+        assert_eq!(counters.stopped.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn backend_failure_does_not_break_guard_accounting() {
+        let (inhibitor, _) = fake_inhibitor(true);
+
+        let guard = inhibitor.acquire();
+        assert!(is_active(&inhibitor));
+        drop(guard);
+        assert!(!is_active(&inhibitor));
+    }
+}
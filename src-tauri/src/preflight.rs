@@ -0,0 +1,179 @@
+use crate::error::AppError;
+use crate::server_url;
+use chrono::{DateTime, Utc};
+use reqwest::{header, redirect, StatusCode, Url, Version};
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+const MAX_REDIRECTS: usize = 10;
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// What a single probe learned about a candidate server, for instant
+/// feedback in the add-server dialog. Network failures are reported in
+/// `error` rather than as an `Err` so the dialog can still show them.
+#[derive(Debug, Clone, Serialize)]
+pub struct UrlValidation {
+    pub url: String,
+    pub final_url: String,
+    pub reachable: bool,
+    pub status: Option<u16>,
+    pub http_version: Option<String>,
+    pub has_date_header: bool,
+    pub server_time: Option<DateTime<Utc>>,
+    pub redirects: Vec<Redirect>,
+    /// Round trip of the last request in the chain.
+    pub rtt_ms: Option<f64>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Redirect {
+    pub from: String,
+    pub to: String,
+    pub status: u16,
+}
+
+pub async fn validate_url(url: String) -> Result<UrlValidation, AppError> {
+    let url = server_url::normalize(&url)?;
+    let start_url = Url::parse(&url).map_err(|e| AppError::InvalidUrl(e.to_string()))?;
+
+    // Redirects are followed by hand so each hop can be reported.
+    let client = reqwest::Client::builder()
+        .redirect(redirect::Policy::none())
+        .timeout(PROBE_TIMEOUT)
+        .build()?;
+
+    let mut report = UrlValidation {
+        url,
+        final_url: start_url.to_string(),
+        reachable: false,
+        status: None,
+        http_version: None,
+        has_date_header: false,
+        server_time: None,
+        redirects: Vec::new(),
+        rtt_ms: None,
+        error: None,
+    };
+
+    let mut current = start_url;
+    loop {
+        let started = Instant::now();
+        let response = match client.head(current.clone()).send().await {
+            Ok(response) => response,
+            Err(e) => {
+                report.error = Some(e.to_string());
+                break;
+            }
+        };
+        report.rtt_ms = Some(started.elapsed().as_secs_f64() * 1000.0);
+        report.reachable = true;
+        report.final_url = current.to_string();
+        report.status = Some(response.status().as_u16());
+        report.http_version = Some(version_label(response.version()).to_string());
+
+        let date = response.headers().get(header::DATE);
+        report.has_date_header = date.is_some();
+        report.server_time = date
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| DateTime::parse_from_rfc2822(v).ok())
+            .map(|dt| dt.with_timezone(&Utc));
+
+        let location = response
+            .headers()
+            .get(header::LOCATION)
+            .and_then(|v| v.to_str().ok());
+        let Some(next) = next_hop(&current, response.status(), location) else {
+            break;
+        };
+        if report.redirects.len() == MAX_REDIRECTS {
+            report.error = Some(format!("more than {MAX_REDIRECTS} redirects"));
+            break;
+        }
+        report.redirects.push(Redirect {
+            from: current.to_string(),
+            to: next.to_string(),
+            status: response.status().as_u16(),
+        });
+        current = next;
+    }
+
+    Ok(report)
+}
+
+/// Where a redirect response points, resolved against the current URL.
+fn next_hop(current: &Url, status: StatusCode, location: Option<&str>) -> Option<Url> {
+    if !status.is_redirection() {
+        return None;
+    }
+    current.join(location?).ok()
+}
+
+pub(crate) fn version_label(version: Version) -> &'static str {
+    match version {
+        Version::HTTP_09 => "HTTP/0.9",
+        Version::HTTP_10 => "HTTP/1.0",
+        Version::HTTP_11 => "HTTP/1.1",
+        Version::HTTP_2 => "HTTP/2",
+        Version::HTTP_3 => "HTTP/3",
+        _ => "unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::response::Redirect as AxumRedirect;
+    use axum::routing::get;
+    use axum::Router;
+
+    async fn serve(router: Router) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+        format!("http://{addr}")
+    }
+
+    #[test]
+    fn next_hop_resolves_relative_locations() {
+        let current = Url::parse("https://example.com/a/b").unwrap();
+        let next = next_hop(&current, StatusCode::FOUND, Some("/login")).unwrap();
+        assert_eq!(next.as_str(), "https://example.com/login");
+        assert!(next_hop(&current, StatusCode::OK, Some("/login")).is_none());
+        assert!(next_hop(&current, StatusCode::MOVED_PERMANENTLY, None).is_none());
+    }
+
+    #[tokio::test]
+    async fn follows_redirects_and_reports_date_header() {
+        let base = serve(
+            Router::new()
+                .route("/", get(|| async { AxumRedirect::temporary("/time") }))
+                .route("/time", get(|| async { "ok" })),
+        )
+        .await;
+
+        let report = validate_url(format!("{base}/")).await.unwrap();
+        assert!(report.reachable);
+        assert_eq!(report.status, Some(200));
+        assert_eq!(report.http_version.as_deref(), Some("HTTP/1.1"));
+        assert!(report.has_date_header);
+        assert!(report.server_time.is_some());
+        assert_eq!(report.redirects.len(), 1);
+        assert_eq!(report.redirects[0].status, 307);
+        assert_eq!(report.final_url, format!("{base}/time"));
+        assert!(report.rtt_ms.is_some());
+    }
+
+    #[tokio::test]
+    async fn unreachable_host_is_reported_not_raised() {
+        // Bind then drop to get a port nothing listens on.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+
+        let report = validate_url(format!("http://{addr}/")).await.unwrap();
+        assert!(!report.reachable);
+        assert!(report.error.is_some());
+        assert!(report.redirects.is_empty());
+    }
+}
@@ -0,0 +1,200 @@
+use crate::models::{IpFamily, RttBreakdown};
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use std::future::Future;
+use std::net::ToSocketAddrs;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tower::{Layer, Service};
+
+/// What the client's connection hooks saw during one request. Both stay
+/// `None` when the request went out on a pooled connection.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct Hooks {
+    dns: Option<Duration>,
+    /// Whole connector call: DNS, TCP and TLS handshakes.
+    connect: Option<Duration>,
+}
+
+/// Shared between a client's resolver and connector layer and the probe
+/// that reads them. Probes go out one at a time, so a single slot is enough.
+#[derive(Debug, Default, Clone)]
+pub struct ConnectTimings(Arc<Mutex<Hooks>>);
+
+impl ConnectTimings {
+    /// Forget what earlier requests (e.g. the warm-up) recorded.
+    pub fn reset(&self) {
+        *self.0.lock().unwrap() = Hooks::default();
+    }
+
+    /// Split `rtt` (seconds, request sent to headers received) into the
+    /// parts the hooks saw since the last `reset`.
+    pub fn breakdown(&self, rtt: f64) -> RttBreakdown {
+        let hooks = *self.0.lock().unwrap();
+        split(rtt, hooks)
+    }
+
+    /// A resolver that only hands out addresses of `family`.
+    pub fn resolver(&self, family: IpFamily) -> Arc<TimedResolver> {
+        Arc::new(TimedResolver(self.clone(), family))
+    }
+
+    pub fn layer(&self) -> TimedConnectLayer {
+        TimedConnectLayer(self.clone())
+    }
+}
+
+/// The connector call includes the DNS lookup, so the handshake is what is
+/// left of it; the rest of the round trip is time to first byte.
+fn split(rtt: f64, hooks: Hooks) -> RttBreakdown {
+    let dns = hooks.dns.map_or(0.0, |d| d.as_secs_f64());
+    let connect = hooks.connect.map_or(0.0, |d| d.as_secs_f64());
+    RttBreakdown {
+        dns,
+        connect: (connect - dns).max(0.0),
+        ttfb: (rtt - connect.max(dns)).max(0.0),
+        new_connections: u32::from(hooks.connect.is_some()),
+    }
+}
+
+/// System resolver (`getaddrinfo` on a blocking thread, like reqwest's
+/// default) that records how long each lookup took.
+pub struct TimedResolver(ConnectTimings, IpFamily);
+
+impl Resolve for TimedResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let timings = self.0.clone();
+        let family = self.1;
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            let start = Instant::now();
+            let lookup = host.clone();
+            let addrs = tokio::task::spawn_blocking(move || (lookup.as_str(), 0).to_socket_addrs())
+                .await??;
+            timings.0.lock().unwrap().dns = Some(start.elapsed());
+            let addrs: Vec<_> = addrs.filter(|addr| family.allows(addr)).collect();
+            if addrs.is_empty() {
+                let message = format!("{host} has no {family:?} address");
+                return Err(std::io::Error::new(std::io::ErrorKind::NotFound, message).into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Wraps the client's connector to time each new connection.
+#[derive(Clone)]
+pub struct TimedConnectLayer(ConnectTimings);
+
+impl<S> Layer<S> for TimedConnectLayer {
+    type Service = TimedConnect<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        TimedConnect {
+            inner,
+            timings: self.0.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct TimedConnect<S> {
+    inner: S,
+    timings: ConnectTimings,
+}
+
+impl<S, R> Service<R> for TimedConnect<S>
+where
+    S: Service<R>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: R) -> Self::Future {
+        let timings = self.timings.clone();
+        let start = Instant::now();
+        let connecting = self.inner.call(request);
+        Box::pin(async move {
+            let outcome = connecting.await;
+            timings.0.lock().unwrap().connect = Some(start.elapsed());
+            outcome
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pooled_connection_is_all_ttfb() {
+        let b = split(0.040, Hooks::default());
+        assert_eq!(b.dns, 0.0);
+        assert_eq!(b.connect, 0.0);
+        assert_eq!(b.ttfb, 0.040);
+        assert_eq!(b.new_connections, 0);
+    }
+
+    #[test]
+    fn new_connection_splits_out_lookup_and_handshake() {
+        let hooks = Hooks {
+            dns: Some(Duration::from_millis(5)),
+            connect: Some(Duration::from_millis(65)),
+        };
+        let b = split(0.100, hooks);
+        assert!((b.dns - 0.005).abs() < 1e-9);
+        assert!((b.connect - 0.060).abs() < 1e-9);
+        assert!((b.ttfb - 0.035).abs() < 1e-9);
+        assert_eq!(b.new_connections, 1);
+    }
+
+    #[tokio::test]
+    async fn resolver_keeps_only_the_pinned_family() {
+        let timings = ConnectTimings::default();
+        let name = || "127.0.0.1".parse::<Name>().unwrap();
+        let addrs: Vec<_> = timings
+            .resolver(IpFamily::Ipv4)
+            .resolve(name())
+            .await
+            .unwrap()
+            .collect();
+        assert_eq!(addrs.len(), 1);
+        assert!(timings
+            .resolver(IpFamily::Ipv6)
+            .resolve(name())
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn layer_records_connect_time() {
+        #[derive(Clone)]
+        struct Ready;
+        impl Service<()> for Ready {
+            type Response = ();
+            type Error = ();
+            type Future = std::future::Ready<Result<(), ()>>;
+            fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), ()>> {
+                Poll::Ready(Ok(()))
+            }
+            fn call(&mut self, _: ()) -> Self::Future {
+                std::future::ready(Ok(()))
+            }
+        }
+
+        let timings = ConnectTimings::default();
+        let mut service = timings.layer().layer(Ready);
+        service.call(()).await.unwrap();
+        assert_eq!(timings.breakdown(0.010).new_connections, 1);
+
+        timings.reset();
+        assert_eq!(timings.breakdown(0.010).new_connections, 0);
+    }
+}
@@ -0,0 +1,118 @@
+use crate::models::SyncPhase;
+use std::time::{Duration, Instant};
+
+/// Length of the window `limit` applies to.
+pub(crate) const WINDOW: Duration = Duration::from_millis(100);
+
+/// Caps how many progress events one sync sends per [`WINDOW`]. Binary
+/// search reports every iteration and latency profiling every probe; with
+/// several syncs running that is more than the webview can draw.
+///
+/// Phase transitions and urgent events (retries) always go through. The
+/// last event dropped in a phase is held back and sent just before the
+/// next phase starts, so the UI never misses a phase's final state.
+pub(crate) struct ProgressCoalescer<T> {
+    /// Events per window; 0 turns coalescing off.
+    limit: u32,
+    window_start: Option<Instant>,
+    sent_in_window: u32,
+    phase: Option<SyncPhase>,
+    pending: Option<T>,
+}
+
+impl<T> ProgressCoalescer<T> {
+    pub(crate) fn new(limit: u32) -> Self {
+        Self {
+            limit,
+            window_start: None,
+            sent_in_window: 0,
+            phase: None,
+            pending: None,
+        }
+    }
+
+    /// The events to send now, oldest first.
+    pub(crate) fn offer(
+        &mut self,
+        now: Instant,
+        phase: SyncPhase,
+        urgent: bool,
+        event: T,
+    ) -> Vec<T> {
+        if self.limit == 0 {
+            return vec![event];
+        }
+
+        let mut out = Vec::with_capacity(2);
+        let transition = self.phase != Some(phase);
+        if transition {
+            out.extend(self.pending.take());
+            self.phase = Some(phase);
+        }
+
+        if self
+            .window_start
+            .map_or(true, |start| now.duration_since(start) >= WINDOW)
+        {
+            self.window_start = Some(now);
+            self.sent_in_window = 0;
+        }
+
+        if transition || urgent || self.sent_in_window < self.limit {
+            self.sent_in_window += 1;
+            self.pending = None;
+            out.push(event);
+        } else {
+            self.pending = Some(event);
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn caps_events_per_window_and_keeps_the_latest() {
+        let mut c = ProgressCoalescer::new(2);
+        let t0 = Instant::now();
+        let phase = SyncPhase::BinarySearch;
+        let mut sent = Vec::new();
+        for i in 0..10 {
+            sent.extend(c.offer(t0 + Duration::from_millis(i), phase, false, i));
+        }
+        assert_eq!(sent, vec![0, 1]);
+
+        // The next window opens up again.
+        sent.extend(c.offer(t0 + WINDOW, phase, false, 10));
+        assert_eq!(sent, vec![0, 1, 10]);
+
+        // A held-back event is flushed ahead of the next phase.
+        sent.extend(c.offer(t0 + WINDOW, phase, false, 11));
+        sent.extend(c.offer(t0 + WINDOW, phase, false, 12));
+        sent.extend(c.offer(t0 + WINDOW, SyncPhase::Verification, false, 13));
+        assert_eq!(sent, vec![0, 1, 10, 11, 12, 13]);
+    }
+
+    #[test]
+    fn retries_always_pass() {
+        let mut c = ProgressCoalescer::new(1);
+        let t0 = Instant::now();
+        let phase = SyncPhase::LatencyProfiling;
+        assert_eq!(c.offer(t0, phase, false, 0), vec![0]);
+        assert!(c.offer(t0, phase, false, 1).is_empty());
+        assert_eq!(c.offer(t0, phase, true, 2), vec![2]);
+        assert!(c.offer(t0, phase, false, 3).is_empty());
+    }
+
+    #[test]
+    fn zero_limit_disables_coalescing() {
+        let mut c = ProgressCoalescer::new(0);
+        let t0 = Instant::now();
+        let sent: Vec<_> = (0..50)
+            .flat_map(|i| c.offer(t0, SyncPhase::BinarySearch, false, i))
+            .collect();
+        assert_eq!(sent.len(), 50);
+    }
+}
@@ -0,0 +1,100 @@
+use crate::db::{Database, NewServer};
+use crate::domain_presets::GET;
+use crate::error::AppError;
+use crate::models::Server;
+use crate::settings_validation::FieldError;
+use crate::sync_profile;
+use crate::time_extractor::{NIST, TIMEAPI_IO, WORLDTIMEAPI};
+use serde::Serialize;
+
+/// A public time service that can be added as a server to compare ticket
+/// servers against.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ReferenceSource {
+    pub kind: &'static str,
+    pub label: &'static str,
+    pub url: &'static str,
+    pub extractor_type: &'static str,
+    pub probe_method: &'static str,
+}
+
+pub const SOURCES: [ReferenceSource; 3] = [
+    ReferenceSource {
+        kind: "nist",
+        label: "NIST (time.gov)",
+        url: "https://time.gov/actualtime.cgi",
+        extractor_type: NIST,
+        probe_method: GET,
+    },
+    ReferenceSource {
+        kind: "worldtimeapi",
+        label: "WorldTimeAPI",
+        url: "https://worldtimeapi.org/api/timezone/Etc/UTC",
+        extractor_type: WORLDTIMEAPI,
+        probe_method: GET,
+    },
+    ReferenceSource {
+        kind: "timeapi_io",
+        label: "TimeAPI.io",
+        url: "https://timeapi.io/api/Time/current/zone?timeZone=UTC",
+        extractor_type: TIMEAPI_IO,
+        probe_method: GET,
+    },
+];
+
+pub fn find(kind: &str) -> Result<ReferenceSource, AppError> {
+    SOURCES.into_iter().find(|s| s.kind == kind).ok_or_else(|| {
+        let kinds: Vec<&str> = SOURCES.iter().map(|s| s.kind).collect();
+        AppError::Validation(vec![FieldError {
+            field: "kind".to_string(),
+            message: format!("must be one of {}", kinds.join(", ")),
+        }])
+    })
+}
+
+/// Add the source as a server, or return it if it is already there. Free
+/// public APIs get the polite profile, and the first one added becomes the
+/// reference server unless one is already chosen.
+pub fn add(db: &Database, kind: &str) -> Result<Server, AppError> {
+    let source = find(kind)?;
+    if let Some(existing) = db.find_server_by_url(source.url)? {
+        return Ok(existing);
+    }
+    let server = NewServer {
+        url: source.url.to_string(),
+        extractor_type: source.extractor_type.to_string(),
+        probe_method: source.probe_method.to_string(),
+        domain_preset: None,
+    };
+    db.add_reference_source(&server, source.label, sync_profile::POLITE.id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_configures_the_source_once() {
+        let db = Database::new_in_memory().unwrap();
+        let nist = add(&db, "nist").unwrap();
+        assert_eq!(nist.name.as_deref(), Some("NIST (time.gov)"));
+        assert_eq!(nist.extractor_type, NIST);
+        assert_eq!(nist.probe_method, GET);
+        assert_eq!(nist.sync_profile.as_deref(), Some("polite"));
+        assert_eq!(db.get_reference_server().unwrap(), Some(nist.id));
+
+        assert_eq!(add(&db, "nist").unwrap().id, nist.id);
+        let world = add(&db, "worldtimeapi").unwrap();
+        assert_ne!(world.id, nist.id);
+        assert_eq!(db.get_reference_server().unwrap(), Some(nist.id));
+        assert_eq!(db.list_servers().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn unknown_kinds_are_rejected() {
+        assert!(matches!(
+            find("pool.ntp.org"),
+            Err(AppError::Validation(ref fields)) if fields[0].field == "kind"
+        ));
+    }
+}
@@ -0,0 +1,464 @@
+use crate::commands;
+use crate::db::Database;
+use crate::health;
+use crate::models::{ScheduledSync, Server, ServerStatus};
+use crate::network_watch;
+use crate::state::AppState;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Emitter, Manager};
+
+pub const SCHEDULER_EVENT: &str = "scheduler-state";
+
+/// Emitted for each server resynced because its health score fell below
+/// `health_resync_threshold`.
+pub const HEALTH_RESYNC_EVENT: &str = "health-resync";
+
+/// Carries the remaining one-time schedules after some have started.
+pub const SCHEDULED_SYNCS_EVENT: &str = "scheduled-syncs";
+
+/// How often the loop looks for servers that are due.
+const CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// How often one-time schedules are checked, and so how late they can start.
+const SCHEDULE_TICK: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// One-time schedules missed by more than this (app closed, machine
+/// asleep) are dropped: a sync long after the moment it was meant to lead
+/// up to is no use.
+const MISSED_GRACE: Duration = Duration::minutes(10);
+
+/// A server resynced for poor health is left alone this long, so one whose
+/// score stays low (a jittery path) is not synced every check.
+const HEALTH_RESYNC_COOLDOWN: std::time::Duration = std::time::Duration::from_secs(15 * 60);
+
+/// Longest wait between connectivity checks while offline.
+const MAX_OFFLINE_BACKOFF: std::time::Duration = std::time::Duration::from_secs(600);
+
+/// Background loop that re-syncs servers whose offset has aged past
+/// `auto_resync_interval_minutes` or whose health score has dropped below
+/// `health_resync_threshold`. Pausing stops all of its traffic without
+/// touching the settings, and lasts until resumed or the app restarts.
+/// Without a network connection it holds off on its own and checks again
+/// with backoff.
+pub struct Scheduler {
+    paused: AtomicBool,
+    offline: AtomicBool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthResync {
+    pub server_id: i64,
+    pub health_score: u8,
+    pub threshold: u8,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SchedulerStatus {
+    pub paused: bool,
+    /// No network connection: due resyncs wait and are retried with
+    /// backoff, and corrected times come from offsets that cannot be
+    /// refreshed.
+    pub offline: bool,
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Self {
+            paused: AtomicBool::new(false),
+            offline: AtomicBool::new(false),
+        }
+    }
+
+    pub fn status(&self) -> SchedulerStatus {
+        SchedulerStatus {
+            paused: self.paused.load(Ordering::SeqCst),
+            offline: self.offline.load(Ordering::SeqCst),
+        }
+    }
+
+    pub fn set_paused(&self, app: &AppHandle, paused: bool) -> SchedulerStatus {
+        self.paused.store(paused, Ordering::SeqCst);
+        let status = self.status();
+        log::info!(
+            "auto-resync scheduler {}",
+            if paused { "paused" } else { "resumed" }
+        );
+        publish(app, &status);
+        status
+    }
+
+    /// Record connectivity, telling the UI only when it changes.
+    pub fn set_offline(&self, app: &AppHandle, offline: bool) {
+        if self.offline.swap(offline, Ordering::SeqCst) != offline {
+            log::info!(
+                "auto-resync scheduler {}",
+                if offline { "offline" } else { "back online" }
+            );
+            publish(app, &self.status());
+        }
+    }
+}
+
+fn publish(app: &AppHandle, status: &SchedulerStatus) {
+    if let Err(e) = app.emit(SCHEDULER_EVENT, status.clone()) {
+        log::warn!("failed to emit scheduler event: {e}");
+    }
+}
+
+pub fn start(app: &AppHandle) {
+    tauri::async_runtime::spawn(run(app.clone()));
+    tauri::async_runtime::spawn(run_scheduled(app.clone()));
+}
+
+/// Start the servers flagged `sync_on_launch`. Each is marked syncing
+/// before this returns, so the first server list the UI loads shows them.
+pub fn sync_on_launch(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let servers = match state.db.list_sync_on_launch_servers() {
+        Ok(servers) => servers,
+        Err(e) => {
+            log::warn!("failed to list servers to sync on launch: {e}");
+            return;
+        }
+    };
+    for server in servers {
+        if let Err(e) = commands::spawn_sync(app, server.id, commands::broadcast_sink(app)) {
+            log::warn!("launch sync of server {} failed to start: {e}", server.id);
+        }
+    }
+}
+
+async fn run(app: AppHandle) {
+    let mut interval = tokio::time::interval(CHECK_INTERVAL);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    let mut offline_checks = 0;
+    let mut retry_at = tokio::time::Instant::now();
+    let mut health_resynced: HashMap<i64, tokio::time::Instant> = HashMap::new();
+    loop {
+        interval.tick().await;
+        let state = app.state::<AppState>();
+        let status = state.scheduler.status();
+        if status.paused {
+            continue;
+        }
+        let now = Utc::now();
+        let handle = app.clone();
+        let read = tokio::task::spawn_blocking(move || {
+            let state = handle.state::<AppState>();
+            let settings = state.db.get_settings().ok()?;
+            let mut servers = state.db.list_servers().unwrap_or_default();
+            // Scores fall as offsets age, so recompute them on every check.
+            for server in servers.iter_mut().filter(|s| s.last_sync_at.is_some()) {
+                match health::refresh(&state.db, server.id, now) {
+                    Ok(score) => server.health_score = score,
+                    Err(e) => log::warn!("health of server {} not updated: {e}", server.id),
+                }
+            }
+            Some((settings, servers))
+        })
+        .await;
+        let Ok(Some((settings, servers))) = read else {
+            continue;
+        };
+        let tick = tokio::time::Instant::now();
+        health_resynced.retain(|_, at| tick.duration_since(*at) < HEALTH_RESYNC_COOLDOWN);
+        let unhealthy: Vec<i64> = unhealthy_servers(&servers, settings.health_resync_threshold)
+            .into_iter()
+            .filter(|id| !health_resynced.contains_key(id))
+            .collect();
+        let mut due = due_servers(&servers, settings.auto_resync_interval_minutes, now);
+        for id in &unhealthy {
+            if !due.contains(id) {
+                due.push(*id);
+            }
+        }
+        let due: Vec<i64> = due
+            .into_iter()
+            .filter(|id| {
+                !state
+                    .active_syncs
+                    .lock()
+                    .expect("active_syncs poisoned")
+                    .contains_key(id)
+            })
+            .collect();
+        if due.is_empty() {
+            continue;
+        }
+        // The network watcher clears `offline` as soon as a route is back.
+        if status.offline && tokio::time::Instant::now() < retry_at {
+            continue;
+        }
+        if network_watch::is_offline().await {
+            let backoff = offline_backoff(offline_checks);
+            offline_checks += 1;
+            retry_at = tokio::time::Instant::now() + backoff;
+            state.scheduler.set_offline(&app, true);
+            log::info!(
+                "{} resyncs due but offline; checking again in {} s",
+                due.len(),
+                backoff.as_secs()
+            );
+            continue;
+        }
+        offline_checks = 0;
+        state.scheduler.set_offline(&app, false);
+        for id in due {
+            if let Err(e) = commands::spawn_sync(&app, id, commands::broadcast_sink(&app)) {
+                log::warn!("scheduled resync of server {id} failed to start: {e}");
+                continue;
+            }
+            if !unhealthy.contains(&id) {
+                continue;
+            }
+            health_resynced.insert(id, tick);
+            let Some(health_score) = servers
+                .iter()
+                .find(|s| s.id == id)
+                .and_then(|s| s.health_score)
+            else {
+                continue;
+            };
+            log::info!("server {id} health {health_score} below threshold; resyncing");
+            let event = HealthResync {
+                server_id: id,
+                health_score,
+                threshold: settings.health_resync_threshold,
+            };
+            if let Err(e) = app.emit(HEALTH_RESYNC_EVENT, event) {
+                log::warn!("failed to emit health resync event: {e}");
+            }
+        }
+    }
+}
+
+/// Starts one-time syncs from `schedule_sync` when their time comes. These
+/// were asked for explicitly, so pausing auto-resync does not hold them.
+async fn run_scheduled(app: AppHandle) {
+    let mut interval = tokio::time::interval(SCHEDULE_TICK);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    loop {
+        interval.tick().await;
+        let handle = app.clone();
+        let taken = tokio::task::spawn_blocking(move || {
+            let state = handle.state::<AppState>();
+            let running = |id| {
+                state
+                    .active_syncs
+                    .lock()
+                    .expect("active_syncs poisoned")
+                    .contains_key(&id)
+            };
+            take_due(&state.db, running, Utc::now())
+        })
+        .await;
+        let Ok(Some((start, remaining))) = taken else {
+            continue;
+        };
+        for id in start {
+            if let Err(e) = commands::spawn_sync(&app, id, commands::broadcast_sink(&app)) {
+                log::warn!("scheduled sync of server {id} failed to start: {e}");
+            }
+        }
+        if let Err(e) = app.emit(SCHEDULED_SYNCS_EVENT, remaining) {
+            log::warn!("failed to emit scheduled syncs event: {e}");
+        }
+    }
+}
+
+/// Remove the one-time schedules that have come up. Returns the servers to
+/// sync and the schedules left, or `None` when none were removed. One whose
+/// server is already syncing stays, and starts once that sync is over.
+fn take_due(
+    db: &Database,
+    running: impl Fn(i64) -> bool,
+    now: DateTime<Utc>,
+) -> Option<(Vec<i64>, Vec<ScheduledSync>)> {
+    let pending = db.list_scheduled_syncs().ok()?;
+    let mut start = Vec::new();
+    let mut changed = false;
+    for schedule in pending.iter().take_while(|s| s.run_at <= now) {
+        let missed = is_missed(schedule, now);
+        if !missed && running(schedule.server_id) {
+            continue;
+        }
+        // Removed before starting, so a sync that fails to start is not
+        // retried every tick.
+        if let Err(e) = db.delete_scheduled_sync(schedule.id) {
+            log::warn!("failed to remove scheduled sync {}: {e}", schedule.id);
+            continue;
+        }
+        changed = true;
+        if missed {
+            log::warn!(
+                "dropped sync of server {} scheduled for {}, missed by more than {} min",
+                schedule.server_id,
+                schedule.run_at,
+                MISSED_GRACE.num_minutes()
+            );
+            continue;
+        }
+        // Two schedules come up at once: one sync serves both.
+        if !start.contains(&schedule.server_id) {
+            start.push(schedule.server_id);
+        }
+    }
+    changed.then(|| (start, db.list_scheduled_syncs().unwrap_or_default()))
+}
+
+/// Whether a due schedule is too late to be worth running.
+fn is_missed(schedule: &ScheduledSync, now: DateTime<Utc>) -> bool {
+    now - schedule.run_at > MISSED_GRACE
+}
+
+/// When the scheduler will next resync `server`. Servers that were never
+/// synced are left for the user to start; 0 disables resyncs.
+pub fn next_sync_at(server: &Server, interval_minutes: u32) -> Option<DateTime<Utc>> {
+    if interval_minutes == 0 {
+        return None;
+    }
+    Some(server.last_sync_at? + Duration::minutes(interval_minutes.into()))
+}
+
+/// Wait before connectivity check `checks` (0-based) while offline:
+/// the loop interval, doubling each time, capped.
+fn offline_backoff(checks: u32) -> std::time::Duration {
+    CHECK_INTERVAL
+        .saturating_mul(2u32.saturating_pow(checks.min(16)))
+        .min(MAX_OFFLINE_BACKOFF)
+}
+
+/// Servers whose last sync is at least `interval_minutes` old.
+fn due_servers(servers: &[Server], interval_minutes: u32, now: DateTime<Utc>) -> Vec<i64> {
+    servers
+        .iter()
+        .filter(|s| s.status != ServerStatus::Syncing)
+        .filter(|s| matches!(next_sync_at(s, interval_minutes), Some(at) if at <= now))
+        .map(|s| s.id)
+        .collect()
+}
+
+/// Servers whose health score is below `threshold`; 0 disables.
+fn unhealthy_servers(servers: &[Server], threshold: u8) -> Vec<i64> {
+    servers
+        .iter()
+        .filter(|s| s.status != ServerStatus::Syncing)
+        .filter(|s| health::needs_resync(s.health_score, threshold))
+        .map(|s| s.id)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(id: i64, last_sync_at: Option<DateTime<Utc>>, status: ServerStatus) -> Server {
+        Server {
+            id,
+            url: format!("https://{id}.example.com"),
+            name: None,
+            offset_ms: last_sync_at.map(|_| 0.0),
+            last_sync_at,
+            created_at: Utc::now(),
+            status,
+            extractor_type: "date_header".to_string(),
+            probe_method: "HEAD".to_string(),
+            domain_preset: None,
+            sync_profile: None,
+            fingerprint: None,
+            asymmetry: None,
+            sync_on_launch: false,
+            health_score: None,
+            drift: None,
+            min_request_interval_ms: None,
+            trusted: false,
+            user_agent: None,
+            transport: Default::default(),
+            timezone: None,
+            timezone_guessed: false,
+        }
+    }
+
+    #[test]
+    fn due_servers_picks_stale_idle_servers() {
+        let now = Utc::now();
+        let servers = [
+            server(1, Some(now - Duration::minutes(45)), ServerStatus::Synced),
+            server(2, Some(now - Duration::minutes(5)), ServerStatus::Synced),
+            server(3, Some(now - Duration::hours(2)), ServerStatus::Syncing),
+            server(4, None, ServerStatus::Idle),
+            server(5, Some(now - Duration::hours(1)), ServerStatus::Error),
+        ];
+        assert_eq!(due_servers(&servers, 30, now), vec![1, 5]);
+    }
+
+    #[test]
+    fn unhealthy_servers_fall_below_the_threshold() {
+        let now = Utc::now();
+        let mut servers = [
+            server(1, Some(now), ServerStatus::Synced),
+            server(2, Some(now), ServerStatus::Synced),
+            server(3, Some(now), ServerStatus::Syncing),
+            server(4, None, ServerStatus::Idle),
+        ];
+        servers[0].health_score = Some(30);
+        servers[1].health_score = Some(80);
+        servers[2].health_score = Some(10);
+        assert_eq!(unhealthy_servers(&servers, 50), vec![1]);
+        assert!(unhealthy_servers(&servers, 0).is_empty());
+    }
+
+    #[test]
+    fn zero_interval_disables_resyncs() {
+        let now = Utc::now();
+        let servers = [server(
+            1,
+            Some(now - Duration::days(1)),
+            ServerStatus::Synced,
+        )];
+        assert!(due_servers(&servers, 0, now).is_empty());
+    }
+
+    #[test]
+    fn schedules_missed_by_long_are_dropped() {
+        let now = Utc::now();
+        let schedule = |late: Duration| ScheduledSync {
+            id: 1,
+            server_id: 1,
+            run_at: now - late,
+            created_at: now - Duration::days(1),
+        };
+        assert!(!is_missed(&schedule(Duration::seconds(1)), now));
+        assert!(!is_missed(&schedule(MISSED_GRACE), now));
+        assert!(is_missed(&schedule(Duration::hours(1)), now));
+    }
+
+    #[test]
+    fn a_schedule_for_a_busy_server_waits_for_its_sync() {
+        let db = Database::new_in_memory().unwrap();
+        let server = db.add_server("https://example.com").unwrap();
+        let now = Utc::now();
+        db.add_scheduled_sync(server.id, now - Duration::seconds(1))
+            .unwrap();
+        db.add_scheduled_sync(server.id, now + Duration::minutes(5))
+            .unwrap();
+
+        assert!(take_due(&db, |_| true, now).is_none());
+        assert_eq!(db.list_scheduled_syncs().unwrap().len(), 2);
+
+        let (start, remaining) = take_due(&db, |_| false, now).unwrap();
+        assert_eq!(start, vec![server.id]);
+        assert_eq!(remaining.len(), 1);
+        assert!(take_due(&db, |_| false, now).is_none());
+    }
+
+    #[test]
+    fn offline_backoff_doubles_up_to_the_cap() {
+        assert_eq!(offline_backoff(0), CHECK_INTERVAL);
+        assert_eq!(offline_backoff(2), CHECK_INTERVAL * 4);
+        assert_eq!(offline_backoff(100), MAX_OFFLINE_BACKOFF);
+    }
+}
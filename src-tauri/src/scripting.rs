@@ -0,0 +1,284 @@
+use crate::error::AppError;
+use crate::settings_validation::FieldError;
+use crate::state::AppState;
+use rhai::{Dynamic, Engine, EvalAltResult, Scope, AST};
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+/// The user's script, in the app data directory.
+const SCRIPT_FILE: &str = "hooks.rhai";
+
+pub const ON_SYNC_COMPLETE: &str = "on_sync_complete";
+pub const ON_TARGET_FIRED: &str = "on_target_fired";
+pub const ON_DRIFT_WARNING: &str = "on_drift_warning";
+
+/// Functions a script may define; each is called with one map argument.
+const HOOKS: [&str; 3] = [ON_SYNC_COMPLETE, ON_TARGET_FIRED, ON_DRIFT_WARNING];
+
+/// Work one hook call may do, so a runaway loop ends in an error instead
+/// of pinning a thread.
+const MAX_OPERATIONS: u64 = 5_000_000;
+const MAX_CALL_LEVELS: usize = 32;
+
+const HTTP_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// User-written Rhai hooks run on app events, for integrations we don't
+/// ship: the script defines any of `HOOKS` and can call `http_get`,
+/// `http_post`, `arm_target`, `disarm_target` and `notify`; `print` goes to
+/// the app log. Only function definitions count; top-level statements are
+/// dropped.
+pub struct Hooks {
+    loaded: Mutex<Option<Arc<Loaded>>>,
+}
+
+struct Loaded {
+    engine: Engine,
+    ast: AST,
+}
+
+impl Hooks {
+    pub fn new() -> Self {
+        Self {
+            loaded: Mutex::new(None),
+        }
+    }
+
+    /// Load the saved script, if there is one. One that no longer compiles
+    /// is logged and left unloaded.
+    pub fn load_saved(&self, app: &AppHandle) {
+        let source = match script_path(app).map(std::fs::read_to_string) {
+            Ok(Ok(source)) => source,
+            _ => return,
+        };
+        if let Err(e) = self.load(app, &source) {
+            log::warn!("hook script not loaded: {e}");
+        }
+    }
+
+    /// Compile `source` and, if it compiles, save it and run it from now
+    /// on. Blank source removes the script. Returns the hooks it defines.
+    pub fn save(&self, app: &AppHandle, source: &str) -> Result<Vec<String>, AppError> {
+        let defined = self.load(app, source)?;
+        let path = script_path(app)?;
+        if source.trim().is_empty() {
+            if path.exists() {
+                std::fs::remove_file(path)?;
+            }
+        } else {
+            std::fs::write(path, source)?;
+        }
+        Ok(defined)
+    }
+
+    fn load(&self, app: &AppHandle, source: &str) -> Result<Vec<String>, AppError> {
+        let loaded = if source.trim().is_empty() {
+            None
+        } else {
+            let engine = engine(app);
+            let ast = compile(&engine, source)?;
+            Some(Arc::new(Loaded { engine, ast }))
+        };
+        let defined = loaded
+            .as_ref()
+            .map_or_else(Vec::new, |l| defined_hooks(&l.ast));
+        *self.loaded.lock().unwrap() = loaded;
+        Ok(defined)
+    }
+
+    /// Run hook `name` with `payload` on a blocking thread, if the script
+    /// defines it. Failures are logged.
+    pub fn fire(&self, name: &'static str, payload: &impl Serialize) {
+        let Some(loaded) = self.loaded.lock().unwrap().clone() else {
+            return;
+        };
+        if !loaded.ast.iter_functions().any(|f| f.name == name) {
+            return;
+        }
+        let arg = match rhai::serde::to_dynamic(payload) {
+            Ok(arg) => arg,
+            Err(e) => {
+                log::warn!("{name} payload not convertible: {e}");
+                return;
+            }
+        };
+        tauri::async_runtime::spawn_blocking(move || {
+            if let Err(e) = call(&loaded.engine, &loaded.ast, name, arg) {
+                log::warn!("hook {name} failed: {e}");
+            }
+        });
+    }
+}
+
+/// The saved script's source, empty when there is none.
+pub fn saved_source(app: &AppHandle) -> Result<String, AppError> {
+    match std::fs::read_to_string(script_path(app)?) {
+        Ok(source) => Ok(source),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn script_path(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let dir = app.path().app_data_dir().map_err(|e| {
+        AppError::Io(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            e.to_string(),
+        ))
+    })?;
+    Ok(dir.join(SCRIPT_FILE))
+}
+
+/// Limits and logging, without the functions that reach into the app.
+fn base_engine() -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine.set_max_call_levels(MAX_CALL_LEVELS);
+    engine.on_print(|text| log::info!("hook script: {text}"));
+    engine.on_debug(|text, _, pos| log::info!("hook script {pos}: {text}"));
+    engine
+}
+
+fn engine(app: &AppHandle) -> Engine {
+    let mut engine = base_engine();
+    engine.register_fn("http_get", |url: &str| {
+        http(reqwest::Method::GET, url, None)
+    });
+    engine.register_fn("http_post", |url: &str, body: Dynamic| {
+        http(reqwest::Method::POST, url, Some(body))
+    });
+
+    let handle = app.clone();
+    engine.register_fn("arm_target", move |id: i64| set_armed(&handle, id, true));
+    let handle = app.clone();
+    engine.register_fn("disarm_target", move |id: i64| {
+        set_armed(&handle, id, false)
+    });
+    let handle = app.clone();
+    engine.register_fn("notify", move |title: &str, body: &str| {
+        handle
+            .notification()
+            .builder()
+            .title(title)
+            .body(body)
+            .show()
+            .map_err(|e| -> Box<EvalAltResult> { e.to_string().into() })
+    });
+    engine
+}
+
+fn compile(engine: &Engine, source: &str) -> Result<AST, AppError> {
+    engine
+        .compile(source)
+        .map(|ast| ast.clone_functions_only())
+        .map_err(|e| {
+            AppError::Validation(vec![FieldError {
+                field: "script".to_string(),
+                message: e.to_string(),
+            }])
+        })
+}
+
+fn defined_hooks(ast: &AST) -> Vec<String> {
+    HOOKS
+        .into_iter()
+        .filter(|hook| {
+            ast.iter_functions()
+                .any(|f| f.name == *hook && f.params.len() == 1)
+        })
+        .map(str::to_string)
+        .collect()
+}
+
+fn call(
+    engine: &Engine,
+    ast: &AST,
+    name: &str,
+    arg: Dynamic,
+) -> Result<Dynamic, Box<EvalAltResult>> {
+    engine.call_fn::<Dynamic>(&mut Scope::new(), ast, name, (arg,))
+}
+
+/// Blocking request for a script; `body`, if any, is sent as JSON. Runs on
+/// the hook's blocking thread.
+fn http(
+    method: reqwest::Method,
+    url: &str,
+    body: Option<Dynamic>,
+) -> Result<String, Box<EvalAltResult>> {
+    let body: Option<serde_json::Value> = body
+        .map(|body| rhai::serde::from_dynamic(&body))
+        .transpose()?;
+    tauri::async_runtime::block_on(async {
+        let mut request = reqwest::Client::new()
+            .request(method, url)
+            .timeout(HTTP_TIMEOUT);
+        if let Some(body) = &body {
+            request = request.json(body);
+        }
+        request.send().await?.error_for_status()?.text().await
+    })
+    .map_err(|e| e.to_string().into())
+}
+
+fn set_armed(app: &AppHandle, id: i64, armed: bool) -> Result<(), Box<EvalAltResult>> {
+    let state = app.state::<AppState>();
+    state
+        .db
+        .set_target_armed(id, armed)
+        .map_err(|e| -> Box<EvalAltResult> { e.to_string().into() })?;
+    state.alerts.refresh();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compile_lists_the_hooks_a_script_defines() {
+        let engine = base_engine();
+        let ast = compile(
+            &engine,
+            "fn on_sync_complete(result) { result.total_offset_ms }\n\
+             fn on_drift_warning() { 0 }\n\
+             fn helper(x) { x }",
+        )
+        .unwrap();
+        assert_eq!(defined_hooks(&ast), vec![ON_SYNC_COMPLETE]);
+
+        let err = compile(&engine, "fn on_sync_complete(r) {").unwrap_err();
+        assert!(matches!(err, AppError::Validation(ref fields) if fields[0].field == "script"));
+    }
+
+    #[test]
+    fn hooks_see_the_payload_as_a_map() {
+        #[derive(Serialize)]
+        struct Payload {
+            server_id: i64,
+            moved_ms: f64,
+        }
+        let engine = base_engine();
+        let ast = compile(
+            &engine,
+            "fn on_drift_warning(w) { if w.moved_ms > 500.0 { w.server_id } else { 0 } }",
+        )
+        .unwrap();
+        let arg = rhai::serde::to_dynamic(Payload {
+            server_id: 7,
+            moved_ms: 1_200.0,
+        })
+        .unwrap();
+        let out = call(&engine, &ast, ON_DRIFT_WARNING, arg).unwrap();
+        assert_eq!(out.as_int().unwrap(), 7);
+    }
+
+    #[test]
+    fn runaway_scripts_are_stopped() {
+        let engine = base_engine();
+        let ast = compile(&engine, "fn on_sync_complete(r) { loop {} }").unwrap();
+        assert!(call(&engine, &ast, ON_SYNC_COMPLETE, Dynamic::UNIT).is_err());
+    }
+}
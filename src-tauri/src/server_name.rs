@@ -0,0 +1,182 @@
+use crate::models::Server;
+use crate::sync_engine::DEFAULT_USER_AGENT;
+use std::time::Duration;
+
+/// Naming is a nicety; an add should not wait on a slow page for long.
+const FETCH_TIMEOUT: Duration = Duration::from_secs(4);
+/// Titles sit in the head, well inside this much of the page.
+const MAX_BYTES: usize = 256 * 1024;
+/// Longest name kept, in characters. Titles often append a slogan.
+const MAX_NAME_LEN: usize = 60;
+
+/// A readable name for `server`: its page's `og:site_name`, else its
+/// `<title>`, else the hostname when the page cannot be read.
+pub async fn fetch(server: &Server) -> String {
+    match page_head(server).await {
+        Some((_, html)) => from_html(&html).unwrap_or_else(|| hostname(&server.url)),
+        None => hostname(&server.url),
+    }
+}
+
+/// The start of the server's page, as text, with the URL it ended up at.
+pub(crate) async fn page_head(server: &Server) -> Option<(reqwest::Url, String)> {
+    let client = reqwest::Client::builder()
+        .timeout(FETCH_TIMEOUT)
+        .user_agent(server.user_agent.as_deref().unwrap_or(DEFAULT_USER_AGENT))
+        .danger_accept_invalid_certs(server.transport.accept_invalid_certs)
+        .build()
+        .ok()?;
+    let mut response = client.get(&server.url).send().await.ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    let url = response.url().clone();
+    let mut body = Vec::new();
+    while body.len() < MAX_BYTES {
+        match response.chunk().await {
+            Ok(Some(chunk)) => body.extend_from_slice(&chunk),
+            Ok(None) => break,
+            Err(_) if !body.is_empty() => break,
+            Err(_) => return None,
+        }
+    }
+    Some((url, String::from_utf8_lossy(&body).into_owned()))
+}
+
+/// `og:site_name` names the site rather than the page, so it wins over
+/// `<title>`.
+pub fn from_html(html: &str) -> Option<String> {
+    site_name(html)
+        .or_else(|| title(html))
+        .map(|name| tidy(&name))
+        .filter(|name| !name.is_empty())
+}
+
+/// The host without a leading `www.`.
+pub fn hostname(url: &str) -> String {
+    let host = reqwest::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_string))
+        .unwrap_or_else(|| url.to_string());
+    host.strip_prefix("www.").unwrap_or(&host).to_string()
+}
+
+fn site_name(html: &str) -> Option<String> {
+    tags(html, "meta").into_iter().find_map(|tag| {
+        let property = attribute(tag, "property").or_else(|| attribute(tag, "name"))?;
+        if property.eq_ignore_ascii_case("og:site_name") {
+            attribute(tag, "content")
+        } else {
+            None
+        }
+    })
+}
+
+/// Every `<name …` opening tag in `html`, up to but not including its `>`.
+pub(crate) fn tags<'a>(html: &'a str, name: &str) -> Vec<&'a str> {
+    let lower = html.to_ascii_lowercase();
+    let open = format!("<{name}");
+    let mut found = Vec::new();
+    let mut rest = 0;
+    while let Some(start) = lower[rest..].find(&open) {
+        let start = rest + start;
+        let Some(len) = lower[start..].find('>') else {
+            break;
+        };
+        found.push(&html[start..start + len]);
+        rest = start + len;
+    }
+    found
+}
+
+fn title(html: &str) -> Option<String> {
+    let lower = html.to_ascii_lowercase();
+    let open = lower.find("<title")?;
+    let start = open + lower[open..].find('>')? + 1;
+    let end = start + lower[start..].find("</title")?;
+    Some(html[start..end].to_string())
+}
+
+/// Value of `name="…"` (or single-quoted) in one tag.
+pub(crate) fn attribute(tag: &str, name: &str) -> Option<String> {
+    let lower = tag.to_ascii_lowercase();
+    let mut rest = 0;
+    while let Some(found) = lower[rest..].find(name) {
+        let at = rest + found;
+        rest = at + name.len();
+        let preceded = lower[..at].ends_with(|c: char| c.is_ascii_whitespace());
+        let value = lower[rest..].trim_start();
+        if !preceded || !value.starts_with('=') {
+            continue;
+        }
+        let value_at = tag.len() - value[1..].trim_start().len();
+        let quote = tag[value_at..].chars().next()?;
+        if quote != '"' && quote != '\'' {
+            continue;
+        }
+        let body = &tag[value_at + 1..];
+        return Some(body[..body.find(quote)?].to_string());
+    }
+    None
+}
+
+/// Decode the common entities, collapse whitespace and cap the length.
+fn tidy(raw: &str) -> String {
+    let decoded = raw
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#039;", "'")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&");
+    let words: Vec<&str> = decoded.split_whitespace().collect();
+    let name = words.join(" ");
+    if name.chars().count() <= MAX_NAME_LEN {
+        return name;
+    }
+    // Leave room for the ellipsis.
+    let (cut, _) = name.char_indices().nth(MAX_NAME_LEN - 1).unwrap();
+    format!("{}…", name[..cut].trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn site_name_wins_over_title() {
+        let html = r#"<html><head>
+            <title>Buy tickets | Concerts, sports &amp; theatre</title>
+            <meta property="og:title" content="Home">
+            <meta content='Ticket Hall' property='og:site_name' />
+        </head>"#;
+        assert_eq!(from_html(html).as_deref(), Some("Ticket Hall"));
+    }
+
+    #[test]
+    fn title_is_decoded_and_tidied() {
+        let html = "<HTML><TITLE lang=\"en\">\n  Seats &amp; More\n  </TITLE>";
+        assert_eq!(from_html(html).as_deref(), Some("Seats & More"));
+
+        let long = format!("<title>{}</title>", "wörds".repeat(30));
+        let name = from_html(&long).unwrap();
+        assert!(name.ends_with('…'));
+        assert_eq!(name.chars().count(), MAX_NAME_LEN);
+
+        let exact = "x".repeat(MAX_NAME_LEN);
+        assert_eq!(from_html(&format!("<title>{exact}</title>")), Some(exact));
+    }
+
+    #[test]
+    fn nothing_usable_falls_back_to_the_host() {
+        assert_eq!(from_html("<title>   </title>"), None);
+        assert_eq!(from_html("<p>no head</p>"), None);
+        assert_eq!(hostname("https://www.example.com/events/1"), "example.com");
+        assert_eq!(
+            hostname("https://tickets.example.com"),
+            "tickets.example.com"
+        );
+    }
+}
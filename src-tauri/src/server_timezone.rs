@@ -0,0 +1,129 @@
+use crate::error::AppError;
+use crate::models::Server;
+use chrono_tz::Tz;
+
+/// Countries that keep a single timezone, by country-code TLD (which is
+/// also the region subtag of a language tag, lowercased, bar `uk`/`gb`).
+const COUNTRY_ZONES: &[(&str, &str)] = &[
+    ("jp", "Asia/Tokyo"),
+    ("kr", "Asia/Seoul"),
+    ("cn", "Asia/Shanghai"),
+    ("tw", "Asia/Taipei"),
+    ("hk", "Asia/Hong_Kong"),
+    ("sg", "Asia/Singapore"),
+    ("th", "Asia/Bangkok"),
+    ("vn", "Asia/Ho_Chi_Minh"),
+    ("ph", "Asia/Manila"),
+    ("in", "Asia/Kolkata"),
+    ("il", "Asia/Jerusalem"),
+    ("tr", "Europe/Istanbul"),
+    ("uk", "Europe/London"),
+    ("gb", "Europe/London"),
+    ("ie", "Europe/Dublin"),
+    ("de", "Europe/Berlin"),
+    ("fr", "Europe/Paris"),
+    ("es", "Europe/Madrid"),
+    ("it", "Europe/Rome"),
+    ("nl", "Europe/Amsterdam"),
+    ("be", "Europe/Brussels"),
+    ("at", "Europe/Vienna"),
+    ("ch", "Europe/Zurich"),
+    ("pl", "Europe/Warsaw"),
+    ("cz", "Europe/Prague"),
+    ("se", "Europe/Stockholm"),
+    ("no", "Europe/Oslo"),
+    ("dk", "Europe/Copenhagen"),
+    ("fi", "Europe/Helsinki"),
+    ("pt", "Europe/Lisbon"),
+    ("gr", "Europe/Athens"),
+    ("za", "Africa/Johannesburg"),
+    ("nz", "Pacific/Auckland"),
+];
+
+/// Languages spoken mostly in one of the countries above, for language
+/// tags without a region.
+const LANGUAGE_COUNTRIES: &[(&str, &str)] = &[
+    ("ja", "jp"),
+    ("ko", "kr"),
+    ("th", "th"),
+    ("vi", "vn"),
+    ("pl", "pl"),
+    ("cs", "cz"),
+    ("he", "il"),
+    ("tr", "tr"),
+];
+
+fn country_zone(country: &str) -> Option<&'static str> {
+    COUNTRY_ZONES
+        .iter()
+        .find(|(code, _)| *code == country)
+        .map(|(_, zone)| *zone)
+}
+
+/// Where a server's clock most likely reads as local time: its country-code
+/// TLD, else the region of its first `Content-Language`. Generic TLDs and
+/// countries spanning several zones give `None`.
+pub fn guess(url: &str, content_language: Option<&str>) -> Option<&'static str> {
+    let from_tld = reqwest::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_ascii_lowercase))
+        .and_then(|host| host.rsplit('.').next().and_then(country_zone));
+    from_tld.or_else(|| {
+        let tag = content_language?
+            .split(',')
+            .next()?
+            .trim()
+            .to_ascii_lowercase();
+        let mut parts = tag.split(['-', '_']);
+        let language = parts.next()?;
+        match parts.find(|p| p.len() == 2) {
+            Some(region) => country_zone(region),
+            None => LANGUAGE_COUNTRIES
+                .iter()
+                .find(|(l, _)| *l == language)
+                .and_then(|(_, country)| country_zone(country)),
+        }
+    })
+}
+
+/// `name` as a timezone of the tz database.
+pub fn parse(name: &str) -> Result<Tz, AppError> {
+    name.parse()
+        .map_err(|_| AppError::InvalidTimezone(name.to_string()))
+}
+
+/// The zone `server`'s `server-local` times are read in, if it has one.
+pub fn of(server: &Server) -> Option<Tz> {
+    server.timezone.as_deref().and_then(|name| parse(name).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn country_tld_wins_over_headers() {
+        assert_eq!(
+            guess("https://tickets.interpark.co.kr", Some("en-US")),
+            Some("Asia/Seoul")
+        );
+        assert_eq!(guess("https://eplus.jp/sf", None), Some("Asia/Tokyo"));
+        assert_eq!(guess("https://example.co.uk", None), Some("Europe/London"));
+    }
+
+    #[test]
+    fn generic_tlds_fall_back_to_content_language() {
+        assert_eq!(
+            guess("https://tickets.example.com", Some("ja-JP")),
+            Some("Asia/Tokyo")
+        );
+        assert_eq!(
+            guess("https://tickets.example.com", Some("ko, en;q=0.5")),
+            Some("Asia/Seoul")
+        );
+        // The US spans several zones; English alone says nothing.
+        assert_eq!(guess("https://tickets.example.com", Some("en-US")), None);
+        assert_eq!(guess("https://tickets.example.com", Some("en")), None);
+        assert_eq!(guess("https://tickets.example.com", None), None);
+    }
+}
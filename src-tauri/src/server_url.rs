@@ -0,0 +1,100 @@
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::Server;
+use crate::simulation::DemoServer;
+use reqwest::Url;
+
+/// Canonical form of a server URL, so spellings of the same server compare
+/// equal: `https://` is assumed when no scheme is given, the host is
+/// lowercased, default ports, fragments and a trailing `/` are dropped.
+pub fn normalize(url: &str) -> Result<String, AppError> {
+    let url = url.trim();
+    if let Some(demo) = DemoServer::from_url(url) {
+        return Ok(demo?.url());
+    }
+    let with_scheme = if url.contains("://") {
+        url.to_string()
+    } else {
+        format!("https://{url}")
+    };
+    let mut parsed = Url::parse(&with_scheme).map_err(|e| AppError::InvalidUrl(e.to_string()))?;
+    if parsed.scheme() != "http" && parsed.scheme() != "https" {
+        return Err(AppError::InvalidUrl(format!(
+            "unsupported scheme: {}",
+            parsed.scheme()
+        )));
+    }
+    if parsed.host_str().map_or(true, str::is_empty) {
+        return Err(AppError::InvalidUrl(format!("no host in {url}")));
+    }
+    parsed.set_fragment(None);
+    if parsed.path().len() > 1 && parsed.path().ends_with('/') {
+        let path = parsed.path().trim_end_matches('/').to_string();
+        parsed.set_path(&path);
+    }
+
+    let canonical = parsed.to_string();
+    Ok(if parsed.path() == "/" && parsed.query().is_none() {
+        canonical.trim_end_matches('/').to_string()
+    } else {
+        canonical
+    })
+}
+
+/// The server already stored under `url` (canonical), including rows saved
+/// before URLs were normalized.
+pub fn find_existing(db: &Database, url: &str) -> Result<Option<Server>, AppError> {
+    if let Some(server) = db.find_server_by_url(url)? {
+        return Ok(Some(server));
+    }
+    Ok(db
+        .list_servers()?
+        .into_iter()
+        .find(|s| normalize(&s.url).is_ok_and(|stored| stored == url)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spellings_of_one_server_normalize_alike() {
+        for spelling in [
+            "https://example.com",
+            "https://example.com/",
+            "HTTPS://Example.COM:443/",
+            "example.com",
+            "  https://example.com/#top ",
+        ] {
+            assert_eq!(normalize(spelling).unwrap(), "https://example.com");
+        }
+        assert_eq!(
+            normalize("http://example.com:8080/shop/").unwrap(),
+            "http://example.com:8080/shop"
+        );
+        assert_eq!(
+            normalize("example.com/?id=1").unwrap(),
+            "https://example.com/?id=1"
+        );
+    }
+
+    #[test]
+    fn rejects_other_schemes_and_missing_hosts() {
+        assert!(matches!(
+            normalize("ftp://example.com"),
+            Err(AppError::InvalidUrl(_))
+        ));
+        assert!(normalize("https://").is_err());
+    }
+
+    #[test]
+    fn finds_servers_stored_before_normalization() {
+        let db = Database::new_in_memory().unwrap();
+        let legacy = db.add_server("https://Example.com/").unwrap();
+        let found = find_existing(&db, "https://example.com").unwrap();
+        assert_eq!(found.map(|s| s.id), Some(legacy.id));
+        assert!(find_existing(&db, "https://other.example.com")
+            .unwrap()
+            .is_none());
+    }
+}
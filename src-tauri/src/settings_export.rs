@@ -0,0 +1,218 @@
+use crate::db::Database;
+use crate::error::AppError;
+use crate::export_crypto;
+use crate::models::AppSettings;
+use crate::settings_validation;
+use crate::sync_profile;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Bump when the file layout changes incompatibly. Files from newer
+/// versions are refused; older ones are read with defaults filling gaps.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Portable snapshot of user configuration. Secrets such as the local API
+/// token are deliberately left out.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettingsFile {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub settings: AppSettings,
+    #[serde(default)]
+    pub servers: Vec<ServerOverride>,
+}
+
+/// Per-server configuration, matched by URL on import.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerOverride {
+    pub url: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default = "default_extractor")]
+    pub extractor_type: String,
+    #[serde(default)]
+    pub sync_profile: Option<String>,
+}
+
+fn default_extractor() -> String {
+    "date_header".to_string()
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportSummary {
+    pub version: u32,
+    pub servers_added: usize,
+    pub servers_updated: usize,
+}
+
+/// A `passphrase` encrypts the file; see [`export_crypto`].
+pub fn export_to(db: &Database, path: &Path, passphrase: Option<&str>) -> Result<(), AppError> {
+    let json = serde_json::to_string_pretty(&snapshot(db)?)
+        .map_err(|e| AppError::InvalidSettingsFile(e.to_string()))?;
+    export_crypto::write(path, json.as_bytes(), passphrase)
+}
+
+pub fn import_from(
+    db: &Database,
+    path: &Path,
+    passphrase: Option<&str>,
+) -> Result<ImportSummary, AppError> {
+    let json = String::from_utf8(export_crypto::read(path, passphrase)?)
+        .map_err(|e| AppError::InvalidSettingsFile(e.to_string()))?;
+    apply(db, &parse(&json)?)
+}
+
+fn snapshot(db: &Database) -> Result<SettingsFile, AppError> {
+    let servers = db
+        .list_servers()?
+        .into_iter()
+        .map(|s| ServerOverride {
+            url: s.url,
+            name: s.name,
+            extractor_type: s.extractor_type,
+            sync_profile: s.sync_profile,
+        })
+        .collect();
+    Ok(SettingsFile {
+        version: FORMAT_VERSION,
+        exported_at: Utc::now(),
+        settings: db.get_settings()?,
+        servers,
+    })
+}
+
+fn parse(json: &str) -> Result<SettingsFile, AppError> {
+    // Check the version before the body so a newer layout gets a clear
+    // message rather than a field-level parse error.
+    #[derive(Deserialize)]
+    struct Header {
+        version: u32,
+    }
+    let header: Header =
+        serde_json::from_str(json).map_err(|e| AppError::InvalidSettingsFile(e.to_string()))?;
+    if header.version == 0 || header.version > FORMAT_VERSION {
+        return Err(AppError::InvalidSettingsFile(format!(
+            "unsupported version {} (this build reads up to {FORMAT_VERSION})",
+            header.version
+        )));
+    }
+    let file: SettingsFile =
+        serde_json::from_str(json).map_err(|e| AppError::InvalidSettingsFile(e.to_string()))?;
+    settings_validation::validate(&file.settings)?;
+    for server in &file.servers {
+        if let Some(profile) = &server.sync_profile {
+            sync_profile::find(profile)
+                .map_err(|e| AppError::InvalidSettingsFile(format!("{}: {e}", server.url)))?;
+        }
+    }
+    Ok(file)
+}
+
+/// Replace settings and upsert servers by URL; servers missing from the
+/// file are left alone.
+fn apply(db: &Database, file: &SettingsFile) -> Result<ImportSummary, AppError> {
+    db.update_settings(&file.settings)?;
+
+    let mut summary = ImportSummary {
+        version: file.version,
+        servers_added: 0,
+        servers_updated: 0,
+    };
+    for entry in &file.servers {
+        let server = match db.find_server_by_url(&entry.url)? {
+            Some(server) => {
+                summary.servers_updated += 1;
+                server
+            }
+            None => {
+                summary.servers_added += 1;
+                db.add_server(&entry.url)?
+            }
+        };
+        db.update_server_config(server.id, entry.name.as_deref(), &entry.extractor_type)?;
+        db.set_server_sync_profile(server.id, entry.sync_profile.as_deref())?;
+    }
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot_roundtrips_into_a_fresh_database() {
+        let source = Database::new_in_memory().unwrap();
+        source
+            .update_settings(&AppSettings {
+                theme: "light".to_string(),
+                auto_resync_interval_minutes: 15,
+                ..AppSettings::default()
+            })
+            .unwrap();
+        let server = source.add_server("https://tickets.example.com").unwrap();
+        source
+            .update_server_config(server.id, Some("Tickets"), "date_header")
+            .unwrap();
+        source
+            .set_server_sync_profile(server.id, Some("polite"))
+            .unwrap();
+
+        let json = serde_json::to_string(&snapshot(&source).unwrap()).unwrap();
+        let target = Database::new_in_memory().unwrap();
+        let summary = apply(&target, &parse(&json).unwrap()).unwrap();
+
+        assert_eq!(summary.servers_added, 1);
+        assert_eq!(summary.servers_updated, 0);
+        let settings = target.get_settings().unwrap();
+        assert_eq!(settings.theme, "light");
+        assert_eq!(settings.auto_resync_interval_minutes, 15);
+        let servers = target.list_servers().unwrap();
+        assert_eq!(servers[0].name.as_deref(), Some("Tickets"));
+        assert_eq!(servers[0].sync_profile.as_deref(), Some("polite"));
+    }
+
+    #[test]
+    fn import_updates_existing_servers_by_url() {
+        let db = Database::new_in_memory().unwrap();
+        db.add_server("https://a.example.com").unwrap();
+        let file = SettingsFile {
+            version: FORMAT_VERSION,
+            exported_at: Utc::now(),
+            settings: AppSettings::default(),
+            servers: vec![ServerOverride {
+                url: "https://a.example.com".to_string(),
+                name: Some("A".to_string()),
+                extractor_type: default_extractor(),
+                sync_profile: None,
+            }],
+        };
+        let summary = apply(&db, &file).unwrap();
+        assert_eq!(summary.servers_updated, 1);
+        assert_eq!(db.list_servers().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn older_files_fill_missing_fields_with_defaults() {
+        let file = parse(
+            r#"{"version":1,"exported_at":"2025-01-01T00:00:00Z","settings":{"theme":"light"}}"#,
+        )
+        .unwrap();
+        assert_eq!(file.settings.theme, "light");
+        assert_eq!(
+            file.settings.local_api_port,
+            AppSettings::default().local_api_port
+        );
+        assert!(file.servers.is_empty());
+    }
+
+    #[test]
+    fn newer_versions_are_refused() {
+        let err = parse(r#"{"version":99,"settings":{}}"#).unwrap_err();
+        assert!(err.to_string().contains("unsupported version 99"));
+        assert!(matches!(
+            parse("not json"),
+            Err(AppError::InvalidSettingsFile(_))
+        ));
+    }
+}
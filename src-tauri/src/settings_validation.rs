@@ -0,0 +1,199 @@
+use crate::alerts;
+use crate::error::AppError;
+use crate::models::AppSettings;
+use crate::sync_profile;
+use crate::time_format::TimeFormat;
+use crate::time_source;
+use serde::Serialize;
+use std::fmt::Display;
+use std::ops::RangeInclusive;
+
+const THEMES: &[&str] = &["dark", "light"];
+
+/// One rejected setting, keyed by its `AppSettings` field name so the
+/// settings page can show the message next to the input.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+/// Check every field and report all problems at once rather than stopping
+/// at the first.
+pub fn validate(settings: &AppSettings) -> Result<(), AppError> {
+    let mut errors = Vec::new();
+    let mut check = |field: &str, problem: Option<String>| {
+        if let Some(message) = problem {
+            errors.push(FieldError {
+                field: field.to_string(),
+                message,
+            });
+        }
+    };
+
+    check("theme", one_of(&settings.theme, THEMES));
+    check(
+        "min_request_interval_ms",
+        in_range(
+            settings.min_request_interval_ms,
+            sync_profile::REQUEST_INTERVAL_RANGE_MS,
+        ),
+    );
+    check(
+        "public_host_floor_ms",
+        in_range(
+            settings.public_host_floor_ms,
+            sync_profile::REQUEST_INTERVAL_RANGE_MS,
+        ),
+    );
+    check(
+        "sync_profile",
+        one_of(&settings.sync_profile, &profile_ids()),
+    );
+    check(
+        "health_resync_threshold",
+        in_range(settings.health_resync_threshold, 0..=100),
+    );
+    check(
+        "auto_resync_interval_minutes",
+        in_range(settings.auto_resync_interval_minutes, 0..=1440),
+    );
+    check(
+        "external_time_source",
+        one_of(&settings.external_time_source, time_source::SOURCES),
+    );
+    check(
+        "millisecond_precision",
+        in_range(settings.millisecond_precision, 1..=3),
+    );
+    check(
+        "time_format",
+        TimeFormat::new(&settings.time_format, 0, false)
+            .err()
+            .map(|e| e.to_string()),
+    );
+    check(
+        "overlay_opacity",
+        in_range(settings.overlay_opacity, 10..=100),
+    );
+    check(
+        "countdown_tick_ms",
+        in_range(settings.countdown_tick_ms, 16..=1000),
+    );
+    check(
+        "alert_intervals",
+        settings
+            .alert_intervals
+            .iter()
+            .find(|m| !(1..=1440).contains(*m))
+            .map(|m| format!("{m} is not between 1 and 1440 minutes")),
+    );
+    check(
+        "alert_method",
+        one_of(&settings.alert_method, alerts::METHODS),
+    );
+    check(
+        "keep_warm_minutes",
+        in_range(settings.keep_warm_minutes, 0..=60),
+    );
+    check(
+        "drift_warning_threshold_ms",
+        in_range(settings.drift_warning_threshold_ms, 100..=10_000),
+    );
+    check(
+        "local_api_port",
+        in_range(settings.local_api_port, 1024..=65_535),
+    );
+    check(
+        "lan_time_port",
+        in_range(settings.lan_time_port, 1024..=65_535),
+    );
+    check(
+        "progress_event_limit",
+        in_range(settings.progress_event_limit, 0..=100),
+    );
+    check(
+        "trash_retention_days",
+        in_range(settings.trash_retention_days, 1..=90),
+    );
+    check(
+        "sync_stall_timeout_secs",
+        in_range(settings.sync_stall_timeout_secs, 15..=600),
+    );
+    check(
+        "sync_deadline_secs",
+        in_range(settings.sync_deadline_secs, 30..=1800),
+    );
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(AppError::Validation(errors))
+    }
+}
+
+fn in_range<T: PartialOrd + Display>(value: T, range: RangeInclusive<T>) -> Option<String> {
+    (!range.contains(&value)).then(|| {
+        format!(
+            "must be between {} and {} (got {value})",
+            range.start(),
+            range.end()
+        )
+    })
+}
+
+fn profile_ids() -> Vec<&'static str> {
+    sync_profile::PROFILES.iter().map(|p| p.id).collect()
+}
+
+fn one_of(value: &str, allowed: &[&str]) -> Option<String> {
+    (!allowed.contains(&value)).then(|| format!("must be one of {}", allowed.join(", ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(result: Result<(), AppError>) -> Vec<String> {
+        match result {
+            Err(AppError::Validation(errors)) => errors.into_iter().map(|e| e.field).collect(),
+            other => panic!("expected validation errors, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn defaults_are_valid() {
+        assert!(validate(&AppSettings::default()).is_ok());
+    }
+
+    #[test]
+    fn reports_every_bad_field() {
+        let settings = AppSettings {
+            overlay_opacity: 255,
+            millisecond_precision: 9,
+            theme: "neon".to_string(),
+            alert_intervals: vec![5, 0],
+            time_format: "%Q".to_string(),
+            ..AppSettings::default()
+        };
+        assert_eq!(
+            fields(validate(&settings)),
+            vec![
+                "theme",
+                "millisecond_precision",
+                "time_format",
+                "overlay_opacity",
+                "alert_intervals"
+            ]
+        );
+    }
+
+    #[test]
+    fn range_message_names_bounds_and_value() {
+        assert_eq!(
+            in_range(255u8, 10..=100).as_deref(),
+            Some("must be between 10 and 100 (got 255)")
+        );
+        assert_eq!(in_range(50u8, 10..=100), None);
+    }
+}
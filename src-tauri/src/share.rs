@@ -0,0 +1,351 @@
+use crate::db::{Database, ImportedServer};
+use crate::error::AppError;
+use crate::server_url;
+use crate::sync_profile;
+use crate::time_extractor::ExtractorRegistry;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+/// Leading tag of every payload. The digit is the layout version; payloads
+/// from newer versions are refused.
+const PREFIX: &str = "TT1";
+
+/// Servers and targets to hand to another device, e.g. as a QR code.
+/// Only configuration travels: no offsets, history, settings or tokens.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharePayload {
+    pub servers: Vec<SharedServer>,
+    #[serde(default)]
+    pub targets: Vec<SharedTarget>,
+}
+
+/// Field names are short to keep QR codes scannable.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharedServer {
+    #[serde(rename = "u")]
+    pub url: String,
+    #[serde(rename = "n", default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(rename = "x", default = "default_extractor")]
+    pub extractor_type: String,
+    #[serde(rename = "p", default, skip_serializing_if = "Option::is_none")]
+    pub sync_profile: Option<String>,
+}
+
+/// A target points at its server by position in `servers`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharedTarget {
+    #[serde(rename = "s")]
+    pub server: usize,
+    #[serde(rename = "t")]
+    pub target_at: DateTime<Utc>,
+    #[serde(rename = "l", default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+fn default_extractor() -> String {
+    "date_header".to_string()
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ShareImportSummary {
+    pub servers_added: usize,
+    pub servers_updated: usize,
+    pub targets_added: usize,
+}
+
+/// Collect the chosen servers, plus the servers of the chosen targets.
+pub fn collect(
+    db: &Database,
+    server_ids: &[i64],
+    target_ids: &[i64],
+) -> Result<SharePayload, AppError> {
+    let targets = target_ids
+        .iter()
+        .map(|&id| db.get_target(id))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut ids: Vec<i64> = server_ids.to_vec();
+    for target in &targets {
+        if !ids.contains(&target.server_id) {
+            ids.push(target.server_id);
+        }
+    }
+
+    let servers = ids
+        .iter()
+        .map(|&id| db.get_server(id))
+        .collect::<Result<Vec<_>, _>>()?;
+    let targets = targets
+        .into_iter()
+        .map(|t| SharedTarget {
+            server: ids.iter().position(|&id| id == t.server_id).unwrap_or(0),
+            target_at: t.target_at,
+            label: t.label,
+        })
+        .collect();
+    Ok(SharePayload {
+        servers: servers
+            .into_iter()
+            .map(|s| SharedServer {
+                url: s.url,
+                name: s.name,
+                extractor_type: s.extractor_type,
+                sync_profile: s.sync_profile,
+            })
+            .collect(),
+        targets,
+    })
+}
+
+/// `TT1.<body>.<signature>`, both base64url. The signature is an
+/// HMAC-SHA256 keyed by a passphrase the team agrees on out of band, so a
+/// payload edited or forged without it is rejected on import.
+pub fn encode(payload: &SharePayload, passphrase: &str) -> Result<String, AppError> {
+    let json =
+        serde_json::to_vec(payload).map_err(|e| AppError::InvalidSharePayload(e.to_string()))?;
+    let body = URL_SAFE_NO_PAD.encode(json);
+    let signature = URL_SAFE_NO_PAD.encode(mac(passphrase, &body)?.finalize().into_bytes());
+    Ok(format!("{PREFIX}.{body}.{signature}"))
+}
+
+pub fn decode(encoded: &str, passphrase: &str) -> Result<SharePayload, AppError> {
+    let mut parts = encoded.trim().split('.');
+    let (Some(prefix), Some(body), Some(signature), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(AppError::InvalidSharePayload(
+            "not a Ticketime share payload".to_string(),
+        ));
+    };
+    if prefix != PREFIX {
+        return Err(AppError::InvalidSharePayload(format!(
+            "unsupported version {prefix} (this build reads {PREFIX})"
+        )));
+    }
+    let signature = URL_SAFE_NO_PAD
+        .decode(signature)
+        .map_err(|e| AppError::InvalidSharePayload(e.to_string()))?;
+    mac(passphrase, body)?
+        .verify_slice(&signature)
+        .map_err(|_| {
+            AppError::InvalidSharePayload(
+                "signature does not match; wrong passphrase or altered payload".to_string(),
+            )
+        })?;
+
+    let json = URL_SAFE_NO_PAD
+        .decode(body)
+        .map_err(|e| AppError::InvalidSharePayload(e.to_string()))?;
+    let payload: SharePayload =
+        serde_json::from_slice(&json).map_err(|e| AppError::InvalidSharePayload(e.to_string()))?;
+    if let Some(t) = payload
+        .targets
+        .iter()
+        .find(|t| t.server >= payload.servers.len())
+    {
+        return Err(AppError::InvalidSharePayload(format!(
+            "target refers to missing server {}",
+            t.server
+        )));
+    }
+    for server in &payload.servers {
+        if let Some(profile) = &server.sync_profile {
+            sync_profile::find(profile)
+                .map_err(|e| AppError::InvalidSharePayload(format!("{}: {e}", server.url)))?;
+        }
+    }
+    Ok(payload)
+}
+
+fn mac(passphrase: &str, body: &str) -> Result<Hmac<Sha256>, AppError> {
+    if passphrase.is_empty() {
+        return Err(AppError::InvalidSharePayload(
+            "a passphrase is required".to_string(),
+        ));
+    }
+    let mut mac = Hmac::<Sha256>::new_from_slice(passphrase.as_bytes())
+        .map_err(|e| AppError::InvalidSharePayload(e.to_string()))?;
+    mac.update(body.as_bytes());
+    Ok(mac)
+}
+
+/// Upsert servers by URL like a settings import, then add targets that are
+/// still ahead and not already present. Nothing is written unless all of
+/// it can be.
+pub fn apply(
+    db: &Database,
+    extractors: &ExtractorRegistry,
+    payload: &SharePayload,
+) -> Result<ShareImportSummary, AppError> {
+    let mut servers = Vec::with_capacity(payload.servers.len());
+    for entry in &payload.servers {
+        extractors.get(&entry.extractor_type)?;
+        let url = server_url::normalize(&entry.url)?;
+        servers.push(ImportedServer {
+            id: server_url::find_existing(db, &url)?.map(|s| s.id),
+            url,
+            name: entry.name.clone(),
+            extractor_type: entry.extractor_type.clone(),
+            sync_profile: entry.sync_profile.clone(),
+        });
+    }
+
+    let now = Utc::now();
+    let existing = db.list_targets()?;
+    let targets: Vec<_> = payload
+        .targets
+        .iter()
+        .filter(|target| {
+            let duplicate = servers[target.server].id.is_some_and(|server_id| {
+                existing
+                    .iter()
+                    .any(|t| t.server_id == server_id && t.target_at == target.target_at)
+            });
+            target.target_at > now && !duplicate
+        })
+        .map(|t| (t.server, t.target_at, t.label.as_deref()))
+        .collect();
+
+    let ids = db.import_shared(&servers, &targets)?;
+    let servers_added = ids.iter().filter(|(_, added)| *added).count();
+    Ok(ShareImportSummary {
+        servers_added,
+        servers_updated: ids.len() - servers_added,
+        targets_added: targets.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn payload_roundtrips_into_a_fresh_database() {
+        let source = Database::new_in_memory().unwrap();
+        let server = source.add_server("https://tickets.example.com").unwrap();
+        source
+            .update_server_config(server.id, Some("Tickets"), "date_header")
+            .unwrap();
+        source
+            .set_server_sync_profile(server.id, Some("polite"))
+            .unwrap();
+        let on_sale = Utc::now() + Duration::hours(2);
+        let target = source
+            .add_target(server.id, on_sale, Some("On sale"))
+            .unwrap();
+
+        // Choosing only the target brings its server along.
+        let encoded = encode(&collect(&source, &[], &[target.id]).unwrap(), "team").unwrap();
+        assert!(encoded.starts_with("TT1."));
+
+        let dest = Database::new_in_memory().unwrap();
+        let summary = apply(
+            &dest,
+            &ExtractorRegistry::new(),
+            &decode(&encoded, "team").unwrap(),
+        )
+        .unwrap();
+        assert_eq!(summary.servers_added, 1);
+        assert_eq!(summary.targets_added, 1);
+        let servers = dest.list_servers().unwrap();
+        assert_eq!(servers[0].name.as_deref(), Some("Tickets"));
+        assert_eq!(servers[0].sync_profile.as_deref(), Some("polite"));
+        assert_eq!(
+            dest.list_targets().unwrap()[0].label.as_deref(),
+            Some("On sale")
+        );
+
+        // Importing the same payload twice adds nothing new.
+        let again = apply(
+            &dest,
+            &ExtractorRegistry::new(),
+            &decode(&encoded, "team").unwrap(),
+        )
+        .unwrap();
+        assert_eq!(again.servers_updated, 1);
+        assert_eq!(again.targets_added, 0);
+    }
+
+    #[test]
+    fn wrong_passphrase_or_edits_are_rejected() {
+        let payload = SharePayload {
+            servers: vec![SharedServer {
+                url: "https://a.example.com".to_string(),
+                name: None,
+                extractor_type: default_extractor(),
+                sync_profile: None,
+            }],
+            targets: Vec::new(),
+        };
+        let encoded = encode(&payload, "team").unwrap();
+        assert!(matches!(
+            decode(&encoded, "other"),
+            Err(AppError::InvalidSharePayload(_))
+        ));
+
+        let forged = SharePayload {
+            servers: vec![SharedServer {
+                url: "https://evil.example.com".to_string(),
+                ..payload.servers[0].clone()
+            }],
+            targets: Vec::new(),
+        };
+        let forged_body = encode(&forged, "guess").unwrap();
+        let signature = encoded.rsplit('.').next().unwrap();
+        let body = forged_body.split('.').nth(1).unwrap();
+        assert!(decode(&format!("TT1.{body}.{signature}"), "team").is_err());
+
+        assert!(decode("TT9.e30.AA", "team")
+            .unwrap_err()
+            .to_string()
+            .contains("unsupported version"));
+        assert!(decode(&encoded, "").is_err());
+    }
+
+    #[test]
+    fn import_keeps_names_and_rejects_unknown_extractors() {
+        let db = Database::new_in_memory().unwrap();
+        let server = db.add_server("https://a.example.com").unwrap();
+        db.update_server_config(server.id, Some("Mine"), "date_header")
+            .unwrap();
+        let entry = SharedServer {
+            url: "https://a.example.com".to_string(),
+            name: None,
+            extractor_type: default_extractor(),
+            sync_profile: None,
+        };
+        let extractors = ExtractorRegistry::new();
+
+        let unknown = SharePayload {
+            servers: vec![
+                SharedServer {
+                    url: "https://b.example.com".to_string(),
+                    ..entry.clone()
+                },
+                SharedServer {
+                    extractor_type: "no_such_extractor".to_string(),
+                    ..entry.clone()
+                },
+            ],
+            targets: Vec::new(),
+        };
+        assert!(apply(&db, &extractors, &unknown).is_err());
+        assert_eq!(db.list_servers().unwrap().len(), 1);
+
+        let unnamed = SharePayload {
+            servers: vec![entry],
+            targets: Vec::new(),
+        };
+        let summary = apply(&db, &extractors, &unnamed).unwrap();
+        assert_eq!(summary.servers_updated, 1);
+        assert_eq!(
+            db.get_server(server.id).unwrap().name.as_deref(),
+            Some("Mine")
+        );
+    }
+}
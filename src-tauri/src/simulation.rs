@@ -0,0 +1,377 @@
+use crate::asymmetry;
+use crate::error::AppError;
+use crate::settings_validation::FieldError;
+use crate::sync_engine::{Clock, ServerProbe};
+use crate::sync_recording::{RecordedError, RecordedProbe};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+
+pub const DEMO_SCHEME: &str = "demo";
+
+// ── Simulated Clock ──
+
+/// A deterministic clock that advances only when explicitly told to.
+/// No real time passes — all waits are instantaneous advances of
+/// the internal counters.
+pub(crate) struct SimulatedClock {
+    wall_time: Mutex<f64>,
+    monotonic: Mutex<f64>,
+}
+
+impl SimulatedClock {
+    pub(crate) fn new(initial_wall_time: f64) -> Self {
+        Self {
+            wall_time: Mutex::new(initial_wall_time),
+            monotonic: Mutex::new(0.0),
+        }
+    }
+
+    pub(crate) fn advance(&self, seconds: f64) {
+        *self.wall_time.lock().unwrap() += seconds;
+        *self.monotonic.lock().unwrap() += seconds;
+    }
+}
+
+impl Clock for SimulatedClock {
+    fn system_time_secs(&self) -> f64 {
+        *self.wall_time.lock().unwrap()
+    }
+
+    fn monotonic_secs(&self) -> f64 {
+        *self.monotonic.lock().unwrap()
+    }
+
+    fn wait(&self, seconds: f64) {
+        if seconds > 0.0 {
+            self.advance(seconds);
+        }
+    }
+}
+
+// ── Simulated Server ──
+
+/// An RTT entry that makes the probe fail with a connection reset
+/// (after advancing the clock by 100 ms).
+pub(crate) const RESET: f64 = -1.0;
+
+/// An RTT entry that makes the probe report a cached response (after
+/// advancing the clock by 50 ms).
+pub(crate) const CACHED: f64 = -2.0;
+
+enum RttSource {
+    /// Pre-loaded RTT values consumed in FIFO order.
+    #[cfg(test)]
+    Sequence(VecDeque<f64>),
+    /// `base ± jitter`, uniformly spread, for as long as asked.
+    Jittered { base: f64, jitter: f64, state: u64 },
+}
+
+impl RttSource {
+    fn next(&mut self) -> f64 {
+        match self {
+            #[cfg(test)]
+            RttSource::Sequence(rtts) => rtts
+                .pop_front()
+                .expect("SimulatedServer: ran out of pre-loaded RTT values"),
+            RttSource::Jittered {
+                base,
+                jitter,
+                state,
+            } => {
+                // xorshift64: deterministic per seed, no dependency needed.
+                *state ^= *state << 13;
+                *state ^= *state >> 7;
+                *state ^= *state << 17;
+                let unit = (*state >> 11) as f64 / (1u64 << 53) as f64;
+                (*base + *jitter * (2.0 * unit - 1.0)).max(0.0)
+            }
+        }
+    }
+}
+
+/// Simulates a remote server with a configurable time offset. Each call
+/// to `probe` takes the next RTT, advances the shared clock by that
+/// amount, and computes the server timestamp based on the offset.
+pub(crate) struct SimulatedServer {
+    clock: Arc<SimulatedClock>,
+    /// server_time = client_send_time + rtt * asymmetry + server_offset
+    server_offset: f64,
+    rtts: Mutex<RttSource>,
+    /// Share of the round trip before the server stamps its time.
+    asymmetry: f64,
+}
+
+impl SimulatedServer {
+    #[cfg(test)]
+    pub(crate) fn new(clock: Arc<SimulatedClock>, server_offset: f64, rtts: Vec<f64>) -> Self {
+        Self {
+            clock,
+            server_offset,
+            rtts: Mutex::new(RttSource::Sequence(rtts.into())),
+            asymmetry: asymmetry::SYMMETRIC,
+        }
+    }
+
+    /// Stamp the time `asymmetry` of the way through each round trip.
+    #[cfg(test)]
+    pub(crate) fn with_asymmetry(self, asymmetry: f64) -> Self {
+        Self { asymmetry, ..self }
+    }
+
+    /// A server whose RTTs never run out.
+    pub(crate) fn jittered(
+        clock: Arc<SimulatedClock>,
+        server_offset: f64,
+        base_rtt: f64,
+        jitter: f64,
+        seed: u64,
+    ) -> Self {
+        Self {
+            clock,
+            server_offset,
+            rtts: Mutex::new(RttSource::Jittered {
+                base: base_rtt,
+                jitter,
+                // xorshift gets stuck on zero.
+                state: seed | 1,
+            }),
+            asymmetry: asymmetry::SYMMETRIC,
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn remaining_rtts(&self) -> usize {
+        match &*self.rtts.lock().unwrap() {
+            RttSource::Sequence(rtts) => rtts.len(),
+            RttSource::Jittered { .. } => usize::MAX,
+        }
+    }
+}
+
+impl ServerProbe for SimulatedServer {
+    fn probe<'a>(
+        &'a self,
+        _url: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<(i64, f64), AppError>> + Send + 'a>> {
+        Box::pin(async move {
+            let rtt = self.rtts.lock().unwrap().next();
+            if rtt == RESET {
+                self.clock.advance(0.1);
+                return Err(AppError::Io(std::io::ErrorKind::ConnectionReset.into()));
+            }
+            if rtt == CACHED {
+                self.clock.advance(0.05);
+                return Err(AppError::CachedResponse(120));
+            }
+            assert!(rtt >= 0.0, "RTT must be non-negative, got {rtt}");
+
+            // Record send time (before network travel)
+            let send_time = self.clock.system_time_secs();
+
+            // Simulate full round-trip (clock advances by RTT)
+            self.clock.advance(rtt);
+
+            // Server processes `asymmetry` of the way through the round-trip
+            let server_process_time = send_time + rtt * self.asymmetry + self.server_offset;
+            let server_timestamp = server_process_time.floor() as i64;
+
+            Ok((server_timestamp, rtt))
+        })
+    }
+}
+
+// ── Replayed server ──
+
+/// Answers with a recorded sync's probes, in order. Before each one the
+/// clock is moved up to the probe's recorded send time, so the engine sees
+/// the run it saw live: its own waits land on the same instants, and what
+/// the original spent on top (scheduling, backoffs) is added back here.
+pub(crate) struct ReplayServer {
+    clock: Arc<SimulatedClock>,
+    probes: Mutex<VecDeque<RecordedProbe>>,
+}
+
+impl ReplayServer {
+    /// The server and a clock starting where the recorded run did.
+    pub(crate) fn new(started_at: f64, probes: &[RecordedProbe]) -> (Arc<SimulatedClock>, Self) {
+        let clock = Arc::new(SimulatedClock::new(started_at));
+        let server = Self {
+            clock: clock.clone(),
+            probes: Mutex::new(probes.iter().cloned().collect()),
+        };
+        (clock, server)
+    }
+
+    /// Recorded probes not asked for yet.
+    pub(crate) fn remaining(&self) -> usize {
+        self.probes.lock().unwrap().len()
+    }
+}
+
+impl ServerProbe for ReplayServer {
+    fn probe<'a>(
+        &'a self,
+        _url: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<(i64, f64), AppError>> + Send + 'a>> {
+        Box::pin(async move {
+            let Some(recorded) = self.probes.lock().unwrap().pop_front() else {
+                return Err(AppError::Replay(
+                    "the engine sent more probes than were recorded".to_string(),
+                ));
+            };
+            let behind = recorded.sent_at - self.clock.system_time_secs();
+            if behind > 0.0 {
+                self.clock.advance(behind);
+            }
+            self.clock.advance(recorded.rtt);
+
+            match (recorded.server_second, recorded.error) {
+                (_, Some(RecordedError::Cached)) => Err(AppError::CachedResponse(0)),
+                (_, Some(RecordedError::Transient(message))) => Err(AppError::Io(
+                    std::io::Error::new(std::io::ErrorKind::ConnectionReset, message),
+                )),
+                (_, Some(RecordedError::Fatal(message))) => Err(AppError::Replay(format!(
+                    "recorded probe failed: {message}"
+                ))),
+                (Some(second), None) => Ok((second, recorded.rtt)),
+                (None, None) => Err(AppError::Replay(
+                    "recorded probe has neither a time nor an error".to_string(),
+                )),
+            }
+        })
+    }
+}
+
+// ── Demo servers ──
+
+/// A fake server that syncs instantly against the simulator, for
+/// onboarding and UI work without a network. Stored as a `demo://` URL
+/// so it lives in the servers table like any other.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DemoServer {
+    pub offset_ms: f64,
+    pub rtt_ms: f64,
+    pub jitter_ms: f64,
+}
+
+impl DemoServer {
+    pub fn url(&self) -> String {
+        format!(
+            "{DEMO_SCHEME}://server?offset_ms={}&rtt_ms={}&jitter_ms={}",
+            self.offset_ms, self.rtt_ms, self.jitter_ms
+        )
+    }
+
+    /// `None` for anything that is not a `demo://` URL.
+    pub fn from_url(url: &str) -> Option<Result<Self, AppError>> {
+        let parsed = reqwest::Url::parse(url).ok()?;
+        if parsed.scheme() != DEMO_SCHEME {
+            return None;
+        }
+        let mut demo = DemoServer {
+            offset_ms: 0.0,
+            rtt_ms: 50.0,
+            jitter_ms: 2.0,
+        };
+        for (key, value) in parsed.query_pairs() {
+            let slot = match key.as_ref() {
+                "offset_ms" => &mut demo.offset_ms,
+                "rtt_ms" => &mut demo.rtt_ms,
+                "jitter_ms" => &mut demo.jitter_ms,
+                _ => continue,
+            };
+            match value.parse() {
+                Ok(v) => *slot = v,
+                Err(_) => return Some(Err(AppError::InvalidUrl(format!("{key}={value}")))),
+            }
+        }
+        Some(demo.validate().map(|_| demo))
+    }
+
+    pub fn validate(&self) -> Result<(), AppError> {
+        let mut errors = Vec::new();
+        let mut check = |field: &str, ok: bool, message: &str| {
+            if !ok {
+                errors.push(FieldError {
+                    field: field.to_string(),
+                    message: message.to_string(),
+                });
+            }
+        };
+        check(
+            "offset_ms",
+            self.offset_ms.abs() <= 86_400_000.0,
+            "must be within a day",
+        );
+        check(
+            "rtt_ms",
+            (1.0..=2000.0).contains(&self.rtt_ms),
+            "must be between 1 and 2000",
+        );
+        check(
+            "jitter_ms",
+            (0.0..self.rtt_ms).contains(&self.jitter_ms),
+            "must be at least 0 and below rtt_ms",
+        );
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::Validation(errors))
+        }
+    }
+
+    /// A simulated clock starting at the real time, and the server behind it.
+    pub(crate) fn build(&self) -> (Arc<SimulatedClock>, SimulatedServer) {
+        let clock = Arc::new(SimulatedClock::new(crate::timing::system_time_secs()));
+        let server = SimulatedServer::jittered(
+            clock.clone(),
+            self.offset_ms / 1000.0,
+            self.rtt_ms / 1000.0,
+            self.jitter_ms / 1000.0,
+            self.offset_ms.to_bits() ^ self.rtt_ms.to_bits(),
+        );
+        (clock, server)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn demo_url_round_trips() {
+        let demo = DemoServer {
+            offset_ms: -1234.5,
+            rtt_ms: 80.0,
+            jitter_ms: 10.0,
+        };
+        let parsed = DemoServer::from_url(&demo.url()).unwrap().unwrap();
+        assert_eq!(parsed, demo);
+        assert!(DemoServer::from_url("https://example.com").is_none());
+    }
+
+    #[test]
+    fn demo_rejects_out_of_range_values() {
+        let err = DemoServer::from_url("demo://server?rtt_ms=50&jitter_ms=60")
+            .unwrap()
+            .unwrap_err();
+        assert!(matches!(err, AppError::Validation(ref f) if f[0].field == "jitter_ms"));
+        assert!(DemoServer::from_url("demo://server?offset_ms=abc")
+            .unwrap()
+            .is_err());
+    }
+
+    #[test]
+    fn jittered_rtts_stay_in_band() {
+        let clock = Arc::new(SimulatedClock::new(0.0));
+        let server = SimulatedServer::jittered(clock, 0.0, 0.050, 0.005, 42);
+        let mut rtts = server.rtts.lock().unwrap();
+        for _ in 0..1000 {
+            let rtt = rtts.next();
+            assert!((0.045..=0.055).contains(&rtt), "{rtt}");
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use crate::error::AppError;
+
+/// Read `text` aloud with the platform's speech engine and return once it
+/// has started; the helper process is reaped on a background thread.
+pub fn speak(text: &str) -> Result<(), AppError> {
+    platform::speak(text)
+}
+
+// ── Platform backends ──
+
+#[cfg(desktop)]
+fn spawn_and_reap(mut command: std::process::Command) -> std::io::Result<()> {
+    use std::process::Stdio;
+
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use crate::error::AppError;
+    use std::process::Command;
+
+    pub fn speak(text: &str) -> Result<(), AppError> {
+        let mut command = Command::new("say");
+        command.arg("--").arg(text);
+        super::spawn_and_reap(command).map_err(AppError::Io)
+    }
+}
+
+#[cfg(all(unix, desktop, not(target_os = "macos")))]
+mod platform {
+    use crate::error::AppError;
+    use std::process::Command;
+
+    /// speech-dispatcher first (desktop environments route it to the
+    /// user's chosen voice), then eSpeak directly.
+    const ENGINES: [&str; 3] = ["spd-say", "espeak-ng", "espeak"];
+
+    pub fn speak(text: &str) -> Result<(), AppError> {
+        let mut last = None;
+        for engine in ENGINES {
+            let mut command = Command::new(engine);
+            command.arg("--").arg(text);
+            match super::spawn_and_reap(command) {
+                Ok(()) => return Ok(()),
+                Err(e) => last = Some(e),
+            }
+        }
+        Err(AppError::Io(last.expect("at least one engine")))
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use crate::error::AppError;
+    use std::os::windows::process::CommandExt;
+    use std::process::Command;
+
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    /// The text goes through the environment so no quoting can break out
+    /// of the script.
+    const SCRIPT: &str = "Add-Type -AssemblyName System.Speech; \
+        (New-Object System.Speech.Synthesis.SpeechSynthesizer).Speak($env:TICKETIME_SPEAK)";
+
+    pub fn speak(text: &str) -> Result<(), AppError> {
+        let mut command = Command::new("powershell");
+        command
+            .args(["-NoProfile", "-NonInteractive", "-Command", SCRIPT])
+            .env("TICKETIME_SPEAK", text)
+            .creation_flags(CREATE_NO_WINDOW);
+        super::spawn_and_reap(command).map_err(AppError::Io)
+    }
+}
+
+/// Android and iOS have no speech engine reachable without a plugin; the
+/// alert dispatcher sounds a notification there instead.
+#[cfg(mobile)]
+mod platform {
+    use crate::error::AppError;
+
+    pub fn speak(_text: &str) -> Result<(), AppError> {
+        Err(AppError::Notification(
+            "speech is not available on this platform".to_string(),
+        ))
+    }
+}
@@ -0,0 +1,303 @@
+use crate::countdown;
+use crate::error::AppError;
+use crate::models::OffsetRecheck;
+use crate::power::SleepGuard;
+use crate::state::AppState;
+use crate::sync_engine;
+use crate::sync_profile;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::collections::HashSet;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Emitted after each pre-target recheck, whatever its outcome.
+pub const CONFIDENCE_EVENT: &str = "target-confidence";
+
+/// Seconds before an armed target at which its offset is rechecked.
+pub const CHECKPOINTS: [u32; 2] = [30, 5];
+
+/// How often the loop looks for a checkpoint that has come up.
+const TICK: std::time::Duration = std::time::Duration::from_millis(250);
+
+/// A checkpoint missed by more than this (busy runtime, machine asleep) is
+/// skipped; a later one still runs.
+const GRACE: Duration = Duration::seconds(2);
+
+/// Narrowing down a moved offset stops this long before T-0, so the new
+/// offset is in place before the moment it matters.
+const DEADLINE_MARGIN: Duration = Duration::seconds(1);
+
+/// The machine is kept from sleeping while an armed target is this close.
+const STAY_AWAKE: Duration = Duration::minutes(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Confidence {
+    /// The probes agree with the offset the countdown is using.
+    Confirmed,
+    /// The offset had moved; the corrected clock now uses the new one.
+    Adjusted,
+    /// The probes failed; the countdown keeps the stored offset.
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TargetConfidence {
+    pub target_id: i64,
+    pub server_id: i64,
+    pub seconds_before: u32,
+    pub confidence: Confidence,
+    pub previous_offset_ms: f64,
+    /// What the corrected clock uses from now on.
+    pub offset_ms: f64,
+    pub recheck: Option<OffsetRecheck>,
+    pub error: Option<String>,
+}
+
+/// Hot standby for armed targets: at T-30s and T-5s the server's offset is
+/// rechecked with a few probes, moved if the server's clock has, and a
+/// `CONFIDENCE_EVENT` tells the UI the countdown is current. It also keeps
+/// the machine awake from `STAY_AWAKE` before an armed target.
+pub fn start(app: &AppHandle) {
+    tauri::async_runtime::spawn(run(app.clone()));
+}
+
+async fn run(app: AppHandle) {
+    let mut interval = tokio::time::interval(TICK);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    let mut done: HashSet<(i64, u32)> = HashSet::new();
+    let mut awake: Option<SleepGuard> = None;
+
+    loop {
+        interval.tick().await;
+        let now = Utc::now();
+        let handle = app.clone();
+        let upcoming =
+            tokio::task::spawn_blocking(move || upcoming(&handle.state::<AppState>(), now))
+                .await
+                .unwrap_or_else(|e| Err(std::io::Error::other(e).into()));
+        let state = app.state::<AppState>();
+        match upcoming.map(|upcoming| start_due(&app, &state, now, upcoming, &mut done)) {
+            Ok(true) => {
+                awake.get_or_insert_with(|| state.sleep_inhibitor.acquire());
+            }
+            Ok(false) => awake = None,
+            // Keep whatever was held until the targets can be read again.
+            Err(e) => log::warn!("failed to check targets for a recheck: {e}"),
+        }
+    }
+}
+
+/// What the loop needs from the database each tick.
+struct Upcoming {
+    /// Every target, armed or not, so finished checkpoints can be dropped.
+    target_ids: Vec<i64>,
+    /// Whether an armed target is close enough to keep the machine awake.
+    close: bool,
+    /// Armed targets still ahead whose server could be read.
+    countdowns: Vec<countdown::TimeUntil>,
+}
+
+fn upcoming(state: &AppState, now: DateTime<Utc>) -> Result<Upcoming, AppError> {
+    let extrapolate_drift = state.db.get_settings()?.extrapolate_drift;
+    let targets = state.db.list_targets()?;
+    let mut countdowns = Vec::new();
+    for target in targets.iter().filter(|t| t.armed && t.target_at > now) {
+        let Ok(server) = state.db.get_server(target.server_id) else {
+            continue;
+        };
+        if let Ok(until) = countdown::time_until(target, &server, now, extrapolate_drift) {
+            countdowns.push(until);
+        }
+    }
+    Ok(Upcoming {
+        target_ids: targets.iter().map(|t| t.id).collect(),
+        close: targets
+            .iter()
+            .any(|t| t.armed && keeps_awake(t.target_at - now)),
+        countdowns,
+    })
+}
+
+/// Start the rechecks that are due. Returns whether an armed target is
+/// close enough to keep the machine awake for.
+fn start_due(
+    app: &AppHandle,
+    state: &AppState,
+    now: DateTime<Utc>,
+    upcoming: Upcoming,
+    done: &mut HashSet<(i64, u32)>,
+) -> bool {
+    done.retain(|(id, _)| upcoming.target_ids.contains(id));
+
+    for until in upcoming.countdowns {
+        let remaining = Duration::milliseconds(until.remaining_ms as i64);
+        let Some(checkpoint) = due_checkpoint(remaining) else {
+            continue;
+        };
+        if !done.insert((until.target_id, checkpoint)) {
+            continue;
+        }
+        // The sync in progress will store a fresh offset anyway.
+        if state
+            .active_syncs
+            .lock()
+            .unwrap()
+            .contains_key(&until.server_id)
+        {
+            continue;
+        }
+        tauri::async_runtime::spawn(recheck(
+            app.clone(),
+            until.target_id,
+            checkpoint,
+            until.offset_ms,
+            now + remaining - DEADLINE_MARGIN,
+        ));
+    }
+    upcoming.close
+}
+
+/// Whether a target `remaining` away is close enough to stay awake for.
+fn keeps_awake(remaining: Duration) -> bool {
+    remaining > Duration::zero() && remaining <= STAY_AWAKE
+}
+
+/// The checkpoint `remaining` has just reached, if any.
+fn due_checkpoint(remaining: Duration) -> Option<u32> {
+    CHECKPOINTS.into_iter().find(|&seconds| {
+        let at = Duration::seconds(seconds.into());
+        remaining <= at && remaining > at - GRACE
+    })
+}
+
+async fn recheck(
+    app: AppHandle,
+    target_id: i64,
+    seconds_before: u32,
+    offset_ms: f64,
+    deadline: DateTime<Utc>,
+) {
+    let state = app.state::<AppState>();
+    let confidence = match recheck_target(&app, target_id, offset_ms, deadline).await {
+        Ok((server_id, recheck)) => {
+            let moved = recheck.moved_ms != 0.0;
+            if moved {
+                state.alerts.refresh();
+            }
+            TargetConfidence {
+                target_id,
+                server_id,
+                seconds_before,
+                confidence: if moved {
+                    Confidence::Adjusted
+                } else {
+                    Confidence::Confirmed
+                },
+                previous_offset_ms: offset_ms,
+                offset_ms: recheck.offset_ms,
+                recheck: Some(recheck),
+                error: None,
+            }
+        }
+        Err((server_id, e)) => {
+            log::warn!("recheck of target {target_id} at T-{seconds_before}s failed: {e}");
+            TargetConfidence {
+                target_id,
+                server_id,
+                seconds_before,
+                confidence: Confidence::Failed,
+                previous_offset_ms: offset_ms,
+                offset_ms,
+                recheck: None,
+                error: Some(e.to_string()),
+            }
+        }
+    };
+    if let Err(e) = app.emit(CONFIDENCE_EVENT, &confidence) {
+        log::warn!("failed to emit target confidence: {e}");
+    }
+}
+
+/// Recheck `offset_ms` (the drift-extrapolated offset the countdown is
+/// using) and store the corrected one if it moved. Errors carry the server
+/// id when it is known.
+async fn recheck_target(
+    app: &AppHandle,
+    target_id: i64,
+    offset_ms: f64,
+    deadline: DateTime<Utc>,
+) -> Result<(i64, OffsetRecheck), (i64, AppError)> {
+    let handle = app.clone();
+    let (server, settings) = tokio::task::spawn_blocking(move || {
+        let state = handle.state::<AppState>();
+        let server = state
+            .db
+            .get_target(target_id)
+            .and_then(|t| state.db.get_server(t.server_id))
+            .map_err(|e| (0, e))?;
+        let settings = state.db.get_settings().map_err(|e| (server.id, e))?;
+        Ok((server, settings))
+    })
+    .await
+    .unwrap_or_else(|e| Err((0, std::io::Error::other(e).into())))?;
+    let failed = |e| (server.id, e);
+    let state = app.state::<AppState>();
+    let profile = sync_profile::resolve(&server, &settings);
+    let extractor = state
+        .extractors
+        .get(&server.extractor_type)
+        .map_err(failed)?;
+    let recheck = sync_engine::recheck_offset(
+        &server.url,
+        &state.probe_settings(&server, &profile),
+        extractor.as_ref(),
+        &profile,
+        offset_ms,
+        deadline,
+    )
+    .await
+    .map_err(failed)?;
+    // Past the deadline the countdown has run out on the old offset;
+    // moving it now would only shift what already fired.
+    if Utc::now() > deadline {
+        return Err(failed(AppError::RecheckTooLate));
+    }
+    if recheck.moved_ms != 0.0 {
+        let handle = app.clone();
+        let (id, moved_to, checked_at) = (server.id, recheck.offset_ms, recheck.checked_at);
+        tokio::task::spawn_blocking(move || {
+            let state = handle.state::<AppState>();
+            state.db.update_server_offset(id, moved_to, checked_at)
+        })
+        .await
+        .unwrap_or_else(|e| Err(std::io::Error::other(e).into()))
+        .map_err(failed)?;
+    }
+    Ok((server.id, recheck))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_checkpoint_is_due_just_once_it_is_reached() {
+        let due = |ms| due_checkpoint(Duration::milliseconds(ms));
+        assert_eq!(due(45_000), None);
+        assert_eq!(due(30_000), Some(30));
+        assert_eq!(due(28_500), Some(30));
+        assert_eq!(due(27_000), None);
+        assert_eq!(due(4_900), Some(5));
+        assert_eq!(due(2_500), None);
+        assert_eq!(due(0), None);
+    }
+
+    #[test]
+    fn stays_awake_only_in_the_minutes_before_a_target() {
+        assert!(!keeps_awake(Duration::minutes(11)));
+        assert!(keeps_awake(Duration::minutes(10)));
+        assert!(keeps_awake(Duration::seconds(1)));
+        assert!(!keeps_awake(Duration::seconds(-1)));
+    }
+}
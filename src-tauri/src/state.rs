@@ -1,11 +1,38 @@
+use crate::alerts::Alerts;
+use crate::countdown_stream::CountdownStreams;
 use crate::db::Database;
+use crate::host_limiter::HostLimiter;
+use crate::keep_warm::KeepWarm;
+use crate::lan_time::LanTime;
+use crate::local_api::LocalApi;
+use crate::models::Server;
+use crate::network_watch::NetworkWatch;
+use crate::power::SleepInhibitor;
+use crate::scheduler::Scheduler;
+use crate::scripting::Hooks;
+use crate::sync_engine::ProbeSettings;
+use crate::sync_profile::SyncProfile;
+use crate::taskbar::TaskbarProgress;
+use crate::time_extractor::ExtractorRegistry;
 use std::collections::HashMap;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 use tokio_util::sync::CancellationToken;
 
 pub struct AppState {
     pub db: Database,
     pub active_syncs: Mutex<HashMap<i64, CancellationToken>>,
+    pub sleep_inhibitor: Arc<SleepInhibitor>,
+    pub taskbar: TaskbarProgress,
+    pub local_api: LocalApi,
+    pub lan_time: LanTime,
+    pub scheduler: Scheduler,
+    pub alerts: Alerts,
+    pub keep_warm: KeepWarm,
+    pub network: NetworkWatch,
+    pub countdown_streams: CountdownStreams,
+    pub hooks: Hooks,
+    pub extractors: ExtractorRegistry,
+    pub host_limiter: Arc<HostLimiter>,
 }
 
 impl AppState {
@@ -13,6 +40,31 @@ impl AppState {
         Self {
             db,
             active_syncs: Mutex::new(HashMap::new()),
+            sleep_inhibitor: Arc::new(SleepInhibitor::new()),
+            taskbar: TaskbarProgress::new(),
+            local_api: LocalApi::new(),
+            lan_time: LanTime::new(),
+            scheduler: Scheduler::new(),
+            alerts: Alerts::new(),
+            keep_warm: KeepWarm::new(),
+            network: NetworkWatch::new(),
+            countdown_streams: CountdownStreams::new(),
+            hooks: Hooks::new(),
+            extractors: ExtractorRegistry::new(),
+            host_limiter: Arc::new(HostLimiter::new()),
+        }
+    }
+
+    /// A server's probe settings: the warm client if there is one, and
+    /// the limiter shared by every sync to its host.
+    pub fn probe_settings<'a>(
+        &self,
+        server: &'a Server,
+        profile: &SyncProfile,
+    ) -> ProbeSettings<'a> {
+        ProbeSettings {
+            limiter: Some(self.host_limiter.clone()),
+            ..self.keep_warm.probe_settings(server, profile)
         }
     }
 }
@@ -1,20 +1,195 @@
+use crate::asymmetry;
+use crate::certificate;
+use crate::clock_issue;
 use crate::error::AppError;
-use crate::models::{LatencyProfile, SyncPhase, SyncResult};
-use crate::time_extractor::TimeExtractor;
-
-use chrono::Utc;
+use crate::fingerprint::{self, ServerFingerprint};
+use crate::host_limiter::{self, HostLimiter};
+use crate::interception::{self, Answer};
+use crate::models::{
+    BenchmarkReport, BenchmarkRun, CertificateInfo, ConnectionTest, FailureContext, LatencyProfile,
+    OffsetEstimate, OffsetRecheck, PhaseTiming, ResponseMetadata, RttBreakdown, Server, SyncPhase,
+    SyncResult, TransportOptions,
+};
+use crate::network_watch;
+use crate::preflight;
+use crate::probe_timing::ConnectTimings;
+use crate::simulation::{DemoServer, ReplayServer};
+use crate::sync_grade::{self, GradeInputs};
+use crate::sync_profile::SyncProfile;
+use crate::sync_recording::{
+    ProbeRecorder, RecordedError, RecordedProbe, ReplayReport, SyncRecording,
+};
+use crate::time_extractor::{DateHeaderExtractor, TimeExtractor};
+
+use chrono::{DateTime, Utc};
+use reqwest::{header, StatusCode};
+use serde::Serialize;
 use std::future::Future;
 use std::pin::Pin;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
 use tokio_util::sync::CancellationToken;
 
-const MAX_RETRIES: u32 = 10;
-const MIN_INTERVAL_SECS: f64 = 0.5;
-const DEFAULT_PROBE_COUNT: usize = 10;
 const IQR_MULTIPLIER: f64 = 1.5;
+const MAX_BACKOFF_SECS: f64 = 8.0;
+const CONNECTION_TEST_PROBES: usize = 3;
+const CACHE_BUST_PARAM: &str = "_ttcb";
+/// Same limit as reqwest's default policy.
+const MAX_REDIRECTS: usize = 10;
+/// How long the probe connection may sit idle between probes.
+const POOL_IDLE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(60);
+/// Body bytes read to hand a connection back to the pool.
+const MAX_DRAIN_BYTES: usize = 64 * 1024;
+/// Relative RTT spread (IQR / median) at which latency probes keep the
+/// profile's interval as is.
+const JITTER_REFERENCE: f64 = 0.2;
+/// Widest latency-probe spacing, as a multiple of the profile's interval.
+const MAX_SPACING_FACTOR: f64 = 4.0;
+/// Round trips needed before their spread says anything.
+const MIN_JITTER_SAMPLES: usize = 4;
+/// Sent when a server has no User-Agent of its own. Browser-shaped, since
+/// some CDNs route unknown agents to slower edges or refuse them.
+pub const DEFAULT_USER_AGENT: &str = concat!(
+    "Mozilla/5.0 (compatible; Ticketime/",
+    env!("CARGO_PKG_VERSION"),
+    "; +https://github.com/mobster570/ticketime)"
+);
+/// Longest User-Agent a server may be given.
+pub const MAX_USER_AGENT_LEN: usize = 512;
+/// Accepted values for `TransportOptions::connect_timeout_ms`.
+pub const CONNECT_TIMEOUT_RANGE_MS: std::ops::RangeInclusive<u32> = 100..=30_000;
+
+/// How a server's probes go out: request method, User-Agent and socket
+/// options, and optionally a client whose connection is already open.
+pub struct ProbeSettings<'a> {
+    pub method: &'a str,
+    pub user_agent: Option<&'a str>,
+    pub transport: TransportOptions,
+    /// Built from the fields above when `None`.
+    pub client: Option<ProbeClient>,
+    /// Keeps every probe of a sync for replaying; see `sync_recording`.
+    pub recorder: Option<Arc<ProbeRecorder>>,
+    /// Shares the host's probe budget with other syncs running against it.
+    pub limiter: Option<Arc<HostLimiter>>,
+    /// Consult the captive-portal canary when the warm-up looks off; see
+    /// `interception`.
+    pub portal_check: bool,
+}
+
+impl<'a> ProbeSettings<'a> {
+    /// Defaults for everything but the method, for URLs with no server.
+    pub fn new(method: &'a str) -> Self {
+        Self {
+            method,
+            user_agent: None,
+            transport: TransportOptions::default(),
+            client: None,
+            recorder: None,
+            limiter: None,
+            portal_check: false,
+        }
+    }
+
+    pub fn for_server(server: &'a Server) -> Self {
+        Self {
+            method: &server.probe_method,
+            user_agent: server.user_agent.as_deref(),
+            transport: server.transport,
+            client: None,
+            recorder: None,
+            limiter: None,
+            portal_check: false,
+        }
+    }
+}
 
 /// Progress callback type
 pub type ProgressCallback = Box<dyn Fn(serde_json::Value) + Send + Sync + 'static>;
 
+/// What the phases report to: a `ProgressCallback`, or a wrapper that
+/// adds to its events before passing them on.
+type ProgressSink<'a> = dyn Fn(serde_json::Value) + Send + Sync + 'a;
+
+/// Why a probe was thrown away and its step retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Rejection {
+    /// Round trip outside the latency profile.
+    OutlierRtt,
+    /// Connection reset, timeout and the like.
+    TransportError,
+    /// Answered from a cache, so its Date is stale.
+    CachedResponse,
+}
+
+/// Rejected probes of one sync so far, by reason.
+#[derive(Debug, Default, Serialize)]
+struct RejectionCounts {
+    outlier_rtt: u32,
+    transport_error: u32,
+    cached_response: u32,
+}
+
+impl RejectionCounts {
+    fn count(&mut self, reason: &str) {
+        match reason {
+            "outlier_rtt" => self.outlier_rtt += 1,
+            "transport_error" => self.transport_error += 1,
+            "cached_response" => self.cached_response += 1,
+            _ => {}
+        }
+    }
+}
+
+/// Report a probe thrown away before retry `retry` (0-based) of a step,
+/// with `detail` merged into the event. The orchestrator adds the sync's
+/// running counts.
+fn report_rejection(
+    progress: &ProgressSink<'_>,
+    profile: &SyncProfile,
+    phase: SyncPhase,
+    retry: u32,
+    reason: Rejection,
+    backoff: f64,
+    detail: serde_json::Value,
+) {
+    let mut event = serde_json::json!({
+        "phase": phase,
+        "reason": reason,
+        "retry": retry + 1,
+        "max_retries": profile.max_retries,
+        "backoff_ms": backoff * 1000.0,
+    });
+    if let (Some(event), serde_json::Value::Object(detail)) = (event.as_object_mut(), detail) {
+        event.extend(detail);
+    }
+    progress(event);
+}
+
+/// Report a sample whose round trip fell outside `latency`; its step
+/// waits out the profile's interval before retrying.
+fn reject_outlier(
+    progress: &ProgressSink<'_>,
+    profile: &SyncProfile,
+    phase: SyncPhase,
+    retry: u32,
+    rtt: f64,
+    latency: &LatencyProfile,
+) {
+    report_rejection(
+        progress,
+        profile,
+        phase,
+        retry,
+        Rejection::OutlierRtt,
+        profile.min_interval_secs(),
+        serde_json::json!({
+            "rtt_ms": rtt * 1000.0,
+            "current_median_ms": latency.median * 1000.0,
+        }),
+    );
+}
+
 // ── Abstraction layer for testability ──
 
 /// Abstracts system clock operations so tests can use simulated time.
@@ -25,19 +200,36 @@ pub(crate) trait Clock: Send + Sync {
     fn monotonic_secs(&self) -> f64;
     /// Wait for a specified duration in seconds.
     fn wait(&self, seconds: f64);
+    /// The wall-clock time a probe wanted at `wanted` may go out. Clocks
+    /// shared with other syncs (see `PacedClock`) can push it later, to the
+    /// first time `align` maps a later instant to.
+    fn claim_send(&self, wanted: f64, _align: &dyn Fn(f64) -> f64) -> f64 {
+        wanted
+    }
     /// Wait until the system clock reaches a specific fractional-second position.
     /// `min_wait` is the minimum seconds to wait before firing (rate limiter).
     fn wait_until_fraction(&self, fraction: f64, min_wait: f64) {
         assert!((0.0..1.0).contains(&fraction), "fraction must be in [0, 1)");
         let now = self.system_time_secs();
-        let not_before = now + min_wait;
-        let base_second = not_before.floor();
-        let mut target = base_second + fraction;
-        if not_before > target {
-            target += 1.0;
-        }
+        let at_fraction = |not_before: f64| {
+            let target = not_before.floor() + fraction;
+            if not_before > target {
+                target + 1.0
+            } else {
+                target
+            }
+        };
+        let target = self.claim_send(at_fraction(now + min_wait), &at_fraction);
         self.wait(target - now);
     }
+    /// Wait until a probe that is not tied to a point of the second may go.
+    fn wait_for_send(&self) {
+        let now = self.system_time_secs();
+        let at = self.claim_send(now, &|t| t);
+        if at > now {
+            self.wait(at - now);
+        }
+    }
 }
 
 /// Abstracts the HTTP probe so tests can simulate network behaviour.
@@ -47,18 +239,204 @@ pub(crate) trait ServerProbe: Send + Sync {
         &'a self,
         url: &'a str,
     ) -> Pin<Box<dyn Future<Output = Result<(i64, f64), AppError>> + Send + 'a>>;
+
+    /// Where the last successful probe's round trip went, if the probe can
+    /// see its connection.
+    fn last_breakdown(&self) -> Option<RttBreakdown> {
+        None
+    }
+}
+
+/// Counts probes so a failed sync can say how far it got.
+struct CountingProbe<'a> {
+    inner: &'a dyn ServerProbe,
+    sent: AtomicU32,
+    failed: AtomicU32,
+}
+
+impl<'a> CountingProbe<'a> {
+    fn new(inner: &'a dyn ServerProbe) -> Self {
+        Self {
+            inner,
+            sent: AtomicU32::new(0),
+            failed: AtomicU32::new(0),
+        }
+    }
+
+    fn sent(&self) -> u32 {
+        self.sent.load(Ordering::SeqCst)
+    }
+
+    /// Probes that came back with an error, cached responses included.
+    fn failed(&self) -> u32 {
+        self.failed.load(Ordering::SeqCst)
+    }
+}
+
+impl ServerProbe for CountingProbe<'_> {
+    fn probe<'a>(
+        &'a self,
+        url: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<(i64, f64), AppError>> + Send + 'a>> {
+        self.sent.fetch_add(1, Ordering::SeqCst);
+        Box::pin(async move {
+            let result = self.inner.probe(url).await;
+            if result.is_err() {
+                self.failed.fetch_add(1, Ordering::SeqCst);
+            }
+            result
+        })
+    }
+
+    fn last_breakdown(&self) -> Option<RttBreakdown> {
+        self.inner.last_breakdown()
+    }
+}
+
+/// Gives up on an in-flight probe the moment the sync is cancelled, rather
+/// than sitting out the request timeout.
+struct CancellableProbe<'a> {
+    inner: &'a dyn ServerProbe,
+    token: &'a CancellationToken,
+}
+
+impl ServerProbe for CancellableProbe<'_> {
+    fn probe<'a>(
+        &'a self,
+        url: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<(i64, f64), AppError>> + Send + 'a>> {
+        Box::pin(async move {
+            tokio::select! {
+                biased;
+                _ = self.token.cancelled() => Err(AppError::Cancelled),
+                outcome = self.inner.probe(url) => outcome,
+            }
+        })
+    }
+
+    fn last_breakdown(&self) -> Option<RttBreakdown> {
+        self.inner.last_breakdown()
+    }
+}
+
+/// Ends the sync with the first probe to come back after `deadline`
+/// (monotonic seconds), so retries cannot keep one server busy forever.
+struct DeadlineProbe<'a> {
+    inner: &'a dyn ServerProbe,
+    clock: &'a dyn Clock,
+    deadline: f64,
+    budget_secs: u64,
+}
+
+impl ServerProbe for DeadlineProbe<'_> {
+    fn probe<'a>(
+        &'a self,
+        url: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<(i64, f64), AppError>> + Send + 'a>> {
+        Box::pin(async move {
+            let outcome = self.inner.probe(url).await;
+            if self.clock.monotonic_secs() >= self.deadline {
+                return Err(AppError::SyncDeadlineExceeded(self.budget_secs));
+            }
+            outcome
+        })
+    }
+
+    fn last_breakdown(&self) -> Option<RttBreakdown> {
+        self.inner.last_breakdown()
+    }
+}
+
+/// Hands each probe's send time, round trip and answer to `recorder`, when
+/// there is one.
+struct RecordingProbe<'a> {
+    inner: &'a dyn ServerProbe,
+    clock: &'a dyn Clock,
+    recorder: Option<&'a ProbeRecorder>,
+}
+
+impl ServerProbe for RecordingProbe<'_> {
+    fn probe<'a>(
+        &'a self,
+        url: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<(i64, f64), AppError>> + Send + 'a>> {
+        let Some(recorder) = self.recorder else {
+            return self.inner.probe(url);
+        };
+        Box::pin(async move {
+            let sent_at = self.clock.system_time_secs();
+            let started = self.clock.monotonic_secs();
+            let outcome = self.inner.probe(url).await;
+            recorder.push(match &outcome {
+                Ok((second, rtt)) => RecordedProbe {
+                    sent_at,
+                    rtt: *rtt,
+                    server_second: Some(*second),
+                    error: None,
+                },
+                Err(e) => RecordedProbe {
+                    sent_at,
+                    rtt: self.clock.monotonic_secs() - started,
+                    server_second: None,
+                    error: Some(match e {
+                        AppError::CachedResponse(_) => RecordedError::Cached,
+                        e if is_transient(e) => RecordedError::Transient(e.to_string()),
+                        e => RecordedError::Fatal(e.to_string()),
+                    }),
+                },
+            });
+            outcome
+        })
+    }
+
+    fn last_breakdown(&self) -> Option<RttBreakdown> {
+        self.inner.last_breakdown()
+    }
+}
+
+/// A sync error plus where it happened.
+#[derive(Debug)]
+pub struct SyncFailure {
+    pub error: AppError,
+    pub context: FailureContext,
+}
+
+impl SyncFailure {
+    /// Failed before any probe was sent (bad URL, client setup).
+    pub(crate) fn before_start(error: AppError) -> Self {
+        Self {
+            error,
+            context: FailureContext {
+                phase: SyncPhase::LatencyProfiling,
+                phase_attempts: 0,
+                total_attempts: 0,
+                latency_profile: None,
+                partial_offset_ms: None,
+                elapsed_ms: 0,
+            },
+        }
+    }
+}
+
+impl From<SyncFailure> for AppError {
+    fn from(failure: SyncFailure) -> Self {
+        failure.error
+    }
 }
 
 // ── Real (production) implementations ──
 
 struct RealClock {
     epoch: std::time::Instant,
+    /// Cuts waits short when the run is cancelled.
+    token: CancellationToken,
 }
 
 impl RealClock {
-    fn new() -> Self {
+    fn new(token: CancellationToken) -> Self {
         Self {
             epoch: std::time::Instant::now(),
+            token,
         }
     }
 }
@@ -71,13 +449,245 @@ impl Clock for RealClock {
         self.epoch.elapsed().as_secs_f64()
     }
     fn wait(&self, seconds: f64) {
-        crate::timing::precise_wait(seconds);
+        crate::timing::precise_wait(seconds, &self.token);
+    }
+}
+
+/// `inner`, with every send time claimed from `limiter` so probes from
+/// other syncs to the same host keep `gap` clear of these.
+struct PacedClock<'a> {
+    inner: &'a dyn Clock,
+    limiter: Option<(&'a HostLimiter, String)>,
+    gap: f64,
+}
+
+impl<'a> PacedClock<'a> {
+    fn new(
+        inner: &'a dyn Clock,
+        settings: &'a ProbeSettings,
+        url: &str,
+        profile: &SyncProfile,
+    ) -> Self {
+        Self {
+            inner,
+            limiter: settings.limiter.as_deref().zip(host_limiter::host_key(url)),
+            // Never below the public-host floor for untrusted hosts; see
+            // `sync_profile::resolve`.
+            gap: profile.min_spacing_secs(),
+        }
+    }
+}
+
+impl Clock for PacedClock<'_> {
+    fn system_time_secs(&self) -> f64 {
+        self.inner.system_time_secs()
+    }
+    fn monotonic_secs(&self) -> f64 {
+        self.inner.monotonic_secs()
+    }
+    fn wait(&self, seconds: f64) {
+        self.inner.wait(seconds)
+    }
+    fn claim_send(&self, wanted: f64, align: &dyn Fn(f64) -> f64) -> f64 {
+        match &self.limiter {
+            Some((limiter, host)) => limiter.claim(host, self.gap, wanted, align),
+            None => wanted,
+        }
+    }
+}
+
+/// The HTTP client probes go out on, with the hooks they read. Clones
+/// share one connection pool, so a client kept between syncs (see
+/// `keep_warm`) spares the next probe its handshakes.
+#[derive(Clone)]
+pub struct ProbeClient {
+    http: reqwest::Client,
+    /// Redirects followed by the current probe; reset before each send.
+    redirects: Arc<AtomicU32>,
+    /// Filled in by the client's resolver and connector hooks.
+    timings: ConnectTimings,
+    /// Counts the clones; the hooks hold on to the other fields.
+    handles: Arc<()>,
+}
+
+impl ProbeClient {
+    pub fn new(profile: &SyncProfile, settings: &ProbeSettings) -> Result<Self, AppError> {
+        let redirects = Arc::new(AtomicU32::new(0));
+        let counter = redirects.clone();
+        let timings = ConnectTimings::default();
+        let transport = settings.transport;
+        let mut builder = reqwest::Client::builder();
+        if let Some(ms) = transport.connect_timeout_ms {
+            builder = builder.connect_timeout(std::time::Duration::from_millis(ms.into()));
+        }
+        let http = builder
+            .timeout(profile.timeout())
+            .user_agent(settings.user_agent.unwrap_or(DEFAULT_USER_AGENT))
+            .tcp_nodelay(transport.tcp_nodelay)
+            .tls_info(true)
+            .danger_accept_invalid_certs(transport.accept_invalid_certs)
+            .redirect(reqwest::redirect::Policy::custom(move |attempt| {
+                let followed = attempt.previous().len();
+                if followed > MAX_REDIRECTS {
+                    return attempt.error("too many redirects");
+                }
+                counter.store(followed as u32, Ordering::SeqCst);
+                attempt.follow()
+            }))
+            // Every probe goes to one host, one at a time: keep that
+            // connection open for the whole sync.
+            .pool_max_idle_per_host(1)
+            .pool_idle_timeout(POOL_IDLE_TIMEOUT)
+            .tcp_keepalive(POOL_IDLE_TIMEOUT)
+            .dns_resolver(timings.resolver(transport.ip_family))
+            .connector_layer(timings.layer())
+            .build()?;
+        Ok(Self {
+            http,
+            redirects,
+            timings,
+            handles: Arc::new(()),
+        })
+    }
+
+    /// Whether another clone of this client is alive, such as one a probe
+    /// is measuring with.
+    pub fn is_shared(&self) -> bool {
+        Arc::strong_count(&self.handles) > 1
     }
 }
 
 struct RealServerProbe<'a> {
-    client: &'a reqwest::Client,
+    client: ProbeClient,
+    method: reqwest::Method,
     extractor: &'a dyn TimeExtractor,
+    /// Headers of the latest response that had anything to fingerprint.
+    fingerprint: Mutex<Option<ServerFingerprint>>,
+    response: Mutex<Option<ResponseMetadata>>,
+    /// The server's certificate, read from the first response over TLS.
+    certificate: Mutex<Option<CertificateInfo>>,
+    /// The probed URL, parsed once; each probe only appends its nonce.
+    target: Mutex<Option<(String, reqwest::Url)>>,
+    /// Local time spent around each request (seconds, probe count).
+    overhead: Mutex<(f64, u32)>,
+    breakdown: Mutex<Option<RttBreakdown>>,
+}
+
+impl<'a> RealServerProbe<'a> {
+    fn new(
+        profile: &SyncProfile,
+        settings: &ProbeSettings,
+        extractor: &'a dyn TimeExtractor,
+    ) -> Result<Self, AppError> {
+        let client = match &settings.client {
+            Some(client) => client.clone(),
+            None => ProbeClient::new(profile, settings)?,
+        };
+        Ok(Self {
+            client,
+            method: request_method(settings.method, extractor.reads_body()),
+            extractor,
+            fingerprint: Mutex::new(None),
+            response: Mutex::new(None),
+            certificate: Mutex::new(None),
+            target: Mutex::new(None),
+            overhead: Mutex::new((0.0, 0)),
+            breakdown: Mutex::new(None),
+        })
+    }
+
+    /// Open the connection with a throwaway request so the TCP and TLS
+    /// handshakes stay out of the measured round trips. Failures are left
+    /// for the first real probe to report; the answer is only looked at
+    /// for signs of interception.
+    async fn warm_up(&self, url: &str) -> Result<Answer, AppError> {
+        let response = self.request(self.target(url)?).send().await?;
+        let answer = Answer::of(&response);
+        self.release(response).await;
+        Ok(answer)
+    }
+
+    fn target(&self, url: &str) -> Result<reqwest::Url, AppError> {
+        let mut cached = self.target.lock().unwrap();
+        match &*cached {
+            Some((raw, parsed)) if raw == url => Ok(cache_busted(parsed)),
+            _ => {
+                let parsed =
+                    reqwest::Url::parse(url).map_err(|e| AppError::InvalidUrl(e.to_string()))?;
+                let busted = cache_busted(&parsed);
+                *cached = Some((url.to_string(), parsed));
+                Ok(busted)
+            }
+        }
+    }
+
+    fn request(&self, target: reqwest::Url) -> reqwest::RequestBuilder {
+        let request = self
+            .client
+            .http
+            .request(self.method.clone(), target)
+            .header(header::CACHE_CONTROL, "no-cache")
+            .header(header::PRAGMA, "no-cache");
+        if self.method == reqwest::Method::GET && !self.extractor.reads_body() {
+            // Only the headers matter; a one-byte body is cheap to drain.
+            request.header(header::RANGE, "bytes=0-0")
+        } else {
+            request
+        }
+    }
+
+    /// The body, up to `MAX_DRAIN_BYTES`, for extractors that read it.
+    async fn read_body(&self, response: &mut reqwest::Response) -> Vec<u8> {
+        let mut body = Vec::new();
+        if !self.extractor.reads_body() {
+            return body;
+        }
+        while let Ok(Some(chunk)) = response.chunk().await {
+            let room = MAX_DRAIN_BYTES - body.len();
+            body.extend_from_slice(&chunk[..chunk.len().min(room)]);
+            if body.len() == MAX_DRAIN_BYTES {
+                break;
+            }
+        }
+        body
+    }
+
+    /// Read what is left of the body so the connection goes back to the
+    /// pool. Servers that ignore `Range` and send more than
+    /// `MAX_DRAIN_BYTES` just get their connection dropped.
+    async fn release(&self, mut response: reqwest::Response) {
+        let mut read = 0;
+        while let Ok(Some(chunk)) = response.chunk().await {
+            read += chunk.len();
+            if read > MAX_DRAIN_BYTES {
+                break;
+            }
+        }
+    }
+
+    /// Mean local overhead per probe so far, in microseconds.
+    fn overhead_us(&self) -> f64 {
+        let (total, probes) = *self.overhead.lock().unwrap();
+        if probes == 0 {
+            0.0
+        } else {
+            total / probes as f64 * 1e6
+        }
+    }
+
+    /// The last response's metadata, with the run's mean overhead.
+    fn take_response(&self) -> Option<ResponseMetadata> {
+        let overhead = self.overhead_us();
+        self.response
+            .lock()
+            .unwrap()
+            .take()
+            .map(|r| ResponseMetadata {
+                probe_overhead_us: overhead,
+                certificate: self.certificate.lock().unwrap().clone(),
+                ..r
+            })
+    }
 }
 
 impl ServerProbe for RealServerProbe<'_> {
@@ -86,17 +696,110 @@ impl ServerProbe for RealServerProbe<'_> {
         url: &'a str,
     ) -> Pin<Box<dyn Future<Output = Result<(i64, f64), AppError>> + Send + 'a>> {
         Box::pin(async move {
+            let entered = std::time::Instant::now();
+            self.client.redirects.store(0, Ordering::SeqCst);
+            let request = self.request(self.target(url)?);
+            self.client.timings.reset();
             let start = std::time::Instant::now();
-            let response = self.client.head(url).send().await?;
+            let mut response = request.send().await.map_err(offline_or_http)?;
             let rtt = start.elapsed().as_secs_f64();
-            let timestamp = self.extractor.extract_time(&response)?;
-            Ok((timestamp, rtt))
+            let received = std::time::Instant::now();
+            *self.breakdown.lock().unwrap() = Some(self.client.timings.breakdown(rtt));
+
+            if let Some(fp) = fingerprint::from_headers(response.headers()) {
+                *self.fingerprint.lock().unwrap() = Some(fp);
+            }
+            *self.response.lock().unwrap() = Some(ResponseMetadata {
+                status: response.status().as_u16(),
+                http_version: preflight::version_label(response.version()).to_string(),
+                remote_ip: response.remote_addr().map(|addr| addr.ip().to_string()),
+                redirects: self.client.redirects.load(Ordering::SeqCst),
+                probe_overhead_us: 0.0,
+                certificate: None,
+            });
+            {
+                let mut cert = self.certificate.lock().unwrap();
+                if cert.is_none() {
+                    *cert = response
+                        .extensions()
+                        .get::<reqwest::tls::TlsInfo>()
+                        .and_then(|tls| tls.peer_certificate())
+                        .and_then(certificate::parse);
+                }
+            }
+            // A portal's Date is the portal's clock, and a cache replaying a
+            // stored response also replays its Date.
+            let outcome = if response.status() == StatusCode::NETWORK_AUTHENTICATION_REQUIRED {
+                Err(interception::sign_in_required())
+            } else {
+                match cached_age(response.headers()) {
+                    Some(age) => Err(AppError::CachedResponse(age)),
+                    None => {
+                        let body = self.read_body(&mut response).await;
+                        self.extractor.extract_time(&response, &body)
+                    }
+                }
+            };
+            {
+                let mut overhead = self.overhead.lock().unwrap();
+                overhead.0 += (start - entered).as_secs_f64() + received.elapsed().as_secs_f64();
+                overhead.1 += 1;
+            }
+            self.release(response).await;
+            Ok((outcome?, rtt))
         })
     }
+
+    fn last_breakdown(&self) -> Option<RttBreakdown> {
+        *self.breakdown.lock().unwrap()
+    }
 }
 
 // ── Helper ──
 
+/// A connection that fails while the machine has no route to the internet
+/// will keep failing; report it as `Offline` so the sync stops instead of
+/// spending its retry budget.
+fn offline_or_http(error: reqwest::Error) -> AppError {
+    if error.is_connect() && network_watch::Route::current().is_offline() {
+        AppError::Offline
+    } else {
+        error.into()
+    }
+}
+
+/// `base` with a throwaway query parameter so no cache has seen it before.
+fn cache_busted(base: &reqwest::Url) -> reqwest::Url {
+    let mut url = base.clone();
+    let nonce = Utc::now().timestamp_nanos_opt().unwrap_or_default();
+    url.query_pairs_mut()
+        .append_pair(CACHE_BUST_PARAM, &format!("{nonce:x}"));
+    url
+}
+
+/// A non-zero `Age` header means the response was served from a cache.
+fn cached_age(headers: &header::HeaderMap) -> Option<u64> {
+    headers
+        .get(header::AGE)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .filter(|&age| age > 0)
+}
+
+/// `GET` if asked for, else `HEAD`: the only methods that carry a `Date`
+/// header without side effects. Always `GET` for an extractor that reads
+/// the body, since a `HEAD` answer has none.
+fn request_method(name: &str, reads_body: bool) -> reqwest::Method {
+    if reads_body || name.eq_ignore_ascii_case("GET") {
+        reqwest::Method::GET
+    } else {
+        reqwest::Method::HEAD
+    }
+}
+
 /// Check cancellation and return Err if cancelled.
 fn check_cancelled(token: &CancellationToken) -> Result<(), AppError> {
     if token.is_cancelled() {
@@ -105,37 +808,187 @@ fn check_cancelled(token: &CancellationToken) -> Result<(), AppError> {
     Ok(())
 }
 
+/// Timeouts, refused or reset connections and truncated bodies. Anything
+/// else (bad Date header, invalid URL) fails the sync straight away.
+fn is_transient(error: &AppError) -> bool {
+    matches!(error, AppError::Http(_) | AppError::Io(_)) && error.retryable()
+}
+
+/// Pause before transient retry `retry` (0-based), on top of the usual
+/// probe spacing: the profile's interval, doubling each time, capped.
+fn backoff_secs(profile: &SyncProfile, retry: u32) -> f64 {
+    (profile.min_interval_secs() * 2f64.powi(retry.min(16) as i32)).min(MAX_BACKOFF_SECS)
+}
+
+/// Send one probe. A transient transport failure is reported, backed off
+/// and returned as `Ok(None)`, so callers count it against their retry
+/// budget like an out-of-range RTT and re-align before trying again. A
+/// cached response is an outlier too, just without the backoff.
+async fn try_probe(
+    probe: &dyn ServerProbe,
+    clock: &dyn Clock,
+    profile: &SyncProfile,
+    url: &str,
+    phase: SyncPhase,
+    retry: u32,
+    progress: &ProgressSink<'_>,
+) -> Result<Option<(i64, f64)>, AppError> {
+    match probe.probe(url).await {
+        Ok(sample) => Ok(Some(sample)),
+        Err(e @ AppError::CachedResponse(_)) => {
+            let detail = serde_json::json!({ "error": e.to_string() });
+            report_rejection(
+                progress,
+                profile,
+                phase,
+                retry,
+                Rejection::CachedResponse,
+                0.0,
+                detail,
+            );
+            Ok(None)
+        }
+        Err(e) if is_transient(&e) => {
+            let backoff = backoff_secs(profile, retry);
+            let detail = serde_json::json!({ "error": e.to_string() });
+            report_rejection(
+                progress,
+                profile,
+                phase,
+                retry,
+                Rejection::TransportError,
+                backoff,
+                detail,
+            );
+            clock.wait(backoff);
+            Ok(None)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// A probe sent at a chosen point of the local second.
+struct AlignedSample {
+    server_second: i64,
+    rtt: f64,
+    /// Local wall-clock time just before sending.
+    sent_at: f64,
+    /// Monotonic time once the answer was in.
+    received_at: f64,
+}
+
+/// Probe when the local clock reaches `fraction` of a second, keeping the
+/// shortest round trip out of `profile.burst_size` tries. On a jittery link
+/// one probe of a short burst usually gets through cleanly where single
+/// probes keep landing outside the latency profile. Probes within a burst
+/// still keep the per-host spacing. `Ok(None)` when every try failed
+/// transiently; the burst counts as one attempt against `retry`.
+#[allow(clippy::too_many_arguments)]
+async fn aligned_probe(
+    probe: &dyn ServerProbe,
+    clock: &dyn Clock,
+    profile: &SyncProfile,
+    url: &str,
+    fraction: f64,
+    phase: SyncPhase,
+    retry: u32,
+    progress: &ProgressSink<'_>,
+) -> Result<Option<AlignedSample>, AppError> {
+    let mut best: Option<AlignedSample> = None;
+    for i in 0..profile.burst_size.max(1) {
+        let min_wait = if i == 0 {
+            profile.min_interval_secs()
+        } else {
+            profile.min_spacing_secs()
+        };
+        clock.wait_until_fraction(fraction, min_wait);
+        let sent_at = clock.system_time_secs();
+        let Some((server_second, rtt)) =
+            try_probe(probe, clock, profile, url, phase, retry, progress).await?
+        else {
+            continue;
+        };
+        if best.as_ref().map_or(true, |b| rtt < b.rtt) {
+            best = Some(AlignedSample {
+                server_second,
+                rtt,
+                sent_at,
+                received_at: clock.monotonic_secs(),
+            });
+        }
+    }
+    Ok(best)
+}
+
 // ── Phase 1: Latency Profiling ──
 
+/// A latency probe's answer, kept so the whole second can be read off it
+/// once the sub-second offset is known.
+struct StampedSample {
+    /// Local wall-clock time just before sending.
+    sent_at: f64,
+    rtt: f64,
+    server_second: i64,
+}
+
 async fn measure_latency(
     probe: &dyn ServerProbe,
     clock: &dyn Clock,
+    profile: &SyncProfile,
     url: &str,
     token: &CancellationToken,
-    progress: &ProgressCallback,
-) -> Result<LatencyProfile, AppError> {
-    let mut rtts: Vec<f64> = Vec::with_capacity(DEFAULT_PROBE_COUNT);
-
-    for i in 0..DEFAULT_PROBE_COUNT {
+    progress: &ProgressSink<'_>,
+) -> Result<(LatencyProfile, Vec<StampedSample>), AppError> {
+    let probe_count = profile.probe_count;
+    let mut rtts: Vec<f64> = Vec::with_capacity(probe_count);
+    let mut samples: Vec<StampedSample> = Vec::with_capacity(probe_count);
+    let mut breakdowns: Vec<RttBreakdown> = Vec::with_capacity(probe_count);
+    let mut failures = 0u32;
+
+    while rtts.len() < probe_count {
         check_cancelled(token)?;
 
-        let (_, rtt) = probe.probe(url).await?;
+        let phase = SyncPhase::LatencyProfiling;
+        clock.wait_for_send();
+        let sent_at = clock.system_time_secs();
+        let Some((server_second, rtt)) =
+            try_probe(probe, clock, profile, url, phase, failures, progress).await?
+        else {
+            failures += 1;
+            if failures >= profile.max_retries {
+                return Err(AppError::MaxRetriesExceeded(profile.max_retries));
+            }
+            continue;
+        };
+        let i = rtts.len();
         rtts.push(rtt);
+        samples.push(StampedSample {
+            sent_at,
+            rtt,
+            server_second,
+        });
+        let breakdown = probe.last_breakdown();
+        breakdowns.extend(breakdown);
 
         let mut sorted = rtts.clone();
         sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
         let current_median = sorted[sorted.len() / 2];
 
+        let spacing = probe_spacing(profile, &rtts);
         progress(serde_json::json!({
             "phase": SyncPhase::LatencyProfiling,
             "probe_index": i,
-            "total_probes": DEFAULT_PROBE_COUNT,
+            "total_probes": probe_count,
+            "spacing_ms": spacing * 1000.0,
             "rtt_ms": rtt * 1000.0,
             "current_median_ms": current_median * 1000.0,
+            "dns_ms": breakdown.map(|b| b.dns * 1000.0),
+            "connect_ms": breakdown.map(|b| b.connect * 1000.0),
+            "ttfb_ms": breakdown.map(|b| b.ttfb * 1000.0),
         }));
 
-        if i < DEFAULT_PROBE_COUNT - 1 {
-            clock.wait(MIN_INTERVAL_SECS);
+        if i < probe_count - 1 {
+            clock.wait(spacing);
         }
     }
 
@@ -150,128 +1003,258 @@ async fn measure_latency(
         rtts[lo] + (rtts[hi] - rtts[lo]) * (index - lo as f64)
     };
 
-    let profile = LatencyProfile {
+    let latency = LatencyProfile {
         min: quartile(0),
         q1: quartile(1),
         median: quartile(2),
         mean: rtts.iter().sum::<f64>() / n as f64,
         q3: quartile(3),
         max: quartile(4),
+        breakdown: RttBreakdown::mean(&breakdowns),
     };
+    Ok((latency, samples))
+}
+
+/// Gap before the next latency probe: the profile's interval, scaled by how
+/// jittery the round trips so far are (IQR over median, against
+/// `JITTER_REFERENCE`). Clean links finish sooner, down to the profile's
+/// per-host floor; congested ones get up to `MAX_SPACING_FACTOR` times
+/// more room to settle.
+fn probe_spacing(profile: &SyncProfile, rtts: &[f64]) -> f64 {
+    let nominal = profile.min_interval_secs();
+    if rtts.len() < MIN_JITTER_SAMPLES {
+        return nominal;
+    }
+    let mut sorted = rtts.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let n = sorted.len();
+    let median = sorted[n / 2];
+    if median <= 0.0 {
+        return nominal;
+    }
+    let jitter = (sorted[n * 3 / 4] - sorted[n / 4]) / median;
+    // Not `clamp`, which panics when the spacing floor is above the cap.
+    (nominal * jitter / JITTER_REFERENCE)
+        .max(profile.min_spacing_secs())
+        .min(nominal * MAX_SPACING_FACTOR)
+}
 
-    Ok(profile)
+/// Longest a sync on `profile` can go between two progress reports: the
+/// widest probe spacing, the wait for the next aligned fraction, and one
+/// request sitting out its timeout.
+pub fn longest_probe_gap(profile: &SyncProfile) -> std::time::Duration {
+    let spacing = profile
+        .min_spacing_secs()
+        .max(profile.min_interval_secs() * MAX_SPACING_FACTOR);
+    std::time::Duration::from_secs_f64(spacing + 1.0) + profile.timeout()
 }
 
 // ── Phase 2: Whole-Second Offset ──
 
+/// Stamps closer than this to a second boundary could fall on either side.
+const BOUNDARY_GUARD_SECS: f64 = 0.010;
+
+/// Fewest latency probes that must agree before the dedicated probe is
+/// skipped.
+const MIN_AGREEING_SAMPLES: usize = 2;
+
+/// The whole-second offset the latency probes imply, given the sub-second
+/// offset and the width it is known to within. A probe sent at an arbitrary
+/// point of the second only settles the whole second once the sub-second
+/// part is known; probes stamped too near a boundary are left out. `None`
+/// when the rest disagree or too few are left.
+fn whole_second_from_samples(
+    samples: &[StampedSample],
+    latency: &LatencyProfile,
+    asymmetry: f64,
+    subsecond: f64,
+    width: f64,
+) -> Option<i64> {
+    let outbound = latency.median * asymmetry;
+    let mut agreed = None;
+    let mut agreeing = 0;
+    for sample in samples {
+        // Local time of the stamp, shifted by the sub-second offset: its
+        // whole part differs from the server's second by the whole offset.
+        let shifted = sample.sent_at + outbound + subsecond;
+        let margin = (sample.rtt - latency.median).abs() * asymmetry + width + BOUNDARY_GUARD_SECS;
+        let into_second = shifted.rem_euclid(1.0);
+        if into_second < margin || into_second > 1.0 - margin {
+            continue;
+        }
+        let whole = sample.server_second - shifted.floor() as i64;
+        if agreed.is_some_and(|w| w != whole) {
+            return None;
+        }
+        agreed = Some(whole);
+        agreeing += 1;
+    }
+    agreed.filter(|_| agreeing >= MIN_AGREEING_SAMPLES)
+}
+
 async fn find_second_offset(
     probe: &dyn ServerProbe,
     clock: &dyn Clock,
+    profile: &SyncProfile,
     url: &str,
     latency: &LatencyProfile,
     token: &CancellationToken,
-    progress: &ProgressCallback,
+    progress: &ProgressSink<'_>,
 ) -> Result<i64, AppError> {
-    let half_rtt = latency.median / 2.0;
+    // Send to the server stamping its Date: half the RTT on a symmetric path.
+    let outbound = latency.median * profile.asymmetry;
 
-    for attempt in 0..MAX_RETRIES {
+    for attempt in 0..profile.max_retries {
         check_cancelled(token)?;
 
-        clock.wait_until_fraction((1.0 - half_rtt).rem_euclid(1.0), MIN_INTERVAL_SECS);
-
-        let client_predicted_second = (clock.system_time_secs() + half_rtt) as i64;
-
-        let (server_second, rtt) = probe.probe(url).await?;
+        let fraction = (1.0 - outbound).rem_euclid(1.0);
+        let phase = SyncPhase::WholeSecondOffset;
+        let Some(sample) = aligned_probe(
+            probe, clock, profile, url, fraction, phase, attempt, progress,
+        )
+        .await?
+        else {
+            continue;
+        };
 
-        if latency.is_in_range(rtt, IQR_MULTIPLIER) {
-            let offset = server_second - client_predicted_second;
+        if latency.is_in_range(sample.rtt, IQR_MULTIPLIER) {
+            let client_predicted_second = (sample.sent_at + outbound) as i64;
+            let offset = sample.server_second - client_predicted_second;
 
             progress(serde_json::json!({
                 "phase": SyncPhase::WholeSecondOffset,
+                "source": "probe",
                 "attempt": attempt,
                 "offset_seconds": offset,
+                "clock_issue": clock_issue::classify(offset as f64 * 1000.0),
                 "current_median_ms": latency.median * 1000.0,
             }));
 
             return Ok(offset);
         }
 
-        clock.wait(MIN_INTERVAL_SECS);
+        reject_outlier(progress, profile, phase, attempt, sample.rtt, latency);
+        clock.wait(profile.min_interval_secs());
     }
 
-    Err(AppError::MaxRetriesExceeded(MAX_RETRIES))
+    Err(AppError::MaxRetriesExceeded(profile.max_retries))
 }
 
 // ── Phase 3: Binary Search for Millisecond Offset ──
 
+/// Narrowest search interval worth probing for, in seconds.
+const SEARCH_RESOLUTION: f64 = 0.001;
+
+/// Widest stopping point, in seconds, so that a link too noisy to narrow
+/// the second down still gets a few halvings instead of the untested
+/// midpoint of the starting interval.
+const MAX_SEARCH_TARGET: f64 = 0.25;
+
+/// Where halving the interval stops paying off. Each probe lands at the
+/// server up to the RTT spread's outbound share from where it was aimed,
+/// so an interval narrower than that cannot be told apart from noise.
+fn search_target(latency: &LatencyProfile, profile: &SyncProfile) -> f64 {
+    (latency.iqr() * profile.asymmetry).clamp(SEARCH_RESOLUTION, MAX_SEARCH_TARGET)
+}
+
+/// The sub-second offset, and the width in seconds of the window it is
+/// known to within: the final search interval plus how far the kept
+/// samples' server stamps could sit from where the median RTT puts them.
+/// The search stops once the interval is inside `search_target`; when that
+/// is above `SEARCH_RESOLUTION` the offset is the interval's midpoint.
 async fn find_millisecond_offset(
     probe: &dyn ServerProbe,
     clock: &dyn Clock,
+    profile: &SyncProfile,
     url: &str,
     latency: &LatencyProfile,
     token: &CancellationToken,
-    progress: &ProgressCallback,
-) -> Result<f64, AppError> {
-    let half_rtt = latency.median / 2.0;
+    progress: &ProgressSink<'_>,
+) -> Result<(f64, f64), AppError> {
+    let outbound = latency.median * profile.asymmetry;
 
     // Step 1: Get baseline server date
-    let mut previous_date: i64;
+    let mut previous: AlignedSample;
     let mut retries = 0u32;
     loop {
         check_cancelled(token)?;
 
-        clock.wait_until_fraction((1.0 - half_rtt).rem_euclid(1.0), MIN_INTERVAL_SECS);
-
-        let (date, rtt) = probe.probe(url).await?;
-        if latency.is_in_range(rtt, IQR_MULTIPLIER) {
-            previous_date = date;
-            break;
+        let fraction = (1.0 - outbound).rem_euclid(1.0);
+        let phase = SyncPhase::BinarySearch;
+        if let Some(sample) = aligned_probe(
+            probe, clock, profile, url, fraction, phase, retries, progress,
+        )
+        .await?
+        {
+            if latency.is_in_range(sample.rtt, IQR_MULTIPLIER) {
+                previous = sample;
+                break;
+            }
+            reject_outlier(progress, profile, phase, retries, sample.rtt, latency);
         }
 
         retries += 1;
-        if retries >= MAX_RETRIES {
-            return Err(AppError::MaxRetriesExceeded(MAX_RETRIES));
+        if retries >= profile.max_retries {
+            return Err(AppError::MaxRetriesExceeded(profile.max_retries));
         }
-        clock.wait(MIN_INTERVAL_SECS);
+        clock.wait(profile.min_interval_secs());
     }
 
     // Step 2: Binary search for second boundary
     let mut left = 0.0_f64;
     let mut right = 1.0_f64;
     let mut iteration = 0u32;
+    let (mut min_rtt, mut max_rtt) = (previous.rtt, previous.rtt);
+    let target = search_target(latency, profile);
 
-    while right - left >= 0.001 {
+    while right - left >= target {
         check_cancelled(token)?;
 
         let mid = (left + right) / 2.0;
-        let wall_start = clock.monotonic_secs();
 
         // Probe at midpoint with retry loop for RTT validation
-        let current_date: i64;
+        let current: AlignedSample;
         let mut inner_retries = 0u32;
         loop {
             check_cancelled(token)?;
 
-            clock.wait_until_fraction((mid - half_rtt).rem_euclid(1.0), MIN_INTERVAL_SECS);
-
-            let (date, rtt) = probe.probe(url).await?;
-            if latency.is_in_range(rtt, IQR_MULTIPLIER) {
-                current_date = date;
-                break;
+            let fraction = (mid - outbound).rem_euclid(1.0);
+            let phase = SyncPhase::BinarySearch;
+            if let Some(sample) = aligned_probe(
+                probe,
+                clock,
+                profile,
+                url,
+                fraction,
+                phase,
+                inner_retries,
+                progress,
+            )
+            .await?
+            {
+                if latency.is_in_range(sample.rtt, IQR_MULTIPLIER) {
+                    min_rtt = min_rtt.min(sample.rtt);
+                    max_rtt = max_rtt.max(sample.rtt);
+                    current = sample;
+                    break;
+                }
+                reject_outlier(progress, profile, phase, inner_retries, sample.rtt, latency);
             }
 
             inner_retries += 1;
-            if inner_retries >= MAX_RETRIES {
-                return Err(AppError::MaxRetriesExceeded(MAX_RETRIES));
+            if inner_retries >= profile.max_retries {
+                return Err(AppError::MaxRetriesExceeded(profile.max_retries));
             }
-            clock.wait(MIN_INTERVAL_SECS);
+            clock.wait(profile.min_interval_secs());
         }
 
         // Truncation (as i64) matches the C++ reference: static_cast<time_t>(elapsed).
         // Do NOT use .round() (Rust rounds 0.5→1, causing ~500ms error) or
         // floor-diff (overcounts when probes straddle a second boundary).
-        let elapsed_seconds = (clock.monotonic_secs() - wall_start) as i64;
-        let date_change = current_date - previous_date;
+        // Measured between the samples kept, which in a burst need not be
+        // the last ones sent.
+        let elapsed_seconds = (current.received_at - previous.received_at) as i64;
+        let date_change = current.server_second - previous.server_second;
 
         if date_change == elapsed_seconds {
             // Server's second did NOT tick over — boundary is LATER
@@ -290,30 +1273,53 @@ async fn find_millisecond_offset(
             "left_bound_ms": left * 1000.0,
             "right_bound_ms": right * 1000.0,
             "interval_width_ms": interval_width_ms,
+            "target_width_ms": target * 1000.0,
             "convergence_percent": convergence_percent,
             "current_median_ms": latency.median * 1000.0,
         }));
 
-        previous_date = current_date;
+        previous = current;
         iteration += 1;
     }
 
-    // Sub-second offset is distance from boundary to next whole second
-    Ok(1.0 - left)
-}
+    let stopped_early = right - left >= SEARCH_RESOLUTION;
+    if stopped_early {
+        progress(serde_json::json!({
+            "phase": SyncPhase::BinarySearch,
+            "stopped_early": true,
+            "iterations": iteration,
+            "interval_width_ms": (right - left) * 1000.0,
+            "target_width_ms": target * 1000.0,
+            "convergence_percent": 100.0,
+            "current_median_ms": latency.median * 1000.0,
+        }));
+    }
+
+    // Sub-second offset is distance from boundary to next whole second.
+    // Cut short, the boundary is anywhere in the interval, so take its middle.
+    let boundary = if stopped_early {
+        (left + right) / 2.0
+    } else {
+        left
+    };
+    let width = (right - left) + (max_rtt - min_rtt) * profile.asymmetry;
+    Ok((1.0 - boundary, width))
+}
 
 // ── Phase 4: Verification ──
 
+#[allow(clippy::too_many_arguments)]
 async fn verify_offset(
     probe: &dyn ServerProbe,
     clock: &dyn Clock,
+    profile: &SyncProfile,
     url: &str,
     offset: f64,
     latency: &LatencyProfile,
     token: &CancellationToken,
-    progress: &ProgressCallback,
+    progress: &ProgressSink<'_>,
 ) -> Result<bool, AppError> {
-    let half_rtt = latency.median / 2.0;
+    let outbound = latency.median * profile.asymmetry;
 
     for shift in &[-0.5_f64, 0.5_f64] {
         check_cancelled(token)?;
@@ -322,38 +1328,44 @@ async fn verify_offset(
         loop {
             check_cancelled(token)?;
 
-            clock.wait_until_fraction(
-                (-offset - half_rtt + shift).rem_euclid(1.0),
-                MIN_INTERVAL_SECS,
-            );
-
-            let predicted = (clock.system_time_secs() + half_rtt + offset) as i64;
-
-            let (actual, rtt) = probe.probe(url).await?;
-
-            if latency.is_in_range(rtt, IQR_MULTIPLIER) {
-                let is_match = predicted == actual;
-
-                progress(serde_json::json!({
-                    "phase": SyncPhase::Verification,
-                    "shift": shift,
-                    "predicted": predicted,
-                    "actual": actual,
-                    "is_match": is_match,
-                    "current_median_ms": latency.median * 1000.0,
-                }));
-
-                if !is_match {
-                    return Ok(false);
+            let fraction = (-offset - outbound + shift).rem_euclid(1.0);
+            let phase = SyncPhase::Verification;
+            let sample = aligned_probe(
+                probe, clock, profile, url, fraction, phase, retries, progress,
+            )
+            .await?;
+
+            match sample {
+                Some(sample) if latency.is_in_range(sample.rtt, IQR_MULTIPLIER) => {
+                    let predicted = (sample.sent_at + outbound + offset) as i64;
+                    let actual = sample.server_second;
+                    let is_match = predicted == actual;
+
+                    progress(serde_json::json!({
+                        "phase": SyncPhase::Verification,
+                        "shift": shift,
+                        "predicted": predicted,
+                        "actual": actual,
+                        "is_match": is_match,
+                        "current_median_ms": latency.median * 1000.0,
+                    }));
+
+                    if !is_match {
+                        return Ok(false);
+                    }
+                    break;
                 }
-                break;
+                Some(sample) => {
+                    reject_outlier(progress, profile, phase, retries, sample.rtt, latency)
+                }
+                None => {}
             }
 
             retries += 1;
-            if retries >= MAX_RETRIES {
-                return Err(AppError::MaxRetriesExceeded(MAX_RETRIES));
+            if retries >= profile.max_retries {
+                return Err(AppError::MaxRetriesExceeded(profile.max_retries));
             }
-            clock.wait(MIN_INTERVAL_SECS);
+            clock.wait(profile.min_interval_secs());
         }
     }
 
@@ -365,39 +1377,188 @@ async fn verify_offset(
 async fn synchronize_with(
     probe: &dyn ServerProbe,
     clock: &dyn Clock,
+    profile: &SyncProfile,
+    server_id: i64,
+    url: &str,
+    token: &CancellationToken,
+    progress: &ProgressSink<'_>,
+) -> Result<SyncResult, SyncFailure> {
+    let start = clock.monotonic_secs();
+    let cancellable = CancellableProbe {
+        inner: probe,
+        token,
+    };
+    let bounded = DeadlineProbe {
+        inner: &cancellable,
+        clock,
+        deadline: start + profile.deadline_secs as f64,
+        budget_secs: profile.deadline_secs,
+    };
+    let counting = CountingProbe::new(&bounded);
+    let mut phase = SyncPhase::LatencyProfiling;
+    let mut phase_started_at = 0;
+    let mut latency = None;
+    let mut partial_offset_ms = None;
+    // (phase, monotonic start, probes sent before it)
+    let mut marks: Vec<(SyncPhase, f64, u32)> = Vec::new();
+
+    // Each rejection also says how many came before it, so a slow sync
+    // shows why it keeps retrying.
+    let rejected = Mutex::new(RejectionCounts::default());
+    let progress = |mut event: serde_json::Value| {
+        if let Some(reason) = event.get("reason").and_then(|r| r.as_str()) {
+            let mut rejected = rejected.lock().unwrap();
+            rejected.count(reason);
+            event["rejected"] = serde_json::json!(*rejected);
+            event["probes_sent"] = counting.sent().into();
+        }
+        progress(event)
+    };
+
+    let outcome = run_phases(
+        &counting,
+        clock,
+        profile,
+        server_id,
+        url,
+        token,
+        &progress,
+        &mut partial_offset_ms,
+        |next, profile| {
+            phase = next;
+            phase_started_at = counting.sent();
+            marks.push((next, clock.monotonic_secs(), phase_started_at));
+            if profile.is_some() {
+                latency = profile.cloned();
+            }
+        },
+    )
+    .await;
+
+    marks.push((SyncPhase::Complete, clock.monotonic_secs(), counting.sent()));
+    let outcome = outcome.map(|mut result| {
+        result.timeline = phase_timeline(&marks);
+        result
+    });
+
+    outcome.map_err(|error| SyncFailure {
+        error,
+        context: FailureContext {
+            phase,
+            phase_attempts: counting.sent() - phase_started_at,
+            total_attempts: counting.sent(),
+            latency_profile: latency,
+            partial_offset_ms,
+            elapsed_ms: ((clock.monotonic_secs() - start) * 1000.0) as u64,
+        },
+    })
+}
+
+/// Per-phase durations and probe counts from consecutive phase marks; the
+/// last mark only closes the phase before it.
+fn phase_timeline(marks: &[(SyncPhase, f64, u32)]) -> Vec<PhaseTiming> {
+    marks
+        .windows(2)
+        .map(|pair| {
+            let (phase, started, sent) = pair[0];
+            let (_, ended, sent_after) = pair[1];
+            PhaseTiming {
+                phase,
+                duration_ms: ((ended - started).max(0.0) * 1000.0) as u64,
+                probes: sent_after - sent,
+            }
+        })
+        .collect()
+}
+
+/// The four phases. The binary search runs before the whole second: it
+/// does not need it, and once the sub-second offset is known the latency
+/// probes usually settle the whole second without probes of their own.
+/// `enter` is told each phase as it starts, along with the latency profile
+/// once there is one; `partial_offset_ms` gets the offset once it is known,
+/// ahead of verification.
+#[allow(clippy::too_many_arguments)]
+async fn run_phases(
+    probe: &CountingProbe<'_>,
+    clock: &dyn Clock,
+    profile: &SyncProfile,
     server_id: i64,
     url: &str,
     token: &CancellationToken,
-    progress: &ProgressCallback,
+    progress: &ProgressSink<'_>,
+    partial_offset_ms: &mut Option<f64>,
+    mut enter: impl FnMut(SyncPhase, Option<&LatencyProfile>),
 ) -> Result<SyncResult, AppError> {
     let start = clock.monotonic_secs();
 
     // Phase 1: Latency Profiling
     check_cancelled(token)?;
-    let latency = measure_latency(probe, clock, url, token, progress).await?;
+    enter(SyncPhase::LatencyProfiling, None);
+    let (latency, samples) = measure_latency(probe, clock, profile, url, token, progress).await?;
 
-    // Phase 2: Whole-Second Offset
+    // Phase 3: Binary Search for Millisecond Offset
     check_cancelled(token)?;
-    let second_offset = find_second_offset(probe, clock, url, &latency, token, progress).await?;
+    enter(SyncPhase::BinarySearch, Some(&latency));
+    let (ms_offset, convergence_width) =
+        find_millisecond_offset(probe, clock, profile, url, &latency, token, progress).await?;
 
-    // Phase 3: Binary Search for Millisecond Offset
+    // Phase 2: Whole-Second Offset, from the latency probes when they agree
     check_cancelled(token)?;
-    let ms_offset = find_millisecond_offset(probe, clock, url, &latency, token, progress).await?;
+    enter(SyncPhase::WholeSecondOffset, None);
+    let from_samples = whole_second_from_samples(
+        &samples,
+        &latency,
+        profile.asymmetry,
+        ms_offset,
+        convergence_width,
+    );
+    let second_offset = match from_samples {
+        Some(offset) => {
+            progress(serde_json::json!({
+                "phase": SyncPhase::WholeSecondOffset,
+                "source": "latency_profiling",
+                "offset_seconds": offset,
+                "clock_issue": clock_issue::classify(offset as f64 * 1000.0),
+                "current_median_ms": latency.median * 1000.0,
+            }));
+            offset
+        }
+        None => find_second_offset(probe, clock, profile, url, &latency, token, progress).await?,
+    };
 
     let total_offset = second_offset as f64 + ms_offset;
     let total_offset_ms = total_offset * 1000.0;
+    *partial_offset_ms = Some(total_offset_ms);
 
     // Phase 4: Verification
     check_cancelled(token)?;
-    let verified =
-        verify_offset(probe, clock, url, total_offset, &latency, token, progress).await?;
+    enter(SyncPhase::Verification, None);
+    let verified = verify_offset(
+        probe,
+        clock,
+        profile,
+        url,
+        total_offset,
+        &latency,
+        token,
+        progress,
+    )
+    .await?;
 
     let duration_ms = ((clock.monotonic_secs() - start) * 1000.0) as u64;
+    let grade = sync_grade::grade(&GradeInputs {
+        jitter_ms: latency.iqr() * 1000.0,
+        retries: probe.failed(),
+        convergence_width_ms: convergence_width * 1000.0,
+        verified,
+    });
 
     progress(serde_json::json!({
         "phase": SyncPhase::Complete,
         "total_offset_ms": total_offset_ms,
+        "clock_issue": clock_issue::classify(total_offset_ms),
         "verified": verified,
+        "grade": grade,
         "duration_ms": duration_ms,
     }));
 
@@ -415,139 +1576,509 @@ async fn synchronize_with(
         } else {
             SyncPhase::Verification
         },
+        fingerprint: None,
+        response: None,
+        id: 0,
+        timeline: Vec::new(),
+        note: None,
+        asymmetry: (profile.asymmetry != asymmetry::SYMMETRIC).then_some(profile.asymmetry),
+        anomalous: false,
+        clock_issue: clock_issue::classify(total_offset_ms),
+        network: None,
+        grade: Some(grade),
+        precision_ms: Some(convergence_width * 1000.0),
     })
 }
 
-// ── Public API (unchanged signature) ──
+// ── Alternative estimator: interval intersection ──
 
-pub async fn synchronize(
+pub const FOUR_PHASE: &str = "four_phase";
+pub const INTERVAL_INTERSECTION: &str = "interval_intersection";
+
+#[derive(Debug, Clone)]
+struct IntervalEstimate {
+    offset: f64,
+    uncertainty: f64,
+}
+
+/// Each probe pins the offset to a one-second window: the server stamped
+/// `date` somewhere in `[date, date + 1)`, at our local midpoint. Probes
+/// sent at evenly spread fractions of a second shift that window, and the
+/// region most windows agree on (Marzullo's algorithm, so a jittery probe
+/// cannot empty it) brackets the offset.
+async fn estimate_by_intervals(
+    probe: &dyn ServerProbe,
+    clock: &dyn Clock,
+    profile: &SyncProfile,
+    url: &str,
+    token: &CancellationToken,
+) -> Result<IntervalEstimate, AppError> {
+    let probe = CancellableProbe {
+        inner: probe,
+        token,
+    };
+    let samples = profile.probe_count * 2;
+    let mut windows = Vec::with_capacity(samples);
+    let mut failures = 0u32;
+
+    while windows.len() < samples {
+        check_cancelled(token)?;
+        let fraction = windows.len() as f64 / samples as f64;
+        clock.wait_until_fraction(fraction, profile.min_interval_secs());
+
+        let sent_at = clock.system_time_secs();
+        match probe.probe(url).await {
+            Ok((date, rtt)) => {
+                let stamped_at = sent_at + rtt * profile.asymmetry;
+                windows.push((date as f64 - stamped_at, date as f64 + 1.0 - stamped_at));
+            }
+            Err(e) if is_transient(&e) || matches!(e, AppError::CachedResponse(_)) => {
+                failures += 1;
+                if failures >= profile.max_retries {
+                    return Err(AppError::MaxRetriesExceeded(profile.max_retries));
+                }
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    let (low, high) = best_overlap(&windows);
+    Ok(IntervalEstimate {
+        offset: (low + high) / 2.0,
+        uncertainty: (high - low) / 2.0,
+    })
+}
+
+/// The stretch covered by the most windows (first one on ties).
+fn best_overlap(windows: &[(f64, f64)]) -> (f64, f64) {
+    // Starts sort before ends at the same point so touching windows overlap.
+    let mut edges: Vec<(f64, i32)> = windows
+        .iter()
+        .flat_map(|&(low, high)| [(low, -1), (high, 1)])
+        .collect();
+    edges.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let (mut depth, mut best) = (0, 0);
+    let mut region = (0.0, 0.0);
+    for (i, &(at, kind)) in edges.iter().enumerate() {
+        depth -= kind;
+        if depth > best {
+            best = depth;
+            region = (at, edges[i + 1].0);
+        }
+    }
+    region
+}
+
+// ── Benchmark ──
+
+async fn benchmark_with(
+    probe: &dyn ServerProbe,
+    clock: &dyn Clock,
+    profile: &SyncProfile,
     server_id: i64,
     url: &str,
-    extractor: &dyn TimeExtractor,
-    token: CancellationToken,
-    progress: ProgressCallback,
-) -> Result<SyncResult, AppError> {
-    // Validate URL
-    reqwest::Url::parse(url).map_err(|e| AppError::InvalidUrl(e.to_string()))?;
+    token: &CancellationToken,
+) -> Result<BenchmarkReport, AppError> {
+    let noop: ProgressCallback = Box::new(|_| {});
 
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(10))
-        .build()
-        .map_err(AppError::Http)?;
+    let counting = CountingProbe::new(probe);
+    let start = clock.monotonic_secs();
+    let four_phase =
+        synchronize_with(&counting, clock, profile, server_id, url, token, &noop).await;
+    let four_phase = match four_phase {
+        Err(failure) if matches!(failure.error, AppError::Cancelled) => return Err(failure.error),
+        outcome => BenchmarkRun {
+            method: FOUR_PHASE.to_string(),
+            offset_ms: outcome.as_ref().ok().map(|r| r.total_offset_ms),
+            // Jitter, not the 1 ms search resolution, bounds its accuracy.
+            uncertainty_ms: outcome
+                .as_ref()
+                .ok()
+                .map(|r| r.latency_profile.iqr() / 2.0 * 1000.0),
+            probes: counting.sent(),
+            duration_ms: ((clock.monotonic_secs() - start) * 1000.0) as u64,
+            error: outcome.err().map(|f| f.error.to_string()),
+        },
+    };
 
-    let clock = RealClock::new();
-    let real_probe = RealServerProbe {
-        client: &client,
-        extractor,
+    let counting = CountingProbe::new(probe);
+    let start = clock.monotonic_secs();
+    let intervals = match estimate_by_intervals(&counting, clock, profile, url, token).await {
+        Err(AppError::Cancelled) => return Err(AppError::Cancelled),
+        outcome => BenchmarkRun {
+            method: INTERVAL_INTERSECTION.to_string(),
+            offset_ms: outcome.as_ref().ok().map(|e| e.offset * 1000.0),
+            uncertainty_ms: outcome.as_ref().ok().map(|e| e.uncertainty * 1000.0),
+            probes: counting.sent(),
+            duration_ms: ((clock.monotonic_secs() - start) * 1000.0) as u64,
+            error: outcome.err().map(|e| e.to_string()),
+        },
     };
 
-    synchronize_with(&real_probe, &clock, server_id, url, &token, &progress).await
+    let difference_ms = four_phase
+        .offset_ms
+        .zip(intervals.offset_ms)
+        .map(|(a, b)| (a - b).abs());
+    let runs = vec![four_phase, intervals];
+    let recommended = runs
+        .iter()
+        .filter_map(|r| r.uncertainty_ms.map(|u| (u, &r.method)))
+        .min_by(|a, b| a.0.partial_cmp(&b.0).unwrap())
+        .map(|(_, method)| method.clone());
+
+    Ok(BenchmarkReport {
+        id: 0,
+        server_id,
+        created_at: Utc::now(),
+        runs,
+        difference_ms,
+        recommended,
+    })
 }
 
-// ── Tests ──
+// ── Quick connection test ──
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::collections::VecDeque;
-    use std::sync::Mutex;
+async fn test_connection_with(
+    probe: &dyn ServerProbe,
+    clock: &dyn Clock,
+    profile: &SyncProfile,
+    url: &str,
+) -> Result<ConnectionTest, AppError> {
+    let mut rtts = Vec::with_capacity(CONNECTION_TEST_PROBES);
+    let mut server_timestamp = 0;
+    for i in 0..CONNECTION_TEST_PROBES {
+        clock.wait_for_send();
+        let (timestamp, rtt) = probe.probe(url).await?;
+        rtts.push(rtt);
+        server_timestamp = timestamp;
+        if i < CONNECTION_TEST_PROBES - 1 {
+            clock.wait(profile.min_interval_secs());
+        }
+    }
 
-    // ── Simulated Clock ──
+    rtts.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    Ok(ConnectionTest {
+        probes: rtts.len(),
+        min_rtt_ms: rtts[0] * 1000.0,
+        median_rtt_ms: rtts[rtts.len() / 2] * 1000.0,
+        server_timestamp,
+        tested_at: Utc::now(),
+    })
+}
 
-    /// A deterministic clock that advances only when explicitly told to.
-    /// No real time passes — all waits are instantaneous advances of
-    /// the internal counters.
-    struct SimulatedClock {
-        wall_time: Mutex<f64>,
-        monotonic: Mutex<f64>,
-    }
+// ── Instant estimate ──
 
-    impl SimulatedClock {
-        fn new(initial_wall_time: f64) -> Self {
-            Self {
-                wall_time: Mutex::new(initial_wall_time),
-                monotonic: Mutex::new(0.0),
-            }
-        }
+/// One probe, no retries. The server's second is assumed to be half
+/// elapsed when it answered, `profile.asymmetry` of the way through the
+/// round trip.
+async fn estimate_offset_with(
+    probe: &dyn ServerProbe,
+    clock: &dyn Clock,
+    profile: &SyncProfile,
+    server_id: i64,
+    url: &str,
+) -> Result<OffsetEstimate, AppError> {
+    clock.wait_for_send();
+    let sent_at = clock.system_time_secs();
+    let (date, rtt) = probe.probe(url).await?;
+    let stamped_at = sent_at + rtt * profile.asymmetry;
+    Ok(OffsetEstimate {
+        server_id,
+        offset_ms: (date as f64 + 0.5 - stamped_at) * 1000.0,
+        uncertainty_ms: (rtt / 2.0 + 0.5) * 1000.0,
+        rtt_ms: rtt * 1000.0,
+        verified: false,
+        estimated_at: Utc::now(),
+    })
+}
+
+// ── Pre-target recheck ──
+
+/// How far either side of the predicted second boundary the aligned
+/// recheck probes land, as a share of the round trip, within bounds.
+const RECHECK_MARGIN_RATIO: f64 = 0.25;
+const RECHECK_MARGIN_SECS: std::ops::RangeInclusive<f64> = 0.01..=0.1;
+
+/// Extra probes to narrow the region down once the stored offset is found
+/// to have moved; each halves it.
+const RECHECK_MAX_BISECTIONS: u32 = 4;
+
+/// Three probes against a stored `offset` (seconds): one plain, then one
+/// landing just before and one just after the second boundary the offset
+/// predicts. Each pins the offset to a one-second window like the interval
+/// estimator. When the stored offset falls outside their overlap, probes
+/// aimed at the middle of the overlap halve it, and the offset moves to the
+/// nearest edge: the smallest correction the probes support. No probe is
+/// sent that could not finish by `deadline` (local Unix seconds).
+async fn recheck_with(
+    probe: &dyn ServerProbe,
+    clock: &dyn Clock,
+    profile: &SyncProfile,
+    url: &str,
+    offset: f64,
+    deadline: f64,
+) -> Result<OffsetRecheck, AppError> {
+    let profile = SyncProfile {
+        burst_size: 1,
+        ..*profile
+    };
+    let window = |second: i64, sent_at: f64, rtt: f64| {
+        let stamped_at = sent_at + rtt * profile.asymmetry;
+        (second as f64 - stamped_at, second as f64 + 1.0 - stamped_at)
+    };
 
-        fn advance(&self, seconds: f64) {
-            *self.wall_time.lock().unwrap() += seconds;
-            *self.monotonic.lock().unwrap() += seconds;
+    let out_of_time = |budget: f64| clock.system_time_secs() + budget >= deadline;
+    if out_of_time(profile.min_interval_secs()) {
+        return Err(AppError::RecheckTooLate);
+    }
+    clock.wait_for_send();
+    let sent_at = clock.system_time_secs();
+    let (date, rtt) = probe.probe(url).await?;
+    let mut windows = vec![window(date, sent_at, rtt)];
+
+    let margin = (rtt * RECHECK_MARGIN_RATIO)
+        .clamp(*RECHECK_MARGIN_SECS.start(), *RECHECK_MARGIN_SECS.end());
+    // One aligned probe takes up to a second plus the probe spacing.
+    let probe_budget = 1.0 + profile.min_interval_secs() + rtt;
+    let quiet: ProgressCallback = Box::new(|_| {});
+    for shift in [-margin, margin] {
+        if out_of_time(probe_budget) {
+            break;
+        }
+        let fraction = (-offset - rtt * profile.asymmetry + shift).rem_euclid(1.0);
+        let phase = SyncPhase::Verification;
+        let sample = aligned_probe(probe, clock, &profile, url, fraction, phase, 0, &quiet).await?;
+        if let Some(s) = sample {
+            windows.push(window(s.server_second, s.sent_at, s.rtt));
         }
     }
 
-    impl Clock for SimulatedClock {
-        fn system_time_secs(&self) -> f64 {
-            *self.wall_time.lock().unwrap()
+    let (mut low, mut high) = best_overlap(&windows);
+    let mut bisections = 0;
+    while !(low..=high).contains(&offset)
+        && high - low > 2.0 * margin
+        && bisections < RECHECK_MAX_BISECTIONS
+        && !out_of_time(probe_budget)
+    {
+        bisections += 1;
+        let fraction = (-(low + high) / 2.0 - rtt * profile.asymmetry).rem_euclid(1.0);
+        let phase = SyncPhase::Verification;
+        let sample = aligned_probe(probe, clock, &profile, url, fraction, phase, 0, &quiet).await?;
+        if let Some(s) = sample {
+            windows.push(window(s.server_second, s.sent_at, s.rtt));
+            (low, high) = best_overlap(&windows);
         }
+    }
 
-        fn monotonic_secs(&self) -> f64 {
-            *self.monotonic.lock().unwrap()
-        }
+    let checked = offset.clamp(low, high);
+    Ok(OffsetRecheck {
+        offset_ms: checked * 1000.0,
+        moved_ms: (checked - offset) * 1000.0,
+        uncertainty_ms: (high - low) / 2.0 * 1000.0,
+        probes: windows.len() as u32,
+        checked_at: Utc::now(),
+    })
+}
 
-        fn wait(&self, seconds: f64) {
-            if seconds > 0.0 {
-                self.advance(seconds);
-            }
+fn deadline_in_secs(deadline: DateTime<Utc>) -> f64 {
+    (deadline - Utc::now()).num_milliseconds() as f64 / 1000.0
+}
+
+// ── Public API (unchanged signature) ──
+//
+// `demo://` URLs run against the simulator instead of the network.
+
+pub async fn synchronize(
+    server_id: i64,
+    url: &str,
+    settings: &ProbeSettings<'_>,
+    extractor: &dyn TimeExtractor,
+    profile: &SyncProfile,
+    token: CancellationToken,
+    progress: ProgressCallback,
+) -> Result<SyncResult, SyncFailure> {
+    let recorder = settings.recorder.as_deref();
+    if let Some(demo) = DemoServer::from_url(url) {
+        let (clock, server) = demo.map_err(SyncFailure::before_start)?.build();
+        let probe = RecordingProbe {
+            inner: &server,
+            clock: &*clock,
+            recorder,
+        };
+        if let Some(recorder) = recorder {
+            recorder.begin(clock.system_time_secs());
         }
+        return synchronize_with(&probe, &*clock, profile, server_id, url, &token, &progress).await;
     }
 
-    // ── Simulated Server ──
+    // Validate URL
+    reqwest::Url::parse(url)
+        .map_err(|e| SyncFailure::before_start(AppError::InvalidUrl(e.to_string())))?;
+
+    let real_clock = RealClock::new(token.clone());
+    let clock = PacedClock::new(&real_clock, settings, url, profile);
+    let real_probe =
+        RealServerProbe::new(profile, settings, extractor).map_err(SyncFailure::before_start)?;
+
+    let warm_up = real_probe.warm_up(url).await;
+    if let Some(error) = interception::check(url, &warm_up, settings.portal_check).await {
+        return Err(SyncFailure::before_start(error));
+    }
+    let probe = RecordingProbe {
+        inner: &real_probe,
+        clock: &clock,
+        recorder,
+    };
+    if let Some(recorder) = recorder {
+        recorder.begin(clock.system_time_secs());
+    }
+    let mut result =
+        synchronize_with(&probe, &clock, profile, server_id, url, &token, &progress).await?;
+    result.response = real_probe.take_response();
+    result.fingerprint = real_probe.fingerprint.into_inner().unwrap();
+    Ok(result)
+}
 
-    /// Simulates a remote server with a configurable time offset and
-    /// a predetermined sequence of RTT values. Each call to `probe`
-    /// pops the next RTT, advances the shared clock by that amount,
-    /// and computes the server timestamp based on the offset.
-    struct SimulatedServer {
-        clock: std::sync::Arc<SimulatedClock>,
-        /// server_time = client_send_time + rtt/2 + server_offset
-        server_offset: f64,
-        /// Pre-loaded RTT values consumed in FIFO order.
-        rtt_sequence: Mutex<VecDeque<f64>>,
+/// Feed a recorded sync back through the engine on a simulated clock.
+/// Nothing is stored; the report puts the replayed outcome beside the
+/// recorded one.
+pub async fn replay(recording: &SyncRecording) -> ReplayReport {
+    let (clock, server) = ReplayServer::new(recording.started_at, &recording.probes);
+    let progress: ProgressCallback = Box::new(|_| {});
+    let outcome = synchronize_with(
+        &server,
+        &*clock,
+        &recording.profile.to_profile(),
+        recording.server_id,
+        &recording.url,
+        &CancellationToken::new(),
+        &progress,
+    )
+    .await;
+    ReplayReport {
+        recorded_offset_ms: recording.offset_ms,
+        recorded_error: recording.error.clone(),
+        error: outcome.as_ref().err().map(|f| f.error.to_string()),
+        result: outcome.ok(),
+        unused_probes: server.remaining(),
     }
+}
 
-    impl SimulatedServer {
-        fn new(clock: std::sync::Arc<SimulatedClock>, server_offset: f64, rtts: Vec<f64>) -> Self {
-            Self {
-                clock,
-                server_offset,
-                rtt_sequence: Mutex::new(rtts.into()),
-            }
-        }
+/// One throwaway request on `settings.client` so its connection and TLS
+/// session stay open until the next probe. Failures are ignored like a
+/// sync's warm-up; the next real probe reports them.
+pub async fn keep_alive(
+    url: &str,
+    settings: &ProbeSettings<'_>,
+    profile: &SyncProfile,
+) -> Result<(), AppError> {
+    let probe = RealServerProbe::new(profile, settings, &DateHeaderExtractor)?;
+    let _ = probe.warm_up(url).await;
+    Ok(())
+}
 
-        fn remaining_rtts(&self) -> usize {
-            self.rtt_sequence.lock().unwrap().len()
-        }
+/// Run the four-phase sync and the interval estimator back-to-back. Nothing
+/// is written to history; the caller stores the report.
+pub async fn benchmark(
+    server_id: i64,
+    url: &str,
+    settings: &ProbeSettings<'_>,
+    extractor: &dyn TimeExtractor,
+    profile: &SyncProfile,
+    token: CancellationToken,
+) -> Result<BenchmarkReport, AppError> {
+    if let Some(demo) = DemoServer::from_url(url) {
+        let (clock, server) = demo?.build();
+        return benchmark_with(&server, &*clock, profile, server_id, url, &token).await;
     }
+    let probe = RealServerProbe::new(profile, settings, extractor)?;
+    let _ = probe.warm_up(url).await;
+    let real_clock = RealClock::new(token.clone());
+    let clock = PacedClock::new(&real_clock, settings, url, profile);
+    benchmark_with(&probe, &clock, profile, server_id, url, &token).await
+}
 
-    impl ServerProbe for SimulatedServer {
-        fn probe<'a>(
-            &'a self,
-            _url: &'a str,
-        ) -> Pin<Box<dyn Future<Output = Result<(i64, f64), AppError>> + Send + 'a>> {
-            Box::pin(async move {
-                let rtt = self
-                    .rtt_sequence
-                    .lock()
-                    .unwrap()
-                    .pop_front()
-                    .expect("SimulatedServer: ran out of pre-loaded RTT values");
-                assert!(rtt >= 0.0, "RTT must be non-negative, got {rtt}");
-
-                // Record send time (before network travel)
-                let send_time = self.clock.system_time_secs();
-
-                // Simulate full round-trip (clock advances by RTT)
-                self.clock.advance(rtt);
-
-                // Server processes at the midpoint of the round-trip
-                let server_process_time = send_time + rtt / 2.0 + self.server_offset;
-                let server_timestamp = server_process_time.floor() as i64;
-
-                Ok((server_timestamp, rtt))
-            })
-        }
+/// A rough offset from a single probe, for the last seconds before an
+/// on-sale when a full sync would not finish in time.
+pub async fn estimate_offset(
+    server_id: i64,
+    url: &str,
+    settings: &ProbeSettings<'_>,
+    extractor: &dyn TimeExtractor,
+    profile: &SyncProfile,
+) -> Result<OffsetEstimate, AppError> {
+    if let Some(demo) = DemoServer::from_url(url) {
+        let (clock, server) = demo?.build();
+        return estimate_offset_with(&server, &*clock, profile, server_id, url).await;
+    }
+    let probe = RealServerProbe::new(profile, settings, extractor)?;
+    let _ = probe.warm_up(url).await;
+    let real_clock = RealClock::new(CancellationToken::new());
+    estimate_offset_with(
+        &probe,
+        &PacedClock::new(&real_clock, settings, url, profile),
+        profile,
+        server_id,
+        url,
+    )
+    .await
+}
+
+/// Check `offset_ms` with three probes, plus a few more to narrow down a
+/// moved offset if they can finish by `deadline` (local time). Nothing is
+/// saved.
+pub async fn recheck_offset(
+    url: &str,
+    settings: &ProbeSettings<'_>,
+    extractor: &dyn TimeExtractor,
+    profile: &SyncProfile,
+    offset_ms: f64,
+    deadline: DateTime<Utc>,
+) -> Result<OffsetRecheck, AppError> {
+    let offset = offset_ms / 1000.0;
+    if let Some(demo) = DemoServer::from_url(url) {
+        let (clock, server) = demo?.build();
+        let deadline = clock.system_time_secs() + deadline_in_secs(deadline);
+        return recheck_with(&server, &*clock, profile, url, offset, deadline).await;
+    }
+    let probe = RealServerProbe::new(profile, settings, extractor)?;
+    let real_clock = RealClock::new(CancellationToken::new());
+    let clock = PacedClock::new(&real_clock, settings, url, profile);
+    let deadline = clock.system_time_secs() + deadline_in_secs(deadline);
+    recheck_with(&probe, &clock, profile, url, offset, deadline).await
+}
+
+/// A few probes to check a server is answering — no sync, nothing saved.
+pub async fn test_connection(
+    url: &str,
+    settings: &ProbeSettings<'_>,
+    extractor: &dyn TimeExtractor,
+    profile: &SyncProfile,
+) -> Result<ConnectionTest, AppError> {
+    if let Some(demo) = DemoServer::from_url(url) {
+        let (clock, server) = demo?.build();
+        return test_connection_with(&server, &*clock, profile, url).await;
     }
+    let probe = RealServerProbe::new(profile, settings, extractor)?;
+    let real_clock = RealClock::new(CancellationToken::new());
+    test_connection_with(
+        &probe,
+        &PacedClock::new(&real_clock, settings, url, profile),
+        profile,
+        url,
+    )
+    .await
+}
+
+// ── Tests ──
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::{SimulatedClock, SimulatedServer, CACHED, RESET};
+    use crate::sync_profile::{BALANCED, NOISY, POLITE};
+    use std::sync::Mutex;
 
     // ── Helpers ──
 
@@ -578,6 +2109,7 @@ mod tests {
             mean: 0.050,
             q3: 0.055,
             max: 0.060,
+            breakdown: None,
         };
         assert!((profile.iqr() - 0.010).abs() < 1e-10);
     }
@@ -591,6 +2123,7 @@ mod tests {
             mean: 0.050,
             q3: 0.055,
             max: 0.060,
+            breakdown: None,
         };
         // IQR = 0.010, multiplier = 1.5
         // lower = 0.045 - 0.015 = 0.030
@@ -656,15 +2189,17 @@ mod tests {
         let server = SimulatedServer::new(clock.clone(), 0.0, rtts);
         let token = CancellationToken::new();
 
-        let profile = measure_latency(
+        let (profile, samples) = measure_latency(
             &server,
             clock.as_ref(),
+            &BALANCED,
             "http://test",
             &token,
             &noop_progress(),
         )
         .await
         .unwrap();
+        assert_eq!(samples.len(), 10);
 
         // Sorted RTTs: [0.048, 0.048, 0.049, 0.049, 0.050, 0.050, 0.051, 0.051, 0.052, 0.052]
         assert!(profile.min <= profile.q1);
@@ -675,6 +2210,25 @@ mod tests {
         assert!((profile.mean - 0.050).abs() < 1e-10);
     }
 
+    // ── test_connection ──
+
+    #[tokio::test]
+    async fn test_connection_reports_min_and_median_rtt() {
+        let clock = std::sync::Arc::new(SimulatedClock::new(1_000_000.0));
+        let server = SimulatedServer::new(clock.clone(), 2.0, vec![0.080, 0.040, 0.060]);
+
+        let result = test_connection_with(&server, clock.as_ref(), &BALANCED, "http://test")
+            .await
+            .unwrap();
+
+        assert_eq!(result.probes, 3);
+        assert!((result.min_rtt_ms - 40.0).abs() < 1e-9);
+        assert!((result.median_rtt_ms - 60.0).abs() < 1e-9);
+        // Last probe: sent at 1_000_001.12, server +2 s ahead
+        assert_eq!(result.server_timestamp, 1_000_003);
+        assert_eq!(server.remaining_rtts(), 0);
+    }
+
     // ── Phase 2: find_second_offset ──
 
     #[tokio::test]
@@ -689,11 +2243,13 @@ mod tests {
             mean: 0.050,
             q3: 0.051,
             max: 0.052,
+            breakdown: None,
         };
 
         let offset = find_second_offset(
             &server,
             clock.as_ref(),
+            &BALANCED,
             "http://test",
             &latency,
             &token,
@@ -717,11 +2273,13 @@ mod tests {
             mean: 0.050,
             q3: 0.051,
             max: 0.052,
+            breakdown: None,
         };
 
         let offset = find_second_offset(
             &server,
             clock.as_ref(),
+            &BALANCED,
             "http://test",
             &latency,
             &token,
@@ -745,11 +2303,13 @@ mod tests {
             mean: 0.050,
             q3: 0.051,
             max: 0.052,
+            breakdown: None,
         };
 
         let offset = find_second_offset(
             &server,
             clock.as_ref(),
+            &BALANCED,
             "http://test",
             &latency,
             &token,
@@ -777,11 +2337,13 @@ mod tests {
             mean: 0.050,
             q3: 0.051,
             max: 0.052,
+            breakdown: None,
         };
 
-        let ms_offset = find_millisecond_offset(
+        let (ms_offset, _) = find_millisecond_offset(
             &server,
             clock.as_ref(),
+            &BALANCED,
             "http://test",
             &latency,
             &token,
@@ -797,6 +2359,95 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_find_millisecond_offset_stops_at_jitter() {
+        let clock = std::sync::Arc::new(SimulatedClock::new(1_000_000.0));
+        // A 40 ms IQR puts the target at 20 ms: the baseline and six
+        // halvings (to 15.6 ms) instead of ten.
+        let server = SimulatedServer::new(clock.clone(), 5.3, vec![0.060; 7]);
+        let token = CancellationToken::new();
+        let latency = LatencyProfile {
+            min: 0.030,
+            q1: 0.040,
+            median: 0.060,
+            mean: 0.060,
+            q3: 0.080,
+            max: 0.100,
+            breakdown: None,
+        };
+        let events = std::sync::Arc::new(Mutex::new(Vec::<serde_json::Value>::new()));
+        let sink = events.clone();
+        let progress: ProgressCallback = Box::new(move |v| sink.lock().unwrap().push(v));
+
+        let (ms_offset, width) = find_millisecond_offset(
+            &server,
+            clock.as_ref(),
+            &BALANCED,
+            "http://test",
+            &latency,
+            &token,
+            &progress,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(server.remaining_rtts(), 0);
+        assert!((width - 0.015625).abs() < 1e-9, "width {width}");
+        assert!(
+            (ms_offset - 0.3).abs() <= width / 2.0,
+            "sub-second offset should be within the window of 0.300, got {ms_offset:.4}"
+        );
+        let events = events.lock().unwrap();
+        let last = events.last().unwrap();
+        assert_eq!(last["stopped_early"], true);
+        assert_eq!(last["iterations"], 6);
+        assert_eq!(last["target_width_ms"], 20.0);
+    }
+
+    #[tokio::test]
+    async fn test_find_millisecond_offset_still_searches_on_a_very_noisy_link() {
+        let clock = std::sync::Arc::new(SimulatedClock::new(1_000_000.0));
+        // A 2.5 s IQR would put the target past the whole second; capped,
+        // the baseline is followed by three halvings.
+        let server = SimulatedServer::new(clock.clone(), 5.3, vec![1.0; 4]);
+        let token = CancellationToken::new();
+        let latency = LatencyProfile {
+            min: 0.2,
+            q1: 0.5,
+            median: 1.0,
+            mean: 1.5,
+            q3: 3.0,
+            max: 4.0,
+            breakdown: None,
+        };
+        let events = std::sync::Arc::new(Mutex::new(Vec::<serde_json::Value>::new()));
+        let sink = events.clone();
+        let progress: ProgressCallback = Box::new(move |v| sink.lock().unwrap().push(v));
+
+        let (ms_offset, width) = find_millisecond_offset(
+            &server,
+            clock.as_ref(),
+            &BALANCED,
+            "http://test",
+            &latency,
+            &token,
+            &progress,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(server.remaining_rtts(), 0);
+        assert!((width - 0.125).abs() < 1e-9, "width {width}");
+        assert!(
+            (ms_offset - 0.3).abs() <= width / 2.0,
+            "sub-second offset should be within the window of 0.300, got {ms_offset:.4}"
+        );
+        let events = events.lock().unwrap();
+        let last = events.last().unwrap();
+        assert_eq!(last["iterations"], 3);
+        assert_eq!(last["target_width_ms"], 250.0);
+    }
+
     #[tokio::test]
     async fn test_find_millisecond_offset_small_subsecond() {
         let clock = std::sync::Arc::new(SimulatedClock::new(1_000_000.0));
@@ -810,11 +2461,13 @@ mod tests {
             mean: 0.050,
             q3: 0.051,
             max: 0.052,
+            breakdown: None,
         };
 
-        let ms_offset = find_millisecond_offset(
+        let (ms_offset, _) = find_millisecond_offset(
             &server,
             clock.as_ref(),
+            &BALANCED,
             "http://test",
             &latency,
             &token,
@@ -842,11 +2495,13 @@ mod tests {
             mean: 0.050,
             q3: 0.051,
             max: 0.052,
+            breakdown: None,
         };
 
-        let ms_offset = find_millisecond_offset(
+        let (ms_offset, _) = find_millisecond_offset(
             &server,
             clock.as_ref(),
+            &BALANCED,
             "http://test",
             &latency,
             &token,
@@ -876,11 +2531,13 @@ mod tests {
             mean: 0.050,
             q3: 0.051,
             max: 0.052,
+            breakdown: None,
         };
 
         let verified = verify_offset(
             &server,
             clock.as_ref(),
+            &BALANCED,
             "http://test",
             5.3,
             &latency,
@@ -906,12 +2563,14 @@ mod tests {
             mean: 0.050,
             q3: 0.051,
             max: 0.052,
+            breakdown: None,
         };
 
         // Deliberately wrong offset (off by 0.5s in the dangerous direction)
         let verified = verify_offset(
             &server,
             clock.as_ref(),
+            &BALANCED,
             "http://test",
             4.8,
             &latency,
@@ -945,6 +2604,7 @@ mod tests {
         let result = synchronize_with(
             &server,
             clock.as_ref(),
+            &BALANCED,
             42,
             "http://test",
             &token,
@@ -966,25 +2626,121 @@ mod tests {
             result.total_offset_ms
         );
         assert!(result.verified, "offset should be verified");
+        assert_eq!(result.grade, Some(sync_grade::SyncGrade::A));
         assert_eq!(result.phase_reached, SyncPhase::Complete);
         assert!(result.duration_ms > 0, "duration should be positive");
+
+        let phases: Vec<SyncPhase> = result.timeline.iter().map(|t| t.phase).collect();
+        assert_eq!(
+            phases,
+            vec![
+                SyncPhase::LatencyProfiling,
+                SyncPhase::BinarySearch,
+                SyncPhase::WholeSecondOffset,
+                SyncPhase::Verification,
+            ]
+        );
+        assert_eq!(result.timeline[0].probes, BALANCED.probe_count as u32);
+        // The latency probes settled the whole second.
+        assert_eq!(result.timeline[2].probes, 0);
+        let total: u64 = result.timeline.iter().map(|t| t.duration_ms).sum();
+        assert!(total <= result.duration_ms + 4);
     }
 
-    #[tokio::test]
-    async fn test_synchronize_negative_offset() {
-        let server_offset = -2.7;
-        let rtt = 0.050;
-        let clock = std::sync::Arc::new(SimulatedClock::new(1_000_000.0));
+    #[test]
+    fn body_reading_extractors_always_get() {
+        assert_eq!(request_method("HEAD", false), reqwest::Method::HEAD);
+        assert_eq!(request_method("get", false), reqwest::Method::GET);
+        assert_eq!(request_method("HEAD", true), reqwest::Method::GET);
+    }
 
-        let mut rtts = generate_rtts(rtt, 0.002, 10);
-        rtts.extend(vec![rtt; 20]);
-        let server = SimulatedServer::new(clock.clone(), server_offset, rtts);
-        let token = CancellationToken::new();
+    #[test]
+    fn probe_spacing_follows_jitter() {
+        // Too few samples: the profile's interval.
+        assert_eq!(probe_spacing(&BALANCED, &[0.050, 0.051]), 0.5);
+
+        let clean = [0.050, 0.050, 0.051, 0.050, 0.050];
+        assert_eq!(
+            probe_spacing(&BALANCED, &clean),
+            BALANCED.min_spacing_secs()
+        );
 
-        let result = synchronize_with(
-            &server,
-            clock.as_ref(),
-            42,
+        let congested = [0.050, 0.300, 0.060, 0.400, 0.090];
+        assert_eq!(
+            probe_spacing(&BALANCED, &congested),
+            0.5 * MAX_SPACING_FACTOR
+        );
+
+        // Rate-limited profiles never speed up.
+        assert_eq!(probe_spacing(&POLITE, &clean), POLITE.min_interval_secs());
+
+        // A spacing floor above the cap gives way instead of panicking.
+        let lopsided = SyncProfile {
+            min_interval_ms: 10,
+            min_spacing_ms: 1_000,
+            ..BALANCED
+        };
+        assert_eq!(probe_spacing(&lopsided, &clean), 0.01 * MAX_SPACING_FACTOR);
+    }
+
+    #[tokio::test]
+    async fn test_synchronize_corrects_path_asymmetry() {
+        // 80 ms of a 100 ms round trip is spent reaching the server.
+        let run = |asymmetry: f64| async move {
+            let clock = std::sync::Arc::new(SimulatedClock::new(1_000_000.0));
+            let mut rtts = generate_rtts(0.100, 0.002, 10);
+            rtts.extend(vec![0.100; 20]);
+            let server = SimulatedServer::new(clock.clone(), 5.3, rtts).with_asymmetry(0.8);
+            let profile = SyncProfile {
+                asymmetry,
+                ..BALANCED
+            };
+            synchronize_with(
+                &server,
+                clock.as_ref(),
+                &profile,
+                1,
+                "http://test",
+                &CancellationToken::new(),
+                &noop_progress(),
+            )
+            .await
+            .unwrap()
+        };
+
+        let symmetric = run(asymmetry::SYMMETRIC).await;
+        assert!(
+            (symmetric.total_offset_ms - 5330.0).abs() < 3.0,
+            "symmetric sync should be 30 ms off, got {:.2}ms",
+            symmetric.total_offset_ms
+        );
+        assert_eq!(symmetric.asymmetry, None);
+
+        let corrected = run(0.8).await;
+        assert!(
+            (corrected.total_offset_ms - 5300.0).abs() < 3.0,
+            "corrected offset should be ~5300ms, got {:.2}ms",
+            corrected.total_offset_ms
+        );
+        assert_eq!(corrected.asymmetry, Some(0.8));
+    }
+
+    #[tokio::test]
+    async fn test_synchronize_negative_offset() {
+        let server_offset = -2.7;
+        let rtt = 0.050;
+        let clock = std::sync::Arc::new(SimulatedClock::new(1_000_000.0));
+
+        let mut rtts = generate_rtts(rtt, 0.002, 10);
+        rtts.extend(vec![rtt; 20]);
+        let server = SimulatedServer::new(clock.clone(), server_offset, rtts);
+        let token = CancellationToken::new();
+
+        let result = synchronize_with(
+            &server,
+            clock.as_ref(),
+            &BALANCED,
+            42,
             "http://test",
             &token,
             &noop_progress(),
@@ -1025,6 +2781,7 @@ mod tests {
         let result = synchronize_with(
             &server,
             clock.as_ref(),
+            &BALANCED,
             42,
             "http://test",
             &token,
@@ -1056,6 +2813,7 @@ mod tests {
         let result = synchronize_with(
             &server,
             clock.as_ref(),
+            &BALANCED,
             42,
             "http://test",
             &token,
@@ -1089,6 +2847,7 @@ mod tests {
         let result = synchronize_with(
             &server,
             clock.as_ref(),
+            &BALANCED,
             42,
             "http://test",
             &token,
@@ -1120,6 +2879,7 @@ mod tests {
         let result = synchronize_with(
             &server,
             clock.as_ref(),
+            &BALANCED,
             42,
             "http://test",
             &token,
@@ -1161,6 +2921,7 @@ mod tests {
         synchronize_with(
             &server,
             clock.as_ref(),
+            &BALANCED,
             42,
             "http://test",
             &token,
@@ -1209,6 +2970,7 @@ mod tests {
         let result = synchronize_with(
             &server,
             clock.as_ref(),
+            &BALANCED,
             42,
             "http://test",
             &token,
@@ -1240,6 +3002,7 @@ mod tests {
         let result = synchronize_with(
             &server,
             clock.as_ref(),
+            &BALANCED,
             42,
             "http://test",
             &token,
@@ -1271,6 +3034,7 @@ mod tests {
         let result = synchronize_with(
             &server,
             clock.as_ref(),
+            &BALANCED,
             42,
             "http://test",
             &token,
@@ -1309,6 +3073,7 @@ mod tests {
         let result = synchronize_with(
             &server,
             clock.as_ref(),
+            &BALANCED,
             42,
             "http://test",
             &token,
@@ -1316,7 +3081,13 @@ mod tests {
         )
         .await;
 
-        assert!(matches!(result, Err(AppError::Cancelled)));
+        assert!(matches!(
+            result,
+            Err(SyncFailure {
+                error: AppError::Cancelled,
+                ..
+            })
+        ));
     }
 
     #[tokio::test]
@@ -1343,6 +3114,7 @@ mod tests {
         let result = synchronize_with(
             &server,
             clock.as_ref(),
+            &BALANCED,
             42,
             "http://test",
             &token,
@@ -1350,7 +3122,10 @@ mod tests {
         )
         .await;
 
-        assert!(matches!(result, Err(AppError::Cancelled)));
+        let failure = result.unwrap_err();
+        assert!(matches!(failure.error, AppError::Cancelled));
+        assert_eq!(failure.context.phase, SyncPhase::LatencyProfiling);
+        assert!(failure.context.latency_profile.is_none());
     }
 
     // ── Retry exhaustion & outlier rejection ──
@@ -1369,15 +3144,24 @@ mod tests {
             mean: 0.050,
             q3: 0.051,
             max: 0.052,
+            breakdown: None,
         };
+        let rejections = std::sync::Arc::new(Mutex::new(Vec::new()));
+        let seen = rejections.clone();
+        let progress: ProgressCallback = Box::new(move |data| {
+            if data.get("reason").is_some() {
+                seen.lock().unwrap().push(data);
+            }
+        });
 
         let offset = find_second_offset(
             &server,
             clock.as_ref(),
+            &BALANCED,
             "http://test",
             &latency,
             &token,
-            &noop_progress(),
+            &progress,
         )
         .await
         .unwrap();
@@ -1385,13 +3169,89 @@ mod tests {
         assert_eq!(offset, 5);
         // All 3 RTTs consumed: 2 outliers + 1 good
         assert_eq!(server.remaining_rtts(), 0);
+        let rejections = rejections.lock().unwrap();
+        assert_eq!(rejections.len(), 2);
+        assert_eq!(rejections[1]["reason"], "outlier_rtt");
+        assert_eq!(rejections[1]["retry"], 2);
+        assert_eq!(rejections[1]["rtt_ms"], 200.0);
+    }
+
+    #[tokio::test]
+    async fn test_find_second_offset_burst_keeps_fastest_probe() {
+        let clock = std::sync::Arc::new(SimulatedClock::new(1_000_000.0));
+        // One burst: only the middle probe is inside the profile.
+        let rtts = vec![0.200, 0.050, 0.200];
+        let server = SimulatedServer::new(clock.clone(), 5.3, rtts);
+        let token = CancellationToken::new();
+        let latency = LatencyProfile {
+            min: 0.048,
+            q1: 0.049,
+            median: 0.050,
+            mean: 0.050,
+            q3: 0.051,
+            max: 0.052,
+            breakdown: None,
+        };
+        let events = std::sync::Arc::new(Mutex::new(Vec::<serde_json::Value>::new()));
+        let sink = events.clone();
+        let progress: ProgressCallback = Box::new(move |v| sink.lock().unwrap().push(v));
+
+        let offset = find_second_offset(
+            &server,
+            clock.as_ref(),
+            &NOISY,
+            "http://test",
+            &latency,
+            &token,
+            &progress,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(offset, 5);
+        assert_eq!(server.remaining_rtts(), 0);
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0]["attempt"], 0, "the burst is a single attempt");
+    }
+
+    #[tokio::test]
+    async fn test_synchronize_noisy_profile_rides_out_jitter() {
+        let clock = std::sync::Arc::new(SimulatedClock::new(1_000_000.0));
+        // After profiling, two of every three probes are far too slow.
+        let mut rtts = generate_rtts(0.050, 0.002, NOISY.probe_count);
+        for _ in 0..100 {
+            rtts.extend([0.250, 0.050, 0.300]);
+        }
+        let server = SimulatedServer::new(clock.clone(), 5.3, rtts);
+        let token = CancellationToken::new();
+
+        let result = synchronize_with(
+            &server,
+            clock.as_ref(),
+            &NOISY,
+            1,
+            "http://test",
+            &token,
+            &noop_progress(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result.whole_second_offset, 5);
+        assert!(
+            (result.total_offset_ms - 5300.0).abs() < 2.0,
+            "total offset should be ~5300ms, got {:.2}ms",
+            result.total_offset_ms
+        );
+        assert!(result.verified);
     }
 
     #[tokio::test]
     async fn test_find_second_offset_max_retries_exceeded() {
         let clock = std::sync::Arc::new(SimulatedClock::new(1_000_000.0));
-        // All probes are outliers — should exhaust MAX_RETRIES
-        let rtts = vec![0.200; MAX_RETRIES as usize];
+        // All probes are outliers — should exhaust the profile's retries
+        let rtts = vec![0.200; BALANCED.max_retries as usize];
         let server = SimulatedServer::new(clock.clone(), 5.3, rtts);
         let token = CancellationToken::new();
         let latency = LatencyProfile {
@@ -1401,11 +3261,13 @@ mod tests {
             mean: 0.050,
             q3: 0.051,
             max: 0.052,
+            breakdown: None,
         };
 
         let result = find_second_offset(
             &server,
             clock.as_ref(),
+            &BALANCED,
             "http://test",
             &latency,
             &token,
@@ -1415,7 +3277,720 @@ mod tests {
 
         assert!(
             matches!(result, Err(AppError::MaxRetriesExceeded(10))),
-            "should return MaxRetriesExceeded after {MAX_RETRIES} outlier RTTs"
+            "should return MaxRetriesExceeded after {} outlier RTTs",
+            BALANCED.max_retries
+        );
+    }
+
+    // ── Transient transport errors ──
+
+    #[tokio::test]
+    async fn test_synchronize_survives_connection_resets() {
+        let clock = std::sync::Arc::new(SimulatedClock::new(1_000_000.0));
+        let rtt = 0.050;
+        // A reset in the middle of latency profiling and another on the
+        // binary search's first probe; both are retried.
+        let mut rtts = generate_rtts(rtt, 0.002, 5);
+        rtts.push(RESET);
+        rtts.extend(generate_rtts(rtt, 0.002, 5));
+        rtts.push(RESET);
+        rtts.extend(vec![rtt; 20]);
+        let server = SimulatedServer::new(clock.clone(), 3.4, rtts);
+        let token = CancellationToken::new();
+
+        let retries = std::sync::Arc::new(Mutex::new(Vec::new()));
+        let seen = retries.clone();
+        let progress: ProgressCallback = Box::new(move |data| {
+            if data.get("retry").is_some() {
+                seen.lock().unwrap().push(data);
+            }
+        });
+
+        let result = synchronize_with(
+            &server,
+            clock.as_ref(),
+            &BALANCED,
+            1,
+            "http://test",
+            &token,
+            &progress,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result.whole_second_offset, 3);
+        assert!((result.subsecond_offset - 0.4).abs() < 0.002);
+        // Two retried probes cap the grade at C.
+        assert_eq!(result.grade, Some(sync_grade::SyncGrade::C));
+        let retries = retries.lock().unwrap();
+        assert_eq!(retries.len(), 2);
+        assert_eq!(retries[0]["phase"], "latency_profiling");
+        assert_eq!(retries[0]["retry"], 1);
+        assert_eq!(retries[0]["reason"], "transport_error");
+        assert_eq!(retries[1]["phase"], "binary_search");
+        assert_eq!(retries[1]["rejected"]["transport_error"], 2);
+        assert_eq!(retries[1]["probes_sent"], 12);
+    }
+
+    #[tokio::test]
+    async fn test_fatal_probe_errors_are_not_retried() {
+        struct NoDateServer;
+        impl ServerProbe for NoDateServer {
+            fn probe<'a>(
+                &'a self,
+                _url: &'a str,
+            ) -> Pin<Box<dyn Future<Output = Result<(i64, f64), AppError>> + Send + 'a>>
+            {
+                Box::pin(async { Err(AppError::NoDateHeader) })
+            }
+        }
+        let clock = SimulatedClock::new(1_000_000.0);
+        let result = measure_latency(
+            &NoDateServer,
+            &clock,
+            &BALANCED,
+            "http://test",
+            &CancellationToken::new(),
+            &noop_progress(),
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::NoDateHeader)));
+        // No backoff happened.
+        assert_eq!(clock.monotonic_secs(), 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_offline_fails_fast() {
+        struct OfflineProbe;
+        impl ServerProbe for OfflineProbe {
+            fn probe<'a>(
+                &'a self,
+                _url: &'a str,
+            ) -> Pin<Box<dyn Future<Output = Result<(i64, f64), AppError>> + Send + 'a>>
+            {
+                Box::pin(async { Err(AppError::Offline) })
+            }
+        }
+        let clock = SimulatedClock::new(1_000_000.0);
+        let failure = synchronize_with(
+            &OfflineProbe,
+            &clock,
+            &BALANCED,
+            1,
+            "http://test",
+            &CancellationToken::new(),
+            &noop_progress(),
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(failure.error, AppError::Offline));
+        assert_eq!(failure.context.total_attempts, 1);
+        assert_eq!(clock.monotonic_secs(), 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_persistent_resets_exhaust_retry_budget() {
+        let clock = std::sync::Arc::new(SimulatedClock::new(1_000_000.0));
+        let server = SimulatedServer::new(
+            clock.clone(),
+            0.0,
+            vec![RESET; BALANCED.max_retries as usize],
+        );
+        let result = measure_latency(
+            &server,
+            clock.as_ref(),
+            &BALANCED,
+            "http://test",
+            &CancellationToken::new(),
+            &noop_progress(),
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::MaxRetriesExceeded(_))));
+        assert_eq!(server.remaining_rtts(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_estimate_offset_brackets_true_offset() {
+        for true_offset in [-3.25, 0.0, 0.7, 12.1] {
+            let clock = Arc::new(SimulatedClock::new(1_000_000.3));
+            let server = SimulatedServer::new(clock.clone(), true_offset, vec![0.080]);
+            let estimate =
+                estimate_offset_with(&server, clock.as_ref(), &BALANCED, 1, "http://test")
+                    .await
+                    .unwrap();
+            assert!(!estimate.verified);
+            assert!((estimate.rtt_ms - 80.0).abs() < 1e-6);
+            assert!((estimate.uncertainty_ms - 540.0).abs() < 1e-6);
+            assert!(
+                (estimate.offset_ms - true_offset * 1000.0).abs() <= estimate.uncertainty_ms,
+                "{true_offset}: {}",
+                estimate.offset_ms
+            );
+            assert_eq!(server.remaining_rtts(), 0);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_recheck_keeps_a_good_offset_and_nudges_a_moved_one() {
+        let clock = Arc::new(SimulatedClock::new(1_000_000.3));
+        let deadline = clock.system_time_secs() + 30.0;
+        let server = SimulatedServer::new(clock.clone(), 5.3, vec![0.040; 3]);
+        let kept = recheck_with(
+            &server,
+            clock.as_ref(),
+            &NOISY,
+            "http://test",
+            5.3,
+            deadline,
+        )
+        .await
+        .unwrap();
+        assert_eq!(kept.moved_ms, 0.0);
+        assert_eq!(kept.probes, 3);
+        assert!(
+            kept.uncertainty_ms <= 10.0 + 1e-6,
+            "{}",
+            kept.uncertainty_ms
         );
+        assert_eq!(server.remaining_rtts(), 0);
+
+        // The server's clock has jumped 200 ms since the stored offset;
+        // bisecting brings the correction most of the way.
+        let server = SimulatedServer::new(clock.clone(), 5.5, vec![0.040; 7]);
+        let moved = recheck_with(
+            &server,
+            clock.as_ref(),
+            &BALANCED,
+            "http://test",
+            5.3,
+            deadline,
+        )
+        .await
+        .unwrap();
+        assert!(moved.probes > 3);
+        assert!(
+            (moved.offset_ms - 5500.0).abs() <= moved.uncertainty_ms * 2.0 + 1e-6,
+            "{moved:?}"
+        );
+        assert!(moved.uncertainty_ms < 100.0, "{moved:?}");
+
+        // Short on time: only the three probes, and only a nudge.
+        let server = SimulatedServer::new(clock.clone(), 5.5, vec![0.040; 3]);
+        let soon = clock.system_time_secs() + 3.5;
+        let nudged = recheck_with(&server, clock.as_ref(), &BALANCED, "http://test", 5.3, soon)
+            .await
+            .unwrap();
+        assert_eq!(nudged.probes, 3);
+        assert!(nudged.moved_ms > 0.0 && nudged.moved_ms < 200.0);
+    }
+
+    #[tokio::test]
+    async fn test_recheck_sends_nothing_it_cannot_finish() {
+        let clock = Arc::new(SimulatedClock::new(1_000_000.3));
+        let server = SimulatedServer::new(clock.clone(), 5.3, vec![0.040; 3]);
+        // Polite spaces probes 2 s apart.
+        let soon = clock.system_time_secs() + 1.0;
+        let none = recheck_with(&server, clock.as_ref(), &POLITE, "http://test", 5.3, soon).await;
+        assert!(matches!(none, Err(AppError::RecheckTooLate)));
+        // Room for the plain probe, not for the aligned ones.
+        let soon = clock.system_time_secs() + 2.5;
+        let plain = recheck_with(&server, clock.as_ref(), &POLITE, "http://test", 5.3, soon)
+            .await
+            .unwrap();
+        assert_eq!(plain.probes, 1);
+        assert_eq!(server.remaining_rtts(), 2);
+
+        let now = clock.system_time_secs();
+        let late = recheck_with(&server, clock.as_ref(), &BALANCED, "http://test", 5.3, now).await;
+        assert!(matches!(late, Err(AppError::RecheckTooLate)));
+    }
+
+    /// Never answers, like a server sitting out the request timeout.
+    struct HangingProbe;
+
+    impl ServerProbe for HangingProbe {
+        fn probe<'a>(
+            &'a self,
+            _url: &'a str,
+        ) -> Pin<Box<dyn Future<Output = Result<(i64, f64), AppError>> + Send + 'a>> {
+            Box::pin(std::future::pending())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancel_interrupts_in_flight_probe() {
+        let clock = SimulatedClock::new(1_000_000.0);
+        let token = CancellationToken::new();
+        let canceller = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            canceller.cancel();
+        });
+
+        let started = std::time::Instant::now();
+        let failure = synchronize_with(
+            &HangingProbe,
+            &clock,
+            &BALANCED,
+            1,
+            "http://test",
+            &token,
+            &noop_progress(),
+        )
+        .await
+        .unwrap_err();
+
+        assert!(matches!(failure.error, AppError::Cancelled));
+        assert_eq!(failure.context.total_attempts, 1);
+        assert!(started.elapsed() < std::time::Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_demo_url_syncs_against_simulator() {
+        let url = "demo://server?offset_ms=-2500&rtt_ms=40&jitter_ms=4";
+        let result = synchronize(
+            1,
+            url,
+            &ProbeSettings::new("HEAD"),
+            &crate::time_extractor::DateHeaderExtractor,
+            &BALANCED,
+            CancellationToken::new(),
+            noop_progress(),
+        )
+        .await
+        .unwrap_or_else(|f| panic!("{}", f.error));
+        assert!(
+            (result.total_offset_ms + 2500.0).abs() < 25.0,
+            "offset {}",
+            result.total_offset_ms
+        );
+
+        let bad = "demo://server?rtt_ms=0";
+        assert!(test_connection(
+            bad,
+            &ProbeSettings::new("HEAD"),
+            &crate::time_extractor::DateHeaderExtractor,
+            &BALANCED
+        )
+        .await
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_recorded_sync_replays_to_the_same_offset() {
+        let url = "demo://server?offset_ms=731.4&rtt_ms=60&jitter_ms=8";
+        let recorder = Arc::new(ProbeRecorder::new());
+        let settings = ProbeSettings {
+            recorder: Some(recorder.clone()),
+            ..ProbeSettings::new("HEAD")
+        };
+        let live = synchronize(
+            1,
+            url,
+            &settings,
+            &crate::time_extractor::DateHeaderExtractor,
+            &POLITE,
+            CancellationToken::new(),
+            noop_progress(),
+        )
+        .await;
+        let run = recorder.take();
+        let probes = run.probes.len();
+        assert!(probes > POLITE.probe_count);
+
+        let db = crate::db::Database::new_in_memory().unwrap();
+        let server = db.add_server(url).unwrap();
+        let recording = SyncRecording::new(&server, &POLITE, run, &live);
+        let report = replay(&recording).await;
+        let replayed = report.result.unwrap();
+        assert_eq!(replayed.total_offset_ms, live.unwrap().total_offset_ms);
+        assert_eq!(report.unused_probes, 0);
+
+        // A recording cut short makes the engine ask for probes it lacks.
+        let mut truncated = recording.clone();
+        truncated.probes.truncate(probes / 2);
+        let report = replay(&truncated).await;
+        assert!(report
+            .error
+            .unwrap()
+            .contains("more probes than were recorded"));
+    }
+
+    #[tokio::test]
+    async fn test_cached_responses_count_as_outliers() {
+        let clock = std::sync::Arc::new(SimulatedClock::new(1_000_000.0));
+        let rtt = 0.050;
+        let mut rtts = generate_rtts(rtt, 0.002, 5);
+        rtts.push(CACHED);
+        rtts.extend(generate_rtts(rtt, 0.002, 5));
+        rtts.push(CACHED); // whole-second probe
+        rtts.extend(vec![rtt; 20]);
+        let server = SimulatedServer::new(clock.clone(), 3.4, rtts);
+
+        let rejections = std::sync::Arc::new(Mutex::new(Vec::new()));
+        let seen = rejections.clone();
+        let progress: ProgressCallback = Box::new(move |data| {
+            if data.get("reason").is_some() {
+                seen.lock().unwrap().push(data);
+            }
+        });
+
+        let result = synchronize_with(
+            &server,
+            clock.as_ref(),
+            &BALANCED,
+            1,
+            "http://test",
+            &CancellationToken::new(),
+            &progress,
+        )
+        .await
+        .unwrap();
+
+        // The stale samples never made it into the offset.
+        assert_eq!(result.whole_second_offset, 3);
+        assert!((result.subsecond_offset - 0.4).abs() < 0.002);
+        let rejections = rejections.lock().unwrap();
+        assert_eq!(rejections.len(), 2);
+        assert!(rejections.iter().all(|r| r["reason"] == "cached_response"));
+        assert_eq!(rejections[1]["rejected"]["cached_response"], 2);
+        assert_eq!(rejections[1]["backoff_ms"], 0.0);
+    }
+
+    #[test]
+    fn test_cache_busting_and_age_detection() {
+        let base = reqwest::Url::parse("https://example.com/time?x=1").unwrap();
+        let busted = cache_busted(&base);
+        assert!(busted
+            .as_str()
+            .starts_with("https://example.com/time?x=1&_ttcb="));
+        assert_ne!(busted, cache_busted(&base));
+
+        let mut headers = header::HeaderMap::new();
+        assert_eq!(cached_age(&headers), None);
+        headers.insert(header::AGE, header::HeaderValue::from_static("0"));
+        assert_eq!(cached_age(&headers), None);
+        headers.insert(header::AGE, header::HeaderValue::from_static("42"));
+        assert_eq!(cached_age(&headers), Some(42));
+    }
+
+    // ── Interval estimator and benchmark ──
+
+    #[test]
+    fn test_best_overlap_ignores_a_stray_window() {
+        let (low, high) = best_overlap(&[(0.0, 1.0), (0.5, 1.5), (0.9, 2.0)]);
+        assert_eq!((low, high), (0.9, 1.0));
+
+        let (low, high) = best_overlap(&[(0.0, 1.0), (0.2, 1.2), (5.0, 6.0)]);
+        assert_eq!((low, high), (0.2, 1.0));
+    }
+
+    #[tokio::test]
+    async fn test_interval_estimator_brackets_offset() {
+        let clock = std::sync::Arc::new(SimulatedClock::new(1_000_000.0));
+        let server = SimulatedServer::new(clock.clone(), 3.4, vec![0.050; 20]);
+
+        let estimate = estimate_by_intervals(
+            &server,
+            clock.as_ref(),
+            &BALANCED,
+            "http://test",
+            &CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+
+        // 20 probes spread over a second narrow the window to ~50 ms.
+        assert!((estimate.offset - 3.4).abs() <= estimate.uncertainty);
+        assert!(estimate.uncertainty <= 0.05, "{}", estimate.uncertainty);
+    }
+
+    #[tokio::test]
+    async fn test_benchmark_reports_both_methods() {
+        let clock = std::sync::Arc::new(SimulatedClock::new(1_000_000.0));
+        let mut rtts = generate_rtts(0.050, 0.002, 10);
+        rtts.extend(vec![0.050; 200]);
+        let server = SimulatedServer::new(clock.clone(), 1.25, rtts);
+
+        let report = benchmark_with(
+            &server,
+            clock.as_ref(),
+            &BALANCED,
+            7,
+            "http://test",
+            &CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(report.server_id, 7);
+        assert_eq!(report.runs[0].method, FOUR_PHASE);
+        assert_eq!(report.runs[1].method, INTERVAL_INTERSECTION);
+        assert_eq!(report.runs[1].probes, 20);
+        for run in &report.runs {
+            assert!(run.error.is_none());
+            assert!((run.offset_ms.unwrap() - 1250.0).abs() < 50.0);
+        }
+        assert!(report.difference_ms.unwrap() < 50.0);
+        // Low jitter: the binary search is far tighter than 1/20 s windows.
+        assert_eq!(report.recommended.as_deref(), Some(FOUR_PHASE));
+    }
+
+    #[test]
+    fn whole_second_needs_agreeing_samples_clear_of_boundaries() {
+        let latency = LatencyProfile {
+            min: 0.048,
+            q1: 0.049,
+            median: 0.050,
+            mean: 0.050,
+            q3: 0.051,
+            max: 0.052,
+            breakdown: None,
+        };
+        // True offset 4.3 s; stamps land 25 ms after sending.
+        let sample = |sent_at: f64| StampedSample {
+            sent_at,
+            rtt: 0.050,
+            server_second: (sent_at + 0.025 + 4.3).floor() as i64,
+        };
+        let samples: Vec<_> = [100.1, 100.5, 101.9].map(sample).into();
+        let whole = |samples: &[StampedSample]| {
+            whole_second_from_samples(samples, &latency, 0.5, 0.3, 0.001)
+        };
+        assert_eq!(whole(&samples), Some(4));
+
+        // 100.675 + 0.025 + 0.3 sits on a second boundary: skipped, which
+        // leaves one sample, too few to go on.
+        let near_boundary: Vec<_> = [100.675, 100.1].map(sample).into();
+        assert_eq!(whole(&near_boundary), None);
+
+        let mut disagreeing = samples;
+        disagreeing[1].server_second += 1;
+        assert_eq!(whole(&disagreeing), None);
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_cap() {
+        assert_eq!(backoff_secs(&BALANCED, 0), 0.5);
+        assert_eq!(backoff_secs(&BALANCED, 1), 1.0);
+        assert_eq!(backoff_secs(&BALANCED, 2), 2.0);
+        assert_eq!(backoff_secs(&BALANCED, 10), MAX_BACKOFF_SECS);
+    }
+
+    #[tokio::test]
+    async fn test_failure_reports_phase_attempts_and_latency() {
+        let clock = std::sync::Arc::new(SimulatedClock::new(1_000_000.0));
+        let rtt = 0.050;
+        let mut rtts = generate_rtts(rtt, 0.002, 10); // Phase 1
+        rtts.extend(vec![RESET; BALANCED.max_retries as usize]); // Phase 3 never gets through
+        let server = SimulatedServer::new(clock.clone(), 2.5, rtts);
+
+        let failure = synchronize_with(
+            &server,
+            clock.as_ref(),
+            &BALANCED,
+            1,
+            "http://test",
+            &CancellationToken::new(),
+            &noop_progress(),
+        )
+        .await
+        .unwrap_err();
+
+        assert!(matches!(failure.error, AppError::MaxRetriesExceeded(10)));
+        assert_eq!(failure.context.phase, SyncPhase::BinarySearch);
+        assert_eq!(failure.context.phase_attempts, 10);
+        assert_eq!(failure.context.total_attempts, 20);
+        assert!(failure.context.latency_profile.is_some());
+        assert!(failure.context.elapsed_ms > 0);
+    }
+
+    #[tokio::test]
+    async fn test_deadline_stops_sync_with_partial_offset() {
+        let clock = std::sync::Arc::new(SimulatedClock::new(1_000_000.0));
+        let rtt = 0.050;
+        let mut rtts = generate_rtts(rtt, 0.002, 10); // Phase 1
+        rtts.extend(vec![rtt; 12]); // Phases 2-3
+        rtts.extend(vec![RESET; BALANCED.max_retries as usize]); // Verification backs off
+        let server = SimulatedServer::new(clock.clone(), 5.3, rtts);
+        let profile = SyncProfile {
+            deadline_secs: 30,
+            ..BALANCED
+        };
+
+        let failure = synchronize_with(
+            &server,
+            clock.as_ref(),
+            &profile,
+            1,
+            "http://test",
+            &CancellationToken::new(),
+            &noop_progress(),
+        )
+        .await
+        .unwrap_err();
+
+        assert!(matches!(failure.error, AppError::SyncDeadlineExceeded(30)));
+        assert_eq!(failure.context.phase, SyncPhase::Verification);
+        let partial = failure.context.partial_offset_ms.unwrap();
+        assert!((partial - 5300.0).abs() < 2.0, "partial offset {partial}");
+        assert!(failure.context.elapsed_ms >= 30_000);
+    }
+
+    #[tokio::test]
+    async fn real_probe_records_response_metadata() {
+        use axum::response::Redirect;
+        use axum::routing::get;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let router = axum::Router::new()
+            .route("/", get(|| async { Redirect::temporary("/time") }))
+            .route("/time", get(|| async { ([("server", "test-edge")], "ok") }));
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+
+        let extractor = crate::time_extractor::DateHeaderExtractor;
+        let probe =
+            RealServerProbe::new(&BALANCED, &ProbeSettings::new("HEAD"), &extractor).unwrap();
+        probe.probe(&format!("http://{addr}/")).await.unwrap();
+
+        let response = probe.response.lock().unwrap().clone().unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(response.http_version, "HTTP/1.1");
+        assert_eq!(response.remote_ip.as_deref(), Some("127.0.0.1"));
+        assert_eq!(response.redirects, 1);
+        let fingerprint = probe.fingerprint.lock().unwrap().clone().unwrap();
+        assert_eq!(fingerprint.server.as_deref(), Some("test-edge"));
+        // No warm-up: this probe paid for its own connection.
+        assert_eq!(probe.last_breakdown().unwrap().new_connections, 1);
+    }
+
+    #[tokio::test]
+    async fn real_probe_sends_the_user_agent() {
+        use axum::http::{HeaderMap, StatusCode};
+        use axum::routing::get;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let router = axum::Router::new().route(
+            "/",
+            get(|headers: HeaderMap| async move {
+                match headers.get("user-agent").map(|v| v.as_bytes()) {
+                    Some(b"Custom/1.0") => StatusCode::OK,
+                    Some(ua) if ua == DEFAULT_USER_AGENT.as_bytes() => StatusCode::NO_CONTENT,
+                    _ => StatusCode::FORBIDDEN,
+                }
+            }),
+        );
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+
+        let extractor = crate::time_extractor::DateHeaderExtractor;
+        let url = format!("http://{addr}/");
+        let custom = RealServerProbe::new(
+            &BALANCED,
+            &ProbeSettings {
+                user_agent: Some("Custom/1.0"),
+                ..ProbeSettings::new("GET")
+            },
+            &extractor,
+        )
+        .unwrap();
+        custom.probe(&url).await.unwrap();
+        assert_eq!(custom.take_response().unwrap().status, 200);
+
+        let default =
+            RealServerProbe::new(&BALANCED, &ProbeSettings::new("GET"), &extractor).unwrap();
+        default.probe(&url).await.unwrap();
+        assert_eq!(default.take_response().unwrap().status, 204);
+    }
+
+    #[tokio::test]
+    async fn real_probe_reuses_one_connection() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let accepted = Arc::new(AtomicU32::new(0));
+        let counter = accepted.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                counter.fetch_add(1, Ordering::SeqCst);
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 4096];
+                    while let Ok(n) = socket.read(&mut buf).await {
+                        if n == 0 {
+                            break;
+                        }
+                        let date = Utc::now().format("%a, %d %b %Y %H:%M:%S GMT");
+                        let reply = format!(
+                            "HTTP/1.1 206 Partial Content\r\nDate: {date}\r\nContent-Length: 1\r\n\r\nx"
+                        );
+                        socket.write_all(reply.as_bytes()).await.unwrap();
+                    }
+                });
+            }
+        });
+
+        let extractor = crate::time_extractor::DateHeaderExtractor;
+        let probe =
+            RealServerProbe::new(&BALANCED, &ProbeSettings::new("GET"), &extractor).unwrap();
+        let url = format!("http://{addr}/");
+        probe.warm_up(&url).await.unwrap();
+        for _ in 0..3 {
+            probe.probe(&url).await.unwrap();
+            let breakdown = probe.last_breakdown().unwrap();
+            assert_eq!(breakdown.new_connections, 0);
+            assert_eq!(breakdown.connect, 0.0);
+        }
+
+        assert_eq!(accepted.load(Ordering::SeqCst), 1);
+        let response = probe.take_response().unwrap();
+        assert_eq!(response.status, 206);
+        assert!(response.probe_overhead_us > 0.0);
+
+        // A later probe on the same client, e.g. one kept warm between
+        // syncs, finds the connection still open.
+        let settings = ProbeSettings {
+            client: Some(probe.client.clone()),
+            ..ProbeSettings::new("GET")
+        };
+        keep_alive(&url, &settings, &BALANCED).await.unwrap();
+        let later = RealServerProbe::new(&BALANCED, &settings, &extractor).unwrap();
+        later.probe(&url).await.unwrap();
+        assert_eq!(later.last_breakdown().unwrap().new_connections, 0);
+        assert_eq!(accepted.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn real_probe_bypasses_and_rejects_caches() {
+        use axum::http::{HeaderMap, StatusCode};
+        use axum::routing::get;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let router = axum::Router::new()
+            .route(
+                "/fresh",
+                get(|headers: HeaderMap| async move {
+                    if headers.get("cache-control").map(|v| v.as_bytes()) == Some(b"no-cache") {
+                        StatusCode::OK
+                    } else {
+                        StatusCode::BAD_REQUEST
+                    }
+                }),
+            )
+            .route("/stale", get(|| async { [("age", "30")] }));
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+
+        let extractor = crate::time_extractor::DateHeaderExtractor;
+        let probe =
+            RealServerProbe::new(&BALANCED, &ProbeSettings::new("GET"), &extractor).unwrap();
+
+        probe.probe(&format!("http://{addr}/fresh")).await.unwrap();
+        assert_eq!(probe.response.lock().unwrap().as_ref().unwrap().status, 200);
+
+        let stale = probe.probe(&format!("http://{addr}/stale")).await;
+        assert!(matches!(stale, Err(AppError::CachedResponse(30))));
     }
 }
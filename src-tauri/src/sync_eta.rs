@@ -0,0 +1,155 @@
+use crate::models::SyncPhase;
+use crate::sync_profile::SyncProfile;
+use serde_json::Value;
+
+/// Halving a one-second window until it is under 1 ms takes ten steps.
+const SEARCH_ITERATIONS: u32 = 10;
+
+/// Aligned probes a clean run sends after latency profiling: a baseline and
+/// `SEARCH_ITERATIONS` for the binary search, and two for verification. The
+/// whole second usually comes from the latency probes for free.
+const ALIGNED_AFTER_PROFILING: u32 = 1 + SEARCH_ITERATIONS + 2;
+
+/// Waiting for a point of the local second costs half a second on average.
+const MEAN_ALIGNMENT_WAIT: f64 = 0.5;
+
+/// Turns progress events into an estimate of the time left, assuming no
+/// more retries. Each event says how far its phase got; the rest is the
+/// probes a clean run still sends, priced from the profile's intervals and
+/// the RTT seen so far.
+pub(crate) struct EtaEstimator {
+    probe_count: u32,
+    min_interval: f64,
+    min_spacing: f64,
+    burst_size: u32,
+    /// Median RTT so far, seconds.
+    rtt: f64,
+    /// Gap between profiling probes, seconds.
+    spacing: f64,
+    last_ms: u64,
+}
+
+impl EtaEstimator {
+    pub(crate) fn new(profile: &SyncProfile) -> Self {
+        Self {
+            probe_count: profile.probe_count as u32,
+            min_interval: profile.min_interval_secs(),
+            min_spacing: profile.min_spacing_secs(),
+            burst_size: profile.burst_size.max(1),
+            rtt: 0.0,
+            spacing: profile.min_interval_secs(),
+            last_ms: 0,
+        }
+    }
+
+    /// Milliseconds left after the progress event `data` of `phase`.
+    pub(crate) fn remaining_ms(&mut self, phase: SyncPhase, data: &Value) -> u64 {
+        let number = |key: &str| data.get(key).and_then(Value::as_f64);
+        if let Some(median) = number("current_median_ms") {
+            self.rtt = median / 1000.0;
+        }
+        if let Some(spacing) = number("spacing_ms") {
+            self.spacing = spacing / 1000.0;
+        }
+        // A retry's backoff comes on top of what was left.
+        if data.get("retry").is_some() {
+            self.last_ms += number("backoff_ms").unwrap_or(0.0) as u64;
+            return self.last_ms;
+        }
+
+        let (profiling, aligned) = match phase {
+            SyncPhase::LatencyProfiling => {
+                let sent = number("probe_index").map_or(0, |i| i as u32 + 1);
+                (
+                    self.probe_count.saturating_sub(sent),
+                    ALIGNED_AFTER_PROFILING,
+                )
+            }
+            SyncPhase::BinarySearch => {
+                // The search halves its interval until it is inside the
+                // target, which jitter can set well above 1 ms.
+                let left = match (number("interval_width_ms"), number("target_width_ms")) {
+                    (Some(width), Some(target)) if width >= target => {
+                        (width / target).log2().floor() as u32 + 1
+                    }
+                    (Some(_), Some(_)) => 0,
+                    _ => {
+                        let done = number("iteration").map_or(0, |i| i as u32 + 1);
+                        SEARCH_ITERATIONS.saturating_sub(done)
+                    }
+                };
+                (0, left + 2)
+            }
+            SyncPhase::WholeSecondOffset => (0, 2),
+            SyncPhase::Verification => match number("shift") {
+                Some(shift) if shift < 0.0 => (0, 1),
+                _ => (0, 0),
+            },
+            SyncPhase::Complete => (0, 0),
+        };
+        let secs =
+            profiling as f64 * (self.spacing + self.rtt) + aligned as f64 * self.aligned_cost();
+        self.last_ms = (secs * 1000.0).round() as u64;
+        self.last_ms
+    }
+
+    /// One aligned attempt: a burst, each probe waiting out its interval
+    /// and then for its point of the second.
+    fn aligned_cost(&self) -> f64 {
+        let probe = |wait: f64| wait + MEAN_ALIGNMENT_WAIT + self.rtt;
+        probe(self.min_interval) + (self.burst_size - 1) as f64 * probe(self.min_spacing)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync_profile::BALANCED;
+    use serde_json::json;
+
+    #[test]
+    fn estimate_shrinks_as_phases_advance() {
+        let mut eta = EtaEstimator::new(&BALANCED);
+        let profiling = eta.remaining_ms(
+            SyncPhase::LatencyProfiling,
+            &json!({ "probe_index": 0, "spacing_ms": 100.0, "current_median_ms": 50.0 }),
+        );
+        let search = eta.remaining_ms(SyncPhase::BinarySearch, &json!({ "iteration": 4 }));
+        let verifying = eta.remaining_ms(SyncPhase::Verification, &json!({ "shift": -0.5 }));
+        assert!(profiling > search && search > verifying && verifying > 0);
+        assert_eq!(eta.remaining_ms(SyncPhase::Complete, &json!({})), 0);
+    }
+
+    #[test]
+    fn retries_add_their_backoff() {
+        let mut eta = EtaEstimator::new(&BALANCED);
+        let before = eta.remaining_ms(SyncPhase::WholeSecondOffset, &json!({}));
+        let after = eta.remaining_ms(
+            SyncPhase::WholeSecondOffset,
+            &json!({ "retry": 1, "backoff_ms": 400.0 }),
+        );
+        assert_eq!(after, before + 400);
+    }
+
+    #[test]
+    fn jitter_target_shortens_the_search() {
+        let mut eta = EtaEstimator::new(&BALANCED);
+        let fine = eta.remaining_ms(
+            SyncPhase::BinarySearch,
+            &json!({ "iteration": 0, "interval_width_ms": 500.0, "target_width_ms": 1.0 }),
+        );
+        let coarse = eta.remaining_ms(
+            SyncPhase::BinarySearch,
+            &json!({ "iteration": 0, "interval_width_ms": 500.0, "target_width_ms": 20.0 }),
+        );
+        let done = eta.remaining_ms(
+            SyncPhase::BinarySearch,
+            &json!({ "stopped_early": true, "interval_width_ms": 15.6, "target_width_ms": 20.0 }),
+        );
+        assert!(fine > coarse && coarse > done);
+        assert_eq!(
+            done,
+            eta.remaining_ms(SyncPhase::WholeSecondOffset, &json!({}))
+        );
+    }
+}
@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+
+/// How far a sync's offset can be trusted, at a glance. F is kept for
+/// offsets that failed verification; a verified sync is graded by its
+/// weakest measure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum SyncGrade {
+    A,
+    B,
+    C,
+    D,
+    F,
+}
+
+impl SyncGrade {
+    const PASSING: [SyncGrade; 4] = [SyncGrade::A, SyncGrade::B, SyncGrade::C, SyncGrade::D];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SyncGrade::A => "A",
+            SyncGrade::B => "B",
+            SyncGrade::C => "C",
+            SyncGrade::D => "D",
+            SyncGrade::F => "F",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::PASSING
+            .into_iter()
+            .chain([SyncGrade::F])
+            .find(|g| g.as_str() == s)
+    }
+}
+
+/// What a sync is graded on.
+#[derive(Debug, Clone, Copy)]
+pub struct GradeInputs {
+    /// Interquartile RTT spread of the latency profile.
+    pub jitter_ms: f64,
+    /// Probes that failed and had to be sent again.
+    pub retries: u32,
+    /// Window the sub-second offset is known to within.
+    pub convergence_width_ms: f64,
+    pub verified: bool,
+}
+
+/// Upper bounds for A, B and C; anything above the last is a D.
+const JITTER_MS: [f64; 3] = [5.0, 15.0, 40.0];
+const RETRIES: [u32; 3] = [0, 1, 3];
+const WIDTH_MS: [f64; 3] = [5.0, 15.0, 40.0];
+
+pub fn grade(inputs: &GradeInputs) -> SyncGrade {
+    if !inputs.verified {
+        return SyncGrade::F;
+    }
+    let band = |within: &dyn Fn(usize) -> bool| {
+        (0..3)
+            .find(|&i| within(i))
+            .map_or(SyncGrade::D, |i| SyncGrade::PASSING[i])
+    };
+    [
+        band(&|i| inputs.jitter_ms <= JITTER_MS[i]),
+        band(&|i| inputs.retries <= RETRIES[i]),
+        band(&|i| inputs.convergence_width_ms <= WIDTH_MS[i]),
+    ]
+    .into_iter()
+    .max()
+    .unwrap_or(SyncGrade::D)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CLEAN: GradeInputs = GradeInputs {
+        jitter_ms: 2.0,
+        retries: 0,
+        convergence_width_ms: 1.5,
+        verified: true,
+    };
+
+    #[test]
+    fn weakest_measure_sets_the_grade() {
+        assert_eq!(grade(&CLEAN), SyncGrade::A);
+        let retried = GradeInputs {
+            retries: 2,
+            ..CLEAN
+        };
+        assert_eq!(grade(&retried), SyncGrade::C);
+        let wide = GradeInputs {
+            convergence_width_ms: 120.0,
+            ..retried
+        };
+        assert_eq!(grade(&wide), SyncGrade::D);
+        let unverified = GradeInputs {
+            verified: false,
+            ..CLEAN
+        };
+        assert_eq!(grade(&unverified), SyncGrade::F);
+    }
+
+    #[test]
+    fn grades_round_trip_as_letters() {
+        for g in SyncGrade::PASSING.into_iter().chain([SyncGrade::F]) {
+            assert_eq!(SyncGrade::parse(g.as_str()), Some(g));
+        }
+        assert_eq!(SyncGrade::parse("E"), None);
+    }
+}
@@ -0,0 +1,340 @@
+use crate::asymmetry;
+use crate::error::AppError;
+use crate::models::{AppSettings, Server};
+use serde::Serialize;
+use std::net::IpAddr;
+use std::time::Duration;
+
+pub const DEFAULT_PROFILE: &str = "balanced";
+
+/// Accepted values for `min_request_interval_ms`, globally and per server.
+pub const REQUEST_INTERVAL_RANGE_MS: std::ops::RangeInclusive<u32> = 100..=10_000;
+
+/// Budget for one whole sync until the settings say otherwise.
+pub const DEFAULT_DEADLINE_SECS: u64 = 180;
+
+/// A named bundle of the sync engine's tuning knobs.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct SyncProfile {
+    pub id: &'static str,
+    pub label: &'static str,
+    pub description: &'static str,
+    /// Probes used to build the latency profile.
+    pub probe_count: usize,
+    /// Gap between consecutive probes; latency profiling stretches or
+    /// shrinks it with the jitter it sees.
+    pub min_interval_ms: u64,
+    /// Never probe one host faster than this, however clean the link.
+    pub min_spacing_ms: u64,
+    /// Retries per step when a probe's RTT falls outside the profile.
+    pub max_retries: u32,
+    /// Probes sent per timed measurement (phases 2–4), keeping the one with
+    /// the shortest round trip. 1 sends single probes.
+    pub burst_size: u32,
+    pub timeout_ms: u64,
+    /// Share of each round trip spent reaching the server. Per server, not
+    /// part of the preset; `resolve` fills it in.
+    #[serde(skip)]
+    pub asymmetry: f64,
+    /// Longest a whole sync may run, retries included. A global setting,
+    /// not part of the preset; `resolve` fills it in.
+    #[serde(skip)]
+    pub deadline_secs: u64,
+}
+
+impl SyncProfile {
+    pub fn min_interval_secs(&self) -> f64 {
+        self.min_interval_ms as f64 / 1000.0
+    }
+
+    pub fn min_spacing_secs(&self) -> f64 {
+        self.min_spacing_ms as f64 / 1000.0
+    }
+
+    pub fn timeout(&self) -> Duration {
+        Duration::from_millis(self.timeout_ms)
+    }
+}
+
+pub const AGGRESSIVE: SyncProfile = SyncProfile {
+    id: "aggressive",
+    label: "Aggressive",
+    description: "Short gaps and more retries for the fastest result; heavy on the server",
+    probe_count: 15,
+    min_interval_ms: 200,
+    min_spacing_ms: 100,
+    max_retries: 20,
+    burst_size: 1,
+    timeout_ms: 5_000,
+    asymmetry: asymmetry::SYMMETRIC,
+    deadline_secs: DEFAULT_DEADLINE_SECS,
+};
+
+pub const BALANCED: SyncProfile = SyncProfile {
+    id: DEFAULT_PROFILE,
+    label: "Balanced",
+    description: "Sensible defaults for most servers",
+    probe_count: 10,
+    min_interval_ms: 500,
+    min_spacing_ms: 200,
+    max_retries: 10,
+    burst_size: 1,
+    timeout_ms: 10_000,
+    asymmetry: asymmetry::SYMMETRIC,
+    deadline_secs: DEFAULT_DEADLINE_SECS,
+};
+
+pub const POLITE: SyncProfile = SyncProfile {
+    id: "polite",
+    label: "Polite",
+    description: "Fewer, spaced-out requests for rate-limited servers; slower to finish",
+    probe_count: 6,
+    min_interval_ms: 2_000,
+    // Rate-limited hosts get no speed-up on clean links.
+    min_spacing_ms: 2_000,
+    max_retries: 5,
+    burst_size: 1,
+    timeout_ms: 20_000,
+    asymmetry: asymmetry::SYMMETRIC,
+    deadline_secs: DEFAULT_DEADLINE_SECS,
+};
+
+pub const NOISY: SyncProfile = SyncProfile {
+    id: "noisy",
+    label: "Noisy Network",
+    description: "Short bursts of probes per step, keeping the fastest; for mobile hotspots and other jittery links",
+    probe_count: 12,
+    min_interval_ms: 500,
+    min_spacing_ms: 200,
+    max_retries: 10,
+    burst_size: 3,
+    timeout_ms: 10_000,
+    asymmetry: asymmetry::SYMMETRIC,
+    deadline_secs: DEFAULT_DEADLINE_SECS,
+};
+
+pub const PROFILES: [SyncProfile; 4] = [AGGRESSIVE, BALANCED, POLITE, NOISY];
+
+pub fn find(id: &str) -> Result<SyncProfile, AppError> {
+    PROFILES
+        .into_iter()
+        .find(|p| p.id == id)
+        .ok_or_else(|| AppError::UnknownSyncProfile(id.to_string()))
+}
+
+/// The server's own profile if it has one, else the global setting, with
+/// the server's path asymmetry. Unknown ids (e.g. from a newer build) fall
+/// back to balanced.
+///
+/// `min_request_interval_ms` (the server's, else the global setting) is
+/// the balanced profile's interval; the others keep their proportion to it,
+/// so 2000 makes every preset four times gentler and 100 suits a LAN. No
+/// resolved interval or spacing is ever shorter than that setting, so a
+/// faster preset only gets as close as it allows. Servers that are not
+/// trusted are held to `public_host_floor_ms` the same way.
+pub fn resolve(server: &Server, settings: &AppSettings) -> SyncProfile {
+    let id = server
+        .sync_profile
+        .as_deref()
+        .unwrap_or(&settings.sync_profile);
+    let profile = find(id).unwrap_or(BALANCED);
+    let mut interval_ms = server
+        .min_request_interval_ms
+        .unwrap_or(settings.min_request_interval_ms);
+    if !is_trusted(server) {
+        interval_ms = interval_ms.max(settings.public_host_floor_ms);
+    }
+    let floor_ms = u64::from(interval_ms);
+    let scale = |ms: u64| (ms * floor_ms / BALANCED.min_interval_ms).max(floor_ms);
+    SyncProfile {
+        asymmetry: server.asymmetry.unwrap_or(asymmetry::SYMMETRIC),
+        deadline_secs: settings.sync_deadline_secs.into(),
+        min_interval_ms: scale(profile.min_interval_ms),
+        min_spacing_ms: scale(profile.min_spacing_ms),
+        ..profile
+    }
+}
+
+/// Whether `server` may be probed faster than the public-host floor:
+/// flagged trusted, or on this machine or the local network.
+pub fn is_trusted(server: &Server) -> bool {
+    if server.trusted {
+        return true;
+    }
+    let Ok(url) = reqwest::Url::parse(&server.url) else {
+        return false;
+    };
+    let Some(host) = url.host_str() else {
+        return false;
+    };
+    match host.trim_matches(['[', ']']).parse::<IpAddr>() {
+        Ok(IpAddr::V4(a)) => a.is_loopback() || a.is_private() || a.is_link_local(),
+        // Loopback and unique local (fc00::/7).
+        Ok(IpAddr::V6(a)) => a.is_loopback() || (a.segments()[0] & 0xfe00) == 0xfc00,
+        Err(_) => host == "localhost" || host.ends_with(".local"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ServerStatus;
+    use chrono::Utc;
+
+    fn server(sync_profile: Option<&str>) -> Server {
+        Server {
+            id: 1,
+            url: "https://example.com".to_string(),
+            name: None,
+            offset_ms: None,
+            last_sync_at: None,
+            created_at: Utc::now(),
+            status: ServerStatus::Idle,
+            extractor_type: "date_header".to_string(),
+            probe_method: "HEAD".to_string(),
+            domain_preset: None,
+            sync_profile: sync_profile.map(str::to_string),
+            fingerprint: None,
+            asymmetry: None,
+            sync_on_launch: false,
+            health_score: None,
+            drift: None,
+            min_request_interval_ms: None,
+            trusted: false,
+            user_agent: None,
+            transport: Default::default(),
+            timezone: None,
+            timezone_guessed: false,
+        }
+    }
+
+    #[test]
+    fn find_knows_every_listed_profile() {
+        for profile in PROFILES {
+            assert_eq!(find(profile.id).unwrap(), profile);
+        }
+        assert!(matches!(
+            find("reckless"),
+            Err(AppError::UnknownSyncProfile(_))
+        ));
+    }
+
+    #[test]
+    fn server_profile_overrides_global_setting() {
+        let settings = AppSettings {
+            sync_profile: "polite".to_string(),
+            ..AppSettings::default()
+        };
+        assert_eq!(resolve(&server(None), &settings).id, POLITE.id);
+        let aggressive = resolve(&server(Some("aggressive")), &settings);
+        assert_eq!(aggressive.id, AGGRESSIVE.id);
+        assert_eq!(aggressive.max_retries, AGGRESSIVE.max_retries);
+        assert_eq!(resolve(&server(Some("gone")), &settings).id, BALANCED.id);
+    }
+
+    #[test]
+    fn request_interval_scales_every_profile() {
+        let slow = AppSettings {
+            min_request_interval_ms: 2_000,
+            ..AppSettings::default()
+        };
+        let balanced = resolve(&server(None), &slow);
+        assert_eq!(balanced.min_interval_ms, 2_000);
+        let polite = resolve(&server(Some("polite")), &slow);
+        assert_eq!(polite.min_interval_ms, 8_000);
+        assert_eq!(polite.min_spacing_ms, 8_000);
+
+        // A per-server interval wins over the setting.
+        let lan = Server {
+            min_request_interval_ms: Some(100),
+            trusted: true,
+            ..server(Some("polite"))
+        };
+        assert_eq!(resolve(&lan, &slow).min_interval_ms, 400);
+    }
+
+    #[test]
+    fn no_profile_goes_faster_than_the_setting() {
+        for interval in [100, 2_000] {
+            let settings = AppSettings {
+                min_request_interval_ms: interval,
+                ..AppSettings::default()
+            };
+            let own = Server {
+                trusted: true,
+                ..server(Some("aggressive"))
+            };
+            for profile in [resolve(&own, &settings), resolve(&server(None), &settings)] {
+                assert!(profile.min_interval_ms >= u64::from(interval));
+                assert!(profile.min_spacing_ms >= u64::from(interval));
+            }
+        }
+    }
+
+    #[test]
+    fn untrusted_servers_stay_above_the_floor() {
+        let settings = AppSettings::default();
+        let fast = Server {
+            min_request_interval_ms: Some(100),
+            ..server(None)
+        };
+        assert_eq!(resolve(&fast, &settings).min_interval_ms, 500);
+        let own = Server {
+            trusted: true,
+            ..fast.clone()
+        };
+        assert_eq!(resolve(&own, &settings).min_interval_ms, 100);
+        let lan = Server {
+            url: "http://192.168.1.20:8080".to_string(),
+            ..fast
+        };
+        assert_eq!(resolve(&lan, &settings).min_interval_ms, 100);
+    }
+
+    #[test]
+    fn the_floor_holds_after_scaling() {
+        let settings = AppSettings {
+            public_host_floor_ms: 500,
+            ..AppSettings::default()
+        };
+        let aggressive = resolve(&server(Some("aggressive")), &settings);
+        assert!(aggressive.min_interval_ms >= 500);
+        // The spacing is also the gap the host limiter keeps between probes.
+        assert!(aggressive.min_spacing_ms >= 500);
+    }
+
+    #[test]
+    fn local_hosts_count_as_trusted() {
+        for url in [
+            "http://localhost:3000",
+            "http://[::1]/",
+            "http://10.0.0.5",
+            "http://nas.local",
+        ] {
+            let local = Server {
+                url: url.to_string(),
+                ..server(None)
+            };
+            assert!(is_trusted(&local), "{url}");
+        }
+        assert!(!is_trusted(&server(None)));
+        assert!(!is_trusted(&Server {
+            url: "http://8.8.8.8".to_string(),
+            ..server(None)
+        }));
+    }
+
+    #[test]
+    fn resolve_carries_the_server_asymmetry() {
+        let settings = AppSettings::default();
+        assert_eq!(
+            resolve(&server(None), &settings).asymmetry,
+            asymmetry::SYMMETRIC
+        );
+        let skewed = Server {
+            asymmetry: Some(0.7),
+            ..server(None)
+        };
+        assert_eq!(resolve(&skewed, &settings).asymmetry, 0.7);
+    }
+}
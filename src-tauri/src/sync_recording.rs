@@ -0,0 +1,354 @@
+use crate::asymmetry;
+use crate::error::AppError;
+use crate::models::{Server, SyncResult};
+use crate::settings_validation::FieldError;
+use crate::sync_engine::SyncFailure;
+use crate::sync_profile::{self, SyncProfile};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+/// Recordings are JSON files in this directory under app data.
+pub const RECORDING_DIR: &str = "recordings";
+
+/// Bumped when the file layout changes incompatibly.
+const FORMAT_VERSION: u32 = 1;
+
+/// One probe as the engine saw it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedProbe {
+    /// Local wall-clock time the probe went out, unix seconds.
+    pub sent_at: f64,
+    /// Seconds until the answer, or the failure, came back.
+    pub rtt: f64,
+    /// The whole second the extractor read; `None` when the probe failed.
+    pub server_second: Option<i64>,
+    pub error: Option<RecordedError>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "message", rename_all = "snake_case")]
+pub enum RecordedError {
+    /// A cache answered; the engine skips these.
+    Cached,
+    /// Retried by the engine after a backoff.
+    Transient(String),
+    /// Ended the sync.
+    Fatal(String),
+}
+
+/// The tuning a sync ran with. The numbers are kept, not just the preset
+/// id, since `sync_profile::resolve` scales them per server.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedProfile {
+    pub id: String,
+    pub probe_count: usize,
+    pub min_interval_ms: u64,
+    pub min_spacing_ms: u64,
+    pub max_retries: u32,
+    pub burst_size: u32,
+    pub timeout_ms: u64,
+    pub asymmetry: f64,
+    /// Missing from recordings made before syncs had a deadline.
+    #[serde(default = "default_deadline_secs")]
+    pub deadline_secs: u64,
+}
+
+fn default_deadline_secs() -> u64 {
+    sync_profile::DEFAULT_DEADLINE_SECS
+}
+
+impl From<&SyncProfile> for RecordedProfile {
+    fn from(profile: &SyncProfile) -> Self {
+        Self {
+            id: profile.id.to_string(),
+            probe_count: profile.probe_count,
+            min_interval_ms: profile.min_interval_ms,
+            min_spacing_ms: profile.min_spacing_ms,
+            max_retries: profile.max_retries,
+            burst_size: profile.burst_size,
+            timeout_ms: profile.timeout_ms,
+            asymmetry: profile.asymmetry,
+            deadline_secs: profile.deadline_secs,
+        }
+    }
+}
+
+impl RecordedProfile {
+    /// The recorded numbers on top of the preset of the same id, which
+    /// only contributes its label.
+    pub fn to_profile(&self) -> SyncProfile {
+        SyncProfile {
+            probe_count: self.probe_count,
+            min_interval_ms: self.min_interval_ms,
+            min_spacing_ms: self.min_spacing_ms,
+            max_retries: self.max_retries,
+            burst_size: self.burst_size,
+            timeout_ms: self.timeout_ms,
+            asymmetry: self.asymmetry,
+            deadline_secs: self.deadline_secs,
+            ..sync_profile::find(&self.id).unwrap_or(sync_profile::BALANCED)
+        }
+    }
+
+    /// The first number no sync could have run with, if any. Recordings
+    /// are files a user can edit or pass around, and the engine trusts its
+    /// profile.
+    fn out_of_range(&self) -> Option<&'static str> {
+        let checks = [
+            ("probe_count", (1..=100).contains(&self.probe_count)),
+            (
+                "min_interval_ms",
+                (1..=60_000).contains(&self.min_interval_ms),
+            ),
+            (
+                "min_spacing_ms",
+                (1..=60_000).contains(&self.min_spacing_ms),
+            ),
+            ("max_retries", self.max_retries <= 100),
+            ("burst_size", (1..=10).contains(&self.burst_size)),
+            ("timeout_ms", (100..=120_000).contains(&self.timeout_ms)),
+            (
+                "asymmetry",
+                (asymmetry::MIN..=asymmetry::MAX).contains(&self.asymmetry),
+            ),
+            ("deadline_secs", (1..=3_600).contains(&self.deadline_secs)),
+        ];
+        checks
+            .into_iter()
+            .find(|(_, ok)| !ok)
+            .map(|(field, _)| field)
+    }
+}
+
+/// What a `ProbeRecorder` collected over one run.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RecordedRun {
+    /// Local wall-clock time the engine started, unix seconds.
+    pub started_at: f64,
+    pub probes: Vec<RecordedProbe>,
+}
+
+/// Collects a run's probes; handed to the engine through `ProbeSettings`.
+#[derive(Default)]
+pub struct ProbeRecorder {
+    run: Mutex<RecordedRun>,
+}
+
+impl ProbeRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn begin(&self, started_at: f64) {
+        *self.run.lock().unwrap() = RecordedRun {
+            started_at,
+            probes: Vec::new(),
+        };
+    }
+
+    pub fn push(&self, probe: RecordedProbe) {
+        self.run.lock().unwrap().probes.push(probe);
+    }
+
+    pub fn take(&self) -> RecordedRun {
+        std::mem::take(&mut *self.run.lock().unwrap())
+    }
+}
+
+/// A sync run's probes and outcome, written when `record_syncs` is on and
+/// fed back through the engine by `sync_engine::replay`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncRecording {
+    pub version: u32,
+    pub server_id: i64,
+    pub url: String,
+    pub extractor_type: String,
+    pub profile: RecordedProfile,
+    pub recorded_at: DateTime<Utc>,
+    pub started_at: f64,
+    pub probes: Vec<RecordedProbe>,
+    /// What the recorded run concluded: its offset, or its error.
+    pub offset_ms: Option<f64>,
+    pub error: Option<String>,
+}
+
+impl SyncRecording {
+    pub fn new(
+        server: &Server,
+        profile: &SyncProfile,
+        run: RecordedRun,
+        outcome: &Result<SyncResult, SyncFailure>,
+    ) -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            server_id: server.id,
+            url: server.url.clone(),
+            extractor_type: server.extractor_type.clone(),
+            profile: profile.into(),
+            recorded_at: Utc::now(),
+            started_at: run.started_at,
+            probes: run.probes,
+            offset_ms: outcome.as_ref().ok().map(|r| r.total_offset_ms),
+            error: outcome.as_ref().err().map(|f| f.error.to_string()),
+        }
+    }
+}
+
+/// A saved recording, as listed in the UI.
+#[derive(Debug, Clone, Serialize)]
+pub struct RecordingInfo {
+    /// File name inside the recordings directory.
+    pub file: String,
+    pub server_id: i64,
+    pub url: String,
+    pub recorded_at: DateTime<Utc>,
+    pub probes: usize,
+    pub offset_ms: Option<f64>,
+    pub error: Option<String>,
+}
+
+/// A replayed recording next to what the live run concluded.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayReport {
+    pub recorded_offset_ms: Option<f64>,
+    pub recorded_error: Option<String>,
+    pub result: Option<SyncResult>,
+    pub error: Option<String>,
+    /// Recorded probes the replay never asked for. Anything but 0 means
+    /// the engine took a different path than it did live.
+    pub unused_probes: usize,
+}
+
+pub fn recording_dir(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let dir = app.path().app_data_dir().map_err(|e| {
+        AppError::Io(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            e.to_string(),
+        ))
+    })?;
+    Ok(dir.join(RECORDING_DIR))
+}
+
+/// Write `recording` to `dir` as `<server id>-<time>.json`.
+pub fn save(dir: &Path, recording: &SyncRecording) -> Result<PathBuf, AppError> {
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(format!(
+        "{}-{}.json",
+        recording.server_id,
+        recording.recorded_at.format("%Y%m%dT%H%M%S%.3f")
+    ));
+    let json = serde_json::to_string_pretty(recording)
+        .map_err(|e| AppError::Replay(format!("recording not serializable: {e}")))?;
+    std::fs::write(&path, json)?;
+    Ok(path)
+}
+
+pub fn load(path: &Path) -> Result<SyncRecording, AppError> {
+    let recording: SyncRecording = serde_json::from_str(&std::fs::read_to_string(path)?)
+        .map_err(|e| AppError::Replay(format!("not a sync recording: {e}")))?;
+    if recording.version > FORMAT_VERSION {
+        return Err(AppError::Replay(format!(
+            "recording format {} is newer than this build reads",
+            recording.version
+        )));
+    }
+    if let Some(field) = recording.profile.out_of_range() {
+        return Err(AppError::Replay(format!(
+            "recorded profile has an impossible {field}"
+        )));
+    }
+    Ok(recording)
+}
+
+/// Readable recordings in `dir`, newest first.
+pub fn list(dir: &Path) -> Vec<RecordingInfo> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut recordings: Vec<RecordingInfo> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| {
+            let recording = load(&path).ok()?;
+            Some(RecordingInfo {
+                file: path.file_name()?.to_string_lossy().into_owned(),
+                server_id: recording.server_id,
+                url: recording.url,
+                recorded_at: recording.recorded_at,
+                probes: recording.probes.len(),
+                offset_ms: recording.offset_ms,
+                error: recording.error,
+            })
+        })
+        .collect();
+    recordings.sort_by_key(|r| std::cmp::Reverse(r.recorded_at));
+    recordings
+}
+
+/// `file` inside `dir`; anything that could point elsewhere is refused.
+pub fn path_in(dir: &Path, file: &str) -> Result<PathBuf, AppError> {
+    if file.is_empty() || file.contains(['/', '\\']) || file.starts_with('.') {
+        return Err(AppError::Validation(vec![FieldError {
+            field: "file".to_string(),
+            message: "must be a file name in the recordings directory".to_string(),
+        }]));
+    }
+    Ok(dir.join(file))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_round_trips_with_scaled_numbers() {
+        let profile = SyncProfile {
+            min_interval_ms: 2000,
+            asymmetry: 0.4,
+            ..sync_profile::POLITE
+        };
+        let recorded = RecordedProfile::from(&profile);
+        assert_eq!(recorded.to_profile(), profile);
+    }
+
+    #[test]
+    fn recordings_save_load_and_list() {
+        let dir = std::env::temp_dir().join(format!("ticketime-rec-{}", std::process::id()));
+        let recording = SyncRecording {
+            version: FORMAT_VERSION,
+            server_id: 3,
+            url: "https://example.com".to_string(),
+            extractor_type: "date_header".to_string(),
+            profile: (&sync_profile::BALANCED).into(),
+            recorded_at: Utc::now(),
+            started_at: 1_700_000_000.25,
+            probes: vec![RecordedProbe {
+                sent_at: 1_700_000_000.25,
+                rtt: 0.04,
+                server_second: None,
+                error: Some(RecordedError::Transient("connection reset".to_string())),
+            }],
+            offset_ms: None,
+            error: Some("max retries exceeded (5 attempts)".to_string()),
+        };
+        let path = save(&dir, &recording).unwrap();
+        let loaded = load(&path).unwrap();
+        assert_eq!(loaded.probes, recording.probes);
+
+        let listed = list(&dir);
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].probes, 1);
+        assert_eq!(path_in(&dir, &listed[0].file).unwrap(), path);
+        assert!(path_in(&dir, "../ticketime.db").is_err());
+
+        // Numbers the engine cannot run with are refused on load.
+        let mut edited = recording.clone();
+        edited.profile.min_interval_ms = 0;
+        let path = save(&dir, &edited).unwrap();
+        assert!(matches!(load(&path), Err(AppError::Replay(_))));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,244 @@
+use crate::clock_issue::ClockIssue;
+use crate::error::AppError;
+use crate::models::{LatencyProfile, PhaseTiming, RttBreakdown, Server, SyncPhase, SyncResult};
+use crate::settings_validation::FieldError;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::fmt::Write;
+
+pub const MARKDOWN: &str = "markdown";
+pub const JSON: &str = "json";
+
+/// A stored sync, summarised for pasting into a group chat.
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncReport {
+    pub sync_id: i64,
+    pub server_id: i64,
+    pub server: String,
+    pub synced_at: DateTime<Utc>,
+    pub offset_ms: f64,
+    /// Set when the offset says the server's clock is misconfigured.
+    pub clock_issue: Option<ClockIssue>,
+    /// Half the fastest round trip: the worst case if the whole trip was
+    /// spent on one leg of the path.
+    pub uncertainty_ms: f64,
+    pub verified: bool,
+    /// Path asymmetry the sync corrected for, if any.
+    pub asymmetry: Option<f64>,
+    pub phase_reached: SyncPhase,
+    pub duration_ms: u64,
+    /// Round-trip times in milliseconds.
+    pub latency_ms: LatencyProfile,
+    pub timeline: Vec<PhaseTiming>,
+}
+
+impl SyncReport {
+    pub fn new(server: &Server, result: &SyncResult) -> Self {
+        let p = &result.latency_profile;
+        let latency_ms = LatencyProfile {
+            min: p.min * 1000.0,
+            q1: p.q1 * 1000.0,
+            median: p.median * 1000.0,
+            mean: p.mean * 1000.0,
+            q3: p.q3 * 1000.0,
+            max: p.max * 1000.0,
+            breakdown: p.breakdown.map(|b| RttBreakdown {
+                dns: b.dns * 1000.0,
+                connect: b.connect * 1000.0,
+                ttfb: b.ttfb * 1000.0,
+                ..b
+            }),
+        };
+        Self {
+            sync_id: result.id,
+            server_id: server.id,
+            server: server.name.clone().unwrap_or_else(|| server.url.clone()),
+            synced_at: result.synced_at,
+            offset_ms: result.total_offset_ms,
+            clock_issue: result.clock_issue.clone(),
+            uncertainty_ms: latency_ms.min / 2.0,
+            verified: result.verified,
+            asymmetry: result.asymmetry,
+            phase_reached: result.phase_reached,
+            duration_ms: result.duration_ms,
+            latency_ms,
+            timeline: result.timeline.clone(),
+        }
+    }
+
+    /// `format` is `"markdown"` or `"json"`.
+    pub fn render(&self, format: &str) -> Result<String, AppError> {
+        match format {
+            MARKDOWN => Ok(self.markdown()),
+            JSON => Ok(serde_json::to_string_pretty(self).expect("report is serializable")),
+            other => Err(AppError::Validation(vec![FieldError {
+                field: "format".to_string(),
+                message: format!("unknown report format \"{other}\" (use markdown or json)"),
+            }])),
+        }
+    }
+
+    fn markdown(&self) -> String {
+        let mut out = String::new();
+        let ahead = if self.offset_ms >= 0.0 {
+            "ahead of"
+        } else {
+            "behind"
+        };
+        let _ = writeln!(out, "**{}** sync report", self.server);
+        let _ = writeln!(out);
+        let _ = writeln!(
+            out,
+            "- Offset: **{:+.1} ms** ± {:.1} ms (server clock {} ours)",
+            self.offset_ms, self.uncertainty_ms, ahead
+        );
+        if let Some(issue) = &self.clock_issue {
+            let _ = writeln!(out, "- Warning: {}", issue.label);
+        }
+        let _ = writeln!(
+            out,
+            "- Verified: {}",
+            if self.verified { "yes" } else { "no" }
+        );
+        let _ = writeln!(
+            out,
+            "- Synced: {} in {:.1} s",
+            self.synced_at.format("%Y-%m-%d %H:%M:%S UTC"),
+            self.duration_ms as f64 / 1000.0
+        );
+        let l = &self.latency_ms;
+        let _ = writeln!(
+            out,
+            "- RTT: min {:.1} / median {:.1} / max {:.1} ms (IQR {:.1} ms)",
+            l.min,
+            l.median,
+            l.max,
+            l.iqr()
+        );
+        if let Some(asymmetry) = self.asymmetry {
+            let _ = writeln!(
+                out,
+                "- Path asymmetry: {:.0}% of the round trip outbound",
+                asymmetry * 100.0
+            );
+        }
+        if let Some(b) = &l.breakdown {
+            let _ = writeln!(
+                out,
+                "- RTT split: DNS {:.1} / connect {:.1} / TTFB {:.1} ms ({} new connections)",
+                b.dns, b.connect, b.ttfb, b.new_connections
+            );
+        }
+        if !self.timeline.is_empty() {
+            let _ = writeln!(out);
+            let _ = writeln!(out, "| Phase | Time | Probes |");
+            let _ = writeln!(out, "|---|---:|---:|");
+            for step in &self.timeline {
+                let _ = writeln!(
+                    out,
+                    "| {} | {:.1} s | {} |",
+                    phase_label(step.phase),
+                    step.duration_ms as f64 / 1000.0,
+                    step.probes
+                );
+            }
+        }
+        out
+    }
+}
+
+fn phase_label(phase: SyncPhase) -> &'static str {
+    match phase {
+        SyncPhase::LatencyProfiling => "Latency profiling",
+        SyncPhase::WholeSecondOffset => "Whole-second offset",
+        SyncPhase::BinarySearch => "Binary search",
+        SyncPhase::Verification => "Verification",
+        SyncPhase::Complete => "Complete",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ServerStatus;
+
+    fn report() -> SyncReport {
+        let server = Server {
+            id: 4,
+            url: "https://tickets.example.com".to_string(),
+            name: Some("Tickets".to_string()),
+            offset_ms: Some(-1234.5),
+            last_sync_at: None,
+            created_at: Utc::now(),
+            status: ServerStatus::Synced,
+            extractor_type: "date_header".to_string(),
+            probe_method: "HEAD".to_string(),
+            domain_preset: None,
+            sync_profile: None,
+            fingerprint: None,
+            asymmetry: None,
+            sync_on_launch: false,
+            health_score: None,
+            drift: None,
+            min_request_interval_ms: None,
+            trusted: false,
+            user_agent: None,
+            transport: Default::default(),
+            timezone: None,
+            timezone_guessed: false,
+        };
+        let result = SyncResult {
+            server_id: 4,
+            whole_second_offset: -2,
+            subsecond_offset: 0.7655,
+            total_offset_ms: -1234.5,
+            latency_profile: LatencyProfile {
+                min: 0.030,
+                q1: 0.032,
+                median: 0.034,
+                mean: 0.035,
+                q3: 0.038,
+                max: 0.050,
+                breakdown: None,
+            },
+            verified: true,
+            synced_at: Utc::now(),
+            duration_ms: 8_200,
+            phase_reached: SyncPhase::Complete,
+            fingerprint: None,
+            asymmetry: None,
+            anomalous: false,
+            clock_issue: None,
+            network: None,
+            grade: None,
+            precision_ms: None,
+            response: None,
+            id: 17,
+            timeline: vec![PhaseTiming {
+                phase: SyncPhase::LatencyProfiling,
+                duration_ms: 1_500,
+                probes: 10,
+            }],
+            note: None,
+        };
+        SyncReport::new(&server, &result)
+    }
+
+    #[test]
+    fn markdown_summarises_offset_and_timeline() {
+        let text = report().render(MARKDOWN).unwrap();
+        assert!(text.starts_with("**Tickets** sync report"));
+        assert!(text.contains("-1234.5 ms** ± 15.0 ms (server clock behind ours)"));
+        assert!(text.contains("Verified: yes"));
+        assert!(text.contains("| Latency profiling | 1.5 s | 10 |"));
+    }
+
+    #[test]
+    fn json_reports_latency_in_milliseconds() {
+        let json: serde_json::Value =
+            serde_json::from_str(&report().render(JSON).unwrap()).unwrap();
+        assert_eq!(json["sync_id"], 17);
+        assert_eq!(json["latency_ms"]["min"], 30.0);
+        assert!(report().render("pdf").is_err());
+    }
+}
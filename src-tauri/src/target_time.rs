@@ -0,0 +1,190 @@
+use crate::error::AppError;
+use chrono::{
+    DateTime, Days, Local, LocalResult, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc,
+};
+use chrono_tz::Tz;
+use std::str::FromStr;
+
+/// Suffix for a wall-clock time in the server's timezone.
+pub const SERVER_LOCAL: &str = "server-local";
+
+/// Whose wall clock a typed time is read on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WallZone {
+    /// The server's presumed timezone.
+    Server,
+    /// This machine's timezone.
+    Local,
+    Utc,
+}
+
+/// A target moment as typed: an RFC 3339 instant, or `[YYYY-MM-DD] HH:MM[:SS]`
+/// followed by `server-local`, `local` or `utc` (`local` when left out).
+/// Without a date the time is its next occurrence.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TargetTime {
+    Instant(DateTime<Utc>),
+    Wall {
+        date: Option<NaiveDate>,
+        time: NaiveTime,
+        zone: WallZone,
+    },
+}
+
+impl FromStr for TargetTime {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Ok(instant) = DateTime::parse_from_rfc3339(s) {
+            return Ok(TargetTime::Instant(instant.with_timezone(&Utc)));
+        }
+        let invalid = || AppError::InvalidTargetTime(s.to_string());
+
+        let mut words: Vec<&str> = s.split_whitespace().collect();
+        let zone = match words.last().map(|w| w.to_ascii_lowercase()).as_deref() {
+            Some(SERVER_LOCAL) => Some(WallZone::Server),
+            Some("local") => Some(WallZone::Local),
+            Some("utc") => Some(WallZone::Utc),
+            _ => None,
+        };
+        if zone.is_some() {
+            words.pop();
+        }
+        let (date, time) = match words.as_slice() {
+            [time] => (None, *time),
+            [date, time] => (
+                Some(NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| invalid())?),
+                *time,
+            ),
+            _ => return Err(invalid()),
+        };
+        let time = NaiveTime::parse_from_str(time, "%H:%M:%S")
+            .or_else(|_| NaiveTime::parse_from_str(time, "%H:%M"))
+            .map_err(|_| invalid())?;
+        Ok(TargetTime::Wall {
+            date,
+            time,
+            zone: zone.unwrap_or(WallZone::Local),
+        })
+    }
+}
+
+impl TargetTime {
+    /// The instant meant, with `server_tz` for `server-local` times. A
+    /// time skipped by a daylight-saving change is an error; one that
+    /// happens twice is its first occurrence.
+    pub fn resolve(
+        &self,
+        server_tz: Option<Tz>,
+        now: DateTime<Utc>,
+    ) -> Result<DateTime<Utc>, AppError> {
+        let (date, time, zone) = match *self {
+            TargetTime::Instant(at) => return Ok(at),
+            TargetTime::Wall { date, time, zone } => (date, time, zone),
+        };
+        match zone {
+            WallZone::Server => {
+                let tz = server_tz.ok_or_else(|| {
+                    AppError::InvalidTargetTime(
+                        "the server has no timezone; set one or give a UTC offset".to_string(),
+                    )
+                })?;
+                on_wall_clock(&tz, date, time, now)
+            }
+            WallZone::Local => on_wall_clock(&Local, date, time, now),
+            WallZone::Utc => on_wall_clock(&Utc, date, time, now),
+        }
+    }
+}
+
+fn on_wall_clock<Z: TimeZone>(
+    zone: &Z,
+    date: Option<NaiveDate>,
+    time: NaiveTime,
+    now: DateTime<Utc>,
+) -> Result<DateTime<Utc>, AppError> {
+    let at = |day: NaiveDate| match zone.from_local_datetime(&NaiveDateTime::new(day, time)) {
+        LocalResult::Single(at) | LocalResult::Ambiguous(at, _) => Ok(at.with_timezone(&Utc)),
+        LocalResult::None => Err(AppError::InvalidTargetTime(format!(
+            "{day} {time} does not exist there (the clocks skip it for daylight saving)"
+        ))),
+    };
+    match date {
+        Some(day) => at(day),
+        None => {
+            let today = now.with_timezone(zone).date_naive();
+            let first = at(today)?;
+            if first > now {
+                Ok(first)
+            } else {
+                at(today + Days::new(1))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seoul() -> Option<Tz> {
+        Some(chrono_tz::Asia::Seoul)
+    }
+
+    #[test]
+    fn server_local_times_use_the_server_zone() {
+        let now: DateTime<Utc> = "2025-06-01T00:30:00Z".parse().unwrap();
+        // 09:30 in Seoul: 10:00 there is still ahead today.
+        let target: TargetTime = "10:00 server-local".parse().unwrap();
+        assert_eq!(
+            target.resolve(seoul(), now).unwrap(),
+            "2025-06-01T01:00:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+        // 09:00 has passed, so it is tomorrow's.
+        let passed: TargetTime = "09:00 server-local".parse().unwrap();
+        assert_eq!(
+            passed.resolve(seoul(), now).unwrap(),
+            "2025-06-02T00:00:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+        assert!(matches!(
+            target.resolve(None, now),
+            Err(AppError::InvalidTargetTime(_))
+        ));
+    }
+
+    #[test]
+    fn daylight_saving_gaps_fail_and_overlaps_take_the_first() {
+        let berlin = Some(chrono_tz::Europe::Berlin);
+        let now: DateTime<Utc> = "2025-01-01T00:00:00Z".parse().unwrap();
+        let skipped: TargetTime = "2025-03-30 02:30 server-local".parse().unwrap();
+        assert!(skipped.resolve(berlin, now).is_err());
+        let twice: TargetTime = "2025-10-26 02:30 server-local".parse().unwrap();
+        assert_eq!(
+            twice.resolve(berlin, now).unwrap(),
+            "2025-10-26T00:30:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+    }
+
+    #[test]
+    fn parses_instants_and_rejects_nonsense() {
+        let instant: TargetTime = "2025-06-01T19:00:00+09:00".parse().unwrap();
+        assert_eq!(
+            instant,
+            TargetTime::Instant("2025-06-01T10:00:00Z".parse().unwrap())
+        );
+        let utc: TargetTime = "2025-06-01 10:00:30 UTC".parse().unwrap();
+        assert_eq!(
+            utc.resolve(None, Utc::now()).unwrap(),
+            "2025-06-01T10:00:30Z".parse::<DateTime<Utc>>().unwrap()
+        );
+        for bad in [
+            "tomorrow",
+            "25:00 utc",
+            "10:00 mars-local",
+            "2025-13-01 10:00",
+        ] {
+            assert!(bad.parse::<TargetTime>().is_err(), "{bad}");
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::window::{ProgressBarState, ProgressBarStatus};
+use tauri::{AppHandle, Manager};
+
+/// Mirrors running syncs onto the taskbar button (Windows) or dock icon
+/// (macOS). With several syncs in flight the indicator shows their average.
+#[derive(Default)]
+pub struct TaskbarProgress {
+    inner: Mutex<Progress>,
+}
+
+#[derive(Default)]
+struct Progress {
+    syncs: HashMap<i64, f64>,
+    shown: Option<u64>,
+}
+
+impl TaskbarProgress {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record `percent` for server `id` and refresh the indicator if the
+    /// whole-percent value changed.
+    pub fn update(&self, app: &AppHandle, id: i64, percent: f64) {
+        let mut progress = self.inner.lock().expect("taskbar progress poisoned");
+        progress.syncs.insert(id, percent);
+        let next = aggregate(&progress.syncs);
+        if next != progress.shown {
+            progress.shown = next;
+            apply(app, next);
+        }
+    }
+
+    /// Forget server `id`; the indicator is cleared once no sync remains.
+    pub fn finish(&self, app: &AppHandle, id: i64) {
+        let mut progress = self.inner.lock().expect("taskbar progress poisoned");
+        progress.syncs.remove(&id);
+        let next = aggregate(&progress.syncs);
+        if next != progress.shown {
+            progress.shown = next;
+            apply(app, next);
+        }
+    }
+}
+
+/// Average progress across running syncs, or `None` when nothing is running.
+fn aggregate(syncs: &HashMap<i64, f64>) -> Option<u64> {
+    if syncs.is_empty() {
+        return None;
+    }
+    let total: f64 = syncs.values().map(|p| p.clamp(0.0, 100.0)).sum();
+    Some((total / syncs.len() as f64).floor() as u64)
+}
+
+fn apply(app: &AppHandle, percent: Option<u64>) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+
+    let state = match percent {
+        Some(p) => ProgressBarState {
+            status: Some(ProgressBarStatus::Normal),
+            progress: Some(p),
+        },
+        None => ProgressBarState {
+            status: Some(ProgressBarStatus::None),
+            progress: None,
+        },
+    };
+    if let Err(e) = window.set_progress_bar(state) {
+        log::debug!("failed to set taskbar progress: {e}");
+    }
+
+    // The dock has no progress bar of its own; a percentage badge stands in.
+    #[cfg(target_os = "macos")]
+    if let Err(e) = window.set_badge_label(percent.map(|p| format!("{p}%"))) {
+        log::debug!("failed to set dock badge: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aggregate_is_none_without_syncs() {
+        assert_eq!(aggregate(&HashMap::new()), None);
+    }
+
+    #[test]
+    fn aggregate_averages_running_syncs() {
+        let syncs = HashMap::from([(1, 20.0), (2, 70.0)]);
+        assert_eq!(aggregate(&syncs), Some(45));
+    }
+
+    #[test]
+    fn aggregate_clamps_and_floors() {
+        let syncs = HashMap::from([(1, 140.0), (2, 59.9)]);
+        assert_eq!(aggregate(&syncs), Some(79));
+    }
+}
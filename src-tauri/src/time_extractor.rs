@@ -1,4 +1,8 @@
 use crate::error::AppError;
+use crate::wasm_extractor::{self, WasmExtractor};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
 
 /// Trait for extracting server time from an HTTP response.
 /// Implement this trait to support different time source strategies.
@@ -6,10 +10,20 @@ pub trait TimeExtractor: Send + Sync {
     /// Human-readable name of this extraction strategy.
     fn name(&self) -> &str;
 
+    /// Whether `extract_time` needs the response body. The probe reads it
+    /// (up to its drain limit) only for extractors that do; everyone else
+    /// gets an empty slice.
+    fn reads_body(&self) -> bool {
+        false
+    }
+
     /// Extract the server's unix timestamp (whole seconds) from the response.
-    fn extract_time(&self, response: &reqwest::Response) -> Result<i64, AppError>;
+    fn extract_time(&self, response: &reqwest::Response, body: &[u8]) -> Result<i64, AppError>;
 }
 
+/// `extractor_type` of the built-in `Date` header extractor.
+pub const DATE_HEADER: &str = "date_header";
+
 /// Default extractor: parses the standard HTTP `Date` response header.
 pub struct DateHeaderExtractor;
 
@@ -18,7 +32,7 @@ impl TimeExtractor for DateHeaderExtractor {
         "Date Header"
     }
 
-    fn extract_time(&self, response: &reqwest::Response) -> Result<i64, AppError> {
+    fn extract_time(&self, response: &reqwest::Response, _body: &[u8]) -> Result<i64, AppError> {
         let date_str = response
             .headers()
             .get("date")
@@ -33,6 +47,166 @@ impl TimeExtractor for DateHeaderExtractor {
     }
 }
 
+/// `extractor_type`s of the built-in public time API extractors.
+pub const WORLDTIMEAPI: &str = "worldtimeapi";
+pub const TIMEAPI_IO: &str = "timeapi_io";
+pub const NIST: &str = "nist_time_gov";
+
+/// Built-in `extractor_type`s, in the order they are listed.
+const BUILTINS: [&str; 4] = [DATE_HEADER, WORLDTIMEAPI, TIMEAPI_IO, NIST];
+
+/// Reads the time from the body of a public time API. These report finer
+/// than a second, but the sync engine works from second transitions like
+/// it does for `Date`, so only whole seconds are returned.
+pub struct BodyExtractor {
+    name: &'static str,
+    parse: fn(&str) -> Option<i64>,
+}
+
+impl TimeExtractor for BodyExtractor {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn reads_body(&self) -> bool {
+        true
+    }
+
+    fn extract_time(&self, _response: &reqwest::Response, body: &[u8]) -> Result<i64, AppError> {
+        std::str::from_utf8(body)
+            .ok()
+            .and_then(self.parse)
+            .ok_or_else(|| AppError::InvalidTimeResponse(format!("not a {} response", self.name)))
+    }
+}
+
+/// worldtimeapi.org: JSON with `unixtime` in seconds.
+fn parse_worldtimeapi(body: &str) -> Option<i64> {
+    let json: serde_json::Value = serde_json::from_str(body).ok()?;
+    json.get("unixtime")?.as_i64()
+}
+
+/// timeapi.io: JSON with `dateTime`, local to the requested zone (UTC) and
+/// without an offset.
+fn parse_timeapi_io(body: &str) -> Option<i64> {
+    let json: serde_json::Value = serde_json::from_str(body).ok()?;
+    let date_time = json.get("dateTime")?.as_str()?;
+    chrono::NaiveDateTime::parse_from_str(date_time, "%Y-%m-%dT%H:%M:%S%.f")
+        .ok()
+        .map(|dt| dt.and_utc().timestamp())
+}
+
+/// time.gov `actualtime.cgi`: `<timestamp time="..." .../>` in unix
+/// microseconds.
+fn parse_nist(body: &str) -> Option<i64> {
+    let rest = &body[body.find("time=\"")? + 6..];
+    let micros: i64 = rest[..rest.find('"')?].parse().ok()?;
+    Some(micros.div_euclid(1_000_000))
+}
+
+fn builtin(id: &str) -> Option<Arc<dyn TimeExtractor>> {
+    let body = |name, parse| -> Arc<dyn TimeExtractor> { Arc::new(BodyExtractor { name, parse }) };
+    match id {
+        DATE_HEADER => Some(Arc::new(DateHeaderExtractor)),
+        WORLDTIMEAPI => Some(body("WorldTimeAPI", parse_worldtimeapi)),
+        TIMEAPI_IO => Some(body("TimeAPI.io", parse_timeapi_io)),
+        NIST => Some(body("NIST time.gov", parse_nist)),
+        _ => None,
+    }
+}
+
+/// An extractor a server can name in `extractor_type`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExtractorInfo {
+    pub id: String,
+    pub name: String,
+    /// The plugin file, for WASM extractors.
+    pub plugin: Option<PathBuf>,
+}
+
+/// A plugin file that could not be loaded.
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginFailure {
+    pub file: PathBuf,
+    pub error: String,
+}
+
+/// Built-in extractors plus the WASM plugins found in the plugins
+/// directory, looked up by a server's `extractor_type`.
+pub struct ExtractorRegistry {
+    plugins: RwLock<Vec<(ExtractorInfo, Arc<WasmExtractor>)>>,
+}
+
+impl ExtractorRegistry {
+    pub fn new() -> Self {
+        Self {
+            plugins: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Replace the loaded plugins with the `*.wasm` files in `dir`. A
+    /// missing directory just means no plugins.
+    pub fn load_plugins(&self, dir: &Path) -> Vec<PluginFailure> {
+        let mut plugins = Vec::new();
+        let mut failed = Vec::new();
+        for (path, loaded) in wasm_extractor::load_dir(dir) {
+            match loaded {
+                Ok(extractor) => {
+                    let info = ExtractorInfo {
+                        id: wasm_extractor::id_for(&path),
+                        name: extractor.name().to_string(),
+                        plugin: Some(path),
+                    };
+                    log::info!("loaded extractor plugin {} ({})", info.id, info.name);
+                    plugins.push((info, Arc::new(extractor)));
+                }
+                Err(e) => {
+                    log::warn!("extractor plugin {} not loaded: {e}", path.display());
+                    failed.push(PluginFailure {
+                        file: path,
+                        error: e.to_string(),
+                    });
+                }
+            }
+        }
+        *self.plugins.write().unwrap() = plugins;
+        failed
+    }
+
+    /// Built-ins first, then plugins in file name order.
+    pub fn list(&self) -> Vec<ExtractorInfo> {
+        let builtins = BUILTINS.into_iter().filter_map(|id| {
+            builtin(id).map(|extractor| ExtractorInfo {
+                id: id.to_string(),
+                name: extractor.name().to_string(),
+                plugin: None,
+            })
+        });
+        builtins
+            .chain(
+                self.plugins
+                    .read()
+                    .unwrap()
+                    .iter()
+                    .map(|(info, _)| info.clone()),
+            )
+            .collect()
+    }
+
+    pub fn get(&self, id: &str) -> Result<Arc<dyn TimeExtractor>, AppError> {
+        if let Some(extractor) = builtin(id) {
+            return Ok(extractor);
+        }
+        self.plugins
+            .read()
+            .unwrap()
+            .iter()
+            .find(|(info, _)| info.id == id)
+            .map(|(_, extractor)| Arc::clone(extractor) as Arc<dyn TimeExtractor>)
+            .ok_or_else(|| AppError::UnknownExtractor(id.to_string()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -64,14 +238,14 @@ mod tests {
     fn extract_time_valid_date_header() {
         // Wed, 21 Oct 2015 07:28:00 GMT  ->  unix timestamp 1445412480
         let resp = mock_response_with_date("Wed, 21 Oct 2015 07:28:00 GMT");
-        let ts = DateHeaderExtractor.extract_time(&resp).unwrap();
+        let ts = DateHeaderExtractor.extract_time(&resp, &[]).unwrap();
         assert_eq!(ts, 1_445_412_480);
     }
 
     #[test]
     fn extract_time_missing_date_header_returns_no_date_header_error() {
         let resp = mock_response_no_date();
-        let err = DateHeaderExtractor.extract_time(&resp).unwrap_err();
+        let err = DateHeaderExtractor.extract_time(&resp, &[]).unwrap_err();
         assert!(
             matches!(err, AppError::NoDateHeader),
             "expected NoDateHeader, got: {err}"
@@ -81,10 +255,59 @@ mod tests {
     #[test]
     fn extract_time_invalid_date_format_returns_invalid_date_header_error() {
         let resp = mock_response_with_date("not-a-real-date");
-        let err = DateHeaderExtractor.extract_time(&resp).unwrap_err();
+        let err = DateHeaderExtractor.extract_time(&resp, &[]).unwrap_err();
         assert!(
             matches!(err, AppError::InvalidDateHeader(_)),
             "expected InvalidDateHeader, got: {err}"
         );
     }
+
+    #[test]
+    fn public_time_api_bodies_give_whole_seconds() {
+        let resp = mock_response_no_date();
+        let extract =
+            |id: &str, body: &str| builtin(id).unwrap().extract_time(&resp, body.as_bytes());
+        assert_eq!(
+            extract(
+                WORLDTIMEAPI,
+                r#"{"datetime":"2015-10-21T07:28:00.512+00:00","unixtime":1445412480}"#
+            )
+            .unwrap(),
+            1_445_412_480
+        );
+        assert_eq!(
+            extract(
+                TIMEAPI_IO,
+                r#"{"year":2015,"dateTime":"2015-10-21T07:28:00.5123456","timeZone":"UTC"}"#
+            )
+            .unwrap(),
+            1_445_412_480
+        );
+        assert_eq!(
+            extract(
+                NIST,
+                r#"<timestamp time="1445412480999999" delay="1445412480000000"/>"#
+            )
+            .unwrap(),
+            1_445_412_480
+        );
+        assert!(matches!(
+            extract(NIST, "<html>busy</html>"),
+            Err(AppError::InvalidTimeResponse(_))
+        ));
+    }
+
+    #[test]
+    fn registry_lists_builtins_first() {
+        let ids: Vec<String> = ExtractorRegistry::new()
+            .list()
+            .into_iter()
+            .map(|e| e.id)
+            .collect();
+        assert_eq!(ids, BUILTINS);
+        assert!(ExtractorRegistry::new()
+            .get(TIMEAPI_IO)
+            .unwrap()
+            .reads_body());
+    }
 }
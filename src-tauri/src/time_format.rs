@@ -0,0 +1,211 @@
+use crate::error::AppError;
+use crate::models::AppSettings;
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Local, TimeZone, Utc};
+use std::fmt::Display;
+
+pub const DEFAULT_PATTERN: &str = "%H:%M:%S";
+
+/// How corrected times are rendered on every surface: a strftime pattern
+/// plus the millisecond and timezone display settings. Fractional seconds
+/// go right after the pattern's seconds field (`%S` or `%T`), so patterns
+/// without seconds never show them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeFormat {
+    pattern: String,
+    fraction_digits: u8,
+    show_timezone_offset: bool,
+}
+
+impl TimeFormat {
+    pub fn new(
+        pattern: &str,
+        fraction_digits: u8,
+        show_timezone_offset: bool,
+    ) -> Result<Self, AppError> {
+        validate_pattern(pattern)?;
+        Ok(Self {
+            pattern: pattern.to_string(),
+            fraction_digits: fraction_digits.min(3),
+            show_timezone_offset,
+        })
+    }
+
+    /// Like [`TimeFormat::from_settings`] but rejects an invalid pattern
+    /// instead of falling back to the default.
+    pub fn try_from_settings(settings: &AppSettings) -> Result<Self, AppError> {
+        let digits = if settings.show_milliseconds {
+            settings.millisecond_precision
+        } else {
+            0
+        };
+        Self::new(&settings.time_format, digits, settings.show_timezone_offset)
+    }
+
+    pub fn from_settings(settings: &AppSettings) -> Self {
+        Self::try_from_settings(settings).unwrap_or_else(|_| {
+            Self::try_from_settings(&AppSettings {
+                time_format: DEFAULT_PATTERN.to_string(),
+                ..settings.clone()
+            })
+            .expect("default pattern is valid")
+        })
+    }
+
+    /// Same pattern and timezone, no fractional seconds — for surfaces that
+    /// only refresh once a second.
+    pub fn whole_seconds(&self) -> Self {
+        Self {
+            fraction_digits: 0,
+            ..self.clone()
+        }
+    }
+
+    pub fn format<Tz: TimeZone>(&self, time: &DateTime<Tz>) -> String
+    where
+        Tz::Offset: Display,
+    {
+        let mut out = match seconds_field_end(&self.pattern) {
+            Some(split) if self.fraction_digits > 0 => {
+                let (head, tail) = self.pattern.split_at(split);
+                format!(
+                    "{}{}{}",
+                    time.format(head),
+                    fraction(time.timestamp_subsec_millis(), self.fraction_digits),
+                    time.format(tail)
+                )
+            }
+            _ => time.format(&self.pattern).to_string(),
+        };
+        if self.show_timezone_offset {
+            out.push(' ');
+            out.push_str(&time.format("%:z").to_string());
+        }
+        out
+    }
+
+    /// Fractional-second digits shown, 0–3.
+    pub fn fraction_digits(&self) -> u8 {
+        self.fraction_digits
+    }
+
+    /// Format in the user's local timezone.
+    pub fn format_local(&self, time: DateTime<Utc>) -> String {
+        self.format(&time.with_timezone(&Local))
+    }
+}
+
+fn validate_pattern(pattern: &str) -> Result<(), AppError> {
+    if pattern.trim().is_empty() {
+        return Err(AppError::InvalidTimeFormat(
+            "pattern must not be empty".to_string(),
+        ));
+    }
+    if StrftimeItems::new(pattern).any(|item| matches!(item, Item::Error)) {
+        return Err(AppError::InvalidTimeFormat(pattern.to_string()));
+    }
+    Ok(())
+}
+
+/// Byte index just past the first `%S` / `%T` specifier (allowing chrono's
+/// padding modifiers), skipping escaped `%%`.
+fn seconds_field_end(pattern: &str) -> Option<usize> {
+    let mut chars = pattern.char_indices().peekable();
+    while let Some((_, c)) = chars.next() {
+        if c != '%' {
+            continue;
+        }
+        while let Some(&(_, m)) = chars.peek() {
+            if matches!(m, '-' | '_' | '0') {
+                chars.next();
+            } else {
+                break;
+            }
+        }
+        match chars.next() {
+            Some((i, 'S' | 'T')) => return Some(i + 1),
+            Some(_) => {}
+            None => return None,
+        }
+    }
+    None
+}
+
+fn fraction(millis: u32, digits: u8) -> String {
+    let digits = digits as usize;
+    let value = millis.min(999) / 10u32.pow(3 - digits as u32);
+    format!(".{value:0digits$}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    fn at(s: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(s).unwrap()
+    }
+
+    #[test]
+    fn default_pattern_without_extras() {
+        let f = TimeFormat::new(DEFAULT_PATTERN, 0, false).unwrap();
+        assert_eq!(f.format(&at("2025-06-01T10:00:05.678+09:00")), "10:00:05");
+    }
+
+    #[test]
+    fn fraction_follows_seconds_at_requested_precision() {
+        let t = at("2025-06-01T10:00:05.678+00:00");
+        let three = TimeFormat::new("%Y-%m-%d %H:%M:%S UTC", 3, false).unwrap();
+        assert_eq!(three.format(&t), "2025-06-01 10:00:05.678 UTC");
+        let one = TimeFormat::new("%T", 1, false).unwrap();
+        assert_eq!(one.format(&t), "10:00:05.6");
+        let two = TimeFormat::new("%T", 2, false).unwrap();
+        assert_eq!(
+            two.format(&at("2025-06-01T10:00:05.007+00:00")),
+            "10:00:05.00"
+        );
+    }
+
+    #[test]
+    fn patterns_without_seconds_never_show_fraction() {
+        let f = TimeFormat::new("%H:%M", 3, false).unwrap();
+        assert_eq!(f.format(&at("2025-06-01T10:00:05.678+00:00")), "10:00");
+    }
+
+    #[test]
+    fn escaped_percent_is_not_a_seconds_field() {
+        assert_eq!(seconds_field_end("%%S %S"), Some(6));
+        assert_eq!(seconds_field_end("%-S"), Some(3));
+        assert_eq!(seconds_field_end("%H:%M"), None);
+    }
+
+    #[test]
+    fn timezone_offset_is_appended() {
+        let f = TimeFormat::new(DEFAULT_PATTERN, 0, true).unwrap();
+        assert_eq!(
+            f.format(&at("2025-06-01T10:00:05+09:00")),
+            "10:00:05 +09:00"
+        );
+    }
+
+    #[test]
+    fn invalid_patterns_are_rejected() {
+        assert!(matches!(
+            TimeFormat::new("%Q", 0, false),
+            Err(AppError::InvalidTimeFormat(_))
+        ));
+        assert!(TimeFormat::new("  ", 0, false).is_err());
+    }
+
+    #[test]
+    fn from_settings_honours_display_flags_and_falls_back() {
+        let settings = AppSettings {
+            show_milliseconds: false,
+            time_format: "%Q".to_string(),
+            ..AppSettings::default()
+        };
+        let f = TimeFormat::from_settings(&settings);
+        assert_eq!(f.format(&at("2025-06-01T10:00:05.678+00:00")), "10:00:05");
+        assert!(TimeFormat::try_from_settings(&settings).is_err());
+    }
+}
@@ -0,0 +1,132 @@
+use crate::error::AppError;
+use crate::ntp::{self, NtpMeasurement};
+use crate::settings_validation::FieldError;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Values accepted by the `external_time_source` setting.
+pub const SOURCES: &[&str] = &["ntp", "google", "cloudflare", "http", "none"];
+
+/// Answers with `ts=<unix seconds>.<ms>` among other fields, over HTTPS, for
+/// networks that block NTP's UDP port.
+const HTTP_TIME_URL: &str = "https://cloudflare.com/cdn-cgi/trace";
+
+const HTTP_TIMEOUT: Duration = Duration::from_secs(5);
+const HTTP_SAMPLES: usize = 4;
+
+/// Where the local clock is checked against: clock-error measurement,
+/// system clock adjustment and asymmetry calibration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeSource {
+    Ntp(&'static str),
+    Http(&'static str),
+}
+
+impl TimeSource {
+    /// `None` for `"none"`: the user has opted out of external references.
+    pub fn from_setting(value: &str) -> Result<Option<Self>, AppError> {
+        match value {
+            "ntp" => Ok(Some(Self::Ntp(ntp::DEFAULT_NTP_HOST))),
+            "google" => Ok(Some(Self::Ntp("time.google.com"))),
+            "cloudflare" => Ok(Some(Self::Ntp("time.cloudflare.com"))),
+            "http" => Ok(Some(Self::Http(HTTP_TIME_URL))),
+            "none" => Ok(None),
+            other => Err(AppError::Validation(vec![FieldError {
+                field: "external_time_source".to_string(),
+                message: format!("unknown source {other}"),
+            }])),
+        }
+    }
+
+    pub async fn measure(self) -> Result<NtpMeasurement, AppError> {
+        match self {
+            Self::Ntp(host) => ntp::measure(host).await,
+            Self::Http(url) => measure_http(url).await,
+        }
+    }
+}
+
+/// Measure the local clock error against the configured source.
+pub async fn measure(setting: &str) -> Result<NtpMeasurement, AppError> {
+    TimeSource::from_setting(setting)?
+        .ok_or(AppError::TimeSourceDisabled)?
+        .measure()
+        .await
+}
+
+/// Like `ntp::measure`, keeping the sample with the shortest round trip.
+/// The connection is reused, so only the first sample pays for the TLS
+/// handshake.
+async fn measure_http(url: &str) -> Result<NtpMeasurement, AppError> {
+    let client = reqwest::Client::builder().timeout(HTTP_TIMEOUT).build()?;
+    let mut best: Option<(f64, f64)> = None;
+    let mut last_error = None;
+    for _ in 0..HTTP_SAMPLES {
+        let t1 = now_unix_ms();
+        let body = match client.get(url).send().await {
+            Ok(response) => response.text().await,
+            Err(e) => Err(e),
+        };
+        let t4 = now_unix_ms();
+        match body.map_err(AppError::from).and_then(|b| parse_trace(&b)) {
+            Ok(server_ms) => {
+                let round_trip_ms = t4 - t1;
+                if !matches!(best, Some((_, rtt)) if rtt <= round_trip_ms) {
+                    best = Some((server_ms - (t1 + t4) / 2.0, round_trip_ms));
+                }
+            }
+            Err(e) => last_error = Some(e),
+        }
+    }
+
+    match (best, last_error) {
+        (Some((offset_ms, round_trip_ms)), _) => Ok(NtpMeasurement {
+            server: url.to_string(),
+            offset_ms,
+            round_trip_ms,
+        }),
+        (None, Some(e)) => Err(e),
+        (None, None) => Err(AppError::Ntp("no samples collected".to_string())),
+    }
+}
+
+/// Server time in Unix ms from the `ts=` line of a trace response.
+fn parse_trace(body: &str) -> Result<f64, AppError> {
+    body.lines()
+        .find_map(|line| line.strip_prefix("ts="))
+        .and_then(|ts| ts.trim().parse::<f64>().ok())
+        .map(|secs| secs * 1000.0)
+        .ok_or_else(|| AppError::Ntp("time API reply has no ts field".to_string()))
+}
+
+fn now_unix_ms() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system clock before 1970")
+        .as_secs_f64()
+        * 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_setting_value_maps_to_a_source() {
+        for value in SOURCES {
+            assert!(TimeSource::from_setting(value).is_ok(), "{value}");
+        }
+        assert_eq!(
+            TimeSource::from_setting("google").unwrap(),
+            Some(TimeSource::Ntp("time.google.com"))
+        );
+        assert_eq!(TimeSource::from_setting("none").unwrap(), None);
+        assert!(TimeSource::from_setting("sundial").is_err());
+    }
+
+    #[test]
+    fn parses_the_trace_timestamp() {
+        let body = "fl=123\nh=cloudflare.com\nts=1700000000.123\nvisit_scheme=https\n";
+        assert!((parse_trace(body).unwrap() - 1_700_000_000_123.0).abs() < 0.01);
+        assert!(parse_trace("fl=123\n").is_err());
+    }
+}
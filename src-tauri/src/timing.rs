@@ -1,23 +1,31 @@
+use chrono::{DateTime, Duration, Utc};
 use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use tokio_util::sync::CancellationToken;
 
-/// High-precision wait. Sleeps for the bulk of the duration via
-/// `std::thread::sleep`, then busy-waits the final 100ms for sub-ms accuracy.
-pub fn precise_wait(seconds: f64) {
+/// How often `precise_wait` checks its token while sleeping.
+const CANCEL_POLL: std::time::Duration = std::time::Duration::from_millis(5);
+
+/// High-precision wait. Sleeps for the bulk of the duration, then
+/// busy-waits the final 100ms for sub-ms accuracy. Returns early once
+/// `token` is cancelled; the sleep is taken in short slices so a cancel
+/// lands within a few ms.
+pub fn precise_wait(seconds: f64, token: &CancellationToken) {
     if seconds <= 0.0 {
         return;
     }
 
     let start = Instant::now();
     let target = std::time::Duration::from_secs_f64(seconds);
+    let coarse = target.saturating_sub(std::time::Duration::from_millis(100));
 
-    // Sleep through the coarse portion (leave 100ms for busy-wait)
-    if seconds > 0.1 {
-        let sleep_duration = std::time::Duration::from_secs_f64(seconds - 0.1);
-        std::thread::sleep(sleep_duration);
+    while let Some(left) = coarse.checked_sub(start.elapsed()) {
+        if token.is_cancelled() || left.is_zero() {
+            break;
+        }
+        std::thread::sleep(left.min(CANCEL_POLL));
     }
 
-    // Busy-wait for the precise tail
-    while start.elapsed() < target {
+    while start.elapsed() < target && !token.is_cancelled() {
         std::hint::spin_loop();
     }
 }
@@ -30,6 +38,11 @@ pub fn system_time_secs() -> f64 {
         .as_secs_f64()
 }
 
+/// Translate a local timestamp into server time using a measured offset (ms).
+pub fn corrected_time(local: DateTime<Utc>, offset_ms: f64) -> DateTime<Utc> {
+    local + Duration::microseconds((offset_ms * 1000.0).round() as i64)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -42,10 +55,25 @@ mod tests {
         assert!(ts > 1_700_000_000.0, "timestamp {ts} looks too small");
     }
 
+    #[test]
+    fn corrected_time_applies_positive_and_negative_offsets() {
+        let local = DateTime::parse_from_rfc3339("2025-06-01T10:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(
+            corrected_time(local, 1500.25).to_rfc3339(),
+            "2025-06-01T10:00:01.500250+00:00"
+        );
+        assert_eq!(
+            corrected_time(local, -250.0).to_rfc3339(),
+            "2025-06-01T09:59:59.750+00:00"
+        );
+    }
+
     #[test]
     fn precise_wait_zero_returns_immediately() {
         let start = Instant::now();
-        precise_wait(0.0);
+        precise_wait(0.0, &CancellationToken::new());
         // Should complete in well under 1ms; allow 50ms for scheduler noise
         assert!(start.elapsed().as_millis() < 50);
     }
@@ -53,14 +81,27 @@ mod tests {
     #[test]
     fn precise_wait_negative_returns_immediately() {
         let start = Instant::now();
-        precise_wait(-1.0);
+        precise_wait(-1.0, &CancellationToken::new());
         assert!(start.elapsed().as_millis() < 50);
     }
 
+    #[test]
+    fn precise_wait_stops_on_cancel() {
+        let token = CancellationToken::new();
+        let canceller = token.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(20));
+            canceller.cancel();
+        });
+        let start = Instant::now();
+        precise_wait(5.0, &token);
+        assert!(start.elapsed().as_millis() < 500);
+    }
+
     #[test]
     fn precise_wait_small_duration_takes_approximately_correct_time() {
         let start = Instant::now();
-        precise_wait(0.01); // 10 ms
+        precise_wait(0.01, &CancellationToken::new()); // 10 ms
         let elapsed_ms = start.elapsed().as_millis();
         // Should be in the 5–50 ms window
         assert!(elapsed_ms >= 5, "elapsed {elapsed_ms}ms is too short");
@@ -0,0 +1,361 @@
+use crate::commands;
+use crate::drift;
+use crate::favicon::Favicon;
+use crate::models::{Server, ServerStatus};
+use crate::state::AppState;
+use crate::time_format::TimeFormat;
+use crate::timing;
+use chrono::{DateTime, Utc};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tauri::image::Image;
+use tauri::menu::{IconMenuItem, IsMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Manager, Wry};
+
+pub const TRAY_ID: &str = "main";
+
+const MENU_OPEN: &str = "open";
+const MENU_QUIT: &str = "quit";
+const MENU_NO_SERVERS: &str = "no_servers";
+const MENU_SYNC_PREFIX: &str = "sync:";
+const DEFAULT_TOOLTIP: &str = "Ticketime";
+
+/// Counts menu refreshes, so a slow one does not overwrite a later one.
+static MENU_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Build the tray icon and start the ticker that keeps its label on the
+/// primary server's corrected time.
+pub fn init(app: &AppHandle) -> tauri::Result<()> {
+    let menu = build_menu(app, &menu_entries(&app.state::<AppState>()))?;
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .menu(&menu)
+        .tooltip(DEFAULT_TOOLTIP)
+        .show_menu_on_left_click(false)
+        .on_menu_event(|app, event| match event.id.as_ref() {
+            MENU_OPEN => show_main_window(app),
+            MENU_QUIT => app.exit(0),
+            other => {
+                if let Some(id) = parse_sync_item(other) {
+                    sync_now(app, id);
+                }
+            }
+        })
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                show_main_window(tray.app_handle());
+            }
+        });
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+
+    tauri::async_runtime::spawn(run_ticker(app.clone()));
+    Ok(())
+}
+
+/// Rebuild the tray menu from the current server list. Call after anything
+/// that changes servers, their offsets, or their status. The database is
+/// read on the blocking pool; when refreshes overlap, only the latest one
+/// sets the menu.
+pub fn refresh_menu(app: &AppHandle) {
+    let generation = MENU_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let handle = app.clone();
+        let entries =
+            tokio::task::spawn_blocking(move || menu_entries(&handle.state::<AppState>()))
+                .await
+                .unwrap_or_default();
+        if MENU_GENERATION.load(Ordering::SeqCst) != generation {
+            return;
+        }
+        let Some(tray) = app.tray_by_id(TRAY_ID) else {
+            return;
+        };
+        match build_menu(&app, &entries) {
+            Ok(menu) => {
+                let _ = tray.set_menu(Some(menu));
+            }
+            Err(e) => log::warn!("failed to rebuild tray menu: {e}"),
+        }
+    });
+}
+
+/// Every server with its cached icon, if it has one the menu can draw.
+/// Never fetches: the menu is rebuilt often and must not wait on the
+/// network.
+fn menu_entries(state: &AppState) -> Vec<(Server, Option<Favicon>)> {
+    state
+        .db
+        .list_servers()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|server| {
+            let favicon = state
+                .db
+                .get_favicon(server.id)
+                .ok()
+                .flatten()
+                .filter(Favicon::is_bitmap);
+            (server, favicon)
+        })
+        .collect()
+}
+
+fn build_menu(app: &AppHandle, entries: &[(Server, Option<Favicon>)]) -> tauri::Result<Menu<Wry>> {
+    let items = if entries.is_empty() {
+        vec![IconMenuItem::with_id(
+            app,
+            MENU_NO_SERVERS,
+            "No servers",
+            false,
+            None,
+            None::<&str>,
+        )?]
+    } else {
+        entries
+            .iter()
+            .map(|(server, favicon)| {
+                IconMenuItem::with_id(
+                    app,
+                    format!("{MENU_SYNC_PREFIX}{}", server.id),
+                    server_menu_label(server),
+                    server.status != ServerStatus::Syncing,
+                    favicon
+                        .as_ref()
+                        .and_then(|f| Image::from_bytes(&f.data).ok()),
+                    None::<&str>,
+                )
+            })
+            .collect::<tauri::Result<Vec<_>>>()?
+    };
+    let item_refs: Vec<&dyn IsMenuItem<Wry>> = items
+        .iter()
+        .map(|item| item as &dyn IsMenuItem<Wry>)
+        .collect();
+    let sync_menu = Submenu::with_items(app, "Sync Now", true, &item_refs)?;
+
+    let open = MenuItem::with_id(app, MENU_OPEN, "Open Ticketime", true, None::<&str>)?;
+    let quit = MenuItem::with_id(app, MENU_QUIT, "Quit", true, None::<&str>)?;
+    Menu::with_items(
+        app,
+        &[
+            &open,
+            &sync_menu,
+            &PredefinedMenuItem::separator(app)?,
+            &quit,
+        ],
+    )
+}
+
+fn sync_now(app: &AppHandle, id: i64) {
+    let already_running = app
+        .state::<AppState>()
+        .active_syncs
+        .lock()
+        .expect("active_syncs poisoned")
+        .contains_key(&id);
+    if already_running {
+        return;
+    }
+    if let Err(e) = commands::spawn_sync(app, id, commands::broadcast_sink(app)) {
+        log::warn!("tray sync for server {id} failed to start: {e}");
+    }
+}
+
+fn parse_sync_item(menu_id: &str) -> Option<i64> {
+    menu_id.strip_prefix(MENU_SYNC_PREFIX)?.parse().ok()
+}
+
+fn server_menu_label(server: &Server) -> String {
+    let label = server.name.as_deref().unwrap_or(&server.url);
+    match (&server.status, server.offset_ms) {
+        (ServerStatus::Syncing, _) => format!("{label} — syncing…"),
+        (_, Some(offset_ms)) => format!("{label} — {offset_ms:+.0} ms"),
+        (_, None) => format!("{label} — not synced"),
+    }
+}
+
+pub fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+async fn run_ticker(app: AppHandle) {
+    loop {
+        let handle = app.clone();
+        let (primary, format, extrapolate_drift) = tokio::task::spawn_blocking(move || {
+            let state = handle.state::<AppState>();
+            let settings = state.db.get_settings().unwrap_or_default();
+            let primary = state
+                .db
+                .get_primary_server()
+                .ok()
+                .flatten()
+                .and_then(|id| state.db.get_server(id).ok());
+            // The tray only repaints once a second, so fractions would sit at zero.
+            let format = TimeFormat::from_settings(&settings).whole_seconds();
+            (primary, format, settings.extrapolate_drift)
+        })
+        .await
+        .unwrap_or_else(|_| (None, TimeFormat::from_settings(&Default::default()), true));
+
+        let now = Utc::now();
+        if let Some(tray) = app.tray_by_id(TRAY_ID) {
+            let (title, tooltip) = tray_labels(primary.as_ref(), now, extrapolate_drift, &format);
+            let _ = tray.set_title(title.as_deref());
+            let _ = tray.set_tooltip(Some(tooltip));
+        }
+
+        // Wake on the next whole second of the displayed clock so the tray
+        // ticks in step with the server rather than with the local clock.
+        let offset = primary
+            .as_ref()
+            .and_then(|s| drift::offset_at(s, now, extrapolate_drift));
+        let displayed = match offset {
+            Some(offset_ms) => timing::corrected_time(now, offset_ms),
+            None => now,
+        };
+        let to_next_second = 1_000_000_000 - displayed.timestamp_subsec_nanos().min(999_999_999);
+        tokio::time::sleep(Duration::from_nanos(to_next_second as u64)).await;
+    }
+}
+
+/// Compute the tray `(title, tooltip)` for the primary server at local time `now`.
+fn tray_labels(
+    primary: Option<&Server>,
+    now: DateTime<Utc>,
+    extrapolate_drift: bool,
+    format: &TimeFormat,
+) -> (Option<String>, String) {
+    let Some(server) = primary else {
+        return (None, DEFAULT_TOOLTIP.to_string());
+    };
+    let label = server.name.as_deref().unwrap_or(&server.url);
+
+    match drift::offset_at(server, now, extrapolate_drift) {
+        Some(offset_ms) => {
+            let time = format.format_local(timing::corrected_time(now, offset_ms));
+            let tooltip = format!("{DEFAULT_TOOLTIP} — {label}: {time} ({offset_ms:+.0} ms)");
+            (Some(time), tooltip)
+        }
+        None => (None, format!("{DEFAULT_TOOLTIP} — {label}: not synced")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Local;
+
+    fn whole_seconds() -> TimeFormat {
+        TimeFormat::from_settings(&Default::default()).whole_seconds()
+    }
+
+    fn make_server(offset_ms: Option<f64>) -> Server {
+        Server {
+            id: 1,
+            url: "https://example.com".to_string(),
+            name: None,
+            offset_ms,
+            last_sync_at: None,
+            created_at: Utc::now(),
+            status: ServerStatus::Idle,
+            extractor_type: "date_header".to_string(),
+            probe_method: "HEAD".to_string(),
+            domain_preset: None,
+            sync_profile: None,
+            fingerprint: None,
+            asymmetry: None,
+            sync_on_launch: false,
+            health_score: None,
+            drift: None,
+            min_request_interval_ms: None,
+            trusted: false,
+            user_agent: None,
+            transport: Default::default(),
+            timezone: None,
+            timezone_guessed: false,
+        }
+    }
+
+    #[test]
+    fn tray_labels_without_primary_shows_app_name() {
+        let (title, tooltip) = tray_labels(None, Utc::now(), true, &whole_seconds());
+        assert!(title.is_none());
+        assert_eq!(tooltip, "Ticketime");
+    }
+
+    #[test]
+    fn tray_labels_unsynced_primary_has_no_title() {
+        let server = make_server(None);
+        let (title, tooltip) = tray_labels(Some(&server), Utc::now(), true, &whole_seconds());
+        assert!(title.is_none());
+        assert!(tooltip.contains("https://example.com"));
+        assert!(tooltip.contains("not synced"));
+    }
+
+    #[test]
+    fn tray_labels_synced_primary_shows_corrected_time() {
+        let server = make_server(Some(2500.0));
+        let now = Utc::now();
+        let (title, tooltip) = tray_labels(Some(&server), now, true, &whole_seconds());
+
+        let expected = (now + chrono::Duration::milliseconds(2500))
+            .with_timezone(&Local)
+            .format("%H:%M:%S")
+            .to_string();
+        assert_eq!(title.as_deref(), Some(expected.as_str()));
+        assert!(tooltip.contains("+2500 ms"));
+    }
+
+    #[test]
+    fn tray_labels_use_custom_pattern() {
+        let server = make_server(Some(0.0));
+        let format = TimeFormat::new("%H:%M", 3, false).unwrap();
+        let (title, _) = tray_labels(Some(&server), Utc::now(), true, &format);
+        assert_eq!(title.map(|t| t.len()), Some(5));
+    }
+
+    #[test]
+    fn parse_sync_item_extracts_server_id() {
+        assert_eq!(parse_sync_item("sync:42"), Some(42));
+        assert_eq!(parse_sync_item("sync:abc"), None);
+        assert_eq!(parse_sync_item("open"), None);
+    }
+
+    #[test]
+    fn server_menu_label_reflects_offset_and_status() {
+        let mut server = make_server(Some(-12.4));
+        assert_eq!(server_menu_label(&server), "https://example.com — -12 ms");
+
+        server.status = ServerStatus::Syncing;
+        assert_eq!(server_menu_label(&server), "https://example.com — syncing…");
+
+        let unsynced = make_server(None);
+        assert_eq!(
+            server_menu_label(&unsynced),
+            "https://example.com — not synced"
+        );
+    }
+
+    #[test]
+    fn tray_labels_prefers_server_name_over_url() {
+        let mut server = make_server(Some(0.0));
+        server.name = Some("Tickets".to_string());
+        let (_, tooltip) = tray_labels(Some(&server), Utc::now(), true, &whole_seconds());
+        assert!(tooltip.contains("Tickets"));
+        assert!(!tooltip.contains("https://example.com"));
+    }
+}
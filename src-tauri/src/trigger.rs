@@ -0,0 +1,220 @@
+use crate::countdown;
+use crate::error::AppError;
+use crate::models::Target;
+use crate::scripting;
+use crate::state::AppState;
+use crate::timing;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio_util::sync::CancellationToken;
+
+/// Emitted at an armed target's corrected T-0.
+pub const TARGET_FIRED_EVENT: &str = "target-fired";
+
+/// How often the loop looks for a target about to come up.
+const TICK: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// A target is handed to its firing thread this long before T-0. Shorter
+/// than the standby deadline margin, so the T-5s recheck has stored its
+/// offset by then.
+const ARM_AHEAD: Duration = Duration::milliseconds(750);
+
+/// A target found already past by up to this much (busy runtime) still
+/// fires, late, with the lateness in its error; one missed by more
+/// (machine asleep) is skipped.
+const GRACE: Duration = Duration::seconds(1);
+
+/// Sent when a target comes up, so frontends and scripts can act knowing
+/// how far off the moment they were told.
+#[derive(Debug, Clone, Serialize)]
+pub struct TargetFired {
+    pub target_id: i64,
+    pub server_id: i64,
+    /// The target instant, in server time.
+    pub intended_at: DateTime<Utc>,
+    /// Server time when the event was sent.
+    pub fired_at: DateTime<Utc>,
+    /// `fired_at - intended_at` in microseconds; positive is late.
+    pub error_us: i64,
+    /// Offset the firing instant was computed with.
+    pub offset_ms: f64,
+}
+
+/// Fires `TARGET_FIRED_EVENT` (and the local API's `target_fired`) for
+/// armed targets at their corrected instant. Each target gets its own
+/// thread for the final stretch, which busy-waits the last 100 ms.
+pub fn start(app: &AppHandle) {
+    tauri::async_runtime::spawn(run(app.clone()));
+}
+
+async fn run(app: AppHandle) {
+    let mut interval = tokio::time::interval(TICK);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    let mut pending: HashMap<i64, (DateTime<Utc>, CancellationToken)> = HashMap::new();
+
+    loop {
+        interval.tick().await;
+        let now = Utc::now();
+        let skip: HashSet<i64> = pending.keys().copied().collect();
+        let handle = app.clone();
+        let armed = tokio::task::spawn_blocking(move || {
+            armed_targets(&handle.state::<AppState>(), now, &skip)
+        })
+        .await
+        .unwrap_or_else(|e| Err(std::io::Error::other(e).into()));
+        match armed {
+            Ok(armed) => arm_due(&app, now, armed, &mut pending),
+            Err(e) => log::warn!("failed to check targets for firing: {e}"),
+        }
+    }
+}
+
+/// Every armed target, with its countdown when it may need arming: not in
+/// `skip` (already pending), not long past, and its server readable.
+fn armed_targets(
+    state: &AppState,
+    now: DateTime<Utc>,
+    skip: &HashSet<i64>,
+) -> Result<Vec<(Target, Option<countdown::TimeUntil>)>, AppError> {
+    let extrapolate_drift = state.db.get_settings()?.extrapolate_drift;
+    let mut armed = Vec::new();
+    for target in state.db.list_targets()?.into_iter().filter(|t| t.armed) {
+        if skip.contains(&target.id) || target.target_at < now - GRACE {
+            armed.push((target, None));
+            continue;
+        }
+        let Ok(server) = state.db.get_server(target.server_id) else {
+            armed.push((target, None));
+            continue;
+        };
+        let until = countdown::time_until(&target, &server, now, extrapolate_drift).ok();
+        armed.push((target, until));
+    }
+    Ok(armed)
+}
+
+/// Hand targets within `ARM_AHEAD` of T-0 to a firing thread. A target
+/// disarmed, deleted or moved after that is cancelled; `pending` keeps it
+/// (by its instant) until it is gone, so it fires once.
+fn arm_due(
+    app: &AppHandle,
+    now: DateTime<Utc>,
+    armed: Vec<(Target, Option<countdown::TimeUntil>)>,
+    pending: &mut HashMap<i64, (DateTime<Utc>, CancellationToken)>,
+) {
+    pending.retain(|id, (at, token)| {
+        let current = armed.iter().any(|(t, _)| t.id == *id && t.target_at == *at);
+        if !current {
+            token.cancel();
+        }
+        current
+    });
+
+    for (target, until) in armed {
+        let Some(until) = until else {
+            continue;
+        };
+        let remaining = Duration::microseconds((until.remaining_ms * 1000.0) as i64);
+        if !is_due(remaining) {
+            continue;
+        }
+        let token = CancellationToken::new();
+        pending.insert(target.id, (target.target_at, token.clone()));
+
+        let app = app.clone();
+        let fire_at = now + remaining;
+        let spawned = std::thread::Builder::new()
+            .name(format!("trigger-{}", target.id))
+            .spawn(move || fire(app, until, fire_at, token));
+        if let Err(e) = spawned {
+            log::warn!("failed to start the trigger for target {}: {e}", target.id);
+        }
+    }
+}
+
+/// Whether a target `remaining` away (server time) should be armed now.
+fn is_due(remaining: Duration) -> bool {
+    remaining <= ARM_AHEAD && remaining > -GRACE
+}
+
+/// Wait until local time `fire_at`, then send the event.
+fn fire(
+    app: AppHandle,
+    until: countdown::TimeUntil,
+    fire_at: DateTime<Utc>,
+    token: CancellationToken,
+) {
+    let wait = (fire_at - Utc::now()).num_microseconds().unwrap_or(0) as f64 / 1e6;
+    timing::precise_wait(wait, &token);
+    if token.is_cancelled() {
+        return;
+    }
+    let fired = fired(&until, Utc::now());
+
+    // WebSocket clients first: the broadcast is a channel send, the Tauri
+    // event serializes for every window.
+    let state = app.state::<AppState>();
+    let _ = state.local_api.target_events().send(fired.clone());
+    if let Err(e) = app.emit(TARGET_FIRED_EVENT, &fired) {
+        log::warn!("failed to emit target fired: {e}");
+    }
+    state.hooks.fire(scripting::ON_TARGET_FIRED, &fired);
+    log::info!(
+        "target {} fired, {:+} us off T-0",
+        fired.target_id,
+        fired.error_us
+    );
+}
+
+fn fired(until: &countdown::TimeUntil, local_now: DateTime<Utc>) -> TargetFired {
+    let fired_at = timing::corrected_time(local_now, until.offset_ms);
+    TargetFired {
+        target_id: until.target_id,
+        server_id: until.server_id,
+        intended_at: until.target_at,
+        fired_at,
+        error_us: (fired_at - until.target_at)
+            .num_microseconds()
+            .unwrap_or(i64::MAX),
+        offset_ms: until.offset_ms,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn targets_are_armed_just_before_t0_and_briefly_after() {
+        assert!(!is_due(Duration::seconds(5)));
+        assert!(is_due(Duration::milliseconds(750)));
+        assert!(is_due(Duration::zero()));
+        assert!(is_due(Duration::milliseconds(-900)));
+        assert!(!is_due(Duration::seconds(-2)));
+    }
+
+    #[test]
+    fn error_is_measured_in_server_time() {
+        let target_at = Utc.with_ymd_and_hms(2026, 3, 1, 10, 0, 0).unwrap();
+        let until = countdown::TimeUntil {
+            target_id: 4,
+            server_id: 2,
+            target_at,
+            corrected_now: target_at,
+            remaining_ms: 0.0,
+            offset_ms: -412.5,
+            drift_ms_per_hour: None,
+            offset_age_ms: None,
+            stale: false,
+        };
+        // Local clock 412.5 ms ahead of the server, plus 180 us of lateness.
+        let local = target_at + Duration::microseconds(412_500 + 180);
+        let fired = fired(&until, local);
+        assert_eq!(fired.error_us, 180);
+        assert_eq!(fired.fired_at, target_at + Duration::microseconds(180));
+        assert_eq!(fired.target_id, 4);
+    }
+}
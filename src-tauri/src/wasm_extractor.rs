@@ -0,0 +1,270 @@
+use crate::error::AppError;
+use crate::time_extractor::TimeExtractor;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+use wasmi::{
+    Config, Engine, Instance, Linker, Memory, Module, Store, StoreLimits, StoreLimitsBuilder,
+};
+
+/// Plugins are the `*.wasm` files in this directory under app data.
+pub const PLUGIN_DIR: &str = "plugins";
+
+/// A plugin's `extractor_type` is this prefix plus its file stem.
+const ID_PREFIX: &str = "wasm:";
+
+/// Roughly the instructions one call may execute.
+const FUEL: u64 = 50_000_000;
+/// Linear memory one instance may grow to.
+const MAX_MEMORY: usize = 16 << 20;
+const MAX_NAME_LEN: usize = 64;
+
+/// A third-party extractor compiled to WebAssembly.
+///
+/// The module exports `memory`, `alloc(len: i32) -> i32`, `name() -> i64`
+/// and `extract_time(headers_ptr, headers_len, body_ptr, body_len: i32) ->
+/// i64`. `name` returns `ptr << 32 | len` of a UTF-8 string in memory.
+/// Headers arrive as `name: value\r\n` lines with lowercase names, each
+/// placed with `alloc`. `extract_time` returns unix seconds, or a negative
+/// value when the response carries no time it recognises.
+///
+/// Modules may not import anything, so a plugin has no way to reach the
+/// file system or network. Every call gets a fresh instance with a fuel
+/// and memory budget.
+pub struct WasmExtractor {
+    engine: Engine,
+    module: Module,
+    name: String,
+}
+
+impl WasmExtractor {
+    pub fn load(path: &Path) -> Result<Self, AppError> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+
+    pub fn from_bytes(wasm: &[u8]) -> Result<Self, AppError> {
+        let mut config = Config::default();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config);
+        let module = Module::new(&engine, wasm).map_err(plugin_error)?;
+        if let Some(import) = module.imports().next() {
+            return Err(AppError::Plugin(format!(
+                "imports {}::{}; plugins may not import anything",
+                import.module(),
+                import.name()
+            )));
+        }
+        let mut extractor = Self {
+            engine,
+            module,
+            name: String::new(),
+        };
+        extractor.name = extractor.call_name()?;
+        Ok(extractor)
+    }
+
+    fn instantiate(&self) -> Result<(Store<StoreLimits>, Instance, Memory), AppError> {
+        let limits = StoreLimitsBuilder::new().memory_size(MAX_MEMORY).build();
+        let mut store = Store::new(&self.engine, limits);
+        store.limiter(|limits| limits);
+        store.set_fuel(FUEL).map_err(plugin_error)?;
+        let instance = Linker::new(&self.engine)
+            .instantiate_and_start(&mut store, &self.module)
+            .map_err(plugin_error)?;
+        let memory = instance
+            .get_memory(&store, "memory")
+            .ok_or_else(|| AppError::Plugin("exports no memory".to_string()))?;
+        Ok((store, instance, memory))
+    }
+
+    fn call_name(&self) -> Result<String, AppError> {
+        let (mut store, instance, memory) = self.instantiate()?;
+        let packed = instance
+            .get_typed_func::<(), i64>(&store, "name")
+            .and_then(|name| name.call(&mut store, ()))
+            .map_err(plugin_error)?;
+        let (ptr, len) = ((packed >> 32) as u32 as usize, packed as u32 as usize);
+        if len == 0 || len > MAX_NAME_LEN {
+            return Err(AppError::Plugin(format!(
+                "name must be 1-{MAX_NAME_LEN} bytes, got {len}"
+            )));
+        }
+        let mut name = vec![0; len];
+        memory.read(&store, ptr, &mut name).map_err(plugin_error)?;
+        String::from_utf8(name).map_err(|_| AppError::Plugin("name is not UTF-8".to_string()))
+    }
+
+    fn call_extract(&self, headers: &[u8], body: &[u8]) -> Result<i64, AppError> {
+        let (mut store, instance, memory) = self.instantiate()?;
+        let (headers_ptr, headers_len) = place(&mut store, &instance, memory, headers)?;
+        let (body_ptr, body_len) = place(&mut store, &instance, memory, body)?;
+        let seconds = instance
+            .get_typed_func::<(i32, i32, i32, i32), i64>(&store, "extract_time")
+            .and_then(|extract| {
+                extract.call(&mut store, (headers_ptr, headers_len, body_ptr, body_len))
+            })
+            .map_err(|e| AppError::Plugin(format!("{}: {e}", self.name)))?;
+        if seconds < 0 {
+            return Err(AppError::Plugin(format!(
+                "{} found no time in the response",
+                self.name
+            )));
+        }
+        Ok(seconds)
+    }
+}
+
+impl TimeExtractor for WasmExtractor {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn reads_body(&self) -> bool {
+        true
+    }
+
+    fn extract_time(&self, response: &reqwest::Response, body: &[u8]) -> Result<i64, AppError> {
+        self.call_extract(&header_block(response.headers()), body)
+    }
+}
+
+/// Copy `bytes` into the instance through its `alloc`.
+fn place(
+    store: &mut Store<StoreLimits>,
+    instance: &Instance,
+    memory: Memory,
+    bytes: &[u8],
+) -> Result<(i32, i32), AppError> {
+    let len = i32::try_from(bytes.len())
+        .map_err(|_| AppError::Plugin("response too large".to_string()))?;
+    let ptr = instance
+        .get_typed_func::<i32, i32>(&*store, "alloc")
+        .and_then(|alloc| alloc.call(&mut *store, len))
+        .map_err(plugin_error)?;
+    memory
+        .write(&mut *store, ptr as u32 as usize, bytes)
+        .map_err(plugin_error)?;
+    Ok((ptr, len))
+}
+
+fn header_block(headers: &reqwest::header::HeaderMap) -> Vec<u8> {
+    let mut block = Vec::new();
+    for (name, value) in headers {
+        block.extend_from_slice(name.as_str().as_bytes());
+        block.extend_from_slice(b": ");
+        block.extend_from_slice(value.as_bytes());
+        block.extend_from_slice(b"\r\n");
+    }
+    block
+}
+
+fn plugin_error(e: impl std::fmt::Display) -> AppError {
+    AppError::Plugin(e.to_string())
+}
+
+/// The `extractor_type` servers use to pick the plugin at `path`.
+pub fn id_for(path: &Path) -> String {
+    format!(
+        "{ID_PREFIX}{}",
+        path.file_stem().unwrap_or_default().to_string_lossy()
+    )
+}
+
+/// Every `*.wasm` file in `dir`, by file name, with its load outcome.
+pub fn load_dir(dir: &Path) -> Vec<(PathBuf, Result<WasmExtractor, AppError>)> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "wasm"))
+        .collect();
+    paths.sort();
+    paths
+        .into_iter()
+        .map(|path| {
+            let loaded = WasmExtractor::load(&path);
+            (path, loaded)
+        })
+        .collect()
+}
+
+pub fn plugin_dir(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let dir = app.path().app_data_dir().map_err(|e| {
+        AppError::Io(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            e.to_string(),
+        ))
+    })?;
+    Ok(dir.join(PLUGIN_DIR))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::response::Builder as HttpResponseBuilder;
+
+    /// Names itself "Test Clock"; the time is a fixed epoch plus the first
+    /// body byte as a digit, so the test sees the body really arrived.
+    const PLUGIN: &str = r#"
+        (module
+          (memory (export "memory") 1)
+          (data (i32.const 0) "Test Clock")
+          (global $next (mut i32) (i32.const 1024))
+          (func (export "alloc") (param $len i32) (result i32)
+            (local $ptr i32)
+            (local.set $ptr (global.get $next))
+            (global.set $next (i32.add (global.get $next) (local.get $len)))
+            (local.get $ptr))
+          (func (export "name") (result i64) (i64.const 10))
+          (func (export "extract_time") (param i32 i32 i32 i32) (result i64)
+            (if (result i64) (i32.eqz (local.get 3))
+              (then (i64.const -1))
+              (else (i64.add
+                (i64.const 1445412480)
+                (i64.sub (i64.load8_u (local.get 2)) (i64.const 48)))))))
+    "#;
+
+    fn response() -> reqwest::Response {
+        reqwest::Response::from(
+            HttpResponseBuilder::new()
+                .status(200)
+                .header("x-clock", "1")
+                .body(b"".to_vec())
+                .unwrap(),
+        )
+    }
+
+    #[test]
+    fn plugin_names_itself_and_reads_the_body() {
+        let plugin = WasmExtractor::from_bytes(PLUGIN.as_bytes()).unwrap();
+        assert_eq!(plugin.name(), "Test Clock");
+        assert!(plugin.reads_body());
+        assert_eq!(
+            plugin.extract_time(&response(), b"7").unwrap(),
+            1_445_412_487
+        );
+        assert!(matches!(
+            plugin.extract_time(&response(), b""),
+            Err(AppError::Plugin(_))
+        ));
+    }
+
+    #[test]
+    fn imports_and_runaway_loops_are_refused() {
+        let importing = r#"(module (import "env" "open" (func)) (memory (export "memory") 1))"#;
+        assert!(WasmExtractor::from_bytes(importing.as_bytes()).is_err());
+
+        let spinning = PLUGIN.replace(
+            "(func (export \"name\") (result i64) (i64.const 10))",
+            "(func (export \"name\") (result i64) (loop (br 0)) (i64.const 10))",
+        );
+        assert!(WasmExtractor::from_bytes(spinning.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn headers_are_passed_as_lines() {
+        let block = header_block(response().headers());
+        assert_eq!(block, b"x-clock: 1\r\n");
+        assert_eq!(id_for(Path::new("/p/acme-json.wasm")), "wasm:acme-json");
+    }
+}
@@ -0,0 +1,102 @@
+use crate::error::AppError;
+use crate::models::{Server, SyncPhase};
+use crate::sync_engine;
+use crate::sync_profile::SyncProfile;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+use tauri_plugin_notification::NotificationExt;
+
+/// When a sync last reported progress, and from which phase. Fed from the
+/// progress callback; a probe wedged on a dead connection stops the beats.
+pub struct Heartbeat {
+    last: Mutex<(Instant, SyncPhase)>,
+}
+
+impl Heartbeat {
+    pub fn new() -> Self {
+        Self {
+            last: Mutex::new((Instant::now(), SyncPhase::LatencyProfiling)),
+        }
+    }
+
+    pub fn beat(&self, phase: SyncPhase) {
+        *self.last.lock().unwrap() = (Instant::now(), phase);
+    }
+
+    /// Resolves once `limit` passes without a beat, with the phase the
+    /// sync was stuck in. Pending forever while beats keep coming.
+    pub async fn stalled(&self, limit: Duration) -> SyncPhase {
+        loop {
+            let (at, phase) = *self.last.lock().unwrap();
+            let quiet = at.elapsed();
+            if quiet >= limit {
+                return phase;
+            }
+            tokio::time::sleep(limit - quiet).await;
+        }
+    }
+}
+
+impl Default for Heartbeat {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The configured stall timeout, stretched for profiles whose probes are
+/// spaced further apart than it: a polite sync at a 10 s request interval
+/// is quiet for over a minute between probes without being stuck.
+pub fn stall_limit(setting_secs: u32, profile: &SyncProfile) -> Duration {
+    Duration::from_secs(setting_secs.into()).max(sync_engine::longest_probe_gap(profile))
+}
+
+pub fn notify(app: &AppHandle, server: &Server, limit: Duration) -> Result<(), AppError> {
+    app.notification()
+        .builder()
+        .title("Sync stalled")
+        .body(format!(
+            "{}: no progress for {} s, so the sync was stopped",
+            server.name.as_deref().unwrap_or(&server.url),
+            limit.as_secs()
+        ))
+        .show()
+        .map_err(|e| AppError::Notification(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn fires_only_after_the_beats_stop() {
+        let heartbeat = Arc::new(Heartbeat::new());
+        let beating = heartbeat.clone();
+        tokio::spawn(async move {
+            for _ in 0..10 {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                beating.beat(SyncPhase::BinarySearch);
+            }
+        });
+
+        let start = Instant::now();
+        let phase = heartbeat.stalled(Duration::from_millis(80)).await;
+        assert!(start.elapsed() >= Duration::from_millis(200));
+        assert_eq!(phase, SyncPhase::BinarySearch);
+    }
+
+    #[test]
+    fn stall_limit_covers_the_widest_probe_gap() {
+        let balanced = crate::sync_profile::BALANCED;
+        assert_eq!(stall_limit(60, &balanced), Duration::from_secs(60));
+
+        // Polite at a 10 s request interval spaces probes 40 s apart.
+        let slow = SyncProfile {
+            min_interval_ms: 40_000,
+            min_spacing_ms: 40_000,
+            ..crate::sync_profile::POLITE
+        };
+        assert!(stall_limit(60, &slow) > Duration::from_secs(160));
+    }
+}
@@ -0,0 +1,182 @@
+use crate::db::DB_FILE_NAME;
+use crate::error::AppError;
+use crate::models::Workspace;
+use crate::settings_validation::FieldError;
+use std::path::{Path, PathBuf};
+
+/// Lives in the original database file, so existing installs keep their data.
+pub const DEFAULT_WORKSPACE: &str = "default";
+
+/// Every other workspace gets its own SQLite file in this directory.
+const WORKSPACE_DIR: &str = "workspaces";
+
+/// Plain-text file holding the name of the workspace to open on launch.
+const ACTIVE_FILE: &str = "active_workspace";
+
+const MAX_NAME_LEN: usize = 40;
+
+/// Names become file names: lowercase letters, digits, `-` and `_` only.
+pub fn validate_name(name: &str) -> Result<(), AppError> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_NAME_LEN
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(AppError::Validation(vec![FieldError {
+            field: "name".to_string(),
+            message: format!(
+                "workspace names are 1-{MAX_NAME_LEN} lowercase letters, digits, '-' or '_'"
+            ),
+        }]))
+    }
+}
+
+pub fn database_path(data_dir: &Path, name: &str) -> PathBuf {
+    if name == DEFAULT_WORKSPACE {
+        data_dir.join(DB_FILE_NAME)
+    } else {
+        data_dir.join(WORKSPACE_DIR).join(format!("{name}.db"))
+    }
+}
+
+/// The workspace to open. Falls back to the default when the marker is
+/// missing or names something that no longer exists.
+pub fn active(data_dir: &Path) -> String {
+    std::fs::read_to_string(data_dir.join(ACTIVE_FILE))
+        .ok()
+        .map(|name| name.trim().to_string())
+        .filter(|name| validate_name(name).is_ok())
+        .filter(|name| database_path(data_dir, name).exists())
+        .unwrap_or_else(|| DEFAULT_WORKSPACE.to_string())
+}
+
+pub fn set_active(data_dir: &Path, name: &str) -> Result<(), AppError> {
+    validate_name(name)?;
+    std::fs::write(data_dir.join(ACTIVE_FILE), name)?;
+    Ok(())
+}
+
+/// The default workspace first, then the others by name.
+pub fn list(data_dir: &Path) -> Result<Vec<Workspace>, AppError> {
+    let mut names = Vec::new();
+    match std::fs::read_dir(data_dir.join(WORKSPACE_DIR)) {
+        Ok(entries) => {
+            for entry in entries {
+                let path = entry?.path();
+                if path.extension().is_some_and(|ext| ext == "db") {
+                    if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+                        if validate_name(stem).is_ok() && stem != DEFAULT_WORKSPACE {
+                            names.push(stem.to_string());
+                        }
+                    }
+                }
+            }
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
+    }
+    names.sort();
+
+    let active = active(data_dir);
+    Ok(std::iter::once(DEFAULT_WORKSPACE.to_string())
+        .chain(names)
+        .map(|name| Workspace {
+            active: name == active,
+            name,
+        })
+        .collect())
+}
+
+/// Create the directory a workspace's database goes in.
+pub fn prepare(data_dir: &Path, name: &str) -> Result<PathBuf, AppError> {
+    validate_name(name)?;
+    let path = database_path(data_dir, name);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    Ok(path)
+}
+
+/// Delete every workspace but the default, whose file stays in use, and
+/// forget which one was active.
+pub fn remove_all(data_dir: &Path) -> Result<(), AppError> {
+    for result in [
+        std::fs::remove_dir_all(data_dir.join(WORKSPACE_DIR)),
+        std::fs::remove_file(data_dir.join(ACTIVE_FILE)),
+    ] {
+        match result {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data_dir(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("ticketime-{}-{test}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn default_workspace_uses_the_original_file() {
+        let dir = data_dir("ws-default");
+        assert_eq!(
+            database_path(&dir, DEFAULT_WORKSPACE),
+            dir.join(DB_FILE_NAME)
+        );
+        assert_eq!(
+            database_path(&dir, "client-a"),
+            dir.join("workspaces").join("client-a.db")
+        );
+        assert_eq!(active(&dir), DEFAULT_WORKSPACE);
+    }
+
+    #[test]
+    fn rejects_names_that_are_not_file_safe() {
+        for name in ["", "../x", "Client", "a b", &"x".repeat(41)] {
+            assert!(validate_name(name).is_err(), "{name:?} accepted");
+        }
+        assert!(validate_name("client_b-2").is_ok());
+    }
+
+    #[test]
+    fn lists_and_activates_workspaces() {
+        let dir = data_dir("ws-list");
+        for name in ["zeta", "acme"] {
+            std::fs::write(prepare(&dir, name).unwrap(), b"").unwrap();
+        }
+        set_active(&dir, "zeta").unwrap();
+
+        let listed = list(&dir).unwrap();
+        let names: Vec<_> = listed.iter().map(|w| w.name.as_str()).collect();
+        assert_eq!(names, vec!["default", "acme", "zeta"]);
+        assert!(listed[2].active);
+        assert!(!listed[0].active);
+
+        // A marker pointing at a deleted workspace falls back to the default.
+        std::fs::remove_file(database_path(&dir, "zeta")).unwrap();
+        assert_eq!(active(&dir), DEFAULT_WORKSPACE);
+    }
+
+    #[test]
+    fn remove_all_keeps_only_the_default() {
+        let dir = data_dir("ws-remove");
+        std::fs::write(prepare(&dir, "acme").unwrap(), b"").unwrap();
+        set_active(&dir, "acme").unwrap();
+
+        remove_all(&dir).unwrap();
+        let names: Vec<_> = list(&dir).unwrap().into_iter().map(|w| w.name).collect();
+        assert_eq!(names, vec![DEFAULT_WORKSPACE]);
+        // Nothing left to remove is fine too.
+        remove_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,93 @@
+use crate::error::AppError;
+use crate::time_format::TimeFormat;
+use chrono::{DateTime, Offset, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::Serialize;
+
+/// One server instant rendered in several IANA timezones.
+#[derive(Debug, Clone, Serialize)]
+pub struct WorldClock {
+    pub server_id: i64,
+    pub corrected_time: DateTime<Utc>,
+    pub zones: Vec<ZoneTime>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ZoneTime {
+    pub timezone: String,
+    /// e.g. "CET" / "CEST"; some zones only have a numeric form like "+09".
+    pub abbreviation: String,
+    pub utc_offset_seconds: i32,
+    pub local_time: String,
+    pub formatted: String,
+}
+
+/// Render `corrected` in each of `timezones`, keeping the caller's order.
+/// Unknown zone names fail the whole request so typos are not silently
+/// dropped.
+pub fn zone_times(
+    corrected: DateTime<Utc>,
+    timezones: &[String],
+    format: &TimeFormat,
+) -> Result<Vec<ZoneTime>, AppError> {
+    timezones
+        .iter()
+        .map(|name| {
+            let tz: Tz = name
+                .parse()
+                .map_err(|_| AppError::InvalidTimezone(name.clone()))?;
+            let local = tz.from_utc_datetime(&corrected.naive_utc());
+            Ok(ZoneTime {
+                timezone: tz.name().to_string(),
+                abbreviation: local.format("%Z").to_string(),
+                utc_offset_seconds: local.offset().fix().local_minus_utc(),
+                local_time: local.to_rfc3339(),
+                formatted: format.format(&local),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zones(names: &[&str]) -> Vec<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn plain() -> TimeFormat {
+        TimeFormat::new("%H:%M", 0, false).unwrap()
+    }
+
+    #[test]
+    fn converts_one_instant_into_each_zone() {
+        let at: DateTime<Utc> = "2025-01-15T09:00:00Z".parse().unwrap();
+        let result = zone_times(
+            at,
+            &zones(&["Europe/Berlin", "America/New_York", "Asia/Seoul"]),
+            &plain(),
+        )
+        .unwrap();
+
+        let formatted: Vec<_> = result.iter().map(|z| z.formatted.as_str()).collect();
+        assert_eq!(formatted, ["10:00", "04:00", "18:00"]);
+        assert_eq!(result[0].abbreviation, "CET");
+        assert_eq!(result[1].utc_offset_seconds, -5 * 3600);
+        assert_eq!(result[2].local_time, "2025-01-15T18:00:00+09:00");
+    }
+
+    #[test]
+    fn follows_daylight_saving() {
+        let at: DateTime<Utc> = "2025-07-15T09:00:00Z".parse().unwrap();
+        let result = zone_times(at, &zones(&["Europe/Berlin"]), &plain()).unwrap();
+        assert_eq!(result[0].abbreviation, "CEST");
+        assert_eq!(result[0].formatted, "11:00");
+    }
+
+    #[test]
+    fn unknown_timezone_is_an_error() {
+        let err = zone_times(Utc::now(), &zones(&["UTC", "Mars/Olympus"]), &plain()).unwrap_err();
+        assert!(matches!(err, AppError::InvalidTimezone(name) if name == "Mars/Olympus"));
+    }
+}
@@ -7,7 +7,10 @@ import { SettingsPage } from "@/pages/SettingsPage";
 import { ServerDetailPage } from "@/pages/ServerDetailPage";
 import { useServerStore } from "@/stores/serverStore";
 import { useSettingsStore } from "@/stores/settingsStore";
+import { useSyncStore } from "@/stores/syncStore";
 import { useThemeStore } from "@/stores/themeStore";
+import * as commands from "@/lib/commands";
+import { playAlertSound } from "@/lib/sound";
 
 function AppLayout() {
   const { fetchServers } = useServerStore();
@@ -15,7 +18,96 @@ function AppLayout() {
   useEffect(() => {
     useThemeStore.getState().initTheme();
     useSettingsStore.getState().fetchSettings();
-    fetchServers();
+    useServerStore.getState().fetchDashboard();
+    useServerStore.getState().fetchReferenceServer();
+  }, []);
+
+  useEffect(() => {
+    const unlisten = useSettingsStore.getState().listenForChanges();
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  useEffect(() => {
+    const unlisten = useSyncStore.getState().listenForBackgroundSyncs();
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  useEffect(() => {
+    const unlisten = commands.onWorkspaceChanged(() => {
+      useServerStore.getState().fetchDashboard();
+      useServerStore.getState().fetchReferenceServer();
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  // Resyncs started here run in the background; refetch to show them.
+  useEffect(() => {
+    const unlisten = commands.onNetworkChanged(() => {
+      useServerStore.getState().fetchDashboard();
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  useEffect(() => {
+    const unlisten = commands.onTargetAlert((alert) => {
+      if (alert.play_sound) playAlertSound();
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  // Syncs started from the tray or schedulers change status here too.
+  useEffect(() => {
+    const unlisten = commands.onServerStatusChanged((change) =>
+      useServerStore.getState().applyStatusChange(change),
+    );
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  // A pre-target recheck may have moved the server's offset.
+  useEffect(() => {
+    const unlisten = commands.onTargetConfidence((confidence) =>
+      useServerStore.getState().applyTargetConfidence(confidence),
+    );
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  // A target that came up drops out of the upcoming list.
+  useEffect(() => {
+    const unlisten = commands.onTargetFired(() =>
+      useServerStore.getState().fetchDashboard(),
+    );
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  // A health resync changed the server's score and status.
+  useEffect(() => {
+    const unlisten = commands.onHealthResync(() => fetchServers());
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [fetchServers]);
+
+  useEffect(() => {
+    const unlisten = commands.onDeepLink(() => fetchServers());
+    return () => {
+      unlisten.then((fn) => fn());
+    };
   }, [fetchServers]);
 
   return (
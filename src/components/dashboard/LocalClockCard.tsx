@@ -0,0 +1,87 @@
+import { useEffect, useState } from "react";
+import { Clock } from "lucide-react";
+import { Button } from "@/components/ui/Button";
+import type { LocalNtpStatus, NtpMeasurement } from "@/types/app";
+import * as commands from "@/lib/commands";
+import { cn, errorMessage } from "@/lib/utils";
+
+function signedMs(ms: number): string {
+  return `${ms >= 0 ? "+" : ""}${ms.toFixed(1)} ms`;
+}
+
+// The local time daemon's view of this machine's clock next to our own
+// measurement, so an off server can be told apart from an off machine.
+export function LocalClockCard() {
+  const [daemon, setDaemon] = useState<LocalNtpStatus | null>(null);
+  const [measured, setMeasured] = useState<NtpMeasurement | null>(null);
+  const [measureError, setMeasureError] = useState<string | null>(null);
+  const [busy, setBusy] = useState(false);
+
+  useEffect(() => {
+    commands.getLocalNtpStatus().then(setDaemon).catch(() => setDaemon(null));
+  }, []);
+
+  const handleMeasure = async () => {
+    setBusy(true);
+    setMeasureError(null);
+    try {
+      const [status, measurement] = await Promise.all([
+        commands.getLocalNtpStatus(),
+        commands.measureClockError(),
+      ]);
+      setDaemon(status);
+      setMeasured(measurement);
+    } catch (e) {
+      setMeasureError(errorMessage(e));
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  return (
+    <div className="flex flex-wrap items-center gap-x-6 gap-y-2 rounded-2xl border border-[var(--color-border)] bg-[var(--color-bg-card)] px-6 py-4 text-sm">
+      <div className="flex items-center gap-2 font-medium text-[var(--color-text-primary)]">
+        <Clock className="h-4 w-4 text-[var(--color-text-secondary)]" />
+        Local clock
+      </div>
+
+      {daemon ? (
+        <div className="flex items-center gap-2 text-[var(--color-text-secondary)]">
+          <span
+            className={cn(
+              "h-2 w-2 rounded-full",
+              daemon.synchronized ? "bg-emerald-400" : "bg-[var(--color-warning)]",
+            )}
+          />
+          <span>
+            {daemon.daemon} {daemon.synchronized ? "synced" : "not synced"}
+            {daemon.server && ` to ${daemon.server}`}
+            {daemon.stratum !== null && ` (stratum ${daemon.stratum})`}
+          </span>
+          {daemon.offset_ms !== null && (
+            <span className="tabular-nums text-[var(--color-text-primary)]">
+              {signedMs(daemon.offset_ms)}
+              {daemon.jitter_ms !== null && ` ± ${daemon.jitter_ms.toFixed(1)} ms`}
+            </span>
+          )}
+        </div>
+      ) : (
+        <span className="text-[var(--color-text-secondary)]">No time daemon found</span>
+      )}
+
+      {measured && (
+        <span className="tabular-nums text-[var(--color-text-secondary)]">
+          Measured vs {measured.server}:{" "}
+          <span className="text-[var(--color-text-primary)]">{signedMs(measured.offset_ms)}</span>
+        </span>
+      )}
+      {measureError && (
+        <span className="max-w-64 truncate text-xs text-[var(--color-warning)]">{measureError}</span>
+      )}
+
+      <Button variant="ghost" size="sm" className="ml-auto" onClick={handleMeasure} disabled={busy}>
+        {busy ? "Measuring..." : "Measure"}
+      </Button>
+    </div>
+  );
+}
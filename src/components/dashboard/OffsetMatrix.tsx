@@ -0,0 +1,92 @@
+import { useEffect, useState } from "react";
+import { errorMessage } from "@/lib/utils";
+import * as commands from "@/lib/commands";
+import type { ServerComparison } from "@/types/server";
+
+// Differences under this are within normal Date-header sync error.
+const AGREE_MS = 10;
+
+function formatDiff(ms: number | null): string {
+  if (ms === null) return "---";
+  return `${ms >= 0 ? "+" : ""}${ms.toFixed(1)}`;
+}
+
+interface OffsetMatrixProps {
+  serverIds: number[];
+}
+
+export function OffsetMatrix({ serverIds }: OffsetMatrixProps) {
+  const [comparison, setComparison] = useState<ServerComparison | null>(null);
+  const [error, setError] = useState<string | null>(null);
+  const key = serverIds.join(",");
+
+  useEffect(() => {
+    commands
+      .compareServers(key.split(",").map(Number))
+      .then((c) => {
+        setComparison(c);
+        setError(null);
+      })
+      .catch((e) => setError(errorMessage(e)));
+  }, [key]);
+
+  if (error) {
+    return <p className="text-sm text-[var(--color-danger)]">{error}</p>;
+  }
+  if (!comparison) return null;
+
+  return (
+    <div className="overflow-x-auto rounded-2xl border border-[var(--color-border)] bg-[var(--color-bg-card)] p-6">
+      <p className="mb-4 text-sm text-[var(--color-text-secondary)]">
+        How far each row runs ahead of each column, in ms.
+      </p>
+      <table className="text-sm tabular-nums">
+        <thead>
+          <tr>
+            <th />
+            {comparison.servers.map((s) => (
+              <th
+                key={s.server_id}
+                className="max-w-32 truncate px-3 py-2 text-left font-medium text-[var(--color-text-secondary)]"
+              >
+                {s.label}
+              </th>
+            ))}
+            <th className="px-3 py-2 text-left font-medium text-[var(--color-text-secondary)]">
+              Jitter
+            </th>
+          </tr>
+        </thead>
+        <tbody>
+          {comparison.servers.map((row, i) => (
+            <tr key={row.server_id}>
+              <th className="max-w-48 truncate px-3 py-2 text-left font-medium text-[var(--color-text-primary)]">
+                {row.label}
+                {row.stale && (
+                  <span className="ml-2 text-xs text-[var(--color-warning)]">stale</span>
+                )}
+              </th>
+              {comparison.matrix[i].map((diff, j) => (
+                <td
+                  key={comparison.servers[j].server_id}
+                  className={`px-3 py-2 font-mono ${
+                    i === j || diff === null
+                      ? "text-[var(--color-text-secondary)]"
+                      : Math.abs(diff) <= AGREE_MS
+                        ? "text-emerald-400"
+                        : "text-amber-400"
+                  }`}
+                >
+                  {i === j ? "·" : formatDiff(diff)}
+                </td>
+              ))}
+              <td className="px-3 py-2 font-mono text-[var(--color-text-secondary)]">
+                {row.jitter_ms === null ? "---" : `${row.jitter_ms.toFixed(1)} ms`}
+              </td>
+            </tr>
+          ))}
+        </tbody>
+      </table>
+    </div>
+  );
+}
@@ -0,0 +1,50 @@
+import { useEffect, useState } from "react";
+import { Select } from "@/components/ui/Select";
+import type { ReferenceSource } from "@/types/server";
+import { useServerStore } from "@/stores/serverStore";
+import * as commands from "@/lib/commands";
+
+interface ReferenceSourceSelectProps {
+  onAdded: (id: number) => void;
+  className?: string;
+}
+
+// Adds a public time service as a server; picking one is the whole flow.
+export function ReferenceSourceSelect({ onAdded, className }: ReferenceSourceSelectProps) {
+  const addReferenceSource = useServerStore((s) => s.addReferenceSource);
+  const [sources, setSources] = useState<ReferenceSource[]>([]);
+  const [adding, setAdding] = useState(false);
+
+  useEffect(() => {
+    commands.listReferenceSources().then(setSources);
+  }, []);
+
+  const handleChange = async (kind: string) => {
+    if (!kind) return;
+    setAdding(true);
+    try {
+      const server = await addReferenceSource(kind);
+      onAdded(server.id);
+    } catch {
+      // The store keeps the error.
+    } finally {
+      setAdding(false);
+    }
+  };
+
+  const options = [
+    { value: "", label: "Add reference..." },
+    ...sources.map((s) => ({ value: s.kind, label: s.label })),
+  ];
+
+  return (
+    <Select
+      options={options}
+      value=""
+      disabled={adding}
+      title="Add a public time source to compare against"
+      onChange={(e) => handleChange(e.target.value)}
+      className={className}
+    />
+  );
+}
@@ -0,0 +1,31 @@
+import { useEffect, useState } from "react";
+import { Globe } from "lucide-react";
+import * as commands from "@/lib/commands";
+
+interface ServerIconProps {
+  serverId: number;
+}
+
+// The site's favicon, or a globe while it loads or when it has none.
+export function ServerIcon({ serverId }: ServerIconProps) {
+  const [src, setSrc] = useState<string | null>(null);
+
+  useEffect(() => {
+    let cancelled = false;
+    setSrc(null);
+    commands
+      .getServerFavicon(serverId)
+      .then((url) => {
+        if (!cancelled) setSrc(url);
+      })
+      .catch(() => {});
+    return () => {
+      cancelled = true;
+    };
+  }, [serverId]);
+
+  if (src === null) {
+    return <Globe className="h-4 w-4 shrink-0 text-[var(--color-text-secondary)]" />;
+  }
+  return <img src={src} alt="" className="h-4 w-4 shrink-0 rounded-sm object-contain" />;
+}
@@ -1,8 +1,9 @@
-import { Play, Trash2, Loader2, ChevronRight } from "lucide-react";
+import { Play, Trash2, Loader2, ChevronRight, Anchor } from "lucide-react";
 import { useNavigate } from "react-router-dom";
 import type { Server } from "@/types/server";
+import { ServerIcon } from "@/components/dashboard/ServerIcon";
 import { useSyncStore } from "@/stores/syncStore";
-import { useServerStore } from "@/stores/serverStore";
+import { offsetVsReference, useServerStore } from "@/stores/serverStore";
 
 function getHealthLabel(server: Server): { label: string; color: string } {
   if (server.status === "error") return { label: "Error", color: "text-[var(--color-danger)]" };
@@ -19,6 +20,13 @@ function formatOffset(ms: number | null): string {
   return `${ms >= 0 ? "+" : ""}${ms.toFixed(2)}ms`;
 }
 
+function formatTimeUntil(isoStr: string): string {
+  const diff = new Date(isoStr).getTime() - Date.now();
+  if (diff < 60_000) return "now";
+  if (diff < 3_600_000) return `in ${Math.floor(diff / 60_000)}m`;
+  return `in ${Math.floor(diff / 3_600_000)}h`;
+}
+
 function formatTimeAgo(isoStr: string | null): string {
   if (!isoStr) return "Never";
   const diff = Date.now() - new Date(isoStr).getTime();
@@ -36,12 +44,16 @@ interface ServerRowProps {
 export function ServerRow({ server, onSyncClick }: ServerRowProps) {
   const navigate = useNavigate();
   const { isSyncing } = useSyncStore();
-  const { removeServer } = useServerStore();
+  const { servers, referenceServerId, nextSyncAt, removeServer, setReferenceServer } =
+    useServerStore();
+  const nextSync = nextSyncAt[server.id] ?? null;
+  const isReference = referenceServerId === server.id;
+  const relative = isReference ? null : offsetVsReference(server, servers, referenceServerId);
   const health = getHealthLabel(server);
   const syncing = isSyncing(server.id) || server.status === "syncing";
 
   const handleDelete = () => {
-    if (confirm(`Delete server ${server.url}?`)) {
+    if (confirm(`Move ${server.url} to the trash? It can be restored from Settings.`)) {
       removeServer(server.id);
     }
   };
@@ -98,19 +110,30 @@ export function ServerRow({ server, onSyncClick }: ServerRowProps) {
         {getStatusDisplay()}
       </td>
       <td className="px-6 py-5">
-        <div>
-          <p className="font-bold text-[var(--color-text-primary)]">
-            {server.url}
-          </p>
-          {server.name && (
-            <p className="text-xs text-[var(--color-text-secondary)]">
-              {server.name}
+        <div className="flex items-center gap-3">
+          <ServerIcon serverId={server.id} />
+          <div>
+            <p className="font-bold text-[var(--color-text-primary)]">
+              {server.url}
             </p>
-          )}
+            {server.name && (
+              <p className="text-xs text-[var(--color-text-secondary)]">
+                {server.name}
+              </p>
+            )}
+          </div>
         </div>
       </td>
       <td className={`px-6 py-5 font-mono font-bold tabular-nums ${getOffsetColor()}`}>
         {formatOffset(server.offset_ms)}
+        {isReference && (
+          <p className="text-xs font-medium text-[var(--color-accent)]">reference</p>
+        )}
+        {relative !== null && (
+          <p className="text-xs font-medium text-[var(--color-text-secondary)]">
+            {formatOffset(relative)} vs ref
+          </p>
+        )}
       </td>
       <td className="px-6 py-5">
         <span className={`text-sm font-medium ${health.color}`}>
@@ -119,6 +142,9 @@ export function ServerRow({ server, onSyncClick }: ServerRowProps) {
       </td>
       <td className="px-6 py-5 text-sm text-[var(--color-text-secondary)]">
         {formatTimeAgo(server.last_sync_at)}
+        {nextSync && (
+          <p className="text-xs">next {formatTimeUntil(nextSync)}</p>
+        )}
       </td>
       <td className="px-6 py-5 text-right">
         <div className="flex items-center justify-end gap-1 opacity-0 group-hover:opacity-100 transition-opacity">
@@ -134,6 +160,18 @@ export function ServerRow({ server, onSyncClick }: ServerRowProps) {
               <Play className="h-4 w-4" />
             )}
           </button>
+          <button
+            onClick={(e) => {
+              e.stopPropagation();
+              setReferenceServer(isReference ? null : server.id);
+            }}
+            title={isReference ? "Stop using as reference" : "Use as reference"}
+            className={`p-2 rounded-lg transition-colors hover:text-[var(--color-accent)] ${
+              isReference ? "text-[var(--color-accent)]" : "text-[var(--color-text-secondary)]"
+            }`}
+          >
+            <Anchor className="h-4 w-4" />
+          </button>
           <button
             onClick={(e) => { e.stopPropagation(); handleDelete(); }}
             title="Delete"
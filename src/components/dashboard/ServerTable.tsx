@@ -1,13 +1,22 @@
 import { ServerRow } from "@/components/dashboard/ServerRow";
 import { useServerStore } from "@/stores/serverStore";
-import { Globe } from "lucide-react";
+import { Globe, FlaskConical } from "lucide-react";
 
 interface ServerTableProps {
   onSyncClick: (id: number) => void;
 }
 
 export function ServerTable({ onSyncClick }: ServerTableProps) {
-  const { servers, loading } = useServerStore();
+  const { servers, loading, addDemoServer } = useServerStore();
+
+  const handleDemo = async () => {
+    try {
+      const server = await addDemoServer({ offset_ms: 1500, rtt_ms: 40, jitter_ms: 5 });
+      onSyncClick(server.id);
+    } catch {
+      // The store keeps the error.
+    }
+  };
 
   if (loading) {
     return (
@@ -24,6 +33,13 @@ export function ServerTable({ onSyncClick }: ServerTableProps) {
         <p className="text-[var(--color-text-secondary)]">
           No servers added. Enter a URL above to get started.
         </p>
+        <button
+          onClick={handleDemo}
+          className="flex items-center gap-2 rounded-lg border border-[var(--color-border)] px-4 py-2 text-sm font-medium text-[var(--color-text-primary)] hover:bg-[var(--color-border)]/50"
+        >
+          <FlaskConical className="h-4 w-4" />
+          Try a demo server
+        </button>
       </div>
     );
   }
@@ -1,14 +1,22 @@
-import { useServerStore } from "@/stores/serverStore";
+import { offsetVsReference, useServerStore } from "@/stores/serverStore";
 import { useSyncStore } from "@/stores/syncStore";
 
 export function StatsCards() {
-  const { servers } = useServerStore();
+  const { servers, referenceServerId } = useServerStore();
   const { syncResults } = useSyncStore();
 
   const syncedCount = servers.filter((s) => s.status === "synced").length;
 
+  // Against the reference server when one is set, so the local clock's own
+  // error drops out of the average.
+  const hasReference = servers.some(
+    (s) => s.id === referenceServerId && s.offset_ms !== null,
+  );
   const offsets = servers
-    .map((s) => s.offset_ms)
+    .filter((s) => !hasReference || s.id !== referenceServerId)
+    .map((s) =>
+      hasReference ? offsetVsReference(s, servers, referenceServerId) : s.offset_ms,
+    )
     .filter((o): o is number => o !== null);
   const avgOffset = offsets.length > 0
     ? offsets.reduce((a, b) => a + b, 0) / offsets.length
@@ -41,7 +49,7 @@ export function StatsCards() {
 
       <div className="rounded-2xl border border-[var(--color-border)] bg-[var(--color-bg-card)] p-6">
         <p className="mb-1 text-sm font-medium uppercase tracking-tight text-[var(--color-text-secondary)]">
-          Avg. Offset
+          {hasReference ? "Avg. Offset vs Ref" : "Avg. Offset"}
         </p>
         <p className="tabular-nums text-3xl font-bold text-[var(--color-text-primary)]">
           {offsets.length > 0 ? (
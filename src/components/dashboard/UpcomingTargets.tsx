@@ -0,0 +1,124 @@
+import { useServerStore } from "@/stores/serverStore";
+import { Select } from "@/components/ui/Select";
+import { setTargetAlertMethod } from "@/lib/commands";
+import { cn } from "@/lib/utils";
+import type { TargetConfidence } from "@/types/app";
+import type { AlertMethod } from "@/types/settings";
+
+// "" follows the alert method setting.
+const TARGET_ALERT_OPTIONS = [
+  { value: "", label: "Default alert" },
+  { value: "notification", label: "Notification" },
+  { value: "sound", label: "Sound" },
+  { value: "both", label: "Both" },
+  { value: "speak", label: "Spoken" },
+  { value: "none", label: "No alert" },
+];
+
+function formatRemaining(ms: number | null): string {
+  if (ms === null) return "not synced";
+  const minutes = Math.floor(ms / 60_000);
+  if (minutes < 60) return `${minutes}m ${Math.floor((ms % 60_000) / 1000)}s`;
+  if (minutes < 24 * 60) return `${Math.floor(minutes / 60)}h ${minutes % 60}m`;
+  return `${Math.floor(minutes / (24 * 60))}d`;
+}
+
+// The target's wall-clock time where the server is, e.g. "10:00 JST".
+function serverLocalTime(at: string, timezone: string | null | undefined): string | null {
+  if (!timezone) return null;
+  try {
+    return new Date(at).toLocaleTimeString(undefined, {
+      timeZone: timezone,
+      hour: "2-digit",
+      minute: "2-digit",
+      timeZoneName: "short",
+    });
+  } catch {
+    return null;
+  }
+}
+
+function confidenceLabel(c: TargetConfidence): string {
+  switch (c.confidence) {
+    case "confirmed":
+      return `checked T-${c.seconds_before}s`;
+    case "adjusted": {
+      const moved = c.offset_ms - c.previous_offset_ms;
+      return `T-${c.seconds_before}s: ${moved >= 0 ? "+" : ""}${moved.toFixed(0)} ms`;
+    }
+    case "failed":
+      return `T-${c.seconds_before}s check failed`;
+  }
+}
+
+export function UpcomingTargets() {
+  const { upcomingTargets, servers, targetConfidence, fetchDashboard } = useServerStore();
+
+  const changeAlertMethod = async (targetId: number, value: string) => {
+    await setTargetAlertMethod(targetId, value === "" ? null : (value as AlertMethod));
+    await fetchDashboard();
+  };
+
+  if (upcomingTargets.length === 0) return null;
+
+  return (
+    <div className="rounded-2xl border border-[var(--color-border)] bg-[var(--color-bg-card)] p-6">
+      <p className="mb-3 text-sm font-medium uppercase tracking-tight text-[var(--color-text-secondary)]">
+        Upcoming Targets
+      </p>
+      <ul className="space-y-2">
+        {upcomingTargets.map(({ target, remaining_ms }) => {
+          const server = servers.find((s) => s.id === target.server_id);
+          const confidence = targetConfidence[target.id];
+          const serverTime = serverLocalTime(target.target_at, server?.timezone);
+          return (
+            <li key={target.id} className="flex items-center justify-between text-sm">
+              <span className="text-[var(--color-text-primary)]">
+                {target.label ?? "Target"}
+                <span className="ml-2 text-xs text-[var(--color-text-secondary)]">
+                  {server?.name ?? server?.url}
+                </span>
+                {serverTime && (
+                  <span
+                    title={`${server?.timezone} (server-local)`}
+                    className="ml-2 font-mono text-xs text-[var(--color-text-secondary)]"
+                  >
+                    {serverTime}
+                  </span>
+                )}
+              </span>
+              <span className="flex items-center gap-2">
+                <Select
+                  aria-label="Alert method"
+                  options={TARGET_ALERT_OPTIONS}
+                  value={target.alert_method ?? ""}
+                  onChange={(e) => changeAlertMethod(target.id, e.target.value)}
+                  className="w-36 text-xs"
+                />
+                {confidence && (
+                  <span
+                    title={confidence.error ?? undefined}
+                    className={cn(
+                      "rounded-full px-2 py-0.5 text-[10px] font-medium",
+                      confidence.confidence === "confirmed" &&
+                        "bg-[var(--color-success)]/10 text-[var(--color-success)]",
+                      confidence.confidence === "adjusted" &&
+                        "bg-[var(--color-accent)]/10 text-[var(--color-accent)]",
+                      confidence.confidence === "failed" &&
+                        "bg-[var(--color-danger)]/10 text-[var(--color-danger)]",
+                    )}
+                  >
+                    {confidenceLabel(confidence)}
+                  </span>
+                )}
+                <span className="font-mono tabular-nums text-[var(--color-text-primary)]">
+                  {formatRemaining(remaining_ms)}
+                </span>
+              </span>
+            </li>
+          );
+        })}
+      </ul>
+    </div>
+  );
+}
@@ -1,6 +1,7 @@
 import { Timer, LayoutDashboard, Activity, BarChart3, Settings } from "lucide-react";
 import { NavLink } from "react-router-dom";
 import { ThemeToggle } from "@/components/ui/ThemeToggle";
+import { WorkspaceSwitcher } from "@/components/layout/WorkspaceSwitcher";
 import { cn } from "@/lib/utils";
 
 export function Sidebar() {
@@ -69,6 +70,7 @@ export function Sidebar() {
 
       {/* Footer */}
       <div className="space-y-3 border-t border-[var(--color-border)] p-4">
+        <WorkspaceSwitcher />
         <ThemeToggle />
         <div className="flex items-center gap-2 px-2">
           <div className="h-2 w-2 rounded-full bg-[var(--color-success)] glow-success"></div>
@@ -1,14 +1,29 @@
-import { useState } from "react";
+import { useState, type ClipboardEvent } from "react";
+import { useNavigate } from "react-router-dom";
 import { Globe, RefreshCw } from "lucide-react";
 import { useServerStore } from "@/stores/serverStore";
 import { useSyncStore } from "@/stores/syncStore";
+import * as commands from "@/lib/commands";
+import { errorMessage, isAppError } from "@/lib/utils";
+import type { UrlValidation } from "@/types/app";
+
+function describeProbe(v: UrlValidation): string {
+  const parts = [v.http_version ?? "HTTP", `${Math.round(v.rtt_ms ?? 0)} ms`];
+  if (v.redirects.length > 0) {
+    parts.push(`${v.redirects.length} redirect${v.redirects.length > 1 ? "s" : ""}`);
+  }
+  return parts.join(" · ");
+}
 
 export function TopBar() {
   const [url, setUrl] = useState("");
   const [adding, setAdding] = useState(false);
   const [error, setError] = useState<string | null>(null);
-  const { addServer, servers } = useServerStore();
+  const [probe, setProbe] = useState<UrlValidation | null>(null);
+  const [notice, setNotice] = useState<string | null>(null);
+  const { addServer, addServersFromText, servers } = useServerStore();
   const { startSync } = useSyncStore();
+  const navigate = useNavigate();
 
   const handleAdd = async () => {
     if (!url.trim()) return;
@@ -17,13 +32,61 @@ export function TopBar() {
     setError(null);
     try {
       let finalUrl = url.trim();
-      if (!finalUrl.startsWith("http://") && !finalUrl.startsWith("https://")) {
+      const isDemo = finalUrl.startsWith("demo://");
+      if (!isDemo && !finalUrl.startsWith("http://") && !finalUrl.startsWith("https://")) {
         finalUrl = `https://${finalUrl}`;
       }
+
+      // Probe first; a failing probe needs a second click to save anyway.
+      // Demo servers are simulated, so there is nothing to probe.
+      if (!isDemo && probe?.url !== finalUrl) {
+        const result = await commands.validateUrl(finalUrl);
+        setProbe(result);
+        if (!result.reachable) {
+          setError(`Unreachable: ${result.error}. Press Add again to save anyway.`);
+          return;
+        }
+        if (!result.has_date_header) {
+          setError(`No Date header (${describeProbe(result)}). Press Add again to save anyway.`);
+          return;
+        }
+      }
       await addServer(finalUrl);
       setUrl("");
+      setProbe(null);
     } catch (e) {
-      setError(String(e));
+      // Already added under another spelling: show that server instead.
+      if (isAppError(e) && e.code === "duplicate_server" && e.server_id !== undefined) {
+        setUrl("");
+        setProbe(null);
+        navigate(`/servers/${e.server_id}`);
+        return;
+      }
+      setError(errorMessage(e));
+    } finally {
+      setAdding(false);
+    }
+  };
+
+  // A pasted list (or message) goes straight to the bulk add; a single
+  // URL lands in the input as usual.
+  const handlePaste = async (e: ClipboardEvent<HTMLInputElement>) => {
+    const text = e.clipboardData.getData("text");
+    if (!/\s/.test(text.trim())) return;
+    e.preventDefault();
+    setAdding(true);
+    setError(null);
+    setNotice(null);
+    try {
+      const report = await addServersFromText(text);
+      const count = (status: string) => report.filter((r) => r.status === status).length;
+      setNotice(
+        report.length === 0
+          ? "No URLs found in the pasted text."
+          : `Added ${count("added")}, skipped ${count("duplicate")} duplicate(s), ${count("invalid")} invalid.`,
+      );
+    } catch (err) {
+      setError(errorMessage(err));
     } finally {
       setAdding(false);
     }
@@ -49,8 +112,11 @@ export function TopBar() {
             onChange={(e) => {
               setUrl(e.target.value);
               setError(null);
+              setNotice(null);
+              setProbe(null);
             }}
             onKeyDown={(e) => e.key === "Enter" && handleAdd()}
+            onPaste={handlePaste}
             disabled={adding}
             className="w-full rounded-xl border-none bg-[var(--color-input-bg)] py-3 pl-12 pr-32 text-sm text-[var(--color-text-primary)] placeholder:text-[var(--color-text-secondary)] focus:outline-none focus:ring-2 focus:ring-[var(--color-accent)]"
           />
@@ -77,6 +143,9 @@ export function TopBar() {
       {error && (
         <p className="mt-2 text-sm text-[var(--color-danger)]">{error}</p>
       )}
+      {notice && (
+        <p className="mt-2 text-sm text-[var(--color-text-secondary)]">{notice}</p>
+      )}
     </div>
   );
 }
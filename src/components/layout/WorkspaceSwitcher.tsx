@@ -0,0 +1,42 @@
+import { useEffect, useState } from "react";
+import { Select } from "@/components/ui/Select";
+import { errorMessage } from "@/lib/utils";
+import * as commands from "@/lib/commands";
+import type { Workspace } from "@/types/app";
+
+const NEW_WORKSPACE = "__new__";
+
+export function WorkspaceSwitcher() {
+  const [workspaces, setWorkspaces] = useState<Workspace[]>([]);
+
+  useEffect(() => {
+    commands.listWorkspaces().then(setWorkspaces);
+  }, []);
+
+  const handleChange = async (value: string) => {
+    const name =
+      value === NEW_WORKSPACE
+        ? window.prompt("New workspace name (lowercase letters, digits, - or _)")?.trim()
+        : value;
+    if (!name) return;
+    try {
+      setWorkspaces(await commands.switchWorkspace(name));
+    } catch (e) {
+      window.alert(`Could not switch workspace: ${errorMessage(e)}`);
+    }
+  };
+
+  const active = workspaces.find((w) => w.active)?.name ?? "";
+
+  return (
+    <Select
+      options={[
+        ...workspaces.map((w) => ({ value: w.name, label: w.name })),
+        { value: NEW_WORKSPACE, label: "New workspace…" },
+      ]}
+      value={active}
+      onChange={(e) => handleChange(e.target.value)}
+      disabled={workspaces.length === 0}
+    />
+  );
+}
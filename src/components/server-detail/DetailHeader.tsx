@@ -1,13 +1,28 @@
-import { RefreshCw, XCircle } from "lucide-react";
+import { useState } from "react";
+import { Activity, Gauge, Power, RefreshCw, ShieldCheck, XCircle, Zap } from "lucide-react";
 import { Button } from "@/components/ui/Button";
+import { NumberInput } from "@/components/ui/NumberInput";
 import { ExportButton } from "@/components/server-detail/ExportButton";
-import type { Server, SyncResult } from "@/types/server";
+import { ShareReportButton } from "@/components/server-detail/ShareReportButton";
+import { DomainPresetSelect } from "@/components/server-detail/DomainPresetSelect";
+import { ExtractorSelect } from "@/components/server-detail/ExtractorSelect";
+import { SyncProfileSelect } from "@/components/settings/SyncProfileSelect";
+import type { BenchmarkRun, Server, SyncResult } from "@/types/server";
 import { useSyncStore } from "@/stores/syncStore";
+import * as commands from "@/lib/commands";
+import { errorMessage } from "@/lib/utils";
+
+const METHOD_LABELS: Record<BenchmarkRun["method"], string> = {
+  four_phase: "4-phase",
+  interval_intersection: "Intervals",
+};
 
 interface DetailHeaderProps {
   server: Server;
   syncHistory: SyncResult[];
   onResync?: () => void;
+  // Called after a change that rewrites the server record (e.g. its URL).
+  onServerChange?: () => void;
   isSyncing?: boolean;
 }
 
@@ -15,9 +30,139 @@ export function DetailHeader({
   server,
   syncHistory,
   onResync,
+  onServerChange,
   isSyncing,
 }: DetailHeaderProps) {
   const { cancelSync } = useSyncStore();
+  const [testing, setTesting] = useState(false);
+  const [testResult, setTestResult] = useState<string | null>(null);
+  const [profile, setProfile] = useState(server.sync_profile);
+  const [preset, setPreset] = useState(server.domain_preset);
+  const [extractor, setExtractor] = useState(server.extractor_type);
+  const [syncOnLaunch, setSyncOnLaunch] = useState(server.sync_on_launch);
+  const [intervalMs, setIntervalMs] = useState(server.min_request_interval_ms?.toString() ?? "");
+  const [trusted, setTrusted] = useState(server.trusted);
+
+  const handleProfileChange = async (next: string | null) => {
+    const previous = profile;
+    setProfile(next);
+    try {
+      await commands.setServerSyncProfile(server.id, next);
+    } catch {
+      setProfile(previous);
+    }
+  };
+
+  const handlePresetChange = async (next: string | null) => {
+    const previous = preset;
+    setPreset(next);
+    try {
+      await commands.setServerDomainPreset(server.id, next);
+      onServerChange?.();
+    } catch {
+      setPreset(previous);
+    }
+  };
+
+  const handleExtractorChange = async (next: string) => {
+    const previous = extractor;
+    setExtractor(next);
+    try {
+      await commands.setServerExtractor(server.id, next);
+      onServerChange?.();
+    } catch (e) {
+      setExtractor(previous);
+      setTestResult(`Extractor not changed · ${errorMessage(e)}`);
+    }
+  };
+
+  // Saved on blur; an empty field goes back to the global setting.
+  const handleIntervalCommit = async () => {
+    const next = intervalMs.trim() === "" ? null : Number(intervalMs);
+    if (next === server.min_request_interval_ms) return;
+    try {
+      await commands.setServerRequestInterval(server.id, next);
+      onServerChange?.();
+    } catch (e) {
+      setIntervalMs(server.min_request_interval_ms?.toString() ?? "");
+      setTestResult(`Interval not saved · ${errorMessage(e)}`);
+    }
+  };
+
+  const handleSyncOnLaunchToggle = async () => {
+    const next = !syncOnLaunch;
+    setSyncOnLaunch(next);
+    try {
+      await commands.setServerSyncOnLaunch(server.id, next);
+    } catch {
+      setSyncOnLaunch(!next);
+    }
+  };
+
+  const handleTrustedToggle = async () => {
+    const next = !trusted;
+    setTrusted(next);
+    try {
+      await commands.setServerTrusted(server.id, next);
+    } catch {
+      setTrusted(!next);
+    }
+  };
+
+  const handleTest = async () => {
+    setTesting(true);
+    try {
+      const result = await commands.testConnection(server.id);
+      setTestResult(
+        `Up · min ${result.min_rtt_ms.toFixed(0)} ms · median ${result.median_rtt_ms.toFixed(0)} ms`,
+      );
+    } catch (e) {
+      setTestResult(`Down · ${errorMessage(e)}`);
+    } finally {
+      setTesting(false);
+    }
+  };
+
+  const handleEstimate = async () => {
+    setTesting(true);
+    try {
+      const estimate = await commands.estimateOffset(server.id);
+      setTestResult(
+        `≈ ${estimate.offset_ms.toFixed(0)} ±${estimate.uncertainty_ms.toFixed(0)} ms (unverified, 1 probe)`,
+      );
+    } catch (e) {
+      setTestResult(`Estimate failed · ${errorMessage(e)}`);
+    } finally {
+      setTesting(false);
+    }
+  };
+
+  const handleBenchmark = async () => {
+    setTesting(true);
+    setTestResult("Benchmarking…");
+    try {
+      const report = await commands.benchmarkSync(server.id);
+      const describe = (run: BenchmarkRun) =>
+        run.offset_ms === null
+          ? `${METHOD_LABELS[run.method]} failed`
+          : `${METHOD_LABELS[run.method]} ${run.offset_ms.toFixed(1)} ±${(run.uncertainty_ms ?? 0).toFixed(1)} ms`;
+      const best = report.recommended ? ` · best: ${METHOD_LABELS[report.recommended]}` : "";
+      setTestResult(report.runs.map(describe).join(" · ") + best);
+    } catch (e) {
+      setTestResult(`Benchmark failed · ${errorMessage(e)}`);
+    } finally {
+      setTesting(false);
+    }
+  };
+
+  const handleRefreshName = async () => {
+    try {
+      await commands.refreshServerName(server.id);
+      onServerChange?.();
+    } catch (e) {
+      setTestResult(errorMessage(e));
+    }
+  };
 
   const handleCancel = () => {
     cancelSync(server.id);
@@ -26,9 +171,19 @@ export function DetailHeader({
   return (
     <div className="flex items-start justify-between gap-4">
       <div className="min-w-0 flex-1">
-        <h1 className="text-2xl font-bold text-[var(--color-text-primary)] font-[var(--font-display)] truncate">
-          {server.name ?? server.url}
-        </h1>
+        <div className="flex items-center gap-2">
+          <h1 className="text-2xl font-bold text-[var(--color-text-primary)] font-[var(--font-display)] truncate">
+            {server.name ?? server.url}
+          </h1>
+          <Button
+            variant="ghost"
+            size="sm"
+            onClick={handleRefreshName}
+            title="Rename from the page's site name or title"
+          >
+            <RefreshCw className="h-4 w-4" />
+          </Button>
+        </div>
         {server.name && (
           <p className="mt-1 font-mono text-sm text-[var(--color-text-secondary)] truncate">
             {server.url}
@@ -36,7 +191,79 @@ export function DetailHeader({
         )}
       </div>
       <div className="flex items-center gap-2 shrink-0">
-        <ExportButton syncHistory={syncHistory} serverName={server.name ?? server.url} />
+        {testResult && (
+          <span className="text-xs text-[var(--color-text-secondary)]">{testResult}</span>
+        )}
+        <SyncProfileSelect
+          value={profile}
+          onChange={handleProfileChange}
+          allowInherit
+          className="w-40"
+        />
+        <NumberInput
+          value={intervalMs}
+          min={100}
+          max={10000}
+          step={100}
+          unit="ms"
+          placeholder="Default"
+          title="Minimum gap between probes to this server; empty follows the global setting"
+          onChange={(e) => setIntervalMs(e.target.value)}
+          onBlur={handleIntervalCommit}
+          className="w-32"
+        />
+        <Button
+          variant={trusted ? "success" : "ghost"}
+          size="sm"
+          onClick={handleTrustedToggle}
+          aria-pressed={trusted}
+          title="Self-hosted: may be probed faster than the public host floor"
+        >
+          <ShieldCheck className="mr-1.5 h-4 w-4" />
+          Trusted
+        </Button>
+        <DomainPresetSelect value={preset} onChange={handlePresetChange} className="w-44" />
+        <ExtractorSelect value={extractor} onChange={handleExtractorChange} className="w-40" />
+        <Button
+          variant={syncOnLaunch ? "success" : "ghost"}
+          size="sm"
+          onClick={handleSyncOnLaunchToggle}
+          aria-pressed={syncOnLaunch}
+          title="Start a sync for this server whenever the app launches"
+        >
+          <Power className="mr-1.5 h-4 w-4" />
+          On Launch
+        </Button>
+        <Button variant="ghost" size="sm" onClick={handleTest} disabled={testing}>
+          <Activity className="mr-1.5 h-4 w-4" />
+          Test
+        </Button>
+        <Button
+          variant="ghost"
+          size="sm"
+          onClick={handleEstimate}
+          disabled={testing}
+          title="Single-probe rough offset for when a full sync won't finish in time"
+        >
+          <Zap className="mr-1.5 h-4 w-4" />
+          Estimate
+        </Button>
+        <Button
+          variant="ghost"
+          size="sm"
+          onClick={handleBenchmark}
+          disabled={testing || isSyncing}
+          title="Compare the 4-phase sync with the interval estimator"
+        >
+          <Gauge className="mr-1.5 h-4 w-4" />
+          Benchmark
+        </Button>
+        <ExportButton
+          serverId={server.id}
+          syncHistory={syncHistory}
+          serverName={server.name ?? server.url}
+        />
+        <ShareReportButton result={syncHistory[0]} />
         {isSyncing ? (
           <Button variant="danger" size="sm" onClick={handleCancel}>
             <XCircle className="mr-1.5 h-4 w-4" />
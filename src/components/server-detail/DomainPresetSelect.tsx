@@ -0,0 +1,34 @@
+import { useEffect, useState } from "react";
+import { Select } from "@/components/ui/Select";
+import type { DomainPreset } from "@/types/server";
+import * as commands from "@/lib/commands";
+
+interface DomainPresetSelectProps {
+  value: string | null;
+  onChange: (preset: string | null) => void;
+  className?: string;
+}
+
+export function DomainPresetSelect({ value, onChange, className }: DomainPresetSelectProps) {
+  const [presets, setPresets] = useState<DomainPreset[]>([]);
+
+  useEffect(() => {
+    commands.listDomainPresets().then(setPresets);
+  }, []);
+
+  const options = [
+    { value: "", label: "No preset" },
+    ...presets.map((p) => ({ value: p.id, label: p.label })),
+  ];
+  const selected = presets.find((p) => p.id === value);
+
+  return (
+    <Select
+      options={options}
+      value={value ?? ""}
+      title={selected ? `${selected.probe_method} ${selected.probe_path ?? "/"}` : undefined}
+      onChange={(e) => onChange(e.target.value === "" ? null : e.target.value)}
+      className={className}
+    />
+  );
+}
@@ -0,0 +1,58 @@
+import { useEffect, useState } from "react";
+import { AlertTriangle } from "lucide-react";
+import { Card } from "@/components/ui/Card";
+import { getErrorHistory } from "@/lib/commands";
+import { useSyncStore } from "@/stores/syncStore";
+import type { SyncErrorRecord } from "@/types/server";
+
+const SHOWN = 20;
+
+interface ErrorHistoryProps {
+  serverId: number;
+}
+
+export function ErrorHistory({ serverId }: ErrorHistoryProps) {
+  const [errors, setErrors] = useState<SyncErrorRecord[]>([]);
+  // Refetch after each failed sync of this server.
+  const lastError = useSyncStore((s) => s.syncErrors[serverId]);
+
+  useEffect(() => {
+    getErrorHistory(serverId, SHOWN).then(setErrors).catch(() => setErrors([]));
+  }, [serverId, lastError]);
+
+  if (errors.length === 0) return null;
+
+  return (
+    <Card>
+      <div className="mb-4 flex items-center gap-2">
+        <AlertTriangle className="h-5 w-5 text-[var(--color-warning)]" />
+        <h3 className="text-lg font-bold text-[var(--color-text-primary)]">
+          Recent Failures
+        </h3>
+      </div>
+      <table className="w-full text-sm">
+        <tbody>
+          {errors.map((e) => (
+            <tr key={e.id} className="border-t border-[var(--color-border)]">
+              <td className="py-2 pr-4 text-[var(--color-text-secondary)] tabular-nums">
+                {new Date(e.occurred_at).toLocaleString()}
+              </td>
+              <td className="py-2 pr-4 font-mono text-xs text-[var(--color-text-secondary)]">
+                {e.phase}
+              </td>
+              <td className="py-2 pr-4 font-mono text-xs text-[var(--color-warning)]">
+                {e.code}
+              </td>
+              <td className="py-2 text-[var(--color-text-primary)]">
+                {e.message}
+                <span className="ml-2 text-xs text-[var(--color-text-secondary)]">
+                  after {e.total_attempts} probes
+                </span>
+              </td>
+            </tr>
+          ))}
+        </tbody>
+      </table>
+    </Card>
+  );
+}
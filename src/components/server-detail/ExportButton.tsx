@@ -1,17 +1,18 @@
 import { useState, useRef, useEffect } from "react";
 import { save } from "@tauri-apps/plugin-dialog";
-import { writeTextFile } from "@tauri-apps/plugin-fs";
 import { Download } from "lucide-react";
 import { Button } from "@/components/ui/Button";
-import { syncHistoryToCsv, syncHistoryToJson } from "@/lib/export";
+import { exportOffsetSeries, writeExport } from "@/lib/commands";
+import { askExportPassphrase, syncHistoryToCsv, syncHistoryToJson } from "@/lib/export";
 import type { SyncResult } from "@/types/server";
 
 interface ExportButtonProps {
+  serverId: number;
   syncHistory: SyncResult[];
   serverName?: string;
 }
 
-export function ExportButton({ syncHistory, serverName }: ExportButtonProps) {
+export function ExportButton({ serverId, syncHistory, serverName }: ExportButtonProps) {
   const [open, setOpen] = useState(false);
   const containerRef = useRef<HTMLDivElement>(null);
 
@@ -29,18 +30,21 @@ export function ExportButton({ syncHistory, serverName }: ExportButtonProps) {
     return () => document.removeEventListener("mousedown", handler);
   }, [open]);
 
-  const exportAs = async (format: "csv" | "json") => {
-    setOpen(false);
-    const ext = format;
-    const filterName = format.toUpperCase();
+  const pickPath = (format: "csv" | "json", title: string, suffix: string) => {
     const slug = (serverName ?? "server").replace(/[^a-zA-Z0-9_-]/g, "_");
     const timestamp = new Date().toISOString().replace(/[:]/g, "-").slice(0, 19);
-    const defaultFilename = `ticketime_${slug}_${timestamp}.${ext}`;
-    const path = await save({
-      title: "Export Sync Logs",
-      defaultPath: defaultFilename,
-      filters: [{ name: filterName, extensions: [ext] }],
+    return save({
+      title,
+      defaultPath: `ticketime_${slug}${suffix}_${timestamp}.${format}`,
+      filters: [{ name: format.toUpperCase(), extensions: [format] }],
     });
+  };
+
+  const exportAs = async (format: "csv" | "json") => {
+    setOpen(false);
+    const passphrase = askExportPassphrase();
+    if (passphrase === null) return;
+    const path = await pickPath(format, "Export Sync Logs", "");
     if (!path) return;
 
     const content =
@@ -48,7 +52,17 @@ export function ExportButton({ syncHistory, serverName }: ExportButtonProps) {
         ? syncHistoryToCsv(syncHistory)
         : syncHistoryToJson(syncHistory);
 
-    await writeTextFile(path, content);
+    await writeExport(path, content, passphrase);
+  };
+
+  // Offsets only, oldest first, for plotting tools.
+  const exportSeries = async (format: "csv" | "json") => {
+    setOpen(false);
+    const passphrase = askExportPassphrase();
+    if (passphrase === null) return;
+    const path = await pickPath(format, "Export Offset Series", "_offsets");
+    if (!path) return;
+    await exportOffsetSeries(serverId, path, format, passphrase);
   };
 
   return (
@@ -77,6 +91,19 @@ export function ExportButton({ syncHistory, serverName }: ExportButtonProps) {
           >
             Export as JSON
           </button>
+          <div className="my-1 border-t border-[var(--color-border)]" />
+          <button
+            className="w-full text-left px-3 py-2 text-sm text-[var(--color-text-primary)] hover:bg-[var(--color-border)]/50 cursor-pointer"
+            onClick={() => exportSeries("csv")}
+          >
+            Offset series (CSV)
+          </button>
+          <button
+            className="w-full text-left px-3 py-2 text-sm text-[var(--color-text-primary)] hover:bg-[var(--color-border)]/50 cursor-pointer"
+            onClick={() => exportSeries("json")}
+          >
+            Offset series (JSON)
+          </button>
         </div>
       )}
     </div>
@@ -0,0 +1,34 @@
+import { useEffect, useState } from "react";
+import { Select } from "@/components/ui/Select";
+import type { ExtractorInfo } from "@/types/server";
+import * as commands from "@/lib/commands";
+
+interface ExtractorSelectProps {
+  value: string;
+  onChange: (extractorType: string) => void;
+  className?: string;
+}
+
+export function ExtractorSelect({ value, onChange, className }: ExtractorSelectProps) {
+  const [extractors, setExtractors] = useState<ExtractorInfo[]>([]);
+
+  useEffect(() => {
+    commands.listExtractors().then(setExtractors);
+  }, []);
+
+  const options = extractors.map((e) => ({ value: e.id, label: e.name }));
+  // A plugin that has since been removed still shows as the current value.
+  if (!extractors.some((e) => e.id === value)) {
+    options.push({ value, label: `${value} (not loaded)` });
+  }
+
+  return (
+    <Select
+      options={options}
+      value={value}
+      title={extractors.find((e) => e.id === value)?.plugin ?? undefined}
+      onChange={(e) => onChange(e.target.value)}
+      className={className}
+    />
+  );
+}
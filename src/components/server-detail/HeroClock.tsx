@@ -1,8 +1,9 @@
-import { useEffect, useRef, useCallback } from "react";
+import { useEffect, useRef, useCallback, useState } from "react";
 import { useServerStore } from "@/stores/serverStore";
 import { Card } from "@/components/ui/Card";
+import * as commands from "@/lib/commands";
 import { cn } from "@/lib/utils";
-import type { Server, SyncResult } from "@/types/server";
+import type { Server, ServerTime, SyncResult } from "@/types/server";
 
 interface HeroClockProps {
   serverId: number;
@@ -27,9 +28,31 @@ export function HeroClock({ serverId, server, latestResult }: HeroClockProps) {
   const msRef = useRef<HTMLSpanElement>(null);
   const rafRef = useRef<number>(0);
 
-  const offsetMs = useServerStore(
+  const storedOffsetMs = useServerStore(
     (s) => s.servers.find((srv) => srv.id === serverId)?.offset_ms ?? null,
   );
+  const [time, setTime] = useState<ServerTime | null>(null);
+
+  // The stored offset carried forward along the server's drift; refreshed
+  // each minute as the extrapolation moves on.
+  useEffect(() => {
+    setTime(null);
+    if (storedOffsetMs === null) return;
+    let cancelled = false;
+    const refresh = () =>
+      commands
+        .getCorrectedTime(serverId)
+        .then((t) => !cancelled && setTime(t))
+        .catch(() => {});
+    refresh();
+    const timer = setInterval(refresh, 60_000);
+    return () => {
+      cancelled = true;
+      clearInterval(timer);
+    };
+  }, [serverId, storedOffsetMs, server.last_sync_at]);
+
+  const offsetMs = time?.offset_ms ?? storedOffsetMs;
 
   const tick = useCallback(() => {
     const now = Date.now();
@@ -133,6 +156,12 @@ export function HeroClock({ serverId, server, latestResult }: HeroClockProps) {
             )}>
               {offsetMs !== null ? formatOffset(offsetMs) : "—"}
             </div>
+            {time?.uncertainty_ms != null && (
+              <div className="mt-1 font-mono text-xs text-[var(--color-text-secondary)]">
+                ±{time.uncertainty_ms.toFixed(1)}ms
+                {time.stale && " · stale"}
+              </div>
+            )}
           </div>
           <div>
             <div className="text-xs uppercase tracking-wider text-[var(--color-text-secondary)] mb-1">
@@ -0,0 +1,82 @@
+import { useEffect, useState } from "react";
+import { Clock3 } from "lucide-react";
+import { Card } from "@/components/ui/Card";
+import { getLatencyStats } from "@/lib/commands";
+import { useSyncStore } from "@/stores/syncStore";
+import type { LatencyBucket, LatencyStats } from "@/types/server";
+
+const WEEKDAYS = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+const BAR_HEIGHT = 64;
+
+interface LatencyByHourProps {
+  serverId: number;
+}
+
+function describe(bucket: LatencyBucket, label: string): string {
+  if (bucket.median_rtt_ms === null) return `${label}: no syncs`;
+  return `${label}: ${bucket.median_rtt_ms.toFixed(0)} ms RTT, ${bucket.jitter_ms?.toFixed(1)} ms jitter (${bucket.syncs} syncs)`;
+}
+
+export function LatencyByHour({ serverId }: LatencyByHourProps) {
+  const [stats, setStats] = useState<LatencyStats | null>(null);
+  // Refetch after each completed sync of this server.
+  const lastResult = useSyncStore((s) => s.syncResults[serverId]);
+
+  useEffect(() => {
+    getLatencyStats(serverId).then(setStats).catch(() => setStats(null));
+  }, [serverId, lastResult]);
+
+  if (!stats || stats.syncs === 0) return null;
+
+  const slowest = Math.max(...stats.by_hour.map((b) => b.median_rtt_ms ?? 0), 1);
+
+  return (
+    <Card>
+      <div className="mb-4 flex items-center gap-2">
+        <Clock3 className="h-5 w-5 text-[var(--color-text-secondary)]" />
+        <h3 className="text-lg font-bold text-[var(--color-text-primary)]">
+          Latency by Time of Day
+        </h3>
+        <span className="ml-auto text-xs text-[var(--color-text-secondary)]">
+          {stats.timezone ?? "local time"} · {stats.syncs} syncs
+        </span>
+      </div>
+      <div className="flex items-end gap-0.5" style={{ height: BAR_HEIGHT }}>
+        {stats.by_hour.map((b) => (
+          <div
+            key={b.slot}
+            title={describe(b, `${String(b.slot).padStart(2, "0")}:00`)}
+            className={
+              b.slot === stats.quietest_hour
+                ? "flex-1 rounded-sm bg-[var(--color-success)]"
+                : "flex-1 rounded-sm bg-[var(--color-accent)]/60"
+            }
+            style={{ height: `${((b.median_rtt_ms ?? 0) / slowest) * 100}%` }}
+          />
+        ))}
+      </div>
+      <div className="mt-1 flex justify-between text-[10px] text-[var(--color-text-secondary)] tabular-nums">
+        <span>00</span>
+        <span>06</span>
+        <span>12</span>
+        <span>18</span>
+        <span>23</span>
+      </div>
+      <div className="mt-3 flex gap-3 text-xs text-[var(--color-text-secondary)]">
+        {stats.by_weekday.map((b) => (
+          <span key={b.slot} title={describe(b, WEEKDAYS[b.slot])}>
+            {WEEKDAYS[b.slot]}{" "}
+            <span className="tabular-nums text-[var(--color-text-primary)]">
+              {b.median_rtt_ms === null ? "–" : `${b.median_rtt_ms.toFixed(0)}`}
+            </span>
+          </span>
+        ))}
+      </div>
+      <p className="mt-3 text-xs text-[var(--color-text-secondary)]">
+        {stats.quietest_hour === null
+          ? "Not enough syncs yet to name a quietest hour."
+          : `Quietest hour: ${String(stats.quietest_hour).padStart(2, "0")}:00, the least jitter for high-precision syncs.`}
+      </p>
+    </Card>
+  );
+}
@@ -1,5 +1,6 @@
 import { Globe, Clock, Cpu, BarChart3 } from "lucide-react";
 import { Card } from "@/components/ui/Card";
+import { GradeBadge } from "@/components/ui/GradeBadge";
 import type { Server, SyncResult } from "@/types/server";
 
 interface MetadataCardsProps {
@@ -29,12 +30,19 @@ function extractDomain(url: string): string {
   }
 }
 
+// Matches the backend's expiry warning.
+const CERTIFICATE_WARNING_DAYS = 14;
+
+function daysUntil(dateStr: string): number {
+  return Math.floor((new Date(dateStr).getTime() - Date.now()) / 86_400_000);
+}
+
 function extractorLabel(type: string): string {
   switch (type) {
     case "date_header":
       return "HTTP Date Header";
     default:
-      return type;
+      return type.startsWith("wasm:") ? "WASM Plugin" : type;
   }
 }
 
@@ -59,6 +67,33 @@ export function MetadataCards({
         <p className="mt-1 text-xs text-[var(--color-text-secondary)]">
           {extractDomain(server.url)}
         </p>
+        {server.fingerprint && (
+          <p
+            className="mt-1 text-xs text-[var(--color-text-secondary)] truncate"
+            title={[server.fingerprint.via, server.fingerprint.cache]
+              .filter(Boolean)
+              .join(" · ")}
+          >
+            {[server.fingerprint.cdn, server.fingerprint.server]
+              .filter(Boolean)
+              .join(" · ")}
+          </p>
+        )}
+        {latestResult?.response?.certificate && (
+          <p
+            className="mt-1 text-xs truncate"
+            title={`${latestResult.response.certificate.subject}\nSHA-256 ${latestResult.response.certificate.sha256}`}
+            style={{
+              color:
+                daysUntil(latestResult.response.certificate.not_after) < CERTIFICATE_WARNING_DAYS
+                  ? "var(--color-warning)"
+                  : "var(--color-text-secondary)",
+            }}
+          >
+            {latestResult.response.certificate.issuer} · expires{" "}
+            {formatDate(latestResult.response.certificate.not_after)}
+          </p>
+        )}
       </Card>
 
       {/* Last Sync */}
@@ -72,6 +107,16 @@ export function MetadataCards({
         <p className="text-sm text-[var(--color-text-primary)]">
           {formatDate(server.last_sync_at)}
         </p>
+        {latestResult?.response && (
+          <p className="mt-1 text-xs font-mono text-[var(--color-text-secondary)] truncate">
+            {latestResult.response.status} · {latestResult.response.http_version}
+            {latestResult.response.remote_ip && ` · ${latestResult.response.remote_ip}`}
+            {latestResult.response.redirects > 0 &&
+              ` · ${latestResult.response.redirects} redirect${latestResult.response.redirects === 1 ? "" : "s"}`}
+            {latestResult.response.probe_overhead_us > 0 &&
+              ` · ${latestResult.response.probe_overhead_us.toFixed(0)} µs/probe overhead`}
+          </p>
+        )}
         {latestResult && (
           <span
             className="mt-1 inline-flex items-center rounded-full px-2 py-0.5 text-xs font-medium"
@@ -87,6 +132,7 @@ export function MetadataCards({
             {latestResult.verified ? "Verified" : "Unverified"}
           </span>
         )}
+        {latestResult && <GradeBadge grade={latestResult.grade} className="ml-2 align-middle" />}
       </Card>
 
       {/* Extractor Type */}
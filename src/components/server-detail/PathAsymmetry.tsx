@@ -0,0 +1,91 @@
+import { useEffect, useState } from "react";
+import { Split } from "lucide-react";
+import { Card } from "@/components/ui/Card";
+import { Button } from "@/components/ui/Button";
+import * as commands from "@/lib/commands";
+import { errorMessage } from "@/lib/utils";
+import type { Server } from "@/types/server";
+
+interface PathAsymmetryProps {
+  server: Server;
+  // Called after the factor changes so the server is refetched.
+  onChange?: () => void;
+}
+
+// Outbound share of the round trip, shown and edited as a percentage.
+export function PathAsymmetry({ server, onChange }: PathAsymmetryProps) {
+  const [draft, setDraft] = useState("");
+  const [busy, setBusy] = useState(false);
+  const [message, setMessage] = useState<string | null>(null);
+
+  useEffect(() => {
+    setDraft(server.asymmetry !== null ? String(Math.round(server.asymmetry * 100)) : "");
+  }, [server.asymmetry]);
+
+  const save = async () => {
+    const percent = draft.trim() === "" ? null : Number(draft);
+    if (percent !== null && Number.isNaN(percent)) return;
+    setMessage(null);
+    try {
+      await commands.setServerAsymmetry(server.id, percent === null ? null : percent / 100);
+      onChange?.();
+    } catch (e) {
+      setMessage(errorMessage(e));
+    }
+  };
+
+  const calibrate = async () => {
+    setBusy(true);
+    setMessage(null);
+    try {
+      const result = await commands.calibrateAsymmetry(server.id);
+      setMessage(
+        `${Math.round(result.asymmetry * 100)}% outbound over ${result.rtt_ms.toFixed(0)} ms RTT`,
+      );
+      onChange?.();
+    } catch (e) {
+      setMessage(errorMessage(e));
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  return (
+    <Card>
+      <div className="mb-4 flex items-center gap-2">
+        <Split className="h-5 w-5 text-[var(--color-text-secondary)]" />
+        <h3 className="text-lg font-bold text-[var(--color-text-primary)]">Path Asymmetry</h3>
+      </div>
+      <p className="mb-3 text-xs text-[var(--color-text-secondary)]">
+        Share of each round trip spent reaching the server. Leave blank for a symmetric
+        path (50%); DOCSIS and LTE uplinks are usually slower.
+      </p>
+      <div className="flex items-center gap-2">
+        <input
+          type="number"
+          min={5}
+          max={95}
+          value={draft}
+          placeholder="50"
+          onChange={(e) => setDraft(e.target.value)}
+          onBlur={save}
+          onKeyDown={(e) => e.key === "Enter" && save()}
+          className="w-20 rounded-md border border-[var(--color-border)] bg-transparent px-2 py-1 text-sm font-mono text-[var(--color-text-primary)]"
+        />
+        <span className="text-sm text-[var(--color-text-secondary)]">%</span>
+        <Button
+          variant="ghost"
+          size="sm"
+          onClick={calibrate}
+          disabled={busy}
+          title="Compare a symmetric sync with NTP time; only for servers whose clock is NTP-synced"
+        >
+          {busy ? "Calibrating…" : "Calibrate"}
+        </Button>
+      </div>
+      {message && (
+        <p className="mt-2 text-xs text-[var(--color-text-secondary)]">{message}</p>
+      )}
+    </Card>
+  );
+}
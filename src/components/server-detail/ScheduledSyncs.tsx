@@ -0,0 +1,97 @@
+import { useCallback, useEffect, useState } from "react";
+import { AlarmClock, X } from "lucide-react";
+import { Card } from "@/components/ui/Card";
+import { Button } from "@/components/ui/Button";
+import * as commands from "@/lib/commands";
+import { errorMessage } from "@/lib/utils";
+import type { ScheduledSync } from "@/types/server";
+
+interface ScheduledSyncsProps {
+  serverId: number;
+}
+
+// One-time syncs queued for a local wall-clock time, e.g. 09:55 before a
+// 10:00 on-sale.
+export function ScheduledSyncs({ serverId }: ScheduledSyncsProps) {
+  const [pending, setPending] = useState<ScheduledSync[]>([]);
+  const [draft, setDraft] = useState("");
+  const [error, setError] = useState<string | null>(null);
+
+  const apply = useCallback(
+    (all: ScheduledSync[]) => setPending(all.filter((s) => s.server_id === serverId)),
+    [serverId],
+  );
+
+  useEffect(() => {
+    commands.listScheduledSyncs().then(apply).catch(() => {});
+    const unlisten = commands.onScheduledSyncs(apply);
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [apply]);
+
+  const add = async () => {
+    if (!draft) return;
+    setError(null);
+    try {
+      // datetime-local has no zone; Date reads it as local time.
+      const created = await commands.scheduleSync(serverId, new Date(draft).toISOString());
+      setPending((prev) =>
+        [...prev, created].sort((a, b) => a.run_at.localeCompare(b.run_at)),
+      );
+      setDraft("");
+    } catch (e) {
+      setError(errorMessage(e));
+    }
+  };
+
+  const cancel = async (id: number) => {
+    try {
+      await commands.cancelScheduledSync(id);
+      setPending((prev) => prev.filter((s) => s.id !== id));
+    } catch (e) {
+      setError(errorMessage(e));
+    }
+  };
+
+  return (
+    <Card>
+      <div className="mb-4 flex items-center gap-2">
+        <AlarmClock className="h-5 w-5 text-[var(--color-text-secondary)]" />
+        <h3 className="text-lg font-bold text-[var(--color-text-primary)]">Scheduled Syncs</h3>
+      </div>
+      <div className="flex items-center gap-2">
+        <input
+          type="datetime-local"
+          step={1}
+          value={draft}
+          onChange={(e) => setDraft(e.target.value)}
+          className="min-w-0 flex-1 rounded-md border border-[var(--color-border)] bg-transparent px-2 py-1 text-sm font-mono text-[var(--color-text-primary)]"
+        />
+        <Button variant="ghost" size="sm" onClick={add} disabled={!draft}>
+          Schedule
+        </Button>
+      </div>
+      {error && <p className="mt-2 text-xs text-[var(--color-danger)]">{error}</p>}
+      {pending.length > 0 && (
+        <ul className="mt-3 space-y-1">
+          {pending.map((s) => (
+            <li key={s.id} className="flex items-center justify-between text-sm">
+              <span className="font-mono tabular-nums text-[var(--color-text-primary)]">
+                {new Date(s.run_at).toLocaleString()}
+              </span>
+              <button
+                type="button"
+                onClick={() => cancel(s.id)}
+                className="text-[var(--color-text-secondary)] hover:text-[var(--color-danger)]"
+                aria-label="Cancel scheduled sync"
+              >
+                <X className="h-4 w-4" />
+              </button>
+            </li>
+          ))}
+        </ul>
+      )}
+    </Card>
+  );
+}
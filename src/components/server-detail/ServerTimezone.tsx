@@ -0,0 +1,64 @@
+import { useEffect, useState } from "react";
+import { Globe } from "lucide-react";
+import { Card } from "@/components/ui/Card";
+import * as commands from "@/lib/commands";
+import { errorMessage } from "@/lib/utils";
+import type { Server } from "@/types/server";
+
+interface ServerTimezoneProps {
+  server: Server;
+  // Called after the timezone changes so the server is refetched.
+  onChange?: () => void;
+}
+
+export function ServerTimezone({ server, onChange }: ServerTimezoneProps) {
+  const pinned = server.timezone_guessed ? null : server.timezone;
+  const [draft, setDraft] = useState("");
+  const [message, setMessage] = useState<string | null>(null);
+
+  useEffect(() => {
+    setDraft(pinned ?? "");
+  }, [pinned]);
+
+  const save = async () => {
+    const next = draft.trim() === "" ? null : draft.trim();
+    if (next === pinned) return;
+    setMessage(null);
+    try {
+      await commands.setServerTimezone(server.id, next);
+      onChange?.();
+    } catch (e) {
+      setMessage(errorMessage(e));
+    }
+  };
+
+  return (
+    <Card>
+      <div className="mb-4 flex items-center gap-2">
+        <Globe className="h-5 w-5 text-[var(--color-text-secondary)]" />
+        <h3 className="text-lg font-bold text-[var(--color-text-primary)]">Timezone</h3>
+      </div>
+      <p className="mb-3 text-xs text-[var(--color-text-secondary)]">
+        Targets such as "10:00 server-local" are read in this zone, daylight saving
+        included. Leave blank to guess it from the domain and Content-Language.
+      </p>
+      <input
+        type="text"
+        value={draft}
+        placeholder={server.timezone ?? "Asia/Tokyo"}
+        onChange={(e) => setDraft(e.target.value)}
+        onBlur={save}
+        onKeyDown={(e) => e.key === "Enter" && save()}
+        className="w-full rounded-md border border-[var(--color-border)] bg-transparent px-2 py-1 text-sm font-mono text-[var(--color-text-primary)]"
+      />
+      <p className="mt-2 text-xs text-[var(--color-text-secondary)]">
+        {message ??
+          (server.timezone_guessed
+            ? `Guessed: ${server.timezone}`
+            : server.timezone === null
+              ? "Unknown; server-local targets need a zone"
+              : null)}
+      </p>
+    </Card>
+  );
+}
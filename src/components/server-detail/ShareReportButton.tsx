@@ -0,0 +1,34 @@
+import { useState } from "react";
+import { Share2 } from "lucide-react";
+import { Button } from "@/components/ui/Button";
+import * as commands from "@/lib/commands";
+import type { SyncResult } from "@/types/server";
+
+interface ShareReportButtonProps {
+  // The sync to report on; usually the newest in history.
+  result: SyncResult | undefined;
+}
+
+export function ShareReportButton({ result }: ShareReportButtonProps) {
+  const [copied, setCopied] = useState(false);
+
+  const handleShare = async () => {
+    if (!result) return;
+    const report = await commands.generateSyncReport(result.id);
+    await navigator.clipboard.writeText(report);
+    setCopied(true);
+    setTimeout(() => setCopied(false), 2000);
+  };
+
+  return (
+    <Button
+      variant="ghost"
+      size="sm"
+      onClick={handleShare}
+      disabled={!result || result.id === 0}
+    >
+      <Share2 className="h-4 w-4 mr-1.5" />
+      {copied ? "Copied" : "Share Report"}
+    </Button>
+  );
+}
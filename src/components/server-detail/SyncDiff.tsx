@@ -0,0 +1,102 @@
+import { useEffect, useState } from "react";
+import { compareSyncResults } from "@/lib/commands";
+import { cn, describeNetwork } from "@/lib/utils";
+import type { Delta, SyncResultDiff } from "@/types/server";
+
+interface SyncDiffProps {
+  // Older sync first, so changes read forward in time.
+  beforeId: number;
+  afterId: number;
+}
+
+function formatChange(delta: Delta, digits: number, unit: string): string {
+  const sign = delta.change > 0 ? "+" : "";
+  return `${sign}${delta.change.toFixed(digits)}${unit}`;
+}
+
+function formatElapsed(ms: number): string {
+  const hours = Math.abs(ms) / 3_600_000;
+  if (hours < 1) return `${Math.round(Math.abs(ms) / 60_000)} min apart`;
+  if (hours < 48) return `${hours.toFixed(1)} h apart`;
+  return `${Math.round(hours / 24)} days apart`;
+}
+
+export function SyncDiff({ beforeId, afterId }: SyncDiffProps) {
+  const [diff, setDiff] = useState<SyncResultDiff | null>(null);
+
+  useEffect(() => {
+    setDiff(null);
+    compareSyncResults(beforeId, afterId)
+      .then(setDiff)
+      .catch(() => {});
+  }, [beforeId, afterId]);
+
+  if (!diff) return null;
+
+  const rows: { label: string; delta: Delta; digits: number; unit: string }[] = [
+    { label: "Offset", delta: diff.offset_ms, digits: 1, unit: " ms" },
+    { label: "Median RTT", delta: diff.latency_ms.median, digits: 1, unit: " ms" },
+    { label: "Min RTT", delta: diff.latency_ms.min, digits: 1, unit: " ms" },
+    { label: "RTT spread (IQR)", delta: diff.latency_ms.iqr, digits: 1, unit: " ms" },
+    { label: "Duration", delta: diff.duration_ms, digits: 0, unit: " ms" },
+    { label: "Probes", delta: diff.probes, digits: 0, unit: "" },
+  ];
+
+  return (
+    <div className="mt-4 rounded-lg border border-[var(--color-border)] p-3 text-sm">
+      <p className="mb-2 text-xs text-[var(--color-text-secondary)]">
+        {formatElapsed(diff.elapsed_ms)}
+        {!diff.same_server && " · different servers"}
+      </p>
+      <table className="w-full">
+        <tbody>
+          {rows.map(({ label, delta, digits, unit }) => (
+            <tr key={label}>
+              <td className="py-1 pr-4 text-[var(--color-text-secondary)]">{label}</td>
+              <td className="py-1 pr-4 font-mono text-xs tabular-nums text-[var(--color-text-secondary)]">
+                {delta.before.toFixed(digits)} → {delta.after.toFixed(digits)}
+              </td>
+              <td
+                className={cn(
+                  "py-1 text-right font-mono text-xs tabular-nums",
+                  delta.change === 0
+                    ? "text-[var(--color-text-secondary)]"
+                    : "text-[var(--color-text-primary)]",
+                )}
+              >
+                {formatChange(delta, digits, unit)}
+              </td>
+            </tr>
+          ))}
+          <tr>
+            <td className="py-1 pr-4 text-[var(--color-text-secondary)]">Verified</td>
+            <td colSpan={2} className="py-1 font-mono text-xs text-[var(--color-text-primary)]">
+              {diff.verified.changed
+                ? `${diff.verified.before ? "yes" : "no"} → ${diff.verified.after ? "yes" : "no"}`
+                : diff.verified.after
+                  ? "yes, both"
+                  : "no, both"}
+            </td>
+          </tr>
+          {diff.network.changed && (
+            <tr>
+              <td className="py-1 pr-4 text-[var(--color-text-secondary)]">Network</td>
+              <td colSpan={2} className="py-1 font-mono text-xs text-[var(--color-warning)]">
+                {diff.network.before ? describeNetwork(diff.network.before) : "unknown"} →{" "}
+                {diff.network.after ? describeNetwork(diff.network.after) : "unknown"}
+              </td>
+            </tr>
+          )}
+          {diff.phase_reached.changed && (
+            <tr>
+              <td className="py-1 pr-4 text-[var(--color-text-secondary)]">Phase reached</td>
+              <td colSpan={2} className="py-1 font-mono text-xs text-[var(--color-text-primary)]">
+                {diff.phase_reached.before} → {diff.phase_reached.after}
+              </td>
+            </tr>
+          )}
+        </tbody>
+      </table>
+    </div>
+  );
+}
@@ -8,6 +8,7 @@ interface SyncHealthPanelProps {
   jitter: number | null;
   jitterStatus: string;
   driftRate: number | null;
+  driftR2: number | null;
   driftStatus: string;
 }
 
@@ -16,12 +17,18 @@ export function SyncHealthPanel({
   jitter,
   jitterStatus,
   driftRate,
+  driftR2,
   driftStatus,
 }: SyncHealthPanelProps) {
   const jitterValue =
     jitter !== null ? `${jitter.toFixed(2)} ms` : "N/A";
   const driftValue =
     driftRate !== null ? `${driftRate.toFixed(2)} ms/hr` : "N/A";
+  // Positive offsets mean the server is ahead, so a rising offset gains.
+  const driftTrend =
+    driftRate !== null && driftR2 !== null
+      ? `${driftRate >= 0 ? "Gains" : "Loses"} ~${Math.abs(driftRate * 24).toFixed(0)} ms/day · R² ${driftR2.toFixed(2)}`
+      : null;
 
   const jitterColor =
     jitterStatus === "Stable"
@@ -102,6 +109,9 @@ export function SyncHealthPanel({
               <p className="text-sm font-semibold text-[var(--color-text-primary)]">
                 {driftValue}
               </p>
+              {driftTrend && (
+                <p className="text-[10px] text-[var(--color-text-secondary)]">{driftTrend}</p>
+              )}
             </div>
           </div>
           <span
@@ -0,0 +1,234 @@
+import { useCallback, useEffect, useState } from "react";
+import { ChevronLeft, ChevronRight, StickyNote } from "lucide-react";
+import { Card } from "@/components/ui/Card";
+import { Button } from "@/components/ui/Button";
+import { Select } from "@/components/ui/Select";
+import { GradeBadge } from "@/components/ui/GradeBadge";
+import { SyncDiff } from "@/components/server-detail/SyncDiff";
+import { annotateSyncResult, getSyncHistoryPage } from "@/lib/commands";
+import { describeNetwork } from "@/lib/utils";
+import type { SyncHistoryPage, SyncPhase, SyncResult } from "@/types/server";
+
+const PAGE_SIZE = 10;
+
+const STATUS_OPTIONS = [
+  { value: "", label: "All syncs" },
+  { value: "verified", label: "Verified" },
+  { value: "unverified", label: "Unverified" },
+  { value: "anomalous", label: "Anomalous" },
+];
+
+const PHASE_OPTIONS: { value: "" | SyncPhase; label: string }[] = [
+  { value: "", label: "Any phase" },
+  { value: "latency_profiling", label: "Latency" },
+  { value: "binary_search", label: "Binary search" },
+  { value: "whole_second_offset", label: "Whole second" },
+  { value: "verification", label: "Verification" },
+  { value: "complete", label: "Complete" },
+];
+
+interface SyncNotesProps {
+  serverId: number;
+  // Id of the newest stored result; a change reloads the first page.
+  latestId?: number;
+  // Called after a note is saved so history is refetched.
+  onChange?: () => void;
+}
+
+export function SyncNotes({ serverId, latestId, onChange }: SyncNotesProps) {
+  const [editing, setEditing] = useState<number | null>(null);
+  const [draft, setDraft] = useState("");
+  const [offset, setOffset] = useState(0);
+  const [page, setPage] = useState<SyncHistoryPage | null>(null);
+  const [status, setStatus] = useState("");
+  const [phase, setPhase] = useState<"" | SyncPhase>("");
+  // Up to two syncs to compare; the latest pick replaces the older one.
+  const [picked, setPicked] = useState<SyncResult[]>([]);
+  const filtered = status !== "" || phase !== "";
+
+  const load = useCallback(
+    (from: number) =>
+      getSyncHistoryPage(serverId, {
+        verified:
+          status === "verified" ? true : status === "unverified" ? false : undefined,
+        anomalous: status === "anomalous" ? true : undefined,
+        phaseReached: phase === "" ? undefined : phase,
+        offset: from,
+        limit: PAGE_SIZE,
+      })
+        .then(setPage)
+        .catch(() => {}),
+    [serverId, status, phase],
+  );
+
+  useEffect(() => {
+    setOffset(0);
+    load(0);
+  }, [load, latestId]);
+
+  const recent = page?.results ?? [];
+  if (!page || (page.total === 0 && !filtered)) return null;
+
+  const goTo = (from: number) => {
+    setOffset(from);
+    load(from);
+  };
+
+  const togglePick = (result: SyncResult) => {
+    setPicked((current) =>
+      current.some((p) => p.id === result.id)
+        ? current.filter((p) => p.id !== result.id)
+        : [...current, result].slice(-2),
+    );
+  };
+
+  const [before, after] = [...picked].sort(
+    (a, b) => new Date(a.synced_at).getTime() - new Date(b.synced_at).getTime(),
+  );
+
+  const startEditing = (result: SyncResult) => {
+    setEditing(result.id);
+    setDraft(result.note ?? "");
+  };
+
+  const save = async (id: number) => {
+    setEditing(null);
+    try {
+      await annotateSyncResult(id, draft.trim() || null);
+      load(offset);
+      onChange?.();
+    } catch {
+      // Leave the previous note in place.
+    }
+  };
+
+  return (
+    <Card>
+      <div className="mb-4 flex items-center gap-2">
+        <StickyNote className="h-5 w-5 text-[var(--color-text-secondary)]" />
+        <h3 className="text-lg font-bold text-[var(--color-text-primary)]">
+          Recent Syncs
+        </h3>
+        <div className="ml-auto flex gap-2">
+          <Select
+            options={STATUS_OPTIONS}
+            value={status}
+            onChange={(e) => setStatus(e.target.value)}
+            className="w-32"
+          />
+          <Select
+            options={PHASE_OPTIONS}
+            value={phase}
+            onChange={(e) => setPhase(e.target.value as "" | SyncPhase)}
+            className="w-36"
+          />
+        </div>
+      </div>
+      {recent.length === 0 && (
+        <p className="text-sm text-[var(--color-text-secondary)]">No syncs match.</p>
+      )}
+      <table className="w-full text-sm">
+        <tbody>
+          {recent.map((r) => (
+            <tr key={r.id} className="border-t border-[var(--color-border)]">
+              <td className="py-2 pr-2">
+                <input
+                  type="checkbox"
+                  checked={picked.some((p) => p.id === r.id)}
+                  onChange={() => togglePick(r)}
+                  aria-label="Compare this sync"
+                  className="cursor-pointer accent-[var(--color-accent)]"
+                />
+              </td>
+              <td className="py-2 pr-4 text-[var(--color-text-secondary)] tabular-nums">
+                {new Date(r.synced_at).toLocaleString()}
+                {r.network && (
+                  <span className="block text-[10px]">{describeNetwork(r.network)}</span>
+                )}
+              </td>
+              <td className="py-2 pr-4 font-mono text-xs text-[var(--color-text-primary)] tabular-nums">
+                {r.total_offset_ms >= 0 ? "+" : ""}
+                {r.total_offset_ms.toFixed(1)}ms
+                <GradeBadge grade={r.grade} className="ml-2" />
+                {r.anomalous && (
+                  <span
+                    title="Far off this server's recent syncs"
+                    className="ml-2 rounded-full bg-[var(--color-warning)]/10 px-1.5 py-0.5 font-sans text-[10px] font-medium text-[var(--color-warning)]"
+                  >
+                    anomalous
+                  </span>
+                )}
+                {r.clock_issue && (
+                  <span
+                    title={r.clock_issue.label}
+                    className="ml-2 rounded-full bg-[var(--color-warning)]/10 px-1.5 py-0.5 font-sans text-[10px] font-medium text-[var(--color-warning)]"
+                  >
+                    {r.clock_issue.kind === "timezone" ? "wrong timezone" : "clock skew"}
+                  </span>
+                )}
+              </td>
+              <td className="py-2">
+                {editing === r.id ? (
+                  <input
+                    autoFocus
+                    value={draft}
+                    placeholder="e.g. synced over hotel Wi-Fi"
+                    onChange={(e) => setDraft(e.target.value)}
+                    onBlur={() => save(r.id)}
+                    onKeyDown={(e) => {
+                      if (e.key === "Enter") save(r.id);
+                      if (e.key === "Escape") setEditing(null);
+                    }}
+                    className="w-full rounded-md border border-[var(--color-border)] bg-transparent px-2 py-1 text-sm text-[var(--color-text-primary)]"
+                  />
+                ) : (
+                  <button
+                    onClick={() => startEditing(r)}
+                    className="w-full text-left cursor-pointer text-[var(--color-text-primary)] hover:text-[var(--color-accent)]"
+                  >
+                    {r.note ?? (
+                      <span className="text-xs text-[var(--color-text-secondary)]">
+                        Add note
+                      </span>
+                    )}
+                  </button>
+                )}
+              </td>
+            </tr>
+          ))}
+        </tbody>
+      </table>
+      {picked.length === 1 && (
+        <p className="mt-3 text-xs text-[var(--color-text-secondary)]">
+          Pick another sync to compare.
+        </p>
+      )}
+      {before && after && <SyncDiff beforeId={before.id} afterId={after.id} />}
+      {page.total > PAGE_SIZE && (
+        <div className="mt-3 flex items-center justify-end gap-2 text-xs text-[var(--color-text-secondary)]">
+          <span className="tabular-nums">
+            {offset + 1}–{offset + recent.length} of {page.total}
+          </span>
+          <Button
+            variant="ghost"
+            size="sm"
+            onClick={() => goTo(Math.max(0, offset - PAGE_SIZE))}
+            disabled={offset === 0}
+            aria-label="Newer syncs"
+          >
+            <ChevronLeft className="h-4 w-4" />
+          </Button>
+          <Button
+            variant="ghost"
+            size="sm"
+            onClick={() => goTo(offset + PAGE_SIZE)}
+            disabled={offset + PAGE_SIZE >= page.total}
+            aria-label="Older syncs"
+          >
+            <ChevronRight className="h-4 w-4" />
+          </Button>
+        </div>
+      )}
+    </Card>
+  );
+}
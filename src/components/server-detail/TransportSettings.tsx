@@ -0,0 +1,126 @@
+import { useEffect, useState } from "react";
+import { Cable } from "lucide-react";
+import { Card } from "@/components/ui/Card";
+import { Select } from "@/components/ui/Select";
+import { Toggle } from "@/components/ui/Toggle";
+import * as commands from "@/lib/commands";
+import { errorMessage } from "@/lib/utils";
+import type { IpFamily, Server, TransportOptions } from "@/types/server";
+
+const IP_FAMILY_OPTIONS: { value: IpFamily; label: string }[] = [
+  { value: "auto", label: "Auto (race IPv6/IPv4)" },
+  { value: "ipv4", label: "IPv4 only" },
+  { value: "ipv6", label: "IPv6 only" },
+];
+
+interface TransportSettingsProps {
+  server: Server;
+  // Called after an option changes so the server is refetched.
+  onChange?: () => void;
+}
+
+// Advanced socket options for the last few milliseconds of jitter.
+export function TransportSettings({ server, onChange }: TransportSettingsProps) {
+  const [timeoutDraft, setTimeoutDraft] = useState("");
+  const [message, setMessage] = useState<string | null>(null);
+  const transport = server.transport;
+
+  useEffect(() => {
+    setTimeoutDraft(transport.connect_timeout_ms?.toString() ?? "");
+  }, [transport.connect_timeout_ms]);
+
+  const save = async (next: TransportOptions) => {
+    setMessage(null);
+    try {
+      await commands.setServerTransport(server.id, next);
+      onChange?.();
+    } catch (e) {
+      setMessage(errorMessage(e));
+    }
+  };
+
+  const setAcceptInvalidCerts = (accept: boolean) => {
+    if (
+      accept &&
+      !window.confirm(
+        `Accept any certificate from ${server.url}? Anyone between you and this server ` +
+          "could then answer in its place and feed it a wrong time.",
+      )
+    ) {
+      return;
+    }
+    save({ ...transport, accept_invalid_certs: accept });
+  };
+
+  const saveTimeout = () => {
+    const ms = timeoutDraft.trim() === "" ? null : Number(timeoutDraft);
+    if (ms !== null && Number.isNaN(ms)) return;
+    if (ms === transport.connect_timeout_ms) return;
+    save({ ...transport, connect_timeout_ms: ms });
+  };
+
+  return (
+    <Card>
+      <div className="mb-4 flex items-center gap-2">
+        <Cable className="h-5 w-5 text-[var(--color-text-secondary)]" />
+        <h3 className="text-lg font-bold text-[var(--color-text-primary)]">Transport</h3>
+      </div>
+      <p className="mb-3 text-xs text-[var(--color-text-secondary)]">
+        Advanced. Applies to new connections on the next sync.
+      </p>
+      <div className="space-y-3">
+        <Toggle
+          label="TCP_NODELAY"
+          description="Send each probe immediately instead of coalescing packets"
+          checked={transport.tcp_nodelay}
+          onChange={(e) => save({ ...transport, tcp_nodelay: e.target.checked })}
+        />
+        <div className="flex items-center justify-between gap-4">
+          <span className="text-sm font-medium text-[var(--color-text-primary)]">
+            Address family
+          </span>
+          <Select
+            options={IP_FAMILY_OPTIONS}
+            value={transport.ip_family}
+            onChange={(e) => save({ ...transport, ip_family: e.target.value as IpFamily })}
+            className="w-48"
+          />
+        </div>
+        <div className="flex items-center justify-between gap-4">
+          <span className="text-sm font-medium text-[var(--color-text-primary)]">
+            Connect timeout
+          </span>
+          <div className="flex items-center gap-2">
+            <input
+              type="number"
+              min={100}
+              max={30000}
+              step={100}
+              value={timeoutDraft}
+              placeholder="Profile"
+              onChange={(e) => setTimeoutDraft(e.target.value)}
+              onBlur={saveTimeout}
+              onKeyDown={(e) => e.key === "Enter" && saveTimeout()}
+              className="w-24 rounded-md border border-[var(--color-border)] bg-transparent px-2 py-1 text-sm font-mono text-[var(--color-text-primary)]"
+            />
+            <span className="text-sm text-[var(--color-text-secondary)]">ms</span>
+          </div>
+        </div>
+        <Toggle
+          label="Accept invalid certificates"
+          description="Dangerous: turns off TLS verification for this server. Only for internal or staging systems with self-signed certificates."
+          checked={transport.accept_invalid_certs}
+          onChange={(e) => setAcceptInvalidCerts(e.target.checked)}
+        />
+        {transport.accept_invalid_certs && (
+          <p className="text-xs text-[var(--color-warning)]">
+            Certificate checks are off: probes cannot tell this server from an impostor.
+          </p>
+        )}
+      </div>
+      {message && (
+        <p className="mt-2 text-xs text-[var(--color-text-secondary)]">{message}</p>
+      )}
+    </Card>
+  );
+}
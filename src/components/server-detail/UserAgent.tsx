@@ -0,0 +1,58 @@
+import { useEffect, useState } from "react";
+import { Fingerprint } from "lucide-react";
+import { Card } from "@/components/ui/Card";
+import * as commands from "@/lib/commands";
+import { errorMessage } from "@/lib/utils";
+import type { Server } from "@/types/server";
+
+interface UserAgentProps {
+  server: Server;
+  // Called after the User-Agent changes so the server is refetched.
+  onChange?: () => void;
+}
+
+export function UserAgent({ server, onChange }: UserAgentProps) {
+  const [draft, setDraft] = useState("");
+  const [message, setMessage] = useState<string | null>(null);
+
+  useEffect(() => {
+    setDraft(server.user_agent ?? "");
+  }, [server.user_agent]);
+
+  const save = async () => {
+    const next = draft.trim() === "" ? null : draft.trim();
+    if (next === server.user_agent) return;
+    setMessage(null);
+    try {
+      await commands.setServerUserAgent(server.id, next);
+      onChange?.();
+    } catch (e) {
+      setMessage(errorMessage(e));
+    }
+  };
+
+  return (
+    <Card>
+      <div className="mb-4 flex items-center gap-2">
+        <Fingerprint className="h-5 w-5 text-[var(--color-text-secondary)]" />
+        <h3 className="text-lg font-bold text-[var(--color-text-primary)]">User-Agent</h3>
+      </div>
+      <p className="mb-3 text-xs text-[var(--color-text-secondary)]">
+        Sent with every probe. Some CDNs route unknown agents to slower edges or block
+        them; paste your browser's if probes are refused. Leave blank for the default.
+      </p>
+      <input
+        type="text"
+        value={draft}
+        placeholder="Mozilla/5.0 (compatible; Ticketime)"
+        onChange={(e) => setDraft(e.target.value)}
+        onBlur={save}
+        onKeyDown={(e) => e.key === "Enter" && save()}
+        className="w-full rounded-md border border-[var(--color-border)] bg-transparent px-2 py-1 text-sm font-mono text-[var(--color-text-primary)]"
+      />
+      {message && (
+        <p className="mt-2 text-xs text-[var(--color-text-secondary)]">{message}</p>
+      )}
+    </Card>
+  );
+}
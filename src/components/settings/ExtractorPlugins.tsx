@@ -0,0 +1,61 @@
+import { useEffect, useState } from "react";
+import { Button } from "@/components/ui/Button";
+import type { ExtractorInfo, PluginFailure } from "@/types/server";
+import * as commands from "@/lib/commands";
+import { errorMessage } from "@/lib/utils";
+
+// Loaded WASM extractor plugins, with a rescan of the plugins directory
+// for files dropped in while the app is running.
+export function ExtractorPlugins() {
+  const [plugins, setPlugins] = useState<ExtractorInfo[]>([]);
+  const [failed, setFailed] = useState<PluginFailure[]>([]);
+  const [message, setMessage] = useState<string | null>(null);
+
+  const refresh = async () => {
+    const extractors = await commands.listExtractors();
+    setPlugins(extractors.filter((e) => e.plugin !== null));
+  };
+
+  useEffect(() => {
+    refresh();
+  }, []);
+
+  const reload = async () => {
+    setMessage(null);
+    try {
+      setFailed(await commands.reloadExtractorPlugins());
+      await refresh();
+    } catch (e) {
+      setMessage(errorMessage(e));
+    }
+  };
+
+  return (
+    <div className="space-y-2 py-3">
+      {plugins.length === 0 && failed.length === 0 && (
+        <p className="text-xs text-[var(--color-text-secondary)]">
+          No plugins. Put .wasm extractors in the app data plugins folder and reload.
+        </p>
+      )}
+      {plugins.map((p) => (
+        <div key={p.id} className="flex items-center justify-between text-sm">
+          <span className="text-[var(--color-text-primary)]">{p.name}</span>
+          <code className="font-mono text-xs text-[var(--color-text-secondary)]">{p.id}</code>
+        </div>
+      ))}
+      {failed.map((f) => (
+        <p key={f.file} className="text-xs text-[var(--color-danger)]">
+          {f.file}: {f.error}
+        </p>
+      ))}
+      <div className="flex items-center justify-end gap-2">
+        {message && (
+          <p className="mr-auto text-xs text-[var(--color-text-secondary)]">{message}</p>
+        )}
+        <Button variant="ghost" size="sm" onClick={reload}>
+          Reload Plugins
+        </Button>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,63 @@
+import { useEffect, useState } from "react";
+import { Button } from "@/components/ui/Button";
+import * as commands from "@/lib/commands";
+import { errorMessage } from "@/lib/utils";
+
+const PLACEHOLDER = `fn on_sync_complete(result) {
+    if result.total_offset_ms.abs() > 1000.0 {
+        http_post("https://example.com/hook", result);
+    }
+}`;
+
+// Edit the Rhai script whose on_sync_complete / on_target_fired /
+// on_drift_warning functions run on those events.
+export function HookScript() {
+  const [source, setSource] = useState("");
+  const [saved, setSaved] = useState("");
+  const [message, setMessage] = useState<string | null>(null);
+
+  useEffect(() => {
+    commands.getHookScript().then((s) => {
+      setSource(s);
+      setSaved(s);
+    });
+  }, []);
+
+  const save = async () => {
+    setMessage(null);
+    try {
+      const hooks = await commands.saveHookScript(source);
+      setSaved(source);
+      setMessage(
+        source.trim() === ""
+          ? "Script removed."
+          : hooks.length > 0
+            ? `Running: ${hooks.join(", ")}`
+            : "Saved, but it defines no hooks.",
+      );
+    } catch (e) {
+      setMessage(errorMessage(e));
+    }
+  };
+
+  return (
+    <div className="space-y-2 py-3">
+      <textarea
+        value={source}
+        onChange={(e) => setSource(e.target.value)}
+        placeholder={PLACEHOLDER}
+        rows={8}
+        spellCheck={false}
+        className="w-full resize-y rounded-xl bg-[var(--color-input-bg)] px-4 py-3 font-mono text-xs text-[var(--color-text-primary)] focus:outline-none focus:ring-2 focus:ring-[var(--color-accent)]"
+      />
+      <div className="flex items-center justify-end gap-2">
+        {message && (
+          <p className="mr-auto text-xs text-[var(--color-text-secondary)]">{message}</p>
+        )}
+        <Button variant="ghost" size="sm" onClick={save} disabled={source === saved}>
+          Save Script
+        </Button>
+      </div>
+    </div>
+  );
+}
@@ -4,6 +4,7 @@ import type { ReactNode } from "react";
 interface SettingsFieldProps {
   label: string;
   description?: string;
+  error?: string;
   children: ReactNode;
   className?: string;
 }
@@ -11,6 +12,7 @@ interface SettingsFieldProps {
 export function SettingsField({
   label,
   description,
+  error,
   children,
   className,
 }: SettingsFieldProps) {
@@ -25,6 +27,9 @@ export function SettingsField({
             {description}
           </p>
         )}
+        {error && (
+          <p className="text-xs text-[var(--color-danger)] leading-relaxed">{error}</p>
+        )}
       </div>
       <div className="shrink-0 flex items-center justify-end min-w-[120px]">
         {children}
@@ -0,0 +1,87 @@
+import { useState } from "react";
+import { Button } from "@/components/ui/Button";
+import { Input } from "@/components/ui/Input";
+import { SettingsField } from "@/components/settings/SettingsField";
+import * as commands from "@/lib/commands";
+import { errorMessage } from "@/lib/utils";
+
+interface ShareSetupProps {
+  // Called after an import so the server list is refetched.
+  onImported?: () => void;
+}
+
+// Copy every server and upcoming target to another device as one signed
+// string (paste it, or turn it into a QR code).
+export function ShareSetup({ onImported }: ShareSetupProps) {
+  const [passphrase, setPassphrase] = useState("");
+  const [payload, setPayload] = useState("");
+  const [message, setMessage] = useState<string | null>(null);
+
+  const create = async () => {
+    setMessage(null);
+    try {
+      const [servers, targets] = await Promise.all([
+        commands.listServers(),
+        commands.listTargets(),
+      ]);
+      const now = Date.now();
+      const upcoming = targets.filter((t) => new Date(t.target_at).getTime() > now);
+      const encoded = await commands.exportSharePayload(
+        servers.map((s) => s.id),
+        upcoming.map((t) => t.id),
+        passphrase,
+      );
+      setPayload(encoded);
+      await navigator.clipboard?.writeText(encoded).catch(() => {});
+      setMessage(`Copied ${servers.length} server(s) and ${upcoming.length} target(s).`);
+    } catch (e) {
+      setMessage(errorMessage(e));
+    }
+  };
+
+  const load = async () => {
+    setMessage(null);
+    try {
+      const summary = await commands.importSharePayload(payload, passphrase);
+      onImported?.();
+      setMessage(
+        `Imported: ${summary.servers_added} server(s) added, ${summary.servers_updated} updated, ${summary.targets_added} target(s) added.`,
+      );
+    } catch (e) {
+      setMessage(errorMessage(e));
+    }
+  };
+
+  return (
+    <>
+      <SettingsField label="Passphrase" description="Shared by everyone using the setup">
+        <Input
+          type="password"
+          value={passphrase}
+          onChange={(e) => setPassphrase(e.target.value)}
+          className="w-48 py-2"
+        />
+      </SettingsField>
+      <div className="space-y-2 py-3">
+        <textarea
+          value={payload}
+          onChange={(e) => setPayload(e.target.value)}
+          placeholder="TT1.…"
+          rows={3}
+          className="w-full resize-none rounded-xl bg-[var(--color-input-bg)] px-4 py-3 font-mono text-xs text-[var(--color-text-primary)] focus:outline-none focus:ring-2 focus:ring-[var(--color-accent)]"
+        />
+        <div className="flex items-center justify-end gap-2">
+          {message && (
+            <p className="mr-auto text-xs text-[var(--color-text-secondary)]">{message}</p>
+          )}
+          <Button variant="ghost" size="sm" onClick={load} disabled={!payload || !passphrase}>
+            Import
+          </Button>
+          <Button variant="ghost" size="sm" onClick={create} disabled={!passphrase}>
+            Create
+          </Button>
+        </div>
+      </div>
+    </>
+  );
+}
@@ -0,0 +1,41 @@
+import { useEffect, useState } from "react";
+import { Select } from "@/components/ui/Select";
+import type { SyncProfile } from "@/types/server";
+import * as commands from "@/lib/commands";
+
+interface SyncProfileSelectProps {
+  value: string | null;
+  onChange: (profile: string | null) => void;
+  // Adds a "use global setting" entry that maps to null.
+  allowInherit?: boolean;
+  className?: string;
+}
+
+export function SyncProfileSelect({
+  value,
+  onChange,
+  allowInherit,
+  className,
+}: SyncProfileSelectProps) {
+  const [profiles, setProfiles] = useState<SyncProfile[]>([]);
+
+  useEffect(() => {
+    commands.listSyncProfiles().then(setProfiles);
+  }, []);
+
+  const options = profiles.map((p) => ({ value: p.id, label: p.label }));
+  if (allowInherit) {
+    options.unshift({ value: "", label: "Global default" });
+  }
+  const selected = profiles.find((p) => p.id === value);
+
+  return (
+    <Select
+      options={options}
+      value={value ?? ""}
+      title={selected?.description}
+      onChange={(e) => onChange(e.target.value === "" ? null : e.target.value)}
+      className={className}
+    />
+  );
+}
@@ -0,0 +1,88 @@
+import { useEffect, useState } from "react";
+import { Button } from "@/components/ui/Button";
+import type { RecordingInfo, ReplayReport } from "@/types/server";
+import * as commands from "@/lib/commands";
+import { errorMessage } from "@/lib/utils";
+
+function outcome(offsetMs: number | null | undefined, error: string | null): string {
+  if (offsetMs !== null && offsetMs !== undefined) {
+    return `${offsetMs >= 0 ? "+" : ""}${offsetMs.toFixed(1)} ms`;
+  }
+  return error ?? "no result";
+}
+
+// Saved sync runs; replaying one shows what today's engine makes of the
+// same probes next to what the live run concluded.
+export function SyncRecordings() {
+  const [recordings, setRecordings] = useState<RecordingInfo[]>([]);
+  const [replays, setReplays] = useState<Record<string, ReplayReport>>({});
+  const [replaying, setReplaying] = useState<string | null>(null);
+  const [message, setMessage] = useState<string | null>(null);
+
+  const refresh = async () => {
+    setRecordings(await commands.listSyncRecordings());
+  };
+
+  useEffect(() => {
+    refresh();
+  }, []);
+
+  const replay = async (file: string) => {
+    setReplaying(file);
+    setMessage(null);
+    try {
+      const report = await commands.replaySyncRecording(file);
+      setReplays((r) => ({ ...r, [file]: report }));
+    } catch (e) {
+      setMessage(errorMessage(e));
+    } finally {
+      setReplaying(null);
+    }
+  };
+
+  return (
+    <div className="space-y-2 py-3">
+      {recordings.length === 0 && (
+        <p className="text-xs text-[var(--color-text-secondary)]">
+          No recordings. Turn on Record Sync Runs and sync a server.
+        </p>
+      )}
+      {recordings.map((r) => {
+        const report = replays[r.file];
+        return (
+          <div key={r.file} className="flex items-center gap-3 text-sm">
+            <div className="min-w-0 flex-1">
+              <p className="truncate text-[var(--color-text-primary)]">{r.url}</p>
+              <p className="text-xs text-[var(--color-text-secondary)]">
+                {new Date(r.recorded_at).toLocaleString()} · {r.probes} probes ·{" "}
+                {outcome(r.offset_ms, r.error)}
+              </p>
+              {report && (
+                <p className="text-xs tabular-nums text-[var(--color-text-secondary)]">
+                  Replayed: {outcome(report.result?.total_offset_ms, report.error)}
+                  {report.unused_probes > 0 && ` (${report.unused_probes} probes unused)`}
+                </p>
+              )}
+            </div>
+            <Button
+              variant="ghost"
+              size="sm"
+              onClick={() => replay(r.file)}
+              disabled={replaying !== null}
+            >
+              {replaying === r.file ? "Replaying..." : "Replay"}
+            </Button>
+          </div>
+        );
+      })}
+      <div className="flex items-center justify-end gap-2">
+        {message && (
+          <p className="mr-auto text-xs text-[var(--color-text-secondary)]">{message}</p>
+        )}
+        <Button variant="ghost" size="sm" onClick={refresh}>
+          Refresh
+        </Button>
+      </div>
+    </div>
+  );
+}
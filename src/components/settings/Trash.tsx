@@ -0,0 +1,86 @@
+import { useEffect, useState } from "react";
+import { Button } from "@/components/ui/Button";
+import type { TrashedServer } from "@/types/server";
+import * as commands from "@/lib/commands";
+import { errorMessage } from "@/lib/utils";
+
+interface TrashProps {
+  // Called after a restore so the server list is refetched.
+  onRestored?: () => void;
+}
+
+// Deleted servers, kept with their history until trash_retention_days
+// have passed.
+export function Trash({ onRestored }: TrashProps) {
+  const [trashed, setTrashed] = useState<TrashedServer[]>([]);
+  const [message, setMessage] = useState<string | null>(null);
+
+  const refresh = async () => {
+    setTrashed(await commands.listTrash());
+  };
+
+  useEffect(() => {
+    refresh();
+  }, []);
+
+  const run = async (action: () => Promise<unknown>) => {
+    setMessage(null);
+    try {
+      await action();
+      await refresh();
+    } catch (e) {
+      setMessage(errorMessage(e));
+    }
+  };
+
+  const restore = (id: number) =>
+    run(async () => {
+      await commands.restoreServer(id);
+      onRestored?.();
+    });
+
+  const purge = (t: TrashedServer) => {
+    if (!window.confirm(`Delete ${t.server.url} and its history for good?`)) return;
+    run(() => commands.purgeServer(t.server.id));
+  };
+
+  const empty = () => {
+    if (!window.confirm("Delete every trashed server for good?")) return;
+    run(() => commands.emptyTrash());
+  };
+
+  return (
+    <div className="space-y-2 py-3">
+      {trashed.length === 0 && (
+        <p className="text-xs text-[var(--color-text-secondary)]">The trash is empty.</p>
+      )}
+      {trashed.map((t) => (
+        <div key={t.server.id} className="flex items-center gap-3 text-sm">
+          <div className="min-w-0 flex-1">
+            <p className="truncate text-[var(--color-text-primary)]">
+              {t.server.name ?? t.server.url}
+            </p>
+            <p className="text-xs text-[var(--color-text-secondary)]">
+              Deleted {new Date(t.deleted_at).toLocaleString()} · purged{" "}
+              {new Date(t.purge_at).toLocaleDateString()}
+            </p>
+          </div>
+          <Button variant="ghost" size="sm" onClick={() => restore(t.server.id)}>
+            Restore
+          </Button>
+          <Button variant="danger" size="sm" onClick={() => purge(t)}>
+            Delete
+          </Button>
+        </div>
+      ))}
+      <div className="flex items-center justify-end gap-2">
+        {message && (
+          <p className="mr-auto text-xs text-[var(--color-text-secondary)]">{message}</p>
+        )}
+        <Button variant="ghost" size="sm" onClick={empty} disabled={trashed.length === 0}>
+          Empty Trash
+        </Button>
+      </div>
+    </div>
+  );
+}
@@ -4,12 +4,15 @@ interface NetworkStatsProps {
   serverUrl: string;
   medianRtt: number | null;
   probeCount: number;
+  // Last probe's split of its round trip, in ms.
+  breakdownMs?: { dns: number; connect: number; ttfb: number } | null;
 }
 
 export function NetworkStats({
   serverUrl,
   medianRtt,
   probeCount,
+  breakdownMs,
 }: NetworkStatsProps) {
   return (
     <div className="space-y-4">
@@ -41,6 +44,12 @@ export function NetworkStats({
             <p className="text-sm font-mono font-semibold text-[var(--color-text-primary)] tabular-nums">
               {medianRtt !== null ? `${(medianRtt * 1000).toFixed(1)} ms` : "--"}
             </p>
+            {breakdownMs && (
+              <p className="text-[10px] font-mono text-[var(--color-text-secondary)] tabular-nums">
+                DNS {breakdownMs.dns.toFixed(1)} · connect {breakdownMs.connect.toFixed(1)} · TTFB{" "}
+                {breakdownMs.ttfb.toFixed(1)} ms
+              </p>
+            )}
           </div>
         </div>
         <div className="bg-[var(--color-bg-deep)] p-4 rounded-xl border border-[var(--color-border)] flex items-center gap-3">
@@ -3,8 +3,8 @@ import { cn } from "@/lib/utils";
 
 const phases = [
   { key: "latency_profiling", label: "Latency Profiling" },
-  { key: "whole_second_offset", label: "Whole-Second" },
   { key: "binary_search", label: "Binary Search" },
+  { key: "whole_second_offset", label: "Whole-Second" },
   { key: "verification", label: "Verification" },
 ];
 
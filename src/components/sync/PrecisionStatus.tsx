@@ -1,12 +1,15 @@
 interface PrecisionStatusProps {
   intervalWidth: number | null;
   convergencePercent: number;
+  // The binary search ended once jitter outweighed further halving.
+  stoppedEarly: boolean;
   verified: boolean | null;
 }
 
 export function PrecisionStatus({
   intervalWidth,
   convergencePercent,
+  stoppedEarly,
   verified,
 }: PrecisionStatusProps) {
   const confidence = Math.min(100, convergencePercent);
@@ -36,6 +39,11 @@ export function PrecisionStatus({
             </span>
           )}
         </p>
+        {stoppedEarly && (
+          <p className="mt-1 text-[10px] text-[var(--color-text-secondary)]">
+            Limited by network jitter
+          </p>
+        )}
       </div>
 
       {/* Confidence bar */}
@@ -1,9 +1,13 @@
+import { formatEta } from "@/lib/utils";
+
 interface ProgressBarProps {
   percent: number;
   elapsedMs: number;
+  // Null once the sync is over.
+  etaMs?: number | null;
 }
 
-export function ProgressBar({ percent, elapsedMs }: ProgressBarProps) {
+export function ProgressBar({ percent, elapsedMs, etaMs = null }: ProgressBarProps) {
   const clamped = Math.min(100, Math.max(0, percent));
   const elapsedSeconds = Math.floor(elapsedMs / 1000);
   const minutes = Math.floor(elapsedSeconds / 60);
@@ -28,6 +32,11 @@ export function ProgressBar({ percent, elapsedMs }: ProgressBarProps) {
           <div className="text-xl font-medium font-mono text-[var(--color-text-primary)]">
             {timeDisplay}<span className="text-xs ml-1">sec</span>
           </div>
+          {etaMs !== null && (
+            <div className="text-xs text-[var(--color-text-secondary)] tabular-nums">
+              {formatEta(etaMs)}
+            </div>
+          )}
         </div>
       </div>
       <div className="w-full h-4 bg-[var(--color-border)] rounded-full overflow-hidden p-1">
@@ -1,12 +1,14 @@
 import { useMemo } from "react";
-import { X, StopCircle, Timer } from "lucide-react";
+import { X, StopCircle, Timer, RotateCcw } from "lucide-react";
 import { Button } from "@/components/ui/Button";
+import { GradeBadge } from "@/components/ui/GradeBadge";
 import { ProgressBar } from "@/components/sync/ProgressBar";
 import { BinarySearchViz } from "@/components/sync/BinarySearchViz";
 import { ConvergenceChart } from "@/components/sync/ConvergenceChart";
 import { NetworkStats } from "@/components/sync/NetworkStats";
 import { PrecisionStatus } from "@/components/sync/PrecisionStatus";
 import { useSyncStore } from "@/stores/syncStore";
+import type { NetworkBaseline } from "@/types/app";
 import type { Server, SyncPhase } from "@/types/server";
 import { cn } from "@/lib/utils";
 
@@ -16,9 +18,10 @@ interface SyncProgressPanelProps {
 }
 
 export function SyncProgressPanel({ server, onClose }: SyncProgressPanelProps) {
-  const { activeSyncs, cancelSync, syncResults } = useSyncStore();
+  const { activeSyncs, cancelSync, startSync, syncResults, syncErrors } = useSyncStore();
   const progress = activeSyncs[server.id];
   const result = syncResults[server.id];
+  const failure = syncErrors[server.id];
   const isActive = !!progress;
 
   const phaseData = progress?.phase_data ?? {};
@@ -45,7 +48,8 @@ export function SyncProgressPanel({ server, onClose }: SyncProgressPanelProps) {
     onClose();
   };
 
-  const currentPhase: SyncPhase | "idle" = progress?.phase ?? (result ? "complete" : "idle");
+  const currentPhase: SyncPhase | "idle" =
+    progress?.phase ?? failure?.phase ?? (result ? "complete" : "idle");
 
   // Get phase display name and color
   const getPhaseDisplay = (phase: SyncPhase | "idle") => {
@@ -74,7 +78,13 @@ export function SyncProgressPanel({ server, onClose }: SyncProgressPanelProps) {
             </div>
             <div>
               <h2 className="text-lg font-semibold text-[var(--color-text-primary)]">
-                {isActive ? "Synchronization in Progress" : result ? "Sync Complete" : "Sync"}
+                {isActive
+                  ? "Synchronization in Progress"
+                  : failure
+                    ? "Sync Failed"
+                    : result
+                      ? "Sync Complete"
+                      : "Sync"}
               </h2>
               <p className="text-sm font-medium" style={{ color: phaseDisplay.color }}>
                 {phaseDisplay.label}
@@ -96,8 +106,60 @@ export function SyncProgressPanel({ server, onClose }: SyncProgressPanelProps) {
             <ProgressBar
               percent={progress?.progress_percent ?? (result ? 100 : 0)}
               elapsedMs={progress?.elapsed_ms ?? (result?.duration_ms ?? 0)}
+              etaMs={result ? null : (progress?.eta_ms ?? null)}
             />
 
+            {/* Rejected probe being retried, and the rejections so far */}
+            {phaseData.retry !== undefined && (
+              <p className="text-xs text-[var(--color-warning)]">
+                {phaseData.reason === "outlier_rtt"
+                  ? `RTT ${Number(phaseData.rtt_ms).toFixed(0)} ms outside the latency profile`
+                  : String(phaseData.error)}{" "}
+                · retry {Number(phaseData.retry)}/{Number(phaseData.max_retries)} in{" "}
+                {(Number(phaseData.backoff_ms) / 1000).toFixed(1)}s
+                {phaseData.rejected != null && (
+                  <>
+                    {" "}
+                    · {rejectionSummary(phaseData.rejected as Record<string, number>)} of{" "}
+                    {Number(phaseData.probes_sent)} probes rejected
+                  </>
+                )}
+              </p>
+            )}
+
+            {/* Pre-check of the local network, before the first probe */}
+            {isActive && phaseData.baseline != null && (
+              <p className="text-xs text-[var(--color-text-secondary)]">
+                {baselineSummary(phaseData.baseline as NetworkBaseline)}
+              </p>
+            )}
+
+            {/* Server clock off by a zone or more, seen once the whole second is known */}
+            {isActive && phaseData.clock_issue != null && (
+              <p className="text-xs text-[var(--color-warning)]">
+                {String((phaseData.clock_issue as { label: string }).label)}
+              </p>
+            )}
+
+            {/* Where the last attempt stopped */}
+            {failure && !isActive && (
+              <div className="bg-[var(--color-bg-deep)] p-4 rounded-xl border border-[var(--color-danger)]/40 text-sm">
+                <p className="font-semibold text-[var(--color-danger)]">
+                  Failed during {getPhaseDisplay(failure.phase).label} after{" "}
+                  {failure.phase_attempts} attempt{failure.phase_attempts === 1 ? "" : "s"}
+                </p>
+                <p className="mt-1 text-[var(--color-text-secondary)]">
+                  {failure.error.message} · {failure.total_attempts} requests in{" "}
+                  {(failure.elapsed_ms / 1000).toFixed(1)}s
+                </p>
+                {failure.partial_offset_ms !== null && (
+                  <p className="mt-1 text-[var(--color-text-secondary)]">
+                    Unverified offset: {failure.partial_offset_ms.toFixed(1)} ms
+                  </p>
+                )}
+              </div>
+            )}
+
             {/* Binary search viz (Phase 3 only) */}
             {currentPhase === "binary_search" && (
               <BinarySearchViz
@@ -126,6 +188,16 @@ export function SyncProgressPanel({ server, onClose }: SyncProgressPanelProps) {
                     <p className="font-mono font-semibold text-lg text-[var(--color-accent)]">
                       {result.total_offset_ms >= 0 ? "+" : ""}{result.total_offset_ms.toFixed(2)} ms
                     </p>
+                    {result.precision_ms !== null && (
+                      <p className="mt-1 text-xs font-mono text-[var(--color-text-secondary)]">
+                        ± {result.precision_ms.toFixed(1)} ms
+                      </p>
+                    )}
+                    {result.clock_issue && (
+                      <p className="mt-1 text-xs text-[var(--color-warning)]">
+                        {result.clock_issue.label}
+                      </p>
+                    )}
                   </div>
                   <div>
                     <p className="text-xs text-[var(--color-text-secondary)] uppercase tracking-wider font-medium mb-1">
@@ -144,6 +216,7 @@ export function SyncProgressPanel({ server, onClose }: SyncProgressPanelProps) {
                       result.verified ? "text-[var(--color-success)]" : "text-[var(--color-danger)]"
                     )}>
                       {result.verified ? "Yes" : "No"}
+                      <GradeBadge grade={result.grade} className="ml-2 align-middle" />
                     </p>
                   </div>
                   <div>
@@ -168,6 +241,7 @@ export function SyncProgressPanel({ server, onClose }: SyncProgressPanelProps) {
                   : null
               }
               convergencePercent={Number(phaseData.convergence_percent ?? progress?.progress_percent ?? 0)}
+              stoppedEarly={phaseData.stopped_early === true}
               verified={result?.verified ?? null}
             />
             <NetworkStats
@@ -180,6 +254,15 @@ export function SyncProgressPanel({ server, onClose }: SyncProgressPanelProps) {
                     : null
               }
               probeCount={Number(phaseData.probe_index ?? phaseData.iteration ?? 0)}
+              breakdownMs={
+                phaseData.ttfb_ms != null
+                  ? {
+                      dns: Number(phaseData.dns_ms),
+                      connect: Number(phaseData.connect_ms),
+                      ttfb: Number(phaseData.ttfb_ms),
+                    }
+                  : null
+              }
             />
           </div>
         </div>
@@ -195,12 +278,39 @@ export function SyncProgressPanel({ server, onClose }: SyncProgressPanelProps) {
               Cancel Sync
             </Button>
           ) : (
-            <Button variant="ghost" onClick={onClose}>
-              Close
-            </Button>
+            <div className="flex gap-2">
+              {failure?.error.retryable && (
+                <Button onClick={() => startSync(server.id)}>
+                  <RotateCcw className="mr-1.5 h-4 w-4" />
+                  Retry
+                </Button>
+              )}
+              <Button variant="ghost" onClick={onClose}>
+                Close
+              </Button>
+            </div>
           )}
         </div>
       </div>
     </div>
   );
 }
+
+const REJECTION_LABELS: Record<string, string> = {
+  outlier_rtt: "outlier",
+  transport_error: "transport",
+  cached_response: "cached",
+};
+
+// "4 (3 outlier, 1 transport)": the sync's rejected probes so far, by reason.
+function rejectionSummary(rejected: Record<string, number>): string {
+  const parts = Object.entries(rejected).filter(([, n]) => n > 0);
+  const total = parts.reduce((sum, [, n]) => sum + n, 0);
+  const detail = parts.map(([reason, n]) => `${n} ${REJECTION_LABELS[reason] ?? reason}`);
+  return `${total} (${detail.join(", ")})`;
+}
+
+function baselineSummary(b: NetworkBaseline): string {
+  const rtt = b.median_rtt_ms === null ? "no reply" : `${b.median_rtt_ms.toFixed(0)} ms`;
+  return `Network baseline: ${rtt} to ${b.reference}, ${b.lost} of ${b.sent} probes lost`;
+}
@@ -0,0 +1,33 @@
+import { cn } from "@/lib/utils";
+import type { SyncGrade } from "@/types/server";
+
+const gradeClass: Record<SyncGrade, string> = {
+  A: "bg-[var(--color-success)]/15 text-[var(--color-success)]",
+  B: "bg-[var(--color-success)]/15 text-[var(--color-success)]",
+  C: "bg-[var(--color-warning)]/15 text-[var(--color-warning)]",
+  D: "bg-[var(--color-warning)]/15 text-[var(--color-warning)]",
+  F: "bg-[var(--color-danger)]/15 text-[var(--color-danger)]",
+};
+
+interface GradeBadgeProps {
+  grade: SyncGrade | null;
+  className?: string;
+}
+
+// A–F trust grade of a sync; renders nothing for results stored before
+// grading.
+export function GradeBadge({ grade, className }: GradeBadgeProps) {
+  if (!grade) return null;
+  return (
+    <span
+      title="Sync quality: jitter, retries, convergence width and verification"
+      className={cn(
+        "inline-flex h-5 w-5 items-center justify-center rounded font-sans text-xs font-bold",
+        gradeClass[grade],
+        className,
+      )}
+    >
+      {grade}
+    </span>
+  );
+}
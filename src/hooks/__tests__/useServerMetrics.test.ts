@@ -11,6 +11,7 @@ function makeProfile(overrides: Partial<LatencyProfile> = {}): LatencyProfile {
     mean: 20,
     q3: 24,
     max: 40,
+    breakdown: null,
     ...overrides,
   };
 }
@@ -26,6 +27,17 @@ function makeResult(overrides: Partial<SyncResult> = {}): SyncResult {
     synced_at: new Date().toISOString(), // very recent — minimal age penalty
     duration_ms: 200,
     phase_reached: "complete",
+    fingerprint: null,
+    asymmetry: null,
+    response: null,
+    id: 1,
+    timeline: [],
+    note: null,
+    anomalous: false,
+    clock_issue: null,
+    network: null,
+    grade: null,
+    precision_ms: null,
     ...overrides,
   };
 }
@@ -152,6 +164,27 @@ describe("useServerMetrics", () => {
     });
   });
 
+  describe("driftR2", () => {
+    it("is null without a fit", () => {
+      const r = makeResult();
+      const { result } = renderHook(() => useServerMetrics([r], r));
+      expect(result.current.driftR2).toBeNull();
+    });
+
+    it("is near 1 for a straight-line history and lower for a noisy one", () => {
+      const t0 = new Date("2024-01-01T00:00:00Z").getTime();
+      const at = (h: number, offset: number) =>
+        makeResult({ synced_at: new Date(t0 + h * 3600000).toISOString(), total_offset_ms: offset });
+      const straight = [at(0, 0), at(1, 5), at(2, 10), at(3, 15)];
+      const noisy = [at(0, 0), at(1, 40), at(2, -20), at(3, 15)];
+
+      const fit = renderHook(() => useServerMetrics(straight, straight[3])).result.current;
+      expect(fit.driftR2).toBeCloseTo(1, 3);
+      const scattered = renderHook(() => useServerMetrics(noisy, noisy[3])).result.current;
+      expect(scattered.driftR2).toBeLessThan(0.5);
+    });
+  });
+
   describe("driftStatus", () => {
     it("returns Unknown when driftRate is null (< 2 results)", () => {
       const latest = makeResult();
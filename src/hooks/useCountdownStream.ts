@@ -0,0 +1,37 @@
+import { useEffect, useState } from "react";
+import type { CountdownTick } from "@/types/server";
+import { startCountdownStream, stopCountdownStream } from "@/lib/commands";
+import { errorMessage } from "@/lib/utils";
+
+interface UseCountdownStreamReturn {
+  tick: CountdownTick | null;
+  error: string | null;
+}
+
+// Latest tick of a target's countdown; the stream stops on unmount.
+export function useCountdownStream(targetId: number | null): UseCountdownStreamReturn {
+  const [tick, setTick] = useState<CountdownTick | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    setTick(null);
+    setError(null);
+    if (targetId === null) return;
+
+    let streamId: number | null = null;
+    let cancelled = false;
+    startCountdownStream(targetId, setTick)
+      .then((id) => {
+        if (cancelled) stopCountdownStream(id).catch(() => {});
+        else streamId = id;
+      })
+      .catch((e) => setError(errorMessage(e)));
+
+    return () => {
+      cancelled = true;
+      if (streamId !== null) stopCountdownStream(streamId).catch(() => {});
+    };
+  }, [targetId]);
+
+  return { tick, error };
+}
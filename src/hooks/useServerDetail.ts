@@ -3,6 +3,7 @@ import { useNavigate } from "react-router-dom";
 import type { Server, SyncResult } from "@/types/server";
 import { getServer, getSyncHistory } from "@/lib/commands";
 import { useSyncStore } from "@/stores/syncStore";
+import { errorMessage, isAppError } from "@/lib/utils";
 
 interface UseServerDetailReturn {
   server: Server | null;
@@ -33,12 +34,11 @@ export function useServerDetail(id: number): UseServerDetailReturn {
       setServer(srv);
       setSyncHistory(history);
     } catch (e) {
-      const msg = String(e);
-      if (msg.includes("not found") || msg.includes("No server")) {
+      if (isAppError(e) && e.code === "not_found") {
         navigate("/", { replace: true });
         return;
       }
-      setError(msg);
+      setError(errorMessage(e));
     } finally {
       setIsLoading(false);
     }
@@ -6,6 +6,8 @@ interface ServerMetrics {
   jitter: number | null;
   jitterStatus: string;
   driftRate: number | null;
+  // How well a straight line explains the offset history (0–1).
+  driftR2: number | null;
   driftStatus: string;
 }
 
@@ -42,7 +44,7 @@ export function useServerMetrics(
     return jitter < profile.median * 0.5 ? "Stable" : "Unstable";
   }, [latestResult, jitter]);
 
-  const driftRate = useMemo(() => {
+  const trend = useMemo(() => {
     if (syncHistory.length < 2) return null;
 
     const points = syncHistory.map((r) => ({
@@ -61,14 +63,25 @@ export function useServerMetrics(
 
     // slope is ms offset per ms time
     const slope = (n * sumTO - sumT * sumO) / denom;
+    const intercept = (sumO - slope * sumT) / n;
+
+    const meanO = sumO / n;
+    const ssTot = points.reduce((s, p) => s + (p.offset - meanO) ** 2, 0);
+    const ssRes = points.reduce((s, p) => s + (p.offset - (intercept + slope * p.t)) ** 2, 0);
+    // A flat history is fitted exactly by a flat line.
+    const r2 = ssTot === 0 ? 1 : Math.max(0, 1 - ssRes / ssTot);
+
     // convert to ms/hr
-    return slope * 3600000;
+    return { rate: slope * 3600000, r2 };
   }, [syncHistory]);
 
+  const driftRate = trend?.rate ?? null;
+  const driftR2 = trend?.r2 ?? null;
+
   const driftStatus = useMemo(() => {
     if (driftRate === null) return "Unknown";
     return Math.abs(driftRate) < 10 ? "Stable" : "Unstable";
   }, [driftRate]);
 
-  return { healthScore, jitter, jitterStatus, driftRate, driftStatus };
+  return { healthScore, jitter, jitterStatus, driftRate, driftR2, driftStatus };
 }
@@ -14,6 +14,17 @@ function makeSyncResult(overrides: Partial<SyncResult> = {}): SyncResult {
     verified: true,
     duration_ms: 250,
     phase_reached: "complete",
+    fingerprint: null,
+    asymmetry: null,
+    response: null,
+    id: 1,
+    timeline: [],
+    note: null,
+    anomalous: false,
+    clock_issue: null,
+    network: null,
+    grade: null,
+    precision_ms: null,
     latency_profile: {
       min: 5,
       q1: 7,
@@ -21,6 +32,7 @@ function makeSyncResult(overrides: Partial<SyncResult> = {}): SyncResult {
       mean: 9.5,
       q3: 11,
       max: 15,
+      breakdown: null,
     },
     ...overrides,
   };
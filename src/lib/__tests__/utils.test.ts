@@ -1,5 +1,5 @@
 import { describe, it, expect } from "vitest";
-import { cn } from "@/lib/utils";
+import { cn, describeNetwork, errorMessage, formatEta, isAppError } from "@/lib/utils";
 
 describe("cn()", () => {
   it("merges multiple class strings", () => {
@@ -39,3 +39,39 @@ describe("cn()", () => {
     );
   });
 });
+
+describe("errorMessage()", () => {
+  it("uses the message of a structured command error", () => {
+    const e = { code: "not_synced", message: "server 4 has not been synced yet", retryable: false };
+    expect(isAppError(e)).toBe(true);
+    expect(errorMessage(e)).toBe("server 4 has not been synced yet");
+  });
+
+  it("falls back to String() for anything else", () => {
+    expect(isAppError("boom")).toBe(false);
+    expect(errorMessage("boom")).toBe("boom");
+    expect(errorMessage(new Error("bad"))).toBe("Error: bad");
+  });
+});
+
+describe("describeNetwork()", () => {
+  it("names the interface kind, a short SSID hash and a VPN", () => {
+    expect(
+      describeNetwork({ interface: "wlan0", kind: "wifi", vpn: true, ssid_hash: "3f9a1c0b7e2d4a58" }),
+    ).toBe("Wi-Fi 3f9a1c · VPN");
+    expect(describeNetwork({ interface: "eth0", kind: "ethernet", vpn: false, ssid_hash: null })).toBe(
+      "Ethernet",
+    );
+    expect(describeNetwork({ interface: null, kind: null, vpn: false, ssid_hash: null })).toBe(
+      "Offline",
+    );
+  });
+});
+
+describe("formatEta()", () => {
+  it("rounds to seconds, then minutes", () => {
+    expect(formatEta(800)).toBe("almost done");
+    expect(formatEta(19_600)).toBe("about 20 s left");
+    expect(formatEta(150_000)).toBe("about 3 min left");
+  });
+});
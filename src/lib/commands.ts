@@ -1,11 +1,218 @@
 import { invoke, Channel } from "@tauri-apps/api/core";
-import type { Server, SyncEvent, SyncResult } from "@/types/server";
-import type { Settings } from "@/types/settings";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import type {
+  AsymmetryCalibration,
+  BenchmarkReport,
+  BulkAddEntry,
+  ConnectionTest,
+  CountdownTick,
+  Dashboard,
+  DemoServer,
+  DomainPreset,
+  ExtractorInfo,
+  LatencyStats,
+  OffsetEstimate,
+  PluginFailure,
+  RecordingInfo,
+  ReferenceSource,
+  RelativeOffset,
+  ReplayReport,
+  ScheduledSync,
+  Server,
+  ServerComparison,
+  ServerStatusChange,
+  ServerTime,
+  SyncProfile,
+  SyncErrorRecord,
+  SyncEvent,
+  SyncHistoryPage,
+  SyncPhase,
+  SyncResult,
+  SyncResultDiff,
+  Target,
+  TimeUntil,
+  TransportOptions,
+  TrashedServer,
+} from "@/types/server";
+import type { AlertMethod, Settings } from "@/types/settings";
+import type {
+  AlertFired,
+  ClockAdjustment,
+  DeepLinkPayload,
+  HealthResync,
+  ImportSummary,
+  LocalNtpStatus,
+  NetworkChange,
+  NetworkBaseline,
+  NtpMeasurement,
+  SchedulerStatus,
+  SecondInstancePayload,
+  ShareImportSummary,
+  TargetConfidence,
+  TargetFired,
+  UrlValidation,
+  Workspace,
+  WorldClock,
+} from "@/types/app";
 
 export async function addServer(url: string): Promise<Server> {
   return invoke<Server>("add_server", { url });
 }
 
+// Names the server from its page's og:site_name or title, else its host.
+export async function refreshServerName(serverId: number): Promise<Server> {
+  return invoke<Server>("refresh_server_name", { serverId });
+}
+
+// A data: URL of the server's icon, cached after the first lookup; null
+// when the site has none.
+export async function getServerFavicon(serverId: number): Promise<string | null> {
+  return invoke<string | null>("get_server_favicon", { serverId });
+}
+
+// Every URL in a pasted list or message, added in one transaction.
+export async function addServersFromText(text: string): Promise<BulkAddEntry[]> {
+  return invoke<BulkAddEntry[]>("add_servers_from_text", { text });
+}
+
+// Re-adding the same configuration returns the existing server.
+export async function addDemoServer(demo: DemoServer): Promise<Server> {
+  return invoke<Server>("add_demo_server", { demo });
+}
+
+export async function listReferenceSources(): Promise<ReferenceSource[]> {
+  return invoke<ReferenceSource[]>("list_reference_sources");
+}
+
+// Re-adding a source returns the existing server. The first one becomes
+// the reference server if none is set.
+export async function addReferenceSource(kind: string): Promise<Server> {
+  return invoke<Server>("add_reference_source", { kind });
+}
+
+export async function validateUrl(url: string): Promise<UrlValidation> {
+  return invoke<UrlValidation>("validate_url", { url });
+}
+
+// Three quick probes; no sync runs and no history is written.
+export async function testConnection(serverId: number): Promise<ConnectionTest> {
+  return invoke<ConnectionTest>("test_connection", { serverId });
+}
+
+// One probe, ±RTT/2 plus the Date header's half second; not a sync.
+export async function estimateOffset(serverId: number): Promise<OffsetEstimate> {
+  return invoke<OffsetEstimate>("estimate_offset", { serverId });
+}
+
+// Runs the four-phase sync and the interval estimator back-to-back; the
+// server's offset and history are left alone.
+export async function benchmarkSync(serverId: number): Promise<BenchmarkReport> {
+  return invoke<BenchmarkReport>("benchmark_sync", { serverId });
+}
+
+export async function listBenchmarkReports(serverId: number): Promise<BenchmarkReport[]> {
+  return invoke<BenchmarkReport[]>("list_benchmark_reports", { serverId });
+}
+
+export async function listSyncRecordings(): Promise<RecordingInfo[]> {
+  return invoke<RecordingInfo[]>("list_sync_recordings");
+}
+
+export async function replaySyncRecording(file: string): Promise<ReplayReport> {
+  return invoke<ReplayReport>("replay_sync_recording", { file });
+}
+
+export async function listSyncProfiles(): Promise<SyncProfile[]> {
+  return invoke<SyncProfile[]>("list_sync_profiles");
+}
+
+// `null` puts the server back on the global profile.
+export async function setServerSyncProfile(
+  serverId: number,
+  profile: string | null,
+): Promise<Server> {
+  return invoke<Server>("set_server_sync_profile", { serverId, profile });
+}
+
+// null goes back to assuming a symmetric path.
+export async function setServerAsymmetry(
+  serverId: number,
+  asymmetry: number | null,
+): Promise<Server> {
+  return invoke<Server>("set_server_asymmetry", { serverId, asymmetry });
+}
+
+export async function setServerRequestInterval(
+  serverId: number,
+  intervalMs: number | null,
+): Promise<Server> {
+  return invoke<Server>("set_server_request_interval", { serverId, intervalMs });
+}
+
+export async function setServerUserAgent(
+  serverId: number,
+  userAgent: string | null,
+): Promise<Server> {
+  return invoke<Server>("set_server_user_agent", { serverId, userAgent });
+}
+
+export async function setServerTransport(
+  serverId: number,
+  transport: TransportOptions,
+): Promise<Server> {
+  return invoke<Server>("set_server_transport", { serverId, transport });
+}
+
+// Null goes back to guessing the zone from the URL and headers.
+export async function setServerTimezone(
+  serverId: number,
+  timezone: string | null,
+): Promise<Server> {
+  return invoke<Server>("set_server_timezone", { serverId, timezone });
+}
+
+export async function setServerTrusted(serverId: number, trusted: boolean): Promise<Server> {
+  return invoke<Server>("set_server_trusted", { serverId, trusted });
+}
+
+export async function setServerSyncOnLaunch(serverId: number, enabled: boolean): Promise<Server> {
+  return invoke<Server>("set_server_sync_on_launch", { serverId, enabled });
+}
+
+// Runs a symmetric sync against NTP time and stores the estimated factor.
+// Only meaningful for servers whose clock is NTP-synced.
+export async function calibrateAsymmetry(serverId: number): Promise<AsymmetryCalibration> {
+  return invoke<AsymmetryCalibration>("calibrate_asymmetry", { serverId });
+}
+
+export async function listDomainPresets(): Promise<DomainPreset[]> {
+  return invoke<DomainPreset[]>("list_domain_presets");
+}
+
+// `null` drops the preset and goes back to plain HEAD probes.
+export async function setServerDomainPreset(
+  serverId: number,
+  preset: string | null,
+): Promise<Server> {
+  return invoke<Server>("set_server_domain_preset", { serverId, preset });
+}
+
+export async function listExtractors(): Promise<ExtractorInfo[]> {
+  return invoke<ExtractorInfo[]>("list_extractors");
+}
+
+// Rescans the plugins directory; resolves to the files that failed to load.
+export async function reloadExtractorPlugins(): Promise<PluginFailure[]> {
+  return invoke<PluginFailure[]>("reload_extractor_plugins");
+}
+
+export async function setServerExtractor(
+  serverId: number,
+  extractorType: string,
+): Promise<Server> {
+  return invoke<Server>("set_server_extractor", { serverId, extractorType });
+}
+
 export async function getServer(id: number): Promise<Server> {
   return invoke<Server>("get_server", { id });
 }
@@ -14,10 +221,147 @@ export async function listServers(): Promise<Server[]> {
   return invoke<Server[]>("list_servers");
 }
 
+// Moves the server, with its history and targets, to the trash.
 export async function deleteServer(id: number): Promise<void> {
   return invoke<void>("delete_server", { id });
 }
 
+// Most recently deleted first; expired entries are purged on the way.
+export async function listTrash(): Promise<TrashedServer[]> {
+  return invoke<TrashedServer[]>("list_trash");
+}
+
+export async function restoreServer(id: number): Promise<Server> {
+  return invoke<Server>("restore_server", { id });
+}
+
+// Deletes one server for good, skipping the trash.
+export async function purgeServer(id: number): Promise<void> {
+  return invoke<void>("purge_server", { id });
+}
+
+// Resolves to the number of servers purged.
+export async function emptyTrash(): Promise<number> {
+  return invoke<number>("empty_trash");
+}
+
+// Servers plus staleness, next scheduled syncs and upcoming targets in one
+// call, for app start.
+export async function getDashboard(): Promise<Dashboard> {
+  return invoke<Dashboard>("get_dashboard");
+}
+
+export async function getPrimaryServer(): Promise<number | null> {
+  return invoke<number | null>("get_primary_server");
+}
+
+export async function setPrimaryServer(id: number | null): Promise<void> {
+  return invoke<void>("set_primary_server", { id });
+}
+
+export async function getReferenceServer(): Promise<number | null> {
+  return invoke<number | null>("get_reference_server");
+}
+
+export async function setReferenceServer(id: number | null): Promise<void> {
+  return invoke<void>("set_reference_server", { id });
+}
+
+// Omit `referenceId` to use the saved reference server.
+export async function getRelativeOffsets(
+  referenceId?: number,
+): Promise<RelativeOffset[]> {
+  return invoke<RelativeOffset[]>("get_relative_offsets", {
+    referenceId: referenceId ?? null,
+  });
+}
+
+// At least two distinct ids; rows and columns follow their order.
+export async function compareServers(serverIds: number[]): Promise<ServerComparison> {
+  return invoke<ServerComparison>("compare_servers", { serverIds });
+}
+
+// targetAt is an RFC 3339 instant or a wall-clock time:
+// "[YYYY-MM-DD] HH:MM[:SS] server-local|local|utc", the next occurrence when
+// the date is left out.
+export async function createTarget(
+  serverId: number,
+  targetAt: string,
+  label?: string,
+): Promise<Target> {
+  return invoke<Target>("create_target", {
+    serverId,
+    targetAt,
+    label: label ?? null,
+  });
+}
+
+export async function listTargets(): Promise<Target[]> {
+  return invoke<Target[]>("list_targets");
+}
+
+// Null makes the target follow the alert_method setting again.
+export async function setTargetAlertMethod(
+  id: number,
+  method: AlertMethod | null,
+): Promise<Target> {
+  return invoke<Target>("set_target_alert_method", { id, method });
+}
+
+export async function deleteTarget(id: number): Promise<void> {
+  return invoke<void>("delete_target", { id });
+}
+
+export async function getHookScript(): Promise<string> {
+  return invoke<string>("get_hook_script");
+}
+
+// Rejects with a validation error when the script doesn't compile; resolves
+// to the hook names it defines.
+export async function saveHookScript(source: string): Promise<string[]> {
+  return invoke<string[]>("save_hook_script", { source });
+}
+
+// `at` must be in the future. Schedules survive restarts; ones missed by
+// more than 10 minutes while the app was closed are dropped.
+export async function scheduleSync(serverId: number, at: string): Promise<ScheduledSync> {
+  return invoke<ScheduledSync>("schedule_sync", { serverId, at });
+}
+
+export async function listScheduledSyncs(): Promise<ScheduledSync[]> {
+  return invoke<ScheduledSync[]>("list_scheduled_syncs");
+}
+
+export async function cancelScheduledSync(id: number): Promise<void> {
+  return invoke<void>("cancel_scheduled_sync", { id });
+}
+
+// Fired with the remaining schedules after some have started.
+export async function onScheduledSyncs(
+  handler: (pending: ScheduledSync[]) => void,
+): Promise<UnlistenFn> {
+  return listen<ScheduledSync[]>("scheduled-syncs", (e) => handler(e.payload));
+}
+
+export async function getTimeUntil(targetId: number): Promise<TimeUntil> {
+  return invoke<TimeUntil>("get_time_until", { targetId });
+}
+
+// Ticks arrive every `countdown_tick_ms` until stopCountdownStream, or
+// until the target or its server is deleted. Resolves to the stream id.
+export async function startCountdownStream(
+  targetId: number,
+  onTick: (tick: CountdownTick) => void,
+): Promise<number> {
+  const channel = new Channel<CountdownTick>();
+  channel.onmessage = onTick;
+  return invoke<number>("start_countdown_stream", { targetId, onTick: channel });
+}
+
+export async function stopCountdownStream(streamId: number): Promise<void> {
+  return invoke<void>("stop_countdown_stream", { streamId });
+}
+
 export async function startSync(
   id: number,
   onEvent: (event: SyncEvent) => void,
@@ -27,6 +371,28 @@ export async function startSync(
   return invoke<void>("start_sync", { id, onEvent: channel });
 }
 
+export async function onSyncEvent(
+  handler: (event: SyncEvent) => void,
+): Promise<UnlistenFn> {
+  return listen<SyncEvent>("sync-event", (e) => handler(e.payload));
+}
+
+// Fired when the app is launched again while already running.
+export async function onSecondInstance(
+  handler: (payload: SecondInstancePayload) => void,
+): Promise<UnlistenFn> {
+  return listen<SecondInstancePayload>("second-instance", (e) =>
+    handler(e.payload),
+  );
+}
+
+// Fired after a ticketime:// link has added a server (and maybe a target).
+export async function onDeepLink(
+  handler: (payload: DeepLinkPayload) => void,
+): Promise<UnlistenFn> {
+  return listen<DeepLinkPayload>("deep-link", (e) => handler(e.payload));
+}
+
 export async function cancelSync(id: number): Promise<void> {
   return invoke<void>("cancel_sync", { id });
 }
@@ -42,6 +408,90 @@ export async function getSyncHistory(
   });
 }
 
+// `limit` defaults to 50 and may be at most 500. `verified` and
+// `phaseReached` are filtered in SQL, so totals count only matches.
+export async function getSyncHistoryPage(
+  id: number,
+  options?: {
+    since?: string;
+    verified?: boolean;
+    phaseReached?: SyncPhase;
+    anomalous?: boolean;
+    offset?: number;
+    limit?: number;
+  },
+): Promise<SyncHistoryPage> {
+  return invoke<SyncHistoryPage>("get_sync_history_page", {
+    id,
+    since: options?.since ?? null,
+    verified: options?.verified ?? null,
+    phaseReached: options?.phaseReached ?? null,
+    anomalous: options?.anomalous ?? null,
+    offset: options?.offset ?? null,
+    limit: options?.limit ?? null,
+  });
+}
+
+// Markdown (default) or pretty-printed JSON, for pasting into a chat.
+export async function generateSyncReport(
+  syncId: number,
+  format?: "markdown" | "json",
+): Promise<string> {
+  return invoke<string>("generate_sync_report", { syncId, format: format ?? null });
+}
+
+// Oldest first: timestamp, offset, uncertainty and verified per sync.
+// Resolves to the number of syncs written.
+export async function exportOffsetSeries(
+  serverId: number,
+  path: string,
+  format: "csv" | "json",
+  passphrase?: string,
+): Promise<number> {
+  return invoke<number>("export_offset_series", {
+    serverId,
+    path,
+    format,
+    passphrase: passphrase || null,
+  });
+}
+
+// Writes an export rendered here, e.g. the sync history. A passphrase
+// encrypts it (AES-256-GCM); blank writes it as is.
+export async function writeExport(
+  path: string,
+  contents: string,
+  passphrase?: string,
+): Promise<void> {
+  return invoke<void>("write_export", { path, contents, passphrase: passphrase || null });
+}
+
+// A blank note clears it.
+export async function annotateSyncResult(id: number, note: string | null): Promise<void> {
+  return invoke("annotate_sync_result", { id, note });
+}
+
+// Deltas read from idA to idB.
+export async function compareSyncResults(idA: number, idB: number): Promise<SyncResultDiff> {
+  return invoke<SyncResultDiff>("compare_sync_results", { idA, idB });
+}
+
+// Newest first.
+export async function getErrorHistory(
+  serverId: number,
+  limit?: number,
+): Promise<SyncErrorRecord[]> {
+  return invoke<SyncErrorRecord[]>("get_error_history", {
+    serverId,
+    limit: limit ?? null,
+  });
+}
+
+// Stored latency profiles by hour of day and weekday.
+export async function getLatencyStats(serverId: number): Promise<LatencyStats> {
+  return invoke<LatencyStats>("get_latency_stats", { serverId });
+}
+
 export async function getSettings(): Promise<Settings> {
   return invoke<Settings>("get_settings");
 }
@@ -49,3 +499,185 @@ export async function getSettings(): Promise<Settings> {
 export async function updateSettings(settings: Settings): Promise<void> {
   return invoke<void>("update_settings", { settings });
 }
+
+export async function getSchedulerStatus(): Promise<SchedulerStatus> {
+  return invoke<SchedulerStatus>("get_scheduler_status");
+}
+
+export async function pauseScheduler(): Promise<SchedulerStatus> {
+  return invoke<SchedulerStatus>("pause_scheduler");
+}
+
+export async function resumeScheduler(): Promise<SchedulerStatus> {
+  return invoke<SchedulerStatus>("resume_scheduler");
+}
+
+// Fired whenever the auto-resync scheduler is paused or resumed.
+export async function onSchedulerState(
+  handler: (status: SchedulerStatus) => void,
+): Promise<UnlistenFn> {
+  return listen<SchedulerStatus>("scheduler-state", (e) => handler(e.payload));
+}
+
+export async function onTargetAlert(
+  handler: (alert: AlertFired) => void,
+): Promise<UnlistenFn> {
+  return listen<AlertFired>("target-alert", (e) => handler(e.payload));
+}
+
+export async function onTargetConfidence(
+  handler: (confidence: TargetConfidence) => void,
+): Promise<UnlistenFn> {
+  return listen<TargetConfidence>("target-confidence", (e) => handler(e.payload));
+}
+
+export async function onTargetFired(
+  handler: (fired: TargetFired) => void,
+): Promise<UnlistenFn> {
+  return listen<TargetFired>("target-fired", (e) => handler(e.payload));
+}
+
+export async function onHealthResync(
+  handler: (resync: HealthResync) => void,
+): Promise<UnlistenFn> {
+  return listen<HealthResync>("health-resync", (e) => handler(e.payload));
+}
+
+export async function onNetworkChanged(
+  handler: (change: NetworkChange) => void,
+): Promise<UnlistenFn> {
+  return listen<NetworkChange>("network-changed", (e) => handler(e.payload));
+}
+
+export async function onServerStatusChanged(
+  handler: (change: ServerStatusChange) => void,
+): Promise<UnlistenFn> {
+  return listen<ServerStatusChange>("server-status-changed", (e) => handler(e.payload));
+}
+
+// Versioned JSON with settings plus per-server name/extractor overrides,
+// encrypted when a passphrase is given.
+export async function exportSettings(path: string, passphrase?: string): Promise<void> {
+  return invoke<void>("export_settings", { path, passphrase: passphrase || null });
+}
+
+// Rejects with code "passphrase_required" for an encrypted file opened
+// without one, "decryption_failed" for a wrong one.
+export async function importSettings(
+  path: string,
+  passphrase?: string,
+): Promise<ImportSummary> {
+  return invoke<ImportSummary>("import_settings", { path, passphrase: passphrase || null });
+}
+
+// Signed "TT1.…" string carrying servers (and the servers of any targets),
+// compact enough for a QR code. Both sides need the same passphrase.
+export async function exportSharePayload(
+  serverIds: number[],
+  targetIds: number[],
+  passphrase: string,
+): Promise<string> {
+  return invoke<string>("export_share_payload", { serverIds, targetIds, passphrase });
+}
+
+export async function importSharePayload(
+  payload: string,
+  passphrase: string,
+): Promise<ShareImportSummary> {
+  return invoke<ShareImportSummary>("import_share_payload", { payload, passphrase });
+}
+
+// Fired after settings are saved or imported, to every window.
+export async function onSettingsChanged(
+  handler: (settings: Settings) => void,
+): Promise<UnlistenFn> {
+  return listen<Settings>("settings-changed", (e) => handler(e.payload));
+}
+
+export async function listWorkspaces(): Promise<Workspace[]> {
+  return invoke<Workspace[]>("list_workspaces");
+}
+
+// Creates `name` if it does not exist. Rejects while a sync is running.
+export async function switchWorkspace(name: string): Promise<Workspace[]> {
+  return invoke<Workspace[]>("switch_workspace", { name });
+}
+
+// Typed by the user to confirm factoryReset.
+export const FACTORY_RESET_CONFIRMATION = "erase everything";
+
+// Erases all workspaces, history, targets, settings, the API token,
+// recordings, hooks and plugins. Rejects unless confirmation matches
+// FACTORY_RESET_CONFIRMATION or while a sync is running.
+export async function factoryReset(confirmation: string): Promise<void> {
+  return invoke<void>("factory_reset", { confirmation });
+}
+
+// Fired with the new workspace name after a switch; settings-changed fires
+// too, but servers and targets must be refetched.
+export async function onWorkspaceChanged(
+  handler: (name: string) => void,
+): Promise<UnlistenFn> {
+  return listen<string>("workspace-changed", (e) => handler(e.payload));
+}
+
+export async function getLocalApiToken(): Promise<string> {
+  return invoke<string>("get_local_api_token");
+}
+
+export async function regenerateLocalApiToken(): Promise<string> {
+  return invoke<string>("regenerate_local_api_token");
+}
+
+// Renders with the saved display settings; pass `pattern` to override
+// time_format for a single call. Omit `serverId` for local time.
+export async function formatTime(
+  serverId?: number,
+  pattern?: string,
+): Promise<string> {
+  return invoke<string>("format_time", {
+    serverId: serverId ?? null,
+    pattern: pattern ?? null,
+  });
+}
+
+// Available right after launch: the stored offset carried forward along
+// the server's drift. Rejects with "not_synced" before its first sync.
+export async function getCorrectedTime(serverId: number): Promise<ServerTime> {
+  return invoke<ServerTime>("get_corrected_time", { serverId });
+}
+
+// `timezones` are IANA names such as "Europe/Berlin".
+export async function getWorldClock(
+  serverId: number,
+  timezones: string[],
+): Promise<WorldClock> {
+  return invoke<WorldClock>("get_world_clock", { serverId, timezones });
+}
+
+export async function measureClockError(): Promise<NtpMeasurement> {
+  return invoke<NtpMeasurement>("measure_clock_error");
+}
+
+export async function checkNetworkBaseline(): Promise<NetworkBaseline> {
+  return invoke<NetworkBaseline>("check_network_baseline");
+}
+
+// null when no chrony, timesyncd or ntpd is running (always on Windows).
+export async function getLocalNtpStatus(): Promise<LocalNtpStatus | null> {
+  return invoke<LocalNtpStatus | null>("get_local_ntp_status");
+}
+
+// Fails unless allow_clock_adjustment is saved and the error still matches
+// the one the user confirmed.
+export async function adjustSystemClock(
+  confirmedOffsetMs: number,
+): Promise<ClockAdjustment> {
+  return invoke<ClockAdjustment>("adjust_system_clock", { confirmedOffsetMs });
+}
+
+// Resolves to "granted", "denied" or "prompt". Target alerts only reach the
+// lock screen on Android and iOS once this is granted.
+export async function requestNotificationPermission(): Promise<string> {
+  return invoke<string>("request_notification_permission");
+}
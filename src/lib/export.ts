@@ -37,3 +37,13 @@ export function syncHistoryToCsv(results: SyncResult[]): string {
 export function syncHistoryToJson(results: SyncResult[]): string {
   return JSON.stringify(results, null, 2);
 }
+
+// Asks for an optional passphrase to encrypt an export with. Null means
+// the user cancelled; "" exports unencrypted.
+export function askExportPassphrase(): string | null {
+  return window.prompt(
+    "Passphrase to encrypt the export with (leave blank to save it unencrypted). " +
+      "Exports may contain auth headers and your server list.",
+    "",
+  );
+}
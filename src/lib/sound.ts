@@ -0,0 +1,17 @@
+let context: AudioContext | null = null;
+
+// Two short beeps, synthesized so no audio asset has to ship.
+export function playAlertSound() {
+  context ??= new AudioContext();
+  const start = context.currentTime;
+  for (const offset of [0, 0.25]) {
+    const oscillator = context.createOscillator();
+    const gain = context.createGain();
+    oscillator.frequency.value = 880;
+    gain.gain.setValueAtTime(0.2, start + offset);
+    gain.gain.exponentialRampToValueAtTime(0.001, start + offset + 0.2);
+    oscillator.connect(gain).connect(context.destination);
+    oscillator.start(start + offset);
+    oscillator.stop(start + offset + 0.2);
+  }
+}
@@ -1,6 +1,39 @@
 import { type ClassValue, clsx } from "clsx";
 import { twMerge } from "tailwind-merge";
+import type { AppError } from "@/types/app";
+import type { InterfaceKind, NetworkContext } from "@/types/server";
+
+const INTERFACE_LABELS: Record<InterfaceKind, string> = {
+  wifi: "Wi-Fi",
+  ethernet: "Ethernet",
+  cellular: "Cellular",
+  other: "Other",
+};
 
 export function cn(...inputs: ClassValue[]) {
   return twMerge(clsx(inputs));
 }
+
+export function isAppError(e: unknown): e is AppError {
+  return typeof e === "object" && e !== null && "code" in e && "message" in e;
+}
+
+// Human-readable text for anything a command can reject with.
+export function errorMessage(e: unknown): string {
+  return isAppError(e) ? e.message : String(e);
+}
+
+// e.g. "Wi-Fi 3f9a1c · VPN": a short SSID hash tells two Wi-Fi networks apart.
+export function describeNetwork(network: NetworkContext): string {
+  const kind = network.kind ? INTERFACE_LABELS[network.kind] : (network.interface ?? "Offline");
+  const ssid = network.ssid_hash ? ` ${network.ssid_hash.slice(0, 6)}` : "";
+  return `${kind}${ssid}${network.vpn ? " · VPN" : ""}`;
+}
+
+// A sync's estimated time left, rounded the way people say it.
+export function formatEta(ms: number): string {
+  const seconds = Math.round(ms / 1000);
+  if (seconds < 2) return "almost done";
+  if (seconds < 60) return `about ${seconds} s left`;
+  return `about ${Math.round(seconds / 60)} min left`;
+}
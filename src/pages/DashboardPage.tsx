@@ -1,6 +1,11 @@
 import { useState } from "react";
 import { StatsCards } from "@/components/dashboard/StatsCards";
+import { LocalClockCard } from "@/components/dashboard/LocalClockCard";
 import { ServerTable } from "@/components/dashboard/ServerTable";
+import { ReferenceSourceSelect } from "@/components/dashboard/ReferenceSourceSelect";
+import { OffsetMatrix } from "@/components/dashboard/OffsetMatrix";
+import { UpcomingTargets } from "@/components/dashboard/UpcomingTargets";
+import { Button } from "@/components/ui/Button";
 import { SyncProgressPanel } from "@/components/sync/SyncProgressPanel";
 import { useSyncStore } from "@/stores/syncStore";
 import { useServerStore } from "@/stores/serverStore";
@@ -9,6 +14,8 @@ export function DashboardPage() {
   const { startSync } = useSyncStore();
   const { servers } = useServerStore();
   const [syncPanelServerId, setSyncPanelServerId] = useState<number | null>(null);
+  const [comparing, setComparing] = useState(false);
+  const syncedIds = servers.filter((s) => s.offset_ms !== null).map((s) => s.id);
 
   const handleSyncClick = (id: number) => {
     setSyncPanelServerId(id);
@@ -24,10 +31,27 @@ export function DashboardPage() {
       <div className="mx-auto max-w-7xl space-y-8">
         <StatsCards />
 
+        <LocalClockCard />
+
+        <UpcomingTargets />
+
         <div className="flex items-center justify-between">
           <h2 className="text-2xl font-bold text-[var(--color-text-primary)]">Network Overview</h2>
+          <div className="flex items-center gap-2">
+            <ReferenceSourceSelect onAdded={handleSyncClick} className="w-44" />
+            <Button
+              variant="ghost"
+              size="sm"
+              onClick={() => setComparing(!comparing)}
+              disabled={syncedIds.length < 2}
+            >
+              {comparing ? "Hide comparison" : "Compare servers"}
+            </Button>
+          </div>
         </div>
 
+        {comparing && syncedIds.length >= 2 && <OffsetMatrix serverIds={syncedIds} />}
+
         <ServerTable onSyncClick={handleSyncClick} />
 
         {syncPanelServer && (
@@ -11,6 +11,14 @@ import { RttBoxPlot } from "@/components/server-detail/RttBoxPlot";
 import { SyncHealthPanel } from "@/components/server-detail/SyncHealthPanel";
 import { OffsetTrendChart } from "@/components/server-detail/OffsetTrendChart";
 import { MetadataCards } from "@/components/server-detail/MetadataCards";
+import { ErrorHistory } from "@/components/server-detail/ErrorHistory";
+import { LatencyByHour } from "@/components/server-detail/LatencyByHour";
+import { SyncNotes } from "@/components/server-detail/SyncNotes";
+import { PathAsymmetry } from "@/components/server-detail/PathAsymmetry";
+import { UserAgent } from "@/components/server-detail/UserAgent";
+import { ServerTimezone } from "@/components/server-detail/ServerTimezone";
+import { TransportSettings } from "@/components/server-detail/TransportSettings";
+import { ScheduledSyncs } from "@/components/server-detail/ScheduledSyncs";
 import { SyncProgressPanel } from "@/components/sync/SyncProgressPanel";
 
 export function ServerDetailPage() {
@@ -18,7 +26,7 @@ export function ServerDetailPage() {
   const navigate = useNavigate();
   const serverId = Number(id);
 
-  const { server, syncHistory, latestResult, isLoading, error } =
+  const { server, syncHistory, latestResult, isLoading, error, refetch } =
     useServerDetail(serverId);
   const metrics = useServerMetrics(syncHistory, latestResult);
   const { startSync, isSyncing } = useSyncStore();
@@ -78,6 +86,7 @@ export function ServerDetailPage() {
           server={server}
           syncHistory={syncHistory}
           onResync={handleResync}
+          onServerChange={refetch}
           isSyncing={syncing}
         />
 
@@ -93,10 +102,11 @@ export function ServerDetailPage() {
           </div>
           <div className="col-span-12 lg:col-span-4">
             <SyncHealthPanel
-              healthScore={metrics.healthScore}
+              healthScore={server.health_score ?? metrics.healthScore}
               jitter={metrics.jitter}
               jitterStatus={metrics.jitterStatus}
               driftRate={metrics.driftRate}
+              driftR2={metrics.driftR2}
               driftStatus={metrics.driftStatus}
             />
           </div>
@@ -120,6 +130,24 @@ export function ServerDetailPage() {
               latestResult={latestResult}
             />
           </div>
+
+          {/* Row 4: Recent syncs with notes; path asymmetry and schedules */}
+          <div className="col-span-12 flex flex-col gap-6 lg:col-span-8">
+            <SyncNotes serverId={serverId} latestId={latestResult?.id} onChange={refetch} />
+            <LatencyByHour serverId={serverId} />
+          </div>
+          <div className="col-span-12 flex flex-col gap-6 lg:col-span-4">
+            <PathAsymmetry server={server} onChange={refetch} />
+            <UserAgent server={server} onChange={refetch} />
+            <ServerTimezone server={server} onChange={refetch} />
+            <TransportSettings server={server} onChange={refetch} />
+            <ScheduledSyncs serverId={serverId} />
+          </div>
+
+          {/* Row 5: Recorded failures */}
+          <div className="col-span-12">
+            <ErrorHistory serverId={serverId} />
+          </div>
         </div>
       </div>
 
@@ -1,4 +1,5 @@
-import { useEffect } from "react";
+import { useEffect, useState } from "react";
+import { open, save } from "@tauri-apps/plugin-dialog";
 import {
   Settings,
   RefreshCw,
@@ -6,16 +7,37 @@ import {
   Bell,
   Keyboard,
   Plug,
+  Globe,
+  Clock,
+  Share2,
+  Code,
+  Puzzle,
+  Disc,
+  Trash2,
+  ArchiveRestore,
+  Wifi,
 } from "lucide-react";
 import { useSettingsStore } from "@/stores/settingsStore";
+import { useServerStore } from "@/stores/serverStore";
 import { SettingsSection } from "@/components/settings/SettingsSection";
 import { SettingsField } from "@/components/settings/SettingsField";
+import { SyncProfileSelect } from "@/components/settings/SyncProfileSelect";
+import { ShareSetup } from "@/components/settings/ShareSetup";
+import { HookScript } from "@/components/settings/HookScript";
+import { ExtractorPlugins } from "@/components/settings/ExtractorPlugins";
+import { SyncRecordings } from "@/components/settings/SyncRecordings";
+import { Trash } from "@/components/settings/Trash";
 import { Button } from "@/components/ui/Button";
+import { Input } from "@/components/ui/Input";
 import { Toggle } from "@/components/ui/Toggle";
 import { Slider } from "@/components/ui/Slider";
 import { Select } from "@/components/ui/Select";
 import { NumberInput } from "@/components/ui/NumberInput";
-import { cn } from "@/lib/utils";
+import { cn, errorMessage, isAppError } from "@/lib/utils";
+import { askExportPassphrase } from "@/lib/export";
+import * as commands from "@/lib/commands";
+import type { SchedulerStatus } from "@/types/app";
+import type { AlertMethod } from "@/types/settings";
 
 const PRECISION_OPTIONS = [
   { value: 1, label: "0.1s" },
@@ -27,16 +49,144 @@ const EXTERNAL_SOURCE_OPTIONS = [
   { value: "ntp", label: "NTP Pool" },
   { value: "google", label: "Google Time" },
   { value: "cloudflare", label: "Cloudflare" },
+  { value: "http", label: "HTTPS (no UDP)" },
+  { value: "none", label: "None" },
 ];
 
 const ALERT_METHOD_OPTIONS = [
-  { value: "visual", label: "Visual Only" },
+  { value: "notification", label: "Notification Only" },
   { value: "sound", label: "Sound Only" },
   { value: "both", label: "Both" },
+  { value: "speak", label: "Spoken" },
+  { value: "none", label: "Off" },
 ];
 
 const ALARM_INTERVALS = [1, 3, 5, 10, 15, 30, 60];
 
+function LocalApiToken() {
+  const [token, setToken] = useState<string | null>(null);
+
+  useEffect(() => {
+    commands.getLocalApiToken().then(setToken);
+  }, []);
+
+  const handleRegenerate = async () => {
+    if (window.confirm("Regenerate the API token? Existing clients will stop working.")) {
+      setToken(await commands.regenerateLocalApiToken());
+    }
+  };
+
+  return (
+    <div className="flex items-center gap-2">
+      <code className="max-w-48 truncate rounded border border-[var(--color-border)] bg-[var(--color-input-bg)] px-2 py-1 font-mono text-xs">
+        {token ?? "…"}
+      </code>
+      <Button variant="ghost" size="sm" onClick={handleRegenerate}>
+        Regenerate
+      </Button>
+    </div>
+  );
+}
+
+function ClockAdjust({ enabled }: { enabled: boolean }) {
+  const [busy, setBusy] = useState(false);
+  const [status, setStatus] = useState<string | null>(null);
+
+  const handleAdjust = async () => {
+    setBusy(true);
+    setStatus(null);
+    try {
+      const measured = await commands.measureClockError();
+      const offset = measured.offset_ms;
+      const direction = offset >= 0 ? "behind" : "ahead of";
+      const confirmed = window.confirm(
+        `Your clock is ${Math.abs(offset).toFixed(0)} ms ${direction} ${measured.server}. ` +
+          "Adjust the system clock now? This affects every app on this computer.",
+      );
+      if (!confirmed) return;
+      const result = await commands.adjustSystemClock(offset);
+      setStatus(`Adjusted by ${result.offset_ms.toFixed(0)} ms (${result.method})`);
+    } catch (e) {
+      setStatus(errorMessage(e));
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  return (
+    <div className="flex items-center gap-2">
+      {status && (
+        <span className="max-w-48 truncate text-xs text-[var(--color-text-secondary)]">
+          {status}
+        </span>
+      )}
+      <Button variant="ghost" size="sm" onClick={handleAdjust} disabled={!enabled || busy}>
+        Measure & Adjust
+      </Button>
+    </div>
+  );
+}
+
+function NotificationPermission() {
+  const [state, setState] = useState<string | null>(null);
+
+  const handleRequest = async () => {
+    try {
+      setState(await commands.requestNotificationPermission());
+    } catch (e) {
+      setState(errorMessage(e));
+    }
+  };
+
+  return (
+    <div className="flex items-center gap-2">
+      {state && (
+        <span className="max-w-48 truncate text-xs text-[var(--color-text-secondary)]">
+          {state}
+        </span>
+      )}
+      <Button variant="ghost" size="sm" onClick={handleRequest}>
+        Allow notifications
+      </Button>
+    </div>
+  );
+}
+
+function SchedulerPause() {
+  const [paused, setPaused] = useState<boolean | null>(null);
+  const [offline, setOffline] = useState(false);
+
+  useEffect(() => {
+    const apply = (s: SchedulerStatus) => {
+      setPaused(s.paused);
+      setOffline(s.offline);
+    };
+    commands.getSchedulerStatus().then(apply);
+    const unlisten = commands.onSchedulerState(apply);
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  const handleToggle = async () => {
+    const status = paused
+      ? await commands.resumeScheduler()
+      : await commands.pauseScheduler();
+    setPaused(status.paused);
+  };
+
+  return (
+    <div className="flex items-center gap-2">
+      {offline && !paused && (
+        <span className="text-xs text-[var(--color-warning)]">Offline · retrying</span>
+      )}
+      <Button variant="ghost" size="sm" onClick={handleToggle} disabled={paused === null}>
+        {paused ? "Resume" : "Pause"}
+      </Button>
+    </div>
+  );
+}
+
 export function SettingsPage() {
   const {
     settings,
@@ -44,7 +194,9 @@ export function SettingsPage() {
     updateField,
     saveSettings,
     resetToDefaults,
+    fieldErrors,
   } = useSettingsStore();
+  const { fetchServers, fetchReferenceServer } = useServerStore();
 
   useEffect(() => {
     return () => {
@@ -59,6 +211,64 @@ export function SettingsPage() {
     }
   };
 
+  const handleExport = async () => {
+    const passphrase = askExportPassphrase();
+    if (passphrase === null) return;
+    const date = new Date().toISOString().slice(0, 10);
+    const path = await save({
+      title: "Export Settings",
+      defaultPath: `ticketime_settings_${date}.json`,
+      filters: [{ name: "JSON", extensions: ["json"] }],
+    });
+    if (!path) return;
+    try {
+      await commands.exportSettings(path, passphrase);
+    } catch (e) {
+      window.alert(`Export failed: ${errorMessage(e)}`);
+    }
+  };
+
+  const handleFactoryReset = async () => {
+    const typed = window.prompt(
+      "This erases every server, sync, target and setting in all workspaces, " +
+        `plus recordings, hooks and plugins. Type "${commands.FACTORY_RESET_CONFIRMATION}" to confirm.`,
+    );
+    if (typed === null) return;
+    try {
+      await commands.factoryReset(typed);
+      window.location.reload();
+    } catch (e) {
+      window.alert(`Reset failed: ${errorMessage(e)}`);
+    }
+  };
+
+  const handleImport = async () => {
+    const path = await open({
+      title: "Import Settings",
+      multiple: false,
+      filters: [{ name: "JSON", extensions: ["json"] }],
+    });
+    if (typeof path !== "string") return;
+    if (!window.confirm("Replace current settings with the imported file?")) return;
+    try {
+      let summary;
+      try {
+        summary = await commands.importSettings(path);
+      } catch (e) {
+        if (!isAppError(e) || e.code !== "passphrase_required") throw e;
+        const passphrase = window.prompt("This file is encrypted. Enter its passphrase:");
+        if (passphrase === null) return;
+        summary = await commands.importSettings(path, passphrase);
+      }
+      await fetchServers();
+      window.alert(
+        `Imported settings: ${summary.servers_added} server(s) added, ${summary.servers_updated} updated.`,
+      );
+    } catch (e) {
+      window.alert(`Import failed: ${errorMessage(e)}`);
+    }
+  };
+
   return (
     <div className="flex-1 overflow-y-auto p-8">
       <div className="mx-auto max-w-3xl space-y-8">
@@ -75,6 +285,12 @@ export function SettingsPage() {
             )}
           </div>
           <div className="flex items-center gap-2">
+            <Button variant="ghost" size="sm" onClick={handleImport}>
+              Import
+            </Button>
+            <Button variant="ghost" size="sm" onClick={handleExport}>
+              Export
+            </Button>
             <Button variant="ghost" size="sm" onClick={handleReset}>
               Reset to Defaults
             </Button>
@@ -95,7 +311,11 @@ export function SettingsPage() {
           description="Basic application configuration"
           icon={Settings}
         >
-          <SettingsField label="Theme" description="Choose your preferred visual theme">
+          <SettingsField
+            label="Theme"
+            error={fieldErrors.theme}
+            description="Choose your preferred visual theme"
+          >
             <Select
               options={[
                 { value: "dark", label: "Dark Mode" },
@@ -114,9 +334,22 @@ export function SettingsPage() {
           description="Network and timing precision parameters"
           icon={RefreshCw}
         >
+          <SettingsField
+            label="Sync Profile"
+            error={fieldErrors.sync_profile}
+            description="Probe count, spacing, retries and timeout; servers can override it"
+          >
+            <SyncProfileSelect
+              value={settings.sync_profile}
+              onChange={(profile) => updateField("sync_profile", profile ?? "balanced")}
+              className="w-36"
+            />
+          </SettingsField>
+
           <SettingsField
             label="Min Request Interval"
-            description="Minimum time between sync requests"
+            error={fieldErrors.min_request_interval_ms}
+            description="Gap between probes under the Balanced profile; other profiles scale with it but never go faster"
           >
             <NumberInput
               value={settings.min_request_interval_ms}
@@ -129,17 +362,103 @@ export function SettingsPage() {
             />
           </SettingsField>
 
+          <SettingsField
+            label="Public Host Floor"
+            error={fieldErrors.public_host_floor_ms}
+            description="Lowest interval for servers not marked trusted; local hosts are exempt"
+          >
+            <NumberInput
+              value={settings.public_host_floor_ms}
+              min={100}
+              max={10000}
+              step={100}
+              unit="ms"
+              onChange={(e) => updateField("public_host_floor_ms", Number(e.target.value))}
+              className="w-36"
+            />
+          </SettingsField>
+
+          <SettingsField
+            label="Progress Updates"
+            error={fieldErrors.progress_event_limit}
+            description="Progress events per sync every 100 ms (0 = unlimited)"
+          >
+            <NumberInput
+              value={settings.progress_event_limit}
+              min={0}
+              max={100}
+              step={1}
+              onChange={(e) => updateField("progress_event_limit", Number(e.target.value))}
+              className="w-36"
+            />
+          </SettingsField>
+
+          <SettingsField
+            label="Stall Timeout"
+            error={fieldErrors.sync_stall_timeout_secs}
+            description="Stop a sync after this many seconds without progress"
+          >
+            <NumberInput
+              value={settings.sync_stall_timeout_secs}
+              min={15}
+              max={600}
+              step={5}
+              unit="s"
+              onChange={(e) => updateField("sync_stall_timeout_secs", Number(e.target.value))}
+              className="w-36"
+            />
+          </SettingsField>
+
+          <SettingsField
+            label="Sync Deadline"
+            error={fieldErrors.sync_deadline_secs}
+            description="Give up on a sync that runs longer than this, retries included"
+          >
+            <NumberInput
+              value={settings.sync_deadline_secs}
+              min={30}
+              max={1800}
+              step={30}
+              unit="s"
+              onChange={(e) => updateField("sync_deadline_secs", Number(e.target.value))}
+              className="w-36"
+            />
+          </SettingsField>
+
+          <SettingsField
+            label="Auto Re-sync"
+            error={fieldErrors.auto_resync_interval_minutes}
+            description="Re-sync servers whose offset is older than this (0 = off)"
+          >
+            <NumberInput
+              value={settings.auto_resync_interval_minutes}
+              min={0}
+              max={1440}
+              step={5}
+              unit="min"
+              onChange={(e) => updateField("auto_resync_interval_minutes", Number(e.target.value))}
+              className="w-36"
+            />
+          </SettingsField>
+
+          <SettingsField
+            label="Background Syncs"
+            description="Pause all scheduled traffic, e.g. on a metered connection"
+          >
+            <SchedulerPause />
+          </SettingsField>
+
           <SettingsField
             label="Health Threshold"
-            description="Offset deviation to trigger health warning"
+            error={fieldErrors.health_resync_threshold}
+            description="Resync a server whose health score drops below this (0 disables)"
           >
             <div className="w-44">
               <Slider
                 value={settings.health_resync_threshold}
-                min={10}
-                max={500}
-                step={10}
-                unit="ms"
+                min={0}
+                max={100}
+                step={5}
                 onChange={(e) => updateField("health_resync_threshold", Number(e.target.value))}
               />
             </div>
@@ -147,7 +466,8 @@ export function SettingsPage() {
 
           <SettingsField
             label="External Source"
-            description="External source for time synchronization"
+            error={fieldErrors.external_time_source}
+            description="Reference for checking and adjusting the local clock"
           >
             <Select
               options={EXTERNAL_SOURCE_OPTIONS}
@@ -156,6 +476,37 @@ export function SettingsPage() {
               className="w-36"
             />
           </SettingsField>
+
+          <SettingsField
+            label="Network Pre-check"
+            description="Probe the external time source first and postpone syncs on a degraded network"
+          >
+            <Toggle
+              checked={settings.network_precheck}
+              disabled={settings.external_time_source === "none"}
+              onChange={(e) => updateField("network_precheck", e.target.checked)}
+            />
+          </SettingsField>
+
+          <SettingsField
+            label="Captive Portal Check"
+            description="When a server's first answer looks off, ask connectivitycheck.gstatic.com whether a portal or proxy is answering"
+          >
+            <Toggle
+              checked={settings.captive_portal_check}
+              onChange={(e) => updateField("captive_portal_check", e.target.checked)}
+            />
+          </SettingsField>
+
+          <SettingsField
+            label="Record Sync Runs"
+            description="Save every probe of each sync so it can be replayed offline"
+          >
+            <Toggle
+              checked={settings.record_syncs}
+              onChange={(e) => updateField("record_syncs", e.target.checked)}
+            />
+          </SettingsField>
         </SettingsSection>
 
         {/* Display */}
@@ -176,6 +527,7 @@ export function SettingsPage() {
 
           <SettingsField
             label="Precision"
+            error={fieldErrors.millisecond_precision}
             description="Number of decimal places for seconds"
           >
             <Select
@@ -197,8 +549,22 @@ export function SettingsPage() {
             />
           </SettingsField>
 
+          <SettingsField
+            label="Time Format"
+            error={fieldErrors.time_format}
+            description="strftime pattern used by the tray, API stream and copied times"
+          >
+            <Input
+              value={settings.time_format}
+              onChange={(e) => updateField("time_format", e.target.value)}
+              placeholder="%H:%M:%S"
+              className="w-36 px-3 py-2 font-mono"
+            />
+          </SettingsField>
+
           <SettingsField
             label="Overlay Opacity"
+            error={fieldErrors.overlay_opacity}
             description="Transparency of the floating clock overlay"
           >
             <div className="w-44">
@@ -232,6 +598,22 @@ export function SettingsPage() {
               onChange={(e) => updateField("overlay_always_on_top", e.target.checked)}
             />
           </SettingsField>
+
+          <SettingsField
+            label="Countdown Refresh"
+            error={fieldErrors.countdown_tick_ms}
+            description="How often the overlay countdown repaints"
+          >
+            <NumberInput
+              value={settings.countdown_tick_ms}
+              min={16}
+              max={1000}
+              step={10}
+              unit="ms"
+              onChange={(e) => updateField("countdown_tick_ms", Number(e.target.value))}
+              className="w-36"
+            />
+          </SettingsField>
         </SettingsSection>
 
         {/* Alerts */}
@@ -242,7 +624,8 @@ export function SettingsPage() {
         >
           <SettingsField
             label="Alarm Intervals"
-            description="Seconds between repetitive drift warnings"
+            error={fieldErrors.alert_intervals}
+            description="Minutes before each armed target to send a notification"
           >
             <div className="flex flex-wrap items-center gap-2">
               {ALARM_INTERVALS.map((interval) => (
@@ -262,7 +645,7 @@ export function SettingsPage() {
                       : "bg-[var(--color-input-bg)] text-[var(--color-text-secondary)] border-[var(--color-border)] hover:border-[var(--color-accent)] hover:text-[var(--color-accent)]"
                   )}
                 >
-                  {interval}s
+                  {interval}m
                 </button>
               ))}
             </div>
@@ -270,18 +653,43 @@ export function SettingsPage() {
 
           <SettingsField
             label="Alert Method"
-            description="How to notify when sync fails or drift occurs"
+            error={fieldErrors.alert_method}
+            description="How target alerts get your attention"
           >
             <Select
               options={ALERT_METHOD_OPTIONS}
               value={settings.alert_method}
-              onChange={(e) => updateField("alert_method", e.target.value as "sound" | "visual" | "both")}
+              onChange={(e) => updateField("alert_method", e.target.value as AlertMethod)}
+              className="w-36"
+            />
+          </SettingsField>
+
+          <SettingsField
+            label="Notifications"
+            description="Required for target alerts while the app is in the background"
+          >
+            <NotificationPermission />
+          </SettingsField>
+
+          <SettingsField
+            label="Keep Connection Warm"
+            error={fieldErrors.keep_warm_minutes}
+            description="Ping the server of an armed target in the minutes before it, so the first probe skips the handshake (0 = off)"
+          >
+            <NumberInput
+              value={settings.keep_warm_minutes}
+              min={0}
+              max={60}
+              step={1}
+              unit="min"
+              onChange={(e) => updateField("keep_warm_minutes", Number(e.target.value))}
               className="w-36"
             />
           </SettingsField>
 
           <SettingsField
             label="Drift Threshold"
+            error={fieldErrors.drift_warning_threshold_ms}
             description="Clock drift threshold for alerts"
           >
             <NumberInput
@@ -294,6 +702,121 @@ export function SettingsPage() {
               className="w-36"
             />
           </SettingsField>
+
+          <SettingsField
+            label="Extrapolate Drift"
+            description="Carry offsets forward along each server's measured drift since its last sync"
+          >
+            <Toggle
+              checked={settings.extrapolate_drift}
+              onChange={(e) => updateField("extrapolate_drift", e.target.checked)}
+            />
+          </SettingsField>
+
+          <SettingsField
+            label="Anomalous Syncs"
+            description="Notify when a sync's offset is far off the server's recent history"
+          >
+            <Toggle
+              checked={settings.notify_anomalies}
+              onChange={(e) => updateField("notify_anomalies", e.target.checked)}
+            />
+          </SettingsField>
+        </SettingsSection>
+
+        {/* Local API */}
+        <SettingsSection
+          title="Local API"
+          description="Expose corrected time to scripts on localhost"
+          icon={Globe}
+        >
+          <SettingsField
+            label="Enable HTTP API"
+            description="Serve /servers, /time/{id}, /sync/{id} and the /ws stream on 127.0.0.1"
+          >
+            <Toggle
+              checked={settings.local_api_enabled}
+              onChange={(e) => updateField("local_api_enabled", e.target.checked)}
+            />
+          </SettingsField>
+
+          <SettingsField
+            label="Port"
+            error={fieldErrors.local_api_port}
+            description="Local port the API listens on"
+          >
+            <NumberInput
+              value={settings.local_api_port}
+              min={1024}
+              max={65535}
+              step={1}
+              onChange={(e) => updateField("local_api_port", Number(e.target.value))}
+              className="w-36"
+            />
+          </SettingsField>
+
+          <SettingsField
+            label="Access Token"
+            description="Send as a Bearer token or ?token= query parameter"
+          >
+            <LocalApiToken />
+          </SettingsField>
+        </SettingsSection>
+
+        {/* LAN Time */}
+        <SettingsSection
+          title="LAN Time"
+          description="Let other devices on the network follow the corrected clock"
+          icon={Wifi}
+        >
+          <SettingsField
+            label="Serve Time on LAN"
+            description="Answer SNTP requests with the primary server's corrected time, advertised over mDNS"
+          >
+            <Toggle
+              checked={settings.lan_time_enabled}
+              onChange={(e) => updateField("lan_time_enabled", e.target.checked)}
+            />
+          </SettingsField>
+
+          <SettingsField
+            label="Port"
+            error={fieldErrors.lan_time_port}
+            description="UDP port other devices query"
+          >
+            <NumberInput
+              value={settings.lan_time_port}
+              min={1024}
+              max={65535}
+              step={1}
+              onChange={(e) => updateField("lan_time_port", Number(e.target.value))}
+              className="w-36"
+            />
+          </SettingsField>
+        </SettingsSection>
+
+        {/* System Clock */}
+        <SettingsSection
+          title="System Clock"
+          description="Correct this computer's clock against NTP"
+          icon={Clock}
+        >
+          <SettingsField
+            label="Allow Clock Adjustment"
+            description="Requires administrator privileges; save before adjusting"
+          >
+            <Toggle
+              checked={settings.allow_clock_adjustment}
+              onChange={(e) => updateField("allow_clock_adjustment", e.target.checked)}
+            />
+          </SettingsField>
+
+          <SettingsField
+            label="Adjust Now"
+            description="Measure the clock error, confirm, then slew or step the clock"
+          >
+            <ClockAdjust enabled={settings.allow_clock_adjustment && !dirty} />
+          </SettingsField>
         </SettingsSection>
 
         {/* Hotkeys (disabled) */}
@@ -315,6 +838,82 @@ export function SettingsPage() {
           </SettingsField>
         </SettingsSection>
 
+        {/* Share */}
+        <SettingsSection
+          title="Share Setup"
+          description="Move servers and targets to another device with a signed payload"
+          icon={Share2}
+        >
+          <ShareSetup onImported={fetchServers} />
+        </SettingsSection>
+
+        {/* Scripting */}
+        <SettingsSection
+          title="Scripting Hooks"
+          description="Rhai functions run after syncs, at target T-0 and on large offset jumps"
+          icon={Code}
+        >
+          <HookScript />
+        </SettingsSection>
+
+        {/* Extractor plugins */}
+        <SettingsSection
+          title="Extractor Plugins"
+          description="Sandboxed WASM time extractors, picked per server like the built-in ones"
+          icon={Puzzle}
+        >
+          <ExtractorPlugins />
+        </SettingsSection>
+
+        {/* Sync recordings */}
+        <SettingsSection
+          title="Sync Recordings"
+          description="Recorded sync runs, replayed through the engine without the network"
+          icon={Disc}
+        >
+          <SyncRecordings />
+        </SettingsSection>
+
+        {/* Trash */}
+        <SettingsSection
+          title="Trash"
+          description="Deleted servers, restorable with their history for a while"
+          icon={ArchiveRestore}
+        >
+          <SettingsField
+            label="Keep Deleted Servers"
+            error={fieldErrors.trash_retention_days}
+            description="Days before a deleted server and its history are purged"
+          >
+            <NumberInput
+              value={settings.trash_retention_days}
+              min={1}
+              max={90}
+              step={1}
+              unit="days"
+              onChange={(e) => updateField("trash_retention_days", Number(e.target.value))}
+              className="w-36"
+            />
+          </SettingsField>
+          <Trash onRestored={() => Promise.all([fetchServers(), fetchReferenceServer()])} />
+        </SettingsSection>
+
+        {/* Factory reset */}
+        <SettingsSection
+          title="Factory Reset"
+          description="Erase all data before handing this machine to someone else"
+          icon={Trash2}
+        >
+          <SettingsField
+            label="Erase Everything"
+            description="Servers, history, targets, settings and the API token in every workspace"
+          >
+            <Button variant="danger" size="sm" onClick={handleFactoryReset}>
+              Factory Reset
+            </Button>
+          </SettingsField>
+        </SettingsSection>
+
         {/* Advanced (disabled) */}
         <SettingsSection
           title="Advanced"
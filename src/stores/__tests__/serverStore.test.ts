@@ -1,11 +1,16 @@
 import { describe, it, expect, vi, beforeEach } from "vitest";
-import { useServerStore } from "@/stores/serverStore";
+import { offsetVsReference, useServerStore } from "@/stores/serverStore";
 import type { Server } from "@/types/server";
 
 vi.mock("@/lib/commands", () => ({
   listServers: vi.fn(),
+  getDashboard: vi.fn(),
   addServer: vi.fn(),
+  addDemoServer: vi.fn(),
+  addReferenceSource: vi.fn(),
   deleteServer: vi.fn(),
+  getReferenceServer: vi.fn(),
+  setReferenceServer: vi.fn(),
   startSync: vi.fn(),
   cancelSync: vi.fn(),
   getSettings: vi.fn(),
@@ -24,11 +29,38 @@ const makeServer = (id: number, url = `https://server${id}.example.com`): Server
   created_at: "2024-01-01T00:00:00Z",
   status: "idle",
   extractor_type: "date_header",
+  probe_method: "HEAD",
+  domain_preset: null,
+  sync_profile: null,
+  fingerprint: null,
+  asymmetry: null,
+  sync_on_launch: false,
+  health_score: null,
+  drift: null,
+  min_request_interval_ms: null,
+  trusted: false,
+  user_agent: null,
+  transport: {
+    tcp_nodelay: true,
+    connect_timeout_ms: null,
+    ip_family: "auto",
+    accept_invalid_certs: false,
+  },
+  timezone: null,
+  timezone_guessed: false,
 });
 
 beforeEach(() => {
   vi.clearAllMocks();
-  useServerStore.setState({ servers: [], loading: false, error: null });
+  useServerStore.setState({
+    servers: [],
+    referenceServerId: null,
+    nextSyncAt: {},
+    upcomingTargets: [],
+    targetConfidence: {},
+    loading: false,
+    error: null,
+  });
 });
 
 describe("serverStore", () => {
@@ -69,6 +101,49 @@ describe("serverStore", () => {
     });
   });
 
+  describe("fetchDashboard", () => {
+    it("fills servers, next syncs and upcoming targets", async () => {
+      const target = {
+        id: 5,
+        server_id: 1,
+        label: null,
+        target_at: "2024-06-01T12:00:00Z",
+        armed: true,
+        created_at: "2024-01-01T00:00:00Z",
+        alert_method: null,
+      };
+      vi.mocked(commands.getDashboard).mockResolvedValue({
+        servers: [
+          {
+            server: makeServer(1),
+            offset_age_ms: null,
+            stale: true,
+            next_sync_at: "2024-06-01T11:30:00Z",
+          },
+        ],
+        upcoming_targets: [{ target, remaining_ms: 60_000 }],
+        scheduler_paused: false,
+        generated_at: "2024-06-01T11:00:00Z",
+      });
+
+      await useServerStore.getState().fetchDashboard();
+
+      const state = useServerStore.getState();
+      expect(state.servers).toEqual([makeServer(1)]);
+      expect(state.nextSyncAt[1]).toBe("2024-06-01T11:30:00Z");
+      expect(state.upcomingTargets[0].target.id).toBe(5);
+      expect(state.loading).toBe(false);
+    });
+
+    it("sets error on failure", async () => {
+      vi.mocked(commands.getDashboard).mockRejectedValue(new Error("db locked"));
+
+      await useServerStore.getState().fetchDashboard();
+
+      expect(useServerStore.getState().error).toContain("db locked");
+    });
+  });
+
   describe("addServer", () => {
     it("appends the new server on success", async () => {
       const existing = makeServer(1);
@@ -93,6 +168,42 @@ describe("serverStore", () => {
     });
   });
 
+  describe("addReferenceSource", () => {
+    it("appends the source once and picks up the reference server", async () => {
+      const server = makeServer(1, "https://time.gov/actualtime.cgi");
+      vi.mocked(commands.addReferenceSource).mockResolvedValue(server);
+      vi.mocked(commands.getReferenceServer).mockResolvedValue(1);
+
+      await useServerStore.getState().addReferenceSource("nist");
+      await useServerStore.getState().addReferenceSource("nist");
+
+      expect(useServerStore.getState().servers).toEqual([server]);
+      expect(useServerStore.getState().referenceServerId).toBe(1);
+    });
+  });
+
+  describe("addDemoServer", () => {
+    const demo = { offset_ms: 1500, rtt_ms: 50, jitter_ms: 5 };
+
+    it("appends the demo server once", async () => {
+      const server = makeServer(1, "demo://server?offset_ms=1500&rtt_ms=50&jitter_ms=5");
+      vi.mocked(commands.addDemoServer).mockResolvedValue(server);
+
+      await useServerStore.getState().addDemoServer(demo);
+      await useServerStore.getState().addDemoServer(demo);
+
+      expect(useServerStore.getState().servers).toEqual([server]);
+    });
+
+    it("sets error and re-throws on failure", async () => {
+      vi.mocked(commands.addDemoServer).mockRejectedValue(new Error("jitter_ms"));
+
+      await expect(useServerStore.getState().addDemoServer(demo)).rejects.toThrow("jitter_ms");
+
+      expect(useServerStore.getState().error).toContain("jitter_ms");
+    });
+  });
+
   describe("removeServer", () => {
     it("removes the server by id on success", async () => {
       useServerStore.setState({ servers: [makeServer(1), makeServer(2)] });
@@ -148,4 +259,85 @@ describe("serverStore", () => {
       expect(server.status).toBe("syncing");
     });
   });
+
+  describe("applyStatusChange", () => {
+    it("updates only the named server", () => {
+      useServerStore.setState({ servers: [makeServer(1), makeServer(2)] });
+
+      useServerStore.getState().applyStatusChange({ server_id: 2, status: "syncing" });
+
+      const { servers } = useServerStore.getState();
+      expect(servers[0].status).toBe("idle");
+      expect(servers[1].status).toBe("syncing");
+    });
+  });
+
+  describe("applyTargetConfidence", () => {
+    const confidence = (kind: "confirmed" | "adjusted", offset_ms: number) => ({
+      target_id: 7,
+      server_id: 1,
+      seconds_before: 5,
+      confidence: kind,
+      previous_offset_ms: 40,
+      offset_ms,
+      recheck: {
+        offset_ms,
+        moved_ms: offset_ms - 40,
+        uncertainty_ms: 10,
+        probes: 3,
+        checked_at: "2024-06-01T12:00:00Z",
+      },
+      error: null,
+    });
+
+    it("records the outcome and moves an adjusted offset", () => {
+      useServerStore.setState({ servers: [{ ...makeServer(1), offset_ms: 40 }, makeServer(2)] });
+
+      useServerStore.getState().applyTargetConfidence(confidence("adjusted", 55));
+
+      const { servers, targetConfidence } = useServerStore.getState();
+      expect(targetConfidence[7].confidence).toBe("adjusted");
+      expect(servers[0].offset_ms).toBe(55);
+      expect(servers[0].last_sync_at).toBe("2024-06-01T12:00:00Z");
+      expect(servers[1].offset_ms).toBeNull();
+    });
+
+    it("leaves a confirmed offset alone", () => {
+      useServerStore.setState({ servers: [{ ...makeServer(1), offset_ms: 40 }] });
+
+      useServerStore.getState().applyTargetConfidence(confidence("confirmed", 40));
+
+      expect(useServerStore.getState().servers[0].last_sync_at).toBeNull();
+    });
+  });
+
+  describe("reference server", () => {
+    it("stores the reference after the backend accepts it", async () => {
+      vi.mocked(commands.setReferenceServer).mockResolvedValue(undefined);
+
+      await useServerStore.getState().setReferenceServer(2);
+
+      expect(commands.setReferenceServer).toHaveBeenCalledWith(2);
+      expect(useServerStore.getState().referenceServerId).toBe(2);
+    });
+
+    it("clears the reference when its server is removed", async () => {
+      useServerStore.setState({ servers: [makeServer(1)], referenceServerId: 1 });
+      vi.mocked(commands.deleteServer).mockResolvedValue(undefined);
+
+      await useServerStore.getState().removeServer(1);
+
+      expect(useServerStore.getState().referenceServerId).toBeNull();
+    });
+
+    it("reports offsets against the reference clock", () => {
+      const reference = { ...makeServer(1), offset_ms: 800 };
+      const other = { ...makeServer(2), offset_ms: 825 };
+      const servers = [reference, other, makeServer(3)];
+
+      expect(offsetVsReference(other, servers, 1)).toBe(25);
+      expect(offsetVsReference(makeServer(3), servers, 1)).toBeNull();
+      expect(offsetVsReference(other, servers, null)).toBeNull();
+    });
+  });
 });
@@ -12,6 +12,7 @@ vi.mock("@/lib/commands", () => ({
   getSettings: vi.fn(),
   updateSettings: vi.fn(),
   getSyncHistory: vi.fn(),
+  onSettingsChanged: vi.fn(),
 }));
 
 import * as commands from "@/lib/commands";
@@ -24,6 +25,7 @@ beforeEach(() => {
     savedSettings: { ...DEFAULT_SETTINGS },
     loading: false,
     error: null,
+    fieldErrors: {},
     dirty: false,
   });
   useThemeStore.setState({ theme: "dark" });
@@ -145,6 +147,24 @@ describe("settingsStore", () => {
       expect(useSettingsStore.getState().error).toContain("save failed");
     });
 
+    it("maps validation errors to fieldErrors", async () => {
+      vi.mocked(commands.updateSettings).mockRejectedValue({
+        code: "validation",
+        retryable: false,
+        message: "invalid settings: overlay_opacity must be between 10 and 100 (got 255)",
+        fields: [{ field: "overlay_opacity", message: "must be between 10 and 100 (got 255)" }],
+      });
+      useSettingsStore.setState({ dirty: true });
+
+      await useSettingsStore.getState().saveSettings();
+
+      expect(useSettingsStore.getState().fieldErrors.overlay_opacity).toBe(
+        "must be between 10 and 100 (got 255)",
+      );
+      expect(useSettingsStore.getState().error).toContain("invalid settings");
+      expect(useSettingsStore.getState().dirty).toBe(true);
+    });
+
     it("calls updateSettings with current settings", async () => {
       vi.mocked(commands.updateSettings).mockResolvedValue(undefined);
       const settings = { ...DEFAULT_SETTINGS, overlay_opacity: 60 };
@@ -214,4 +234,28 @@ describe("settingsStore", () => {
       expect(useSettingsStore.getState().error).toContain("reset failed");
     });
   });
+
+  describe("applySaved", () => {
+    it("replaces settings when there are no local edits", () => {
+      const incoming = { ...DEFAULT_SETTINGS, overlay_opacity: 40 };
+      useSettingsStore.getState().applySaved(incoming);
+      expect(useSettingsStore.getState().settings.overlay_opacity).toBe(40);
+      expect(useSettingsStore.getState().savedSettings.overlay_opacity).toBe(40);
+    });
+
+    it("keeps unsaved edits but updates savedSettings", () => {
+      useSettingsStore.getState().updateField("overlay_opacity", 50);
+      const incoming = { ...DEFAULT_SETTINGS, overlay_opacity: 40 };
+      useSettingsStore.getState().applySaved(incoming);
+      expect(useSettingsStore.getState().settings.overlay_opacity).toBe(50);
+      expect(useSettingsStore.getState().savedSettings.overlay_opacity).toBe(40);
+      expect(useSettingsStore.getState().dirty).toBe(true);
+    });
+
+    it("applies the incoming theme", () => {
+      const setTheme = vi.spyOn(useThemeStore.getState(), "setTheme");
+      useSettingsStore.getState().applySaved({ ...DEFAULT_SETTINGS, theme: "light" });
+      expect(setTheme).toHaveBeenCalledWith("light");
+    });
+  });
 });
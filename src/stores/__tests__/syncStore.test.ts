@@ -1,13 +1,18 @@
 import { describe, it, expect, vi, beforeEach } from "vitest";
 import { useSyncStore } from "@/stores/syncStore";
 import { useServerStore } from "@/stores/serverStore";
-import type { SyncProgressPayload, SyncResult } from "@/types/server";
+import type {
+  SyncErrorPayload,
+  SyncProgressPayload,
+  SyncResult,
+} from "@/types/server";
 
 vi.mock("@/lib/commands", () => ({
   listServers: vi.fn(),
   addServer: vi.fn(),
   deleteServer: vi.fn(),
   startSync: vi.fn(),
+  onSyncEvent: vi.fn(),
   cancelSync: vi.fn(),
   getSettings: vi.fn(),
   updateSettings: vi.fn(),
@@ -22,6 +27,7 @@ const makeProgress = (serverId: number): SyncProgressPayload => ({
   progress_percent: 50,
   phase_data: {},
   elapsed_ms: 100,
+  eta_ms: 20_000,
 });
 
 const makeResult = (serverId: number): SyncResult => ({
@@ -29,16 +35,38 @@ const makeResult = (serverId: number): SyncResult => ({
   whole_second_offset: 0,
   subsecond_offset: 5,
   total_offset_ms: 5,
-  latency_profile: { min: 10, q1: 12, median: 15, mean: 15, q3: 18, max: 25 },
+  latency_profile: { min: 10, q1: 12, median: 15, mean: 15, q3: 18, max: 25, breakdown: null },
   verified: true,
   synced_at: new Date().toISOString(),
   duration_ms: 500,
   phase_reached: "complete",
+  fingerprint: null,
+  asymmetry: null,
+  response: null,
+  id: 1,
+  timeline: [],
+  note: null,
+  anomalous: false,
+  clock_issue: null,
+  network: null,
+  grade: null,
+  precision_ms: null,
+});
+
+const makeError = (serverId: number): SyncErrorPayload => ({
+  server_id: serverId,
+  error: { code: "network", message: "timeout", retryable: true },
+  phase: "binary_search",
+  phase_attempts: 4,
+  total_attempts: 17,
+  latency_profile: null,
+  partial_offset_ms: null,
+  elapsed_ms: 3200,
 });
 
 beforeEach(() => {
   vi.clearAllMocks();
-  useSyncStore.setState({ activeSyncs: {}, syncResults: {} });
+  useSyncStore.setState({ activeSyncs: {}, syncResults: {}, syncErrors: {} });
   useServerStore.setState({ servers: [], loading: false, error: null });
 });
 
@@ -112,6 +140,25 @@ describe("syncStore", () => {
             created_at: "2024-01-01T00:00:00Z",
             status: "idle",
             extractor_type: "date_header",
+            probe_method: "HEAD",
+            domain_preset: null,
+            sync_profile: null,
+            fingerprint: null,
+            asymmetry: null,
+            sync_on_launch: false,
+            health_score: null,
+            drift: null,
+            min_request_interval_ms: null,
+            trusted: false,
+            user_agent: null,
+            transport: {
+              tcp_nodelay: true,
+              connect_timeout_ms: null,
+              ip_family: "auto",
+              accept_invalid_certs: false,
+            },
+            timezone: null,
+            timezone_guessed: false,
           },
         ],
       });
@@ -158,12 +205,46 @@ describe("syncStore", () => {
       useSyncStore.setState({ activeSyncs: { 1: makeProgress(1) } });
 
       vi.mocked(commands.startSync).mockImplementation(async (_id, cb) => {
-        cb({ event: "Error", data: { server_id: 1, error: "timeout" } });
+        cb({ event: "Error", data: makeError(1) });
       });
 
       await useSyncStore.getState().startSync(1);
 
       expect(useSyncStore.getState().activeSyncs).not.toHaveProperty("1");
     });
+
+    it("records the failure context on Error event", async () => {
+      const error = makeError(1);
+      vi.mocked(commands.startSync).mockImplementation(async (_id, cb) => {
+        cb({ event: "Error", data: error });
+      });
+
+      await useSyncStore.getState().startSync(1);
+
+      expect(useSyncStore.getState().syncErrors[1]).toEqual(error);
+    });
+
+    it("clears the previous failure when a new sync starts", async () => {
+      useSyncStore.setState({ syncErrors: { 1: makeError(1) } });
+      vi.mocked(commands.startSync).mockResolvedValue(undefined);
+
+      await useSyncStore.getState().startSync(1);
+
+      expect(useSyncStore.getState().syncErrors).not.toHaveProperty("1");
+    });
+  });
+
+  describe("handleSyncEvent", () => {
+    it("tracks background syncs by the payload server id", () => {
+      useSyncStore.getState().handleSyncEvent({ event: "Progress", data: makeProgress(7) });
+      expect(useSyncStore.getState().isSyncing(7)).toBe(true);
+
+      const result = makeResult(7);
+      useSyncStore
+        .getState()
+        .handleSyncEvent({ event: "Complete", data: { server_id: 7, result } });
+      expect(useSyncStore.getState().isSyncing(7)).toBe(false);
+      expect(useSyncStore.getState().getLatestResult(7)).toEqual(result);
+    });
   });
 });
@@ -1,24 +1,52 @@
 import { create } from "zustand";
-import type { Server, ServerStatus } from "@/types/server";
+import type { TargetConfidence } from "@/types/app";
+import type {
+  BulkAddEntry,
+  DemoServer,
+  Server,
+  ServerStatus,
+  ServerStatusChange,
+  UpcomingTarget,
+} from "@/types/server";
 import * as commands from "@/lib/commands";
+import { errorMessage } from "@/lib/utils";
 
 interface ServerStore {
   servers: Server[];
+  // Offsets are shown against this server's clock instead of the local one.
+  referenceServerId: number | null;
+  // From the last dashboard fetch; keyed by server id.
+  nextSyncAt: Record<number, string | null>;
+  upcomingTargets: UpcomingTarget[];
+  // Latest pre-target recheck; keyed by target id.
+  targetConfidence: Record<number, TargetConfidence>;
   loading: boolean;
   error: string | null;
   fetchServers: () => Promise<void>;
+  fetchDashboard: () => Promise<void>;
   addServer: (url: string) => Promise<void>;
+  addServersFromText: (text: string) => Promise<BulkAddEntry[]>;
+  addDemoServer: (demo: DemoServer) => Promise<Server>;
+  addReferenceSource: (kind: string) => Promise<Server>;
   removeServer: (id: number) => Promise<void>;
+  fetchReferenceServer: () => Promise<void>;
+  setReferenceServer: (id: number | null) => Promise<void>;
   updateServerFromSync: (
     id: number,
     offsetMs: number | null,
     lastSyncAt: string | null,
     status: ServerStatus,
   ) => void;
+  applyStatusChange: (change: ServerStatusChange) => void;
+  applyTargetConfidence: (confidence: TargetConfidence) => void;
 }
 
 export const useServerStore = create<ServerStore>((set, get) => ({
   servers: [],
+  referenceServerId: null,
+  nextSyncAt: {},
+  upcomingTargets: [],
+  targetConfidence: {},
   loading: false,
   error: null,
 
@@ -28,7 +56,24 @@ export const useServerStore = create<ServerStore>((set, get) => ({
       const servers = await commands.listServers();
       set({ servers, loading: false });
     } catch (e) {
-      set({ error: String(e), loading: false });
+      set({ error: errorMessage(e), loading: false });
+    }
+  },
+
+  fetchDashboard: async () => {
+    set({ loading: true, error: null });
+    try {
+      const dashboard = await commands.getDashboard();
+      set({
+        servers: dashboard.servers.map((d) => d.server),
+        nextSyncAt: Object.fromEntries(
+          dashboard.servers.map((d) => [d.server.id, d.next_sync_at]),
+        ),
+        upcomingTargets: dashboard.upcoming_targets,
+        loading: false,
+      });
+    } catch (e) {
+      set({ error: errorMessage(e), loading: false });
     }
   },
 
@@ -38,7 +83,50 @@ export const useServerStore = create<ServerStore>((set, get) => ({
       const server = await commands.addServer(url);
       set({ servers: [...get().servers, server] });
     } catch (e) {
-      set({ error: String(e) });
+      set({ error: errorMessage(e) });
+      throw e;
+    }
+  },
+
+  addServersFromText: async (text: string) => {
+    set({ error: null });
+    try {
+      const report = await commands.addServersFromText(text);
+      if (report.some((e) => e.status === "added")) {
+        await get().fetchServers();
+      }
+      return report;
+    } catch (e) {
+      set({ error: errorMessage(e) });
+      throw e;
+    }
+  },
+
+  addDemoServer: async (demo: DemoServer) => {
+    set({ error: null });
+    try {
+      const server = await commands.addDemoServer(demo);
+      if (!get().servers.some((s) => s.id === server.id)) {
+        set({ servers: [...get().servers, server] });
+      }
+      return server;
+    } catch (e) {
+      set({ error: errorMessage(e) });
+      throw e;
+    }
+  },
+
+  addReferenceSource: async (kind: string) => {
+    set({ error: null });
+    try {
+      const server = await commands.addReferenceSource(kind);
+      if (!get().servers.some((s) => s.id === server.id)) {
+        set({ servers: [...get().servers, server] });
+      }
+      set({ referenceServerId: await commands.getReferenceServer() });
+      return server;
+    } catch (e) {
+      set({ error: errorMessage(e) });
       throw e;
     }
   },
@@ -48,8 +136,27 @@ export const useServerStore = create<ServerStore>((set, get) => ({
     try {
       await commands.deleteServer(id);
       set({ servers: get().servers.filter((s) => s.id !== id) });
+      if (get().referenceServerId === id) set({ referenceServerId: null });
+    } catch (e) {
+      set({ error: errorMessage(e) });
+    }
+  },
+
+  fetchReferenceServer: async () => {
+    try {
+      set({ referenceServerId: await commands.getReferenceServer() });
+    } catch (e) {
+      set({ error: errorMessage(e) });
+    }
+  },
+
+  setReferenceServer: async (id: number | null) => {
+    set({ error: null });
+    try {
+      await commands.setReferenceServer(id);
+      set({ referenceServerId: id });
     } catch (e) {
-      set({ error: String(e) });
+      set({ error: errorMessage(e) });
     }
   },
 
@@ -62,4 +169,40 @@ export const useServerStore = create<ServerStore>((set, get) => ({
       ),
     });
   },
+
+  applyStatusChange: ({ server_id, status }) => {
+    set({
+      servers: get().servers.map((s) => (s.id === server_id ? { ...s, status } : s)),
+    });
+  },
+
+  applyTargetConfidence: (confidence) => {
+    const { servers, targetConfidence } = get();
+    const checkedAt = confidence.recheck?.checked_at;
+    set({
+      targetConfidence: { ...targetConfidence, [confidence.target_id]: confidence },
+      servers:
+        confidence.confidence === "adjusted" && checkedAt
+          ? servers.map((s) =>
+              s.id === confidence.server_id
+                ? { ...s, offset_ms: confidence.offset_ms, last_sync_at: checkedAt }
+                : s,
+            )
+          : servers,
+    });
+  },
 }));
+
+// `server`'s offset against the reference server, or null when there is no
+// reference or either side has not been synced.
+export function offsetVsReference(
+  server: Server,
+  servers: Server[],
+  referenceId: number | null,
+): number | null {
+  const reference = servers.find((s) => s.id === referenceId);
+  if (!reference || reference.offset_ms === null || server.offset_ms === null) {
+    return null;
+  }
+  return server.offset_ms - reference.offset_ms;
+}
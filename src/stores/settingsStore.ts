@@ -1,7 +1,10 @@
 import { create } from "zustand";
+import type { UnlistenFn } from "@tauri-apps/api/event";
 import type { Settings } from "@/types/settings";
 import { DEFAULT_SETTINGS } from "@/types/settings";
-import { getSettings, updateSettings } from "@/lib/commands";
+import type { AppError } from "@/types/app";
+import { errorMessage, isAppError } from "@/lib/utils";
+import { getSettings, onSettingsChanged, updateSettings } from "@/lib/commands";
 import { useThemeStore } from "@/stores/themeStore";
 
 interface SettingsStore {
@@ -9,12 +12,19 @@ interface SettingsStore {
   savedSettings: Settings;
   loading: boolean;
   error: string | null;
+  fieldErrors: Partial<Record<keyof Settings, string>>;
   dirty: boolean;
   fetchSettings: () => Promise<void>;
   updateField: <K extends keyof Settings>(key: K, value: Settings[K]) => void;
   saveSettings: () => Promise<void>;
   resetToDefaults: () => Promise<void>;
   revertUnsaved: () => void;
+  applySaved: (settings: Settings) => void;
+  listenForChanges: () => Promise<UnlistenFn>;
+}
+
+function fieldErrorMap(e: AppError): SettingsStore["fieldErrors"] {
+  return Object.fromEntries((e.fields ?? []).map((f) => [f.field, f.message]));
 }
 
 export const useSettingsStore = create<SettingsStore>((set, get) => ({
@@ -22,6 +32,7 @@ export const useSettingsStore = create<SettingsStore>((set, get) => ({
   savedSettings: { ...DEFAULT_SETTINGS },
   loading: false,
   error: null,
+  fieldErrors: {},
   dirty: false,
 
   fetchSettings: async () => {
@@ -40,7 +51,7 @@ export const useSettingsStore = create<SettingsStore>((set, get) => ({
       useThemeStore.getState().setTheme(settings.theme);
       set({ settings, savedSettings: { ...settings }, loading: false });
     } catch (e) {
-      set({ error: String(e), loading: false });
+      set({ error: errorMessage(e), loading: false });
     }
   },
 
@@ -55,13 +66,16 @@ export const useSettingsStore = create<SettingsStore>((set, get) => ({
 
   saveSettings: async () => {
     const { settings } = get();
-    set({ error: null });
+    set({ error: null, fieldErrors: {} });
     try {
       await updateSettings(settings);
       set({ dirty: false, savedSettings: { ...settings } });
       useThemeStore.getState().setTheme(settings.theme);
     } catch (e) {
-      set({ error: String(e) });
+      set({
+        error: errorMessage(e),
+        fieldErrors: isAppError(e) ? fieldErrorMap(e) : {},
+      });
     }
   },
 
@@ -76,7 +90,7 @@ export const useSettingsStore = create<SettingsStore>((set, get) => ({
       });
       useThemeStore.getState().setTheme(DEFAULT_SETTINGS.theme);
     } catch (e) {
-      set({ error: String(e) });
+      set({ error: errorMessage(e) });
     }
   },
 
@@ -85,6 +99,19 @@ export const useSettingsStore = create<SettingsStore>((set, get) => ({
     if (settings.theme !== savedSettings.theme) {
       useThemeStore.getState().setTheme(savedSettings.theme);
     }
-    set({ settings: { ...savedSettings }, dirty: false });
+    set({ settings: { ...savedSettings }, dirty: false, fieldErrors: {} });
   },
+
+  // Settings saved elsewhere (another window, an import). Unsaved local
+  // edits are kept; they now diff against the new saved copy.
+  applySaved: (settings: Settings) => {
+    if (get().dirty) {
+      set({ savedSettings: { ...settings } });
+      return;
+    }
+    useThemeStore.getState().setTheme(settings.theme);
+    set({ settings: { ...settings }, savedSettings: { ...settings } });
+  },
+
+  listenForChanges: () => onSettingsChanged(get().applySaved),
 }));
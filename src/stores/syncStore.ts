@@ -1,12 +1,22 @@
 import { create } from "zustand";
-import type { SyncProgressPayload, SyncResult } from "@/types/server";
+import type { UnlistenFn } from "@tauri-apps/api/event";
+import type {
+  SyncErrorPayload,
+  SyncEvent,
+  SyncProgressPayload,
+  SyncResult,
+} from "@/types/server";
 import * as commands from "@/lib/commands";
 import { useServerStore } from "@/stores/serverStore";
 
 interface SyncStore {
   activeSyncs: Record<number, SyncProgressPayload>;
   syncResults: Record<number, SyncResult>;
+  // Last failure per server; cleared when a new sync starts.
+  syncErrors: Record<number, SyncErrorPayload>;
   startSync: (id: number) => Promise<void>;
+  handleSyncEvent: (event: SyncEvent) => void;
+  listenForBackgroundSyncs: () => Promise<UnlistenFn>;
   cancelSync: (id: number) => Promise<void>;
   isSyncing: (id: number) => boolean;
   getProgress: (id: number) => SyncProgressPayload | undefined;
@@ -16,51 +26,65 @@ interface SyncStore {
 export const useSyncStore = create<SyncStore>((set, get) => ({
   activeSyncs: {},
   syncResults: {},
+  syncErrors: {},
 
   startSync: async (id: number) => {
+    set((state) => {
+      const { [id]: _, ...rest } = state.syncErrors;
+      return { syncErrors: rest };
+    });
     const serverStore = useServerStore.getState();
     serverStore.updateServerFromSync(id, null, null, "syncing");
 
-    await commands.startSync(id, (event) => {
-      switch (event.event) {
-        case "Progress":
-          set((state) => ({
-            activeSyncs: { ...state.activeSyncs, [id]: event.data },
-          }));
-          break;
-        case "Complete": {
-          const result = event.data.result;
-          set((state) => {
-            const { [id]: _, ...rest } = state.activeSyncs;
-            return {
-              activeSyncs: rest,
-              syncResults: { ...state.syncResults, [id]: result },
-            };
-          });
-          useServerStore
-            .getState()
-            .updateServerFromSync(
-              id,
-              result.total_offset_ms,
-              result.synced_at,
-              "synced",
-            );
-          break;
-        }
-        case "Error": {
-          set((state) => {
-            const { [id]: _, ...rest } = state.activeSyncs;
-            return { activeSyncs: rest };
-          });
-          useServerStore
-            .getState()
-            .updateServerFromSync(id, null, null, "error");
-          break;
-        }
+    await commands.startSync(id, get().handleSyncEvent);
+  },
+
+  handleSyncEvent: (event: SyncEvent) => {
+    const id = event.data.server_id;
+    switch (event.event) {
+      case "Progress":
+        set((state) => ({
+          activeSyncs: { ...state.activeSyncs, [id]: event.data },
+        }));
+        break;
+      case "Complete": {
+        const result = event.data.result;
+        set((state) => {
+          const { [id]: _, ...rest } = state.activeSyncs;
+          return {
+            activeSyncs: rest,
+            syncResults: { ...state.syncResults, [id]: result },
+          };
+        });
+        useServerStore
+          .getState()
+          .updateServerFromSync(
+            id,
+            result.total_offset_ms,
+            result.synced_at,
+            "synced",
+          );
+        break;
       }
-    });
+      case "Error": {
+        set((state) => {
+          const { [id]: _, ...rest } = state.activeSyncs;
+          return {
+            activeSyncs: rest,
+            syncErrors: { ...state.syncErrors, [id]: event.data },
+          };
+        });
+        useServerStore
+          .getState()
+          .updateServerFromSync(id, null, null, "error");
+        break;
+      }
+    }
   },
 
+  // Syncs started outside the UI (tray menu) report via a global event.
+  listenForBackgroundSyncs: () => commands.onSyncEvent(get().handleSyncEvent),
+
   cancelSync: async (id: number) => {
     await commands.cancelSync(id);
     set((state) => {
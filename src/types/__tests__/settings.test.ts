@@ -7,17 +7,37 @@ describe("DEFAULT_SETTINGS", () => {
     const requiredKeys: (keyof Settings)[] = [
       "theme",
       "min_request_interval_ms",
+      "sync_profile",
       "health_resync_threshold",
+      "auto_resync_interval_minutes",
       "external_time_source",
       "show_milliseconds",
       "millisecond_precision",
       "show_timezone_offset",
+      "time_format",
       "overlay_opacity",
       "overlay_auto_hide",
       "overlay_always_on_top",
+      "countdown_tick_ms",
       "alert_intervals",
       "alert_method",
+      "keep_warm_minutes",
       "drift_warning_threshold_ms",
+      "notify_anomalies",
+      "local_api_enabled",
+      "local_api_port",
+      "allow_clock_adjustment",
+      "progress_event_limit",
+      "record_syncs",
+      "public_host_floor_ms",
+      "trash_retention_days",
+      "sync_stall_timeout_secs",
+      "sync_deadline_secs",
+      "network_precheck",
+      "captive_portal_check",
+      "extrapolate_drift",
+      "lan_time_enabled",
+      "lan_time_port",
     ];
     for (const key of requiredKeys) {
       expect(DEFAULT_SETTINGS).toHaveProperty(key);
@@ -25,7 +45,7 @@ describe("DEFAULT_SETTINGS", () => {
   });
 
   it("has no unexpected extra keys beyond the Settings interface", () => {
-    const expectedKeyCount = 13;
+    const expectedKeyCount = 33;
     expect(Object.keys(DEFAULT_SETTINGS)).toHaveLength(expectedKeyCount);
   });
 
@@ -88,5 +108,62 @@ describe("DEFAULT_SETTINGS", () => {
     it("drift_warning_threshold_ms defaults to 1000", () => {
       expect(DEFAULT_SETTINGS.drift_warning_threshold_ms).toBe(1000);
     });
+
+    it("auto_resync_interval_minutes defaults to 0 (off)", () => {
+      expect(DEFAULT_SETTINGS.auto_resync_interval_minutes).toBe(0);
+    });
+
+    it("time_format defaults to %H:%M:%S", () => {
+      expect(DEFAULT_SETTINGS.time_format).toBe("%H:%M:%S");
+    });
+
+    it("local_api_enabled defaults to false", () => {
+      expect(DEFAULT_SETTINGS.local_api_enabled).toBe(false);
+    });
+
+    it("local_api_port defaults to 47321", () => {
+      expect(DEFAULT_SETTINGS.local_api_port).toBe(47321);
+    });
+
+    it("allow_clock_adjustment defaults to false", () => {
+      expect(DEFAULT_SETTINGS.allow_clock_adjustment).toBe(false);
+    });
+
+    it("progress_event_limit defaults to 4", () => {
+      expect(DEFAULT_SETTINGS.progress_event_limit).toBe(4);
+    });
+
+    it("record_syncs defaults to false", () => {
+      expect(DEFAULT_SETTINGS.record_syncs).toBe(false);
+    });
+
+    it("trash_retention_days defaults to 7", () => {
+      expect(DEFAULT_SETTINGS.trash_retention_days).toBe(7);
+    });
+
+    it("sync_stall_timeout_secs defaults to 60", () => {
+      expect(DEFAULT_SETTINGS.sync_stall_timeout_secs).toBe(60);
+    });
+
+    it("sync_deadline_secs defaults to 180", () => {
+      expect(DEFAULT_SETTINGS.sync_deadline_secs).toBe(180);
+    });
+
+    it("network_precheck defaults to off", () => {
+      expect(DEFAULT_SETTINGS.network_precheck).toBe(false);
+    });
+
+    it("captive_portal_check defaults to off", () => {
+      expect(DEFAULT_SETTINGS.captive_portal_check).toBe(false);
+    });
+
+    it("extrapolate_drift defaults to on", () => {
+      expect(DEFAULT_SETTINGS.extrapolate_drift).toBe(true);
+    });
+
+    it("lan_time_enabled defaults to off on port 47323", () => {
+      expect(DEFAULT_SETTINGS.lan_time_enabled).toBe(false);
+      expect(DEFAULT_SETTINGS.lan_time_port).toBe(47323);
+    });
   });
 });
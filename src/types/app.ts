@@ -0,0 +1,207 @@
+import type { OffsetRecheck, Server, Target } from "@/types/server";
+
+export interface SecondInstancePayload {
+  args: string[];
+  cwd: string;
+}
+
+export interface DeepLinkPayload {
+  server: Server;
+  target: Target | null;
+}
+
+export interface NtpMeasurement {
+  server: string;
+  // Reference minus local: positive means the local clock is behind.
+  offset_ms: number;
+  round_trip_ms: number;
+}
+
+// Loss and latency to the external time source, checked before syncs.
+export interface NetworkBaseline {
+  reference: string;
+  sent: number;
+  lost: number;
+  median_rtt_ms: number | null;
+  jitter_ms: number | null;
+  // A reference that never answers does not count as degraded.
+  degraded: boolean;
+}
+
+// The machine's own time daemon, from get_local_ntp_status.
+export interface LocalNtpStatus {
+  daemon: "chrony" | "timesyncd" | "ntpd";
+  synchronized: boolean;
+  server: string | null;
+  stratum: number | null;
+  // Same sign as NtpMeasurement: positive means the local clock is behind.
+  offset_ms: number | null;
+  jitter_ms: number | null;
+}
+
+export interface ClockAdjustment {
+  offset_ms: number;
+  method: "slew" | "step";
+}
+
+export interface ZoneTime {
+  timezone: string;
+  abbreviation: string;
+  utc_offset_seconds: number;
+  local_time: string;
+  formatted: string;
+}
+
+export interface WorldClock {
+  server_id: number;
+  corrected_time: string;
+  zones: ZoneTime[];
+}
+
+export interface UrlRedirect {
+  from: string;
+  to: string;
+  status: number;
+}
+
+export interface UrlValidation {
+  url: string;
+  final_url: string;
+  reachable: boolean;
+  status: number | null;
+  http_version: string | null;
+  has_date_header: boolean;
+  server_time: string | null;
+  redirects: UrlRedirect[];
+  rtt_ms: number | null;
+  error: string | null;
+}
+
+export interface SchedulerStatus {
+  paused: boolean;
+  // No network: due resyncs wait and retry with backoff.
+  offline: boolean;
+}
+
+// Fired once the outbound route settles on a new network. Offsets synced
+// earlier are flagged stale; with auto-resync on they are resynced too.
+export interface NetworkChange {
+  changed_at: string;
+  resyncing: number[];
+}
+
+// A named, isolated set of servers, targets and settings.
+export interface Workspace {
+  name: string;
+  active: boolean;
+}
+
+export interface ImportSummary {
+  version: number;
+  servers_added: number;
+  servers_updated: number;
+}
+
+// A target alert fired (desktop); play_sound asks the window to sound it.
+export interface AlertFired {
+  target_id: number;
+  minutes_before: number;
+  title: string;
+  body: string;
+  play_sound: boolean;
+}
+
+// Outcome of the T-30s / T-5s recheck of an armed target's offset.
+export interface TargetConfidence {
+  target_id: number;
+  server_id: number;
+  seconds_before: number;
+  confidence: "confirmed" | "adjusted" | "failed";
+  previous_offset_ms: number;
+  // What the corrected clock uses from now on.
+  offset_ms: number;
+  recheck: OffsetRecheck | null;
+  error: string | null;
+}
+
+// An armed target came up; error_us is how far (server time) the event
+// went out after the intended instant.
+export interface TargetFired {
+  target_id: number;
+  server_id: number;
+  intended_at: string;
+  fired_at: string;
+  error_us: number;
+  offset_ms: number;
+}
+
+// A server resynced because its health score fell below the threshold.
+export interface HealthResync {
+  server_id: number;
+  health_score: number;
+  threshold: number;
+}
+
+export interface ShareImportSummary {
+  servers_added: number;
+  servers_updated: number;
+  targets_added: number;
+}
+
+export interface FieldError {
+  field: string;
+  message: string;
+}
+
+export type ErrorCode =
+  | "not_found"
+  | "database"
+  | "network"
+  | "io"
+  | "no_date_header"
+  | "invalid_date_header"
+  | "cancelled"
+  | "max_retries_exceeded"
+  | "cached_response"
+  | "invalid_url"
+  | "invalid_deep_link"
+  | "invalid_time_format"
+  | "invalid_timezone"
+  | "invalid_target_time"
+  | "invalid_settings_file"
+  | "unknown_sync_profile"
+  | "unknown_domain_preset"
+  | "unknown_extractor"
+  | "plugin"
+  | "invalid_time_response"
+  | "replay"
+  | "not_synced"
+  | "ntp"
+  | "clock_adjustment_disabled"
+  | "clock_error_changed"
+  | "clock_permission_denied"
+  | "notification"
+  | "calibration_failed"
+  | "offline"
+  | "invalid_share_payload"
+  | "sync_stalled"
+  | "sync_deadline_exceeded"
+  | "recheck_too_late"
+  | "network_degraded"
+  | "intercepted"
+  | "passphrase_required"
+  | "decryption_failed"
+  | "duplicate_server"
+  | "time_source_disabled"
+  | "validation";
+
+// What every failed command rejects with.
+export interface AppError {
+  code: ErrorCode;
+  message: string;
+  retryable: boolean;
+  // Only present for code "validation".
+  fields?: FieldError[];
+  // Only present for code "duplicate_server": the server already added.
+  server_id?: number;
+}
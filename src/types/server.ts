@@ -1,3 +1,5 @@
+import type { AppError, ErrorCode } from "@/types/app";
+import type { AlertMethod } from "@/types/settings";
 export type ServerStatus = "idle" | "syncing" | "synced" | "error";
 
 export type SyncPhase =
@@ -16,6 +18,349 @@ export interface Server {
   created_at: string;
   status: ServerStatus;
   extractor_type: string;
+  // "HEAD" unless a domain preset says otherwise.
+  probe_method: string;
+  domain_preset: string | null;
+  // Overrides the global sync_profile setting when set.
+  sync_profile: string | null;
+  // From the most recent successful sync.
+  fingerprint: ServerFingerprint | null;
+  // Share of the round trip spent reaching the server; null assumes a
+  // symmetric path (0.5).
+  asymmetry: number | null;
+  // Start a sync for this server when the app launches.
+  sync_on_launch: boolean;
+  // 0–100 from recent syncs (success, stability, jitter, staleness);
+  // null until the server has synced.
+  health_score: number | null;
+  // Fit of recent offsets over time; null until there are enough syncs.
+  drift: DriftEstimate | null;
+  // Per-server min_request_interval_ms; null follows the setting.
+  min_request_interval_ms: number | null;
+  // Self-hosted: may be probed faster than public_host_floor_ms. Local
+  // hosts are treated as trusted either way.
+  trusted: boolean;
+  // Sent with every probe; null uses the app's default.
+  user_agent: string | null;
+  transport: TransportOptions;
+  // IANA zone "server-local" target times are read in; null when unknown.
+  timezone: string | null;
+  // timezone was guessed from the TLD or Content-Language, not pinned.
+  timezone_guessed: boolean;
+}
+
+export interface DriftEstimate {
+  rate_ms_per_hour: number;
+  samples: number;
+  // Scatter of the offsets around the fit: one sync's noise.
+  residual_ms: number;
+  rate_error_ms_per_hour: number;
+}
+
+// A server's corrected clock, extrapolated from the stored offset and drift.
+export interface ServerTime {
+  server_id: number;
+  offset_ms: number;
+  corrected_time: string;
+  corrected_unix_ms: number;
+  last_sync_at: string | null;
+  offset_age_ms: number | null;
+  stale: boolean;
+  drift_ms_per_hour: number | null;
+  // One standard error of offset_ms; null without a drift fit.
+  uncertainty_ms: number | null;
+}
+
+export type IpFamily = "auto" | "ipv4" | "ipv6";
+
+// Advanced socket options for a server's probes.
+export interface TransportOptions {
+  tcp_nodelay: boolean;
+  // null leaves new connections to the sync profile's timeout.
+  connect_timeout_ms: number | null;
+  ip_family: IpFamily;
+  // Skip certificate checks; only for internal systems with self-signed certs.
+  accept_invalid_certs: boolean;
+}
+
+// Result of calibrate_asymmetry: a symmetric sync set against NTP time.
+export type BulkAddStatus = "added" | "duplicate" | "invalid";
+
+// One URL found by add_servers_from_text and what became of it.
+export interface BulkAddEntry {
+  input: string;
+  url: string | null;
+  status: BulkAddStatus;
+  server_id: number | null;
+  message: string | null;
+}
+
+export interface AsymmetryCalibration {
+  server_id: number;
+  asymmetry: number;
+  symmetric_offset_ms: number;
+  ntp_offset_ms: number;
+  rtt_ms: number;
+  calibrated_at: string;
+}
+
+// A server's offset against the reference server rather than the local
+// clock. Positive: the server runs ahead of the reference.
+export interface RelativeOffset {
+  server_id: number;
+  reference_server_id: number;
+  offset_ms: number | null;
+  relative_offset_ms: number | null;
+}
+
+export interface ComparedServer {
+  server_id: number;
+  label: string;
+  offset_ms: number | null;
+  offset_age_ms: number | null;
+  stale: boolean;
+  // RTT interquartile range of the latest sync.
+  jitter_ms: number | null;
+}
+
+// matrix[i][j]: how far servers[i] runs ahead of servers[j] (null when
+// either is unsynced).
+export interface ServerComparison {
+  servers: ComparedServer[];
+  matrix: (number | null)[][];
+  compared_at: string;
+}
+
+// One measure in two syncs; change is after - before.
+export interface Delta {
+  before: number;
+  after: number;
+  change: number;
+}
+
+export interface Change<T> {
+  before: T;
+  after: T;
+  changed: boolean;
+}
+
+export interface SyncResultDiff {
+  before_id: number;
+  after_id: number;
+  same_server: boolean;
+  // Negative when the first sync is the newer one.
+  elapsed_ms: number;
+  offset_ms: Delta;
+  latency_ms: Record<"min" | "q1" | "median" | "mean" | "q3" | "max" | "iqr", Delta>;
+  duration_ms: Delta;
+  probes: Delta;
+  verified: Change<boolean>;
+  phase_reached: Change<SyncPhase>;
+  asymmetry: Change<number | null>;
+  network: Change<NetworkContext | null>;
+}
+
+export interface DashboardServer {
+  server: Server;
+  offset_age_ms: number | null;
+  // Also set for offsets measured before the last network change.
+  stale: boolean;
+  // Null when auto-resync is off, paused, or the server was never synced.
+  next_sync_at: string | null;
+}
+
+export interface UpcomingTarget {
+  target: Target;
+  // On the server's corrected clock; null until the server is synced.
+  remaining_ms: number | null;
+}
+
+export interface Dashboard {
+  servers: DashboardServer[];
+  // Armed targets still ahead, soonest first.
+  upcoming_targets: UpcomingTarget[];
+  scheduler_paused: boolean;
+  generated_at: string;
+}
+
+export interface ServerFingerprint {
+  server: string | null;
+  via: string | null;
+  cache: string | null;
+  cdn: string | null;
+  content_language: string | null;
+}
+
+// A deleted server, restorable until purge_at.
+export interface TrashedServer {
+  server: Server;
+  deleted_at: string;
+  purge_at: string;
+}
+
+export interface Target {
+  id: number;
+  server_id: number;
+  label: string | null;
+  target_at: string;
+  armed: boolean;
+  created_at: string;
+  // Overrides the alert_method setting; null follows it.
+  alert_method: AlertMethod | null;
+}
+
+// A one-time sync queued for a wall-clock moment; removed once started.
+export interface ScheduledSync {
+  id: number;
+  server_id: number;
+  run_at: string;
+  created_at: string;
+}
+
+export interface TimeUntil {
+  target_id: number;
+  server_id: number;
+  target_at: string;
+  corrected_now: string;
+  // Negative once the target has passed.
+  remaining_ms: number;
+  offset_ms: number;
+  drift_ms_per_hour: number | null;
+  offset_age_ms: number | null;
+  stale: boolean;
+}
+
+// One frame of a countdown stream, formatted in Rust.
+export interface CountdownTick {
+  target_id: number;
+  // Negative once the target has passed.
+  remaining_ms: number;
+  // "1:02:03.4" before the target, "+0:00:02.1" after it.
+  remaining_text: string;
+  // The server's corrected time in the user's time format.
+  corrected_time: string;
+  offset_ms: number;
+  stale: boolean;
+}
+
+export interface SyncProfile {
+  id: string;
+  label: string;
+  description: string;
+  probe_count: number;
+  // Nominal gap between latency probes; stretched or shrunk with jitter.
+  min_interval_ms: number;
+  // Per-host floor the gap never goes below.
+  min_spacing_ms: number;
+  max_retries: number;
+  // Probes per timed measurement, keeping the fastest; 1 sends singles.
+  burst_size: number;
+  timeout_ms: number;
+}
+
+export interface DomainPreset {
+  id: string;
+  label: string;
+  hosts: string[];
+  extractor_type: string;
+  probe_path: string | null;
+  probe_method: string;
+}
+
+// A value for `Server.extractor_type`: a built-in, or a WASM plugin
+// ("wasm:<file stem>") loaded from the plugins directory.
+export interface ExtractorInfo {
+  id: string;
+  name: string;
+  plugin: string | null;
+}
+
+export interface PluginFailure {
+  file: string;
+  error: string;
+}
+
+// A public time service offered as a server to compare ticket servers
+// against.
+export interface ReferenceSource {
+  kind: string;
+  label: string;
+  url: string;
+  extractor_type: string;
+  probe_method: string;
+}
+
+// Simulated server stored as a `demo://` URL; syncs instantly, no network.
+export interface DemoServer {
+  offset_ms: number;
+  rtt_ms: number;
+  jitter_ms: number;
+}
+
+export interface BenchmarkRun {
+  method: "four_phase" | "interval_intersection";
+  offset_ms: number | null;
+  uncertainty_ms: number | null;
+  probes: number;
+  duration_ms: number;
+  error: string | null;
+}
+
+export interface BenchmarkReport {
+  id: number;
+  server_id: number;
+  created_at: string;
+  runs: BenchmarkRun[];
+  difference_ms: number | null;
+  recommended: BenchmarkRun["method"] | null;
+}
+
+// A sync saved while record_syncs was on.
+export interface RecordingInfo {
+  file: string;
+  server_id: number;
+  url: string;
+  recorded_at: string;
+  probes: number;
+  offset_ms: number | null;
+  error: string | null;
+}
+
+export interface ReplayReport {
+  recorded_offset_ms: number | null;
+  recorded_error: string | null;
+  result: SyncResult | null;
+  error: string | null;
+  // Non-zero when the replay took a different path than the live run.
+  unused_probes: number;
+}
+
+export interface ConnectionTest {
+  probes: number;
+  min_rtt_ms: number;
+  median_rtt_ms: number;
+  server_timestamp: number;
+  tested_at: string;
+}
+
+// Single-probe guess; `verified` is always false and nothing is saved.
+export interface OffsetEstimate {
+  server_id: number;
+  offset_ms: number;
+  uncertainty_ms: number;
+  rtt_ms: number;
+  verified: false;
+  estimated_at: string;
+}
+
+// A stored offset checked against a few fresh probes just before a target.
+export interface OffsetRecheck {
+  offset_ms: number;
+  // 0 when the probes agree with the stored offset.
+  moved_ms: number;
+  uncertainty_ms: number;
+  probes: number;
+  checked_at: string;
 }
 
 export interface LatencyProfile {
@@ -25,8 +370,22 @@ export interface LatencyProfile {
   mean: number;
   q3: number;
   max: number;
+  // Mean split of the probes' round trips; null for simulated servers and
+  // results stored before it was recorded.
+  breakdown: RttBreakdown | null;
+}
+
+// Seconds. `dns` and `connect` (TCP + TLS) are zero on a reused connection.
+export interface RttBreakdown {
+  dns: number;
+  connect: number;
+  ttfb: number;
+  new_connections: number;
 }
 
+// A is best; F means the offset failed verification.
+export type SyncGrade = "A" | "B" | "C" | "D" | "F";
+
 export interface SyncResult {
   server_id: number;
   whole_second_offset: number;
@@ -37,6 +396,86 @@ export interface SyncResult {
   synced_at: string;
   duration_ms: number;
   phase_reached: SyncPhase;
+  // Only set on a fresh result; history rows carry null.
+  fingerprint: ServerFingerprint | null;
+  // How the last probe of the run was answered.
+  response: ResponseMetadata | null;
+  // Row id in history; 0 if the result could not be stored.
+  id: number;
+  // How long each phase took and how many probes it sent, in order.
+  timeline: PhaseTiming[];
+  // Free-form context added afterwards, e.g. "synced over hotel Wi-Fi".
+  note: string | null;
+  // Path asymmetry the sync corrected for; null if it assumed symmetry.
+  asymmetry: number | null;
+  // Offset far off the server's recent history.
+  anomalous: boolean;
+  // Set when the offset is a minute or more: the server's clock is
+  // misconfigured rather than slightly off.
+  clock_issue: ClockIssue | null;
+  // Null for syncs stored before it was recorded or run from the CLI
+  // without a stored server.
+  network: NetworkContext | null;
+  // Null for syncs stored before grading.
+  grade: SyncGrade | null;
+  // Width of the window the sub-second offset was narrowed to; above 1 ms
+  // when jitter ended the binary search early. Null for older syncs.
+  precision_ms: number | null;
+}
+
+export type InterfaceKind = "wifi" | "ethernet" | "cellular" | "other";
+
+// The network a sync ran over.
+export interface NetworkContext {
+  // Physical interface under the route, even when a VPN carries it.
+  interface: string | null;
+  kind: InterfaceKind | null;
+  vpn: boolean;
+  // Truncated SHA-256 of the Wi-Fi SSID; equal hashes mean the same network.
+  ssid_hash: string | null;
+}
+
+export interface ClockIssue {
+  // "timezone": off by a whole zone, local time served as GMT.
+  // "skew": minutes or more off with no zone to explain it.
+  kind: "timezone" | "skew";
+  // The zone the server appears to be set to, for "timezone".
+  utc_offset_minutes: number | null;
+  label: string;
+}
+
+// One page of a server's sync history, newest first.
+export interface SyncHistoryPage {
+  results: SyncResult[];
+  // Results matching the query across all pages.
+  total: number;
+  offset: number;
+}
+
+export interface PhaseTiming {
+  phase: SyncPhase;
+  duration_ms: number;
+  probes: number;
+}
+
+export interface ResponseMetadata {
+  status: number;
+  http_version: string;
+  remote_ip: string | null;
+  redirects: number;
+  // Mean local cost per probe, outside the network round trip.
+  probe_overhead_us: number;
+  // The server's TLS certificate; null over plain HTTP.
+  certificate: CertificateInfo | null;
+}
+
+export interface CertificateInfo {
+  subject: string;
+  issuer: string;
+  not_before: string;
+  not_after: string;
+  // SHA-256 of the DER encoding, hex.
+  sha256: string;
 }
 
 export interface SyncProgressPayload {
@@ -45,6 +484,8 @@ export interface SyncProgressPayload {
   progress_percent: number;
   phase_data: Record<string, unknown>;
   elapsed_ms: number;
+  // Estimated time left if no more probes need retrying.
+  eta_ms: number;
 }
 
 export interface SyncCompletePayload {
@@ -52,9 +493,53 @@ export interface SyncCompletePayload {
   result: SyncResult;
 }
 
+// Broadcast as `server-status-changed` whenever a status is stored.
+export interface ServerStatusChange {
+  server_id: number;
+  status: ServerStatus;
+}
+
 export interface SyncErrorPayload {
   server_id: number;
-  error: string;
+  error: AppError;
+  phase: SyncPhase;
+  phase_attempts: number;
+  total_attempts: number;
+  latency_profile: LatencyProfile | null;
+  // The offset found before verification, when the sync got that far.
+  partial_offset_ms: number | null;
+  elapsed_ms: number;
+}
+
+// A failed sync as kept in history. User cancellations are not recorded.
+export interface SyncErrorRecord {
+  id: number;
+  server_id: number;
+  phase: SyncPhase;
+  code: ErrorCode;
+  message: string;
+  total_attempts: number;
+  occurred_at: string;
+}
+
+// Round trips of the syncs run in one hour of the day or on one weekday.
+export interface LatencyBucket {
+  // Hour 0-23, or weekday 0-6 from Monday.
+  slot: number;
+  syncs: number;
+  median_rtt_ms: number | null;
+  // Median RTT spread (IQR); bounds how precise a sync can get.
+  jitter_ms: number | null;
+}
+
+export interface LatencyStats {
+  server_id: number;
+  // Zone of the hours and days; null means this machine's.
+  timezone: string | null;
+  syncs: number;
+  by_hour: LatencyBucket[];
+  by_weekday: LatencyBucket[];
+  quietest_hour: number | null;
 }
 
 export type SyncEvent =
@@ -1,31 +1,90 @@
+// How target alerts reach the user; "sound" plays without a notification.
+// "speak" reads the alert aloud; on mobile it sounds a notification instead.
+export type AlertMethod = "notification" | "sound" | "both" | "speak" | "none";
+
 export interface Settings {
   theme: "dark" | "light";
   min_request_interval_ms: number;
+  // Untrusted servers are never probed faster than this.
+  public_host_floor_ms: number;
+  sync_profile: string;
   health_resync_threshold: number;
+  auto_resync_interval_minutes: number;
   external_time_source: string;
   show_milliseconds: boolean;
   millisecond_precision: 1 | 2 | 3;
   show_timezone_offset: boolean;
+  time_format: string;
   overlay_opacity: number;
   overlay_auto_hide: boolean;
   overlay_always_on_top: boolean;
+  // Milliseconds between countdown stream ticks (16–1000).
+  countdown_tick_ms: number;
   alert_intervals: number[];
-  alert_method: "sound" | "visual" | "both";
+  alert_method: AlertMethod;
+  keep_warm_minutes: number;
   drift_warning_threshold_ms: number;
+  notify_anomalies: boolean;
+  local_api_enabled: boolean;
+  local_api_port: number;
+  allow_clock_adjustment: boolean;
+  progress_event_limit: number;
+  // Save every probe of each sync for replaying later.
+  record_syncs: boolean;
+  // Days a deleted server can be restored before it is purged.
+  trash_retention_days: number;
+  // Stop a sync that has reported no progress for this many seconds.
+  sync_stall_timeout_secs: number;
+  // Give up on a sync, keeping what it found, once it has run this long.
+  sync_deadline_secs: number;
+  // Check loss and latency to the external time source before each sync,
+  // and stop it when the local network looks degraded.
+  network_precheck: boolean;
+  // When a sync's first answer looks off, ask connectivitycheck.gstatic.com
+  // whether a captive portal or proxy is answering instead.
+  captive_portal_check: boolean;
+  // Carry each server's offset forward along its fitted drift since the
+  // last sync; off, corrected times use the offset as measured.
+  extrapolate_drift: boolean;
+  // Answer SNTP requests from other devices on the LAN with the primary
+  // server's corrected time, advertised over mDNS.
+  lan_time_enabled: boolean;
+  // UDP port the LAN time responder listens on (1024–65535).
+  lan_time_port: number;
 }
 
 export const DEFAULT_SETTINGS: Settings = {
   theme: "dark",
   min_request_interval_ms: 500,
+  public_host_floor_ms: 500,
+  sync_profile: "balanced",
   health_resync_threshold: 50,
+  auto_resync_interval_minutes: 0,
   external_time_source: "ntp",
   show_milliseconds: true,
   millisecond_precision: 3,
   show_timezone_offset: false,
+  time_format: "%H:%M:%S",
   overlay_opacity: 75,
   overlay_auto_hide: false,
   overlay_always_on_top: true,
+  countdown_tick_ms: 100,
   alert_intervals: [10, 5, 1],
   alert_method: "both",
+  keep_warm_minutes: 0,
   drift_warning_threshold_ms: 1000,
+  notify_anomalies: false,
+  local_api_enabled: false,
+  local_api_port: 47321,
+  allow_clock_adjustment: false,
+  progress_event_limit: 4,
+  record_syncs: false,
+  trash_retention_days: 7,
+  sync_stall_timeout_secs: 60,
+  sync_deadline_secs: 180,
+  network_precheck: false,
+  captive_portal_check: false,
+  extrapolate_drift: true,
+  lan_time_enabled: false,
+  lan_time_port: 47323,
 };